@@ -777,4 +777,43 @@ mod tests {
         }
         panic!("Could not find entrypoint");
     }
+
+    #[test]
+    fn test_push_constant_members() {
+        let includes: [PathBuf; 0] = [];
+        let defines: [(String, String); 0] = [];
+        let (comp, _) = compile(
+            None,
+            &Path::new(""),
+            "
+        #version 450
+
+        layout(push_constant) uniform PushConstants {
+            layout(offset = 16) vec4 color;
+            float scale;
+        } pc;
+
+        layout(location = 0) out vec4 f_color;
+
+        void main() {
+            f_color = pc.color * pc.scale;
+        }
+        ",
+            ShaderKind::Fragment,
+            &includes,
+            &defines,
+            None,
+            None,
+        )
+        .unwrap();
+        let spirv = Spirv::new(comp.as_binary()).unwrap();
+
+        let (_, _, info) = reflect::entry_points(&spirv).next().unwrap();
+        let members: Vec<_> = info
+            .push_constant_members
+            .iter()
+            .map(|member| (member.name.as_deref(), member.offset, member.size))
+            .collect();
+        assert_eq!(members, [(Some("color"), 16, 16), (Some("scale"), 32, 4)]);
+    }
 }
@@ -12,9 +12,9 @@ use proc_macro2::TokenStream;
 use vulkano::{
     pipeline::layout::PushConstantRange,
     shader::{
-        spirv::ExecutionModel, DescriptorIdentifier, DescriptorRequirements, EntryPointInfo,
-        ShaderExecution, ShaderInterface, ShaderInterfaceEntry, ShaderInterfaceEntryType,
-        ShaderStages, SpecializationConstantRequirements,
+        spirv::ExecutionModel, DescriptorBindingRequirements, DescriptorIdentifier, EntryPointInfo,
+        LocalSizeValue, PushConstantMember, ShaderExecution, ShaderInterface, ShaderInterfaceEntry,
+        ShaderInterfaceEntryType, ShaderStages, SpecializationConstantRequirements,
     },
 };
 
@@ -32,10 +32,13 @@ pub(super) fn write_entry_point(
     let descriptor_requirements = write_descriptor_requirements(&info.descriptor_requirements);
     let push_constant_requirements =
         write_push_constant_requirements(&info.push_constant_requirements);
+    let push_constant_members = write_push_constant_members(&info.push_constant_members);
     let specialization_constant_requirements =
         write_specialization_constant_requirements(&info.specialization_constant_requirements);
     let input_interface = write_interface(&info.input_interface);
     let output_interface = write_interface(&info.output_interface);
+    let local_size = write_local_size(info.local_size.as_ref());
+    let initializes_workgroup_memory = info.initializes_workgroup_memory;
 
     quote! {
         (
@@ -45,9 +48,12 @@ pub(super) fn write_entry_point(
                 execution: #execution,
                 descriptor_requirements: #descriptor_requirements.into_iter().collect(),
                 push_constant_requirements: #push_constant_requirements,
+                push_constant_members: #push_constant_members,
                 specialization_constant_requirements: #specialization_constant_requirements.into_iter().collect(),
                 input_interface: #input_interface,
                 output_interface: #output_interface,
+                local_size: #local_size,
+                initializes_workgroup_memory: #initializes_workgroup_memory,
             },
         ),
     }
@@ -85,14 +91,39 @@ fn write_shader_execution(execution: &ShaderExecution) -> TokenStream {
     }
 }
 
+fn write_local_size(local_size: Option<&[LocalSizeValue; 3]>) -> TokenStream {
+    match local_size {
+        Some(local_size) => {
+            let values = local_size.iter().map(|value| match *value {
+                LocalSizeValue::Literal(value) => {
+                    quote! { ::vulkano::shader::LocalSizeValue::Literal(#value) }
+                }
+                LocalSizeValue::SpecializationConstant {
+                    constant_id,
+                    default_value,
+                } => quote! {
+                    ::vulkano::shader::LocalSizeValue::SpecializationConstant {
+                        constant_id: #constant_id,
+                        default_value: #default_value,
+                    }
+                },
+            });
+
+            quote! { Some([#(#values),*]) }
+        }
+        None => quote! { None },
+    }
+}
+
 fn write_descriptor_requirements(
-    descriptor_requirements: &HashMap<(u32, u32), DescriptorRequirements>,
+    descriptor_requirements: &HashMap<(u32, u32), DescriptorBindingRequirements>,
 ) -> TokenStream {
     let descriptor_requirements = descriptor_requirements.iter().map(|(loc, reqs)| {
         let (set_num, binding_num) = loc;
-        let DescriptorRequirements {
+        let DescriptorBindingRequirements {
             descriptor_types,
             descriptor_count,
+            buffer_block_size,
             image_format,
             image_multisampled,
             image_scalar_type,
@@ -115,6 +146,10 @@ fn write_descriptor_requirements(
             Some(descriptor_count) => quote! { Some(#descriptor_count) },
             None => quote! { None },
         };
+        let buffer_block_size = match buffer_block_size {
+            Some(buffer_block_size) => quote! { Some(#buffer_block_size) },
+            None => quote! { None },
+        };
         let image_format = match image_format {
             Some(image_format) => {
                 let ident = format_ident!("{}", format!("{:?}", image_format));
@@ -206,9 +241,10 @@ fn write_descriptor_requirements(
         quote! {
             (
                 (#set_num, #binding_num),
-                ::vulkano::shader::DescriptorRequirements {
+                ::vulkano::shader::DescriptorBindingRequirements {
                     descriptor_types: vec![#(#descriptor_types),*],
                     descriptor_count: #descriptor_count,
+                    buffer_block_size: #buffer_block_size,
                     image_format: #image_format,
                     image_multisampled: #image_multisampled,
                     image_scalar_type: #image_scalar_type,
@@ -292,6 +328,30 @@ fn write_push_constant_requirements(
     }
 }
 
+fn write_push_constant_members(push_constant_members: &[PushConstantMember]) -> TokenStream {
+    let push_constant_members = push_constant_members.iter().map(|member| {
+        let PushConstantMember { name, offset, size } = member;
+        let name = match name {
+            Some(name) => quote! { Some(#name.to_owned()) },
+            None => quote! { None },
+        };
+
+        quote! {
+            ::vulkano::shader::PushConstantMember {
+                name: #name,
+                offset: #offset,
+                size: #size,
+            },
+        }
+    });
+
+    quote! {
+        vec![
+            #( #push_constant_members )*
+        ]
+    }
+}
+
 fn write_specialization_constant_requirements(
     specialization_constant_requirements: &HashMap<u32, SpecializationConstantRequirements>,
 ) -> TokenStream {
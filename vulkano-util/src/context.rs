@@ -7,7 +7,11 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use std::sync::Arc;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
 use vulkano::{
     device::{
         physical::{PhysicalDevice, PhysicalDeviceType},
@@ -41,10 +45,130 @@ pub struct VulkanoConfig {
 
     pub device_features: Features,
 
+    /// Pin the selection to a specific physical device, bypassing `device_priority_fn`. The
+    /// device must still pass `device_filter_fn`.
+    ///
+    /// The default value is read from the environment with [`DeviceOverride::from_env`]. If a
+    /// variable has an invalid value, it is ignored. Use [`VulkanoConfig::try_default`] to get
+    /// an error instead.
+    pub device_override: Option<DeviceOverride>,
+
     /// Print your selected device name at start.
     pub print_device_name: bool,
 }
 
+/// Selects a specific physical device, instead of letting [`VulkanoConfig`] choose one by
+/// priority.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeviceOverride {
+    /// The device at this index in the order returned by
+    /// [`Instance::enumerate_physical_devices`].
+    Index(usize),
+
+    /// The device whose [`device_uuid`](vulkano::device::Properties::device_uuid) property matches
+    /// this value.
+    Uuid([u8; 16]),
+}
+
+impl DeviceOverride {
+    /// The environment variable that selects a device by index.
+    pub const INDEX_VAR: &'static str = "VULKANO_DEVICE_INDEX";
+
+    /// The environment variable that selects a device by UUID, given as 32 hexadecimal digits
+    /// that may be separated by dashes.
+    pub const UUID_VAR: &'static str = "VULKANO_DEVICE_UUID";
+
+    /// Reads the device override from the `VULKANO_DEVICE_INDEX` or `VULKANO_DEVICE_UUID`
+    /// environment variables. If both are set, the UUID takes precedence.
+    ///
+    /// Returns an error if a variable is set but its value can't be parsed.
+    pub fn from_env() -> Result<Option<Self>, DeviceOverrideError> {
+        Self::from_values(
+            std::env::var(Self::UUID_VAR).ok().as_deref(),
+            std::env::var(Self::INDEX_VAR).ok().as_deref(),
+        )
+    }
+
+    fn from_values(
+        uuid: Option<&str>,
+        index: Option<&str>,
+    ) -> Result<Option<Self>, DeviceOverrideError> {
+        if let Some(uuid) = uuid {
+            return match Self::parse_uuid(uuid) {
+                Some(uuid) => Ok(Some(Self::Uuid(uuid))),
+                None => Err(DeviceOverrideError {
+                    variable: Self::UUID_VAR,
+                    value: uuid.to_owned(),
+                }),
+            };
+        }
+
+        if let Some(index) = index {
+            return match index.trim().parse() {
+                Ok(index) => Ok(Some(Self::Index(index))),
+                Err(_) => Err(DeviceOverrideError {
+                    variable: Self::INDEX_VAR,
+                    value: index.to_owned(),
+                }),
+            };
+        }
+
+        Ok(None)
+    }
+
+    fn parse_uuid(value: &str) -> Option<[u8; 16]> {
+        let digits: Vec<u8> = value
+            .trim()
+            .bytes()
+            .filter(|&b| b != b'-')
+            .map(|b| (b as char).to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+
+        if digits.len() != 32 {
+            return None;
+        }
+
+        let mut uuid = [0; 16];
+
+        for (byte, pair) in uuid.iter_mut().zip(digits.chunks_exact(2)) {
+            *byte = (pair[0] << 4) | pair[1];
+        }
+
+        Some(uuid)
+    }
+
+    /// Returns whether `physical_device`, found at `index` in the enumeration order, is the one
+    /// selected by this override.
+    pub fn matches(&self, index: usize, physical_device: &PhysicalDevice) -> bool {
+        match *self {
+            Self::Index(selected) => index == selected,
+            Self::Uuid(uuid) => physical_device.device_uuid() == Some(uuid),
+        }
+    }
+}
+
+/// Error that can happen when reading a [`DeviceOverride`] from the environment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeviceOverrideError {
+    /// The environment variable that has an invalid value.
+    pub variable: &'static str,
+
+    /// The value of the variable.
+    pub value: String,
+}
+
+impl Error for DeviceOverrideError {}
+
+impl Display for DeviceOverrideError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(
+            f,
+            "the environment variable {} has an invalid value: {:?}",
+            self.variable, self.value,
+        )
+    }
+}
+
 impl Default for VulkanoConfig {
     #[inline]
     fn default() -> Self {
@@ -76,6 +200,7 @@ impl Default for VulkanoConfig {
                 PhysicalDeviceType::Other => 5,
                 _ => 6,
             }),
+            device_override: DeviceOverride::from_env().unwrap_or(None),
             print_device_name: false,
             device_extensions,
             device_features: Features::empty(),
@@ -83,6 +208,17 @@ impl Default for VulkanoConfig {
     }
 }
 
+impl VulkanoConfig {
+    /// Returns the default configuration, but returns an error if the device override
+    /// environment variables have an invalid value, instead of ignoring them.
+    pub fn try_default() -> Result<Self, DeviceOverrideError> {
+        Ok(VulkanoConfig {
+            device_override: DeviceOverride::from_env()?,
+            ..Default::default()
+        })
+    }
+}
+
 /// A utility struct to create, access and hold alive Vulkano device, instance and queues.
 ///
 /// Vulkano context is used in the creation of your graphics or compute pipelines, images and
@@ -150,13 +286,28 @@ impl VulkanoContext {
                         .expect("Failed to create debug callback")
                 });
 
-        // Get prioritized device
-        let physical_device = instance
+        // Get the pinned device, or the prioritized device
+        let physical_devices = instance
             .enumerate_physical_devices()
-            .expect("Failed to enumerate physical devices")
-            .filter(|p| (config.device_filter_fn)(p))
-            .min_by_key(|p| (config.device_priority_fn)(p))
-            .expect("Failed to create physical device");
+            .expect("Failed to enumerate physical devices");
+        let physical_device = if let Some(device_override) = config.device_override {
+            let physical_device = physical_devices
+                .enumerate()
+                .find(|(i, p)| device_override.matches(*i, p))
+                .map(|(_, p)| p)
+                .unwrap_or_else(|| panic!("No physical device matches {:?}", device_override));
+            assert!(
+                (config.device_filter_fn)(&physical_device),
+                "The physical device selected by {:?} does not meet the requirements",
+                device_override,
+            );
+            physical_device
+        } else {
+            physical_devices
+                .filter(|p| (config.device_filter_fn)(p))
+                .min_by_key(|p| (config.device_priority_fn)(p))
+                .expect("Failed to create physical device")
+        };
         // Print used device
         if config.print_device_name {
             println!(
@@ -293,3 +444,42 @@ impl VulkanoContext {
         &self.compute_queue
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn device_override_from_values() {
+        assert_eq!(DeviceOverride::from_values(None, None), Ok(None));
+        assert_eq!(
+            DeviceOverride::from_values(None, Some(" 2 ")),
+            Ok(Some(DeviceOverride::Index(2))),
+        );
+        assert_eq!(
+            DeviceOverride::from_values(Some("00112233-4455-6677-8899-aabbccddeeff"), Some("2")),
+            Ok(Some(DeviceOverride::Uuid([
+                0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd,
+                0xee, 0xff,
+            ]))),
+        );
+    }
+
+    #[test]
+    fn device_override_invalid_values() {
+        assert_eq!(
+            DeviceOverride::from_values(None, Some("first")),
+            Err(DeviceOverrideError {
+                variable: DeviceOverride::INDEX_VAR,
+                value: "first".to_owned(),
+            }),
+        );
+        assert_eq!(
+            DeviceOverride::from_values(Some("0011"), None),
+            Err(DeviceOverrideError {
+                variable: DeviceOverride::UUID_VAR,
+                value: "0011".to_owned(),
+            }),
+        );
+    }
+}
@@ -0,0 +1,267 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A table of live Vulkan objects, for inclusion in crash dumps.
+//!
+//! When the driver reports a device loss or the application panics, the only clues are often raw
+//! Vulkan handles in a driver log or minidump. A [`HandleTable`] keeps track of the objects that
+//! the application registers with it, and can write out the type, raw handle, debug name and size
+//! of each object that is still alive, so that those handles can be correlated with the objects
+//! that the application created.
+//!
+//! Objects are held weakly, so registering an object does not keep it alive.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use vulkano_util::handle_table::HandleTable;
+//! # let buffer: std::sync::Arc<vulkano::buffer::sys::UnsafeBuffer> = return;
+//!
+//! let table = HandleTable::new();
+//! table.install_panic_hook(|| std::fs::File::create("handles.txt"));
+//!
+//! table.register(&buffer, Some("vertex buffer"), Some(buffer.size()));
+//! ```
+
+use std::{
+    any::{type_name, Any},
+    io::{self, Write},
+    panic,
+    sync::{Arc, Mutex, MutexGuard, TryLockError, Weak},
+};
+use vulkano::{sync::FlushError, DeviceSize, Handle, VulkanObject};
+
+/// Keeps track of registered Vulkan objects, and writes out those that are still alive.
+#[derive(Debug, Default)]
+pub struct HandleTable {
+    entries: Mutex<Vec<Entry>>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    object: Weak<dyn Any + Send + Sync>,
+    info: HandleTableEntry,
+}
+
+/// Information about an object in a [`HandleTable`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandleTableEntry {
+    /// The Rust type name of the object.
+    pub type_name: &'static str,
+
+    /// The raw Vulkan handle of the object.
+    pub handle: u64,
+
+    /// The debug name that was given when registering the object.
+    pub name: Option<String>,
+
+    /// The size in bytes that was given when registering the object.
+    pub size: Option<DeviceSize>,
+}
+
+impl HandleTable {
+    /// Creates a new empty `HandleTable`.
+    #[inline]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Adds an object to the table.
+    ///
+    /// The object is removed from the table automatically once it is dropped.
+    pub fn register<T>(&self, object: &Arc<T>, name: Option<&str>, size: Option<DeviceSize>)
+    where
+        T: VulkanObject + Send + Sync + 'static,
+    {
+        let weak: Weak<T> = Arc::downgrade(object);
+        let entry = Entry {
+            object: weak,
+            info: HandleTableEntry {
+                type_name: type_name::<T>(),
+                handle: object.internal_object().as_raw(),
+                name: name.map(ToOwned::to_owned),
+                size,
+            },
+        };
+
+        let mut entries = self.lock();
+        entries.retain(|entry| entry.object.strong_count() > 0);
+        entries.push(entry);
+    }
+
+    /// Returns the entries of all registered objects that are still alive, in the order they were
+    /// registered.
+    pub fn live_objects(&self) -> Vec<HandleTableEntry> {
+        Self::retain_live(self.lock())
+    }
+
+    /// Writes a table of all registered objects that are still alive to `writer`.
+    pub fn dump(&self, writer: &mut impl Write) -> io::Result<()> {
+        Self::write_entries(&self.live_objects(), writer)
+    }
+
+    // Every modification of the table leaves it in a consistent state, so a panic while it was
+    // locked doesn't make it unusable.
+    fn lock(&self) -> MutexGuard<'_, Vec<Entry>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn retain_live(mut entries: MutexGuard<'_, Vec<Entry>>) -> Vec<HandleTableEntry> {
+        entries.retain(|entry| entry.object.strong_count() > 0);
+        entries.iter().map(|entry| entry.info.clone()).collect()
+    }
+
+    fn write_entries(live_objects: &[HandleTableEntry], writer: &mut impl Write) -> io::Result<()> {
+        writeln!(writer, "{} live Vulkan objects", live_objects.len())?;
+        writeln!(
+            writer,
+            "{:<18}  {:>12}  {:<32}  type",
+            "handle", "size", "name"
+        )?;
+
+        for entry in live_objects {
+            let size = entry
+                .size
+                .map_or_else(|| "-".to_owned(), |size| size.to_string());
+
+            writeln!(
+                writer,
+                "{:#018x}  {:>12}  {:<32}  {}",
+                entry.handle,
+                size,
+                entry.name.as_deref().unwrap_or("-"),
+                entry.type_name,
+            )?;
+        }
+
+        writer.flush()
+    }
+
+    /// Writes the table to `writer` if `error` indicates that the device was lost, and returns the
+    /// error unchanged.
+    ///
+    /// This is intended to be used on the result of flushing or presenting a future.
+    pub fn dump_on_device_lost(&self, error: FlushError, writer: &mut impl Write) -> FlushError {
+        if matches!(error, FlushError::DeviceLost) {
+            // The error being reported is more important than a failure to write the dump.
+            let _ = self.dump(writer);
+        }
+
+        error
+    }
+
+    /// Installs a panic hook that writes the table to the writer returned by `make_writer` before
+    /// calling the previously installed hook.
+    ///
+    /// If the panic happens while the table is locked, for example because the thread that
+    /// panicked was registering an object, the table is not written.
+    pub fn install_panic_hook<W>(
+        self: &Arc<Self>,
+        make_writer: impl Fn() -> io::Result<W> + Send + Sync + 'static,
+    ) where
+        W: Write,
+    {
+        let table = Arc::downgrade(self);
+        let previous_hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if let Some(table) = table.upgrade() {
+                // Blocking here would deadlock if the panicking thread holds the lock.
+                let entries = match table.entries.try_lock() {
+                    Ok(entries) => Some(entries),
+                    Err(TryLockError::Poisoned(poisoned)) => Some(poisoned.into_inner()),
+                    Err(TryLockError::WouldBlock) => None,
+                };
+
+                if let Some(live_objects) = entries.map(Self::retain_live) {
+                    if let Ok(mut writer) = make_writer() {
+                        let _ = Self::write_entries(&live_objects, &mut writer);
+                    }
+                }
+            }
+
+            previous_hook(info);
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vulkano::sync::Fence;
+
+    #[test]
+    fn register_and_list() {
+        let (device, _) = gfx_dev_and_queue!();
+        let table = HandleTable::new();
+
+        let first = Arc::new(Fence::from_pool(device.clone()).unwrap());
+        let second = Arc::new(Fence::from_pool(device).unwrap());
+        table.register(&first, Some("first"), None);
+        table.register(&second, None, Some(16));
+
+        assert_eq!(
+            table.live_objects(),
+            [
+                HandleTableEntry {
+                    type_name: type_name::<Fence>(),
+                    handle: first.internal_object().as_raw(),
+                    name: Some("first".to_owned()),
+                    size: None,
+                },
+                HandleTableEntry {
+                    type_name: type_name::<Fence>(),
+                    handle: second.internal_object().as_raw(),
+                    name: None,
+                    size: Some(16),
+                },
+            ],
+        );
+
+        let mut dump = Vec::new();
+        table.dump(&mut dump).unwrap();
+        let dump = String::from_utf8(dump).unwrap();
+        assert!(dump.starts_with("2 live Vulkan objects"));
+        assert!(dump.contains("first"));
+    }
+
+    #[test]
+    fn dropped_objects_are_removed() {
+        let (device, _) = gfx_dev_and_queue!();
+        let table = HandleTable::new();
+
+        let kept = Arc::new(Fence::from_pool(device.clone()).unwrap());
+        let dropped = Arc::new(Fence::from_pool(device).unwrap());
+        table.register(&kept, Some("kept"), None);
+        table.register(&dropped, Some("dropped"), None);
+        drop(dropped);
+
+        let live_objects = table.live_objects();
+        assert_eq!(live_objects.len(), 1);
+        assert_eq!(live_objects[0].name.as_deref(), Some("kept"));
+    }
+
+    #[test]
+    fn poisoned_table() {
+        let (device, _) = gfx_dev_and_queue!();
+        let table = HandleTable::new();
+
+        let _ = panic::catch_unwind(|| {
+            let _entries = table.entries.lock().unwrap();
+            panic!();
+        });
+        assert!(table.entries.is_poisoned());
+
+        let fence = Arc::new(Fence::from_pool(device).unwrap());
+        table.register(&fence, None, None);
+        assert_eq!(table.live_objects().len(), 1);
+    }
+}
@@ -0,0 +1,481 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Hierarchical depth pyramids, for occlusion culling.
+//!
+//! A depth pyramid is a single-channel image with a full mip chain, in which each texel holds the
+//! minimum or maximum depth of the area of the depth buffer that it covers. An occlusion culling
+//! shader can project the bounds of an object, pick the mip level in which the bounds cover only
+//! a few texels, and compare the depth of the object with the depth stored in the pyramid.
+//!
+//! [`DepthPyramid`] creates the pyramid image, an image view for each of its mip levels, and a
+//! compute pipeline that downsamples one level into the next. The downsampling uses a sampler
+//! with a `Min` or `Max` [`SamplerReductionMode`], so the
+//! [`sampler_filter_minmax`](vulkano::device::Features::sampler_filter_minmax) feature or the
+//! [`ext_sampler_filter_minmax`](vulkano::device::DeviceExtensions::ext_sampler_filter_minmax)
+//! extension must be enabled on the device.
+//!
+//! Level 0 of the pyramid is half the size of the depth buffer, rounded down. When a level has an
+//! odd width or height, the last column or row of the next level covers three columns or rows of
+//! it instead of two, so that no depth is left out.
+
+use std::{
+    error::Error,
+    fmt::{Debug, Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+use vulkano::{
+    command_buffer::{
+        allocator::CommandBufferAllocator, AutoCommandBufferBuilder, PipelineExecutionError,
+    },
+    descriptor_set::{
+        allocator::DescriptorSetAllocator, DescriptorSet, DescriptorSetCreationError,
+        DescriptorSetWithOffsets, PersistentDescriptorSet, WriteDescriptorSet,
+    },
+    device::DeviceOwned,
+    format::Format,
+    image::{
+        view::{ImageView, ImageViewCreateInfo, ImageViewCreationError, ImageViewType},
+        ImageAccess, ImageCreateFlags, ImageCreationError, ImageDimensions, ImageSubresourceRange,
+        ImageUsage, ImageViewAbstract, MipmapsCount, StorageImage,
+    },
+    pipeline::{
+        compute::ComputePipelineCreationError, ComputePipeline, Pipeline, PipelineBindPoint,
+    },
+    sampler::{
+        Filter, Sampler, SamplerAddressMode, SamplerCreateInfo, SamplerCreationError,
+        SamplerMipmapMode, SamplerReductionMode,
+    },
+    shader::ShaderCreationError,
+};
+
+mod downsample {
+    vulkano_shaders::shader! {
+        ty: "compute",
+        path: "src/hiz/downsample.glsl",
+    }
+}
+
+/// A hierarchical depth pyramid, and the compute pipeline that generates it from a depth buffer.
+pub struct DepthPyramid {
+    image: Arc<StorageImage>,
+    view: Arc<ImageView<StorageImage>>,
+    level_views: Vec<Arc<ImageView<StorageImage>>>,
+    sampler: Arc<Sampler>,
+    pipeline: Arc<ComputePipeline>,
+    descriptor_sets: Vec<Arc<dyn DescriptorSet>>,
+}
+
+impl DepthPyramid {
+    /// Creates a new `DepthPyramid` that is generated from `depth`.
+    ///
+    /// `depth` must be a 2D image view that selects only the `depth` aspect, and has the
+    /// `sampled` usage. Its format must support the `sampled_image_filter_minmax` format feature.
+    pub fn new<A>(
+        descriptor_set_allocator: &A,
+        depth: Arc<dyn ImageViewAbstract>,
+        create_info: DepthPyramidCreateInfo,
+    ) -> Result<DepthPyramid, DepthPyramidError>
+    where
+        A: DescriptorSetAllocator + ?Sized,
+        A::Alloc: 'static,
+    {
+        let DepthPyramidCreateInfo { reduction_mode } = create_info;
+
+        let device = depth.device().clone();
+
+        if reduction_mode == SamplerReductionMode::WeightedAverage {
+            return Err(DepthPyramidError::ReductionModeWeightedAverage);
+        }
+
+        if depth.view_type() != ImageViewType::Dim2d {
+            return Err(DepthPyramidError::DepthViewNotDim2d);
+        }
+
+        let aspects = depth.subresource_range().aspects;
+
+        if !(aspects.depth && !aspects.stencil) {
+            return Err(DepthPyramidError::DepthViewNotDepthAspect);
+        }
+
+        if !depth.format_features().sampled_image_filter_minmax {
+            return Err(DepthPyramidError::DepthViewFormatNotSupported);
+        }
+
+        let [width, height] = depth
+            .image()
+            .dimensions()
+            .mip_level_dimensions(depth.subresource_range().mip_levels.start)
+            .unwrap()
+            .width_height();
+        let dimensions = ImageDimensions::Dim2d {
+            width: (width / 2).max(1),
+            height: (height / 2).max(1),
+            array_layers: 1,
+        };
+
+        let image = StorageImage::with_mip_levels(
+            device.clone(),
+            dimensions,
+            MipmapsCount::Log2,
+            Format::R32_SFLOAT,
+            ImageUsage {
+                sampled: true,
+                storage: true,
+                ..ImageUsage::empty()
+            },
+            ImageCreateFlags::empty(),
+            device.active_queue_family_indices().iter().copied(),
+        )?;
+        let view = ImageView::new_default(image.clone())?;
+        let level_views = (0..image.mip_levels())
+            .map(|level| {
+                ImageView::new(
+                    image.clone(),
+                    ImageViewCreateInfo {
+                        subresource_range: ImageSubresourceRange {
+                            mip_levels: level..level + 1,
+                            ..image.subresource_range()
+                        },
+                        ..ImageViewCreateInfo::from_image(&image)
+                    },
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let sampler = Sampler::new(
+            device.clone(),
+            SamplerCreateInfo {
+                mag_filter: Filter::Linear,
+                min_filter: Filter::Linear,
+                mipmap_mode: SamplerMipmapMode::Nearest,
+                address_mode: [SamplerAddressMode::ClampToEdge; 3],
+                reduction_mode,
+                ..Default::default()
+            },
+        )?;
+
+        let module = downsample::load(device.clone())?;
+        let pipeline = ComputePipeline::new(
+            device,
+            module.entry_point("main").unwrap(),
+            &downsample::SpecializationConstants {
+                reduce_max: (reduction_mode == SamplerReductionMode::Max) as u32,
+            },
+            None,
+            |_| {},
+        )?;
+
+        let set_layout = &pipeline.layout().set_layouts()[0];
+        let descriptor_sets = (0..level_views.len())
+            .map(|level| {
+                let src = if level == 0 {
+                    depth.clone()
+                } else {
+                    level_views[level - 1].clone() as Arc<dyn ImageViewAbstract>
+                };
+
+                PersistentDescriptorSet::new(
+                    descriptor_set_allocator,
+                    set_layout.clone(),
+                    [
+                        WriteDescriptorSet::image_view_sampler(0, src, sampler.clone()),
+                        WriteDescriptorSet::image_view(1, level_views[level].clone()),
+                    ],
+                )
+                .map(|set| set as Arc<dyn DescriptorSet>)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(DepthPyramid {
+            image,
+            view,
+            level_views,
+            sampler,
+            pipeline,
+            descriptor_sets,
+        })
+    }
+
+    /// Returns the pyramid image.
+    #[inline]
+    pub fn image(&self) -> &Arc<StorageImage> {
+        &self.image
+    }
+
+    /// Returns an image view of all mip levels of the pyramid, to be read by an occlusion culling
+    /// shader.
+    #[inline]
+    pub fn view(&self) -> &Arc<ImageView<StorageImage>> {
+        &self.view
+    }
+
+    /// Returns an image view of a single mip level of the pyramid.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `level` is not less than the number of mip levels of the pyramid.
+    #[inline]
+    pub fn level_view(&self, level: u32) -> &Arc<ImageView<StorageImage>> {
+        &self.level_views[level as usize]
+    }
+
+    /// Returns the sampler with the reduction mode that the pyramid was created with.
+    ///
+    /// Sampling the pyramid with this sampler and a linear filter returns the minimum or maximum
+    /// of the texels in the filter footprint, rather than their average.
+    #[inline]
+    pub fn sampler(&self) -> &Arc<Sampler> {
+        &self.sampler
+    }
+
+    /// Records the commands that generate the pyramid from the depth buffer.
+    ///
+    /// Each mip level is generated by a separate dispatch. The barriers between the dispatches,
+    /// and between the writes to the depth buffer and the first dispatch, are inserted by
+    /// `builder`.
+    ///
+    /// This binds a compute pipeline and a descriptor set to set 0 of the compute bind point, so
+    /// these must be bound again before recording other dispatches.
+    pub fn record<L, A>(
+        &self,
+        builder: &mut AutoCommandBufferBuilder<L, A>,
+    ) -> Result<(), PipelineExecutionError>
+    where
+        A: CommandBufferAllocator,
+    {
+        builder.bind_pipeline_compute(self.pipeline.clone());
+
+        for (level, descriptor_set) in self.descriptor_sets.iter().enumerate() {
+            let [width, height] = self
+                .image
+                .dimensions()
+                .mip_level_dimensions(level as u32)
+                .unwrap()
+                .width_height();
+
+            builder
+                .bind_descriptor_sets(
+                    PipelineBindPoint::Compute,
+                    self.pipeline.layout().clone(),
+                    0,
+                    DescriptorSetWithOffsets::new(descriptor_set.clone(), []),
+                )
+                .dispatch([(width + 7) / 8, (height + 7) / 8, 1])?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for DepthPyramid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        f.debug_struct("DepthPyramid")
+            .field("image", &self.image)
+            .field("sampler", &self.sampler)
+            .field("pipeline", &self.pipeline)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Parameters to create a new `DepthPyramid`.
+#[derive(Clone, Debug)]
+pub struct DepthPyramidCreateInfo {
+    /// How the depths of an area are reduced to a single depth.
+    ///
+    /// For a depth buffer where greater values are farther away, `Max` stores the farthest depth
+    /// of each area, which is what an occlusion test compares against. For a reversed depth
+    /// buffer, use `Min`.
+    ///
+    /// The value must be `Min` or `Max`.
+    ///
+    /// The default value is [`SamplerReductionMode::Max`].
+    pub reduction_mode: SamplerReductionMode,
+}
+
+impl Default for DepthPyramidCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            reduction_mode: SamplerReductionMode::Max,
+        }
+    }
+}
+
+/// Error that can happen when creating a `DepthPyramid`.
+#[derive(Clone, Debug)]
+pub enum DepthPyramidError {
+    ComputePipelineCreationError(ComputePipelineCreationError),
+    DescriptorSetCreationError(DescriptorSetCreationError),
+    ImageCreationError(ImageCreationError),
+    ImageViewCreationError(ImageViewCreationError),
+    SamplerCreationError(SamplerCreationError),
+    ShaderCreationError(ShaderCreationError),
+
+    /// The format of the depth image view does not support the `sampled_image_filter_minmax`
+    /// format feature.
+    DepthViewFormatNotSupported,
+
+    /// The depth image view does not select only the `depth` aspect.
+    DepthViewNotDepthAspect,
+
+    /// The depth image view does not have the `Dim2d` view type.
+    DepthViewNotDim2d,
+
+    /// The reduction mode is `WeightedAverage`.
+    ReductionModeWeightedAverage,
+}
+
+impl Error for DepthPyramidError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::ComputePipelineCreationError(err) => Some(err),
+            Self::DescriptorSetCreationError(err) => Some(err),
+            Self::ImageCreationError(err) => Some(err),
+            Self::ImageViewCreationError(err) => Some(err),
+            Self::SamplerCreationError(err) => Some(err),
+            Self::ShaderCreationError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for DepthPyramidError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::ComputePipelineCreationError(_) => {
+                write!(f, "creating the compute pipeline failed")
+            }
+            Self::DescriptorSetCreationError(_) => write!(f, "creating a descriptor set failed"),
+            Self::ImageCreationError(_) => write!(f, "creating the pyramid image failed"),
+            Self::ImageViewCreationError(_) => write!(f, "creating an image view failed"),
+            Self::SamplerCreationError(_) => write!(f, "creating the sampler failed"),
+            Self::ShaderCreationError(_) => write!(f, "creating the shader module failed"),
+            Self::DepthViewFormatNotSupported => write!(
+                f,
+                "the format of the depth image view does not support the \
+                `sampled_image_filter_minmax` format feature",
+            ),
+            Self::DepthViewNotDepthAspect => write!(
+                f,
+                "the depth image view does not select only the `depth` aspect",
+            ),
+            Self::DepthViewNotDim2d => write!(
+                f,
+                "the depth image view does not have the `Dim2d` view type",
+            ),
+            Self::ReductionModeWeightedAverage => {
+                write!(f, "the reduction mode is `WeightedAverage`")
+            }
+        }
+    }
+}
+
+impl From<ComputePipelineCreationError> for DepthPyramidError {
+    fn from(err: ComputePipelineCreationError) -> Self {
+        Self::ComputePipelineCreationError(err)
+    }
+}
+
+impl From<DescriptorSetCreationError> for DepthPyramidError {
+    fn from(err: DescriptorSetCreationError) -> Self {
+        Self::DescriptorSetCreationError(err)
+    }
+}
+
+impl From<ImageCreationError> for DepthPyramidError {
+    fn from(err: ImageCreationError) -> Self {
+        Self::ImageCreationError(err)
+    }
+}
+
+impl From<ImageViewCreationError> for DepthPyramidError {
+    fn from(err: ImageViewCreationError) -> Self {
+        Self::ImageViewCreationError(err)
+    }
+}
+
+impl From<SamplerCreationError> for DepthPyramidError {
+    fn from(err: SamplerCreationError) -> Self {
+        Self::SamplerCreationError(err)
+    }
+}
+
+impl From<ShaderCreationError> for DepthPyramidError {
+    fn from(err: ShaderCreationError) -> Self {
+        Self::ShaderCreationError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vulkano::{
+        command_buffer::{allocator::StandardCommandBufferAllocator, CommandBufferUsage},
+        descriptor_set::allocator::StandardDescriptorSetAllocator,
+        image::AttachmentImage,
+        sync::{self, GpuFuture},
+    };
+
+    #[test]
+    fn reduction_mode_weighted_average() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let depth = AttachmentImage::sampled(device.clone(), [64, 64], Format::D16_UNORM).unwrap();
+        let depth = ImageView::new_default(depth).unwrap();
+        let descriptor_set_allocator = StandardDescriptorSetAllocator::new(device);
+
+        match DepthPyramid::new(
+            &descriptor_set_allocator,
+            depth,
+            DepthPyramidCreateInfo {
+                reduction_mode: SamplerReductionMode::WeightedAverage,
+            },
+        ) {
+            Err(DepthPyramidError::ReductionModeWeightedAverage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn odd_dimensions() {
+        let (device, queue) = gfx_dev_and_queue!(sampler_filter_minmax);
+
+        let depth = AttachmentImage::sampled(device.clone(), [65, 33], Format::D32_SFLOAT).unwrap();
+        let depth = ImageView::new_default(depth).unwrap();
+
+        if !depth.format_features().sampled_image_filter_minmax {
+            return; // test ignored
+        }
+
+        let descriptor_set_allocator = StandardDescriptorSetAllocator::new(device.clone());
+        let pyramid =
+            DepthPyramid::new(&descriptor_set_allocator, depth, Default::default()).unwrap();
+        assert_eq!(pyramid.image().dimensions().width_height(), [32, 16]);
+        assert_eq!(pyramid.image().mip_levels(), 6);
+
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device.clone());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        pyramid.record(&mut builder).unwrap();
+        let command_buffer = builder.build().unwrap();
+
+        sync::now(device)
+            .then_execute(queue, command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+}
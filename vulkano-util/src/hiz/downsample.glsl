@@ -0,0 +1,51 @@
+#version 450
+
+// Downsamples one level of a depth pyramid into the next one. Each texel of `dst` holds the
+// minimum or maximum of the texels of `src` that it covers.
+
+layout(local_size_x = 8, local_size_y = 8, local_size_z = 1) in;
+
+// Whether the sampler reduces to the maximum of the texels, rather than the minimum.
+layout(constant_id = 0) const bool reduce_max = true;
+
+layout(set = 0, binding = 0) uniform sampler2D src;
+layout(set = 0, binding = 1, r32f) uniform writeonly image2D dst;
+
+float reduce(float a, float b) {
+    return reduce_max ? max(a, b) : min(a, b);
+}
+
+void main() {
+    ivec2 pos = ivec2(gl_GlobalInvocationID.xy);
+    ivec2 dst_size = imageSize(dst);
+
+    if (pos.x >= dst_size.x || pos.y >= dst_size.y) {
+        return;
+    }
+
+    ivec2 src_size = textureSize(src, 0);
+    vec2 texel_size = 1.0 / vec2(src_size);
+
+    // The sampler reduces the 2x2 texels of `src` that surround `uv`.
+    vec2 uv = vec2(pos * 2 + 1) * texel_size;
+    float depth = textureLod(src, uv, 0.0).r;
+
+    // When `src` has an odd width or height, the last column or row of `dst` also covers the last
+    // column or row of `src`. Sampling two texels further clamps to it.
+    bool extra_x = (src_size.x & 1) != 0 && pos.x == dst_size.x - 1;
+    bool extra_y = (src_size.y & 1) != 0 && pos.y == dst_size.y - 1;
+
+    if (extra_x) {
+        depth = reduce(depth, textureLod(src, uv + vec2(2.0 * texel_size.x, 0.0), 0.0).r);
+    }
+
+    if (extra_y) {
+        depth = reduce(depth, textureLod(src, uv + vec2(0.0, 2.0 * texel_size.y), 0.0).r);
+    }
+
+    if (extra_x && extra_y) {
+        depth = reduce(depth, textureLod(src, uv + 2.0 * texel_size, 0.0).r);
+    }
+
+    imageStore(dst, pos, vec4(depth));
+}
@@ -10,6 +10,19 @@
 #![allow(clippy::missing_safety_doc)]
 #![warn(rust_2018_idioms, rust_2021_compatibility)]
 
+#[macro_use]
+mod tests;
+
 pub mod context;
+pub mod handle_table;
+pub mod hiz;
+pub mod picking;
+pub mod profiling;
+pub mod readback;
 pub mod renderer;
+pub mod rendergraph;
+pub mod shader_assert;
+pub mod submitter;
+pub mod subpass_inputs;
+pub mod upload;
 pub mod window;
@@ -0,0 +1,322 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Pixel-perfect object picking with an ID buffer.
+//!
+//! The application renders the scene a second time, or as an additional color attachment of its
+//! main pass, with pipelines whose fragment shader writes an object ID to an `R32_UINT` output.
+//! The attachment is provided by [`PickingBuffer`], which can then read back the ID under a given
+//! pixel with [`pick`](PickingBuffer::pick).
+//!
+//! The ID `0` is reserved to mean "no object", and is the value that the attachment should be
+//! cleared to (see [`PickingBuffer::CLEAR_VALUE`]).
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use vulkano::sync::{self, GpuFuture};
+//! use vulkano_util::picking::PickingBuffer;
+//! # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+//! # let render_future: Box<dyn GpuFuture> = return;
+//!
+//! let picking_buffer = PickingBuffer::new(queue, [1920, 1080]).unwrap();
+//!
+//! // Record a render pass that uses `picking_buffer.image_view()` as an attachment, and clears
+//! // it to `PickingBuffer::CLEAR_VALUE`...
+//!
+//! let object_id = picking_buffer.pick(640, 360, render_future).unwrap().wait().unwrap();
+//!
+//! if let Some(object_id) = object_id {
+//!     println!("clicked on object {}", object_id);
+//! }
+//! ```
+
+use crate::readback::{ReadbackError, ReadbackFuture};
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+use vulkano::{
+    command_buffer::allocator::StandardCommandBufferAllocator,
+    device::{DeviceOwned, Queue},
+    format::{ClearValue, Format},
+    image::{
+        view::{ImageView, ImageViewCreationError},
+        AttachmentImage, ImageAccess, ImageCreationError, ImageUsage,
+    },
+    sync::GpuFuture,
+};
+
+/// An `R32_UINT` color attachment that object IDs are rendered into, and that can be queried for
+/// the ID under a pixel.
+pub struct PickingBuffer {
+    queue: Arc<Queue>,
+    command_buffer_allocator: StandardCommandBufferAllocator,
+    image_view: Arc<ImageView<AttachmentImage>>,
+}
+
+impl PickingBuffer {
+    /// The format of the attachment.
+    pub const FORMAT: Format = Format::R32_UINT;
+
+    /// The value that the attachment should be cleared to before rendering object IDs.
+    pub const CLEAR_VALUE: ClearValue = ClearValue::Uint([0; 4]);
+
+    /// Creates a new `PickingBuffer` with the given dimensions, whose readbacks are submitted to
+    /// `queue`.
+    pub fn new(
+        queue: Arc<Queue>,
+        dimensions: [u32; 2],
+    ) -> Result<Self, PickingBufferCreationError> {
+        let image_view = Self::create_image_view(&queue, dimensions)?;
+
+        Ok(PickingBuffer {
+            command_buffer_allocator: StandardCommandBufferAllocator::new(queue.device().clone()),
+            queue,
+            image_view,
+        })
+    }
+
+    /// Recreates the attachment with new dimensions, for example after the window was resized.
+    ///
+    /// Framebuffers that use the previous image view must be recreated as well.
+    pub fn resize(&mut self, dimensions: [u32; 2]) -> Result<(), PickingBufferCreationError> {
+        if self.dimensions() != dimensions {
+            self.image_view = Self::create_image_view(&self.queue, dimensions)?;
+        }
+
+        Ok(())
+    }
+
+    fn create_image_view(
+        queue: &Queue,
+        dimensions: [u32; 2],
+    ) -> Result<Arc<ImageView<AttachmentImage>>, PickingBufferCreationError> {
+        let image = AttachmentImage::with_usage(
+            queue.device().clone(),
+            dimensions,
+            Self::FORMAT,
+            ImageUsage {
+                color_attachment: true,
+                transfer_src: true,
+                ..ImageUsage::empty()
+            },
+        )?;
+
+        Ok(ImageView::new_default(image)?)
+    }
+
+    /// Returns the image view to use as the color attachment that object IDs are written to.
+    #[inline]
+    pub fn image_view(&self) -> &Arc<ImageView<AttachmentImage>> {
+        &self.image_view
+    }
+
+    /// Returns the dimensions of the attachment.
+    #[inline]
+    pub fn dimensions(&self) -> [u32; 2] {
+        self.image_view.image().dimensions().width_height()
+    }
+
+    /// Reads back the object ID at pixel `x`, `y` once `after` has completed.
+    ///
+    /// `after` should be the future of the submission that rendered the object IDs. The returned
+    /// future produces `None` if no object was rendered at that pixel.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `x` or `y` is outside the dimensions of the attachment.
+    pub fn pick(
+        &self,
+        x: u32,
+        y: u32,
+        after: impl GpuFuture + 'static,
+    ) -> Result<ReadbackFuture<Option<u32>>, ReadbackError> {
+        let [width, height] = self.dimensions();
+        assert!(x < width && y < height);
+
+        let future = ReadbackFuture::image_region(
+            &self.command_buffer_allocator,
+            self.queue.clone(),
+            self.image_view.image().clone(),
+            [x, y, 0],
+            [1, 1, 1],
+            after,
+        )?;
+
+        Ok(future.map(|bytes| {
+            let id = u32::from_ne_bytes(bytes[..4].try_into().unwrap());
+            (id != 0).then_some(id)
+        }))
+    }
+}
+
+/// Error that can happen when creating a [`PickingBuffer`].
+#[derive(Clone, Debug)]
+pub enum PickingBufferCreationError {
+    /// Creating the image failed.
+    ImageCreation(ImageCreationError),
+
+    /// Creating the image view failed.
+    ImageViewCreation(ImageViewCreationError),
+}
+
+impl Error for PickingBufferCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::ImageCreation(err) => Some(err),
+            Self::ImageViewCreation(err) => Some(err),
+        }
+    }
+}
+
+impl Display for PickingBufferCreationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::ImageCreation(_) => write!(f, "creating the image failed"),
+            Self::ImageViewCreation(_) => write!(f, "creating the image view failed"),
+        }
+    }
+}
+
+impl From<ImageCreationError> for PickingBufferCreationError {
+    #[inline]
+    fn from(err: ImageCreationError) -> Self {
+        Self::ImageCreation(err)
+    }
+}
+
+impl From<ImageViewCreationError> for PickingBufferCreationError {
+    #[inline]
+    fn from(err: ImageViewCreationError) -> Self {
+        Self::ImageViewCreation(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vulkano::{
+        command_buffer::{
+            AutoCommandBufferBuilder, CommandBufferUsage, RenderPassBeginInfo, SubpassContents,
+        },
+        render_pass::{Framebuffer, FramebufferCreateInfo},
+        sync,
+    };
+
+    fn pick_after_clear(
+        queue: Arc<Queue>,
+        picking_buffer: &PickingBuffer,
+        clear_value: ClearValue,
+    ) -> Option<u32> {
+        let device = queue.device().clone();
+        let render_pass = vulkano::single_pass_renderpass!(
+            device.clone(),
+            attachments: {
+                ids: {
+                    load: Clear,
+                    store: Store,
+                    format: PickingBuffer::FORMAT,
+                    samples: 1,
+                }
+            },
+            pass: {
+                color: [ids],
+                depth_stencil: {}
+            }
+        )
+        .unwrap();
+        let framebuffer = Framebuffer::new(
+            render_pass,
+            FramebufferCreateInfo {
+                attachments: vec![picking_buffer.image_view().clone() as _],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device.clone());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        builder
+            .begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values: vec![Some(clear_value)],
+                    ..RenderPassBeginInfo::framebuffer(framebuffer)
+                },
+                SubpassContents::Inline,
+            )
+            .unwrap()
+            .end_render_pass()
+            .unwrap();
+        let render_future = sync::now(device)
+            .then_execute(queue, builder.build().unwrap())
+            .unwrap();
+
+        picking_buffer
+            .pick(3, 2, render_future)
+            .unwrap()
+            .wait()
+            .unwrap()
+    }
+
+    #[test]
+    fn pick_object() {
+        let (_device, queue) = gfx_dev_and_queue!();
+
+        let picking_buffer = PickingBuffer::new(queue.clone(), [4, 4]).unwrap();
+
+        assert_eq!(
+            pick_after_clear(queue, &picking_buffer, ClearValue::Uint([42, 0, 0, 0])),
+            Some(42),
+        );
+    }
+
+    #[test]
+    fn pick_nothing() {
+        let (_device, queue) = gfx_dev_and_queue!();
+
+        let picking_buffer = PickingBuffer::new(queue.clone(), [4, 4]).unwrap();
+
+        assert_eq!(
+            pick_after_clear(queue, &picking_buffer, PickingBuffer::CLEAR_VALUE),
+            None,
+        );
+    }
+
+    #[test]
+    fn resize() {
+        let (_device, queue) = gfx_dev_and_queue!();
+
+        let mut picking_buffer = PickingBuffer::new(queue, [4, 4]).unwrap();
+        let image_view = picking_buffer.image_view().clone();
+
+        picking_buffer.resize([4, 4]).unwrap();
+        assert!(Arc::ptr_eq(&image_view, picking_buffer.image_view()));
+
+        picking_buffer.resize([8, 2]).unwrap();
+        assert_eq!(picking_buffer.dimensions(), [8, 2]);
+    }
+
+    #[test]
+    fn pick_out_of_bounds() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let picking_buffer = PickingBuffer::new(queue, [4, 4]).unwrap();
+
+        assert_should_panic!({
+            let _ = picking_buffer.pick(4, 0, sync::now(device));
+        });
+    }
+}
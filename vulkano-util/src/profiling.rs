@@ -0,0 +1,785 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Measuring how long the device spends on parts of a frame.
+//!
+//! A [`GpuProfiler`] writes a pair of timestamp queries around each scope that is opened with
+//! [`scope`](GpuProfiler::scope). Scopes can be nested, and if the
+//! [`ext_debug_utils`](vulkano::instance::InstanceExtensions::ext_debug_utils) extension is
+//! enabled on the instance, each scope is also a debug label region, so that it shows up by name
+//! in graphics debuggers.
+//!
+//! The profiler has one query pool for each frame in flight. When a frame is started with
+//! [`begin_frame`](GpuProfiler::begin_frame), the results of the frame that last used the same
+//! query pool are read back and returned, and the queries that they were read from are reset. If
+//! those results are not available yet, the frame continues with a new query pool instead. If the
+//! [`ext_calibrated_timestamps`](vulkano::device::DeviceExtensions::ext_calibrated_timestamps)
+//! extension is enabled on the device, and the host clock can be calibrated against the device,
+//! the results also contain the host time at which each scope started on the device.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use vulkano::command_buffer::{AutoCommandBufferBuilder, CommandBufferUsage};
+//! use vulkano_util::profiling::GpuProfiler;
+//! # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+//! # let command_buffer_allocator: vulkano::command_buffer::allocator::StandardCommandBufferAllocator = return;
+//!
+//! let mut profiler =
+//!     GpuProfiler::new(queue.device().clone(), queue.queue_family_index(), 2, 64).unwrap();
+//!
+//! let mut builder = AutoCommandBufferBuilder::primary(
+//!     &command_buffer_allocator,
+//!     queue.queue_family_index(),
+//!     CommandBufferUsage::OneTimeSubmit,
+//! )
+//! .unwrap();
+//!
+//! if let Some(profile) = profiler.begin_frame(&mut builder).unwrap() {
+//!     for scope in &profile.scopes {
+//!         println!("{:indent$}{}: {:?}", "", scope.name, scope.duration, indent = scope.depth as usize * 2);
+//!     }
+//! }
+//!
+//! {
+//!     let mut shadow_pass = profiler.scope("shadow pass", &mut builder).unwrap();
+//!     // Record the commands of the shadow pass into `shadow_pass`.
+//! }
+//! ```
+
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    ops::{Deref, DerefMut},
+    sync::Arc,
+    time::Duration,
+};
+use vulkano::{
+    command_buffer::{
+        allocator::CommandBufferAllocator, AutoCommandBufferBuilder, DebugUtilsError, QueryError,
+    },
+    device::{
+        physical::{PhysicalDeviceError, TimeDomain},
+        CalibratedTimestampsError, Device,
+    },
+    instance::debug::DebugUtilsLabel,
+    query::{
+        GetResultsError, QueryPool, QueryPoolCreateInfo, QueryPoolCreationError, QueryResultFlags,
+        QueryType,
+    },
+    sync::PipelineStage,
+};
+
+/// Records timestamp queries around scopes of a command buffer, and reads back their results.
+pub struct GpuProfiler {
+    device: Arc<Device>,
+    frames: Vec<ProfilerFrame>,
+    current_frame: Option<usize>,
+    max_scopes: u32,
+    timestamp_mask: u64,
+    timestamp_period: f32,
+    host_time_domain: Option<TimeDomain>,
+    debug_labels: bool,
+    deferred_error: Option<GpuProfilerError>,
+}
+
+impl GpuProfiler {
+    /// Creates a new `GpuProfiler` for command buffers that are executed on queues of the family
+    /// `queue_family_index`.
+    ///
+    /// `frames_in_flight` query pools are created, each of which can hold the timestamps of
+    /// `max_scopes` scopes.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `frames_in_flight` is 0.
+    pub fn new(
+        device: Arc<Device>,
+        queue_family_index: u32,
+        frames_in_flight: usize,
+        max_scopes: u32,
+    ) -> Result<Self, GpuProfilerError> {
+        assert!(frames_in_flight != 0);
+
+        let timestamp_valid_bits = device.physical_device().queue_family_properties()
+            [queue_family_index as usize]
+            .timestamp_valid_bits
+            .ok_or(GpuProfilerError::TimestampsNotSupported)?;
+        let timestamp_mask = u64::MAX >> (64 - timestamp_valid_bits.min(64));
+
+        let frames = (0..frames_in_flight)
+            .map(|_| {
+                Ok(ProfilerFrame {
+                    query_pool: Self::create_query_pool(&device, max_scopes)?,
+                    reset_all: true,
+                    scopes: Vec::new(),
+                    open_scopes: Vec::new(),
+                    calibration: None,
+                })
+            })
+            .collect::<Result<_, GpuProfilerError>>()?;
+
+        // The host clocks that are measured in nanoseconds, in order of preference.
+        // `ClockMonotonic` is also the clock of `std::time::Instant` on Linux.
+        let host_time_domain = if device.enabled_extensions().ext_calibrated_timestamps {
+            let time_domains = device.physical_device().calibrateable_time_domains()?;
+
+            if time_domains.contains(&TimeDomain::Device) {
+                [TimeDomain::ClockMonotonic, TimeDomain::ClockMonotonicRaw]
+                    .into_iter()
+                    .find(|time_domain| time_domains.contains(time_domain))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        Ok(GpuProfiler {
+            timestamp_period: device.physical_device().properties().timestamp_period,
+            debug_labels: device.instance().enabled_extensions().ext_debug_utils,
+            device,
+            frames,
+            current_frame: None,
+            max_scopes,
+            timestamp_mask,
+            host_time_domain,
+            deferred_error: None,
+        })
+    }
+
+    fn create_query_pool(
+        device: &Arc<Device>,
+        max_scopes: u32,
+    ) -> Result<Arc<QueryPool>, GpuProfilerError> {
+        Ok(QueryPool::new(
+            device.clone(),
+            QueryPoolCreateInfo {
+                query_count: max_scopes * 2,
+                ..QueryPoolCreateInfo::query_type(QueryType::Timestamp)
+            },
+        )?)
+    }
+
+    /// Returns the device that the profiler was created for.
+    #[inline]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+
+    /// Returns the host clock that [`ScopeTiming::host_start`] is measured with, or `None` if
+    /// the device timestamps can't be calibrated against a host clock.
+    #[inline]
+    pub fn host_time_domain(&self) -> Option<TimeDomain> {
+        self.host_time_domain
+    }
+
+    /// Starts a new frame, recording the reset of its queries into `builder`.
+    ///
+    /// Returns the results of the frame that was started `frames_in_flight` calls earlier, or
+    /// `None` if there is no such frame or its results are not available yet. The results are
+    /// available once the command buffers of that frame have finished executing, which is usually
+    /// known because the fence of that frame has been waited on before its resources are reused.
+    ///
+    /// The command must be recorded outside of a render pass.
+    ///
+    /// If closing a scope failed when it was dropped, that error is returned instead.
+    pub fn begin_frame<L, A>(
+        &mut self,
+        builder: &mut AutoCommandBufferBuilder<L, A>,
+    ) -> Result<Option<FrameProfile>, GpuProfilerError>
+    where
+        A: CommandBufferAllocator,
+    {
+        if let Some(err) = self.deferred_error.take() {
+            return Err(err);
+        }
+
+        let frame_index = self
+            .current_frame
+            .map_or(0, |frame_index| (frame_index + 1) % self.frames.len());
+        let profile = self.read_results(frame_index)?;
+
+        let frame = &mut self.frames[frame_index];
+
+        if !frame.scopes.is_empty() && profile.is_none() {
+            // The results of the previous use of this frame are not available, so its queries
+            // may still be in use by the device and can't be reset. The command buffers that use
+            // the old query pool keep it alive.
+            frame.query_pool = Self::create_query_pool(&self.device, self.max_scopes)?;
+            frame.reset_all = true;
+        }
+
+        // Only the queries whose results were read back have been written since they were last
+        // reset, unless the query pool has never been reset.
+        let reset_count = if frame.reset_all {
+            self.max_scopes * 2
+        } else {
+            frame.scopes.len() as u32 * 2
+        };
+
+        if reset_count != 0 {
+            unsafe {
+                builder.reset_query_pool(frame.query_pool.clone(), 0..reset_count)?;
+            }
+        }
+
+        frame.reset_all = false;
+        frame.scopes.clear();
+        frame.open_scopes.clear();
+        frame.calibration = None;
+
+        if let Some(host_time_domain) = self.host_time_domain {
+            // Both clocks are sampled by the same call, so that they refer to the same moment.
+            let (timestamps, _max_deviation) = self
+                .device
+                .calibrated_timestamps([TimeDomain::Device, host_time_domain])?;
+            frame.calibration = Some((timestamps[0], timestamps[1]));
+        }
+
+        self.current_frame = Some(frame_index);
+
+        Ok(profile)
+    }
+
+    /// Opens a scope named `name`, that ends when the returned `ProfilerScope` is dropped.
+    ///
+    /// The scope dereferences to `builder`, so commands can be recorded into the scope through it.
+    /// Nested scopes are opened with [`ProfilerScope::scope`].
+    ///
+    /// If closing a scope failed when it was dropped, that error is returned instead.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if [`begin_frame`](Self::begin_frame) hasn't been called yet.
+    pub fn scope<'a, L, A>(
+        &'a mut self,
+        name: impl Into<String>,
+        builder: &'a mut AutoCommandBufferBuilder<L, A>,
+    ) -> Result<ProfilerScope<'a, L, A>, GpuProfilerError>
+    where
+        A: CommandBufferAllocator,
+    {
+        if let Some(err) = self.deferred_error.take() {
+            return Err(err);
+        }
+
+        let frame_index = self
+            .current_frame
+            .expect("`begin_frame` must be called before opening a scope");
+        let frame = &mut self.frames[frame_index];
+
+        if frame.scopes.len() as u32 >= self.max_scopes {
+            return Err(GpuProfilerError::ScopeLimitExceeded {
+                max_scopes: self.max_scopes,
+            });
+        }
+
+        let name = name.into();
+        let scope_index = frame.scopes.len();
+
+        // The queries were reset in `begin_frame`, and each query is written only once per frame.
+        unsafe {
+            builder.write_timestamp(
+                frame.query_pool.clone(),
+                scope_index as u32 * 2,
+                PipelineStage::TopOfPipe,
+            )?;
+        }
+
+        if self.debug_labels {
+            builder.begin_debug_utils_label(DebugUtilsLabel {
+                label_name: name.clone(),
+                ..Default::default()
+            })?;
+        }
+
+        frame.scopes.push(ScopeRecord {
+            name,
+            parent: frame.open_scopes.last().copied(),
+            depth: frame.open_scopes.len() as u32,
+        });
+        frame.open_scopes.push(scope_index);
+
+        Ok(ProfilerScope {
+            profiler: self,
+            builder,
+            frame_index,
+            scope_index,
+            closed: false,
+        })
+    }
+
+    fn read_results(&self, frame_index: usize) -> Result<Option<FrameProfile>, GpuProfilerError> {
+        let frame = &self.frames[frame_index];
+
+        if frame.scopes.is_empty() {
+            return Ok(None);
+        }
+
+        let mut timestamps = vec![0u64; frame.scopes.len() * 2];
+        let available = frame
+            .query_pool
+            .queries_range(0..timestamps.len() as u32)
+            .unwrap()
+            .get_results(&mut timestamps, QueryResultFlags::empty())?;
+
+        if !available {
+            return Ok(None);
+        }
+
+        let first_timestamp = timestamps[0];
+        let scopes = frame
+            .scopes
+            .iter()
+            .zip(timestamps.chunks_exact(2))
+            .map(|(scope, timestamps)| {
+                let (begin, end) = (timestamps[0], timestamps[1]);
+
+                ScopeTiming {
+                    name: scope.name.clone(),
+                    parent: scope.parent,
+                    depth: scope.depth,
+                    start: self.ticks_to_duration(begin.wrapping_sub(first_timestamp)),
+                    duration: self.ticks_to_duration(end.wrapping_sub(begin)),
+                    host_start: frame.calibration.and_then(|(device, host)| {
+                        let host = Duration::from_nanos(host);
+                        let ticks = begin.wrapping_sub(device) & self.timestamp_mask;
+
+                        // Scopes normally start on the device after the calibration, so a
+                        // difference of more than half the range means that it started before.
+                        if ticks > self.timestamp_mask / 2 {
+                            let ticks = device.wrapping_sub(begin) & self.timestamp_mask;
+                            host.checked_sub(self.ticks_to_duration(ticks))
+                        } else {
+                            host.checked_add(self.ticks_to_duration(ticks))
+                        }
+                    }),
+                }
+            })
+            .collect();
+
+        Ok(Some(FrameProfile { scopes }))
+    }
+
+    fn ticks_to_duration(&self, ticks: u64) -> Duration {
+        let ticks = ticks & self.timestamp_mask;
+        Duration::from_nanos((ticks as f64 * self.timestamp_period as f64) as u64)
+    }
+}
+
+struct ProfilerFrame {
+    query_pool: Arc<QueryPool>,
+    // Whether none of the queries of `query_pool` have been reset yet.
+    reset_all: bool,
+    scopes: Vec<ScopeRecord>,
+    open_scopes: Vec<usize>,
+    // A device timestamp and the host timestamp that was sampled at the same moment.
+    calibration: Option<(u64, u64)>,
+}
+
+struct ScopeRecord {
+    name: String,
+    parent: Option<usize>,
+    depth: u32,
+}
+
+/// A scope of a [`GpuProfiler`], opened by [`GpuProfiler::scope`], that is closed when it is
+/// dropped or when [`end`](ProfilerScope::end) is called.
+///
+/// If closing the scope fails when it is dropped, the error is returned by the next call to
+/// [`GpuProfiler::begin_frame`] or [`GpuProfiler::scope`].
+pub struct ProfilerScope<'a, L, A>
+where
+    A: CommandBufferAllocator,
+{
+    profiler: &'a mut GpuProfiler,
+    builder: &'a mut AutoCommandBufferBuilder<L, A>,
+    frame_index: usize,
+    scope_index: usize,
+    closed: bool,
+}
+
+impl<'a, L, A> ProfilerScope<'a, L, A>
+where
+    A: CommandBufferAllocator,
+{
+    /// Opens a scope nested inside this one.
+    pub fn scope(
+        &mut self,
+        name: impl Into<String>,
+    ) -> Result<ProfilerScope<'_, L, A>, GpuProfilerError> {
+        self.profiler.scope(name, self.builder)
+    }
+
+    /// Closes the scope, returning any error that happens while doing so.
+    #[inline]
+    pub fn end(mut self) -> Result<(), GpuProfilerError> {
+        self.close()
+    }
+
+    fn close(&mut self) -> Result<(), GpuProfilerError> {
+        self.closed = true;
+
+        let frame = &mut self.profiler.frames[self.frame_index];
+        frame.open_scopes.pop();
+
+        // The queries were reset in `begin_frame`, and each query is written only once per frame.
+        unsafe {
+            self.builder.write_timestamp(
+                frame.query_pool.clone(),
+                self.scope_index as u32 * 2 + 1,
+                PipelineStage::BottomOfPipe,
+            )?;
+        }
+
+        if self.profiler.debug_labels {
+            // The debug label region was opened in this command buffer when the scope was opened.
+            unsafe {
+                self.builder.end_debug_utils_label()?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, L, A> Deref for ProfilerScope<'a, L, A>
+where
+    A: CommandBufferAllocator,
+{
+    type Target = AutoCommandBufferBuilder<L, A>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.builder
+    }
+}
+
+impl<'a, L, A> DerefMut for ProfilerScope<'a, L, A>
+where
+    A: CommandBufferAllocator,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.builder
+    }
+}
+
+impl<'a, L, A> Drop for ProfilerScope<'a, L, A>
+where
+    A: CommandBufferAllocator,
+{
+    fn drop(&mut self) {
+        if self.closed {
+            return;
+        }
+
+        if let Err(err) = self.close() {
+            self.profiler.deferred_error.get_or_insert(err);
+        }
+    }
+}
+
+/// The timings of the scopes of one frame.
+#[derive(Clone, Debug)]
+pub struct FrameProfile {
+    /// The scopes, in the order they were opened.
+    pub scopes: Vec<ScopeTiming>,
+}
+
+impl FrameProfile {
+    /// Returns the indices and timings of the scopes directly inside the scope with index
+    /// `parent`, or of the outermost scopes if `parent` is `None`.
+    pub fn children(
+        &self,
+        parent: Option<usize>,
+    ) -> impl Iterator<Item = (usize, &ScopeTiming)> + '_ {
+        self.scopes
+            .iter()
+            .enumerate()
+            .filter(move |(_, scope)| scope.parent == parent)
+    }
+}
+
+/// The timing of a single scope of a [`FrameProfile`].
+#[derive(Clone, Debug)]
+pub struct ScopeTiming {
+    /// The name that the scope was opened with.
+    pub name: String,
+
+    /// The index of the scope that this scope is nested in, if any.
+    pub parent: Option<usize>,
+
+    /// The number of scopes that this scope is nested in.
+    pub depth: u32,
+
+    /// The time between the start of the first scope of the frame and the start of this scope.
+    pub start: Duration,
+
+    /// The time between the start and the end of this scope.
+    pub duration: Duration,
+
+    /// The time at which the scope started on the device, as a value of the host clock given by
+    /// [`GpuProfiler::host_time_domain`], if there is one.
+    pub host_start: Option<Duration>,
+}
+
+/// Error that can happen when using a [`GpuProfiler`].
+#[derive(Clone, Debug)]
+pub enum GpuProfilerError {
+    /// The queue family doesn't support timestamp queries.
+    TimestampsNotSupported,
+
+    /// More scopes were opened in a frame than the profiler was created for.
+    ScopeLimitExceeded { max_scopes: u32 },
+
+    /// Creating a query pool failed.
+    QueryPoolCreation(QueryPoolCreationError),
+
+    /// Recording a query command failed.
+    Query(QueryError),
+
+    /// Recording a debug label command failed.
+    DebugUtils(DebugUtilsError),
+
+    /// Reading the results of the queries failed.
+    GetResults(GetResultsError),
+
+    /// Querying the clocks that the device timestamps can be calibrated against failed.
+    PhysicalDevice(PhysicalDeviceError),
+
+    /// Calibrating the device timestamps against the host failed.
+    CalibratedTimestamps(CalibratedTimestampsError),
+}
+
+impl Error for GpuProfilerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::QueryPoolCreation(err) => Some(err),
+            Self::Query(err) => Some(err),
+            Self::DebugUtils(err) => Some(err),
+            Self::GetResults(err) => Some(err),
+            Self::PhysicalDevice(err) => Some(err),
+            Self::CalibratedTimestamps(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for GpuProfilerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::TimestampsNotSupported => {
+                write!(f, "the queue family doesn't support timestamp queries")
+            }
+            Self::ScopeLimitExceeded { max_scopes } => {
+                write!(f, "more than {} scopes were opened in a frame", max_scopes,)
+            }
+            Self::QueryPoolCreation(_) => write!(f, "creating a query pool failed"),
+            Self::Query(_) => write!(f, "recording a query command failed"),
+            Self::DebugUtils(_) => write!(f, "recording a debug label command failed"),
+            Self::GetResults(_) => write!(f, "reading the results of the queries failed"),
+            Self::PhysicalDevice(_) => write!(
+                f,
+                "querying the clocks that the device timestamps can be calibrated against failed",
+            ),
+            Self::CalibratedTimestamps(_) => write!(
+                f,
+                "calibrating the device timestamps against the host failed",
+            ),
+        }
+    }
+}
+
+impl From<QueryPoolCreationError> for GpuProfilerError {
+    #[inline]
+    fn from(err: QueryPoolCreationError) -> Self {
+        Self::QueryPoolCreation(err)
+    }
+}
+
+impl From<QueryError> for GpuProfilerError {
+    #[inline]
+    fn from(err: QueryError) -> Self {
+        Self::Query(err)
+    }
+}
+
+impl From<DebugUtilsError> for GpuProfilerError {
+    #[inline]
+    fn from(err: DebugUtilsError) -> Self {
+        Self::DebugUtils(err)
+    }
+}
+
+impl From<GetResultsError> for GpuProfilerError {
+    #[inline]
+    fn from(err: GetResultsError) -> Self {
+        Self::GetResults(err)
+    }
+}
+
+impl From<PhysicalDeviceError> for GpuProfilerError {
+    #[inline]
+    fn from(err: PhysicalDeviceError) -> Self {
+        Self::PhysicalDevice(err)
+    }
+}
+
+impl From<CalibratedTimestampsError> for GpuProfilerError {
+    #[inline]
+    fn from(err: CalibratedTimestampsError) -> Self {
+        Self::CalibratedTimestamps(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vulkano::{
+        command_buffer::{allocator::StandardCommandBufferAllocator, CommandBufferUsage},
+        sync::{self, GpuFuture},
+    };
+
+    #[test]
+    fn zero_frames_in_flight() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        assert_should_panic!({
+            let _ = GpuProfiler::new(device, queue.queue_family_index(), 0, 4);
+        });
+    }
+
+    #[test]
+    fn scope_limit() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let mut profiler = match GpuProfiler::new(device.clone(), queue.queue_family_index(), 1, 1)
+        {
+            Ok(x) => x,
+            Err(GpuProfilerError::TimestampsNotSupported) => return, // test ignored
+            Err(err) => panic!("{}", err),
+        };
+
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device);
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert!(profiler.begin_frame(&mut builder).unwrap().is_none());
+        profiler
+            .scope("first", &mut builder)
+            .unwrap()
+            .end()
+            .unwrap();
+
+        match profiler.scope("second", &mut builder) {
+            Err(GpuProfilerError::ScopeLimitExceeded { max_scopes: 1 }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn nested_scopes() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let mut profiler = match GpuProfiler::new(device.clone(), queue.queue_family_index(), 1, 4)
+        {
+            Ok(x) => x,
+            Err(GpuProfilerError::TimestampsNotSupported) => return, // test ignored
+            Err(err) => panic!("{}", err),
+        };
+
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device.clone());
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert!(profiler.begin_frame(&mut builder).unwrap().is_none());
+        {
+            let mut outer = profiler.scope("outer", &mut builder).unwrap();
+            outer.scope("inner").unwrap().end().unwrap();
+        }
+
+        let command_buffer = builder.build().unwrap();
+        sync::now(device)
+            .then_execute(queue.clone(), command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        let profile = profiler.begin_frame(&mut builder).unwrap().unwrap();
+
+        assert_eq!(profile.scopes.len(), 2);
+        assert_eq!(profile.scopes[0].name, "outer");
+        assert_eq!(profile.scopes[0].parent, None);
+        assert_eq!(profile.scopes[0].depth, 0);
+        assert_eq!(profile.scopes[1].name, "inner");
+        assert_eq!(profile.scopes[1].parent, Some(0));
+        assert_eq!(profile.scopes[1].depth, 1);
+        assert_eq!(
+            profile.scopes[0].host_start.is_some(),
+            profiler.host_time_domain().is_some(),
+        );
+
+        let children: Vec<_> = profile.children(Some(0)).map(|(index, _)| index).collect();
+        assert_eq!(children, [1]);
+    }
+
+    #[test]
+    fn results_not_available() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let mut profiler = match GpuProfiler::new(device.clone(), queue.queue_family_index(), 1, 4)
+        {
+            Ok(x) => x,
+            Err(GpuProfilerError::TimestampsNotSupported) => return, // test ignored
+            Err(err) => panic!("{}", err),
+        };
+
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device);
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert!(profiler.begin_frame(&mut builder).unwrap().is_none());
+        profiler
+            .scope("never submitted", &mut builder)
+            .unwrap()
+            .end()
+            .unwrap();
+        let old_query_pool = profiler.frames[0].query_pool.clone();
+
+        // The command buffer was never executed, so its queries must not be reset.
+        assert!(profiler.begin_frame(&mut builder).unwrap().is_none());
+        assert!(!Arc::ptr_eq(
+            &profiler.frames[0].query_pool,
+            &old_query_pool
+        ));
+        assert!(profiler.frames[0].scopes.is_empty());
+    }
+}
@@ -0,0 +1,421 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Reading data back from the device to the host.
+//!
+//! A [`ReadbackFuture`] records a copy of a buffer or image into a host-visible buffer, submits
+//! it after a previous future, and signals a fence. Once the fence is signaled, the copied bytes
+//! are converted into a value by a chain of closures added with [`map`](ReadbackFuture::map).
+//! This is the same whether the data is a screenshot, an object ID for picking or query
+//! statistics.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use vulkano::sync::{self, GpuFuture};
+//! use vulkano_util::readback::ReadbackFuture;
+//! # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+//! # let command_buffer_allocator: vulkano::command_buffer::allocator::StandardCommandBufferAllocator = return;
+//! # let buffer: std::sync::Arc<vulkano::buffer::DeviceLocalBuffer<[u32]>> = return;
+//!
+//! let future = ReadbackFuture::buffer(
+//!     &command_buffer_allocator,
+//!     queue.clone(),
+//!     buffer,
+//!     sync::now(queue.device().clone()),
+//! )
+//! .unwrap()
+//! .map(|bytes| u32::from_ne_bytes(bytes[..4].try_into().unwrap()));
+//!
+//! let object_id = future.wait().unwrap();
+//! ```
+
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+use vulkano::{
+    buffer::{cpu_access::ReadLockError, BufferAccess, BufferUsage, CpuAccessibleBuffer},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, BufferImageCopy,
+        BuildError, CommandBufferBeginError, CommandBufferExecError, CommandBufferExecFuture,
+        CommandBufferUsage, CopyBufferInfo, CopyError, CopyImageToBufferInfo,
+        PrimaryAutoCommandBuffer,
+    },
+    device::Queue,
+    image::ImageAccess,
+    memory::DeviceMemoryError,
+    sync::{FenceSignalFuture, FlushError, GpuFuture},
+    DeviceSize, OomError,
+};
+
+/// A pending copy of device data to the host, which produces a value of type `T` once it has
+/// completed.
+pub struct ReadbackFuture<T> {
+    fence: FenceSignalFuture<CommandBufferExecFuture<Box<dyn GpuFuture>>>,
+    buffer: Arc<CpuAccessibleBuffer<[u8]>>,
+    map: Box<dyn FnOnce(&[u8]) -> T + Send>,
+}
+
+impl ReadbackFuture<Vec<u8>> {
+    /// Copies the contents of `source` to the host once `after` has completed.
+    pub fn buffer(
+        command_buffer_allocator: &StandardCommandBufferAllocator,
+        queue: Arc<Queue>,
+        source: Arc<dyn BufferAccess>,
+        after: impl GpuFuture + 'static,
+    ) -> Result<Self, ReadbackError> {
+        let buffer = host_buffer(&queue, source.size())?;
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )?;
+        builder.copy_buffer(CopyBufferInfo::buffers(source, buffer.clone()))?;
+
+        Self::submit(queue, builder, buffer, after)
+    }
+
+    /// Copies the first mip level of all array layers of `source` to the host once `after` has
+    /// completed.
+    ///
+    /// The bytes are tightly packed: rows of texel blocks follow each other without padding,
+    /// then depth slices, then array layers.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the format of `source` has a depth or stencil aspect, or is multi-planar.
+    pub fn image(
+        command_buffer_allocator: &StandardCommandBufferAllocator,
+        queue: Arc<Queue>,
+        source: Arc<dyn ImageAccess>,
+        after: impl GpuFuture + 'static,
+    ) -> Result<Self, ReadbackError> {
+        let extent = source.dimensions().width_height_depth();
+
+        Self::image_region(
+            command_buffer_allocator,
+            queue,
+            source,
+            [0; 3],
+            extent,
+            after,
+        )
+    }
+
+    /// Copies the region of `extent` texels starting at `offset` of the first mip level of all
+    /// array layers of `source` to the host once `after` has completed.
+    ///
+    /// The bytes are packed the same way as for [`image`](Self::image).
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the format of `source` has a depth or stencil aspect, or is multi-planar.
+    pub fn image_region(
+        command_buffer_allocator: &StandardCommandBufferAllocator,
+        queue: Arc<Queue>,
+        source: Arc<dyn ImageAccess>,
+        offset: [u32; 3],
+        extent: [u32; 3],
+        after: impl GpuFuture + 'static,
+    ) -> Result<Self, ReadbackError> {
+        let format = source.format();
+        let aspects = format.aspects();
+        assert!(!(aspects.depth || aspects.stencil || aspects.plane0));
+
+        let block_extent = format.block_extent();
+        let block_size = format.block_size().unwrap();
+        let [width, height, depth] = extent;
+
+        let row_blocks = (width + block_extent[0] - 1) / block_extent[0];
+        let slice_rows = (height + block_extent[1] - 1) / block_extent[1];
+        let slices = (depth + block_extent[2] - 1) / block_extent[2];
+        let size = row_blocks as DeviceSize
+            * block_size
+            * slice_rows as DeviceSize
+            * slices as DeviceSize
+            * source.dimensions().array_layers() as DeviceSize;
+
+        let buffer = host_buffer(&queue, size)?;
+        let region = BufferImageCopy {
+            image_subresource: source.subresource_layers(),
+            image_offset: offset,
+            image_extent: extent,
+            ..Default::default()
+        };
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )?;
+        builder.copy_image_to_buffer(CopyImageToBufferInfo {
+            regions: [region].into_iter().collect(),
+            ..CopyImageToBufferInfo::image_buffer(source, buffer.clone())
+        })?;
+
+        Self::submit(queue, builder, buffer, after)
+    }
+
+    fn submit(
+        queue: Arc<Queue>,
+        builder: AutoCommandBufferBuilder<PrimaryAutoCommandBuffer, StandardCommandBufferAllocator>,
+        buffer: Arc<CpuAccessibleBuffer<[u8]>>,
+        after: impl GpuFuture + 'static,
+    ) -> Result<Self, ReadbackError> {
+        let fence = after
+            .boxed()
+            .then_execute(queue, builder.build()?)?
+            .then_signal_fence_and_flush()?;
+
+        Ok(ReadbackFuture {
+            fence,
+            buffer,
+            map: Box::new(|bytes| bytes.to_vec()),
+        })
+    }
+}
+
+impl<T: 'static> ReadbackFuture<T> {
+    /// Returns a future that applies `f` to the value of this future once the copy has completed.
+    pub fn map<U: 'static>(self, f: impl FnOnce(T) -> U + Send + 'static) -> ReadbackFuture<U> {
+        let map = self.map;
+
+        ReadbackFuture {
+            fence: self.fence,
+            buffer: self.buffer,
+            map: Box::new(move |bytes| f(map(bytes))),
+        }
+    }
+}
+
+impl<T> ReadbackFuture<T> {
+    /// Returns whether the copy has completed, without blocking.
+    #[inline]
+    pub fn is_ready(&self) -> Result<bool, OomError> {
+        self.fence.is_signaled()
+    }
+
+    /// Blocks until the copy has completed, then produces the value.
+    pub fn wait(self) -> Result<T, ReadbackError> {
+        self.fence.wait(None)?;
+        let bytes = self.buffer.read()?;
+
+        Ok((self.map)(&*bytes))
+    }
+}
+
+fn host_buffer(
+    queue: &Queue,
+    size: DeviceSize,
+) -> Result<Arc<CpuAccessibleBuffer<[u8]>>, DeviceMemoryError> {
+    unsafe {
+        CpuAccessibleBuffer::uninitialized_array(
+            queue.device().clone(),
+            size,
+            BufferUsage {
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            true,
+        )
+    }
+}
+
+/// Error that can happen when reading data back from the device.
+#[derive(Clone, Debug)]
+pub enum ReadbackError {
+    /// Allocating the host-visible buffer failed.
+    Allocation(DeviceMemoryError),
+
+    /// The host-visible buffer could not be read from.
+    Read(ReadLockError),
+
+    /// Beginning a command buffer failed.
+    CommandBufferBegin(CommandBufferBeginError),
+
+    /// Recording the copy command failed.
+    Copy(CopyError),
+
+    /// Building a command buffer failed.
+    Build(BuildError),
+
+    /// Executing a command buffer failed.
+    Execute(CommandBufferExecError),
+
+    /// Flushing or waiting for the fence failed.
+    Flush(FlushError),
+}
+
+impl Error for ReadbackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Allocation(err) => Some(err),
+            Self::Read(err) => Some(err),
+            Self::CommandBufferBegin(err) => Some(err),
+            Self::Copy(err) => Some(err),
+            Self::Build(err) => Some(err),
+            Self::Execute(err) => Some(err),
+            Self::Flush(err) => Some(err),
+        }
+    }
+}
+
+impl Display for ReadbackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Allocation(_) => write!(f, "allocating the host-visible buffer failed"),
+            Self::Read(_) => write!(f, "the host-visible buffer could not be read from"),
+            Self::CommandBufferBegin(_) => write!(f, "beginning a command buffer failed"),
+            Self::Copy(_) => write!(f, "recording the copy command failed"),
+            Self::Build(_) => write!(f, "building a command buffer failed"),
+            Self::Execute(_) => write!(f, "executing a command buffer failed"),
+            Self::Flush(_) => write!(f, "flushing or waiting for the fence failed"),
+        }
+    }
+}
+
+impl From<DeviceMemoryError> for ReadbackError {
+    #[inline]
+    fn from(err: DeviceMemoryError) -> Self {
+        Self::Allocation(err)
+    }
+}
+
+impl From<ReadLockError> for ReadbackError {
+    #[inline]
+    fn from(err: ReadLockError) -> Self {
+        Self::Read(err)
+    }
+}
+
+impl From<CommandBufferBeginError> for ReadbackError {
+    #[inline]
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::CommandBufferBegin(err)
+    }
+}
+
+impl From<CopyError> for ReadbackError {
+    #[inline]
+    fn from(err: CopyError) -> Self {
+        Self::Copy(err)
+    }
+}
+
+impl From<BuildError> for ReadbackError {
+    #[inline]
+    fn from(err: BuildError) -> Self {
+        Self::Build(err)
+    }
+}
+
+impl From<CommandBufferExecError> for ReadbackError {
+    #[inline]
+    fn from(err: CommandBufferExecError) -> Self {
+        Self::Execute(err)
+    }
+}
+
+impl From<FlushError> for ReadbackError {
+    #[inline]
+    fn from(err: FlushError) -> Self {
+        Self::Flush(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vulkano::{
+        command_buffer::ClearColorImageInfo,
+        format::{ClearColorValue, Format},
+        image::{ImageCreateFlags, ImageDimensions, ImageUsage, StorageImage},
+        sync,
+    };
+
+    #[test]
+    fn readback_buffer_map() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device.clone());
+
+        let source = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage {
+                transfer_src: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            [1u32, 2, 3, 4],
+        )
+        .unwrap();
+
+        let future =
+            ReadbackFuture::buffer(&command_buffer_allocator, queue, source, sync::now(device))
+                .unwrap()
+                .map(|bytes| {
+                    bytes
+                        .chunks_exact(4)
+                        .map(|word| u32::from_ne_bytes(word.try_into().unwrap()))
+                        .collect::<Vec<_>>()
+                })
+                .map(|values| values.into_iter().sum::<u32>());
+
+        assert_eq!(future.wait().unwrap(), 10);
+    }
+
+    #[test]
+    fn readback_image() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device.clone());
+
+        let image = StorageImage::with_usage(
+            device.clone(),
+            ImageDimensions::Dim2d {
+                width: 2,
+                height: 2,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            ImageUsage {
+                transfer_src: true,
+                transfer_dst: true,
+                ..ImageUsage::empty()
+            },
+            ImageCreateFlags::empty(),
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        builder
+            .clear_color_image(ClearColorImageInfo {
+                clear_value: ClearColorValue::Float([1.0, 0.0, 0.0, 1.0]),
+                ..ClearColorImageInfo::image(image.clone())
+            })
+            .unwrap();
+        let clear = sync::now(device)
+            .then_execute(queue.clone(), builder.build().unwrap())
+            .unwrap();
+
+        let bytes = ReadbackFuture::image(&command_buffer_allocator, queue, image, clear)
+            .unwrap()
+            .wait()
+            .unwrap();
+
+        assert_eq!(bytes, [255, 0, 0, 255].repeat(4));
+    }
+}
@@ -0,0 +1,1194 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! A render graph that builds render passes and transient attachments from a list of passes.
+//!
+//! Each pass of a [`RenderGraph`] declares the images that it renders to and the images that it
+//! samples from. [`compile`](RenderGraph::compile) checks that every image is written before it is
+//! read, creates a single-subpass render pass for each pass, and allocates the transient images.
+//! Transient images whose lifetimes don't overlap are bound to the same memory, regardless of
+//! their size and format, and images that are never read after they are rendered to are given
+//! the `transient_attachment` usage, so that they can be backed by lazily allocated memory.
+//!
+//! Each pass runs on the queue that the graph was created with, unless another queue is given
+//! with [`PassBuilder::queue`]. Consecutive passes that run on the same queue are recorded into
+//! the same primary command buffer, in the order they were added, and a semaphore is signaled
+//! whenever execution moves to another queue. No barriers or layout transitions are specified by
+//! the graph itself: they are inserted by the automatic synchronization of
+//! [`AutoCommandBufferBuilder`], which also separates the uses of images that share memory.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use vulkano::{
+//!     format::{ClearValue, Format},
+//!     image::SampleCount,
+//!     sync::{self, GpuFuture},
+//! };
+//! use vulkano_util::rendergraph::{AttachmentLoad, RenderGraph, TransientImageInfo};
+//! # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+//! # let command_buffer_allocator: vulkano::command_buffer::allocator::StandardCommandBufferAllocator = return;
+//! # let swapchain_image: std::sync::Arc<dyn vulkano::image::ImageViewAbstract> = return;
+//!
+//! let mut graph = RenderGraph::new(queue.clone());
+//! let scene = graph.add_transient_image(TransientImageInfo {
+//!     extent: [1024, 768],
+//!     format: Format::R16G16B16A16_SFLOAT,
+//!     samples: SampleCount::Sample1,
+//! });
+//! let output = graph.import_image(swapchain_image);
+//!
+//! graph
+//!     .add_pass("scene")
+//!     .color_attachment(scene, AttachmentLoad::Clear(ClearValue::Float([0.0; 4])))
+//!     .build(|builder, resources| {
+//!         // Draw the scene.
+//!     });
+//! graph
+//!     .add_pass("tonemap")
+//!     .color_attachment(output, AttachmentLoad::DontCare)
+//!     .sampled_image(scene)
+//!     .build(move |builder, resources| {
+//!         let scene = resources.image(scene);
+//!         // Draw a full-screen triangle that samples `scene`.
+//!     });
+//!
+//! let mut compiled = graph.compile().unwrap();
+//!
+//! compiled
+//!     .submit(&command_buffer_allocator, sync::now(queue.device().clone()))
+//!     .unwrap()
+//!     .then_signal_fence_and_flush()
+//!     .unwrap()
+//!     .wait(None)
+//!     .unwrap();
+//! ```
+
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use vulkano::{
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, BuildError,
+        CommandBufferBeginError, CommandBufferExecError, CommandBufferUsage,
+        PrimaryAutoCommandBuffer, RenderPassBeginInfo, RenderPassError, SubpassContents,
+    },
+    device::{Device, DeviceOwned, Queue},
+    format::{ClearValue, Format},
+    image::{
+        sys::{UnsafeImage, UnsafeImageCreateInfo},
+        view::{ImageView, ImageViewCreationError},
+        ImageAccess, ImageCreationError, ImageDescriptorLayouts, ImageDimensions, ImageInner,
+        ImageLayout, ImageUsage, ImageViewAbstract, SampleCount,
+    },
+    memory::{DeviceMemory, DeviceMemoryError, MemoryAllocateInfo},
+    render_pass::{
+        AttachmentDescription, AttachmentReference, Framebuffer, FramebufferCreateInfo,
+        FramebufferCreationError, LoadOp, RenderPass, RenderPassCreateInfo,
+        RenderPassCreationError, StoreOp, SubpassDescription,
+    },
+    sync::{GpuFuture, Sharing},
+    DeviceSize,
+};
+
+/// The command buffer builder that passes are recorded into.
+pub type PassCommandBufferBuilder =
+    AutoCommandBufferBuilder<PrimaryAutoCommandBuffer, StandardCommandBufferAllocator>;
+
+type RecordFn = Box<dyn FnMut(&mut PassCommandBufferBuilder, &PassResources<'_>)>;
+
+/// A list of passes and the images that they use, which can be compiled into render passes.
+pub struct RenderGraph {
+    queue: Arc<Queue>,
+    images: Vec<ImageDecl>,
+    passes: Vec<PassDecl>,
+}
+
+impl RenderGraph {
+    /// Creates a new `RenderGraph` without any images or passes. Passes run on `queue` unless
+    /// they are given another queue.
+    #[inline]
+    pub fn new(queue: Arc<Queue>) -> Self {
+        RenderGraph {
+            queue,
+            images: Vec::new(),
+            passes: Vec::new(),
+        }
+    }
+
+    /// Returns the device that the images of the graph are created on.
+    #[inline]
+    pub fn device(&self) -> &Arc<Device> {
+        self.queue.device()
+    }
+
+    /// Declares an image that is created by the graph, and only lives while the graph is
+    /// executed.
+    ///
+    /// The contents of a transient image are undefined before the first pass that uses it, so
+    /// that pass must clear it or not care about its contents.
+    pub fn add_transient_image(&mut self, info: TransientImageInfo) -> ImageId {
+        self.images.push(ImageDecl::Transient(info));
+        ImageId(self.images.len() - 1)
+    }
+
+    /// Declares an image that is created outside of the graph, such as a swapchain image.
+    ///
+    /// The contents of an imported image are always stored at the end of the passes that render
+    /// to it.
+    pub fn import_image(&mut self, image_view: Arc<dyn ImageViewAbstract>) -> ImageId {
+        self.images.push(ImageDecl::Imported(image_view));
+        ImageId(self.images.len() - 1)
+    }
+
+    /// Starts declaring a new pass. The pass is added to the graph once
+    /// [`build`](PassBuilder::build) is called.
+    ///
+    /// Passes are executed in the order they are added.
+    pub fn add_pass(&mut self, name: impl Into<String>) -> PassBuilder<'_> {
+        PassBuilder {
+            graph: self,
+            pass: PassDecl {
+                name: name.into(),
+                queue: None,
+                color_attachments: Vec::new(),
+                depth_stencil_attachment: None,
+                sampled_images: Vec::new(),
+                record: Box::new(|_, _| ()),
+            },
+        }
+    }
+
+    /// Checks the passes, allocates the transient images and creates the render passes.
+    pub fn compile(self) -> Result<CompiledRenderGraph, RenderGraphError> {
+        let RenderGraph {
+            queue,
+            images,
+            passes,
+        } = self;
+        let device = queue.device().clone();
+
+        let mut written = vec![false; images.len()];
+        let mut first_use = vec![None; images.len()];
+        let mut last_use = vec![0; images.len()];
+        let mut sampled = vec![false; images.len()];
+        let mut loaded = vec![false; images.len()];
+
+        for (pass_index, pass) in passes.iter().enumerate() {
+            if pass.color_attachments.is_empty() && pass.depth_stencil_attachment.is_none() {
+                return Err(RenderGraphError::NoAttachments {
+                    pass: pass.name.clone(),
+                });
+            }
+
+            for &image in &pass.sampled_images {
+                if !written[image.0] && matches!(images[image.0], ImageDecl::Transient(_)) {
+                    return Err(RenderGraphError::ImageReadBeforeWrite {
+                        pass: pass.name.clone(),
+                        image,
+                    });
+                }
+
+                sampled[image.0] = true;
+            }
+
+            for (image, load) in pass.attachments() {
+                if matches!(load, AttachmentLoad::Load) {
+                    if !written[image.0] && matches!(images[image.0], ImageDecl::Transient(_)) {
+                        return Err(RenderGraphError::ImageReadBeforeWrite {
+                            pass: pass.name.clone(),
+                            image,
+                        });
+                    }
+
+                    loaded[image.0] = true;
+                }
+            }
+
+            for image in pass.images() {
+                first_use[image.0].get_or_insert(pass_index);
+                last_use[image.0] = pass_index;
+            }
+
+            for (image, _) in pass.attachments() {
+                written[image.0] = true;
+            }
+        }
+
+        let queues: Vec<Arc<Queue>> = passes
+            .iter()
+            .map(|pass| pass.queue.as_ref().unwrap_or(&queue).clone())
+            .collect();
+
+        // The transient images must be accessible from every queue family that runs a pass.
+        let mut queue_family_indices: Vec<u32> = queues
+            .iter()
+            .map(|queue| queue.queue_family_index())
+            .collect();
+        queue_family_indices.sort_unstable();
+        queue_family_indices.dedup();
+        let sharing = if queue_family_indices.len() > 1 {
+            Sharing::Concurrent(queue_family_indices.into())
+        } else {
+            Sharing::Exclusive
+        };
+
+        let memory_types = &device.physical_device().memory_properties().memory_types;
+        let allowed_memory_type_bits = memory_types
+            .iter()
+            .enumerate()
+            .filter(|(_, memory_type)| !memory_type.property_flags.protected)
+            .fold(0, |bits, (index, _)| bits | (1 << index));
+
+        // Create the transient images in the order they are first used, and assign each of them
+        // to a memory slot. A slot is reused if the images that were assigned to it earlier are
+        // no longer used by the time the new image is needed.
+        let mut order: Vec<usize> = (0..images.len())
+            .filter(|&index| first_use[index].is_some())
+            .collect();
+        order.sort_by_key(|&index| first_use[index]);
+
+        let mut slots: Vec<MemorySlot> = Vec::new();
+        let mut memory_slots = vec![None; images.len()];
+        let mut unbound_images = Vec::new();
+        let mut image_views: Vec<Option<Arc<dyn ImageViewAbstract>>> = vec![None; images.len()];
+
+        for index in order {
+            let info = match &images[index] {
+                ImageDecl::Transient(info) => *info,
+                ImageDecl::Imported(image_view) => {
+                    image_views[index] = Some(image_view.clone());
+                    continue;
+                }
+            };
+
+            let aspects = info.format.aspects();
+            let image = UnsafeImage::new(
+                device.clone(),
+                UnsafeImageCreateInfo {
+                    dimensions: ImageDimensions::Dim2d {
+                        width: info.extent[0],
+                        height: info.extent[1],
+                        array_layers: 1,
+                    },
+                    format: Some(info.format),
+                    samples: info.samples,
+                    usage: ImageUsage {
+                        color_attachment: aspects.color,
+                        depth_stencil_attachment: !aspects.color,
+                        sampled: sampled[index],
+                        transient_attachment: !sampled[index] && !loaded[index],
+                        ..ImageUsage::empty()
+                    },
+                    sharing: sharing.clone(),
+                    ..Default::default()
+                },
+            )?;
+            let requirements = image.memory_requirements();
+            let memory_type_bits = requirements.memory_type_bits & allowed_memory_type_bits;
+            let first = first_use[index].unwrap();
+
+            let slot_index = match slots.iter().position(|slot| {
+                slot.last_use < first && slot.memory_type_bits & memory_type_bits != 0
+            }) {
+                Some(slot_index) => slot_index,
+                None => {
+                    slots.push(MemorySlot {
+                        size: 0,
+                        memory_type_bits,
+                        last_use: 0,
+                    });
+                    slots.len() - 1
+                }
+            };
+
+            let slot = &mut slots[slot_index];
+            slot.size = slot.size.max(requirements.size);
+            slot.memory_type_bits &= memory_type_bits;
+            slot.last_use = last_use[index];
+            memory_slots[index] = Some(slot_index);
+
+            let attachment_layout = if aspects.color {
+                ImageLayout::ColorAttachmentOptimal
+            } else {
+                ImageLayout::DepthStencilAttachmentOptimal
+            };
+            unbound_images.push((index, image, attachment_layout));
+        }
+
+        let memories = slots
+            .iter()
+            .map(|slot| {
+                // Prefer lazily allocated memory, which is only available if all images of the
+                // slot are transient attachments, and then device-local memory.
+                let memory_type_index = memory_types
+                    .iter()
+                    .enumerate()
+                    .filter(|&(index, _)| slot.memory_type_bits & (1 << index) != 0)
+                    .min_by_key(|(_, memory_type)| {
+                        (
+                            !memory_type.property_flags.lazily_allocated,
+                            !memory_type.property_flags.device_local,
+                        )
+                    })
+                    .map(|(index, _)| index as u32)
+                    .ok_or(RenderGraphError::NoSuitableMemoryType)?;
+
+                let memory = DeviceMemory::allocate(
+                    device.clone(),
+                    MemoryAllocateInfo {
+                        allocation_size: slot.size,
+                        memory_type_index,
+                        ..Default::default()
+                    },
+                )?;
+
+                Ok(Arc::new(memory))
+            })
+            .collect::<Result<Vec<_>, RenderGraphError>>()?;
+
+        for (index, image, attachment_layout) in unbound_images {
+            let memory = memories[memory_slots[index].unwrap()].clone();
+            unsafe { image.bind_memory(&memory, 0) }.map_err(ImageCreationError::from)?;
+
+            image_views[index] = Some(ImageView::new_default(Arc::new(TransientImage {
+                image,
+                _memory: memory,
+                attachment_layout,
+                initialized: AtomicBool::new(false),
+            }))?);
+        }
+
+        let compiled_passes = passes
+            .into_iter()
+            .zip(queues)
+            .enumerate()
+            .map(|(pass_index, (pass, queue))| {
+                // The contents of an attachment must be stored if a later pass reads them, or if
+                // they belong to an image outside of the graph.
+                let store_op = |image: ImageId| {
+                    let needed_later = matches!(images[image.0], ImageDecl::Imported(_))
+                        || ((sampled[image.0] || loaded[image.0])
+                            && last_use[image.0] > pass_index);
+
+                    if needed_later {
+                        StoreOp::Store
+                    } else {
+                        StoreOp::DontCare
+                    }
+                };
+
+                let attachments = pass
+                    .attachments()
+                    .map(|(image, load)| {
+                        let image_view = image_views[image.0].as_ref().unwrap();
+                        let format = image_view.format().unwrap();
+                        let aspects = format.aspects();
+                        let layout = if aspects.color {
+                            ImageLayout::ColorAttachmentOptimal
+                        } else {
+                            ImageLayout::DepthStencilAttachmentOptimal
+                        };
+                        let (load_op, store_op) = (load.load_op(), store_op(image));
+
+                        // The previous contents of a transient image are discarded when it is
+                        // first used, which also covers the contents of any image that shared its
+                        // memory before.
+                        let initial_layout = if matches!(images[image.0], ImageDecl::Transient(_))
+                            && first_use[image.0] == Some(pass_index)
+                        {
+                            ImageLayout::Undefined
+                        } else {
+                            layout
+                        };
+                        let (stencil_load_op, stencil_store_op) = if aspects.stencil {
+                            (load_op, store_op)
+                        } else {
+                            (LoadOp::DontCare, StoreOp::DontCare)
+                        };
+
+                        AttachmentDescription {
+                            format: Some(format),
+                            samples: image_view.image().samples(),
+                            load_op,
+                            store_op,
+                            stencil_load_op,
+                            stencil_store_op,
+                            initial_layout,
+                            final_layout: layout,
+                            ..Default::default()
+                        }
+                    })
+                    .collect();
+
+                let color_attachments = (0..pass.color_attachments.len() as u32)
+                    .map(|attachment| {
+                        Some(AttachmentReference {
+                            attachment,
+                            layout: ImageLayout::ColorAttachmentOptimal,
+                            ..Default::default()
+                        })
+                    })
+                    .collect();
+                let depth_stencil_attachment =
+                    pass.depth_stencil_attachment.map(|_| AttachmentReference {
+                        attachment: pass.color_attachments.len() as u32,
+                        layout: ImageLayout::DepthStencilAttachmentOptimal,
+                        ..Default::default()
+                    });
+
+                let render_pass = RenderPass::new(
+                    device.clone(),
+                    RenderPassCreateInfo {
+                        attachments,
+                        subpasses: vec![SubpassDescription {
+                            color_attachments,
+                            depth_stencil_attachment,
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    },
+                )?;
+
+                Ok(CompiledPass {
+                    pass,
+                    queue,
+                    render_pass,
+                    framebuffer: None,
+                })
+            })
+            .collect::<Result<_, RenderGraphError>>()?;
+
+        Ok(CompiledRenderGraph {
+            image_views,
+            memory_slots,
+            imported: images
+                .iter()
+                .map(|image| matches!(image, ImageDecl::Imported(_)))
+                .collect(),
+            passes: compiled_passes,
+        })
+    }
+}
+
+/// Identifies an image of a [`RenderGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ImageId(usize);
+
+/// Parameters of a transient image of a [`RenderGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransientImageInfo {
+    /// The width and height of the image.
+    pub extent: [u32; 2],
+
+    /// The format of the image.
+    pub format: Format,
+
+    /// The number of samples per pixel.
+    pub samples: SampleCount,
+}
+
+/// What happens to the contents of an attachment at the start of a pass.
+#[derive(Clone, Copy, Debug)]
+pub enum AttachmentLoad {
+    /// The attachment is cleared to the given value.
+    Clear(ClearValue),
+
+    /// The previous contents of the attachment are preserved. An earlier pass must have written
+    /// to the attachment if it is a transient image.
+    Load,
+
+    /// The previous contents of the attachment are undefined.
+    DontCare,
+}
+
+impl AttachmentLoad {
+    fn load_op(&self) -> LoadOp {
+        match self {
+            Self::Clear(_) => LoadOp::Clear,
+            Self::Load => LoadOp::Load,
+            Self::DontCare => LoadOp::DontCare,
+        }
+    }
+}
+
+/// Declares the images that a pass uses. Returned by [`RenderGraph::add_pass`].
+pub struct PassBuilder<'a> {
+    graph: &'a mut RenderGraph,
+    pass: PassDecl,
+}
+
+impl<'a> PassBuilder<'a> {
+    /// Sets the queue that the pass runs on, instead of the queue that the graph was created
+    /// with.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `queue` was not created from the same device as the graph.
+    #[inline]
+    pub fn queue(mut self, queue: Arc<Queue>) -> Self {
+        assert_eq!(queue.device(), self.graph.device());
+
+        self.pass.queue = Some(queue);
+        self
+    }
+
+    /// Adds a color attachment that the pass renders to.
+    #[inline]
+    pub fn color_attachment(mut self, image: ImageId, load: AttachmentLoad) -> Self {
+        self.pass.color_attachments.push((image, load));
+        self
+    }
+
+    /// Sets the depth/stencil attachment of the pass.
+    #[inline]
+    pub fn depth_stencil_attachment(mut self, image: ImageId, load: AttachmentLoad) -> Self {
+        self.pass.depth_stencil_attachment = Some((image, load));
+        self
+    }
+
+    /// Adds an image that the pass samples from in its shaders.
+    #[inline]
+    pub fn sampled_image(mut self, image: ImageId) -> Self {
+        self.pass.sampled_images.push(image);
+        self
+    }
+
+    /// Adds the pass to the graph. `record` is called inside the render pass every time the
+    /// graph is executed, to record the commands of the pass.
+    pub fn build(
+        mut self,
+        record: impl FnMut(&mut PassCommandBufferBuilder, &PassResources<'_>) + 'static,
+    ) {
+        self.pass.record = Box::new(record);
+        self.graph.passes.push(self.pass);
+    }
+}
+
+/// A render graph whose images and render passes have been created.
+pub struct CompiledRenderGraph {
+    image_views: Vec<Option<Arc<dyn ImageViewAbstract>>>,
+    memory_slots: Vec<Option<usize>>,
+    imported: Vec<bool>,
+    passes: Vec<CompiledPass>,
+}
+
+impl CompiledRenderGraph {
+    /// Returns the image view that is used for `image`, or `None` if no pass uses it.
+    #[inline]
+    pub fn image(&self, image: ImageId) -> Option<&Arc<dyn ImageViewAbstract>> {
+        self.image_views[image.0].as_ref()
+    }
+
+    /// Returns whether `a` and `b` are transient images that are bound to the same memory,
+    /// because their lifetimes don't overlap.
+    #[inline]
+    pub fn shares_memory(&self, a: ImageId, b: ImageId) -> bool {
+        match (self.memory_slots[a.0], self.memory_slots[b.0]) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// Returns the render pass that was created for the pass with the given index.
+    #[inline]
+    pub fn render_pass(&self, pass_index: usize) -> &Arc<RenderPass> {
+        &self.passes[pass_index].render_pass
+    }
+
+    /// Replaces an imported image, for example with the swapchain image that was just acquired.
+    ///
+    /// The new image view must be compatible with the render passes that use the image.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `image` was not declared with [`RenderGraph::import_image`].
+    pub fn set_imported_image(&mut self, image: ImageId, image_view: Arc<dyn ImageViewAbstract>) {
+        assert!(self.imported[image.0]);
+
+        for compiled in &mut self.passes {
+            if compiled.pass.images().any(|used| used == image) {
+                compiled.framebuffer = None;
+            }
+        }
+
+        self.image_views[image.0] = Some(image_view);
+    }
+
+    /// Records the passes of the graph into command buffers.
+    ///
+    /// Consecutive passes that run on the same queue are recorded into the same command buffer.
+    /// The command buffers are returned in the order that they must be executed in, along with
+    /// the queue that each of them must be submitted to. Use [`submit`](Self::submit) to execute
+    /// them with the necessary semaphores in between.
+    pub fn execute(
+        &mut self,
+        command_buffer_allocator: &StandardCommandBufferAllocator,
+    ) -> Result<Vec<(Arc<Queue>, PrimaryAutoCommandBuffer)>, RenderGraphError> {
+        let resources = PassResources {
+            image_views: &self.image_views,
+        };
+        let mut command_buffers = Vec::new();
+        let mut current: Option<(Arc<Queue>, PassCommandBufferBuilder)> = None;
+
+        for compiled in &mut self.passes {
+            if !matches!(&current, Some((queue, _)) if Arc::ptr_eq(queue, &compiled.queue)) {
+                if let Some((queue, builder)) = current.take() {
+                    command_buffers.push((queue, builder.build()?));
+                }
+
+                let builder = AutoCommandBufferBuilder::primary(
+                    command_buffer_allocator,
+                    compiled.queue.queue_family_index(),
+                    CommandBufferUsage::OneTimeSubmit,
+                )?;
+                current = Some((compiled.queue.clone(), builder));
+            }
+
+            let (_, builder) = current.as_mut().unwrap();
+
+            let framebuffer = match &compiled.framebuffer {
+                Some(framebuffer) => framebuffer.clone(),
+                None => {
+                    let framebuffer = Framebuffer::new(
+                        compiled.render_pass.clone(),
+                        FramebufferCreateInfo {
+                            attachments: compiled
+                                .pass
+                                .attachments()
+                                .map(|(image, _)| resources.image(image).clone())
+                                .collect(),
+                            ..Default::default()
+                        },
+                    )?;
+                    compiled.framebuffer = Some(framebuffer.clone());
+                    framebuffer
+                }
+            };
+
+            let clear_values = compiled
+                .pass
+                .attachments()
+                .map(|(_, load)| match load {
+                    AttachmentLoad::Clear(clear_value) => Some(clear_value),
+                    AttachmentLoad::Load | AttachmentLoad::DontCare => None,
+                })
+                .collect();
+
+            builder.begin_render_pass(
+                RenderPassBeginInfo {
+                    clear_values,
+                    ..RenderPassBeginInfo::framebuffer(framebuffer)
+                },
+                SubpassContents::Inline,
+            )?;
+            (compiled.pass.record)(builder, &resources);
+            builder.end_render_pass()?;
+        }
+
+        if let Some((queue, builder)) = current {
+            command_buffers.push((queue, builder.build()?));
+        }
+
+        Ok(command_buffers)
+    }
+
+    /// Records the passes of the graph and executes them after `before`.
+    ///
+    /// A semaphore is signaled between command buffers that are executed on different queues.
+    /// `before` must be a future that the command buffer of the first pass can be executed after
+    /// on its queue.
+    pub fn submit<F>(
+        &mut self,
+        command_buffer_allocator: &StandardCommandBufferAllocator,
+        before: F,
+    ) -> Result<Box<dyn GpuFuture>, RenderGraphError>
+    where
+        F: GpuFuture + 'static,
+    {
+        let mut future = before.boxed();
+        let mut previous_queue: Option<Arc<Queue>> = None;
+
+        for (queue, command_buffer) in self.execute(command_buffer_allocator)? {
+            if previous_queue.map_or(false, |previous_queue| {
+                !Arc::ptr_eq(&previous_queue, &queue)
+            }) {
+                future = future.then_signal_semaphore().boxed();
+            }
+
+            future = future.then_execute(queue.clone(), command_buffer)?.boxed();
+            previous_queue = Some(queue);
+        }
+
+        Ok(future)
+    }
+}
+
+/// The images of a compiled graph, as seen by the passes while they are recorded.
+pub struct PassResources<'a> {
+    image_views: &'a [Option<Arc<dyn ImageViewAbstract>>],
+}
+
+impl<'a> PassResources<'a> {
+    /// Returns the image view that is used for `image`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if no pass uses `image`.
+    #[inline]
+    pub fn image(&self, image: ImageId) -> &'a Arc<dyn ImageViewAbstract> {
+        self.image_views[image.0]
+            .as_ref()
+            .expect("the image is not used by any pass")
+    }
+}
+
+enum ImageDecl {
+    Transient(TransientImageInfo),
+    Imported(Arc<dyn ImageViewAbstract>),
+}
+
+struct PassDecl {
+    name: String,
+    queue: Option<Arc<Queue>>,
+    color_attachments: Vec<(ImageId, AttachmentLoad)>,
+    depth_stencil_attachment: Option<(ImageId, AttachmentLoad)>,
+    sampled_images: Vec<ImageId>,
+    record: RecordFn,
+}
+
+impl PassDecl {
+    /// The attachments of the pass, in the order of the render pass attachments.
+    fn attachments(&self) -> impl Iterator<Item = (ImageId, AttachmentLoad)> + '_ {
+        self.color_attachments
+            .iter()
+            .chain(self.depth_stencil_attachment.iter())
+            .copied()
+    }
+
+    fn images(&self) -> impl Iterator<Item = ImageId> + '_ {
+        self.attachments()
+            .map(|(image, _)| image)
+            .chain(self.sampled_images.iter().copied())
+    }
+}
+
+struct CompiledPass {
+    pass: PassDecl,
+    queue: Arc<Queue>,
+    render_pass: Arc<RenderPass>,
+    framebuffer: Option<Arc<Framebuffer>>,
+}
+
+/// A memory allocation that is shared by transient images whose lifetimes don't overlap.
+struct MemorySlot {
+    size: DeviceSize,
+    memory_type_bits: u32,
+    last_use: usize,
+}
+
+/// A transient image, bound to the memory of its slot.
+#[derive(Debug)]
+struct TransientImage {
+    image: Arc<UnsafeImage>,
+    _memory: Arc<DeviceMemory>,
+    attachment_layout: ImageLayout,
+    initialized: AtomicBool,
+}
+
+unsafe impl ImageAccess for TransientImage {
+    fn inner(&self) -> ImageInner<'_> {
+        ImageInner {
+            image: &self.image,
+            first_layer: 0,
+            num_layers: 1,
+            first_mipmap_level: 0,
+            num_mipmap_levels: 1,
+        }
+    }
+
+    fn initial_layout_requirement(&self) -> ImageLayout {
+        self.attachment_layout
+    }
+
+    fn final_layout_requirement(&self) -> ImageLayout {
+        self.attachment_layout
+    }
+
+    fn descriptor_layouts(&self) -> Option<ImageDescriptorLayouts> {
+        Some(ImageDescriptorLayouts {
+            storage_image: ImageLayout::General,
+            combined_image_sampler: ImageLayout::ShaderReadOnlyOptimal,
+            sampled_image: ImageLayout::ShaderReadOnlyOptimal,
+            input_attachment: ImageLayout::ShaderReadOnlyOptimal,
+        })
+    }
+
+    unsafe fn layout_initialized(&self) {
+        self.initialized.store(true, Ordering::SeqCst);
+    }
+
+    fn is_layout_initialized(&self) -> bool {
+        self.initialized.load(Ordering::SeqCst)
+    }
+}
+
+unsafe impl DeviceOwned for TransientImage {
+    fn device(&self) -> &Arc<Device> {
+        self.image.device()
+    }
+}
+
+/// Error that can happen when compiling or executing a [`RenderGraph`].
+#[derive(Clone, Debug)]
+pub enum RenderGraphError {
+    /// A pass has neither color nor depth/stencil attachments.
+    NoAttachments { pass: String },
+
+    /// A pass reads a transient image before any earlier pass has written to it.
+    ImageReadBeforeWrite { pass: String, image: ImageId },
+
+    /// None of the memory types that the transient images can be bound to can be allocated by
+    /// the graph.
+    NoSuitableMemoryType,
+
+    /// Creating a transient image failed.
+    ImageCreation(ImageCreationError),
+
+    /// Allocating the memory of transient images failed.
+    MemoryAllocation(DeviceMemoryError),
+
+    /// Creating the view of a transient image failed.
+    ImageViewCreation(ImageViewCreationError),
+
+    /// Creating the render pass of a pass failed.
+    RenderPassCreation(RenderPassCreationError),
+
+    /// Creating the framebuffer of a pass failed.
+    FramebufferCreation(FramebufferCreationError),
+
+    /// Beginning or ending the render pass of a pass failed.
+    RenderPass(RenderPassError),
+
+    /// Beginning the recording of a command buffer failed.
+    CommandBufferBegin(CommandBufferBeginError),
+
+    /// Building a command buffer failed.
+    CommandBufferBuild(BuildError),
+
+    /// Executing a command buffer failed.
+    CommandBufferExec(CommandBufferExecError),
+}
+
+impl Error for RenderGraphError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::ImageCreation(err) => Some(err),
+            Self::MemoryAllocation(err) => Some(err),
+            Self::ImageViewCreation(err) => Some(err),
+            Self::RenderPassCreation(err) => Some(err),
+            Self::FramebufferCreation(err) => Some(err),
+            Self::RenderPass(err) => Some(err),
+            Self::CommandBufferBegin(err) => Some(err),
+            Self::CommandBufferBuild(err) => Some(err),
+            Self::CommandBufferExec(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for RenderGraphError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::NoAttachments { pass } => {
+                write!(f, "pass `{}` has no attachments", pass)
+            }
+            Self::ImageReadBeforeWrite { pass, image } => write!(
+                f,
+                "pass `{}` reads image {:?} before any earlier pass has written to it",
+                pass, image,
+            ),
+            Self::NoSuitableMemoryType => write!(
+                f,
+                "none of the memory types that the transient images can be bound to can be \
+                allocated",
+            ),
+            Self::ImageCreation(_) => write!(f, "creating a transient image failed"),
+            Self::MemoryAllocation(_) => {
+                write!(f, "allocating the memory of transient images failed")
+            }
+            Self::ImageViewCreation(_) => {
+                write!(f, "creating the view of a transient image failed")
+            }
+            Self::RenderPassCreation(_) => write!(f, "creating the render pass of a pass failed"),
+            Self::FramebufferCreation(_) => {
+                write!(f, "creating the framebuffer of a pass failed")
+            }
+            Self::RenderPass(_) => write!(f, "beginning or ending a render pass failed"),
+            Self::CommandBufferBegin(_) => {
+                write!(f, "beginning the recording of a command buffer failed")
+            }
+            Self::CommandBufferBuild(_) => write!(f, "building a command buffer failed"),
+            Self::CommandBufferExec(_) => write!(f, "executing a command buffer failed"),
+        }
+    }
+}
+
+impl From<ImageCreationError> for RenderGraphError {
+    #[inline]
+    fn from(err: ImageCreationError) -> Self {
+        Self::ImageCreation(err)
+    }
+}
+
+impl From<DeviceMemoryError> for RenderGraphError {
+    #[inline]
+    fn from(err: DeviceMemoryError) -> Self {
+        Self::MemoryAllocation(err)
+    }
+}
+
+impl From<ImageViewCreationError> for RenderGraphError {
+    #[inline]
+    fn from(err: ImageViewCreationError) -> Self {
+        Self::ImageViewCreation(err)
+    }
+}
+
+impl From<RenderPassCreationError> for RenderGraphError {
+    #[inline]
+    fn from(err: RenderPassCreationError) -> Self {
+        Self::RenderPassCreation(err)
+    }
+}
+
+impl From<FramebufferCreationError> for RenderGraphError {
+    #[inline]
+    fn from(err: FramebufferCreationError) -> Self {
+        Self::FramebufferCreation(err)
+    }
+}
+
+impl From<RenderPassError> for RenderGraphError {
+    #[inline]
+    fn from(err: RenderPassError) -> Self {
+        Self::RenderPass(err)
+    }
+}
+
+impl From<CommandBufferBeginError> for RenderGraphError {
+    #[inline]
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::CommandBufferBegin(err)
+    }
+}
+
+impl From<BuildError> for RenderGraphError {
+    #[inline]
+    fn from(err: BuildError) -> Self {
+        Self::CommandBufferBuild(err)
+    }
+}
+
+impl From<CommandBufferExecError> for RenderGraphError {
+    #[inline]
+    fn from(err: CommandBufferExecError) -> Self {
+        Self::CommandBufferExec(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vulkano::{
+        device::{DeviceCreateInfo, QueueCreateInfo},
+        sync,
+    };
+
+    const INFO: TransientImageInfo = TransientImageInfo {
+        extent: [64, 64],
+        format: Format::R8G8B8A8_UNORM,
+        samples: SampleCount::Sample1,
+    };
+    const CLEAR: AttachmentLoad = AttachmentLoad::Clear(ClearValue::Float([0.0; 4]));
+
+    #[test]
+    fn no_attachments() {
+        let (_device, queue) = gfx_dev_and_queue!();
+
+        let mut graph = RenderGraph::new(queue);
+        graph.add_pass("empty").build(|_, _| ());
+
+        match graph.compile() {
+            Err(RenderGraphError::NoAttachments { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn read_before_write() {
+        let (_device, queue) = gfx_dev_and_queue!();
+
+        let mut graph = RenderGraph::new(queue);
+        let a = graph.add_transient_image(INFO);
+        let b = graph.add_transient_image(INFO);
+        graph
+            .add_pass("read")
+            .color_attachment(b, CLEAR)
+            .sampled_image(a)
+            .build(|_, _| ());
+
+        match graph.compile() {
+            Err(RenderGraphError::ImageReadBeforeWrite { image, .. }) if image == a => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn aliasing() {
+        let (_device, queue) = gfx_dev_and_queue!();
+
+        let mut graph = RenderGraph::new(queue);
+        let a = graph.add_transient_image(INFO);
+        let b = graph.add_transient_image(INFO);
+        let c = graph.add_transient_image(INFO);
+        graph
+            .add_pass("a")
+            .color_attachment(a, CLEAR)
+            .build(|_, _| ());
+        graph
+            .add_pass("b")
+            .color_attachment(b, CLEAR)
+            .sampled_image(a)
+            .build(|_, _| ());
+        graph
+            .add_pass("c")
+            .color_attachment(c, CLEAR)
+            .sampled_image(b)
+            .build(|_, _| ());
+        graph
+            .add_pass("d")
+            .color_attachment(b, AttachmentLoad::Load)
+            .sampled_image(c)
+            .build(|_, _| ());
+
+        let compiled = graph.compile().unwrap();
+        assert!(compiled.shares_memory(a, c));
+        assert!(!compiled.shares_memory(a, b));
+        assert!(!compiled.shares_memory(b, c));
+    }
+
+    #[test]
+    fn execute_single_queue() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let mut graph = RenderGraph::new(queue);
+        let a = graph.add_transient_image(INFO);
+        let b = graph.add_transient_image(INFO);
+        graph
+            .add_pass("a")
+            .color_attachment(a, CLEAR)
+            .build(|_, _| ());
+        graph
+            .add_pass("b")
+            .color_attachment(b, CLEAR)
+            .sampled_image(a)
+            .build(|_, _| ());
+
+        let mut compiled = graph.compile().unwrap();
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device.clone());
+        assert_eq!(
+            compiled.execute(&command_buffer_allocator).unwrap().len(),
+            1
+        );
+
+        compiled
+            .submit(&command_buffer_allocator, sync::now(device))
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+
+    #[test]
+    fn execute_per_queue() {
+        let instance = instance!();
+
+        let select = match instance.enumerate_physical_devices() {
+            Ok(x) => x,
+            Err(_) => return,
+        }
+        .find_map(|p| {
+            p.queue_family_properties()
+                .iter()
+                .position(|q| q.queue_flags.graphics && q.queue_count >= 2)
+                .map(|i| (p, i as u32))
+        });
+
+        let (physical_device, queue_family_index) = match select {
+            Some(x) => x,
+            None => return, // test ignored
+        };
+
+        let (device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    queues: vec![0.5; 2],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let first_queue = queues.next().unwrap();
+        let second_queue = queues.next().unwrap();
+
+        let mut graph = RenderGraph::new(first_queue.clone());
+        let a = graph.add_transient_image(INFO);
+        let b = graph.add_transient_image(INFO);
+        graph
+            .add_pass("a")
+            .color_attachment(a, CLEAR)
+            .build(|_, _| ());
+        graph
+            .add_pass("b")
+            .queue(second_queue.clone())
+            .color_attachment(b, CLEAR)
+            .sampled_image(a)
+            .build(|_, _| ());
+        graph
+            .add_pass("c")
+            .queue(second_queue.clone())
+            .color_attachment(b, AttachmentLoad::Load)
+            .build(|_, _| ());
+
+        let mut compiled = graph.compile().unwrap();
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device.clone());
+        let command_buffers = compiled.execute(&command_buffer_allocator).unwrap();
+        assert_eq!(command_buffers.len(), 2);
+        assert!(Arc::ptr_eq(&command_buffers[0].0, &first_queue));
+        assert!(Arc::ptr_eq(&command_buffers[1].0, &second_queue));
+
+        compiled
+            .submit(&command_buffer_allocator, sync::now(device))
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+}
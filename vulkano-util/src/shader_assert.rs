@@ -0,0 +1,295 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Assertions in compute shaders, reported back to the host.
+//!
+//! [`patch_glsl`] inserts a prelude into the GLSL source of a compute shader, which declares a
+//! storage buffer and a `VULKANO_ASSERT(condition, id)` macro. When the condition is false, the
+//! macro records `id` and `gl_GlobalInvocationID` in the buffer, and optionally returns from the
+//! current function. The `id` is any `uint` that the application chooses to identify the
+//! assertion.
+//!
+//! The storage buffer is provided by a [`ShaderAssertBuffer`], which must be bound to the set and
+//! binding that were given to `patch_glsl`. Once the dispatch has completed, the recorded failures
+//! can be read with [`read_failures`](ShaderAssertBuffer::read_failures).
+//!
+//! This is a much lighter tool than GPU-assisted validation in the validation layers, but it only
+//! checks the conditions that the application asserts.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use vulkano_util::shader_assert::{patch_glsl, ShaderAssertBuffer};
+//! # let device: std::sync::Arc<vulkano::device::Device> = return;
+//!
+//! let source = patch_glsl(
+//!     "#version 450
+//!     layout(local_size_x = 64) in;
+//!
+//!     void main() {
+//!         VULKANO_ASSERT(gl_GlobalInvocationID.x < 1024, 1);
+//!     }",
+//!     1,
+//!     0,
+//!     true,
+//! );
+//!
+//! // Compile `source`, and bind `asserts.buffer()` to set 1, binding 0 of the pipeline...
+//! let asserts = ShaderAssertBuffer::new(device, 64).unwrap();
+//!
+//! // ...and after the dispatch has completed:
+//! for failure in asserts.read_failures().unwrap().failures {
+//!     println!("{}", failure);
+//! }
+//! ```
+
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+use vulkano::{
+    buffer::{
+        cpu_access::{ReadLockError, WriteLockError},
+        BufferUsage, CpuAccessibleBuffer,
+    },
+    device::Device,
+    memory::DeviceMemoryError,
+};
+
+// Number of `u32`s before the array of failures. The array consists of `uvec4`s, so it must start
+// at an offset that is a multiple of 16 bytes.
+const HEADER_LEN: usize = 4;
+
+/// Returns `source` with the shader assertion prelude inserted after its `#version` and
+/// `#extension` directives.
+///
+/// The storage buffer is declared at `set` and `binding`. If `abort` is true, a failed assertion
+/// also returns from the function that contains it, which means that `VULKANO_ASSERT` can then
+/// only be used in functions that return `void`.
+pub fn patch_glsl(source: &str, set: u32, binding: u32, abort: bool) -> String {
+    let mut insert_at = 0;
+    let mut offset = 0;
+
+    for line in source.split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim_start();
+
+        if line.starts_with("#version") || line.starts_with("#extension") {
+            insert_at = offset;
+        }
+    }
+
+    let mut patched = String::with_capacity(source.len() + 512);
+    patched.push_str(&source[..insert_at]);
+
+    if !patched.is_empty() && !patched.ends_with('\n') {
+        patched.push('\n');
+    }
+
+    patched.push_str(&glsl_prelude(set, binding, abort));
+    patched.push_str(&source[insert_at..]);
+    patched
+}
+
+fn glsl_prelude(set: u32, binding: u32, abort: bool) -> String {
+    format!(
+        "layout(set = {set}, binding = {binding}) buffer VulkanoAssertBuffer {{
+    uint vulkano_assert_count;
+    uvec4 vulkano_assert_failures[];
+}};
+#define VULKANO_ASSERT(condition, id) \\
+    if (!(condition)) {{ \\
+        uint vulkano_assert_index = atomicAdd(vulkano_assert_count, 1); \\
+        if (vulkano_assert_index < vulkano_assert_failures.length()) {{ \\
+            vulkano_assert_failures[vulkano_assert_index] = uvec4(id, gl_GlobalInvocationID); \\
+        }} \\
+        {abort} \\
+    }}
+",
+        set = set,
+        binding = binding,
+        abort = if abort { "return;" } else { "" },
+    )
+}
+
+/// The storage buffer that failed shader assertions are written to.
+#[derive(Debug)]
+pub struct ShaderAssertBuffer {
+    buffer: Arc<CpuAccessibleBuffer<[u32]>>,
+    capacity: u32,
+}
+
+impl ShaderAssertBuffer {
+    /// Creates a new `ShaderAssertBuffer` that can hold up to `capacity` failures.
+    ///
+    /// Failures beyond the capacity are counted, but their details are discarded.
+    pub fn new(device: Arc<Device>, capacity: u32) -> Result<Self, DeviceMemoryError> {
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device,
+            BufferUsage {
+                storage_buffer: true,
+                ..BufferUsage::empty()
+            },
+            true,
+            (0..HEADER_LEN + capacity as usize * 4).map(|_| 0u32),
+        )?;
+
+        Ok(ShaderAssertBuffer { buffer, capacity })
+    }
+
+    /// Returns the buffer to bind to the set and binding that were given to [`patch_glsl`].
+    #[inline]
+    pub fn buffer(&self) -> &Arc<CpuAccessibleBuffer<[u32]>> {
+        &self.buffer
+    }
+
+    /// Returns the maximum number of failures whose details can be recorded.
+    #[inline]
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Reads the failures that have been recorded so far.
+    ///
+    /// The buffer is locked by the device while a submission that uses it is executing, so this
+    /// should be called once that submission has completed.
+    pub fn read_failures(&self) -> Result<ShaderAssertReport, ReadLockError> {
+        let data = self.buffer.read()?;
+        let count = data[0];
+        let failures = data[HEADER_LEN..]
+            .chunks_exact(4)
+            .take(count.min(self.capacity) as usize)
+            .map(|entry| ShaderAssertFailure {
+                id: entry[0],
+                invocation: [entry[1], entry[2], entry[3]],
+            })
+            .collect();
+
+        Ok(ShaderAssertReport { count, failures })
+    }
+
+    /// Clears the recorded failures, so that the buffer can be reused for another submission.
+    pub fn reset(&self) -> Result<(), WriteLockError> {
+        self.buffer.write()?[0] = 0;
+
+        Ok(())
+    }
+}
+
+/// The failures that were read from a [`ShaderAssertBuffer`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ShaderAssertReport {
+    /// The total number of failed assertions, including those that didn't fit in the buffer.
+    pub count: u32,
+
+    /// The details of the failed assertions that fit in the buffer, in the order that they were
+    /// recorded.
+    pub failures: Vec<ShaderAssertFailure>,
+}
+
+/// A failed shader assertion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShaderAssertFailure {
+    /// The `id` that was given to `VULKANO_ASSERT`.
+    pub id: u32,
+
+    /// The global invocation ID of the invocation that failed the assertion.
+    pub invocation: [u32; 3],
+}
+
+impl Display for ShaderAssertFailure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        write!(
+            f,
+            "shader assertion {} failed in invocation ({}, {}, {})",
+            self.id, self.invocation[0], self.invocation[1], self.invocation[2],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patch_after_directives() {
+        let source = "#version 450\n#extension GL_EXT_debug_printf : enable\n\nvoid main() {}\n";
+        let patched = patch_glsl(source, 2, 3, false);
+
+        assert!(patched.starts_with("#version 450\n#extension GL_EXT_debug_printf : enable\n"));
+        assert!(patched.ends_with("\nvoid main() {}\n"));
+        assert!(patched.contains("layout(set = 2, binding = 3) buffer VulkanoAssertBuffer"));
+        assert!(!patched.contains("return;"));
+
+        let prelude = patched.find("VulkanoAssertBuffer").unwrap();
+        assert!(prelude > patched.find("#extension").unwrap());
+        assert!(prelude < patched.find("void main").unwrap());
+    }
+
+    #[test]
+    fn patch_abort() {
+        let patched = patch_glsl("#version 450\nvoid main() {}", 0, 0, true);
+
+        assert!(patched.starts_with("#version 450\nlayout(set = 0, binding = 0)"));
+        assert!(patched.contains("return;"));
+    }
+
+    #[test]
+    fn patch_without_version() {
+        let patched = patch_glsl("void main() {}", 0, 1, false);
+
+        assert!(patched.starts_with("layout(set = 0, binding = 1)"));
+        assert!(patched.ends_with("void main() {}"));
+    }
+
+    #[test]
+    fn read_failures() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let asserts = ShaderAssertBuffer::new(device, 2).unwrap();
+        assert_eq!(asserts.capacity(), 2);
+        assert_eq!(
+            asserts.read_failures().unwrap(),
+            ShaderAssertReport {
+                count: 0,
+                failures: Vec::new(),
+            },
+        );
+
+        // Write what the shader would have written for three failures, the last of which doesn't
+        // fit in the buffer.
+        {
+            let mut data = asserts.buffer().write().unwrap();
+            data[0] = 3;
+            data[HEADER_LEN..HEADER_LEN + 8].copy_from_slice(&[7, 1, 2, 3, 8, 4, 5, 6]);
+        }
+
+        let report = asserts.read_failures().unwrap();
+        assert_eq!(report.count, 3);
+        assert_eq!(
+            report.failures,
+            [
+                ShaderAssertFailure {
+                    id: 7,
+                    invocation: [1, 2, 3],
+                },
+                ShaderAssertFailure {
+                    id: 8,
+                    invocation: [4, 5, 6],
+                },
+            ],
+        );
+        assert_eq!(
+            report.failures[0].to_string(),
+            "shader assertion 7 failed in invocation (1, 2, 3)",
+        );
+
+        asserts.reset().unwrap();
+        assert_eq!(asserts.read_failures().unwrap().count, 0);
+    }
+}
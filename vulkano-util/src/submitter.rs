@@ -0,0 +1,484 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Submitting work to a queue from a background thread.
+//!
+//! Submitting a command buffer or presenting a swapchain image can block inside the driver for a
+//! noticeable amount of time. A [`QueueSubmitter`] moves these calls to a dedicated thread: the
+//! render thread records command buffers and hands them over through a channel, then carries on
+//! with the next frame while the worker submits them.
+//!
+//! Requests are processed strictly in the order they were sent, and each one is submitted after
+//! the previous one, so the GPU sees the same order as the render thread. Every request returns a
+//! [`Submission`], which can be waited on or turned into a [`GpuFuture`] once the worker has
+//! submitted it.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use vulkano::swapchain::SwapchainPresentInfo;
+//! use vulkano_util::submitter::QueueSubmitter;
+//! # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+//! # let swapchain: std::sync::Arc<vulkano::swapchain::Swapchain<()>> = return;
+//! # let command_buffer: std::sync::Arc<vulkano::command_buffer::PrimaryAutoCommandBuffer> = return;
+//! # let acquire_future: vulkano::swapchain::SwapchainAcquireFuture<()> = return;
+//! # let image_index: u32 = return;
+//!
+//! let submitter = QueueSubmitter::new(queue);
+//!
+//! // The acquire future must come before the commands that draw to the image.
+//! let _rendered = submitter.execute_after(acquire_future, [command_buffer as _]);
+//! let presented = submitter.present(SwapchainPresentInfo::swapchain_image_index(
+//!     swapchain,
+//!     image_index,
+//! ));
+//!
+//! // Record the next frame here, then wait before reusing this frame's resources.
+//! presented.wait(None).unwrap();
+//! ```
+
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use vulkano::{
+    command_buffer::{CommandBufferExecError, PrimaryCommandBuffer},
+    device::Queue,
+    swapchain::SwapchainPresentInfo,
+    sync::{self, FenceSignalFuture, FlushError, GpuFuture},
+};
+
+/// The future that a [`Submission`] resolves to once the worker has submitted it.
+///
+/// The fence is signaled when the submitted work has completed on the device.
+pub type SubmittedFuture = Arc<FenceSignalFuture<Box<dyn GpuFuture + Send>>>;
+
+/// Submits command buffers and presents swapchain images on a dedicated thread.
+///
+/// Dropping the submitter processes the requests that are still queued, then waits for the
+/// worker thread to exit.
+pub struct QueueSubmitter {
+    queue: Arc<Queue>,
+    sender: Option<Sender<Request>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl QueueSubmitter {
+    /// Starts a worker thread that submits to `queue`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the thread could not be spawned.
+    pub fn new(queue: Arc<Queue>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let thread = thread::Builder::new()
+            .name("vulkano queue submitter".into())
+            .spawn({
+                let queue = queue.clone();
+                move || run(queue, receiver)
+            })
+            .expect("failed to spawn the queue submitter thread");
+
+        QueueSubmitter {
+            queue,
+            sender: Some(sender),
+            thread: Some(thread),
+        }
+    }
+
+    /// Returns the queue that requests are submitted to.
+    #[inline]
+    pub fn queue(&self) -> &Arc<Queue> {
+        &self.queue
+    }
+
+    /// Submits `command_buffers` in a single batch, after all previously sent requests.
+    pub fn execute(
+        &self,
+        command_buffers: impl IntoIterator<Item = Arc<dyn PrimaryCommandBuffer>>,
+    ) -> Submission {
+        self.send(|sender| Request::Execute {
+            after: None,
+            command_buffers: command_buffers.into_iter().collect(),
+            sender,
+        })
+    }
+
+    /// Submits `command_buffers` in a single batch, after all previously sent requests and after
+    /// `after`.
+    ///
+    /// This is how a future that was created on the render thread, such as the future returned
+    /// by [`acquire_next_image`](vulkano::swapchain::acquire_next_image), is inserted into the
+    /// chain.
+    pub fn execute_after(
+        &self,
+        after: impl GpuFuture + Send + 'static,
+        command_buffers: impl IntoIterator<Item = Arc<dyn PrimaryCommandBuffer>>,
+    ) -> Submission {
+        self.send(|sender| Request::Execute {
+            after: Some(after.boxed_send()),
+            command_buffers: command_buffers.into_iter().collect(),
+            sender,
+        })
+    }
+
+    /// Presents a swapchain image, after all previously sent requests.
+    ///
+    /// The image must have been acquired by a future that was passed to
+    /// [`execute_after`](Self::execute_after) before this call.
+    pub fn present(&self, swapchain_info: SwapchainPresentInfo) -> Submission {
+        self.send(|sender| Request::Present {
+            swapchain_info,
+            sender,
+        })
+    }
+
+    fn send(&self, request: impl FnOnce(Sender<SubmissionResult>) -> Request) -> Submission {
+        let (sender, receiver) = mpsc::channel();
+
+        // If the worker has exited, the request is dropped along with `sender` and the
+        // submission reports `Disconnected`.
+        let _ = self.sender.as_ref().unwrap().send(request(sender));
+
+        Submission { receiver }
+    }
+}
+
+impl Drop for QueueSubmitter {
+    fn drop(&mut self) {
+        // Closing the channel makes the worker exit once it has drained it.
+        self.sender = None;
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A request that was sent to a [`QueueSubmitter`].
+pub struct Submission {
+    receiver: Receiver<SubmissionResult>,
+}
+
+impl Submission {
+    /// Blocks until the worker has submitted the request, then returns the resulting future.
+    pub fn future(self) -> Result<SubmittedFuture, QueueSubmitterError> {
+        self.receiver
+            .recv()
+            .map_err(|_| QueueSubmitterError::Disconnected)?
+    }
+
+    /// Returns the resulting future if the worker has already submitted the request, or gives
+    /// the submission back otherwise.
+    pub fn try_future(self) -> Result<Result<SubmittedFuture, QueueSubmitterError>, Self> {
+        match self.receiver.try_recv() {
+            Ok(result) => Ok(result),
+            Err(mpsc::TryRecvError::Empty) => Err(self),
+            Err(mpsc::TryRecvError::Disconnected) => Ok(Err(QueueSubmitterError::Disconnected)),
+        }
+    }
+
+    /// Blocks until the request has been submitted and has completed on the device.
+    pub fn wait(self, timeout: Option<Duration>) -> Result<(), QueueSubmitterError> {
+        self.future()?.wait(timeout)?;
+
+        Ok(())
+    }
+}
+
+type SubmissionResult = Result<SubmittedFuture, QueueSubmitterError>;
+
+enum Request {
+    Execute {
+        after: Option<Box<dyn GpuFuture + Send>>,
+        command_buffers: Vec<Arc<dyn PrimaryCommandBuffer>>,
+        sender: Sender<SubmissionResult>,
+    },
+    Present {
+        swapchain_info: SwapchainPresentInfo,
+        sender: Sender<SubmissionResult>,
+    },
+}
+
+fn run(queue: Arc<Queue>, receiver: Receiver<Request>) {
+    let mut previous = sync::now(queue.device().clone()).boxed_send();
+
+    for request in receiver {
+        let (result, sender) = match request {
+            Request::Execute {
+                after,
+                command_buffers,
+                sender,
+            } => {
+                previous.cleanup_finished();
+
+                let future = match after {
+                    Some(after) => previous.join(after).boxed_send(),
+                    None => previous,
+                };
+                let result = future
+                    .then_execute_batch(queue.clone(), command_buffers)
+                    .map_err(QueueSubmitterError::from)
+                    .and_then(|future| Ok(future.boxed_send().then_signal_fence_and_flush()?));
+
+                (result, sender)
+            }
+            Request::Present {
+                swapchain_info,
+                sender,
+            } => {
+                // The previous request has already been flushed, so presenting after it doesn't
+                // wait for anything, and a present is not ordered with earlier submissions to the
+                // queue by itself. A semaphore that is signaled after the earlier submissions
+                // makes the present wait for them. If the chain has started over, there is
+                // nothing to wait for.
+                let previous = if previous.queue().is_some() {
+                    previous.then_signal_semaphore().boxed_send()
+                } else {
+                    previous
+                };
+
+                let result = previous
+                    .then_swapchain_present(queue.clone(), swapchain_info)
+                    .boxed_send()
+                    .then_signal_fence_and_flush()
+                    .map_err(QueueSubmitterError::from);
+
+                (result, sender)
+            }
+        };
+
+        let result = result.map(Arc::new);
+
+        // After a failure, the chain starts over so that later requests can still be submitted.
+        previous = match &result {
+            Ok(future) => future.clone().boxed_send(),
+            Err(_) => sync::now(queue.device().clone()).boxed_send(),
+        };
+
+        // The caller may have dropped the submission without waiting for it.
+        let _ = sender.send(result);
+    }
+}
+
+/// Error that can happen when submitting through a [`QueueSubmitter`].
+#[derive(Clone, Debug)]
+pub enum QueueSubmitterError {
+    /// Executing the command buffers failed.
+    Execute(CommandBufferExecError),
+
+    /// Flushing the submission or waiting for the fence failed.
+    Flush(FlushError),
+
+    /// The worker thread exited before processing the request.
+    Disconnected,
+}
+
+impl Error for QueueSubmitterError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Execute(err) => Some(err),
+            Self::Flush(err) => Some(err),
+            Self::Disconnected => None,
+        }
+    }
+}
+
+impl Display for QueueSubmitterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Execute(_) => write!(f, "executing the command buffers failed"),
+            Self::Flush(_) => write!(f, "flushing the submission or waiting for the fence failed"),
+            Self::Disconnected => {
+                write!(f, "the worker thread exited before processing the request",)
+            }
+        }
+    }
+}
+
+impl From<CommandBufferExecError> for QueueSubmitterError {
+    #[inline]
+    fn from(err: CommandBufferExecError) -> Self {
+        Self::Execute(err)
+    }
+}
+
+impl From<FlushError> for QueueSubmitterError {
+    #[inline]
+    fn from(err: FlushError) -> Self {
+        Self::Flush(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vulkano::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+            ClearColorImageInfo, CommandBufferUsage,
+        },
+        device::{Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo},
+        image::{ImageUsage, SwapchainImage},
+        instance::{Instance, InstanceCreateInfo, InstanceExtensions},
+        swapchain::{self, Surface, Swapchain, SwapchainCreateInfo},
+        VulkanLibrary,
+    };
+
+    #[test]
+    fn execute_in_order() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device);
+        let command_buffer = || -> Arc<dyn PrimaryCommandBuffer> {
+            Arc::new(
+                AutoCommandBufferBuilder::primary(
+                    &command_buffer_allocator,
+                    queue.queue_family_index(),
+                    CommandBufferUsage::OneTimeSubmit,
+                )
+                .unwrap()
+                .build()
+                .unwrap(),
+            )
+        };
+
+        let submitter = QueueSubmitter::new(queue.clone());
+        let first = submitter.execute([command_buffer()]);
+        let second = submitter.execute_after(sync::now(queue.device().clone()), [command_buffer()]);
+
+        second.wait(None).unwrap();
+        first.wait(None).unwrap();
+    }
+
+    #[test]
+    fn present_after_execute() {
+        let (queue, swapchain, images) = match headless_swapchain() {
+            Some(x) => x,
+            None => return, // test ignored
+        };
+
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(queue.device().clone());
+        let submitter = QueueSubmitter::new(queue.clone());
+
+        for _ in 0..2 {
+            let (image_index, _suboptimal, acquire_future) =
+                swapchain::acquire_next_image(swapchain.clone(), None).unwrap();
+
+            // Clearing the image also transitions it to the layout for presenting.
+            let mut builder = AutoCommandBufferBuilder::primary(
+                &command_buffer_allocator,
+                queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .unwrap();
+            builder
+                .clear_color_image(ClearColorImageInfo::image(
+                    images[image_index as usize].clone(),
+                ))
+                .unwrap();
+            let command_buffer = Arc::new(builder.build().unwrap());
+
+            let _rendered = submitter.execute_after(acquire_future, [command_buffer as _]);
+            submitter
+                .present(SwapchainPresentInfo::swapchain_image_index(
+                    swapchain.clone(),
+                    image_index,
+                ))
+                .wait(None)
+                .unwrap();
+        }
+    }
+
+    fn headless_swapchain() -> Option<(Arc<Queue>, Arc<Swapchain<()>>, Vec<Arc<SwapchainImage<()>>>)>
+    {
+        let library = VulkanLibrary::new().ok()?;
+        let instance = Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_extensions: InstanceExtensions {
+                    khr_surface: true,
+                    ext_headless_surface: true,
+                    ..InstanceExtensions::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .ok()?;
+        let surface = Surface::headless(instance.clone(), ()).ok()?;
+
+        let enabled_extensions = DeviceExtensions {
+            khr_swapchain: true,
+            ..DeviceExtensions::empty()
+        };
+        let (physical_device, queue_family_index) = instance
+            .enumerate_physical_devices()
+            .ok()?
+            .filter(|p| p.supported_extensions().contains(&enabled_extensions))
+            .find_map(|p| {
+                let queue_family_index = (0..p.queue_family_properties().len() as u32)
+                    .find(|&i| p.surface_support(i, &surface).unwrap_or(false))?;
+
+                Some((p, queue_family_index))
+            })?;
+
+        let (device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    ..Default::default()
+                }],
+                enabled_extensions,
+                ..Default::default()
+            },
+        )
+        .ok()?;
+
+        let surface_capabilities = device
+            .physical_device()
+            .surface_capabilities(&surface, Default::default())
+            .ok()?;
+        let (image_format, image_color_space) = device
+            .physical_device()
+            .surface_formats(&surface, Default::default())
+            .ok()?
+            .into_iter()
+            .next()?;
+
+        if !surface_capabilities.supported_usage_flags.transfer_dst {
+            return None;
+        }
+
+        let (swapchain, images) = Swapchain::new(
+            device,
+            surface,
+            SwapchainCreateInfo {
+                min_image_count: surface_capabilities.min_image_count,
+                image_format: Some(image_format),
+                image_color_space,
+                image_extent: [16, 16],
+                image_usage: ImageUsage {
+                    transfer_dst: true,
+                    ..ImageUsage::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .ok()?;
+
+        Some((queues.next().unwrap(), swapchain, images))
+    }
+}
@@ -0,0 +1,401 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Descriptors for the input attachments of a subpass.
+//!
+//! In deferred rendering, a subpass reads the attachments that earlier subpasses wrote to as
+//! input attachments. Each input attachment needs a descriptor, and the image view that is
+//! written to it must be the one that the framebuffer uses for the referenced attachment.
+//! [`SubpassInputs`] derives both from the render pass, so that they can't get out of sync.
+//!
+//! Input attachment `i` of the subpass is given binding `first_binding + i`, so the shader
+//! declares it with `layout(input_attachment_index = i, set = ..., binding = first_binding + i)`.
+//! Elements of `input_attachments` that are `None` don't get a binding.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use vulkano::{
+//!     descriptor_set::{
+//!         layout::{DescriptorSetLayout, DescriptorSetLayoutCreateInfo},
+//!         PersistentDescriptorSet,
+//!     },
+//!     render_pass::Subpass,
+//! };
+//! use vulkano_util::subpass_inputs::SubpassInputs;
+//! # let device: std::sync::Arc<vulkano::device::Device> = return;
+//! # let descriptor_set_allocator: vulkano::descriptor_set::allocator::StandardDescriptorSetAllocator = return;
+//! # let render_pass: std::sync::Arc<vulkano::render_pass::RenderPass> = return;
+//! # let framebuffer: std::sync::Arc<vulkano::render_pass::Framebuffer> = return;
+//!
+//! let lighting_inputs = SubpassInputs::new(Subpass::from(render_pass, 1).unwrap(), 0);
+//!
+//! let layout = DescriptorSetLayout::new(
+//!     device,
+//!     DescriptorSetLayoutCreateInfo {
+//!         bindings: lighting_inputs.bindings(),
+//!         ..Default::default()
+//!     },
+//! )
+//! .unwrap();
+//!
+//! let descriptor_set = PersistentDescriptorSet::new(
+//!     &descriptor_set_allocator,
+//!     layout,
+//!     lighting_inputs.writes_for_framebuffer(&framebuffer).unwrap(),
+//! )
+//! .unwrap();
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+use vulkano::{
+    descriptor_set::{
+        layout::{DescriptorSetLayoutBinding, DescriptorType},
+        WriteDescriptorSet,
+    },
+    format::Format,
+    image::ImageViewAbstract,
+    render_pass::{Framebuffer, Subpass},
+    shader::ShaderStages,
+};
+
+/// The input attachments of a subpass, and the descriptors that they are read through.
+#[derive(Clone, Debug)]
+pub struct SubpassInputs {
+    subpass: Subpass,
+    first_binding: u32,
+}
+
+impl SubpassInputs {
+    /// Creates a new `SubpassInputs` for the input attachments of `subpass`, starting at
+    /// `first_binding`.
+    #[inline]
+    pub fn new(subpass: Subpass, first_binding: u32) -> Self {
+        SubpassInputs {
+            subpass,
+            first_binding,
+        }
+    }
+
+    /// Returns the subpass that the inputs belong to.
+    #[inline]
+    pub fn subpass(&self) -> &Subpass {
+        &self.subpass
+    }
+
+    /// Returns the binding of the first input attachment.
+    #[inline]
+    pub fn first_binding(&self) -> u32 {
+        self.first_binding
+    }
+
+    /// Returns the binding numbers, and the indices of the render pass attachments that are bound
+    /// to them.
+    pub fn attachments(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.subpass
+            .subpass_desc()
+            .input_attachments
+            .iter()
+            .enumerate()
+            .filter_map(move |(input_index, atch_ref)| {
+                atch_ref
+                    .as_ref()
+                    .map(|atch_ref| (self.first_binding + input_index as u32, atch_ref.attachment))
+            })
+    }
+
+    /// Returns the descriptor set layout bindings of the input attachments, to be used in
+    /// [`DescriptorSetLayoutCreateInfo::bindings`].
+    ///
+    /// The bindings are only visible to the fragment shader, which is the only stage that can
+    /// read input attachments.
+    ///
+    /// [`DescriptorSetLayoutCreateInfo::bindings`]: vulkano::descriptor_set::layout::DescriptorSetLayoutCreateInfo::bindings
+    pub fn bindings(&self) -> BTreeMap<u32, DescriptorSetLayoutBinding> {
+        self.attachments()
+            .map(|(binding, _)| {
+                (
+                    binding,
+                    DescriptorSetLayoutBinding {
+                        stages: ShaderStages {
+                            fragment: true,
+                            ..ShaderStages::empty()
+                        },
+                        ..DescriptorSetLayoutBinding::descriptor_type(
+                            DescriptorType::InputAttachment,
+                        )
+                    },
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the descriptor writes that bind the input attachments to the image views in
+    /// `attachments`.
+    ///
+    /// `attachments` must contain an image view for every attachment of the render pass, in the
+    /// same order as in [`FramebufferCreateInfo::attachments`]. The image views that are
+    /// referenced as input attachments must have the format of the attachment, and the
+    /// `input_attachment` usage.
+    ///
+    /// [`FramebufferCreateInfo::attachments`]: vulkano::render_pass::FramebufferCreateInfo::attachments
+    pub fn writes(
+        &self,
+        attachments: &[Arc<dyn ImageViewAbstract>],
+    ) -> Result<Vec<WriteDescriptorSet>, SubpassInputsError> {
+        let attachment_descs = self.subpass.render_pass().attachments();
+
+        if attachments.len() != attachment_descs.len() {
+            return Err(SubpassInputsError::AttachmentCountMismatch {
+                provided: attachments.len() as u32,
+                required: attachment_descs.len() as u32,
+            });
+        }
+
+        self.attachments()
+            .map(|(binding, attachment)| {
+                let image_view = &attachments[attachment as usize];
+                let required = attachment_descs[attachment as usize].format;
+
+                if image_view.format() != required {
+                    return Err(SubpassInputsError::AttachmentFormatMismatch {
+                        attachment,
+                        provided: image_view.format(),
+                        required,
+                    });
+                }
+
+                if !image_view.usage().input_attachment {
+                    return Err(SubpassInputsError::AttachmentMissingUsage { attachment });
+                }
+
+                Ok(WriteDescriptorSet::image_view(binding, image_view.clone()))
+            })
+            .collect()
+    }
+
+    /// Returns the descriptor writes that bind the input attachments to the image views of
+    /// `framebuffer`.
+    ///
+    /// The render pass of `framebuffer` must be compatible with the render pass of the subpass.
+    pub fn writes_for_framebuffer(
+        &self,
+        framebuffer: &Framebuffer,
+    ) -> Result<Vec<WriteDescriptorSet>, SubpassInputsError> {
+        if !framebuffer
+            .render_pass()
+            .is_compatible_with(self.subpass.render_pass())
+        {
+            return Err(SubpassInputsError::RenderPassNotCompatible);
+        }
+
+        self.writes(framebuffer.attachments())
+    }
+}
+
+/// Error that can happen when writing the descriptors of [`SubpassInputs`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubpassInputsError {
+    /// The number of image views is not equal to the number of attachments of the render pass.
+    AttachmentCountMismatch { provided: u32, required: u32 },
+
+    /// The image view of an input attachment does not have the format of the attachment.
+    AttachmentFormatMismatch {
+        attachment: u32,
+        provided: Option<Format>,
+        required: Option<Format>,
+    },
+
+    /// The image view of an input attachment does not have the `input_attachment` usage.
+    AttachmentMissingUsage { attachment: u32 },
+
+    /// The render pass of the framebuffer is not compatible with the render pass of the subpass.
+    RenderPassNotCompatible,
+}
+
+impl Error for SubpassInputsError {}
+
+impl Display for SubpassInputsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::AttachmentCountMismatch { provided, required } => write!(
+                f,
+                "{} image views were provided, but the render pass has {} attachments",
+                provided, required,
+            ),
+            Self::AttachmentFormatMismatch {
+                attachment,
+                provided,
+                required,
+            } => write!(
+                f,
+                "the image view of input attachment {} has format {:?}, but the attachment has \
+                format {:?}",
+                attachment, provided, required,
+            ),
+            Self::AttachmentMissingUsage { attachment } => write!(
+                f,
+                "the image view of input attachment {} does not have the `input_attachment` usage",
+                attachment,
+            ),
+            Self::RenderPassNotCompatible => write!(
+                f,
+                "the render pass of the framebuffer is not compatible with the render pass of the \
+                subpass",
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use vulkano::{
+        device::Device,
+        image::{view::ImageView, AttachmentImage},
+        render_pass::{FramebufferCreateInfo, RenderPass},
+    };
+
+    // A render pass whose second subpass reads the output of the first one as an input
+    // attachment.
+    fn deferred_render_pass(device: Arc<Device>) -> Arc<RenderPass> {
+        vulkano::ordered_passes_renderpass!(
+            device,
+            attachments: {
+                final_color: {
+                    load: Clear,
+                    store: Store,
+                    format: Format::R8G8B8A8_UNORM,
+                    samples: 1,
+                },
+                diffuse: {
+                    load: Clear,
+                    store: DontCare,
+                    format: Format::R8G8B8A8_UNORM,
+                    samples: 1,
+                }
+            },
+            passes: [
+                { color: [diffuse], depth_stencil: {}, input: [] },
+                { color: [final_color], depth_stencil: {}, input: [diffuse] }
+            ]
+        )
+        .unwrap()
+    }
+
+    fn attachment_view(
+        device: Arc<Device>,
+        format: Format,
+        input_attachment: bool,
+    ) -> Arc<dyn ImageViewAbstract> {
+        let image = if input_attachment {
+            AttachmentImage::input_attachment(device, [4, 4], format).unwrap()
+        } else {
+            AttachmentImage::new(device, [4, 4], format).unwrap()
+        };
+
+        ImageView::new_default(image).unwrap()
+    }
+
+    #[test]
+    fn bindings() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let render_pass = deferred_render_pass(device);
+
+        let inputs = SubpassInputs::new(Subpass::from(render_pass.clone(), 1).unwrap(), 2);
+        assert_eq!(inputs.attachments().collect::<Vec<_>>(), [(2, 1)]);
+
+        let bindings = inputs.bindings();
+        assert_eq!(bindings.keys().copied().collect::<Vec<_>>(), [2]);
+        assert_eq!(
+            bindings[&2].descriptor_type,
+            DescriptorType::InputAttachment
+        );
+        assert_eq!(
+            bindings[&2].stages,
+            ShaderStages {
+                fragment: true,
+                ..ShaderStages::empty()
+            },
+        );
+
+        let inputs = SubpassInputs::new(Subpass::from(render_pass, 0).unwrap(), 0);
+        assert_eq!(inputs.attachments().count(), 0);
+        assert!(inputs.bindings().is_empty());
+    }
+
+    #[test]
+    fn writes() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let render_pass = deferred_render_pass(device.clone());
+        let inputs = SubpassInputs::new(Subpass::from(render_pass.clone(), 1).unwrap(), 2);
+
+        let final_color = attachment_view(device.clone(), Format::R8G8B8A8_UNORM, false);
+        let diffuse = attachment_view(device.clone(), Format::R8G8B8A8_UNORM, true);
+
+        let writes = inputs
+            .writes(&[final_color.clone(), diffuse.clone()])
+            .unwrap();
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].binding(), 2);
+
+        let framebuffer = Framebuffer::new(
+            render_pass,
+            FramebufferCreateInfo {
+                attachments: vec![final_color.clone(), diffuse],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            inputs.writes_for_framebuffer(&framebuffer).unwrap().len(),
+            1
+        );
+
+        assert_eq!(
+            inputs.writes(&[final_color.clone()]).err(),
+            Some(SubpassInputsError::AttachmentCountMismatch {
+                provided: 1,
+                required: 2,
+            }),
+        );
+    }
+
+    #[test]
+    fn writes_invalid_views() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let render_pass = deferred_render_pass(device.clone());
+        let inputs = SubpassInputs::new(Subpass::from(render_pass, 1).unwrap(), 0);
+
+        let final_color = attachment_view(device.clone(), Format::R8G8B8A8_UNORM, false);
+
+        // The view for the input attachment lacks the `input_attachment` usage.
+        let diffuse = attachment_view(device.clone(), Format::R8G8B8A8_UNORM, false);
+        assert_eq!(
+            inputs.writes(&[final_color.clone(), diffuse]).err(),
+            Some(SubpassInputsError::AttachmentMissingUsage { attachment: 1 }),
+        );
+
+        // The view for the input attachment has the wrong format.
+        let diffuse = attachment_view(device, Format::R8G8B8A8_SRGB, true);
+        assert_eq!(
+            inputs.writes(&[final_color, diffuse]).err(),
+            Some(SubpassInputsError::AttachmentFormatMismatch {
+                attachment: 1,
+                provided: Some(Format::R8G8B8A8_SRGB),
+                required: Some(Format::R8G8B8A8_UNORM),
+            }),
+        );
+    }
+}
@@ -0,0 +1,564 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Uploading large amounts of data to the device in bounded-size chunks.
+//!
+//! Recording a single copy of several gigabytes can keep the device busy for long enough that the
+//! operating system considers it hung and resets it. [`ChunkedUploader`] instead splits the
+//! transfer into a series of copies of at most [`chunk_size`](ChunkedUploader::chunk_size) bytes,
+//! each in its own submission, and waits for every submission to complete before starting the
+//! next one. In between chunks, progress is reported to a callback and the upload can be
+//! cancelled.
+//!
+//! ## Examples
+//!
+//! ```no_run
+//! use vulkano_util::upload::{CancellationToken, ChunkedUploader};
+//! # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+//! # let buffer: std::sync::Arc<vulkano::buffer::DeviceLocalBuffer<[u8]>> = return;
+//! # let data: Vec<u8> = return;
+//!
+//! let mut uploader = ChunkedUploader::new(queue, 64 * 1024 * 1024);
+//! let cancel = CancellationToken::new();
+//!
+//! uploader
+//!     .upload_buffer(&data, buffer, 0, &cancel, |progress| {
+//!         println!("{:.1}%", progress.fraction() * 100.0);
+//!     })
+//!     .unwrap();
+//! ```
+
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use vulkano::{
+    buffer::{
+        cpu_access::WriteLockError, BufferAccess, BufferUsage, CpuAccessibleBuffer,
+        TypedBufferAccess,
+    },
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, BufferCopy,
+        BufferImageCopy, BuildError, CommandBufferBeginError, CommandBufferExecError,
+        CommandBufferUsage, CopyBufferInfo, CopyBufferToImageInfo, CopyError,
+        PrimaryAutoCommandBuffer, PrimaryCommandBuffer,
+    },
+    device::{DeviceOwned, Queue},
+    image::{ImageAccess, ImageSubresourceLayers},
+    memory::DeviceMemoryError,
+    sync::{FlushError, GpuFuture},
+    DeviceSize,
+};
+
+/// A shareable flag that can be used to stop an upload that is in progress.
+///
+/// Cancellation is checked before each chunk is submitted, so a chunk that was already submitted
+/// will still complete.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a new token that is not cancelled.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests that uploads using this token stop at the next chunk boundary.
+    #[inline]
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Returns whether `cancel` has been called on this token or one of its clones.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// The progress of an upload, passed to the progress callback after each chunk completes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// The number of bytes that have been copied to the destination so far.
+    pub bytes_uploaded: DeviceSize,
+
+    /// The total number of bytes of the upload.
+    pub total_bytes: DeviceSize,
+}
+
+impl UploadProgress {
+    /// Returns the fraction of the upload that has completed, between `0.0` and `1.0`.
+    #[inline]
+    pub fn fraction(&self) -> f64 {
+        if self.total_bytes == 0 {
+            1.0
+        } else {
+            self.bytes_uploaded as f64 / self.total_bytes as f64
+        }
+    }
+}
+
+/// Uploads data to buffers and images in chunks of bounded size, one submission per chunk.
+///
+/// The uploader owns a host-visible staging buffer that is reused between chunks, and grows only
+/// if a single row of an image does not fit in `chunk_size` bytes.
+pub struct ChunkedUploader {
+    queue: Arc<Queue>,
+    command_buffer_allocator: StandardCommandBufferAllocator,
+    chunk_size: DeviceSize,
+    staging_buffer: Option<Arc<CpuAccessibleBuffer<[u8]>>>,
+}
+
+impl ChunkedUploader {
+    /// Creates a new `ChunkedUploader` that submits to `queue`, copying at most `chunk_size`
+    /// bytes per submission.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `chunk_size` is zero.
+    pub fn new(queue: Arc<Queue>, chunk_size: DeviceSize) -> Self {
+        assert!(chunk_size != 0);
+
+        ChunkedUploader {
+            command_buffer_allocator: StandardCommandBufferAllocator::new(queue.device().clone()),
+            queue,
+            chunk_size,
+            staging_buffer: None,
+        }
+    }
+
+    /// Returns the queue that the uploads are submitted to.
+    #[inline]
+    pub fn queue(&self) -> &Arc<Queue> {
+        &self.queue
+    }
+
+    /// Returns the maximum number of bytes copied per submission.
+    #[inline]
+    pub fn chunk_size(&self) -> DeviceSize {
+        self.chunk_size
+    }
+
+    /// Copies `data` into `destination`, starting at byte `destination_offset`.
+    ///
+    /// This function blocks until the whole upload has completed, or until `cancel` is cancelled,
+    /// in which case [`UploadError::Cancelled`] is returned and the remaining contents of the
+    /// destination range are left unchanged.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `destination` does not have enough room for `data` at `destination_offset`.
+    pub fn upload_buffer(
+        &mut self,
+        data: &[u8],
+        destination: Arc<dyn BufferAccess>,
+        destination_offset: DeviceSize,
+        cancel: &CancellationToken,
+        mut progress: impl FnMut(UploadProgress),
+    ) -> Result<(), UploadError> {
+        let total_bytes = data.len() as DeviceSize;
+        assert!(destination_offset
+            .checked_add(total_bytes)
+            .map_or(false, |end| end <= destination.size()));
+
+        let mut bytes_uploaded = 0;
+
+        for chunk in data.chunks(self.chunk_size as usize) {
+            if cancel.is_cancelled() {
+                return Err(UploadError::Cancelled);
+            }
+
+            let staging_buffer = self.write_staging(chunk)?;
+            let region = BufferCopy {
+                src_offset: 0,
+                dst_offset: destination_offset + bytes_uploaded,
+                size: chunk.len() as DeviceSize,
+                ..Default::default()
+            };
+
+            let mut builder = self.begin()?;
+            builder.copy_buffer(CopyBufferInfo {
+                regions: [region].into_iter().collect(),
+                ..CopyBufferInfo::buffers(staging_buffer, destination.clone())
+            })?;
+            self.submit_and_wait(builder)?;
+
+            bytes_uploaded += chunk.len() as DeviceSize;
+            progress(UploadProgress {
+                bytes_uploaded,
+                total_bytes,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Copies `data` into the first mip level of `destination`.
+    ///
+    /// `data` must be tightly packed: rows of texel blocks follow each other without padding,
+    /// then depth slices, then array layers. The image is split into chunks of whole rows, with
+    /// each chunk staying within a single depth slice and array layer.
+    ///
+    /// This function blocks until the whole upload has completed, or until `cancel` is cancelled,
+    /// in which case [`UploadError::Cancelled`] is returned.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the format of `destination` has a depth or stencil aspect, or is multi-planar.
+    /// - Panics if the length of `data` doesn't match the size of the first mip level of
+    ///   `destination`.
+    pub fn upload_image(
+        &mut self,
+        data: &[u8],
+        destination: Arc<dyn ImageAccess>,
+        cancel: &CancellationToken,
+        mut progress: impl FnMut(UploadProgress),
+    ) -> Result<(), UploadError> {
+        let format = destination.format();
+        let aspects = format.aspects();
+        assert!(!(aspects.depth || aspects.stencil || aspects.plane0));
+
+        let block_extent = format.block_extent();
+        let block_size = format.block_size().unwrap();
+        let dimensions = destination.dimensions();
+        let [width, height, depth] = dimensions.width_height_depth();
+
+        let row_blocks = (width + block_extent[0] - 1) / block_extent[0];
+        let slice_rows = (height + block_extent[1] - 1) / block_extent[1];
+        let slices = (depth + block_extent[2] - 1) / block_extent[2];
+        let row_size = row_blocks as DeviceSize * block_size;
+
+        let total_bytes = data.len() as DeviceSize;
+        assert_eq!(
+            total_bytes,
+            row_size
+                * slice_rows as DeviceSize
+                * slices as DeviceSize
+                * dimensions.array_layers() as DeviceSize,
+        );
+
+        let rows_per_chunk = (self.chunk_size / row_size).max(1) as u32;
+        let subresource = destination.subresource_layers();
+        let mut bytes_uploaded = 0;
+
+        for array_layer in subresource.array_layers.clone() {
+            for slice in 0..slices {
+                let mut row = 0;
+
+                while row < slice_rows {
+                    if cancel.is_cancelled() {
+                        return Err(UploadError::Cancelled);
+                    }
+
+                    let rows = rows_per_chunk.min(slice_rows - row);
+                    let chunk_bytes = rows as DeviceSize * row_size;
+                    let chunk =
+                        &data[bytes_uploaded as usize..(bytes_uploaded + chunk_bytes) as usize];
+
+                    let staging_buffer = self.write_staging(chunk)?;
+                    let region = BufferImageCopy {
+                        image_subresource: ImageSubresourceLayers {
+                            array_layers: array_layer..array_layer + 1,
+                            ..subresource.clone()
+                        },
+                        image_offset: [0, row * block_extent[1], slice * block_extent[2]],
+                        image_extent: [
+                            width,
+                            (rows * block_extent[1]).min(height - row * block_extent[1]),
+                            block_extent[2].min(depth - slice * block_extent[2]),
+                        ],
+                        ..Default::default()
+                    };
+
+                    let mut builder = self.begin()?;
+                    builder.copy_buffer_to_image(CopyBufferToImageInfo {
+                        regions: [region].into_iter().collect(),
+                        ..CopyBufferToImageInfo::buffer_image(staging_buffer, destination.clone())
+                    })?;
+                    self.submit_and_wait(builder)?;
+
+                    row += rows;
+                    bytes_uploaded += chunk_bytes;
+                    progress(UploadProgress {
+                        bytes_uploaded,
+                        total_bytes,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Writes `chunk` to the start of the staging buffer, reallocating it if it's too small.
+    fn write_staging(
+        &mut self,
+        chunk: &[u8],
+    ) -> Result<Arc<CpuAccessibleBuffer<[u8]>>, UploadError> {
+        let len = chunk.len() as DeviceSize;
+
+        let staging_buffer = match &self.staging_buffer {
+            Some(staging_buffer) if staging_buffer.len() >= len => staging_buffer.clone(),
+            _ => {
+                let staging_buffer = unsafe {
+                    CpuAccessibleBuffer::uninitialized_array(
+                        self.queue.device().clone(),
+                        len.max(self.chunk_size),
+                        BufferUsage {
+                            transfer_src: true,
+                            ..BufferUsage::empty()
+                        },
+                        false,
+                    )?
+                };
+                self.staging_buffer = Some(staging_buffer.clone());
+                staging_buffer
+            }
+        };
+
+        staging_buffer.write()?[..chunk.len()].copy_from_slice(chunk);
+
+        Ok(staging_buffer)
+    }
+
+    fn begin(
+        &self,
+    ) -> Result<
+        AutoCommandBufferBuilder<PrimaryAutoCommandBuffer, StandardCommandBufferAllocator>,
+        UploadError,
+    > {
+        Ok(AutoCommandBufferBuilder::primary(
+            &self.command_buffer_allocator,
+            self.queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )?)
+    }
+
+    // Every chunk is waited on before the next one is recorded, so the staging buffer is never in
+    // use by the device when it's written to.
+    fn submit_and_wait(
+        &self,
+        builder: AutoCommandBufferBuilder<
+            vulkano::command_buffer::PrimaryAutoCommandBuffer,
+            StandardCommandBufferAllocator,
+        >,
+    ) -> Result<(), UploadError> {
+        builder
+            .build()?
+            .execute(self.queue.clone())?
+            .then_signal_fence_and_flush()?
+            .wait(None)?;
+
+        Ok(())
+    }
+}
+
+/// Error that can happen during a chunked upload.
+#[derive(Clone, Debug)]
+pub enum UploadError {
+    /// The upload was cancelled through its [`CancellationToken`].
+    Cancelled,
+
+    /// Allocating the staging buffer failed.
+    StagingAllocation(DeviceMemoryError),
+
+    /// The staging buffer could not be written to.
+    StagingWrite(WriteLockError),
+
+    /// Beginning a command buffer failed.
+    CommandBufferBegin(CommandBufferBeginError),
+
+    /// Recording the copy command failed.
+    Copy(CopyError),
+
+    /// Building a command buffer failed.
+    Build(BuildError),
+
+    /// Executing a command buffer failed.
+    Execute(CommandBufferExecError),
+
+    /// Flushing or waiting for a submission failed.
+    Flush(FlushError),
+}
+
+impl Error for UploadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Cancelled => None,
+            Self::StagingAllocation(err) => Some(err),
+            Self::StagingWrite(err) => Some(err),
+            Self::CommandBufferBegin(err) => Some(err),
+            Self::Copy(err) => Some(err),
+            Self::Build(err) => Some(err),
+            Self::Execute(err) => Some(err),
+            Self::Flush(err) => Some(err),
+        }
+    }
+}
+
+impl Display for UploadError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::Cancelled => write!(f, "the upload was cancelled"),
+            Self::StagingAllocation(_) => write!(f, "allocating the staging buffer failed"),
+            Self::StagingWrite(_) => write!(f, "the staging buffer could not be written to"),
+            Self::CommandBufferBegin(_) => write!(f, "beginning a command buffer failed"),
+            Self::Copy(_) => write!(f, "recording the copy command failed"),
+            Self::Build(_) => write!(f, "building a command buffer failed"),
+            Self::Execute(_) => write!(f, "executing a command buffer failed"),
+            Self::Flush(_) => write!(f, "flushing or waiting for a submission failed"),
+        }
+    }
+}
+
+impl From<DeviceMemoryError> for UploadError {
+    fn from(err: DeviceMemoryError) -> Self {
+        Self::StagingAllocation(err)
+    }
+}
+
+impl From<WriteLockError> for UploadError {
+    fn from(err: WriteLockError) -> Self {
+        Self::StagingWrite(err)
+    }
+}
+
+impl From<CommandBufferBeginError> for UploadError {
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::CommandBufferBegin(err)
+    }
+}
+
+impl From<CopyError> for UploadError {
+    fn from(err: CopyError) -> Self {
+        Self::Copy(err)
+    }
+}
+
+impl From<BuildError> for UploadError {
+    fn from(err: BuildError) -> Self {
+        Self::Build(err)
+    }
+}
+
+impl From<CommandBufferExecError> for UploadError {
+    fn from(err: CommandBufferExecError) -> Self {
+        Self::Execute(err)
+    }
+}
+
+impl From<FlushError> for UploadError {
+    fn from(err: FlushError) -> Self {
+        Self::Flush(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn destination_buffer(queue: &Arc<Queue>, len: DeviceSize) -> Arc<CpuAccessibleBuffer<[u8]>> {
+        CpuAccessibleBuffer::from_iter(
+            queue.device().clone(),
+            BufferUsage {
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            (0..len).map(|_| 0u8),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn upload_buffer_chunks() {
+        let (_device, queue) = gfx_dev_and_queue!();
+
+        let destination = destination_buffer(&queue, 20);
+        let data: Vec<u8> = (1..=10).collect();
+        let mut uploader = ChunkedUploader::new(queue, 4);
+        let mut reports = Vec::new();
+
+        uploader
+            .upload_buffer(
+                &data,
+                destination.clone(),
+                8,
+                &CancellationToken::new(),
+                |progress| reports.push(progress.bytes_uploaded),
+            )
+            .unwrap();
+
+        assert_eq!(reports, [4, 8, 10]);
+
+        let contents = destination.read().unwrap();
+        assert!(contents[..8].iter().all(|&byte| byte == 0));
+        assert_eq!(&contents[8..18], &data[..]);
+        assert!(contents[18..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn upload_buffer_cancelled() {
+        let (_device, queue) = gfx_dev_and_queue!();
+
+        let destination = destination_buffer(&queue, 16);
+        let mut uploader = ChunkedUploader::new(queue, 4);
+        let cancel = CancellationToken::new();
+
+        let result = uploader.upload_buffer(&[1; 16], destination.clone(), 0, &cancel, |_| {
+            cancel.cancel()
+        });
+
+        assert!(matches!(result, Err(UploadError::Cancelled)));
+        assert_eq!(
+            &destination.read().unwrap()[..],
+            &[1, 1, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn upload_buffer_offset_overflow() {
+        let (_device, queue) = gfx_dev_and_queue!();
+
+        let destination = destination_buffer(&queue, 16);
+        let mut uploader = ChunkedUploader::new(queue, 4);
+
+        assert_should_panic!({
+            let _ = uploader.upload_buffer(
+                &[1; 4],
+                destination,
+                DeviceSize::MAX - 1,
+                &CancellationToken::new(),
+                |_| (),
+            );
+        });
+    }
+
+    #[test]
+    fn progress_fraction() {
+        let progress = UploadProgress {
+            bytes_uploaded: 0,
+            total_bytes: 0,
+        };
+        assert_eq!(progress.fraction(), 1.0);
+
+        let progress = UploadProgress {
+            bytes_uploaded: 1,
+            total_bytes: 4,
+        };
+        assert_eq!(progress.fraction(), 0.25);
+    }
+}
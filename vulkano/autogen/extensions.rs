@@ -173,10 +173,108 @@ fn device_extensions_output(members: &[ExtensionsMember]) -> TokenStream {
         }
     });
 
+    let promote_to_core_items = members.iter().filter_map(
+        |ExtensionsMember { name, status, .. }| match status {
+            Some(ExtensionStatus::Promoted(Replacement::Core(version))) => {
+                let version = format_ident!("V{}_{}", version.0, version.1);
+                Some(quote! {
+                    if self.#name && !supported.#name && api_version >= crate::Version::#version {
+                        self.#name = false;
+                        promoted.#name = true;
+                    }
+                })
+            }
+            _ => None,
+        },
+    );
+
+    let enable_dependencies_items =
+        members
+            .iter()
+            .map(|ExtensionsMember { name, requires, .. }| {
+                let requires_items = requires
+                    .iter()
+                    .filter(|require| !require.device_extensions.is_empty())
+                    .map(|require| {
+                        let require_items = require
+                            .api_version
+                            .iter()
+                            .map(|version| {
+                                let version = format_ident!("V{}_{}", version.0, version.1);
+                                quote! { api_version >= crate::Version::#version }
+                            })
+                            .chain(require.instance_extensions.iter().map(|ext| {
+                                quote! { instance_extensions.#ext }
+                            }))
+                            .chain(require.device_extensions.iter().map(|ext| {
+                                quote! { self.#ext }
+                            }));
+                        let enable_items = require.device_extensions.iter().map(|ext| {
+                            quote! {
+                                if supported.#ext {
+                                    self.#ext = true;
+                                    enabled.#ext = true;
+                                    changed = true;
+                                } else
+                            }
+                        });
+
+                        quote! {
+                            if !(#(#require_items)||*) {
+                                #(#enable_items)* {}
+                            }
+                        }
+                    });
+
+                quote! {
+                    if self.#name {
+                        #(#requires_items)*
+                    }
+                }
+            });
+
     quote! {
         #common
 
         impl DeviceExtensions {
+            /// Disables enabled extensions that are not supported by the device, but that have been
+            /// promoted to a core API version that the device supports.
+            ///
+            /// Returns the extensions that were disabled.
+            pub(super) fn promote_to_core(
+                &mut self,
+                supported: &DeviceExtensions,
+                api_version: crate::Version,
+            ) -> DeviceExtensions {
+                let mut promoted = DeviceExtensions::empty();
+                #(#promote_to_core_items)*
+                promoted
+            }
+
+            /// Enables the supported device extensions that enabled extensions depend on, if the
+            /// dependency is not already met by the device version or by another extension.
+            ///
+            /// Returns the extensions that were enabled.
+            pub(super) fn enable_dependencies(
+                &mut self,
+                supported: &DeviceExtensions,
+                api_version: crate::Version,
+                instance_extensions: &crate::instance::InstanceExtensions,
+            ) -> DeviceExtensions {
+                let mut enabled = DeviceExtensions::empty();
+
+                loop {
+                    let mut changed = false;
+                    #(#enable_dependencies_items)*
+
+                    if !changed {
+                        break;
+                    }
+                }
+
+                enabled
+            }
+
             /// Checks enabled extensions against the device version, instance extensions and each other.
             pub(super) fn check_requirements(
                 &self,
@@ -158,6 +158,31 @@ fn features_output(members: &[FeaturesMember]) -> TokenStream {
         },
     );
 
+    let enable_promoted_items = members
+        .iter()
+        .filter(
+            |FeaturesMember {
+                 required_by_extensions,
+                 ..
+             }| !required_by_extensions.is_empty(),
+        )
+        .map(
+            |FeaturesMember {
+                 name,
+                 required_by_extensions,
+                 ..
+             }| {
+                let extensions = required_by_extensions
+                    .iter()
+                    .map(|(_, extension)| extension);
+                quote! {
+                    if (#(promoted.#extensions)||*) && supported.#name {
+                        self.#name = true;
+                    }
+                }
+            },
+        );
+
     let empty_items = members.iter().map(|FeaturesMember { name, .. }| {
         quote! {
             #name: false,
@@ -336,6 +361,16 @@ fn features_output(members: &[FeaturesMember]) -> TokenStream {
                 Ok(())
             }
 
+            /// Enables the supported features that provide the functionality of device extensions
+            /// that were promoted to core in place of being enabled.
+            pub(super) fn enable_promoted(
+                &mut self,
+                supported: &Features,
+                promoted: &crate::device::DeviceExtensions,
+            ) {
+                #(#enable_promoted_items)*
+            }
+
             /// Returns an `Features` object with none of the members set.
             #[inline]
             pub const fn empty() -> Self {
@@ -27,10 +27,11 @@ pub fn write(vk_data: &VkRegistryData, grammar: &SpirvGrammar) {
         .unwrap()
         .enumerants
         .as_slice();
-    let spirv_capabilities_output = spirv_reqs_output(
-        &spirv_capabilities_members(&vk_data.spirv_capabilities, grammar_enumerants),
-        false,
-    );
+    let spirv_capabilities_members =
+        spirv_capabilities_members(&vk_data.spirv_capabilities, grammar_enumerants);
+    let spirv_capabilities_output = spirv_reqs_output(&spirv_capabilities_members, false);
+    let spirv_capability_features_output =
+        spirv_capability_features_output(&spirv_capabilities_members);
     let spirv_extensions_output =
         spirv_reqs_output(&spirv_extensions_members(&vk_data.spirv_extensions), true);
     write_file(
@@ -41,6 +42,7 @@ pub fn write(vk_data: &VkRegistryData, grammar: &SpirvGrammar) {
         ),
         quote! {
             #spirv_capabilities_output
+            #spirv_capability_features_output
             #spirv_extensions_output
         },
     );
@@ -144,6 +146,44 @@ fn spirv_reqs_output(members: &[SpirvReqsMember], extension: bool) -> TokenStrea
     }
 }
 
+fn spirv_capability_features_output(members: &[SpirvReqsMember]) -> TokenStream {
+    let items = members
+        .iter()
+        .filter_map(|SpirvReqsMember { name, enables }| {
+            // Capabilities that are available with a core version don't need a feature.
+            if enables
+                .iter()
+                .any(|(enable, _)| matches!(enable, Enable::Core(_)))
+            {
+                return None;
+            }
+
+            // If several features enable the capability, any one of them will do, so pick the
+            // first.
+            let feature = enables.iter().find_map(|(enable, _)| match enable {
+                Enable::Feature(feature) => Some(feature),
+                _ => None,
+            })?;
+            let name = format_ident!("{}", name);
+
+            Some(quote! {
+                Capability::#name => crate::device::Features {
+                    #feature: true,
+                    ..crate::device::Features::empty()
+                },
+            })
+        });
+
+    quote! {
+        pub(crate) fn spirv_capability_features(capability: Capability) -> crate::device::Features {
+            match capability {
+                #(#items)*
+                _ => crate::device::Features::empty(),
+            }
+        }
+    }
+}
+
 fn spirv_capabilities_members(
     capabilities: &[&SpirvExtOrCap],
     grammar_enumerants: &[SpirvKindEnumerant],
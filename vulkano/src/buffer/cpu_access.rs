@@ -12,9 +12,11 @@
 //! The `CpuAccessibleBuffer` is a basic general-purpose buffer. It can be used in any situation
 //! but may not perform as well as other buffer types.
 //!
-//! Each access from the CPU or from the GPU locks the whole buffer for either reading or writing.
-//! You can read the buffer multiple times simultaneously. Trying to read and write simultaneously,
-//! or write and write simultaneously will block.
+//! Each access from the CPU or from the GPU locks the range of the buffer that it uses for either
+//! reading or writing. `read` and `write` lock the whole buffer, while `read_range`, `write_range`
+//! and their typed variants lock only part of it. You can read the buffer multiple times
+//! simultaneously. Trying to read and write simultaneously, or write and write simultaneously will
+//! block.
 
 use super::{
     sys::UnsafeBuffer, BufferAccess, BufferAccessObject, BufferContents, BufferInner, BufferUsage,
@@ -30,7 +32,7 @@ use crate::{
         DedicatedAllocation, DeviceMemoryError, MemoryPool,
     },
     sync::Sharing,
-    DeviceSize,
+    DeviceSize, VulkanObject,
 };
 use smallvec::SmallVec;
 use std::{
@@ -216,7 +218,9 @@ where
         host_cached: bool,
         queue_family_indices: impl IntoIterator<Item = u32>,
     ) -> Result<Arc<CpuAccessibleBuffer<T>>, DeviceMemoryError> {
-        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        let mut queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        queue_family_indices.sort_unstable();
+        queue_family_indices.dedup();
 
         let buffer = {
             match UnsafeBuffer::new(
@@ -300,35 +304,30 @@ where
     /// that uses it in exclusive mode will fail. You can still submit this buffer for non-exclusive
     /// accesses (ie. reads).
     pub fn read(&self) -> Result<ReadLock<'_, T, A>, ReadLockError> {
-        let mut state = self.inner.state();
-        let buffer_range = self.inner().offset..self.inner().offset + self.size();
-
-        unsafe {
-            state.check_cpu_read(buffer_range.clone())?;
-            state.cpu_read_lock(buffer_range.clone());
-        }
-
-        let mapped_memory = self.memory.mapped_memory().unwrap();
-        let offset = self.memory.offset();
-        let memory_range = offset..offset + self.inner.size();
-
-        let bytes = unsafe {
-            // If there are other read locks being held at this point, they also called
-            // `invalidate_range` when locking. The GPU can't write data while the CPU holds a read
-            // lock, so there will no new data and this call will do nothing.
-            // TODO: probably still more efficient to call it only if we're the first to acquire a
-            // read lock, but the number of CPU locks isn't currently tracked anywhere.
-            mapped_memory
-                .invalidate_range(memory_range.clone())
-                .unwrap();
-            mapped_memory.read(memory_range).unwrap()
-        };
+        unsafe { self.read_unchecked(0..self.size()) }
+    }
 
-        Ok(ReadLock {
-            inner: self,
-            buffer_range,
-            data: T::from_bytes(bytes).unwrap(),
-        })
+    /// Locks a range of the buffer in order to read its content from the CPU.
+    ///
+    /// `range` is specified in bytes, relative to the start of the buffer. Only that range is
+    /// locked and invalidated, so the GPU can still write to other parts of the buffer while the
+    /// lock is held. If the memory of the buffer is not host-coherent, the locked range is
+    /// extended to a multiple of the
+    /// [`non_coherent_atom_size`](crate::device::Properties::non_coherent_atom_size) device
+    /// property.
+    ///
+    /// See [`read`](Self::read) for when this function returns an error.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `range` is empty or is not within the buffer.
+    pub fn read_range(
+        &self,
+        range: Range<DeviceSize>,
+    ) -> Result<ReadLock<'_, [u8], A>, ReadLockError> {
+        assert!(!range.is_empty() && range.end <= self.size());
+
+        unsafe { self.read_unchecked(range) }
     }
 
     /// Locks the buffer in order to write its content from the CPU.
@@ -340,31 +339,246 @@ where
     /// After this function successfully locks the buffer, any attempt to submit a command buffer
     /// that uses it and any attempt to call `read()` will return an error.
     pub fn write(&self) -> Result<WriteLock<'_, T, A>, WriteLockError> {
-        let mut state = self.inner.state();
-        let buffer_range = self.inner().offset..self.inner().offset + self.size();
+        unsafe { self.write_unchecked(0..self.size()) }
+    }
 
-        unsafe {
-            state.check_cpu_write(buffer_range.clone())?;
-            state.cpu_write_lock(buffer_range.clone());
-        }
+    /// Locks a range of the buffer in order to write its content from the CPU.
+    ///
+    /// `range` is specified in bytes, relative to the start of the buffer. Only that range is
+    /// locked, invalidated and flushed, so the GPU can still use other parts of the buffer while
+    /// the lock is held. If the memory of the buffer is not host-coherent, the locked range is
+    /// extended to a multiple of the
+    /// [`non_coherent_atom_size`](crate::device::Properties::non_coherent_atom_size) device
+    /// property.
+    ///
+    /// See [`write`](Self::write) for when this function returns an error.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `range` is empty or is not within the buffer.
+    pub fn write_range(
+        &self,
+        range: Range<DeviceSize>,
+    ) -> Result<WriteLock<'_, [u8], A>, WriteLockError> {
+        assert!(!range.is_empty() && range.end <= self.size());
+
+        unsafe { self.write_unchecked(range) }
+    }
 
-        let mapped_memory = self.memory.mapped_memory().unwrap();
-        let offset = self.memory.offset();
-        let memory_range = offset..offset + self.size();
+    // Safety: `range` must be a non-empty range within the buffer, and its bytes must be a valid
+    // `U`.
+    unsafe fn read_unchecked<U>(
+        &self,
+        range: Range<DeviceSize>,
+    ) -> Result<ReadLock<'_, U, A>, ReadLockError>
+    where
+        U: BufferContents + ?Sized,
+    {
+        read_mapped(&self.inner, &self.memory, range)
+    }
 
-        let bytes = unsafe {
-            mapped_memory
-                .invalidate_range(memory_range.clone())
-                .unwrap();
-            mapped_memory.write(memory_range.clone()).unwrap()
-        };
+    // Safety: `range` must be a non-empty range within the buffer, and its bytes must be a valid
+    // `U`.
+    unsafe fn write_unchecked<U>(
+        &self,
+        range: Range<DeviceSize>,
+    ) -> Result<WriteLock<'_, U, A>, WriteLockError>
+    where
+        U: BufferContents + ?Sized,
+    {
+        write_mapped(&self.inner, &self.memory, range)
+    }
+}
+
+// Locks `range` of `buffer`, which must be bound at the start of the mapped allocation `memory`,
+// for reading from the CPU.
+//
+// Safety: `range` must be a non-empty range within the buffer, and its bytes must be a valid
+// `U`.
+pub(super) unsafe fn read_mapped<'a, U, A>(
+    buffer: &'a UnsafeBuffer,
+    memory: &'a A,
+    range: Range<DeviceSize>,
+) -> Result<ReadLock<'a, U, A>, ReadLockError>
+where
+    U: BufferContents + ?Sized,
+    A: MemoryPoolAlloc,
+{
+    let memory_range = lock_memory_range(buffer, memory, &range);
+    let buffer_range = buffer_range(memory, &memory_range);
+
+    let mut state = buffer.state();
+    state.check_cpu_read(buffer_range.clone())?;
+    state.cpu_read_lock(buffer_range.clone());
+
+    let mapped_memory = memory.mapped_memory().unwrap();
+    let offset = memory.offset();
+
+    // If there are other read locks being held at this point, they also called
+    // `invalidate_range` when locking. The GPU can't write data while the CPU holds a read
+    // lock, so there will no new data and this call will do nothing.
+    // TODO: probably still more efficient to call it only if we're the first to acquire a
+    // read lock, but the number of CPU locks isn't currently tracked anywhere.
+    mapped_memory.invalidate_range(memory_range).unwrap();
+    let bytes = mapped_memory
+        .read(offset + range.start..offset + range.end)
+        .unwrap();
+
+    Ok(ReadLock {
+        inner: buffer,
+        buffer_range,
+        data: U::from_bytes(bytes).unwrap(),
+        marker: PhantomData,
+    })
+}
+
+// Locks `range` of `buffer`, which must be bound at the start of the mapped allocation `memory`,
+// for writing from the CPU.
+//
+// Safety: `range` must be a non-empty range within the buffer, and its bytes must be a valid
+// `U`.
+pub(super) unsafe fn write_mapped<'a, U, A>(
+    buffer: &'a UnsafeBuffer,
+    memory: &'a A,
+    range: Range<DeviceSize>,
+) -> Result<WriteLock<'a, U, A>, WriteLockError>
+where
+    U: BufferContents + ?Sized,
+    A: MemoryPoolAlloc,
+{
+    let memory_range = lock_memory_range(buffer, memory, &range);
+    let buffer_range = buffer_range(memory, &memory_range);
+
+    let mut state = buffer.state();
+    state.check_cpu_write(buffer_range.clone())?;
+    state.cpu_write_lock(buffer_range.clone());
+
+    let mapped_memory = memory.mapped_memory().unwrap();
+    let offset = memory.offset();
+
+    mapped_memory
+        .invalidate_range(memory_range.clone())
+        .unwrap();
+    let bytes = mapped_memory
+        .write(offset + range.start..offset + range.end)
+        .unwrap();
+
+    Ok(WriteLock {
+        inner: buffer,
+        memory,
+        buffer_range,
+        memory_range,
+        data: U::from_bytes_mut(bytes).unwrap(),
+    })
+}
+
+// Returns the range of memory that must be locked, invalidated and flushed to access `range`
+// of the buffer. For non-coherent memory, this is `range` rounded outwards to the atom size.
+fn lock_memory_range<A>(
+    buffer: &UnsafeBuffer,
+    memory: &A,
+    range: &Range<DeviceSize>,
+) -> Range<DeviceSize>
+where
+    A: MemoryPoolAlloc,
+{
+    let offset = memory.offset();
+    let memory_range = offset + range.start..offset + range.end;
+
+    let physical_device = buffer.device().physical_device();
+    let memory_type = &physical_device.memory_properties().memory_types
+        [memory.memory().memory_type_index() as usize];
+
+    if memory_type.property_flags.host_coherent {
+        return memory_range;
+    }
+
+    let atom_size = physical_device.properties().non_coherent_atom_size;
+    let start = (memory_range.start / atom_size * atom_size).max(offset);
+    let end =
+        ((memory_range.end + atom_size - 1) / atom_size * atom_size).min(offset + buffer.size());
+
+    start..end
+}
+
+// Converts a range of memory to the corresponding range of the buffer.
+fn buffer_range<A>(memory: &A, memory_range: &Range<DeviceSize>) -> Range<DeviceSize>
+where
+    A: MemoryPoolAlloc,
+{
+    let memory_offset = memory.offset();
+
+    memory_range.start - memory_offset..memory_range.end - memory_offset
+}
+
+impl<T, A> CpuAccessibleBuffer<[T], A>
+where
+    [T]: BufferContents,
+    T: BufferContents,
+    A: MemoryPoolAlloc,
+{
+    /// Locks a single element of the buffer in order to read it from the CPU.
+    ///
+    /// See [`read_range`](Self::read_range) for how the lock behaves.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `index` is not less than the number of elements in the buffer.
+    pub fn read_index(&self, index: DeviceSize) -> Result<ReadLock<'_, T, A>, ReadLockError> {
+        assert!(index < self.len());
+        let element_size = size_of::<T>() as DeviceSize;
+
+        unsafe { self.read_unchecked(index * element_size..(index + 1) * element_size) }
+    }
+
+    /// Locks a range of elements of the buffer in order to read them from the CPU.
+    ///
+    /// `range` is specified in elements. See [`read_range`](Self::read_range) for how the lock
+    /// behaves.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `range` is empty or is not within the buffer.
+    pub fn read_slice(
+        &self,
+        range: Range<DeviceSize>,
+    ) -> Result<ReadLock<'_, [T], A>, ReadLockError> {
+        assert!(!range.is_empty() && range.end <= self.len());
+        let element_size = size_of::<T>() as DeviceSize;
+
+        unsafe { self.read_unchecked(range.start * element_size..range.end * element_size) }
+    }
+
+    /// Locks a single element of the buffer in order to write it from the CPU.
+    ///
+    /// See [`write_range`](Self::write_range) for how the lock behaves.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `index` is not less than the number of elements in the buffer.
+    pub fn write_index(&self, index: DeviceSize) -> Result<WriteLock<'_, T, A>, WriteLockError> {
+        assert!(index < self.len());
+        let element_size = size_of::<T>() as DeviceSize;
+
+        unsafe { self.write_unchecked(index * element_size..(index + 1) * element_size) }
+    }
 
-        Ok(WriteLock {
-            inner: self,
-            buffer_range,
-            memory_range,
-            data: T::from_bytes_mut(bytes).unwrap(),
-        })
+    /// Locks a range of elements of the buffer in order to write them from the CPU.
+    ///
+    /// `range` is specified in elements. See [`write_range`](Self::write_range) for how the lock
+    /// behaves.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `range` is empty or is not within the buffer.
+    pub fn write_slice(
+        &self,
+        range: Range<DeviceSize>,
+    ) -> Result<WriteLock<'_, [T], A>, WriteLockError> {
+        assert!(!range.is_empty() && range.end <= self.len());
+        let element_size = size_of::<T>() as DeviceSize;
+
+        unsafe { self.write_unchecked(range.start * element_size..range.end * element_size) }
     }
 }
 
@@ -412,6 +626,18 @@ where
     }
 }
 
+unsafe impl<T, A> VulkanObject for CpuAccessibleBuffer<T, A>
+where
+    T: BufferContents + ?Sized,
+{
+    type Object = ash::vk::Buffer;
+
+    #[inline]
+    fn internal_object(&self) -> ash::vk::Buffer {
+        self.inner.internal_object()
+    }
+}
+
 impl<T, A> PartialEq for CpuAccessibleBuffer<T, A>
 where
     T: BufferContents + ?Sized,
@@ -440,7 +666,8 @@ where
     }
 }
 
-/// Object that can be used to read or write the content of a `CpuAccessibleBuffer`.
+/// Object that can be used to read the content of a `CpuAccessibleBuffer`, or of a
+/// [host-mapped](super::DeviceLocalBuffer::is_host_mapped) `DeviceLocalBuffer`.
 ///
 /// Note that this object holds a rwlock read guard on the chunk. If another thread tries to access
 /// this buffer's content or tries to submit a GPU command that uses this buffer, it will block.
@@ -450,9 +677,10 @@ where
     T: BufferContents + ?Sized,
     A: MemoryPoolAlloc,
 {
-    inner: &'a CpuAccessibleBuffer<T, A>,
+    inner: &'a UnsafeBuffer,
     buffer_range: Range<DeviceSize>,
     data: &'a T,
+    marker: PhantomData<&'a A>,
 }
 
 impl<'a, T, A> Drop for ReadLock<'a, T, A>
@@ -462,7 +690,7 @@ where
 {
     fn drop(&mut self) {
         unsafe {
-            let mut state = self.inner.inner.state();
+            let mut state = self.inner.state();
             state.cpu_read_unlock(self.buffer_range.clone());
         }
     }
@@ -480,7 +708,8 @@ where
     }
 }
 
-/// Object that can be used to read or write the content of a `CpuAccessibleBuffer`.
+/// Object that can be used to read or write the content of a `CpuAccessibleBuffer`, or of a
+/// [host-mapped](super::DeviceLocalBuffer::is_host_mapped) `DeviceLocalBuffer`.
 ///
 /// Note that this object holds a rwlock write guard on the chunk. If another thread tries to access
 /// this buffer's content or tries to submit a GPU command that uses this buffer, it will block.
@@ -490,7 +719,8 @@ where
     T: BufferContents + ?Sized,
     A: MemoryPoolAlloc,
 {
-    inner: &'a CpuAccessibleBuffer<T, A>,
+    inner: &'a UnsafeBuffer,
+    memory: &'a A,
     buffer_range: Range<DeviceSize>,
     memory_range: Range<DeviceSize>,
     data: &'a mut T,
@@ -503,14 +733,13 @@ where
 {
     fn drop(&mut self) {
         unsafe {
-            self.inner
-                .memory
+            self.memory
                 .mapped_memory()
                 .unwrap()
                 .flush_range(self.memory_range.clone())
                 .unwrap();
 
-            let mut state = self.inner.inner.state();
+            let mut state = self.inner.state();
             state.cpu_write_unlock(self.buffer_range.clone());
         }
     }
@@ -592,6 +821,7 @@ impl Display for WriteLockError {
 
 #[cfg(test)]
 mod tests {
+    use super::ReadLockError;
     use crate::buffer::{BufferUsage, CpuAccessibleBuffer};
 
     #[test]
@@ -623,4 +853,34 @@ mod tests {
             .unwrap();
         });
     }
+
+    #[test]
+    fn lock_disjoint_ranges() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device,
+            BufferUsage {
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            0..4096u32,
+        )
+        .unwrap();
+
+        let mut write_lock = buffer.write_slice(0..64).unwrap();
+        write_lock[0] = 42;
+
+        let read_lock = buffer.read_slice(2048..4096).unwrap();
+        assert_eq!(read_lock[0], 2048);
+
+        assert!(matches!(
+            buffer.read_index(0),
+            Err(ReadLockError::CpuWriteLocked)
+        ));
+
+        drop(write_lock);
+        assert_eq!(*buffer.read_index(0).unwrap(), 42);
+    }
 }
@@ -15,6 +15,7 @@
 //!
 
 use super::{
+    cpu_access::{read_mapped, write_mapped, ReadLock, ReadLockError, WriteLock, WriteLockError},
     sys::{UnsafeBuffer, UnsafeBufferCreateInfo},
     BufferAccess, BufferAccessObject, BufferContents, BufferCreationError, BufferInner,
     BufferUsage, CpuAccessibleBuffer, TypedBufferAccess,
@@ -27,15 +28,15 @@ use crate::{
     device::{Device, DeviceOwned},
     memory::{
         pool::{
-            alloc_dedicated_with_exportable_fd, AllocFromRequirementsFilter, AllocLayout,
-            MappingRequirement, MemoryPoolAlloc, PotentialDedicatedAllocation,
-            StandardMemoryPoolAlloc,
+            alloc_dedicated_with_exportable_fd, alloc_dedicated_with_priority,
+            AllocFromRequirementsFilter, AllocLayout, MappingRequirement, MemoryPoolAlloc,
+            MemoryTypePolicy, PotentialDedicatedAllocation, StandardMemoryPoolAlloc,
         },
         DedicatedAllocation, DeviceMemoryError, ExternalMemoryHandleType, MemoryPool,
         MemoryRequirements,
     },
     sync::Sharing,
-    DeviceSize,
+    DeviceSize, VulkanObject,
 };
 use smallvec::SmallVec;
 use std::{
@@ -68,6 +69,11 @@ use std::{
 /// the host, then there may be significant performance gains by using a `DeviceLocalBuffer` over a
 /// buffer type which allows host access.
 ///
+/// On devices where some device-local memory is also host-visible, such as devices with resizable
+/// BAR or integrated GPUs, a buffer created with [`new_host_mapped`](Self::new_host_mapped) or
+/// [`array_host_mapped`](DeviceLocalBuffer::array_host_mapped) can be written to and read from the
+/// host directly, without a staging buffer.
+///
 /// # Examples
 ///
 /// The following example outlines the general strategy one may take when initializing a
@@ -169,6 +175,32 @@ where
             )
         }
     }
+
+    /// Builds a new buffer that the host can access directly, with [`read`](Self::read) and
+    /// [`write`](Self::write). Only allowed for sized data.
+    ///
+    /// The buffer is allocated with [`MemoryTypePolicy::PreferDeviceLocalHostVisible`]. If the
+    /// device has no memory type that is both device-local and host-visible, the buffer is
+    /// allocated in host-visible memory instead.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `T` has zero size.
+    pub fn new_host_mapped(
+        device: Arc<Device>,
+        usage: BufferUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+    ) -> Result<Arc<DeviceLocalBuffer<T>>, DeviceMemoryError> {
+        unsafe {
+            DeviceLocalBuffer::raw_with_memory_policy(
+                device,
+                size_of::<T>() as DeviceSize,
+                usage,
+                queue_family_indices,
+                MemoryTypePolicy::PreferDeviceLocalHostVisible,
+            )
+        }
+    }
 }
 
 impl<T> DeviceLocalBuffer<T>
@@ -326,6 +358,97 @@ where
             )
         }
     }
+
+    /// Builds a new buffer that the host can access directly, with [`read`](Self::read) and
+    /// [`write`](Self::write). Can be used for arrays.
+    ///
+    /// See [`new_host_mapped`](DeviceLocalBuffer::new_host_mapped) for how the memory is chosen.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `T` has zero size.
+    /// - Panics if `len` is zero.
+    pub fn array_host_mapped(
+        device: Arc<Device>,
+        len: DeviceSize,
+        usage: BufferUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+    ) -> Result<Arc<DeviceLocalBuffer<[T]>>, DeviceMemoryError> {
+        unsafe {
+            DeviceLocalBuffer::raw_with_memory_policy(
+                device,
+                len * size_of::<T>() as DeviceSize,
+                usage,
+                queue_family_indices,
+                MemoryTypePolicy::PreferDeviceLocalHostVisible,
+            )
+        }
+    }
+}
+
+impl<T> DeviceLocalBuffer<[T]>
+where
+    [T]: BufferContents,
+{
+    /// Builds a `DeviceLocalBuffer` of indirect commands, such as
+    /// [`DrawIndirectCommand`](crate::command_buffer::DrawIndirectCommand), from an iterator.
+    ///
+    /// This is equivalent to calling [`from_iter`](DeviceLocalBuffer::from_iter) with the
+    /// `indirect_buffer` usage added to `usage`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `T` has zero size.
+    /// - Panics if `data` is empty.
+    /// - Panics if `usage.shader_device_address` is `true`.
+    pub fn indirect_from_iter<D, L, A>(
+        data: D,
+        usage: BufferUsage,
+        command_buffer_builder: &mut AutoCommandBufferBuilder<L, A>,
+    ) -> Result<Arc<DeviceLocalBuffer<[T]>>, DeviceLocalBufferCreationError>
+    where
+        D: IntoIterator<Item = T>,
+        D::IntoIter: ExactSizeIterator,
+        A: CommandBufferAllocator,
+    {
+        DeviceLocalBuffer::from_iter(
+            data,
+            BufferUsage {
+                indirect_buffer: true,
+                ..usage
+            },
+            command_buffer_builder,
+        )
+    }
+
+    /// Builds a new buffer for `len` indirect commands that are written by the GPU, for example
+    /// by a compute shader that performs culling.
+    ///
+    /// This is equivalent to calling [`array`](DeviceLocalBuffer::array) with the
+    /// `indirect_buffer` and `storage_buffer` usages added to `usage`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `T` has zero size.
+    /// - Panics if `len` is zero.
+    /// - Panics if `usage.shader_device_address` is `true`.
+    pub fn indirect_array(
+        device: Arc<Device>,
+        len: DeviceSize,
+        usage: BufferUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+    ) -> Result<Arc<DeviceLocalBuffer<[T]>>, DeviceMemoryError> {
+        DeviceLocalBuffer::array(
+            device,
+            len,
+            BufferUsage {
+                indirect_buffer: true,
+                storage_buffer: true,
+                ..usage
+            },
+            queue_family_indices,
+        )
+    }
 }
 
 impl<T> DeviceLocalBuffer<T>
@@ -349,7 +472,39 @@ where
         usage: BufferUsage,
         queue_family_indices: impl IntoIterator<Item = u32>,
     ) -> Result<Arc<DeviceLocalBuffer<T>>, DeviceMemoryError> {
-        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        DeviceLocalBuffer::raw_with_memory_policy(
+            device,
+            size,
+            usage,
+            queue_family_indices,
+            MemoryTypePolicy::PreferDeviceLocal,
+        )
+    }
+
+    /// Same as `raw`, but chooses the memory type according to `policy`.
+    ///
+    /// If `policy` maps the allocation, the buffer can be accessed with [`read`](Self::read) and
+    /// [`write`](Self::write).
+    ///
+    /// # Safety
+    ///
+    /// - You must ensure that the size that you pass is correct for `T`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `size` is zero.
+    /// - Panics if `usage.shader_device_address` is `true`.
+    // TODO: ^
+    pub unsafe fn raw_with_memory_policy(
+        device: Arc<Device>,
+        size: DeviceSize,
+        usage: BufferUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+        policy: MemoryTypePolicy,
+    ) -> Result<Arc<DeviceLocalBuffer<T>>, DeviceMemoryError> {
+        let mut queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        queue_family_indices.sort_unstable();
+        queue_family_indices.dedup();
 
         let (buffer, mem_reqs) = Self::build_buffer(&device, size, usage, &queue_family_indices)?;
 
@@ -357,15 +512,59 @@ where
             &device.standard_memory_pool(),
             &mem_reqs,
             AllocLayout::Linear,
-            MappingRequirement::DoNotMap,
+            policy.mapping_requirement(),
             Some(DedicatedAllocation::Buffer(&buffer)),
-            |t| {
-                if t.property_flags.device_local {
-                    AllocFromRequirementsFilter::Preferred
-                } else {
-                    AllocFromRequirementsFilter::Allowed
-                }
-            },
+            |t| policy.filter(t),
+        )?;
+        debug_assert!((memory.offset() % mem_reqs.alignment) == 0);
+        buffer.bind_memory(memory.memory(), memory.offset())?;
+
+        Ok(Arc::new(DeviceLocalBuffer {
+            inner: buffer,
+            memory,
+            queue_family_indices,
+            marker: PhantomData,
+        }))
+    }
+
+    /// Same as `raw`, but the buffer gets a dedicated memory allocation with the given
+    /// `priority`, between 0.0 and 1.0. Buffers with a higher priority are less likely to be moved
+    /// out of device-local memory when it is oversubscribed.
+    ///
+    /// The [`memory_priority`](crate::device::Features::memory_priority) feature must be enabled
+    /// on the device. The priority can be changed later with
+    /// [`DeviceMemory::set_priority`](crate::memory::DeviceMemory::set_priority).
+    ///
+    /// # Safety
+    ///
+    /// - You must ensure that the size that you pass is correct for `T`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `size` is zero.
+    /// - Panics if `usage.shader_device_address` is `true`.
+    // TODO: ^
+    pub unsafe fn raw_with_priority(
+        device: Arc<Device>,
+        size: DeviceSize,
+        usage: BufferUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+        priority: f32,
+    ) -> Result<Arc<DeviceLocalBuffer<T>>, DeviceMemoryError> {
+        let mut queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        queue_family_indices.sort_unstable();
+        queue_family_indices.dedup();
+
+        let (buffer, mem_reqs) = Self::build_buffer(&device, size, usage, &queue_family_indices)?;
+
+        let policy = MemoryTypePolicy::PreferDeviceLocal;
+        let memory = alloc_dedicated_with_priority(
+            device,
+            &mem_reqs,
+            policy.mapping_requirement(),
+            DedicatedAllocation::Buffer(&buffer),
+            |t| policy.filter(t),
+            priority,
         )?;
         debug_assert!((memory.offset() % mem_reqs.alignment) == 0);
         buffer.bind_memory(memory.memory(), memory.offset())?;
@@ -394,7 +593,9 @@ where
         assert!(device.enabled_extensions().khr_external_memory_fd);
         assert!(device.enabled_extensions().khr_external_memory);
 
-        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        let mut queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        queue_family_indices.sort_unstable();
+        queue_family_indices.dedup();
 
         let (buffer, mem_reqs) = Self::build_buffer(&device, size, usage, &queue_family_indices)?;
 
@@ -475,6 +676,45 @@ where
     }
 }
 
+impl<T, A> DeviceLocalBuffer<T, A>
+where
+    T: BufferContents + ?Sized,
+    A: MemoryPoolAlloc,
+{
+    /// Returns whether the memory of the buffer is mapped, so that it can be accessed with
+    /// [`read`](Self::read) and [`write`](Self::write).
+    #[inline]
+    pub fn is_host_mapped(&self) -> bool {
+        self.memory.mapped_memory().is_some()
+    }
+
+    /// Locks the buffer in order to read its content from the host.
+    ///
+    /// This behaves the same as [`CpuAccessibleBuffer::read`].
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the buffer is not [host-mapped](Self::is_host_mapped).
+    pub fn read(&self) -> Result<ReadLock<'_, T, A>, ReadLockError> {
+        assert!(self.is_host_mapped());
+
+        unsafe { read_mapped(&self.inner, &self.memory, 0..self.size()) }
+    }
+
+    /// Locks the buffer in order to write its content from the host.
+    ///
+    /// This behaves the same as [`CpuAccessibleBuffer::write`].
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the buffer is not [host-mapped](Self::is_host_mapped).
+    pub fn write(&self) -> Result<WriteLock<'_, T, A>, WriteLockError> {
+        assert!(self.is_host_mapped());
+
+        unsafe { write_mapped(&self.inner, &self.memory, 0..self.size()) }
+    }
+}
+
 unsafe impl<T, A> DeviceOwned for DeviceLocalBuffer<T, A>
 where
     T: BufferContents + ?Sized,
@@ -484,6 +724,18 @@ where
     }
 }
 
+unsafe impl<T, A> VulkanObject for DeviceLocalBuffer<T, A>
+where
+    T: BufferContents + ?Sized,
+{
+    type Object = ash::vk::Buffer;
+
+    #[inline]
+    fn internal_object(&self) -> ash::vk::Buffer {
+        self.inner.internal_object()
+    }
+}
+
 unsafe impl<T, A> BufferAccess for DeviceLocalBuffer<T, A>
 where
     T: BufferContents + ?Sized,
@@ -717,6 +969,38 @@ mod tests {
         });
     }
 
+    #[test]
+    fn host_mapped_write_read() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer = DeviceLocalBuffer::<[u32]>::array_host_mapped(
+            device.clone(),
+            4,
+            BufferUsage {
+                storage_buffer: true,
+                ..BufferUsage::empty()
+            },
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+        assert!(buffer.is_host_mapped());
+
+        buffer.write().unwrap().copy_from_slice(&[1, 2, 3, 4]);
+        assert_eq!(*buffer.read().unwrap(), [1, 2, 3, 4]);
+
+        let buffer = DeviceLocalBuffer::<[u32]>::array(
+            device,
+            4,
+            BufferUsage {
+                storage_buffer: true,
+                ..BufferUsage::empty()
+            },
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+        assert!(!buffer.is_host_mapped());
+    }
+
     // TODO: write tons of tests that try to exploit loopholes
     // this isn't possible yet because checks aren't correctly implemented yet
 }
@@ -79,6 +79,7 @@ pub use self::{
     cpu_pool::CpuBufferPool,
     device_local::DeviceLocalBuffer,
     slice::BufferSlice,
+    subbuffer::Subbuffer,
     sys::{BufferCreationError, SparseLevel},
     traits::{
         BufferAccess, BufferAccessObject, BufferDeviceAddressError, BufferInner, TypedBufferAccess,
@@ -102,6 +103,7 @@ pub mod sys;
 pub mod view;
 
 mod slice;
+mod subbuffer;
 mod traits;
 mod usage;
 
@@ -0,0 +1,397 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use super::{BufferAccess, BufferAccessObject, BufferContents, BufferInner, TypedBufferAccess};
+use crate::{
+    device::{Device, DeviceOwned},
+    DeviceSize,
+};
+use bytemuck::PodCastError;
+use std::{
+    fmt::{Debug, Error as FmtError, Formatter},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    mem::{align_of, size_of},
+    ops::Range,
+    sync::Arc,
+};
+
+/// A typed view of a range of a buffer.
+///
+/// Unlike [`BufferSlice`](super::BufferSlice), a `Subbuffer` doesn't carry the type of the buffer
+/// that it views, so subbuffers of different kinds of buffers have the same type. It implements
+/// [`BufferAccess`] and [`TypedBufferAccess`], so it can be used with any command or descriptor
+/// that accepts a buffer.
+///
+/// This object doesn't correspond to any Vulkan object. It exists for API convenience.
+///
+/// # Examples
+///
+/// ```
+/// use vulkano::buffer::{BufferAccess, DeviceLocalBuffer, Subbuffer};
+/// # let buffer: std::sync::Arc<DeviceLocalBuffer<[u32]>> = return;
+///
+/// let subbuffer = buffer.into_subbuffer();
+/// let (first_half, second_half) = subbuffer.split_at(subbuffer.len() / 2).unwrap();
+/// let bytes: Subbuffer<[u8]> = second_half.cast_slice().unwrap();
+/// ```
+pub struct Subbuffer<T: ?Sized> {
+    buffer: Arc<dyn BufferAccess>,
+    offset: DeviceSize,
+    size: DeviceSize,
+    marker: PhantomData<Arc<T>>,
+}
+
+impl<T: ?Sized> Subbuffer<T> {
+    /// Returns a `Subbuffer` covering the whole of `buffer`.
+    #[inline]
+    pub fn new<B>(buffer: Arc<B>) -> Self
+    where
+        B: TypedBufferAccess<Content = T> + 'static,
+    {
+        let size = buffer.size();
+
+        Subbuffer {
+            buffer,
+            offset: 0,
+            size,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a `Subbuffer` covering `size` bytes of `buffer`, starting at `offset`.
+    ///
+    /// # Safety
+    ///
+    /// - The range must contain a valid value of type `T`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the range is not within `buffer`.
+    #[inline]
+    pub unsafe fn from_raw(
+        buffer: Arc<dyn BufferAccess>,
+        offset: DeviceSize,
+        size: DeviceSize,
+    ) -> Self {
+        assert!(offset
+            .checked_add(size)
+            .map_or(false, |end| end <= buffer.size()));
+
+        Subbuffer {
+            buffer,
+            offset,
+            size,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the buffer that this subbuffer is a view of.
+    #[inline]
+    pub fn buffer(&self) -> &Arc<dyn BufferAccess> {
+        &self.buffer
+    }
+
+    /// Returns the offset of the subbuffer, in bytes, relative to the start of the buffer.
+    #[inline]
+    pub fn offset(&self) -> DeviceSize {
+        self.offset
+    }
+
+    /// Returns the size of the subbuffer in bytes.
+    #[inline]
+    pub fn size(&self) -> DeviceSize {
+        self.size
+    }
+
+    /// Changes the `T` generic parameter of the subbuffer, without any checks.
+    ///
+    /// # Safety
+    ///
+    /// - The range of the subbuffer must contain a valid value of type `R`.
+    #[inline]
+    pub unsafe fn reinterpret<R: ?Sized>(self) -> Subbuffer<R> {
+        Subbuffer {
+            buffer: self.buffer,
+            offset: self.offset,
+            size: self.size,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Subbuffer<[T]> {
+    /// Returns the number of elements in the subbuffer.
+    #[inline]
+    pub fn len(&self) -> DeviceSize {
+        debug_assert_eq!(self.size % size_of::<T>() as DeviceSize, 0);
+        self.size / size_of::<T>() as DeviceSize
+    }
+
+    /// Returns whether the subbuffer has no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Reduces the subbuffer to just one element.
+    ///
+    /// Returns `None` if out of range.
+    pub fn index(&self, index: DeviceSize) -> Option<Subbuffer<T>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        Some(Subbuffer {
+            buffer: self.buffer.clone(),
+            offset: self.offset + index * size_of::<T>() as DeviceSize,
+            size: size_of::<T>() as DeviceSize,
+            marker: PhantomData,
+        })
+    }
+
+    /// Reduces the subbuffer to just a range of elements.
+    ///
+    /// Returns `None` if out of range.
+    pub fn slice(&self, range: Range<DeviceSize>) -> Option<Subbuffer<[T]>> {
+        if range.start > range.end || range.end > self.len() {
+            return None;
+        }
+
+        Some(Subbuffer {
+            buffer: self.buffer.clone(),
+            offset: self.offset + range.start * size_of::<T>() as DeviceSize,
+            size: (range.end - range.start) * size_of::<T>() as DeviceSize,
+            marker: PhantomData,
+        })
+    }
+
+    /// Splits the subbuffer into two at the element index `mid`. The first subbuffer contains
+    /// the elements `0..mid`, the second the elements `mid..len`.
+    ///
+    /// Returns `None` if `mid` is greater than the number of elements.
+    pub fn split_at(&self, mid: DeviceSize) -> Option<(Subbuffer<[T]>, Subbuffer<[T]>)> {
+        Some((self.slice(0..mid)?, self.slice(mid..self.len())?))
+    }
+
+    /// Reinterprets the subbuffer as a slice of a different element type.
+    ///
+    /// The same rules apply as for [`bytemuck::try_cast_slice`]. The alignment is checked
+    /// against the offset of the subbuffer in the underlying `UnsafeBuffer`.
+    pub fn cast_slice<U>(self) -> Result<Subbuffer<[U]>, PodCastError>
+    where
+        T: BufferContents,
+        U: BufferContents,
+    {
+        if size_of::<U>() == 0 || size_of::<T>() == 0 {
+            return Err(PodCastError::SizeMismatch);
+        }
+
+        if align_of::<U>() > align_of::<T>()
+            && (self.buffer.inner().offset + self.offset) % align_of::<U>() as DeviceSize != 0
+        {
+            return Err(PodCastError::TargetAlignmentGreaterAndInputNotAligned);
+        }
+
+        if self.size % size_of::<U>() as DeviceSize != 0 {
+            return Err(PodCastError::OutputSliceWouldHaveSlop);
+        }
+
+        Ok(unsafe { self.reinterpret() })
+    }
+}
+
+// We need to implement `Clone` manually, otherwise the derive adds a `T: Clone` requirement.
+impl<T: ?Sized> Clone for Subbuffer<T> {
+    fn clone(&self) -> Self {
+        Subbuffer {
+            buffer: self.buffer.clone(),
+            offset: self.offset,
+            size: self.size,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Debug for Subbuffer<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        f.debug_struct("Subbuffer")
+            .field("buffer", &self.buffer)
+            .field("offset", &self.offset)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+unsafe impl<T> BufferAccess for Subbuffer<T>
+where
+    T: Send + Sync + ?Sized,
+{
+    fn inner(&self) -> BufferInner<'_> {
+        let inner = self.buffer.inner();
+        BufferInner {
+            buffer: inner.buffer,
+            offset: inner.offset + self.offset,
+        }
+    }
+
+    fn size(&self) -> DeviceSize {
+        self.size
+    }
+}
+
+impl<T> BufferAccessObject for Subbuffer<T>
+where
+    T: Send + Sync + ?Sized + 'static,
+{
+    fn as_buffer_access_object(&self) -> Arc<dyn BufferAccess> {
+        Arc::new(self.clone())
+    }
+}
+
+impl<T> BufferAccessObject for Arc<Subbuffer<T>>
+where
+    T: Send + Sync + ?Sized + 'static,
+{
+    fn as_buffer_access_object(&self) -> Arc<dyn BufferAccess> {
+        self.clone()
+    }
+}
+
+unsafe impl<T> TypedBufferAccess for Subbuffer<T>
+where
+    T: BufferContents + ?Sized,
+{
+    type Content = T;
+}
+
+unsafe impl<T: ?Sized> DeviceOwned for Subbuffer<T> {
+    fn device(&self) -> &Arc<Device> {
+        self.buffer.device()
+    }
+}
+
+impl<T> From<Subbuffer<T>> for Subbuffer<[T]> {
+    fn from(r: Subbuffer<T>) -> Subbuffer<[T]> {
+        unsafe { r.reinterpret() }
+    }
+}
+
+impl<T> PartialEq for Subbuffer<T>
+where
+    T: Send + Sync + ?Sized,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.inner() == other.inner() && self.size() == other.size()
+    }
+}
+
+impl<T> Eq for Subbuffer<T> where T: Send + Sync + ?Sized {}
+
+impl<T> Hash for Subbuffer<T>
+where
+    T: Send + Sync + ?Sized,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.inner().hash(state);
+        self.size().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        buffer::{BufferAccess, BufferUsage, DeviceLocalBuffer, Subbuffer},
+        DeviceSize,
+    };
+    use bytemuck::PodCastError;
+
+    #[test]
+    fn split_at() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer = DeviceLocalBuffer::<[u32]>::array(
+            device,
+            16,
+            BufferUsage {
+                storage_buffer: true,
+                ..BufferUsage::empty()
+            },
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+
+        let subbuffer = buffer.into_subbuffer();
+        let (first, second) = subbuffer.split_at(4).unwrap();
+        assert_eq!((first.offset(), first.len()), (0, 4));
+        assert_eq!((second.offset(), second.len()), (16, 12));
+        assert_eq!(second.index(0).unwrap().offset(), 16);
+
+        assert!(subbuffer.split_at(17).is_none());
+        assert!(subbuffer.index(16).is_none());
+    }
+
+    #[test]
+    fn cast_slice() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer = DeviceLocalBuffer::<[u8]>::array(
+            device,
+            16,
+            BufferUsage {
+                storage_buffer: true,
+                ..BufferUsage::empty()
+            },
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+
+        let subbuffer = buffer.into_subbuffer();
+
+        let words: Subbuffer<[u32]> = subbuffer.clone().cast_slice().unwrap();
+        assert_eq!(words.len(), 4);
+
+        assert_eq!(
+            subbuffer.slice(2..10).unwrap().cast_slice::<u32>(),
+            Err(PodCastError::TargetAlignmentGreaterAndInputNotAligned),
+        );
+        assert_eq!(
+            subbuffer.slice(0..6).unwrap().cast_slice::<u32>(),
+            Err(PodCastError::OutputSliceWouldHaveSlop),
+        );
+    }
+
+    #[test]
+    fn from_raw_out_of_bounds() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer = DeviceLocalBuffer::<[u32]>::array(
+            device,
+            16,
+            BufferUsage {
+                storage_buffer: true,
+                ..BufferUsage::empty()
+            },
+            [queue.queue_family_index()],
+        )
+        .unwrap();
+
+        let subbuffer = unsafe { Subbuffer::<[u32]>::from_raw(buffer.clone(), 16, 48) };
+        assert_eq!(subbuffer.len(), 12);
+
+        assert_should_panic!({
+            unsafe { Subbuffer::<[u32]>::from_raw(buffer.clone(), 16, 52) };
+        });
+
+        // The end of the range would overflow.
+        assert_should_panic!({
+            unsafe { Subbuffer::<[u32]>::from_raw(buffer, 16, DeviceSize::MAX) };
+        });
+    }
+}
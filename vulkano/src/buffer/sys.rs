@@ -19,7 +19,10 @@
 //!
 //! - Synchronization, ie. avoid reading and writing simultaneously to the same buffer.
 //! - Memory aliasing considerations. If you use the same memory to back multiple resources, you
-//!   must ensure that they are not used together and must enable some additional flags.
+//!   must ensure that they are not used together and must enable some additional flags. Within a
+//!   single command buffer, vulkano treats accesses to resources with overlapping memory as
+//!   conflicting and inserts barriers between them, but it does not track aliasing across
+//!   command buffers.
 //! - Binding memory correctly and only once. If you use sparse binding, respect the rules of
 //!   sparse binding.
 //! - Type safety.
@@ -57,8 +60,11 @@ pub struct UnsafeBuffer {
 
     size: DeviceSize,
     usage: BufferUsage,
+    sharing: Sharing<SmallVec<[u32; 4]>>,
     external_memory_handle_types: ExternalMemoryHandleTypes,
+    protected: bool,
 
+    memory_binding: Mutex<Option<(ash::vk::DeviceMemory, Range<DeviceSize>)>>,
     state: Mutex<BufferState>,
 }
 
@@ -100,6 +106,7 @@ impl UnsafeBuffer {
             sparse,
             usage,
             external_memory_handle_types,
+            protected,
             _ne: _,
         } = create_info;
 
@@ -149,6 +156,24 @@ impl UnsafeBuffer {
             // VUID-VkBufferCreateInfo-flags-00918
         }
 
+        if protected {
+            // VUID-VkBufferCreateInfo-flags-01887
+            if !device.enabled_features().protected_memory {
+                return Err(BufferCreationError::RequirementNotMet {
+                    required_for: "`create_info.protected` is set",
+                    requires_one_of: RequiresOneOf {
+                        features: &["protected_memory"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkBufferCreateInfo-None-01888
+            if sparse.is_some() {
+                return Err(BufferCreationError::ProtectedSparse);
+            }
+        }
+
         match sharing {
             Sharing::Exclusive => (),
             Sharing::Concurrent(queue_family_indices) => {
@@ -217,6 +242,7 @@ impl UnsafeBuffer {
             sparse,
             usage,
             external_memory_handle_types,
+            protected,
             _ne: _,
         } = &create_info;
 
@@ -226,6 +252,10 @@ impl UnsafeBuffer {
             flags |= sparse_level.into();
         }
 
+        if protected {
+            flags |= ash::vk::BufferCreateFlags::PROTECTED;
+        }
+
         let (sharing_mode, queue_family_index_count, p_queue_family_indices) = match sharing {
             Sharing::Exclusive => (ash::vk::SharingMode::EXCLUSIVE, 0, &[] as _),
             Sharing::Concurrent(queue_family_indices) => (
@@ -290,9 +320,10 @@ impl UnsafeBuffer {
         let UnsafeBufferCreateInfo {
             size,
             usage,
-            sharing: _,
+            sharing,
             sparse: _,
             external_memory_handle_types,
+            protected,
             _ne: _,
         } = create_info;
 
@@ -302,8 +333,11 @@ impl UnsafeBuffer {
 
             size,
             usage,
+            sharing,
             external_memory_handle_types,
+            protected,
 
+            memory_binding: Mutex::new(None),
             state: Mutex::new(BufferState::new(size)),
         })
     }
@@ -408,6 +442,9 @@ impl UnsafeBuffer {
     ///   with the [`device_address`] flag set and the [`ext_buffer_device_address`] extension is
     ///   not enabled on the device.
     ///
+    /// - Panics if `self.protected()` is `true` and the memory type of `memory` is not protected,
+    ///   or the other way around.
+    ///
     /// [`device_address`]: crate::memory::MemoryAllocateFlags::device_address
     /// [`ext_buffer_device_address`]: crate::device::DeviceExtensions::ext_buffer_device_address
     pub unsafe fn bind_memory(
@@ -415,6 +452,107 @@ impl UnsafeBuffer {
         memory: &DeviceMemory,
         offset: DeviceSize,
     ) -> Result<(), OomError> {
+        self.check_bind_memory(memory, offset);
+
+        let fns = self.device.fns();
+        (fns.v1_0.bind_buffer_memory)(
+            self.device.internal_object(),
+            self.handle,
+            memory.internal_object(),
+            offset,
+        )
+        .result()
+        .map_err(VulkanError::from)?;
+
+        self.set_memory_binding(memory, offset);
+
+        Ok(())
+    }
+
+    /// Binds device memory to multiple buffers at once.
+    ///
+    /// If the device API version is at least 1.1, or the
+    /// [`khr_bind_memory2`](crate::device::DeviceExtensions::khr_bind_memory2) extension is
+    /// enabled, all the buffers are bound with a single `vkBindBufferMemory2` call. Otherwise they
+    /// are bound one by one.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the buffers do not all belong to the same device.
+    /// - Panics for the same reasons as [`bind_memory`](Self::bind_memory), for each element of
+    ///   `bind_infos`.
+    pub unsafe fn bind_memory_batch<'a>(
+        bind_infos: impl IntoIterator<Item = BindBufferMemoryInfo<'a>>,
+    ) -> Result<(), OomError> {
+        let bind_infos: SmallVec<[_; 4]> = bind_infos.into_iter().collect();
+
+        let device = match bind_infos.first() {
+            Some(bind_info) => bind_info.buffer.device(),
+            None => return Ok(()),
+        };
+
+        for bind_info in &bind_infos {
+            assert_eq!(bind_info.buffer.device(), device);
+            bind_info
+                .buffer
+                .check_bind_memory(bind_info.memory, bind_info.offset);
+        }
+
+        let fns = device.fns();
+
+        if device.api_version() >= Version::V1_1 || device.enabled_extensions().khr_bind_memory2 {
+            let bind_infos_vk: SmallVec<[_; 4]> = bind_infos
+                .iter()
+                .map(|bind_info| ash::vk::BindBufferMemoryInfo {
+                    buffer: bind_info.buffer.handle,
+                    memory: bind_info.memory.internal_object(),
+                    memory_offset: bind_info.offset,
+                    ..Default::default()
+                })
+                .collect();
+
+            if device.api_version() >= Version::V1_1 {
+                (fns.v1_1.bind_buffer_memory2)(
+                    device.internal_object(),
+                    bind_infos_vk.len() as u32,
+                    bind_infos_vk.as_ptr(),
+                )
+            } else {
+                (fns.khr_bind_memory2.bind_buffer_memory2_khr)(
+                    device.internal_object(),
+                    bind_infos_vk.len() as u32,
+                    bind_infos_vk.as_ptr(),
+                )
+            }
+            .result()
+            .map_err(VulkanError::from)?;
+
+            for bind_info in &bind_infos {
+                bind_info
+                    .buffer
+                    .set_memory_binding(bind_info.memory, bind_info.offset);
+            }
+        } else {
+            for bind_info in &bind_infos {
+                (fns.v1_0.bind_buffer_memory)(
+                    device.internal_object(),
+                    bind_info.buffer.handle,
+                    bind_info.memory.internal_object(),
+                    bind_info.offset,
+                )
+                .result()
+                .map_err(VulkanError::from)?;
+
+                bind_info
+                    .buffer
+                    .set_memory_binding(bind_info.memory, bind_info.offset);
+            }
+        }
+
+        Ok(())
+    }
+
+    unsafe fn check_bind_memory(&self, memory: &DeviceMemory, offset: DeviceSize) {
         let fns = self.device.fns();
 
         // We check for correctness in debug mode.
@@ -453,16 +591,28 @@ impl UnsafeBuffer {
             assert!(memory.flags().device_address);
         }
 
-        (fns.v1_0.bind_buffer_memory)(
-            self.device.internal_object(),
-            self.handle,
-            memory.internal_object(),
-            offset,
-        )
-        .result()
-        .map_err(VulkanError::from)?;
+        // VUID-vkBindBufferMemory-None-01898
+        // VUID-vkBindBufferMemory-None-01899
+        assert_eq!(
+            self.protected,
+            self.device
+                .physical_device()
+                .memory_properties()
+                .memory_types[memory.memory_type_index() as usize]
+                .property_flags
+                .protected,
+        );
+    }
 
-        Ok(())
+    fn set_memory_binding(&self, memory: &DeviceMemory, offset: DeviceSize) {
+        let size = self.memory_requirements().size;
+        *self.memory_binding.lock() = Some((memory.internal_object(), offset..offset + size));
+    }
+
+    /// Returns the memory object that is bound to the buffer, and the range within it, if memory
+    /// was bound with `bind_memory`.
+    pub(crate) fn memory_binding(&self) -> Option<(ash::vk::DeviceMemory, Range<DeviceSize>)> {
+        self.memory_binding.lock().clone()
     }
 
     pub(crate) fn state(&self) -> MutexGuard<'_, BufferState> {
@@ -481,12 +631,24 @@ impl UnsafeBuffer {
         &self.usage
     }
 
+    /// Returns the sharing the buffer was created with.
+    #[inline]
+    pub fn sharing(&self) -> &Sharing<SmallVec<[u32; 4]>> {
+        &self.sharing
+    }
+
     /// Returns the external memory handle types that are supported with this buffer.
     #[inline]
     pub fn external_memory_handle_types(&self) -> ExternalMemoryHandleTypes {
         self.external_memory_handle_types
     }
 
+    /// Returns whether the buffer is a protected buffer.
+    #[inline]
+    pub fn protected(&self) -> bool {
+        self.protected
+    }
+
     /// Returns a key unique to each `UnsafeBuffer`. Can be used for the `conflicts_key` method.
     #[inline]
     pub fn key(&self) -> u64 {
@@ -568,6 +730,15 @@ pub struct UnsafeBufferCreateInfo {
     /// The default value is [`ExternalMemoryHandleTypes::empty()`].
     pub external_memory_handle_types: ExternalMemoryHandleTypes,
 
+    /// Create a protected buffer, which must be bound to protected memory and can only be
+    /// accessed by protected command buffers.
+    ///
+    /// If set, the [`protected_memory`](crate::device::Features::protected_memory) feature must
+    /// be enabled on the device, and `sparse` must be `None`.
+    ///
+    /// The default value is `false`.
+    pub protected: bool,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -580,11 +751,25 @@ impl Default for UnsafeBufferCreateInfo {
             sparse: None,
             usage: BufferUsage::empty(),
             external_memory_handle_types: ExternalMemoryHandleTypes::empty(),
+            protected: false,
             _ne: crate::NonExhaustive(()),
         }
     }
 }
 
+/// Parameters to bind memory to a buffer with [`UnsafeBuffer::bind_memory_batch`].
+#[derive(Clone, Copy, Debug)]
+pub struct BindBufferMemoryInfo<'a> {
+    /// The buffer to bind memory to.
+    pub buffer: &'a UnsafeBuffer,
+
+    /// The memory to bind to the buffer.
+    pub memory: &'a DeviceMemory,
+
+    /// The offset in bytes in `memory` where the buffer starts.
+    pub offset: DeviceSize,
+}
+
 /// Error that can happen when creating a buffer.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum BufferCreationError {
@@ -599,6 +784,9 @@ pub enum BufferCreationError {
     /// The specified size exceeded the value of the `max_buffer_size` limit.
     MaxBufferSizeExceeded { size: DeviceSize, max: DeviceSize },
 
+    /// The buffer was created protected and with sparse memory, which is not allowed.
+    ProtectedSparse,
+
     /// The sharing mode was set to `Concurrent`, but one of the specified queue family indices was
     /// out of range.
     SharingQueueFamilyIndexOutOfRange {
@@ -632,6 +820,10 @@ impl Display for BufferCreationError {
                 f,
                 "the specified size exceeded the value of the `max_buffer_size` limit",
             ),
+            Self::ProtectedSparse => write!(
+                f,
+                "the buffer was created protected and with sparse memory, which is not allowed",
+            ),
             Self::SharingQueueFamilyIndexOutOfRange { .. } => write!(
                 f,
                 "the sharing mode was set to `Concurrent`, but one of the specified queue family \
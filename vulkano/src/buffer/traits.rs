@@ -7,7 +7,7 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use super::{sys::UnsafeBuffer, BufferContents, BufferSlice, BufferUsage};
+use super::{sys::UnsafeBuffer, BufferContents, BufferSlice, BufferUsage, Subbuffer};
 use crate::{device::DeviceOwned, DeviceSize, RequiresOneOf, SafeDeref, Version, VulkanObject};
 use std::{
     error::Error,
@@ -44,6 +44,15 @@ pub unsafe trait BufferAccess: DeviceOwned + Send + Sync {
         BufferSlice::from_typed_buffer_access(self.clone())
     }
 
+    /// Returns a `Subbuffer` covering the whole buffer.
+    #[inline]
+    fn into_subbuffer(self: &Arc<Self>) -> Subbuffer<Self::Content>
+    where
+        Self: Sized + TypedBufferAccess + 'static,
+    {
+        Subbuffer::new(self.clone())
+    }
+
     /// Returns a `BufferSlice` for a subrange of elements in the buffer. Returns `None` if
     /// out of range.
     ///
@@ -46,7 +46,7 @@
 //! ).unwrap();
 //! ```
 
-use super::{BufferAccess, BufferAccessObject, BufferInner};
+use super::{BufferAccess, BufferAccessObject, BufferInner, Subbuffer};
 use crate::{
     device::{Device, DeviceOwned},
     format::{Format, FormatFeatures},
@@ -82,19 +82,41 @@ where
     B: BufferAccess + ?Sized,
 {
     /// Creates a new `BufferView`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.format` is `None`.
+    /// - Panics if `create_info.range` is `Some` and empty.
     pub fn new(
         buffer: Arc<B>,
         create_info: BufferViewCreateInfo,
     ) -> Result<Arc<BufferView<B>>, BufferViewCreationError> {
-        let BufferViewCreateInfo { format, _ne: _ } = create_info;
+        let BufferViewCreateInfo {
+            format,
+            range,
+            _ne: _,
+        } = create_info;
 
         let device = buffer.device();
         let properties = device.physical_device().properties();
-        let size = buffer.size();
+        let buffer_size = buffer.size();
+        let range = range.unwrap_or(0..buffer_size);
+
+        // VUID-VkBufferViewCreateInfo-range-00928
+        assert!(!range.is_empty());
+
+        // VUID-VkBufferViewCreateInfo-offset-00925
+        // VUID-VkBufferViewCreateInfo-range-00931
+        if range.end > buffer_size {
+            return Err(BufferViewCreationError::RangeOutOfBounds { range, buffer_size });
+        }
+
+        let size = range.end - range.start;
         let BufferInner {
             buffer: inner_buffer,
             offset,
         } = buffer.inner();
+        let offset = offset + range.start;
 
         // No VUID, but seems sensible?
         let format = format.unwrap();
@@ -234,7 +256,7 @@ where
 
             format: Some(format),
             format_features,
-            range: 0..size,
+            range,
         }))
     }
 
@@ -244,6 +266,23 @@ where
     }
 }
 
+impl<T> BufferView<Subbuffer<T>>
+where
+    T: Send + Sync + ?Sized,
+{
+    /// Creates a new `BufferView` that covers `subbuffer`.
+    ///
+    /// This is equivalent to calling [`BufferView::new`] with the subbuffer wrapped in an `Arc`.
+    /// `create_info.range` is relative to the start of the subbuffer.
+    #[inline]
+    pub fn from_subbuffer(
+        subbuffer: Subbuffer<T>,
+        create_info: BufferViewCreateInfo,
+    ) -> Result<Arc<Self>, BufferViewCreationError> {
+        Self::new(Arc::new(subbuffer), create_info)
+    }
+}
+
 impl<B> Drop for BufferView<B>
 where
     B: BufferAccess + ?Sized,
@@ -309,6 +348,21 @@ pub struct BufferViewCreateInfo {
     /// The default value is `None`, which must be overridden.
     pub format: Option<Format>,
 
+    /// The range of bytes of the buffer that the view covers, relative to the start of the
+    /// buffer.
+    ///
+    /// The start of the range, added to the offset of the buffer within its underlying buffer
+    /// object, must be a multiple of the
+    /// [`min_texel_buffer_offset_alignment`](crate::device::Properties::min_texel_buffer_offset_alignment)
+    /// limit, or of the texel buffer alignment limits if the
+    /// [`texel_buffer_alignment`](crate::device::Features::texel_buffer_alignment) feature is
+    /// enabled. The length of the range must be a multiple of the block size of `format`, and
+    /// must not contain more texels than the
+    /// [`max_texel_buffer_elements`](crate::device::Properties::max_texel_buffer_elements) limit.
+    ///
+    /// The default value is `None`, which covers the whole buffer.
+    pub range: Option<Range<DeviceSize>>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -317,13 +371,14 @@ impl Default for BufferViewCreateInfo {
     fn default() -> Self {
         Self {
             format: None,
+            range: None,
             _ne: crate::NonExhaustive(()),
         }
     }
 }
 
 /// Error that can happen when creating a buffer view.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum BufferViewCreationError {
     /// Out of memory.
     OomError(OomError),
@@ -349,6 +404,12 @@ pub enum BufferViewCreationError {
         required_alignment: DeviceSize,
     },
 
+    /// The range extends past the end of the buffer.
+    RangeOutOfBounds {
+        range: Range<DeviceSize>,
+        buffer_size: DeviceSize,
+    },
+
     /// The requested format is not supported for this usage.
     UnsupportedFormat,
 
@@ -390,6 +451,11 @@ impl Display for BufferViewCreationError {
                 f,
                 "the range within the buffer is not a multiple of the required alignment",
             ),
+            Self::RangeOutOfBounds { range, buffer_size } => write!(
+                f,
+                "the range {:?} extends past the end of the buffer ({} bytes)",
+                range, buffer_size,
+            ),
             Self::UnsupportedFormat => {
                 write!(f, "the requested format is not supported for this usage")
             }
@@ -479,7 +545,7 @@ impl Hash for dyn BufferViewAbstract {
 mod tests {
     use crate::{
         buffer::{
-            view::{BufferView, BufferViewCreateInfo, BufferViewCreationError},
+            view::{BufferView, BufferViewAbstract, BufferViewCreateInfo, BufferViewCreationError},
             BufferUsage, DeviceLocalBuffer,
         },
         format::Format,
@@ -554,6 +620,49 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn create_range() {
+        // `VK_FORMAT_R32_UINT` guaranteed to be a supported format
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let usage = BufferUsage {
+            uniform_texel_buffer: true,
+            ..BufferUsage::empty()
+        };
+        let offset = device
+            .physical_device()
+            .properties()
+            .min_texel_buffer_offset_alignment;
+
+        let buffer =
+            DeviceLocalBuffer::<[u32]>::array(device, 1024, usage, [queue.queue_family_index()])
+                .unwrap();
+        let view = BufferView::new(
+            buffer.clone(),
+            BufferViewCreateInfo {
+                format: Some(Format::R32_UINT),
+                range: Some(offset..offset + 64),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(view.range(), offset..offset + 64);
+
+        match BufferView::new(
+            buffer,
+            BufferViewCreateInfo {
+                format: Some(Format::R32_UINT),
+                range: Some(0..8192),
+                ..Default::default()
+            },
+        ) {
+            Err(BufferViewCreationError::RangeOutOfBounds {
+                buffer_size: 4096, ..
+            }) => (),
+            _ => panic!(),
+        }
+    }
+
     #[test]
     fn wrong_usage() {
         // `VK_FORMAT_R8G8B8A8_UNORM` guaranteed to be a supported format
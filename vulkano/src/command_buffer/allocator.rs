@@ -111,12 +111,31 @@ pub struct StandardCommandBufferAllocator {
     device: Arc<Device>,
     /// Each queue family index points directly to its pool.
     pools: SmallVec<[UnsafeCell<Option<Arc<Pool>>>; 8]>,
+    protected: bool,
 }
 
 impl StandardCommandBufferAllocator {
     /// Creates a new `StandardCommandBufferAllocator`.
     #[inline]
     pub fn new(device: Arc<Device>) -> Self {
+        Self::new_impl(device, false)
+    }
+
+    /// Creates a new `StandardCommandBufferAllocator` that allocates protected command buffers.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the [`protected_memory`](crate::device::Features::protected_memory) feature is
+    ///   not enabled on the device.
+    #[inline]
+    pub fn new_protected(device: Arc<Device>) -> Self {
+        // VUID-VkCommandPoolCreateInfo-flags-02860
+        assert!(device.enabled_features().protected_memory);
+
+        Self::new_impl(device, true)
+    }
+
+    fn new_impl(device: Arc<Device>, protected: bool) -> Self {
         let pools = device
             .physical_device()
             .queue_family_properties()
@@ -124,7 +143,17 @@ impl StandardCommandBufferAllocator {
             .map(|_| UnsafeCell::new(None))
             .collect();
 
-        StandardCommandBufferAllocator { device, pools }
+        StandardCommandBufferAllocator {
+            device,
+            pools,
+            protected,
+        }
+    }
+
+    /// Returns whether the allocator allocates protected command buffers.
+    #[inline]
+    pub fn protected(&self) -> bool {
+        self.protected
     }
 }
 
@@ -153,7 +182,11 @@ unsafe impl CommandBufferAllocator for StandardCommandBufferAllocator {
 
         let pool = unsafe { &mut *self.pools[queue_family_index as usize].get() };
         if pool.is_none() {
-            *pool = Some(Pool::new(self.device.clone(), queue_family_index)?);
+            *pool = Some(Pool::new(
+                self.device.clone(),
+                queue_family_index,
+                self.protected,
+            )?);
         }
 
         pool.as_ref().unwrap().allocate(level, command_buffer_count)
@@ -178,12 +211,17 @@ struct Pool {
 }
 
 impl Pool {
-    fn new(device: Arc<Device>, queue_family_index: u32) -> Result<Arc<Self>, OomError> {
+    fn new(
+        device: Arc<Device>,
+        queue_family_index: u32,
+        protected: bool,
+    ) -> Result<Arc<Self>, OomError> {
         CommandPool::new(
             device,
             CommandPoolCreateInfo {
                 queue_family_index,
                 reset_command_buffer: true,
+                protected,
                 ..Default::default()
             },
         )
@@ -197,8 +235,10 @@ impl Pool {
         .map_err(|err| match err {
             CommandPoolCreationError::OomError(err) => err,
             // We check that the provided queue family index is active on the device, so it can't
-            // be out of range.
-            CommandPoolCreationError::QueueFamilyIndexOutOfRange { .. } => unreachable!(),
+            // be out of range, and that the `protected_memory` feature is enabled if the pool is
+            // protected.
+            CommandPoolCreationError::RequirementNotMet { .. }
+            | CommandPoolCreationError::QueueFamilyIndexOutOfRange { .. } => unreachable!(),
         })
     }
 
@@ -366,4 +406,28 @@ mod tests {
             .unwrap();
         assert_eq!(raw, cb2.inner().internal_object());
     }
+
+    #[test]
+    fn protected_feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        assert_should_panic!({
+            StandardCommandBufferAllocator::new_protected(device);
+        });
+    }
+
+    #[test]
+    fn protected_command_buffers() {
+        let (device, queue) = gfx_dev_and_queue!(protected_memory);
+
+        let allocator = StandardCommandBufferAllocator::new_protected(device);
+        assert!(allocator.protected());
+
+        let cb = allocator
+            .allocate(queue.queue_family_index(), CommandBufferLevel::Primary, 1)
+            .unwrap()
+            .next()
+            .unwrap();
+        assert!(cb.inner().protected());
+    }
 }
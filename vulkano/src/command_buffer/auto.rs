@@ -24,6 +24,7 @@ use crate::{
     device::{Device, DeviceOwned, Queue, QueueFamilyProperties},
     format::Format,
     image::{sys::UnsafeImage, ImageAccess, ImageLayout, ImageSubresourceRange},
+    pipeline::{graphics::rasterization::ProvokingVertexMode, Pipeline},
     query::{QueryControlFlags, QueryType},
     render_pass::{Framebuffer, Subpass},
     sync::{AccessCheckError, AccessFlags, GpuFuture, PipelineMemoryAccess, PipelineStages},
@@ -78,6 +79,9 @@ pub(super) struct RenderPassState {
     pub(super) render_area_extent: [u32; 2],
     pub(super) render_pass: RenderPassStateType,
     pub(super) view_mask: u32,
+    /// The provoking vertex mode of the graphics pipeline that was bound when the render pass
+    /// began, or else of the first graphics pipeline bound inside it.
+    pub(super) provoking_vertex_mode: Option<ProvokingVertexMode>,
 }
 
 pub(super) enum RenderPassStateType {
@@ -239,6 +243,7 @@ where
                             }
                             .into(),
                             view_mask: info.subpass.subpass_desc().view_mask,
+                            provoking_vertex_mode: None,
                         });
                     }
                     CommandBufferInheritanceRenderPassType::BeginRendering(info) => {
@@ -255,6 +260,7 @@ where
                             }
                             .into(),
                             view_mask: info.view_mask,
+                            provoking_vertex_mode: None,
                         });
                     }
                 }
@@ -615,7 +621,22 @@ where
             return Err(BuildError::QueryActive);
         }
 
-        let submit_state = match self.usage {
+        let inner = self.inner.build()?;
+
+        // If a secondary command buffer that was not recorded with simultaneous use is executed,
+        // the primary command buffer is no longer considered to have simultaneous use either.
+        let usage = if self.usage == CommandBufferUsage::SimultaneousUse
+            && inner
+                .secondary_command_buffers()
+                .iter()
+                .any(|cb| cb.inner().usage() != CommandBufferUsage::SimultaneousUse)
+        {
+            CommandBufferUsage::MultipleSubmit
+        } else {
+            self.usage
+        };
+
+        let submit_state = match usage {
             CommandBufferUsage::MultipleSubmit => SubmitState::ExclusiveUse {
                 in_use: AtomicBool::new(false),
             },
@@ -626,7 +647,7 @@ where
         };
 
         Ok(PrimaryAutoCommandBuffer {
-            inner: self.inner.build()?,
+            inner,
             _alloc: self.builder_alloc.into_alloc(),
             submit_state,
         })
@@ -713,6 +734,25 @@ where
     pub fn state(&self) -> CommandBufferState<'_> {
         self.inner.state()
     }
+
+    /// Sets whether the pipeline barriers that are automatically inserted between commands are
+    /// optimized before they are recorded.
+    ///
+    /// When enabled, the individual buffer and image barriers of each pipeline barrier are
+    /// combined as described in [`DependencyInfo::optimize`]. Pipeline barriers that would be
+    /// recorded directly after each other are merged into one, and access types and stages that
+    /// have no effect are removed from the barriers. This reduces the work the device has to do
+    /// for command buffers that use many resources, at the cost of some precision in the
+    /// dependencies. The setting applies to the barriers that are recorded after the call.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`DependencyInfo::optimize`]: crate::sync::DependencyInfo::optimize
+    #[inline]
+    pub fn optimize_barriers(&mut self, enabled: bool) -> &mut Self {
+        self.inner.set_optimize_barriers(enabled);
+        self
+    }
 }
 
 unsafe impl<L, A> DeviceOwned for AutoCommandBufferBuilder<L, A>
@@ -738,6 +778,75 @@ unsafe impl<P> DeviceOwned for PrimaryAutoCommandBuffer<P> {
     }
 }
 
+/// # Introspection
+///
+/// These methods describe what the command buffer ended up containing after it was built. They
+/// are meant for debugging and profiling tools, and have no influence on how the command buffer
+/// is executed.
+impl<A> PrimaryAutoCommandBuffer<A> {
+    /// Returns the buffers that are used by the command buffer, along with the range and the kind
+    /// of access of each use.
+    ///
+    /// A buffer that is used by several commands appears once for each use.
+    #[inline]
+    pub fn buffers(
+        &self,
+    ) -> impl ExactSizeIterator<
+        Item = (
+            &Arc<dyn BufferAccess>,
+            Range<DeviceSize>,
+            PipelineMemoryAccess,
+        ),
+    > {
+        (0..self.inner.num_buffers()).map(|index| self.inner.buffer(index).unwrap())
+    }
+
+    /// Returns the images that are used by the command buffer, along with the subresource range,
+    /// the kind of access and the layout that the image is expected to be in before and after
+    /// each use.
+    ///
+    /// An image that is used by several commands appears once for each use.
+    #[inline]
+    pub fn images(
+        &self,
+    ) -> impl ExactSizeIterator<
+        Item = (
+            &Arc<dyn ImageAccess>,
+            &ImageSubresourceRange,
+            PipelineMemoryAccess,
+            ImageLayout,
+            ImageLayout,
+        ),
+    > {
+        (0..self.inner.num_images()).map(|index| self.inner.image(index).unwrap())
+    }
+
+    /// Returns the number of pipeline barriers that were automatically inserted to synchronize
+    /// the commands.
+    ///
+    /// Barriers that could be merged together count as one. The barrier that transitions images
+    /// to their final layout at the end of the command buffer is included.
+    #[inline]
+    pub fn num_barriers(&self) -> usize {
+        self.inner.num_barriers()
+    }
+
+    /// Returns the pipelines that were bound, in the order they were bound.
+    ///
+    /// A pipeline that was bound several times appears once for each time. Pipelines that were
+    /// bound inside executed secondary command buffers are not included.
+    #[inline]
+    pub fn bound_pipelines(&self) -> &[Arc<dyn Pipeline + Send + Sync>] {
+        self.inner.bound_pipelines()
+    }
+
+    /// Returns the secondary command buffers that were executed, in the order they were executed.
+    #[inline]
+    pub fn secondary_command_buffers(&self) -> &[Arc<dyn SecondaryCommandBuffer>] {
+        self.inner.secondary_command_buffers()
+    }
+}
+
 unsafe impl<A> PrimaryCommandBuffer for PrimaryAutoCommandBuffer<A>
 where
     A: CommandBufferAlloc,
@@ -933,7 +1042,7 @@ where
 #[derive(Debug)]
 enum SubmitState {
     // The command buffer was created with the "SimultaneousUse" flag. Can always be submitted at
-    // any time.
+    // any time, including while previous submissions are still pending.
     Concurrent,
 
     // The command buffer can only be submitted once simultaneously.
@@ -956,10 +1065,14 @@ mod tests {
     use crate::{
         buffer::{BufferUsage, CpuAccessibleBuffer},
         command_buffer::{
-            synced::SyncCommandBufferBuilderError, BufferCopy, CopyBufferInfoTyped, CopyError,
-            ExecuteCommandsError,
+            synced::SyncCommandBufferBuilderError, BufferCopy, ClearColorImageInfo,
+            CopyBufferInfoTyped, CopyError, CopyErrorResource, CopyImageInfo, ExecuteCommandsError,
+            RenderPassError, RenderingAttachmentInfo, RenderingInfo,
         },
         device::{DeviceCreateInfo, QueueCreateInfo},
+        format::{ClearValue, Format},
+        image::{view::ImageView, AttachmentImage, ImageDimensions, StorageImage},
+        render_pass::LoadOp,
     };
 
     #[test]
@@ -1041,6 +1154,55 @@ mod tests {
         assert_eq!(*result, [0_u32, 1, 2, 3, 4]);
     }
 
+    #[test]
+    fn execute_batch() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer_usage = BufferUsage {
+            transfer_src: true,
+            transfer_dst: true,
+            ..BufferUsage::empty()
+        };
+        let source =
+            CpuAccessibleBuffer::from_iter(device.clone(), buffer_usage, true, [1_u32, 2]).unwrap();
+        let intermediate =
+            CpuAccessibleBuffer::from_iter(device.clone(), buffer_usage, true, [0_u32, 0]).unwrap();
+        let destination =
+            CpuAccessibleBuffer::from_iter(device.clone(), buffer_usage, true, [0_u32, 0]).unwrap();
+
+        let allocator = StandardCommandBufferAllocator::new(device.clone());
+        let copy = |src: Arc<CpuAccessibleBuffer<[u32]>>, dst: Arc<CpuAccessibleBuffer<[u32]>>| {
+            let mut cbb = AutoCommandBufferBuilder::primary(
+                &allocator,
+                queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .unwrap();
+            cbb.copy_buffer(CopyBufferInfoTyped::buffers(src, dst))
+                .unwrap();
+            Arc::new(cbb.build().unwrap()) as Arc<dyn PrimaryCommandBuffer>
+        };
+
+        let future = crate::sync::now(device)
+            .then_execute_batch(
+                queue.clone(),
+                [
+                    copy(source, intermediate.clone()),
+                    copy(intermediate, destination.clone()),
+                ],
+            )
+            .unwrap();
+        assert_eq!(future.command_buffers().len(), 2);
+
+        future
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        assert_eq!(*destination.read().unwrap(), [1_u32, 2]);
+    }
+
     #[test]
     fn secondary_nonconcurrent_conflict() {
         let (device, queue) = gfx_dev_and_queue!();
@@ -1115,6 +1277,162 @@ mod tests {
         }
     }
 
+    #[test]
+    fn primary_simultaneous_use_secondary_usage() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let allocator = StandardCommandBufferAllocator::new(device.clone());
+        let future = crate::sync::now(device);
+
+        let primary_executing = |secondary_usage| {
+            let secondary = Arc::new(
+                AutoCommandBufferBuilder::secondary(
+                    &allocator,
+                    queue.queue_family_index(),
+                    secondary_usage,
+                    Default::default(),
+                )
+                .unwrap()
+                .build()
+                .unwrap(),
+            );
+
+            let mut builder = AutoCommandBufferBuilder::primary(
+                &allocator,
+                queue.queue_family_index(),
+                CommandBufferUsage::SimultaneousUse,
+            )
+            .unwrap();
+            builder.execute_commands(secondary).unwrap();
+            builder.build().unwrap()
+        };
+
+        // Executing a secondary command buffer with simultaneous use keeps it for the primary
+        // command buffer, so it can be submitted again while still pending.
+        let cb = primary_executing(CommandBufferUsage::SimultaneousUse);
+        cb.lock_submit(&future, &queue).unwrap();
+        cb.lock_submit(&future, &queue).unwrap();
+
+        unsafe {
+            cb.unlock();
+            cb.unlock();
+        }
+
+        // Executing a secondary command buffer without simultaneous use takes it away from the
+        // primary command buffer.
+        let cb = primary_executing(CommandBufferUsage::MultipleSubmit);
+        cb.lock_submit(&future, &queue).unwrap();
+        assert!(matches!(
+            cb.lock_submit(&future, &queue),
+            Err(CommandBufferExecError::ExclusiveAlreadyInUse)
+        ));
+
+        unsafe {
+            cb.unlock();
+        }
+    }
+
+    #[test]
+    fn introspection() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let usage = BufferUsage {
+            transfer_src: true,
+            transfer_dst: true,
+            ..BufferUsage::empty()
+        };
+        let a = CpuAccessibleBuffer::from_iter(device.clone(), usage, true, [0_u32; 4]).unwrap();
+        let b = CpuAccessibleBuffer::from_iter(device.clone(), usage, true, [0_u32; 4]).unwrap();
+
+        let allocator = StandardCommandBufferAllocator::new(device);
+        let secondary = AutoCommandBufferBuilder::secondary(
+            &allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::MultipleSubmit,
+            Default::default(),
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        // The second copy reads what the first one wrote, so a barrier is needed in between.
+        builder
+            .copy_buffer(CopyBufferInfoTyped::buffers(a.clone(), b.clone()))
+            .unwrap()
+            .copy_buffer(CopyBufferInfoTyped::buffers(b, a))
+            .unwrap()
+            .execute_commands(secondary)
+            .unwrap();
+
+        let cb = builder.build().unwrap();
+
+        assert_eq!(cb.buffers().len(), 4);
+        assert_eq!(
+            cb.buffers()
+                .filter(|(_, _, memory)| memory.exclusive)
+                .count(),
+            2
+        );
+        assert_eq!(cb.images().len(), 0);
+        assert_eq!(cb.num_barriers(), 1);
+        assert!(cb.bound_pipelines().is_empty());
+        assert_eq!(cb.secondary_command_buffers().len(), 1);
+    }
+
+    #[test]
+    fn optimize_barriers_merges_adjacent() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let image = || {
+            StorageImage::new(
+                device.clone(),
+                ImageDimensions::Dim2d {
+                    width: 4,
+                    height: 4,
+                    array_layers: 1,
+                },
+                Format::R8G8B8A8_UNORM,
+                [queue.queue_family_index()],
+            )
+            .unwrap()
+        };
+        let (a, b) = (image(), image());
+
+        let allocator = StandardCommandBufferAllocator::new(device.clone());
+        let num_barriers = |optimize: bool| {
+            let mut builder = AutoCommandBufferBuilder::primary(
+                &allocator,
+                queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .unwrap();
+
+            // The last copy changes the layout of both images. The second transition has no
+            // command before it, so without optimization it is recorded as a separate barrier.
+            builder
+                .optimize_barriers(optimize)
+                .clear_color_image(ClearColorImageInfo::image(a.clone()))
+                .unwrap()
+                .clear_color_image(ClearColorImageInfo::image(b.clone()))
+                .unwrap()
+                .copy_image(CopyImageInfo::images(a.clone(), b.clone()))
+                .unwrap()
+                .copy_image(CopyImageInfo::images(b.clone(), a.clone()))
+                .unwrap();
+
+            builder.build().unwrap().num_barriers()
+        };
+
+        assert_eq!(num_barriers(true), num_barriers(false) - 1);
+    }
+
     #[test]
     fn buffer_self_copy_overlapping() {
         let (device, queue) = gfx_dev_and_queue!();
@@ -1207,4 +1525,231 @@ mod tests {
             })
         ));
     }
+
+    #[test]
+    fn buffer_self_copy_overlapping_other_region() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let source = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage {
+                transfer_src: true,
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            true,
+            0_u32..8,
+        )
+        .unwrap();
+
+        let allocator = StandardCommandBufferAllocator::new(device);
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        // The source range of region 0 only overlaps with the destination range of region 1.
+        assert!(matches!(
+            builder.copy_buffer(CopyBufferInfoTyped {
+                regions: [
+                    BufferCopy {
+                        src_offset: 3,
+                        dst_offset: 7,
+                        size: 1,
+                        ..Default::default()
+                    },
+                    BufferCopy {
+                        src_offset: 4,
+                        dst_offset: 1,
+                        size: 3,
+                        ..Default::default()
+                    },
+                ]
+                .into(),
+                ..CopyBufferInfoTyped::buffers(source.clone(), source.clone())
+            }),
+            Err(CopyError::OverlappingRegions {
+                src_region_index: 0,
+                dst_region_index: 1,
+            })
+        ));
+
+        assert!(matches!(
+            builder.copy_buffer(CopyBufferInfoTyped {
+                regions: [BufferCopy {
+                    src_offset: 0,
+                    dst_offset: 4,
+                    size: 0,
+                    ..Default::default()
+                }]
+                .into(),
+                ..CopyBufferInfoTyped::buffers(source.clone(), source.clone())
+            }),
+            Err(CopyError::RegionSizeZero { region_index: 0 })
+        ));
+
+        assert!(matches!(
+            builder.copy_buffer(CopyBufferInfoTyped {
+                regions: [BufferCopy {
+                    src_offset: 0,
+                    dst_offset: 4,
+                    size: DeviceSize::MAX,
+                    ..Default::default()
+                }]
+                .into(),
+                ..CopyBufferInfoTyped::buffers(source.clone(), source)
+            }),
+            Err(CopyError::RegionOutOfBufferBounds {
+                resource: CopyErrorResource::Source,
+                region_index: 0,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn begin_rendering_clear_values() {
+        let (device, queue) = gfx_dev_and_queue!(dynamic_rendering);
+
+        let image_view = ImageView::new_default(
+            AttachmentImage::new(device.clone(), [4, 4], Format::R8G8B8A8_UNORM).unwrap(),
+        )
+        .unwrap();
+
+        let allocator = StandardCommandBufferAllocator::new(device);
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        let rendering_info = |clear_value| RenderingInfo {
+            color_attachments: vec![Some(RenderingAttachmentInfo {
+                load_op: LoadOp::Clear,
+                clear_value,
+                ..RenderingAttachmentInfo::image_view(image_view.clone())
+            })],
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            builder.begin_rendering(rendering_info(None)),
+            Err(RenderPassError::ClearValueMissing {
+                attachment_index: 0
+            })
+        ));
+        assert!(matches!(
+            builder.begin_rendering(rendering_info(Some(ClearValue::Uint([0; 4])))),
+            Err(RenderPassError::ClearValueNotCompatible {
+                attachment_index: 0,
+                ..
+            })
+        ));
+
+        builder
+            .begin_rendering(rendering_info(Some(ClearValue::Float([0.0; 4]))))
+            .unwrap();
+    }
+
+    #[test]
+    fn bind_index_buffer_u8_without_feature() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let index_buffer = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage {
+                index_buffer: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            [0_u8, 1, 2].iter().copied(),
+        )
+        .unwrap();
+
+        let allocator = StandardCommandBufferAllocator::new(device);
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert_should_panic!({
+            builder.bind_index_buffer(index_buffer);
+        });
+    }
+
+    #[test]
+    fn bind_null_vertex_buffers_without_feature() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let allocator = StandardCommandBufferAllocator::new(device);
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert_should_panic!({
+            builder.bind_null_vertex_buffers(0, 1);
+        });
+    }
+
+    #[test]
+    fn write_buffer() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let destination = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage {
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            true,
+            (0..20000).map(|_| 0_u32),
+        )
+        .unwrap();
+
+        let allocator = StandardCommandBufferAllocator::new(device);
+        let mut builder = AutoCommandBufferBuilder::primary(
+            &allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        // Larger than the limit of `update_buffer`, so it goes through a staging buffer.
+        let data: Vec<u32> = (0..20000).collect();
+        builder
+            .write_buffer(data.as_slice(), destination.clone(), 0)
+            .unwrap();
+
+        // A single repeated value, so it's recorded as a fill.
+        builder
+            .write_buffer(&[7_u32; 4][..], destination.clone(), 16)
+            .unwrap();
+
+        // Small enough for `update_buffer`.
+        builder
+            .write_buffer(&[8_u32, 9][..], destination.clone(), 32)
+            .unwrap();
+
+        let cb = builder.build().unwrap();
+
+        let future = cb
+            .execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+        future.wait(None).unwrap();
+
+        let result = destination.read().unwrap();
+
+        assert_eq!(result[..12], [0, 1, 2, 3, 7, 7, 7, 7, 8, 9, 10, 11]);
+        assert_eq!(result[19999], 19999);
+    }
 }
@@ -17,21 +17,23 @@ use crate::{
         AutoCommandBufferBuilder,
     },
     descriptor_set::{
-        check_descriptor_write, sys::UnsafeDescriptorSet, DescriptorSetResources,
+        check_descriptor_write, layout::DescriptorType, sys::UnsafeDescriptorSet,
+        DescriptorBindingResources, DescriptorSet, DescriptorSetResources,
         DescriptorSetUpdateError, DescriptorSetWithOffsets, DescriptorSetsCollection,
-        DescriptorWriteInfo, WriteDescriptorSet,
+        DynamicOffset, DynamicOffsets, WriteDescriptorSet,
     },
     device::DeviceOwned,
     pipeline::{
         graphics::{
             input_assembly::{Index, IndexType},
+            rasterization::ProvokingVertexMode,
             render_pass::PipelineRenderPassType,
             vertex_input::VertexBuffersCollection,
         },
         ComputePipeline, GraphicsPipeline, PipelineBindPoint, PipelineLayout,
     },
     shader::ShaderStages,
-    DeviceSize, RequirementNotMet, RequiresOneOf, VulkanObject,
+    DeviceSize, RequirementNotMet, RequiresOneOf, Version, VulkanObject,
 };
 use parking_lot::Mutex;
 use smallvec::SmallVec;
@@ -78,14 +80,67 @@ where
         .unwrap();
 
         unsafe {
-            let mut sets_binder = self.inner.bind_descriptor_sets();
-            for set in descriptor_sets.into_iter() {
-                sets_binder.add(set);
-            }
-            sets_binder.submit(pipeline_bind_point, pipeline_layout, first_set);
+            self.bind_descriptor_sets_unchecked(
+                pipeline_bind_point,
+                pipeline_layout,
+                first_set,
+                descriptor_sets,
+            )
         }
+    }
 
-        self
+    /// Binds descriptor sets for future dispatch or draw calls, taking the dynamic offsets of
+    /// their dynamic uniform and storage buffer descriptors from `dynamic_offsets`.
+    ///
+    /// The set numbers in `dynamic_offsets` are the slots that the sets are bound to, starting
+    /// at `first_set`. Every dynamic descriptor of `descriptor_sets` must have an offset.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the queue family of the command buffer does not support `pipeline_bind_point`.
+    /// - Panics if the highest descriptor set slot being bound is not less than the number of sets
+    ///   in `pipeline_layout`.
+    /// - Panics if `self` and any element of `descriptor_sets` do not belong to the same device.
+    /// - Panics if a dynamic descriptor has no offset in `dynamic_offsets`, or if
+    ///   `dynamic_offsets` contains an offset for a descriptor that is not being bound or is not
+    ///   dynamic.
+    /// - Panics if an offset that was given as a buffer is not within the buffer that was written
+    ///   to the descriptor.
+    /// - Panics if an offset is not a multiple of the
+    ///   [`min_uniform_buffer_offset_alignment`](crate::device::Properties::min_uniform_buffer_offset_alignment)
+    ///   or
+    ///   [`min_storage_buffer_offset_alignment`](crate::device::Properties::min_storage_buffer_offset_alignment)
+    ///   limit, or if it makes the descriptor extend past the end of its buffer.
+    pub fn bind_descriptor_sets_with_offsets(
+        &mut self,
+        pipeline_bind_point: PipelineBindPoint,
+        pipeline_layout: Arc<PipelineLayout>,
+        first_set: u32,
+        descriptor_sets: impl IntoIterator<Item = Arc<dyn DescriptorSet>>,
+        dynamic_offsets: &DynamicOffsets,
+    ) -> &mut Self {
+        let descriptor_sets = resolve_dynamic_offsets(
+            first_set,
+            descriptor_sets.into_iter().collect(),
+            dynamic_offsets,
+        )
+        .unwrap();
+        self.validate_bind_descriptor_sets(
+            pipeline_bind_point,
+            &pipeline_layout,
+            first_set,
+            &descriptor_sets,
+        )
+        .unwrap();
+
+        unsafe {
+            self.bind_descriptor_sets_unchecked(
+                pipeline_bind_point,
+                pipeline_layout,
+                first_set,
+                descriptor_sets,
+            )
+        }
     }
 
     fn validate_bind_descriptor_sets(
@@ -136,16 +191,107 @@ where
                 return Err(BindPushError::DescriptorSetNotCompatible { set_num });
             }
 
+            let (descriptor_set, dynamic_offsets) = set.as_ref();
+            let properties = self.device().physical_device().properties();
+
+            // VUID-vkCmdBindDescriptorSets-dynamicOffsetCount-00359
+            let required_count: u32 = descriptor_set
+                .layout()
+                .bindings()
+                .values()
+                .filter(|binding| {
+                    matches!(
+                        binding.descriptor_type,
+                        DescriptorType::UniformBufferDynamic | DescriptorType::StorageBufferDynamic
+                    )
+                })
+                .map(|binding| binding.descriptor_count)
+                .sum();
+
+            if dynamic_offsets.len() as u32 != required_count {
+                return Err(BindPushError::DynamicOffsetCountMismatch {
+                    set_num,
+                    required: required_count,
+                    provided: dynamic_offsets.len() as u32,
+                });
+            }
+
+            let mut dynamic_offsets = dynamic_offsets.iter().copied();
+
+            for (&binding_num, binding) in descriptor_set.layout().bindings() {
+                let required_alignment = match binding.descriptor_type {
+                    DescriptorType::UniformBufferDynamic => {
+                        properties.min_uniform_buffer_offset_alignment
+                    }
+                    DescriptorType::StorageBufferDynamic => {
+                        properties.min_storage_buffer_offset_alignment
+                    }
+                    _ => continue,
+                };
+
+                let elements = match descriptor_set.resources().binding(binding_num) {
+                    Some(DescriptorBindingResources::Buffer(elements)) => elements,
+                    _ => unreachable!(),
+                };
+
+                for (index, element) in elements.iter().enumerate() {
+                    let offset = dynamic_offsets.next().unwrap() as DeviceSize;
+
+                    // VUID-vkCmdBindDescriptorSets-pDynamicOffsets-01971
+                    // VUID-vkCmdBindDescriptorSets-pDynamicOffsets-01972
+                    if offset % required_alignment != 0 {
+                        return Err(BindPushError::DynamicOffsetNotAligned {
+                            set_num,
+                            binding: binding_num,
+                            index: index as u32,
+                            offset,
+                            required_alignment,
+                        });
+                    }
+
+                    // VUID-vkCmdBindDescriptorSets-pDescriptorSets-01979
+                    if let Some(buffer) = element {
+                        let inner = buffer.inner();
+
+                        if inner.offset + offset + buffer.size() > inner.buffer.size() {
+                            return Err(BindPushError::DynamicOffsetOutOfRange {
+                                set_num,
+                                binding: binding_num,
+                                index: index as u32,
+                                offset,
+                            });
+                        }
+                    }
+                }
+            }
+
             // TODO: see https://github.com/vulkano-rs/vulkano/issues/1643
-            // VUID-vkCmdBindDescriptorSets-pDynamicOffsets-01971
-            // VUID-vkCmdBindDescriptorSets-pDynamicOffsets-01972
-            // VUID-vkCmdBindDescriptorSets-pDescriptorSets-01979
             // VUID-vkCmdBindDescriptorSets-pDescriptorSets-06715
         }
 
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_descriptor_sets_unchecked<S>(
+        &mut self,
+        pipeline_bind_point: PipelineBindPoint,
+        pipeline_layout: Arc<PipelineLayout>,
+        first_set: u32,
+        descriptor_sets: S,
+    ) -> &mut Self
+    where
+        S: DescriptorSetsCollection,
+    {
+        let mut sets_binder = self.inner.bind_descriptor_sets();
+        for set in descriptor_sets.into_vec() {
+            sets_binder.add(set);
+        }
+        sets_binder.submit(pipeline_bind_point, pipeline_layout, first_set);
+
+        self
+    }
+
     /// Binds an index buffer for future indexed draw calls.
     ///
     /// # Panics
@@ -157,6 +303,8 @@ where
     /// - If the index buffer contains `u8` indices, panics if the
     ///   [`index_type_uint8`](crate::device::Features::index_type_uint8) feature is not
     ///   enabled on the device.
+    /// - Panics if the offset of `index_buffer` within its underlying buffer is not a multiple of
+    ///   the size of the index type.
     pub fn bind_index_buffer<Ib, I>(&mut self, index_buffer: Arc<Ib>) -> &mut Self
     where
         Ib: TypedBufferAccess<Content = [I]> + 'static,
@@ -165,11 +313,7 @@ where
         self.validate_bind_index_buffer(&index_buffer, I::ty())
             .unwrap();
 
-        unsafe {
-            self.inner.bind_index_buffer(index_buffer, I::ty());
-        }
-
-        self
+        unsafe { self.bind_index_buffer_unchecked(index_buffer) }
     }
 
     fn validate_bind_index_buffer(
@@ -203,12 +347,28 @@ where
             });
         }
 
-        // TODO:
         // VUID-vkCmdBindIndexBuffer-offset-00432
+        if index_buffer.inner().offset % index_type.size() != 0 {
+            return Err(BindPushError::IndexBufferOffsetNotAligned {
+                offset: index_buffer.inner().offset,
+                required_alignment: index_type.size(),
+            });
+        }
 
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_index_buffer_unchecked<Ib, I>(&mut self, index_buffer: Arc<Ib>) -> &mut Self
+    where
+        Ib: TypedBufferAccess<Content = [I]> + 'static,
+        I: Index + 'static,
+    {
+        self.inner.bind_index_buffer(index_buffer, I::ty());
+
+        self
+    }
+
     /// Binds a compute pipeline for future dispatch calls.
     ///
     /// # Panics
@@ -218,11 +378,7 @@ where
     pub fn bind_pipeline_compute(&mut self, pipeline: Arc<ComputePipeline>) -> &mut Self {
         self.validate_bind_pipeline_compute(&pipeline).unwrap();
 
-        unsafe {
-            self.inner.bind_pipeline_compute(pipeline);
-        }
-
-        self
+        unsafe { self.bind_pipeline_compute_unchecked(pipeline) }
     }
 
     fn validate_bind_pipeline_compute(
@@ -242,6 +398,16 @@ where
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_pipeline_compute_unchecked(
+        &mut self,
+        pipeline: Arc<ComputePipeline>,
+    ) -> &mut Self {
+        self.inner.bind_pipeline_compute(pipeline);
+
+        self
+    }
+
     /// Binds a graphics pipeline for future draw calls.
     ///
     /// # Panics
@@ -251,11 +417,7 @@ where
     pub fn bind_pipeline_graphics(&mut self, pipeline: Arc<GraphicsPipeline>) -> &mut Self {
         self.validate_bind_pipeline_graphics(&pipeline).unwrap();
 
-        unsafe {
-            self.inner.bind_pipeline_graphics(pipeline);
-        }
-
-        self
+        unsafe { self.bind_pipeline_graphics_unchecked(pipeline) }
     }
 
     fn validate_bind_pipeline_graphics(
@@ -311,11 +473,47 @@ where
             }
         }
 
+        // VUID-vkCmdBindPipeline-pipelineBindPoint-04881
+        if let Some(provoking_vertex_mode) = self
+            .render_pass_state
+            .as_ref()
+            .and_then(|render_pass_state| render_pass_state.provoking_vertex_mode)
+        {
+            let properties = self.device().physical_device().properties();
+
+            if !properties
+                .provoking_vertex_mode_per_pipeline
+                .unwrap_or(false)
+                && pipeline.rasterization_state().provoking_vertex_mode != provoking_vertex_mode
+            {
+                return Err(BindPushError::PreviousPipelineProvokingVertexModeMismatch {
+                    provoking_vertex_mode: pipeline.rasterization_state().provoking_vertex_mode,
+                    previous_provoking_vertex_mode: provoking_vertex_mode,
+                });
+            }
+        }
+
         // VUID-vkCmdBindPipeline-pipeline-00781
 
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_pipeline_graphics_unchecked(
+        &mut self,
+        pipeline: Arc<GraphicsPipeline>,
+    ) -> &mut Self {
+        if let Some(render_pass_state) = self.render_pass_state.as_mut() {
+            render_pass_state
+                .provoking_vertex_mode
+                .get_or_insert(pipeline.rasterization_state().provoking_vertex_mode);
+        }
+
+        self.inner.bind_pipeline_graphics(pipeline);
+
+        self
+    }
+
     /// Binds vertex buffers for future draw calls.
     ///
     /// # Panics
@@ -323,7 +521,7 @@ where
     /// - Panics if the queue family of the command buffer does not support graphics operations.
     /// - Panics if the highest vertex buffer binding being bound is greater than the
     ///   [`max_vertex_input_bindings`](crate::device::Properties::max_vertex_input_bindings)
-    //    device property.
+    ///   device property.
     /// - Panics if `self` and any element of `vertex_buffers` do not belong to the same device.
     /// - Panics if any element of `vertex_buffers` does not have the
     ///   [`vertex_buffer`](crate::buffer::BufferUsage::vertex_buffer) usage enabled.
@@ -336,15 +534,7 @@ where
         self.validate_bind_vertex_buffers(first_binding, &vertex_buffers)
             .unwrap();
 
-        unsafe {
-            let mut binder = self.inner.bind_vertex_buffers();
-            for vb in vertex_buffers.into_iter() {
-                binder.add(vb);
-            }
-            binder.submit(first_binding);
-        }
-
-        self
+        unsafe { self.bind_vertex_buffers_unchecked(first_binding, vertex_buffers) }
     }
 
     fn validate_bind_vertex_buffers(
@@ -391,6 +581,191 @@ where
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn bind_vertex_buffers_unchecked(
+        &mut self,
+        first_binding: u32,
+        vertex_buffers: impl VertexBuffersCollection,
+    ) -> &mut Self {
+        let mut binder = self.inner.bind_vertex_buffers();
+        for vb in vertex_buffers.into_vec() {
+            binder.add(vb);
+        }
+        binder.submit(first_binding);
+
+        self
+    }
+
+    /// Binds null vertex buffers to `count` bindings starting at `first_binding`, for future draw
+    /// calls.
+    ///
+    /// Vertex attributes that read from a null vertex buffer get the value zero for all their
+    /// components, except for a fourth component which gets the value one.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the queue family of the command buffer does not support graphics operations.
+    /// - Panics if the [`null_descriptor`](crate::device::Features::null_descriptor) feature is
+    ///   not enabled on the device.
+    /// - Panics if `count` is 0.
+    /// - Panics if the highest vertex buffer binding being bound is greater than the
+    ///   [`max_vertex_input_bindings`](crate::device::Properties::max_vertex_input_bindings)
+    ///   device property.
+    pub fn bind_null_vertex_buffers(&mut self, first_binding: u32, count: u32) -> &mut Self {
+        self.validate_bind_null_vertex_buffers(first_binding, count)
+            .unwrap();
+
+        unsafe {
+            let mut binder = self.inner.bind_vertex_buffers();
+            for _ in 0..count {
+                binder.add_null();
+            }
+            binder.submit(first_binding);
+        }
+
+        self
+    }
+
+    fn validate_bind_null_vertex_buffers(
+        &self,
+        first_binding: u32,
+        count: u32,
+    ) -> Result<(), BindPushError> {
+        self.validate_bind_vertex_buffers(first_binding, &[])?;
+
+        // VUID-vkCmdBindVertexBuffers-pBuffers-04001
+        if !self.device().enabled_features().null_descriptor {
+            return Err(BindPushError::RequirementNotMet {
+                required_for: "`bind_null_vertex_buffers`",
+                requires_one_of: RequiresOneOf {
+                    features: &["null_descriptor"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdBindVertexBuffers-bindingCount-arraylength
+        assert!(count != 0);
+
+        // VUID-vkCmdBindVertexBuffers-firstBinding-00624
+        // VUID-vkCmdBindVertexBuffers-firstBinding-00625
+        let max_bindings = self
+            .device()
+            .physical_device()
+            .properties()
+            .max_vertex_input_bindings;
+
+        match first_binding.checked_add(count) {
+            Some(binding_count) if binding_count <= max_bindings => (),
+            binding_count => {
+                return Err(BindPushError::MaxVertexInputBindingsExceeded {
+                    _binding_count: binding_count.unwrap_or(u32::MAX),
+                    _max: max_bindings,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Binds vertex buffers for future draw calls, and sets the stride of each binding.
+    ///
+    /// The bound graphics pipeline must have the
+    /// [`VertexInputBindingStride`](crate::pipeline::DynamicState::VertexInputBindingStride)
+    /// dynamic state enabled, in which case the `stride` values of the pipeline's vertex input
+    /// state are ignored. The offset and size that each binding reads from are the range of the
+    /// corresponding element of `vertex_buffers`, so a slice of a larger buffer can be bound
+    /// directly.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the queue family of the command buffer does not support graphics operations.
+    /// - Panics if the device API version is less than 1.3 and the
+    ///   [`extended_dynamic_state`](crate::device::Features::extended_dynamic_state) feature is
+    ///   not enabled on the device.
+    /// - Panics if the highest vertex buffer binding being bound is greater than the
+    ///   [`max_vertex_input_bindings`](crate::device::Properties::max_vertex_input_bindings)
+    ///   device property.
+    /// - Panics if `self` and any element of `vertex_buffers` do not belong to the same device.
+    /// - Panics if any element of `vertex_buffers` does not have the
+    ///   [`vertex_buffer`](crate::buffer::BufferUsage::vertex_buffer) usage enabled.
+    /// - Panics if the number of elements in `strides` is not equal to the number of elements in
+    ///   `vertex_buffers`.
+    /// - Panics if an element of `strides` is greater than the
+    ///   [`max_vertex_input_binding_stride`](crate::device::Properties::max_vertex_input_binding_stride)
+    ///   device property.
+    pub fn bind_vertex_buffers_with_strides(
+        &mut self,
+        first_binding: u32,
+        vertex_buffers: impl VertexBuffersCollection,
+        strides: impl IntoIterator<Item = DeviceSize>,
+    ) -> &mut Self {
+        let vertex_buffers = vertex_buffers.into_vec();
+        let strides: SmallVec<[_; 4]> = strides.into_iter().collect();
+        self.validate_bind_vertex_buffers_with_strides(first_binding, &vertex_buffers, &strides)
+            .unwrap();
+
+        unsafe {
+            let mut binder = self.inner.bind_vertex_buffers();
+            for (vb, stride) in vertex_buffers.into_iter().zip(strides) {
+                binder.add_with_stride(vb, stride);
+            }
+            binder.submit(first_binding);
+        }
+
+        self
+    }
+
+    fn validate_bind_vertex_buffers_with_strides(
+        &self,
+        first_binding: u32,
+        vertex_buffers: &[Arc<dyn BufferAccess>],
+        strides: &[DeviceSize],
+    ) -> Result<(), BindPushError> {
+        self.validate_bind_vertex_buffers(first_binding, vertex_buffers)?;
+
+        // VUID?
+        if !(self.device().api_version() >= Version::V1_3
+            || self.device().enabled_features().extended_dynamic_state)
+        {
+            return Err(BindPushError::RequirementNotMet {
+                required_for: "`bind_vertex_buffers_with_strides`",
+                requires_one_of: RequiresOneOf {
+                    api_version: Some(Version::V1_3),
+                    features: &["extended_dynamic_state"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID?
+        if strides.len() != vertex_buffers.len() {
+            return Err(BindPushError::VertexBufferStrideCountMismatch {
+                buffer_count: vertex_buffers.len() as u32,
+                stride_count: strides.len() as u32,
+            });
+        }
+
+        let max_stride = self
+            .device()
+            .physical_device()
+            .properties()
+            .max_vertex_input_binding_stride;
+
+        for (index, &stride) in strides.iter().enumerate() {
+            // VUID-vkCmdBindVertexBuffers2-pStrides-06209
+            if stride > max_stride as DeviceSize {
+                return Err(BindPushError::MaxVertexInputBindingStrideExceeded {
+                    binding: first_binding + index as u32,
+                    stride,
+                    max: max_stride,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Sets push constants for future dispatch or draw calls.
     ///
     /// # Panics
@@ -663,6 +1038,7 @@ impl SyncCommandBufferBuilder {
         }
 
         self.current_state.pipeline_compute = Some(pipeline.clone());
+        self.bound_pipelines.push(pipeline.clone());
         self.commands.push(Box::new(Cmd { pipeline }));
     }
 
@@ -692,6 +1068,7 @@ impl SyncCommandBufferBuilder {
                 .map(|(s, _)| s),
         );
         self.current_state.pipeline_graphics = Some(pipeline.clone());
+        self.bound_pipelines.push(pipeline.clone());
         self.commands.push(Box::new(Cmd { pipeline }));
     }
 
@@ -911,7 +1288,7 @@ impl<'b> SyncCommandBufferBuilderBindDescriptorSets<'b> {
 pub struct SyncCommandBufferBuilderBindVertexBuffer<'a> {
     builder: &'a mut SyncCommandBufferBuilder,
     inner: UnsafeCommandBufferBuilderBindVertexBuffer,
-    buffers: SmallVec<[Arc<dyn BufferAccess>; 4]>,
+    buffers: SmallVec<[Option<Arc<dyn BufferAccess>>; 4]>,
 }
 
 impl<'a> SyncCommandBufferBuilderBindVertexBuffer<'a> {
@@ -919,7 +1296,21 @@ impl<'a> SyncCommandBufferBuilderBindVertexBuffer<'a> {
     #[inline]
     pub fn add(&mut self, buffer: Arc<dyn BufferAccess>) {
         self.inner.add(buffer.as_ref());
-        self.buffers.push(buffer);
+        self.buffers.push(Some(buffer));
+    }
+
+    /// Adds a buffer to the list, with a dynamic stride.
+    #[inline]
+    pub fn add_with_stride(&mut self, buffer: Arc<dyn BufferAccess>, stride: DeviceSize) {
+        self.inner.add_with_stride(buffer.as_ref(), stride);
+        self.buffers.push(Some(buffer));
+    }
+
+    /// Adds a null buffer to the list.
+    #[inline]
+    pub fn add_null(&mut self) {
+        self.inner.add_null();
+        self.buffers.push(None);
     }
 
     #[inline]
@@ -927,7 +1318,7 @@ impl<'a> SyncCommandBufferBuilderBindVertexBuffer<'a> {
         struct Cmd {
             first_set: u32,
             inner: Mutex<Option<UnsafeCommandBufferBuilderBindVertexBuffer>>,
-            _buffers: SmallVec<[Arc<dyn BufferAccess>; 4]>,
+            _buffers: SmallVec<[Option<Arc<dyn BufferAccess>>; 4]>,
         }
 
         impl Command for Cmd {
@@ -940,11 +1331,30 @@ impl<'a> SyncCommandBufferBuilderBindVertexBuffer<'a> {
             }
         }
 
+        let current_state = &mut self.builder.current_state;
+
         for (i, buffer) in self.buffers.iter().enumerate() {
+            let binding_num = first_set + i as u32;
+
+            match buffer {
+                Some(buffer) => {
+                    current_state
+                        .vertex_buffers
+                        .insert(binding_num, buffer.clone());
+                    current_state.null_vertex_buffers.remove(&binding_num);
+                }
+                None => {
+                    current_state.vertex_buffers.remove(&binding_num);
+                    current_state.null_vertex_buffers.insert(binding_num);
+                }
+            }
+        }
+
+        for (i, &stride) in self.inner.strides.iter().enumerate() {
             self.builder
                 .current_state
-                .vertex_buffers
-                .insert(first_set + i as u32, buffer.clone());
+                .vertex_buffer_strides
+                .insert(first_set + i as u32, stride);
         }
 
         self.builder.commands.push(Box::new(Cmd {
@@ -1031,11 +1441,13 @@ impl UnsafeCommandBufferBuilder {
         );
     }
 
-    /// Calls `vkCmdBindVertexBuffers` on the builder.
+    /// Calls `vkCmdBindVertexBuffers` or `vkCmdBindVertexBuffers2` on the builder.
+    ///
+    /// `vkCmdBindVertexBuffers2` is used if `params` contains strides, or if it is available on
+    /// the device, so that the size of each binding is passed to the implementation as well.
     ///
     /// Does nothing if the list of buffers is empty, as it would be a no-op and isn't a valid
     /// usage of the command anyway.
-    // TODO: vkCmdBindVertexBuffers2EXT
     #[inline]
     pub unsafe fn bind_vertex_buffers(
         &mut self,
@@ -1043,6 +1455,10 @@ impl UnsafeCommandBufferBuilder {
         params: UnsafeCommandBufferBuilderBindVertexBuffer,
     ) {
         debug_assert_eq!(params.raw_buffers.len(), params.offsets.len());
+        debug_assert_eq!(params.raw_buffers.len(), params.sizes.len());
+        debug_assert!(
+            params.strides.is_empty() || params.raw_buffers.len() == params.strides.len()
+        );
 
         if params.raw_buffers.is_empty() {
             return;
@@ -1061,13 +1477,42 @@ impl UnsafeCommandBufferBuilder {
             first_binding + num_bindings <= max_bindings
         });
 
-        (fns.v1_0.cmd_bind_vertex_buffers)(
-            self.handle,
-            first_binding,
-            num_bindings,
-            params.raw_buffers.as_ptr(),
-            params.offsets.as_ptr(),
-        );
+        let strides = if params.strides.is_empty() {
+            ptr::null()
+        } else {
+            params.strides.as_ptr()
+        };
+
+        if self.device.api_version() >= Version::V1_3 {
+            (fns.v1_3.cmd_bind_vertex_buffers2)(
+                self.handle,
+                first_binding,
+                num_bindings,
+                params.raw_buffers.as_ptr(),
+                params.offsets.as_ptr(),
+                params.sizes.as_ptr(),
+                strides,
+            );
+        } else if self.device.enabled_extensions().ext_extended_dynamic_state {
+            (fns.ext_extended_dynamic_state.cmd_bind_vertex_buffers2_ext)(
+                self.handle,
+                first_binding,
+                num_bindings,
+                params.raw_buffers.as_ptr(),
+                params.offsets.as_ptr(),
+                params.sizes.as_ptr(),
+                strides,
+            );
+        } else {
+            debug_assert!(params.strides.is_empty());
+            (fns.v1_0.cmd_bind_vertex_buffers)(
+                self.handle,
+                first_binding,
+                num_bindings,
+                params.raw_buffers.as_ptr(),
+                params.offsets.as_ptr(),
+            );
+        }
     }
 
     /// Calls `vkCmdPushConstants` on the builder.
@@ -1111,7 +1556,7 @@ impl UnsafeCommandBufferBuilder {
     ) {
         debug_assert!(self.device.enabled_extensions().khr_push_descriptor);
 
-        let (infos, mut writes): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = descriptor_writes
+        let (mut infos, mut writes): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = descriptor_writes
             .into_iter()
             .map(|write| {
                 let binding =
@@ -1129,21 +1574,8 @@ impl UnsafeCommandBufferBuilder {
         }
 
         // Set the info pointers separately.
-        for (info, write) in infos.iter().zip(writes.iter_mut()) {
-            match info {
-                DescriptorWriteInfo::Image(info) => {
-                    write.descriptor_count = info.len() as u32;
-                    write.p_image_info = info.as_ptr();
-                }
-                DescriptorWriteInfo::Buffer(info) => {
-                    write.descriptor_count = info.len() as u32;
-                    write.p_buffer_info = info.as_ptr();
-                }
-                DescriptorWriteInfo::BufferView(info) => {
-                    write.descriptor_count = info.len() as u32;
-                    write.p_texel_buffer_view = info.as_ptr();
-                }
-            }
+        for (info, write) in infos.iter_mut().zip(writes.iter_mut()) {
+            info.set_pointers(write);
 
             debug_assert!(write.descriptor_count != 0);
         }
@@ -1161,13 +1593,17 @@ impl UnsafeCommandBufferBuilder {
     }
 }
 
-/// Prototype for a `vkCmdBindVertexBuffers`.
+/// Prototype for a `vkCmdBindVertexBuffers` or `vkCmdBindVertexBuffers2`.
 #[derive(Debug)]
 pub struct UnsafeCommandBufferBuilderBindVertexBuffer {
     // Raw handles of the buffers to bind.
     pub raw_buffers: SmallVec<[ash::vk::Buffer; 4]>,
     // Raw offsets of the buffers to bind.
     pub offsets: SmallVec<[DeviceSize; 4]>,
+    // Sizes of the buffers to bind.
+    pub sizes: SmallVec<[DeviceSize; 4]>,
+    // Dynamic strides of the buffers to bind. Either empty, or the same length as `raw_buffers`.
+    pub strides: SmallVec<[DeviceSize; 4]>,
 }
 
 impl UnsafeCommandBufferBuilderBindVertexBuffer {
@@ -1177,17 +1613,134 @@ impl UnsafeCommandBufferBuilderBindVertexBuffer {
         UnsafeCommandBufferBuilderBindVertexBuffer {
             raw_buffers: SmallVec::new(),
             offsets: SmallVec::new(),
+            sizes: SmallVec::new(),
+            strides: SmallVec::new(),
         }
     }
 
     /// Adds a buffer to the list.
     #[inline]
     pub fn add(&mut self, buffer: &dyn BufferAccess) {
+        debug_assert!(self.strides.is_empty());
         let inner = buffer.inner();
         debug_assert!(inner.buffer.usage().vertex_buffer);
         self.raw_buffers.push(inner.buffer.internal_object());
         self.offsets.push(inner.offset);
+        self.sizes.push(buffer.size());
     }
+
+    /// Adds a buffer to the list, with a dynamic stride.
+    ///
+    /// Either all or none of the buffers in the list must have a stride.
+    #[inline]
+    pub fn add_with_stride(&mut self, buffer: &dyn BufferAccess, stride: DeviceSize) {
+        debug_assert_eq!(self.strides.len(), self.raw_buffers.len());
+        let inner = buffer.inner();
+        debug_assert!(inner.buffer.usage().vertex_buffer);
+        self.raw_buffers.push(inner.buffer.internal_object());
+        self.offsets.push(inner.offset);
+        self.sizes.push(buffer.size());
+        self.strides.push(stride);
+    }
+
+    /// Adds a null buffer to the list. This requires the `null_descriptor` feature.
+    #[inline]
+    pub fn add_null(&mut self) {
+        debug_assert!(self.strides.is_empty());
+        self.raw_buffers.push(ash::vk::Buffer::null());
+        self.offsets.push(0);
+        self.sizes.push(ash::vk::WHOLE_SIZE);
+    }
+}
+
+/// Builds the list of dynamic offsets of each descriptor set from `dynamic_offsets`.
+fn resolve_dynamic_offsets(
+    first_set: u32,
+    descriptor_sets: Vec<Arc<dyn DescriptorSet>>,
+    dynamic_offsets: &DynamicOffsets,
+) -> Result<Vec<DescriptorSetWithOffsets>, BindPushError> {
+    for (set_num, binding_num, index) in dynamic_offsets.keys() {
+        let is_dynamic = set_num
+            .checked_sub(first_set)
+            .and_then(|i| descriptor_sets.get(i as usize))
+            .and_then(|set| set.layout().bindings().get(&binding_num))
+            .map_or(false, |binding| {
+                matches!(
+                    binding.descriptor_type,
+                    DescriptorType::UniformBufferDynamic | DescriptorType::StorageBufferDynamic
+                ) && index < binding.descriptor_count
+            });
+
+        if !is_dynamic {
+            return Err(BindPushError::DynamicOffsetNotUsed {
+                set_num,
+                binding: binding_num,
+                index,
+            });
+        }
+    }
+
+    descriptor_sets
+        .into_iter()
+        .enumerate()
+        .map(|(i, descriptor_set)| {
+            let set_num = first_set + i as u32;
+            let mut offsets = SmallVec::new();
+
+            for (&binding_num, binding) in descriptor_set.layout().bindings() {
+                if !matches!(
+                    binding.descriptor_type,
+                    DescriptorType::UniformBufferDynamic | DescriptorType::StorageBufferDynamic
+                ) {
+                    continue;
+                }
+
+                for index in 0..binding.descriptor_count {
+                    let offset = match dynamic_offsets.get(set_num, binding_num, index) {
+                        Some(&DynamicOffset::Offset(offset)) => offset,
+                        Some(DynamicOffset::Buffer(buffer)) => {
+                            let written = match descriptor_set.resources().binding(binding_num) {
+                                Some(DescriptorBindingResources::Buffer(elements)) => {
+                                    elements[index as usize].as_ref()
+                                }
+                                _ => None,
+                            };
+
+                            match written {
+                                Some(written)
+                                    if written.inner().buffer == buffer.inner().buffer
+                                        && written.inner().offset <= buffer.inner().offset =>
+                                {
+                                    (buffer.inner().offset - written.inner().offset) as u32
+                                }
+                                _ => {
+                                    return Err(BindPushError::DynamicOffsetBufferMismatch {
+                                        set_num,
+                                        binding: binding_num,
+                                        index,
+                                    })
+                                }
+                            }
+                        }
+                        None => {
+                            return Err(BindPushError::DynamicOffsetMissing {
+                                set_num,
+                                binding: binding_num,
+                                index,
+                            })
+                        }
+                    };
+
+                    offsets.push(offset);
+                }
+            }
+
+            Ok(DescriptorSetWithOffsets::new_unchecked(
+                descriptor_set,
+                offsets,
+            ))
+        })
+        .collect()
 }
 
 #[derive(Clone, Debug)]
@@ -1218,9 +1771,71 @@ enum BindPushError {
         pipeline_layout_set_count: u32,
     },
 
+    /// An offset in `dynamic_offsets` was given as a buffer, but that buffer is not within the
+    /// buffer that was written to the descriptor.
+    DynamicOffsetBufferMismatch {
+        set_num: u32,
+        binding: u32,
+        index: u32,
+    },
+
+    /// The number of dynamic offsets provided for a descriptor set is not equal to the number of
+    /// dynamic descriptors in the set.
+    DynamicOffsetCountMismatch {
+        set_num: u32,
+        required: u32,
+        provided: u32,
+    },
+
+    /// A dynamic descriptor has no offset in `dynamic_offsets`.
+    DynamicOffsetMissing {
+        set_num: u32,
+        binding: u32,
+        index: u32,
+    },
+
+    /// A dynamic offset is not a multiple of the `min_uniform_buffer_offset_alignment` or
+    /// `min_storage_buffer_offset_alignment` limit.
+    DynamicOffsetNotAligned {
+        set_num: u32,
+        binding: u32,
+        index: u32,
+        offset: DeviceSize,
+        required_alignment: DeviceSize,
+    },
+
+    /// `dynamic_offsets` contains an offset for a descriptor that is not being bound or is not
+    /// dynamic.
+    DynamicOffsetNotUsed {
+        set_num: u32,
+        binding: u32,
+        index: u32,
+    },
+
+    /// A dynamic offset makes a descriptor extend past the end of its buffer.
+    DynamicOffsetOutOfRange {
+        set_num: u32,
+        binding: u32,
+        index: u32,
+        offset: DeviceSize,
+    },
+
     /// An index buffer is missing the `index_buffer` usage.
     IndexBufferMissingUsage,
 
+    /// The offset of an index buffer is not a multiple of the size of the index type.
+    IndexBufferOffsetNotAligned {
+        offset: DeviceSize,
+        required_alignment: DeviceSize,
+    },
+
+    /// The stride of a vertex buffer binding exceeds the `max_vertex_input_binding_stride` limit.
+    MaxVertexInputBindingStrideExceeded {
+        binding: u32,
+        stride: DeviceSize,
+        max: u32,
+    },
+
     /// The `max_vertex_input_bindings` limit has been exceeded.
     MaxVertexInputBindingsExceeded {
         _binding_count: u32,
@@ -1238,6 +1853,15 @@ enum BindPushError {
     /// previously used pipeline.
     PreviousPipelineDepthAttachmentFormatMismatch,
 
+    /// The newly set pipeline has a provoking vertex mode that does not match the previously used
+    /// pipeline in the current render pass, and the
+    /// [`provoking_vertex_mode_per_pipeline`](crate::device::Properties::provoking_vertex_mode_per_pipeline)
+    /// device property is not `true`.
+    PreviousPipelineProvokingVertexModeMismatch {
+        provoking_vertex_mode: ProvokingVertexMode,
+        previous_provoking_vertex_mode: ProvokingVertexMode,
+    },
+
     /// The newly set pipeline has a stencil attachment format that does not match the
     /// previously used pipeline.
     PreviousPipelineStencilAttachmentFormatMismatch,
@@ -1256,6 +1880,12 @@ enum BindPushError {
 
     /// A vertex buffer is missing the `vertex_buffer` usage.
     VertexBufferMissingUsage,
+
+    /// The number of strides is not equal to the number of vertex buffers.
+    VertexBufferStrideCountMismatch {
+        buffer_count: u32,
+        stride_count: u32,
+    },
 }
 
 impl error::Error for BindPushError {
@@ -1300,9 +1930,89 @@ impl Display for BindPushError {
                 sets in `pipeline_layout` ({})",
                 set_num, pipeline_layout_set_count,
             ),
+            Self::DynamicOffsetBufferMismatch {
+                set_num,
+                binding,
+                index,
+            } => write!(
+                f,
+                "the buffer given as the dynamic offset of set {} binding {} index {} is not \
+                within the buffer that was written to the descriptor",
+                set_num, binding, index,
+            ),
+            Self::DynamicOffsetCountMismatch {
+                set_num,
+                required,
+                provided,
+            } => write!(
+                f,
+                "the number of dynamic offsets provided for set {} ({}) is not equal to the \
+                number of dynamic descriptors in the set ({})",
+                set_num, provided, required,
+            ),
+            Self::DynamicOffsetMissing {
+                set_num,
+                binding,
+                index,
+            } => write!(
+                f,
+                "the dynamic descriptor at set {} binding {} index {} has no dynamic offset",
+                set_num, binding, index,
+            ),
+            Self::DynamicOffsetNotAligned {
+                set_num,
+                binding,
+                index,
+                offset,
+                required_alignment,
+            } => write!(
+                f,
+                "the dynamic offset of set {} binding {} index {} ({}) is not a multiple of {}",
+                set_num, binding, index, offset, required_alignment,
+            ),
+            Self::DynamicOffsetNotUsed {
+                set_num,
+                binding,
+                index,
+            } => write!(
+                f,
+                "a dynamic offset was provided for set {} binding {} index {}, which is not \
+                being bound or is not a dynamic descriptor",
+                set_num, binding, index,
+            ),
+            Self::DynamicOffsetOutOfRange {
+                set_num,
+                binding,
+                index,
+                offset,
+            } => write!(
+                f,
+                "the dynamic offset of set {} binding {} index {} ({}) makes the descriptor \
+                extend past the end of its buffer",
+                set_num, binding, index, offset,
+            ),
             Self::IndexBufferMissingUsage => {
                 write!(f, "an index buffer is missing the `index_buffer` usage")
             }
+            Self::IndexBufferOffsetNotAligned {
+                offset,
+                required_alignment,
+            } => write!(
+                f,
+                "the offset of the index buffer ({}) is not a multiple of the size of the index \
+                type ({})",
+                offset, required_alignment,
+            ),
+            Self::MaxVertexInputBindingStrideExceeded {
+                binding,
+                stride,
+                max,
+            } => write!(
+                f,
+                "the stride of vertex buffer binding {} ({}) exceeds the \
+                `max_vertex_input_binding_stride` limit ({})",
+                binding, stride, max,
+            ),
             Self::MaxVertexInputBindingsExceeded { .. } => {
                 write!(f, "the `max_vertex_input_bindings` limit has been exceeded")
             }
@@ -1319,6 +2029,16 @@ impl Display for BindPushError {
                 "the newly set pipeline has a depth attachment format that does not match the \
                 previously used pipeline",
             ),
+            Self::PreviousPipelineProvokingVertexModeMismatch {
+                provoking_vertex_mode,
+                previous_provoking_vertex_mode,
+            } => write!(
+                f,
+                "the newly set pipeline has a provoking vertex mode ({:?}) that does not match the \
+                previously used pipeline in the current render pass ({:?}), and the \
+                `provoking_vertex_mode_per_pipeline` property is not `true`",
+                provoking_vertex_mode, previous_provoking_vertex_mode,
+            ),
             Self::PreviousPipelineStencilAttachmentFormatMismatch => write!(
                 f,
                 "the newly set pipeline has a stencil attachment format that does not match the \
@@ -1339,6 +2059,14 @@ impl Display for BindPushError {
             Self::VertexBufferMissingUsage => {
                 write!(f, "a vertex buffer is missing the `vertex_buffer` usage")
             }
+            Self::VertexBufferStrideCountMismatch {
+                buffer_count,
+                stride_count,
+            } => write!(
+                f,
+                "the number of strides ({}) is not equal to the number of vertex buffers ({})",
+                stride_count, buffer_count,
+            ),
         }
     }
 }
@@ -1357,3 +2085,99 @@ impl From<RequirementNotMet> for BindPushError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{resolve_dynamic_offsets, BindPushError};
+    use crate::{
+        buffer::{BufferAccess, BufferUsage, CpuAccessibleBuffer},
+        descriptor_set::{
+            allocator::StandardDescriptorSetAllocator,
+            layout::{
+                DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+                DescriptorType,
+            },
+            DescriptorSet, DynamicOffsets, PersistentDescriptorSet, WriteDescriptorSet,
+        },
+        shader::ShaderStages,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn dynamic_offsets() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let layout = DescriptorSetLayout::new(
+            device.clone(),
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        descriptor_count: 2,
+                        stages: ShaderStages::all(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(
+                            DescriptorType::UniformBufferDynamic,
+                        )
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage {
+                uniform_buffer: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            [0u8; 1024],
+        )
+        .unwrap();
+        let written = buffer.slice::<u8>(0..16).unwrap();
+
+        let ds_allocator = StandardDescriptorSetAllocator::new(device);
+        let set: Arc<dyn DescriptorSet> = PersistentDescriptorSet::new(
+            &ds_allocator,
+            layout,
+            [WriteDescriptorSet::buffer_array(
+                0,
+                0,
+                [written.clone() as Arc<dyn BufferAccess>, written],
+            )],
+        )
+        .unwrap();
+
+        let dynamic_offsets = DynamicOffsets::new().offset(1, 0, 0, 256).buffer(
+            1,
+            0,
+            1,
+            buffer.slice::<u8>(512..528).unwrap(),
+        );
+        let sets = resolve_dynamic_offsets(1, vec![set.clone()], &dynamic_offsets).unwrap();
+        assert_eq!(sets[0].as_ref().1, &[256, 512]);
+
+        assert!(matches!(
+            resolve_dynamic_offsets(
+                1,
+                vec![set.clone()],
+                &DynamicOffsets::new().offset(1, 0, 0, 0)
+            ),
+            Err(BindPushError::DynamicOffsetMissing {
+                set_num: 1,
+                binding: 0,
+                index: 1,
+            }),
+        ));
+        assert!(matches!(
+            resolve_dynamic_offsets(1, vec![set], &dynamic_offsets.offset(0, 0, 0, 0)),
+            Err(BindPushError::DynamicOffsetNotUsed {
+                set_num: 0,
+                binding: 0,
+                index: 0,
+            }),
+        ));
+    }
+}
@@ -22,6 +22,7 @@ use std::{
     error::Error,
     ffi::CString,
     fmt::{Display, Error as FmtError, Formatter},
+    ops::{Deref, DerefMut},
 };
 
 /// # Commands for debugging.
@@ -171,6 +172,66 @@ where
 
         Ok(())
     }
+
+    /// Opens a command buffer debug label region that is closed again when the returned scope is
+    /// dropped.
+    ///
+    /// The scope dereferences to the builder, so commands can be recorded into the region through
+    /// it.
+    pub fn debug_utils_label_scope(
+        &mut self,
+        label_info: DebugUtilsLabel,
+    ) -> Result<DebugUtilsLabelScope<'_, L, A>, DebugUtilsError> {
+        self.begin_debug_utils_label(label_info)?;
+
+        Ok(DebugUtilsLabelScope { builder: self })
+    }
+}
+
+/// A command buffer debug label region, opened by
+/// [`debug_utils_label_scope`](AutoCommandBufferBuilder::debug_utils_label_scope), that is closed
+/// when it is dropped.
+pub struct DebugUtilsLabelScope<'a, L, A>
+where
+    A: CommandBufferAllocator,
+{
+    builder: &'a mut AutoCommandBufferBuilder<L, A>,
+}
+
+impl<'a, L, A> Deref for DebugUtilsLabelScope<'a, L, A>
+where
+    A: CommandBufferAllocator,
+{
+    type Target = AutoCommandBufferBuilder<L, A>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.builder
+    }
+}
+
+impl<'a, L, A> DerefMut for DebugUtilsLabelScope<'a, L, A>
+where
+    A: CommandBufferAllocator,
+{
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.builder
+    }
+}
+
+impl<'a, L, A> Drop for DebugUtilsLabelScope<'a, L, A>
+where
+    A: CommandBufferAllocator,
+{
+    #[inline]
+    fn drop(&mut self) {
+        // The region was opened when the scope was created, and the requirements were validated
+        // at that point.
+        unsafe {
+            self.builder.inner.end_debug_utils_label();
+        }
+    }
 }
 
 impl SyncCommandBufferBuilder {
@@ -15,12 +15,15 @@ use crate::{
         AutoCommandBufferBuilder,
     },
     device::DeviceOwned,
+    image::SampleCount,
     pipeline::{
         graphics::{
             color_blend::LogicOp,
             depth_stencil::{CompareOp, StencilFaces, StencilOp, StencilOps},
+            fragment_shading_rate::{FragmentShadingRate, FragmentShadingRateCombinerOp},
             input_assembly::PrimitiveTopology,
-            rasterization::{CullMode, DepthBias, FrontFace, LineStipple},
+            multisample::SampleLocations,
+            rasterization::{CullMode, DepthBias, FrontFace, LineStipple, PolygonMode},
             viewport::{Scissor, Viewport},
         },
         DynamicState,
@@ -411,6 +414,67 @@ where
         Ok(())
     }
 
+    /// Sets whether dynamic depth clamping is enabled for future draw calls.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the queue family of the command buffer does not support graphics operations.
+    /// - Panics if the
+    ///   [`extended_dynamic_state3_depth_clamp_enable`](crate::device::Features::extended_dynamic_state3_depth_clamp_enable)
+    ///   feature is not enabled on the device.
+    /// - Panics if `enable` is `true` and the
+    ///   [`depth_clamp`](crate::device::Features::depth_clamp) feature is not enabled on the
+    ///   device.
+    /// - Panics if the currently bound graphics pipeline already contains this state internally.
+    pub fn set_depth_clamp_enable(&mut self, enable: bool) -> &mut Self {
+        self.validate_set_depth_clamp_enable(enable).unwrap();
+
+        unsafe {
+            self.inner.set_depth_clamp_enable(enable);
+        }
+
+        self
+    }
+
+    fn validate_set_depth_clamp_enable(&self, enable: bool) -> Result<(), SetDynamicStateError> {
+        self.validate_pipeline_fixed_state(DynamicState::DepthClampEnable)?;
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdSetDepthClampEnableEXT-commandBuffer-cmdpool
+        if !queue_family_properties.queue_flags.graphics {
+            return Err(SetDynamicStateError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdSetDepthClampEnableEXT-extendedDynamicState3DepthClampEnable-07448
+        if !self
+            .device()
+            .enabled_features()
+            .extended_dynamic_state3_depth_clamp_enable
+        {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`set_depth_clamp_enable`",
+                requires_one_of: RequiresOneOf {
+                    features: &["extended_dynamic_state3_depth_clamp_enable"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdSetDepthClampEnableEXT-depthClamp-07449
+        if enable && !self.device().enabled_features().depth_clamp {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`enable` is `true`",
+                requires_one_of: RequiresOneOf {
+                    features: &["depth_clamp"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
     /// Sets the dynamic depth compare op for future draw calls.
     ///
     /// # Panics
@@ -631,6 +695,150 @@ where
         Ok(())
     }
 
+    /// Sets the dynamic fragment shading rate for future draw calls.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the queue family of the command buffer does not support graphics operations.
+    /// - Panics if none of the
+    ///   [`pipeline_fragment_shading_rate`](crate::device::Features::pipeline_fragment_shading_rate),
+    ///   [`primitive_fragment_shading_rate`](crate::device::Features::primitive_fragment_shading_rate)
+    ///   or [`attachment_fragment_shading_rate`](crate::device::Features::attachment_fragment_shading_rate)
+    ///   features are enabled on the device.
+    /// - Panics if the currently bound graphics pipeline already contains this state internally.
+    /// - Panics if an element of `fragment_size` is not 1, 2 or 4.
+    /// - Panics if `fragment_size` is not `[1, 1]` and the `pipeline_fragment_shading_rate`
+    ///   feature is not enabled, or if an element of `combiner_ops` is not `Keep` and the
+    ///   corresponding `primitive_fragment_shading_rate` or `attachment_fragment_shading_rate`
+    ///   feature is not enabled.
+    /// - Panics if an element of `combiner_ops` is not `Keep` or `Replace` and the
+    ///   [`fragment_shading_rate_non_trivial_combiner_ops`](crate::device::Properties::fragment_shading_rate_non_trivial_combiner_ops)
+    ///   device property is `false`.
+    pub fn set_fragment_shading_rate(
+        &mut self,
+        fragment_size: [u32; 2],
+        combiner_ops: [FragmentShadingRateCombinerOp; 2],
+    ) -> &mut Self {
+        self.validate_set_fragment_shading_rate(fragment_size, combiner_ops)
+            .unwrap();
+
+        unsafe {
+            self.inner
+                .set_fragment_shading_rate(fragment_size, combiner_ops);
+        }
+
+        self
+    }
+
+    fn validate_set_fragment_shading_rate(
+        &self,
+        fragment_size: [u32; 2],
+        combiner_ops: [FragmentShadingRateCombinerOp; 2],
+    ) -> Result<(), SetDynamicStateError> {
+        self.validate_pipeline_fixed_state(DynamicState::FragmentShadingRate)?;
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdSetFragmentShadingRateKHR-commandBuffer-cmdpool
+        if !queue_family_properties.queue_flags.graphics {
+            return Err(SetDynamicStateError::NotSupportedByQueueFamily);
+        }
+
+        let features = self.device().enabled_features();
+
+        // VUID-vkCmdSetFragmentShadingRateKHR-pipelineFragmentShadingRate-04509
+        if !(features.pipeline_fragment_shading_rate
+            || features.primitive_fragment_shading_rate
+            || features.attachment_fragment_shading_rate)
+        {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`set_fragment_shading_rate`",
+                requires_one_of: RequiresOneOf {
+                    features: &[
+                        "pipeline_fragment_shading_rate",
+                        "primitive_fragment_shading_rate",
+                        "attachment_fragment_shading_rate",
+                    ],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdSetFragmentShadingRateKHR-pFragmentSize-04513
+        // VUID-vkCmdSetFragmentShadingRateKHR-pFragmentSize-04514
+        // VUID-vkCmdSetFragmentShadingRateKHR-pFragmentSize-04515
+        // VUID-vkCmdSetFragmentShadingRateKHR-pFragmentSize-04516
+        // VUID-vkCmdSetFragmentShadingRateKHR-pFragmentSize-04517
+        // VUID-vkCmdSetFragmentShadingRateKHR-pFragmentSize-04518
+        if !fragment_size
+            .into_iter()
+            .all(|size| matches!(size, 1 | 2 | 4))
+        {
+            return Err(SetDynamicStateError::FragmentSizeInvalid {
+                provided: fragment_size,
+            });
+        }
+
+        // VUID-vkCmdSetFragmentShadingRateKHR-pipelineFragmentShadingRate-04507
+        // VUID-vkCmdSetFragmentShadingRateKHR-pipelineFragmentShadingRate-04508
+        if fragment_size != [1, 1] && !features.pipeline_fragment_shading_rate {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`set_fragment_shading_rate`, where `fragment_size` is not `[1, 1]`",
+                requires_one_of: RequiresOneOf {
+                    features: &["pipeline_fragment_shading_rate"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        for combiner_op in combiner_ops {
+            // VUID-vkCmdSetFragmentShadingRateKHR-combinerOps-parameter
+            combiner_op.validate_device(self.device())?;
+
+            // VUID-vkCmdSetFragmentShadingRateKHR-fragmentSizeNonTrivialCombinerOps-04512
+            if !matches!(
+                combiner_op,
+                FragmentShadingRateCombinerOp::Keep | FragmentShadingRateCombinerOp::Replace
+            ) && !self
+                .device()
+                .physical_device()
+                .properties()
+                .fragment_shading_rate_non_trivial_combiner_ops
+                .unwrap_or(false)
+            {
+                return Err(SetDynamicStateError::NonTrivialCombinerOpsNotSupported);
+            }
+        }
+
+        // VUID-vkCmdSetFragmentShadingRateKHR-primitiveFragmentShadingRate-04510
+        if combiner_ops[0] != FragmentShadingRateCombinerOp::Keep
+            && !features.primitive_fragment_shading_rate
+        {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`set_fragment_shading_rate`, where `combiner_ops[0]` is not `FragmentShadingRateCombinerOp::Keep`",
+                requires_one_of: RequiresOneOf {
+                    features: &["primitive_fragment_shading_rate"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdSetFragmentShadingRateKHR-attachmentFragmentShadingRate-04511
+        if combiner_ops[1] != FragmentShadingRateCombinerOp::Keep
+            && !features.attachment_fragment_shading_rate
+        {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`set_fragment_shading_rate`, where `combiner_ops[1]` is not `FragmentShadingRateCombinerOp::Keep`",
+                requires_one_of: RequiresOneOf {
+                    features: &["attachment_fragment_shading_rate"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
     /// Sets the dynamic front face for future draw calls.
     ///
     /// # Panics
@@ -872,7 +1080,9 @@ where
         }
 
         // VUID-vkCmdSetPatchControlPointsEXT-patchControlPoints-04874
-        assert!(num > 0, "num must be greater than 0");
+        if num == 0 {
+            return Err(SetDynamicStateError::PatchControlPointsZero);
+        }
 
         // VUID-vkCmdSetPatchControlPointsEXT-patchControlPoints-04874
         if num
@@ -895,6 +1105,75 @@ where
         Ok(())
     }
 
+    /// Sets the dynamic polygon mode for future draw calls.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the queue family of the command buffer does not support graphics operations.
+    /// - Panics if the
+    ///   [`extended_dynamic_state3_polygon_mode`](crate::device::Features::extended_dynamic_state3_polygon_mode)
+    ///   feature is not enabled on the device.
+    /// - Panics if `polygon_mode` is not `PolygonMode::Fill` and the
+    ///   [`fill_mode_non_solid`](crate::device::Features::fill_mode_non_solid) feature is not
+    ///   enabled on the device.
+    /// - Panics if the currently bound graphics pipeline already contains this state internally.
+    pub fn set_polygon_mode(&mut self, polygon_mode: PolygonMode) -> &mut Self {
+        self.validate_set_polygon_mode(polygon_mode).unwrap();
+
+        unsafe {
+            self.inner.set_polygon_mode(polygon_mode);
+        }
+
+        self
+    }
+
+    fn validate_set_polygon_mode(
+        &self,
+        polygon_mode: PolygonMode,
+    ) -> Result<(), SetDynamicStateError> {
+        self.validate_pipeline_fixed_state(DynamicState::PolygonMode)?;
+
+        // VUID-vkCmdSetPolygonModeEXT-polygonMode-parameter
+        polygon_mode.validate_device(self.device())?;
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdSetPolygonModeEXT-commandBuffer-cmdpool
+        if !queue_family_properties.queue_flags.graphics {
+            return Err(SetDynamicStateError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdSetPolygonModeEXT-extendedDynamicState3PolygonMode-07421
+        if !self
+            .device()
+            .enabled_features()
+            .extended_dynamic_state3_polygon_mode
+        {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`set_polygon_mode`",
+                requires_one_of: RequiresOneOf {
+                    features: &["extended_dynamic_state3_polygon_mode"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdSetPolygonModeEXT-fillModeNonSolid-07424
+        if polygon_mode != PolygonMode::Fill
+            && !self.device().enabled_features().fill_mode_non_solid
+        {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`polygon_mode` is not `PolygonMode::Fill`",
+                requires_one_of: RequiresOneOf {
+                    features: &["fill_mode_non_solid"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
     /// Sets whether dynamic primitive restart is enabled for future draw calls.
     ///
     /// # Panics
@@ -1081,6 +1360,123 @@ where
         Ok(())
     }
 
+    /// Sets the dynamic sample locations for future draw calls.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the queue family of the command buffer does not support graphics operations.
+    /// - Panics if the
+    ///   [`ext_sample_locations`](crate::device::DeviceExtensions::ext_sample_locations)
+    ///   extension is not enabled on the device.
+    /// - Panics if the currently bound graphics pipeline already contains this state internally.
+    /// - Panics if `sample_locations.per_pixel` is not included in the
+    ///   [`sample_location_sample_counts`](crate::device::Properties::sample_location_sample_counts)
+    ///   device property.
+    /// - Panics if the number of locations in `sample_locations` does not match
+    ///   [`sample_locations.required_count()`](SampleLocations::required_count).
+    /// - If the [`variable_sample_locations`](crate::device::Properties::variable_sample_locations)
+    ///   device property is `false`, panics if `sample_locations.per_pixel` does not match the
+    ///   `rasterization_samples` of the currently bound graphics pipeline.
+    pub fn set_sample_locations(&mut self, sample_locations: SampleLocations) -> &mut Self {
+        self.validate_set_sample_locations(&sample_locations)
+            .unwrap();
+
+        unsafe {
+            self.inner.set_sample_locations(sample_locations);
+        }
+
+        self
+    }
+
+    fn validate_set_sample_locations(
+        &self,
+        sample_locations: &SampleLocations,
+    ) -> Result<(), SetDynamicStateError> {
+        self.validate_pipeline_fixed_state(DynamicState::SampleLocations)?;
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdSetSampleLocationsEXT-commandBuffer-cmdpool
+        if !queue_family_properties.queue_flags.graphics {
+            return Err(SetDynamicStateError::NotSupportedByQueueFamily);
+        }
+
+        if !self.device().enabled_extensions().ext_sample_locations {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`set_sample_locations`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_sample_locations"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let &SampleLocations {
+            per_pixel,
+            grid_size,
+            ref locations,
+        } = sample_locations;
+
+        // VUID-VkSampleLocationsInfoEXT-sampleLocationsPerPixel-parameter
+        per_pixel.validate_device(self.device())?;
+
+        let properties = self.device().physical_device().properties();
+
+        // VUID-VkSampleLocationsInfoEXT-sampleLocationsPerPixel-01526
+        if !properties
+            .sample_location_sample_counts
+            .map_or(false, |counts| counts.contains_count(per_pixel))
+        {
+            return Err(SetDynamicStateError::SampleLocationsPerPixelNotSupported { per_pixel });
+        }
+
+        // VUID-VkSampleLocationsInfoEXT-sampleLocationGridSize-01528
+        // VUID-VkSampleLocationsInfoEXT-sampleLocationGridSize-01529
+        // Use unchecked, because all validation has been done above.
+        let max_grid_size = unsafe {
+            self.device()
+                .physical_device()
+                .multisample_properties_unchecked(per_pixel)
+        }
+        .max_sample_location_grid_size;
+
+        if !sample_locations.grid_size_divides(max_grid_size) {
+            return Err(SetDynamicStateError::SampleLocationsGridSizeNotSupported {
+                grid_size,
+                max_grid_size,
+            });
+        }
+
+        // VUID-VkSampleLocationsInfoEXT-sampleLocationsCount-01527
+        let required = sample_locations.required_count();
+
+        if required != Some(locations.len() as u32) {
+            return Err(SetDynamicStateError::SampleLocationsCountMismatch {
+                provided: locations.len() as u32,
+                required,
+            });
+        }
+
+        // VUID-vkCmdSetSampleLocationsEXT-variableSampleLocations-01530
+        if !properties.variable_sample_locations.unwrap_or(false) {
+            if let Some(rasterization_samples) = self
+                .state()
+                .pipeline_graphics()
+                .and_then(|pipeline| pipeline.multisample_state())
+                .map(|multisample_state| multisample_state.rasterization_samples)
+            {
+                if per_pixel != rasterization_samples {
+                    return Err(SetDynamicStateError::SampleLocationsPerPixelMismatch {
+                        per_pixel,
+                        rasterization_samples,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Sets the dynamic scissors for future draw calls.
     ///
     /// # Panics
@@ -1623,6 +2019,52 @@ where
 
         Ok(())
     }
+
+    /// Sets the device mask for future commands, when the device was created from a device group.
+    ///
+    /// Each bit of `device_mask` is the device index of a physical device in
+    /// [`Device::physical_devices`](crate::device::Device::physical_devices). Subsequent commands
+    /// are only executed on the physical devices whose bit is set.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the device API version is less than 1.1 and the
+    ///   [`khr_device_group`](crate::device::DeviceExtensions::khr_device_group) extension is not
+    ///   enabled on the device.
+    /// - Panics if `device_mask` is zero, or contains bits for physical devices that the device
+    ///   was not created from.
+    pub fn set_device_mask(&mut self, device_mask: u32) -> &mut Self {
+        self.validate_set_device_mask(device_mask).unwrap();
+
+        unsafe {
+            self.inner.set_device_mask(device_mask);
+        }
+
+        self
+    }
+
+    fn validate_set_device_mask(&self, device_mask: u32) -> Result<(), SetDynamicStateError> {
+        if !(self.device().api_version() >= Version::V1_1
+            || self.device().enabled_extensions().khr_device_group)
+        {
+            return Err(SetDynamicStateError::RequirementNotMet {
+                required_for: "`set_device_mask`",
+                requires_one_of: RequiresOneOf {
+                    api_version: Some(Version::V1_1),
+                    device_extensions: &["khr_device_group"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkCmdSetDeviceMask-deviceMask-00108
+        // VUID-vkCmdSetDeviceMask-deviceMask-00109
+        if device_mask == 0 || device_mask & !self.device().all_devices_mask() != 0 {
+            return Err(SetDynamicStateError::DeviceMaskInvalid { device_mask });
+        }
+
+        Ok(())
+    }
 }
 
 impl SyncCommandBufferBuilder {
@@ -1792,6 +2234,27 @@ impl SyncCommandBufferBuilder {
         self.current_state.depth_bounds_test_enable = Some(enable);
     }
 
+    /// Calls `vkCmdSetDepthClampEnableEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_depth_clamp_enable(&mut self, enable: bool) {
+        struct Cmd {
+            enable: bool,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "set_depth_clamp_enable"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.set_depth_clamp_enable(self.enable);
+            }
+        }
+
+        self.commands.push(Box::new(Cmd { enable }));
+        self.current_state.depth_clamp_enable = Some(enable);
+    }
+
     /// Calls `vkCmdSetDepthCompareOpEXT` on the builder.
     #[inline]
     pub unsafe fn set_depth_compare_op(&mut self, compare_op: CompareOp) {
@@ -1891,6 +2354,38 @@ impl SyncCommandBufferBuilder {
         }));
     }
 
+    /// Calls `vkCmdSetFragmentShadingRateKHR` on the builder.
+    #[inline]
+    pub unsafe fn set_fragment_shading_rate(
+        &mut self,
+        fragment_size: [u32; 2],
+        combiner_ops: [FragmentShadingRateCombinerOp; 2],
+    ) {
+        struct Cmd {
+            fragment_size: [u32; 2],
+            combiner_ops: [FragmentShadingRateCombinerOp; 2],
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "set_fragment_shading_rate"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.set_fragment_shading_rate(self.fragment_size, self.combiner_ops);
+            }
+        }
+
+        self.commands.push(Box::new(Cmd {
+            fragment_size,
+            combiner_ops,
+        }));
+        self.current_state.fragment_shading_rate = Some(FragmentShadingRate {
+            fragment_size,
+            combiner_ops,
+        });
+    }
+
     /// Calls `vkCmdSetFrontFaceEXT` on the builder.
     #[inline]
     pub unsafe fn set_front_face(&mut self, face: FrontFace) {
@@ -1997,6 +2492,27 @@ impl SyncCommandBufferBuilder {
         self.current_state.patch_control_points = Some(num);
     }
 
+    /// Calls `vkCmdSetPolygonModeEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_polygon_mode(&mut self, polygon_mode: PolygonMode) {
+        struct Cmd {
+            polygon_mode: PolygonMode,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "set_polygon_mode"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.set_polygon_mode(self.polygon_mode);
+            }
+        }
+
+        self.commands.push(Box::new(Cmd { polygon_mode }));
+        self.current_state.polygon_mode = Some(polygon_mode);
+    }
+
     /// Calls `vkCmdSetPrimitiveRestartEnableEXT` on the builder.
     #[inline]
     pub unsafe fn set_primitive_restart_enable(&mut self, enable: bool) {
@@ -2060,6 +2576,29 @@ impl SyncCommandBufferBuilder {
         self.current_state.rasterizer_discard_enable = Some(enable);
     }
 
+    /// Calls `vkCmdSetSampleLocationsEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_sample_locations(&mut self, sample_locations: SampleLocations) {
+        struct Cmd {
+            sample_locations: SampleLocations,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "set_sample_locations"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.set_sample_locations(&self.sample_locations);
+            }
+        }
+
+        self.commands.push(Box::new(Cmd {
+            sample_locations: sample_locations.clone(),
+        }));
+        self.current_state.sample_locations = Some(sample_locations);
+    }
+
     /// Calls `vkCmdSetStencilCompareMask` on the builder.
     #[inline]
     pub unsafe fn set_stencil_compare_mask(&mut self, faces: StencilFaces, compare_mask: u32) {
@@ -2364,6 +2903,26 @@ impl SyncCommandBufferBuilder {
             viewports: Mutex::new(viewports),
         }));
     }
+
+    /// Calls `vkCmdSetDeviceMask` on the builder.
+    #[inline]
+    pub unsafe fn set_device_mask(&mut self, device_mask: u32) {
+        struct Cmd {
+            device_mask: u32,
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "set_device_mask"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.set_device_mask(self.device_mask);
+            }
+        }
+
+        self.commands.push(Box::new(Cmd { device_mask }));
+    }
 }
 
 impl UnsafeCommandBufferBuilder {
@@ -2451,6 +3010,20 @@ impl UnsafeCommandBufferBuilder {
         }
     }
 
+    /// Calls `vkCmdSetDepthClampEnableEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_depth_clamp_enable(&mut self, enable: bool) {
+        debug_assert!(self.device.enabled_extensions().ext_extended_dynamic_state3);
+        debug_assert!(
+            self.device
+                .enabled_features()
+                .extended_dynamic_state3_depth_clamp_enable
+        );
+        let fns = self.device.fns();
+        (fns.ext_extended_dynamic_state3
+            .cmd_set_depth_clamp_enable_ext)(self.handle, enable.into());
+    }
+
     /// Calls `vkCmdSetDepthCompareOpEXT` on the builder.
     #[inline]
     pub unsafe fn set_depth_compare_op(&mut self, compare_op: CompareOp) {
@@ -2534,6 +3107,25 @@ impl UnsafeCommandBufferBuilder {
         );
     }
 
+    /// Calls `vkCmdSetFragmentShadingRateKHR` on the builder.
+    #[inline]
+    pub unsafe fn set_fragment_shading_rate(
+        &mut self,
+        fragment_size: [u32; 2],
+        combiner_ops: [FragmentShadingRateCombinerOp; 2],
+    ) {
+        debug_assert!(self.device.enabled_extensions().khr_fragment_shading_rate);
+        let fragment_size = ash::vk::Extent2D {
+            width: fragment_size[0],
+            height: fragment_size[1],
+        };
+        let combiner_ops = combiner_ops.map(Into::into);
+
+        let fns = self.device.fns();
+        (fns.khr_fragment_shading_rate
+            .cmd_set_fragment_shading_rate_khr)(self.handle, &fragment_size, &combiner_ops);
+    }
+
     /// Calls `vkCmdSetFrontFaceEXT` on the builder.
     #[inline]
     pub unsafe fn set_front_face(&mut self, face: FrontFace) {
@@ -2585,6 +3177,22 @@ impl UnsafeCommandBufferBuilder {
             .cmd_set_patch_control_points_ext)(self.handle, num);
     }
 
+    /// Calls `vkCmdSetPolygonModeEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_polygon_mode(&mut self, polygon_mode: PolygonMode) {
+        debug_assert!(self.device.enabled_extensions().ext_extended_dynamic_state3);
+        debug_assert!(
+            self.device
+                .enabled_features()
+                .extended_dynamic_state3_polygon_mode
+        );
+        let fns = self.device.fns();
+        (fns.ext_extended_dynamic_state3.cmd_set_polygon_mode_ext)(
+            self.handle,
+            polygon_mode.into(),
+        );
+    }
+
     /// Calls `vkCmdSetPrimitiveRestartEnableEXT` on the builder.
     #[inline]
     pub unsafe fn set_primitive_restart_enable(&mut self, enable: bool) {
@@ -2627,6 +3235,20 @@ impl UnsafeCommandBufferBuilder {
         }
     }
 
+    /// Calls `vkCmdSetSampleLocationsEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_sample_locations(&mut self, sample_locations: &SampleLocations) {
+        debug_assert!(self.device.enabled_extensions().ext_sample_locations);
+        let locations_vk = sample_locations.locations_vk();
+        let sample_locations_info = sample_locations.to_vk(&locations_vk);
+
+        let fns = self.device.fns();
+        (fns.ext_sample_locations.cmd_set_sample_locations_ext)(
+            self.handle,
+            &sample_locations_info,
+        );
+    }
+
     /// Calls `vkCmdSetStencilCompareMask` on the builder.
     #[inline]
     pub unsafe fn set_stencil_compare_mask(&mut self, face_mask: StencilFaces, compare_mask: u32) {
@@ -2810,6 +3432,19 @@ impl UnsafeCommandBufferBuilder {
             );
         }
     }
+
+    /// Calls `vkCmdSetDeviceMask` on the builder.
+    #[inline]
+    pub unsafe fn set_device_mask(&mut self, device_mask: u32) {
+        let fns = self.device.fns();
+
+        if self.device.api_version() >= Version::V1_1 {
+            (fns.v1_1.cmd_set_device_mask)(self.handle, device_mask);
+        } else {
+            debug_assert!(self.device.enabled_extensions().khr_device_group);
+            (fns.khr_device_group.cmd_set_device_mask_khr)(self.handle, device_mask);
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -2820,9 +3455,16 @@ enum SetDynamicStateError {
         requires_one_of: RequiresOneOf,
     },
 
+    /// The provided `device_mask` is zero, or contains bits for physical devices that the device
+    /// was not created from.
+    DeviceMaskInvalid { device_mask: u32 },
+
     /// The provided `factor` is not between 1 and 256 inclusive.
     FactorOutOfRange,
 
+    /// An element of the provided `fragment_size` is not 1, 2 or 4.
+    FragmentSizeInvalid { provided: [u32; 2] },
+
     /// The [`max_discard_rectangles`](crate::device::Properties::max_discard_rectangles)
     /// limit has been exceeded.
     MaxDiscardRectanglesExceeded { provided: u32, max: u32 },
@@ -2835,9 +3477,17 @@ enum SetDynamicStateError {
     /// limit has been exceeded.
     MaxViewportsExceeded { provided: u32, max: u32 },
 
+    /// A provided combiner operation is not `Keep` or `Replace`, but the
+    /// [`fragment_shading_rate_non_trivial_combiner_ops`](crate::device::Properties::fragment_shading_rate_non_trivial_combiner_ops)
+    /// property is `false`.
+    NonTrivialCombinerOpsNotSupported,
+
     /// The queue family doesn't allow this operation.
     NotSupportedByQueueFamily,
 
+    /// The provided number of patch control points is zero.
+    PatchControlPointsZero,
+
     /// The provided item count is different from the number of attachments in the color blend
     /// state of the currently bound pipeline.
     PipelineColorBlendAttachmentCountMismatch {
@@ -2848,6 +3498,35 @@ enum SetDynamicStateError {
     /// The currently bound pipeline contains this state as internally fixed state, which cannot be
     /// overridden with dynamic state.
     PipelineHasFixedState,
+
+    /// The number of provided sample locations does not match the sample count and grid size.
+    /// `required` is `None` if the required number overflows a `u32`.
+    SampleLocationsCountMismatch {
+        provided: u32,
+        required: Option<u32>,
+    },
+
+    /// The grid size of the sample locations does not evenly divide the
+    /// [`max_sample_location_grid_size`](crate::pipeline::graphics::multisample::MultisampleProperties::max_sample_location_grid_size)
+    /// for the number of samples per pixel.
+    SampleLocationsGridSizeNotSupported {
+        grid_size: [u32; 2],
+        max_grid_size: [u32; 2],
+    },
+
+    /// The `per_pixel` value of the sample locations does not match the `rasterization_samples`
+    /// of the currently bound pipeline, and the
+    /// [`variable_sample_locations`](crate::device::Properties::variable_sample_locations) device
+    /// property is `false`.
+    SampleLocationsPerPixelMismatch {
+        per_pixel: SampleCount,
+        rasterization_samples: SampleCount,
+    },
+
+    /// The `per_pixel` value of the sample locations is not included in the
+    /// [`sample_location_sample_counts`](crate::device::Properties::sample_location_sample_counts)
+    /// device property.
+    SampleLocationsPerPixelNotSupported { per_pixel: SampleCount },
 }
 
 impl Error for SetDynamicStateError {}
@@ -2863,10 +3542,21 @@ impl Display for SetDynamicStateError {
                 "a requirement was not met for: {}; requires one of: {}",
                 required_for, requires_one_of,
             ),
+            Self::DeviceMaskInvalid { device_mask } => write!(
+                f,
+                "the provided `device_mask` ({:#b}) is zero, or contains bits for physical devices \
+                that the device was not created from",
+                device_mask,
+            ),
             Self::FactorOutOfRange => write!(
                 f,
                 "the provided `factor` is not between 1 and 256 inclusive",
             ),
+            Self::FragmentSizeInvalid { provided } => write!(
+                f,
+                "an element of the provided `fragment_size` ({:?}) is not 1, 2 or 4",
+                provided,
+            ),
             Self::MaxDiscardRectanglesExceeded { .. } => {
                 write!(f, "the `max_discard_rectangles` limit has been exceeded")
             }
@@ -2877,9 +3567,17 @@ impl Display for SetDynamicStateError {
             Self::MaxViewportsExceeded { .. } => {
                 write!(f, "the `max_viewports` limit has been exceeded")
             }
+            Self::NonTrivialCombinerOpsNotSupported => write!(
+                f,
+                "a provided combiner operation is not `Keep` or `Replace`, but the \
+                `fragment_shading_rate_non_trivial_combiner_ops` property is `false`",
+            ),
             Self::NotSupportedByQueueFamily => {
                 write!(f, "the queue family doesn't allow this operation")
             }
+            Self::PatchControlPointsZero => {
+                write!(f, "the provided number of patch control points is zero")
+            }
             Self::PipelineColorBlendAttachmentCountMismatch {
                 provided_count,
                 required_count,
@@ -2894,6 +3592,30 @@ impl Display for SetDynamicStateError {
                 "the currently bound pipeline contains this state as internally fixed state, which \
                 cannot be overridden with dynamic state",
             ),
+            Self::SampleLocationsCountMismatch { provided, .. } => write!(
+                f,
+                "the number of provided sample locations ({}) does not match the number required \
+                by the sample count and grid size",
+                provided,
+            ),
+            Self::SampleLocationsGridSizeNotSupported {
+                grid_size,
+                max_grid_size,
+            } => write!(
+                f,
+                "the grid size of the sample locations ({:?}) does not evenly divide the maximum \
+                grid size supported by the device ({:?})",
+                grid_size, max_grid_size,
+            ),
+            Self::SampleLocationsPerPixelMismatch { .. } => write!(
+                f,
+                "the `per_pixel` value of the sample locations does not match the \
+                `rasterization_samples` of the currently bound pipeline",
+            ),
+            Self::SampleLocationsPerPixelNotSupported { .. } => write!(
+                f,
+                "the `per_pixel` value of the sample locations is not supported by the device",
+            ),
         }
     }
 }
@@ -2906,3 +3628,54 @@ impl From<RequirementNotMet> for SetDynamicStateError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SetDynamicStateError;
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+        },
+        pipeline::graphics::fragment_shading_rate::FragmentShadingRateCombinerOp,
+    };
+
+    #[test]
+    fn set_fragment_shading_rate_without_feature() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device);
+        let builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            builder.validate_set_fragment_shading_rate(
+                [1, 1],
+                [FragmentShadingRateCombinerOp::Keep; 2],
+            ),
+            Err(SetDynamicStateError::RequirementNotMet { .. })
+        ));
+    }
+
+    #[test]
+    fn set_patch_control_points_zero() {
+        let (device, queue) = gfx_dev_and_queue!(
+            extended_dynamic_state2_patch_control_points;
+            extensions: ext_extended_dynamic_state2
+        );
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device);
+        let builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            builder.validate_set_patch_control_points(0),
+            Err(SetDynamicStateError::PatchControlPointsZero)
+        ));
+    }
+}
@@ -22,6 +22,7 @@ use super::synced::SyncCommandBufferBuilderError;
 use crate::{
     format::Format,
     image::{ImageAspects, ImageLayout, SampleCount, SampleCounts},
+    memory::DeviceMemoryError,
     DeviceSize, RequirementNotMet, RequiresOneOf,
 };
 use std::{
@@ -32,6 +33,7 @@ use std::{
 /// Error that can happen when recording a copy command.
 #[derive(Clone, Debug)]
 pub enum CopyError {
+    DeviceMemoryAllocationError(DeviceMemoryError),
     SyncCommandBufferBuilderError(SyncCommandBufferBuilderError),
 
     RequirementNotMet {
@@ -253,6 +255,11 @@ pub enum CopyError {
         subresource_extent: [u32; 3],
     },
 
+    /// The size of a region is zero.
+    RegionSizeZero {
+        region_index: usize,
+    },
+
     /// An image has a sample count that is not valid for this operation.
     SampleCountInvalid {
         resource: CopyErrorResource,
@@ -278,6 +285,7 @@ pub enum CopyError {
 impl Error for CopyError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
+            Self::DeviceMemoryAllocationError(err) => Some(err),
             Self::SyncCommandBufferBuilderError(err) => Some(err),
             _ => None,
         }
@@ -287,6 +295,9 @@ impl Error for CopyError {
 impl Display for CopyError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         match self {
+            Self::DeviceMemoryAllocationError(_) => {
+                write!(f, "allocating a staging buffer failed")
+            }
             Self::SyncCommandBufferBuilderError(_) => write!(f, "a SyncCommandBufferBuilderError"),
             Self::RequirementNotMet {
                 required_for,
@@ -566,6 +577,9 @@ impl Display for CopyError {
                 than the extent of the selected subresource of the {} image ({:?})",
                 resource, offset_range_end, region_index, resource, subresource_extent,
             ),
+            Self::RegionSizeZero { region_index } => {
+                write!(f, "the size of region {} is zero", region_index)
+            }
             Self::SampleCountInvalid {
                 resource,
                 sample_count,
@@ -600,6 +614,12 @@ impl Display for CopyError {
     }
 }
 
+impl From<DeviceMemoryError> for CopyError {
+    fn from(err: DeviceMemoryError) -> Self {
+        Self::DeviceMemoryAllocationError(err)
+    }
+}
+
 impl From<SyncCommandBufferBuilderError> for CopyError {
     fn from(err: SyncCommandBufferBuilderError) -> Self {
         Self::SyncCommandBufferBuilderError(err)
@@ -33,9 +33,9 @@ use crate::{
         PipelineLayout,
     },
     sampler::{Sampler, SamplerImageViewIncompatibleError},
-    shader::{DescriptorRequirements, ShaderScalarType, ShaderStage},
+    shader::{DescriptorBindingRequirements, ShaderScalarType, ShaderStage},
     sync::{AccessFlags, PipelineMemoryAccess, PipelineStages},
-    DeviceSize, RequiresOneOf, VulkanObject,
+    DeviceSize, RequiresOneOf, Version, VulkanObject,
 };
 use std::{
     borrow::Cow,
@@ -65,11 +65,7 @@ where
     ) -> Result<&mut Self, PipelineExecutionError> {
         self.validate_dispatch(group_counts)?;
 
-        unsafe {
-            self.inner.dispatch(group_counts)?;
-        }
-
-        Ok(self)
+        unsafe { self.dispatch_unchecked(group_counts) }
     }
 
     fn validate_dispatch(&self, group_counts: [u32; 3]) -> Result<(), PipelineExecutionError> {
@@ -113,6 +109,155 @@ where
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn dispatch_unchecked(
+        &mut self,
+        group_counts: [u32; 3],
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        self.inner.dispatch(group_counts)?;
+
+        Ok(self)
+    }
+
+    /// Perform a single compute operation using a compute pipeline, with the workgroup IDs offset
+    /// by `base_group`.
+    ///
+    /// A compute pipeline must have been bound using
+    /// [`bind_pipeline_compute`](Self::bind_pipeline_compute). Any resources used by the compute
+    /// pipeline, such as descriptor sets, must have been set beforehand. If `base_group` is not
+    /// all zeroes, the pipeline must have been created with the
+    /// [`dispatch_base`](crate::pipeline::PipelineCreateFlags::dispatch_base) flag.
+    ///
+    /// The device API version must be at least 1.1, or the
+    /// [`khr_device_group`](crate::device::DeviceExtensions::khr_device_group) extension must be
+    /// enabled on the device.
+    pub fn dispatch_base(
+        &mut self,
+        base_group: [u32; 3],
+        group_counts: [u32; 3],
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        self.validate_dispatch_base(base_group, group_counts)?;
+
+        unsafe { self.dispatch_base_unchecked(base_group, group_counts) }
+    }
+
+    fn validate_dispatch_base(
+        &self,
+        base_group: [u32; 3],
+        group_counts: [u32; 3],
+    ) -> Result<(), PipelineExecutionError> {
+        let device = self.device();
+
+        if !(device.api_version() >= Version::V1_1 || device.enabled_extensions().khr_device_group)
+        {
+            return Err(PipelineExecutionError::RequirementNotMet {
+                required_for: "`dispatch_base`",
+                requires_one_of: RequiresOneOf {
+                    api_version: Some(Version::V1_1),
+                    device_extensions: &["khr_device_group"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let queue_family_properties = self.queue_family_properties();
+
+        // VUID-vkCmdDispatchBase-commandBuffer-cmdpool
+        if !queue_family_properties.queue_flags.compute {
+            return Err(PipelineExecutionError::NotSupportedByQueueFamily);
+        }
+
+        // VUID-vkCmdDispatchBase-renderpass
+        if self.render_pass_state.is_some() {
+            return Err(PipelineExecutionError::ForbiddenInsideRenderPass);
+        }
+
+        // VUID-vkCmdDispatchBase-None-02700
+        let pipeline = match self.state().pipeline_compute() {
+            Some(x) => x.as_ref(),
+            None => return Err(PipelineExecutionError::PipelineNotBound),
+        };
+
+        self.validate_pipeline_descriptor_sets(pipeline, pipeline.descriptor_requirements())?;
+        self.validate_pipeline_push_constants(pipeline.layout())?;
+
+        // VUID-vkCmdDispatchBase-baseGroupX-00427
+        if base_group != [0; 3] && !pipeline.flags().dispatch_base {
+            return Err(PipelineExecutionError::PipelineDispatchBaseNotEnabled);
+        }
+
+        let max = device
+            .physical_device()
+            .properties()
+            .max_compute_work_group_count;
+
+        // VUID-vkCmdDispatchBase-baseGroupX-00421
+        // VUID-vkCmdDispatchBase-baseGroupY-00422
+        // VUID-vkCmdDispatchBase-baseGroupZ-00423
+        // VUID-vkCmdDispatchBase-groupCountX-00424
+        // VUID-vkCmdDispatchBase-groupCountY-00425
+        // VUID-vkCmdDispatchBase-groupCountZ-00426
+        if (0..3).any(|i| base_group[i] >= max[i] || group_counts[i] > max[i] - base_group[i]) {
+            return Err(PipelineExecutionError::MaxComputeWorkGroupCountExceeded {
+                requested: [
+                    base_group[0].saturating_add(group_counts[0]),
+                    base_group[1].saturating_add(group_counts[1]),
+                    base_group[2].saturating_add(group_counts[2]),
+                ],
+                max,
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn dispatch_base_unchecked(
+        &mut self,
+        base_group: [u32; 3],
+        group_counts: [u32; 3],
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        self.inner.dispatch_base(base_group, group_counts)?;
+
+        Ok(self)
+    }
+
+    /// Perform a single compute operation using a compute pipeline, running at least `work_size`
+    /// invocations in each dimension.
+    ///
+    /// The number of workgroups is calculated from the
+    /// [`local_size`](crate::pipeline::ComputePipeline::local_size) of the bound compute pipeline,
+    /// rounding up. If `work_size` is not a multiple of the local size, the shader must check the
+    /// global invocation ID against the work size itself. If the shader does not do this, set
+    /// `exact_cover` to `true`, so that an error is returned instead.
+    ///
+    /// A compute pipeline must have been bound using
+    /// [`bind_pipeline_compute`](Self::bind_pipeline_compute). Any resources used by the compute
+    /// pipeline, such as descriptor sets, must have been set beforehand.
+    pub fn dispatch_work_size(
+        &mut self,
+        work_size: [u32; 3],
+        exact_cover: bool,
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        let pipeline = match self.state().pipeline_compute() {
+            Some(x) => x.as_ref(),
+            None => return Err(PipelineExecutionError::PipelineNotBound),
+        };
+
+        let local_size = pipeline.local_size();
+
+        if exact_cover && (0..3).any(|i| work_size[i] % local_size[i].max(1) != 0) {
+            return Err(PipelineExecutionError::WorkSizeNotMultipleOfLocalSize {
+                work_size,
+                local_size,
+            });
+        }
+
+        let group_counts = pipeline.group_counts(work_size);
+
+        self.dispatch(group_counts)
+    }
+
     /// Perform multiple compute operations using a compute pipeline. One dispatch is performed for
     /// each [`DispatchIndirectCommand`] struct in `indirect_buffer`.
     ///
@@ -128,11 +273,7 @@ where
     {
         self.validate_dispatch_indirect(&indirect_buffer)?;
 
-        unsafe {
-            self.inner.dispatch_indirect(indirect_buffer)?;
-        }
-
-        Ok(self)
+        unsafe { self.dispatch_indirect_unchecked(indirect_buffer) }
     }
 
     fn validate_dispatch_indirect(
@@ -164,6 +305,19 @@ where
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn dispatch_indirect_unchecked<Inb>(
+        &mut self,
+        indirect_buffer: Arc<Inb>,
+    ) -> Result<&mut Self, PipelineExecutionError>
+    where
+        Inb: TypedBufferAccess<Content = [DispatchIndirectCommand]> + 'static,
+    {
+        self.inner.dispatch_indirect(indirect_buffer)?;
+
+        Ok(self)
+    }
+
     /// Perform a single draw operation using a graphics pipeline.
     ///
     /// The parameters specify the first vertex and the number of vertices to draw, and the first
@@ -184,18 +338,7 @@ where
     ) -> Result<&mut Self, PipelineExecutionError> {
         self.validate_draw(vertex_count, instance_count, first_vertex, first_instance)?;
 
-        unsafe {
-            self.inner
-                .draw(vertex_count, instance_count, first_vertex, first_instance)?;
-        }
-
-        if let RenderPassStateType::BeginRendering(state) =
-            &mut self.render_pass_state.as_mut().unwrap().render_pass
-        {
-            state.pipeline_used = true;
-        }
-
-        Ok(self)
+        unsafe { self.draw_unchecked(vertex_count, instance_count, first_vertex, first_instance) }
     }
 
     fn validate_draw(
@@ -230,6 +373,26 @@ where
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn draw_unchecked(
+        &mut self,
+        vertex_count: u32,
+        instance_count: u32,
+        first_vertex: u32,
+        first_instance: u32,
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        self.inner
+            .draw(vertex_count, instance_count, first_vertex, first_instance)?;
+
+        if let Some(render_pass_state) = self.render_pass_state.as_mut() {
+            if let RenderPassStateType::BeginRendering(state) = &mut render_pass_state.render_pass {
+                state.pipeline_used = true;
+            }
+        }
+
+        Ok(self)
+    }
+
     /// Perform multiple draw operations using a graphics pipeline.
     ///
     /// One draw is performed for each [`DrawIndirectCommand`] struct in `indirect_buffer`.
@@ -256,18 +419,7 @@ where
         let stride = size_of::<DrawIndirectCommand>() as u32;
         self.validate_draw_indirect(&indirect_buffer, draw_count, stride)?;
 
-        unsafe {
-            self.inner
-                .draw_indirect(indirect_buffer, draw_count, stride)?;
-        }
-
-        if let RenderPassStateType::BeginRendering(state) =
-            &mut self.render_pass_state.as_mut().unwrap().render_pass
-        {
-            state.pipeline_used = true;
-        }
-
-        Ok(self)
+        unsafe { self.draw_indirect_unchecked(indirect_buffer) }
     }
 
     fn validate_draw_indirect(
@@ -324,6 +476,28 @@ where
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn draw_indirect_unchecked<Inb>(
+        &mut self,
+        indirect_buffer: Arc<Inb>,
+    ) -> Result<&mut Self, PipelineExecutionError>
+    where
+        Inb: TypedBufferAccess<Content = [DrawIndirectCommand]> + Send + Sync + 'static,
+    {
+        let draw_count = indirect_buffer.len() as u32;
+        let stride = size_of::<DrawIndirectCommand>() as u32;
+        self.inner
+            .draw_indirect(indirect_buffer, draw_count, stride)?;
+
+        if let Some(render_pass_state) = self.render_pass_state.as_mut() {
+            if let RenderPassStateType::BeginRendering(state) = &mut render_pass_state.render_pass {
+                state.pipeline_used = true;
+            }
+        }
+
+        Ok(self)
+    }
+
     /// Perform a single draw operation using a graphics pipeline, using an index buffer.
     ///
     /// The parameters specify the first index and the number of indices in the index buffer that
@@ -359,22 +533,14 @@ where
         )?;
 
         unsafe {
-            self.inner.draw_indexed(
+            self.draw_indexed_unchecked(
                 index_count,
                 instance_count,
                 first_index,
                 vertex_offset,
                 first_instance,
-            )?;
-        }
-
-        if let RenderPassStateType::BeginRendering(state) =
-            &mut self.render_pass_state.as_mut().unwrap().render_pass
-        {
-            state.pipeline_used = true;
+            )
         }
-
-        Ok(self)
     }
 
     fn validate_draw_indexed(
@@ -414,6 +580,32 @@ where
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn draw_indexed_unchecked(
+        &mut self,
+        index_count: u32,
+        instance_count: u32,
+        first_index: u32,
+        vertex_offset: i32,
+        first_instance: u32,
+    ) -> Result<&mut Self, PipelineExecutionError> {
+        self.inner.draw_indexed(
+            index_count,
+            instance_count,
+            first_index,
+            vertex_offset,
+            first_instance,
+        )?;
+
+        if let Some(render_pass_state) = self.render_pass_state.as_mut() {
+            if let RenderPassStateType::BeginRendering(state) = &mut render_pass_state.render_pass {
+                state.pipeline_used = true;
+            }
+        }
+
+        Ok(self)
+    }
+
     /// Perform multiple draw operations using a graphics pipeline, using an index buffer.
     ///
     /// One draw is performed for each [`DrawIndexedIndirectCommand`] struct in `indirect_buffer`.
@@ -445,18 +637,7 @@ where
         let stride = size_of::<DrawIndexedIndirectCommand>() as u32;
         self.validate_draw_indexed_indirect(&indirect_buffer, draw_count, stride)?;
 
-        unsafe {
-            self.inner
-                .draw_indexed_indirect(indirect_buffer, draw_count, stride)?;
-        }
-
-        if let RenderPassStateType::BeginRendering(state) =
-            &mut self.render_pass_state.as_mut().unwrap().render_pass
-        {
-            state.pipeline_used = true;
-        }
-
-        Ok(self)
+        unsafe { self.draw_indexed_indirect_unchecked(indirect_buffer) }
     }
 
     fn validate_draw_indexed_indirect(
@@ -514,6 +695,28 @@ where
         Ok(())
     }
 
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn draw_indexed_indirect_unchecked<Inb>(
+        &mut self,
+        indirect_buffer: Arc<Inb>,
+    ) -> Result<&mut Self, PipelineExecutionError>
+    where
+        Inb: TypedBufferAccess<Content = [DrawIndexedIndirectCommand]> + 'static,
+    {
+        let draw_count = indirect_buffer.len() as u32;
+        let stride = size_of::<DrawIndexedIndirectCommand>() as u32;
+        self.inner
+            .draw_indexed_indirect(indirect_buffer, draw_count, stride)?;
+
+        if let Some(render_pass_state) = self.render_pass_state.as_mut() {
+            if let RenderPassStateType::BeginRendering(state) = &mut render_pass_state.render_pass {
+                state.pipeline_used = true;
+            }
+        }
+
+        Ok(self)
+    }
+
     fn validate_index_buffer(
         &self,
         indices: Option<(u32, u32)>,
@@ -553,8 +756,14 @@ where
             return Err(PipelineExecutionError::IndirectBufferMissingUsage);
         }
 
+        let offset = buffer.inner().offset;
+
         // VUID-vkCmdDispatchIndirect-offset-02710
-        // TODO:
+        // VUID-vkCmdDrawIndirect-offset-02710
+        // VUID-vkCmdDrawIndexedIndirect-offset-02710
+        if offset % 4 != 0 {
+            return Err(PipelineExecutionError::IndirectBufferOffsetNotAligned { offset });
+        }
 
         Ok(())
     }
@@ -562,13 +771,16 @@ where
     fn validate_pipeline_descriptor_sets<'a, Pl: Pipeline>(
         &self,
         pipeline: &Pl,
-        descriptor_requirements: impl IntoIterator<Item = ((u32, u32), &'a DescriptorRequirements)>,
+        descriptor_requirements: impl IntoIterator<
+            Item = ((u32, u32), &'a DescriptorBindingRequirements),
+        >,
     ) -> Result<(), PipelineExecutionError> {
         fn validate_resources<T>(
             set_num: u32,
             binding_num: u32,
-            reqs: &DescriptorRequirements,
+            reqs: &DescriptorBindingRequirements,
             elements: &[Option<T>],
+            is_null: impl Fn(u32) -> bool,
             mut extra_check: impl FnMut(u32, &T) -> Result<(), DescriptorResourceInvalidError>,
         ) -> Result<(), PipelineExecutionError> {
             let elements_to_check = if let Some(descriptor_count) = reqs.descriptor_count {
@@ -595,6 +807,9 @@ where
                 // VUID-vkCmdDispatch-None-02699
                 let element = match element {
                     Some(x) => x,
+                    // Null descriptors were written explicitly, and are allowed by the
+                    // `null_descriptor` feature.
+                    None if is_null(index) => continue,
                     None => {
                         return Err(PipelineExecutionError::DescriptorResourceInvalid {
                             set_num,
@@ -643,7 +858,18 @@ where
             let layout_binding =
                 &pipeline.layout().set_layouts()[set_num as usize].bindings()[&binding_num];
 
-            let check_buffer = |_index: u32, _buffer: &Arc<dyn BufferAccess>| Ok(());
+            let check_buffer = |_index: u32, buffer: &Arc<dyn BufferAccess>| {
+                if let Some(required) = reqs.buffer_block_size {
+                    if buffer.size() < required {
+                        return Err(DescriptorResourceInvalidError::BufferTooSmall {
+                            required,
+                            provided: buffer.size(),
+                        });
+                    }
+                }
+
+                Ok(())
+            };
 
             let check_buffer_view = |index: u32, buffer_view: &Arc<dyn BufferViewAbstract>| {
                 if layout_binding.descriptor_type == DescriptorType::StorageTexelBuffer {
@@ -883,19 +1109,41 @@ where
             };
 
             let binding_resources = set_resources.binding(binding_num).unwrap();
+            let is_null = |index| set_resources.is_null(binding_num, index);
 
             match binding_resources {
                 DescriptorBindingResources::None(elements) => {
-                    validate_resources(set_num, binding_num, reqs, elements, check_none)?;
+                    validate_resources(set_num, binding_num, reqs, elements, is_null, check_none)?;
                 }
                 DescriptorBindingResources::Buffer(elements) => {
-                    validate_resources(set_num, binding_num, reqs, elements, check_buffer)?;
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        reqs,
+                        elements,
+                        is_null,
+                        check_buffer,
+                    )?;
                 }
                 DescriptorBindingResources::BufferView(elements) => {
-                    validate_resources(set_num, binding_num, reqs, elements, check_buffer_view)?;
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        reqs,
+                        elements,
+                        is_null,
+                        check_buffer_view,
+                    )?;
                 }
                 DescriptorBindingResources::ImageView(elements) => {
-                    validate_resources(set_num, binding_num, reqs, elements, check_image_view)?;
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        reqs,
+                        elements,
+                        is_null,
+                        check_image_view,
+                    )?;
                 }
                 DescriptorBindingResources::ImageViewSampler(elements) => {
                     validate_resources(
@@ -903,11 +1151,21 @@ where
                         binding_num,
                         reqs,
                         elements,
+                        is_null,
                         check_image_view_sampler,
                     )?;
                 }
+                // The contents of an inline uniform block are always valid to read.
+                DescriptorBindingResources::InlineUniformBlock => (),
                 DescriptorBindingResources::Sampler(elements) => {
-                    validate_resources(set_num, binding_num, reqs, elements, check_sampler)?;
+                    validate_resources(
+                        set_num,
+                        binding_num,
+                        reqs,
+                        elements,
+                        is_null,
+                        check_sampler,
+                    )?;
                 }
             }
         }
@@ -1027,6 +1285,12 @@ where
                         return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
                     }
                 }
+                DynamicState::DepthClampEnable => {
+                    // VUID-vkCmdDraw-None-07620
+                    if current_state.depth_clamp_enable().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
                 DynamicState::DepthCompareOp => {
                     // VUID?
                     if current_state.depth_compare_op().is_none() {
@@ -1062,7 +1326,12 @@ where
                     }
                 }
                 DynamicState::ExclusiveScissor => todo!(),
-                DynamicState::FragmentShadingRate => todo!(),
+                DynamicState::FragmentShadingRate => {
+                    // VUID?
+                    if current_state.fragment_shading_rate().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
                 DynamicState::FrontFace => {
                     // VUID?
                     if current_state.front_face().is_none() {
@@ -1093,6 +1362,12 @@ where
                         return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
                     }
                 }
+                DynamicState::PolygonMode => {
+                    // VUID-vkCmdDraw-None-07621
+                    if current_state.polygon_mode().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
                 DynamicState::PrimitiveRestartEnable => {
                     // VUID-vkCmdDraw-None-04879
                     let primitive_restart_enable =
@@ -1202,7 +1477,12 @@ where
                 DynamicState::RayTracingPipelineStackSize => unreachable!(
                     "RayTracingPipelineStackSize dynamic state should not occur on a graphics pipeline"
                 ),
-                DynamicState::SampleLocations => todo!(),
+                DynamicState::SampleLocations => {
+                    // VUID?
+                    if current_state.sample_locations().is_none() {
+                        return Err(PipelineExecutionError::DynamicStateNotSet { dynamic_state });
+                    }
+                }
                 DynamicState::Scissor => {
                     for num in 0..pipeline.viewport_state().unwrap().count().unwrap() {
                         // VUID?
@@ -1276,7 +1556,16 @@ where
                     }
                 }
                 DynamicState::VertexInput => todo!(),
-                DynamicState::VertexInputBindingStride => todo!(),
+                DynamicState::VertexInputBindingStride => {
+                    for &binding_num in pipeline.vertex_input_state().bindings.keys() {
+                        // VUID-vkCmdDraw-pStrides-04884
+                        if current_state.vertex_buffer_stride(binding_num).is_none() {
+                            return Err(PipelineExecutionError::DynamicStateNotSet {
+                                dynamic_state,
+                            });
+                        }
+                    }
+                }
                 DynamicState::Viewport => {
                     for num in 0..pipeline.viewport_state().unwrap().count().unwrap() {
                         // VUID?
@@ -1331,6 +1620,35 @@ where
                     // vkCmdSetViewportWithCountEXT must be 1
                 }
                 DynamicState::ViewportWScaling => todo!(),
+                DynamicState::TessellationDomainOrigin
+                | DynamicState::RasterizationSamples
+                | DynamicState::SampleMask
+                | DynamicState::AlphaToCoverageEnable
+                | DynamicState::AlphaToOneEnable
+                | DynamicState::LogicOpEnable
+                | DynamicState::ColorBlendEnable
+                | DynamicState::ColorBlendEquation
+                | DynamicState::ColorWriteMask
+                | DynamicState::RasterizationStream
+                | DynamicState::ConservativeRasterizationMode
+                | DynamicState::ExtraPrimitiveOverestimationSize
+                | DynamicState::DepthClipEnable
+                | DynamicState::SampleLocationsEnable
+                | DynamicState::ColorBlendAdvanced
+                | DynamicState::ProvokingVertexMode
+                | DynamicState::LineRasterizationMode
+                | DynamicState::LineStippleEnable
+                | DynamicState::DepthClipNegativeOneToOne
+                | DynamicState::ViewportWScalingEnable
+                | DynamicState::ViewportSwizzle
+                | DynamicState::CoverageToColorEnable
+                | DynamicState::CoverageToColorLocation
+                | DynamicState::CoverageModulationMode
+                | DynamicState::CoverageModulationTableEnable
+                | DynamicState::CoverageModulationTable
+                | DynamicState::ShadingRateImageEnable
+                | DynamicState::RepresentativeFragmentTestEnable
+                | DynamicState::CoverageReductionMode => todo!(),
             }
         }
 
@@ -1483,10 +1801,27 @@ where
             // VUID-vkCmdDraw-None-04007
             let vertex_buffer = match current_state.vertex_buffer(binding_num) {
                 Some(x) => x,
+                // A null vertex buffer reads zeroes, so it doesn't limit the vertex or instance
+                // range.
+                None if current_state.vertex_buffer_is_null(binding_num) => continue,
                 None => return Err(PipelineExecutionError::VertexBufferNotBound { binding_num }),
             };
 
-            let mut num_elements = vertex_buffer.size() as u64 / binding_desc.stride as u64;
+            let stride =
+                if pipeline.dynamic_state(DynamicState::VertexInputBindingStride) == Some(true) {
+                    current_state
+                        .vertex_buffer_stride(binding_num)
+                        .unwrap_or(binding_desc.stride as DeviceSize)
+                } else {
+                    binding_desc.stride as DeviceSize
+                };
+
+            // With a stride of 0, every vertex or instance reads the same element.
+            let mut num_elements = match stride {
+                0 if vertex_buffer.size() != 0 => u64::MAX,
+                0 => 0,
+                _ => vertex_buffer.size() as u64 / stride as u64,
+            };
 
             match binding_desc.input_rate {
                 VertexInputRate::Vertex => {
@@ -1550,7 +1885,7 @@ where
 
             let view_mask = match pipeline.render_pass() {
                 PipelineRenderPassType::BeginRenderPass(subpass) => {
-                    subpass.render_pass().views_used()
+                    subpass.subpass_desc().view_mask
                 }
                 PipelineRenderPassType::BeginRendering(rendering_info) => rendering_info.view_mask,
             };
@@ -1622,6 +1957,53 @@ impl SyncCommandBufferBuilder {
         Ok(())
     }
 
+    /// Calls `vkCmdDispatchBase` on the builder.
+    #[inline]
+    pub unsafe fn dispatch_base(
+        &mut self,
+        base_group: [u32; 3],
+        group_counts: [u32; 3],
+    ) -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            base_group: [u32; 3],
+            group_counts: [u32; 3],
+        }
+
+        impl Command for Cmd {
+            fn name(&self) -> &'static str {
+                "dispatch_base"
+            }
+
+            unsafe fn send(&self, out: &mut UnsafeCommandBufferBuilder) {
+                out.dispatch_base(self.base_group, self.group_counts);
+            }
+        }
+
+        let pipeline = self.current_state.pipeline_compute.as_ref().unwrap();
+
+        let mut resources = Vec::new();
+        self.add_descriptor_set_resources(
+            &mut resources,
+            PipelineBindPoint::Compute,
+            pipeline.descriptor_requirements(),
+        );
+
+        for resource in &resources {
+            self.check_resource_conflicts(resource)?;
+        }
+
+        self.commands.push(Box::new(Cmd {
+            base_group,
+            group_counts,
+        }));
+
+        for resource in resources {
+            self.add_resource(resource);
+        }
+
+        Ok(())
+    }
+
     /// Calls `vkCmdDispatchIndirect` on the builder.
     #[inline]
     pub unsafe fn dispatch_indirect(
@@ -1901,7 +2283,9 @@ impl SyncCommandBufferBuilder {
         &self,
         resources: &mut Vec<(Cow<'static, str>, Resource)>,
         pipeline_bind_point: PipelineBindPoint,
-        descriptor_requirements: impl IntoIterator<Item = ((u32, u32), &'a DescriptorRequirements)>,
+        descriptor_requirements: impl IntoIterator<
+            Item = ((u32, u32), &'a DescriptorBindingRequirements),
+        >,
     ) {
         let state = match self.current_state.descriptor_sets.get(&pipeline_bind_point) {
             Some(x) => x,
@@ -1923,11 +2307,11 @@ impl SyncCommandBufferBuilder {
                 continue;
             }
 
-            // TODO: Maybe include this on DescriptorRequirements?
+            // TODO: Maybe include this on DescriptorBindingRequirements?
             let access = PipelineMemoryAccess {
                 stages: reqs.stages.into(),
                 access: match descriptor_type {
-                    DescriptorType::Sampler => continue,
+                    DescriptorType::Sampler | DescriptorType::InlineUniformBlock => continue,
                     DescriptorType::CombinedImageSampler
                     | DescriptorType::SampledImage
                     | DescriptorType::StorageImage
@@ -2001,7 +2385,8 @@ impl SyncCommandBufferBuilder {
                 .binding(binding)
                 .unwrap()
             {
-                DescriptorBindingResources::None(_) => continue,
+                DescriptorBindingResources::None(_)
+                | DescriptorBindingResources::InlineUniformBlock => continue,
                 DescriptorBindingResources::Buffer(elements) => {
                     resources.extend(
                         access
@@ -2072,27 +2457,33 @@ impl SyncCommandBufferBuilder {
         resources: &mut Vec<(Cow<'static, str>, Resource)>,
         vertex_input: &VertexInputState,
     ) {
-        resources.extend(vertex_input.bindings.iter().map(|(&binding_num, _)| {
-            let vertex_buffer = &self.current_state.vertex_buffers[&binding_num];
-            (
-                format!("Vertex buffer binding {}", binding_num).into(),
-                Resource::Buffer {
-                    buffer: vertex_buffer.clone(),
-                    range: 0..vertex_buffer.size(), // TODO:
-                    memory: PipelineMemoryAccess {
-                        stages: PipelineStages {
-                            vertex_input: true,
-                            ..PipelineStages::empty()
-                        },
-                        access: AccessFlags {
-                            vertex_attribute_read: true,
-                            ..AccessFlags::empty()
+        resources.extend(
+            vertex_input
+                .bindings
+                .iter()
+                .filter_map(|(&binding_num, _)| {
+                    // Null vertex buffers have no resource to synchronize.
+                    let vertex_buffer = self.current_state.vertex_buffers.get(&binding_num)?;
+                    Some((
+                        format!("Vertex buffer binding {}", binding_num).into(),
+                        Resource::Buffer {
+                            buffer: vertex_buffer.clone(),
+                            range: 0..vertex_buffer.size(), // TODO:
+                            memory: PipelineMemoryAccess {
+                                stages: PipelineStages {
+                                    vertex_input: true,
+                                    ..PipelineStages::empty()
+                                },
+                                access: AccessFlags {
+                                    vertex_attribute_read: true,
+                                    ..AccessFlags::empty()
+                                },
+                                exclusive: false,
+                            },
                         },
-                        exclusive: false,
-                    },
-                },
-            )
-        }));
+                    ))
+                }),
+        );
     }
 
     fn add_index_buffer_resources(&self, resources: &mut Vec<(Cow<'static, str>, Resource)>) {
@@ -2167,6 +2558,35 @@ impl UnsafeCommandBufferBuilder {
         );
     }
 
+    /// Calls `vkCmdDispatchBase` on the builder.
+    #[inline]
+    pub unsafe fn dispatch_base(&mut self, base_group: [u32; 3], group_counts: [u32; 3]) {
+        let fns = self.device.fns();
+
+        if self.device.api_version() >= Version::V1_1 {
+            (fns.v1_1.cmd_dispatch_base)(
+                self.handle,
+                base_group[0],
+                base_group[1],
+                base_group[2],
+                group_counts[0],
+                group_counts[1],
+                group_counts[2],
+            );
+        } else {
+            debug_assert!(self.device.enabled_extensions().khr_device_group);
+            (fns.khr_device_group.cmd_dispatch_base_khr)(
+                self.handle,
+                base_group[0],
+                base_group[1],
+                base_group[2],
+                group_counts[0],
+                group_counts[1],
+                group_counts[2],
+            );
+        }
+    }
+
     /// Calls `vkCmdDispatchIndirect` on the builder.
     #[inline]
     pub unsafe fn dispatch_indirect(&mut self, buffer: &dyn BufferAccess) {
@@ -2353,6 +2773,11 @@ pub enum PipelineExecutionError {
     /// The `indirect_buffer` usage was not enabled on the indirect buffer.
     IndirectBufferMissingUsage,
 
+    /// The offset of the indirect buffer is not a multiple of 4.
+    IndirectBufferOffsetNotAligned {
+        offset: DeviceSize,
+    },
+
     /// The `max_compute_work_group_count` limit has been exceeded.
     MaxComputeWorkGroupCountExceeded {
         requested: [u32; 3],
@@ -2396,6 +2821,10 @@ pub enum PipelineExecutionError {
         required_format: Format,
     },
 
+    /// A nonzero base workgroup was provided, but the bound compute pipeline was not created with
+    /// the [`dispatch_base`](crate::pipeline::PipelineCreateFlags::dispatch_base) flag.
+    PipelineDispatchBaseNotEnabled,
+
     /// The bound pipeline is not compatible with the layout used to bind the descriptor sets.
     PipelineLayoutNotCompatible,
 
@@ -2455,6 +2884,13 @@ pub enum PipelineExecutionError {
         vertices_needed: u64,
         vertices_in_buffers: u64,
     },
+
+    /// The provided work size is not a multiple of the local size of the bound compute pipeline,
+    /// and an exact cover was requested.
+    WorkSizeNotMultipleOfLocalSize {
+        work_size: [u32; 3],
+        local_size: [u32; 3],
+    },
 }
 
 impl Error for PipelineExecutionError {
@@ -2563,6 +2999,10 @@ impl Display for PipelineExecutionError {
                 f,
                 "the `indirect_buffer` usage was not enabled on the indirect buffer",
             ),
+            Self::IndirectBufferOffsetNotAligned { .. } => write!(
+                f,
+                "the offset of the indirect buffer is not a multiple of 4",
+            ),
             Self::MaxComputeWorkGroupCountExceeded { .. } => write!(
                 f,
                 "the `max_compute_work_group_count` limit has been exceeded",
@@ -2605,6 +3045,11 @@ impl Display for PipelineExecutionError {
                 the format of the depth attachment in the current render pass ({:?})",
                 pipeline_format, required_format,
             ),
+            Self::PipelineDispatchBaseNotEnabled => write!(
+                f,
+                "a nonzero base workgroup was provided, but the bound compute pipeline was not \
+                created with the `dispatch_base` flag",
+            ),
             Self::PipelineLayoutNotCompatible => write!(
                 f,
                 "the bound pipeline is not compatible with the layout used to bind the descriptor \
@@ -2680,6 +3125,15 @@ impl Display for PipelineExecutionError {
                 in the bound vertex buffers ({}) used by the pipeline",
                 vertices_needed, vertices_in_buffers,
             ),
+            Self::WorkSizeNotMultipleOfLocalSize {
+                work_size,
+                local_size,
+            } => write!(
+                f,
+                "the provided work size ({:?}) is not a multiple of the local size of the bound \
+                compute pipeline ({:?}), and an exact cover was requested",
+                work_size, local_size,
+            ),
         }
     }
 }
@@ -2692,6 +3146,10 @@ impl From<SyncCommandBufferBuilderError> for PipelineExecutionError {
 
 #[derive(Clone, Copy, Debug)]
 pub enum DescriptorResourceInvalidError {
+    BufferTooSmall {
+        required: DeviceSize,
+        provided: DeviceSize,
+    },
     ImageViewFormatMismatch {
         required: Format,
         provided: Option<Format>,
@@ -2738,6 +3196,12 @@ impl Error for DescriptorResourceInvalidError {
 impl Display for DescriptorResourceInvalidError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         match self {
+            Self::BufferTooSmall { provided, required } => write!(
+                f,
+                "the size of the bound buffer ({}) is smaller than what the pipeline requires \
+                ({})",
+                provided, required,
+            ),
             Self::ImageViewFormatMismatch { provided, required } => write!(
                 f,
                 "the format of the bound image view ({:?}) does not match what the pipeline \
@@ -2798,3 +3262,162 @@ impl Display for DescriptorResourceInvalidError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PipelineExecutionError;
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+        },
+        device::{Device, DeviceCreateInfo, QueueCreateInfo},
+        instance::{Instance, InstanceCreateInfo},
+        pipeline::{ComputePipeline, PipelineCreateFlags, PipelineLayout},
+        shader::ShaderModule,
+        Version, VulkanLibrary,
+    };
+    use std::sync::Arc;
+
+    /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %main = OpFunction %void None %fn
+        %label = OpLabel
+        OpReturn
+        OpFunctionEnd
+    */
+    const EMPTY_COMPUTE_MODULE: [u32; 35] = [
+        0x07230203, 0x00010000, 0, 5, 0, 0x00020011, 1, 0x0003000e, 0, 1, 0x0005000f, 5, 1,
+        0x6e69616d, 0, 0x00060010, 1, 17, 1, 1, 1, 0x00020013, 2, 0x00030021, 3, 2, 0x00050036, 2,
+        1, 0, 3, 0x000200f8, 4, 0x000100fd, 0x00010038,
+    ];
+
+    fn empty_compute_pipeline(
+        device: Arc<Device>,
+        flags: PipelineCreateFlags,
+    ) -> Arc<ComputePipeline> {
+        let module =
+            unsafe { ShaderModule::from_words(device.clone(), &EMPTY_COMPUTE_MODULE).unwrap() };
+        let layout = PipelineLayout::new(device.clone(), Default::default()).unwrap();
+
+        ComputePipeline::with_pipeline_layout_and_flags(
+            device,
+            module.entry_point("main").unwrap(),
+            &(),
+            layout,
+            None,
+            flags,
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn dispatch_base_work_group_count_exceeded() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        if device.api_version() < Version::V1_1 {
+            return;
+        }
+
+        let pipeline = empty_compute_pipeline(
+            device.clone(),
+            PipelineCreateFlags {
+                dispatch_base: true,
+                ..PipelineCreateFlags::empty()
+            },
+        );
+        let max = device
+            .physical_device()
+            .properties()
+            .max_compute_work_group_count;
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device);
+        let mut cbb = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        cbb.bind_pipeline_compute(pipeline);
+
+        // The last workgroup is exactly at the limit.
+        assert!(cbb.dispatch_base([max[0] - 1, 0, 0], [1, 1, 1]).is_ok());
+
+        // The base is within the limit, but the base plus the count isn't.
+        assert!(matches!(
+            cbb.dispatch_base([max[0] - 1, 0, 0], [2, 1, 1]),
+            Err(PipelineExecutionError::MaxComputeWorkGroupCountExceeded {
+                requested,
+                max: error_max,
+            }) if requested == [max[0] + 1, 1, 1] && error_max == max
+        ));
+    }
+
+    #[test]
+    fn dispatch_base_requirement_not_met() {
+        let library = match VulkanLibrary::new() {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+
+        // Limiting the instance to Vulkan 1.0 also limits the device, so that `dispatch_base` is
+        // only available with `khr_device_group`, which isn't enabled.
+        let instance = match Instance::new(
+            library,
+            InstanceCreateInfo {
+                max_api_version: Some(Version::V1_0),
+                ..Default::default()
+            },
+        ) {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+
+        let (physical_device, queue_family_index) = match instance
+            .enumerate_physical_devices()
+            .unwrap()
+            .find_map(|p| {
+                p.queue_family_properties()
+                    .iter()
+                    .position(|q| q.queue_flags.compute)
+                    .map(|i| (p, i as u32))
+            }) {
+            Some(x) => x,
+            None => return, // test ignored
+        };
+
+        let (device, _) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(device.api_version(), Version::V1_0);
+
+        let pipeline = empty_compute_pipeline(device.clone(), PipelineCreateFlags::empty());
+
+        let cb_allocator = StandardCommandBufferAllocator::new(device);
+        let mut cbb = AutoCommandBufferBuilder::primary(
+            &cb_allocator,
+            queue_family_index,
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+        cbb.bind_pipeline_compute(pipeline);
+
+        assert!(matches!(
+            cbb.dispatch_base([0; 3], [1; 3]),
+            Err(PipelineExecutionError::RequirementNotMet { .. })
+        ));
+    }
+}
@@ -21,9 +21,10 @@ use crate::{
     device::DeviceOwned,
     format::{ClearColorValue, ClearValue, Format, NumericType},
     image::{ImageLayout, ImageViewAbstract, SampleCount},
+    pipeline::graphics::multisample::SampleLocations,
     render_pass::{
-        AttachmentDescription, Framebuffer, LoadOp, RenderPass, ResolveMode, StoreOp,
-        SubpassDescription,
+        is_fragment_shading_rate_texel_size_supported, AttachmentDescription, Framebuffer, LoadOp,
+        RenderPass, ResolveMode, StoreOp, SubpassDescription,
     },
     sync::{AccessFlags, PipelineMemoryAccess, PipelineStages},
     RequirementNotMet, RequiresOneOf, Version, VulkanObject,
@@ -64,6 +65,8 @@ where
                 render_area_offset,
                 render_area_extent,
                 clear_values: _,
+                attachment_initial_sample_locations: _,
+                post_subpass_sample_locations: _,
                 _ne: _,
             } = render_pass_begin_info;
 
@@ -80,6 +83,11 @@ where
                 }
                 .into(),
                 view_mask,
+                // The pipeline that is already bound counts as bound inside the render pass.
+                provoking_vertex_mode: self
+                    .state()
+                    .pipeline_graphics()
+                    .map(|pipeline| pipeline.rasterization_state().provoking_vertex_mode),
             };
 
             self.inner
@@ -118,6 +126,8 @@ where
             render_area_offset,
             render_area_extent,
             clear_values,
+            attachment_initial_sample_locations,
+            post_subpass_sample_locations,
             _ne: _,
         } = render_pass_begin_info;
 
@@ -201,6 +211,24 @@ where
                             });
                         }
                     }
+                    ImageLayout::FragmentShadingRateAttachmentOptimal => {
+                        // VUID?
+                        if !image_view.usage().fragment_shading_rate_attachment {
+                            return Err(RenderPassError::AttachmentImageMissingUsage {
+                                attachment_index,
+                                usage: "fragment_shading_rate_attachment",
+                            });
+                        }
+                    }
+                    ImageLayout::FragmentDensityMapOptimal => {
+                        // VUID?
+                        if !image_view.usage().fragment_density_map {
+                            return Err(RenderPassError::AttachmentImageMissingUsage {
+                                attachment_index,
+                                usage: "fragment_density_map",
+                            });
+                        }
+                    }
                     _ => (),
                 }
             }
@@ -213,6 +241,10 @@ where
                 color_attachments,
                 resolve_attachments,
                 depth_stencil_attachment,
+                depth_stencil_resolve_attachment,
+                depth_resolve_mode: _,
+                stencil_resolve_mode: _,
+                fragment_shading_rate_attachment,
                 preserve_attachments: _,
                 _ne: _,
             } = subpass_desc;
@@ -220,8 +252,9 @@ where
             for atch_ref in (input_attachments.iter())
                 .chain(color_attachments)
                 .chain(resolve_attachments)
-                .chain([depth_stencil_attachment])
+                .chain([depth_stencil_attachment, depth_stencil_resolve_attachment])
                 .flatten()
+                .chain((fragment_shading_rate_attachment.iter()).map(|atch| &atch.attachment_ref))
             {
                 let image_view = &framebuffer.attachments()[atch_ref.attachment as usize];
 
@@ -274,6 +307,15 @@ where
                             });
                         }
                     }
+                    ImageLayout::FragmentShadingRateAttachmentOptimal => {
+                        // VUID?
+                        if !image_view.usage().fragment_shading_rate_attachment {
+                            return Err(RenderPassError::AttachmentImageMissingUsage {
+                                attachment_index: atch_ref.attachment,
+                                usage: "fragment_shading_rate_attachment",
+                            });
+                        }
+                    }
                     _ => (),
                 }
             }
@@ -307,40 +349,12 @@ where
                 if let (Some(numeric_type), LoadOp::Clear) =
                     (attachment_format.type_color(), attachment_desc.load_op)
                 {
-                    match numeric_type {
-                        NumericType::SFLOAT
-                        | NumericType::UFLOAT
-                        | NumericType::SNORM
-                        | NumericType::UNORM
-                        | NumericType::SSCALED
-                        | NumericType::USCALED
-                        | NumericType::SRGB => {
-                            if !matches!(clear_value, ClearValue::Float(_)) {
-                                return Err(RenderPassError::ClearValueNotCompatible {
-                                    clear_value,
-                                    attachment_index,
-                                    attachment_format,
-                                });
-                            }
-                        }
-                        NumericType::SINT => {
-                            if !matches!(clear_value, ClearValue::Int(_)) {
-                                return Err(RenderPassError::ClearValueNotCompatible {
-                                    clear_value,
-                                    attachment_index,
-                                    attachment_format,
-                                });
-                            }
-                        }
-                        NumericType::UINT => {
-                            if !matches!(clear_value, ClearValue::Uint(_)) {
-                                return Err(RenderPassError::ClearValueNotCompatible {
-                                    clear_value,
-                                    attachment_index,
-                                    attachment_format,
-                                });
-                            }
-                        }
+                    if !is_color_clear_value_compatible(numeric_type, clear_value) {
+                        return Err(RenderPassError::ClearValueNotCompatible {
+                            clear_value,
+                            attachment_index,
+                            attachment_format,
+                        });
                     }
                 } else {
                     let attachment_aspects = attachment_format.aspects();
@@ -378,6 +392,95 @@ where
             }
         }
 
+        if !(attachment_initial_sample_locations.is_empty()
+            && post_subpass_sample_locations.is_empty())
+            && !device.enabled_extensions().ext_sample_locations
+        {
+            return Err(RenderPassError::RequirementNotMet {
+                required_for: "`render_pass_begin_info.attachment_initial_sample_locations` or \
+                    `render_pass_begin_info.post_subpass_sample_locations` are not empty",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_sample_locations"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let check_sample_locations =
+            |sample_locations: &SampleLocations| -> Result<(), RenderPassError> {
+                let &SampleLocations {
+                    per_pixel,
+                    grid_size,
+                    ref locations,
+                } = sample_locations;
+
+                // VUID-VkSampleLocationsInfoEXT-sampleLocationsPerPixel-parameter
+                per_pixel.validate_device(device)?;
+
+                // VUID-VkSampleLocationsInfoEXT-sampleLocationsPerPixel-01526
+                if !device
+                    .physical_device()
+                    .properties()
+                    .sample_location_sample_counts
+                    .map_or(false, |counts| counts.contains_count(per_pixel))
+                {
+                    return Err(RenderPassError::SampleLocationsPerPixelNotSupported { per_pixel });
+                }
+
+                // VUID-VkSampleLocationsInfoEXT-sampleLocationGridSize-01528
+                // VUID-VkSampleLocationsInfoEXT-sampleLocationGridSize-01529
+                // Use unchecked, because all validation has been done above.
+                let max_grid_size = unsafe {
+                    device
+                        .physical_device()
+                        .multisample_properties_unchecked(per_pixel)
+                }
+                .max_sample_location_grid_size;
+
+                if !sample_locations.grid_size_divides(max_grid_size) {
+                    return Err(RenderPassError::SampleLocationsGridSizeNotSupported {
+                        grid_size,
+                        max_grid_size,
+                    });
+                }
+
+                // VUID-VkSampleLocationsInfoEXT-sampleLocationsCount-01527
+                let required = sample_locations.required_count();
+
+                if required != Some(locations.len() as u32) {
+                    return Err(RenderPassError::SampleLocationsCountMismatch {
+                        provided: locations.len() as u32,
+                        required,
+                    });
+                }
+
+                Ok(())
+            };
+
+        for (attachment_index, sample_locations) in attachment_initial_sample_locations {
+            // VUID-VkAttachmentSampleLocationsEXT-attachmentIndex-01531
+            if *attachment_index as usize >= render_pass.attachments().len() {
+                return Err(RenderPassError::SampleLocationsAttachmentIndexOutOfRange {
+                    attachment_index: *attachment_index,
+                    num_attachments: render_pass.attachments().len() as u32,
+                });
+            }
+
+            check_sample_locations(sample_locations)?;
+        }
+
+        for (subpass_index, sample_locations) in post_subpass_sample_locations {
+            // VUID-VkSubpassSampleLocationsEXT-subpassIndex-01532
+            if *subpass_index as usize >= render_pass.subpasses().len() {
+                return Err(RenderPassError::SampleLocationsSubpassIndexOutOfRange {
+                    subpass_index: *subpass_index,
+                    num_subpasses: render_pass.subpasses().len() as u32,
+                });
+            }
+
+            check_sample_locations(sample_locations)?;
+        }
+
         // VUID-vkCmdBeginRenderPass2-initialLayout-03100
         // TODO:
 
@@ -546,6 +649,7 @@ where
                 ref color_attachments,
                 ref depth_attachment,
                 ref stencil_attachment,
+                fragment_shading_rate_attachment: _,
                 contents: _,
                 _ne: _,
             } = rendering_info;
@@ -630,6 +734,7 @@ where
                 ref color_attachments,
                 ref depth_attachment,
                 ref stencil_attachment,
+                fragment_shading_rate_attachment: _,
                 contents,
                 _ne: _,
             } = rendering_info;
@@ -658,6 +763,11 @@ where
                 }
                 .into(),
                 view_mask,
+                // The pipeline that is already bound counts as bound inside the render pass.
+                provoking_vertex_mode: self
+                    .state()
+                    .pipeline_graphics()
+                    .map(|pipeline| pipeline.rasterization_state().provoking_vertex_mode),
             };
 
             self.inner.begin_rendering(rendering_info)?;
@@ -706,6 +816,7 @@ where
             ref color_attachments,
             ref depth_attachment,
             ref stencil_attachment,
+            ref fragment_shading_rate_attachment,
             contents,
             _ne: _,
         } = rendering_info;
@@ -744,6 +855,14 @@ where
             });
         }
 
+        // The number of array layers that each attachment image view must have: one for each view
+        // if multiview is enabled, `layer_count` otherwise.
+        let required_layers = if view_mask != 0 {
+            view_count
+        } else {
+            layer_count
+        };
+
         let mut samples = None;
 
         // VUID-VkRenderingInfo-colorAttachmentCount-06106
@@ -771,7 +890,7 @@ where
                 resolve_info,
                 load_op,
                 store_op,
-                clear_value: _,
+                clear_value,
                 _ne: _,
             } = attachment_info;
 
@@ -784,6 +903,25 @@ where
             // VUID-VkRenderingAttachmentInfo-storeOp-parameter
             store_op.validate_device(device)?;
 
+            if *load_op == LoadOp::Clear {
+                let attachment_format = image_view.format().unwrap();
+                let clear_value =
+                    clear_value.ok_or(RenderPassError::ClearValueMissing { attachment_index })?;
+
+                if !attachment_format
+                    .type_color()
+                    .map_or(false, |numeric_type| {
+                        is_color_clear_value_compatible(numeric_type, clear_value)
+                    })
+                {
+                    return Err(RenderPassError::ClearValueNotCompatible {
+                        clear_value,
+                        attachment_index,
+                        attachment_format,
+                    });
+                }
+            }
+
             // VUID-VkRenderingInfo-colorAttachmentCount-06087
             if !image_view.usage().color_attachment {
                 return Err(RenderPassError::ColorAttachmentMissingUsage { attachment_index });
@@ -800,6 +938,16 @@ where
                 }
             }
 
+            let image_view_array_layers = array_layer_count(image_view.as_ref());
+
+            if image_view_array_layers < required_layers {
+                return Err(RenderPassError::ColorAttachmentNotEnoughLayers {
+                    attachment_index,
+                    provided: image_view_array_layers,
+                    min: required_layers,
+                });
+            }
+
             // VUID-VkRenderingInfo-imageView-06070
             match samples {
                 Some(samples) if samples == image.samples() => (),
@@ -926,7 +1074,7 @@ where
                 resolve_info,
                 load_op,
                 store_op,
-                clear_value: _,
+                clear_value,
                 _ne: _,
             } = attachment_info;
 
@@ -939,6 +1087,22 @@ where
             // VUID-VkRenderingAttachmentInfo-storeOp-parameter
             store_op.validate_device(device)?;
 
+            if *load_op == LoadOp::Clear {
+                let clear_value =
+                    clear_value.ok_or(RenderPassError::DepthAttachmentClearValueMissing)?;
+
+                // The depth attachment only uses the depth value, so a combined value is also
+                // accepted, to allow sharing it with the stencil attachment.
+                if !matches!(
+                    clear_value,
+                    ClearValue::Depth(_) | ClearValue::DepthStencil(_)
+                ) {
+                    return Err(RenderPassError::DepthAttachmentClearValueNotCompatible {
+                        clear_value,
+                    });
+                }
+            }
+
             let image_aspects = image_view.format().unwrap().aspects();
 
             // VUID-VkRenderingInfo-pDepthAttachment-06547
@@ -962,6 +1126,15 @@ where
                 }
             }
 
+            let image_view_array_layers = array_layer_count(image_view.as_ref());
+
+            if image_view_array_layers < required_layers {
+                return Err(RenderPassError::DepthAttachmentNotEnoughLayers {
+                    provided: image_view_array_layers,
+                    min: required_layers,
+                });
+            }
+
             // VUID-VkRenderingInfo-imageView-06070
             match samples {
                 Some(samples) if samples == image.samples() => (),
@@ -1053,7 +1226,7 @@ where
                 resolve_info,
                 load_op,
                 store_op,
-                clear_value: _,
+                clear_value,
                 _ne: _,
             } = attachment_info;
 
@@ -1066,6 +1239,22 @@ where
             // VUID-VkRenderingAttachmentInfo-storeOp-parameter
             store_op.validate_device(device)?;
 
+            if *load_op == LoadOp::Clear {
+                let clear_value =
+                    clear_value.ok_or(RenderPassError::StencilAttachmentClearValueMissing)?;
+
+                // The stencil attachment only uses the stencil value, so a combined value is also
+                // accepted, to allow sharing it with the depth attachment.
+                if !matches!(
+                    clear_value,
+                    ClearValue::Stencil(_) | ClearValue::DepthStencil(_)
+                ) {
+                    return Err(RenderPassError::StencilAttachmentClearValueNotCompatible {
+                        clear_value,
+                    });
+                }
+            }
+
             let image_aspects = image_view.format().unwrap().aspects();
 
             // VUID-VkRenderingInfo-pStencilAttachment-06548
@@ -1089,6 +1278,15 @@ where
                 }
             }
 
+            let image_view_array_layers = array_layer_count(image_view.as_ref());
+
+            if image_view_array_layers < required_layers {
+                return Err(RenderPassError::StencilAttachmentNotEnoughLayers {
+                    provided: image_view_array_layers,
+                    min: required_layers,
+                });
+            }
+
             // VUID-VkRenderingInfo-imageView-06070
             match samples {
                 Some(samples) if samples == image.samples() => (),
@@ -1214,6 +1412,65 @@ where
             }
         }
 
+        if let Some(fragment_shading_rate_attachment_info) = fragment_shading_rate_attachment {
+            let &RenderingFragmentShadingRateAttachmentInfo {
+                ref image_view,
+                image_layout,
+                texel_size,
+            } = fragment_shading_rate_attachment_info;
+
+            if !device.enabled_features().attachment_fragment_shading_rate {
+                return Err(RenderPassError::RequirementNotMet {
+                    required_for: "`rendering_info.fragment_shading_rate_attachment` is `Some`",
+                    requires_one_of: RequiresOneOf {
+                        features: &["attachment_fragment_shading_rate"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageLayout-parameter
+            image_layout.validate_device(device)?;
+
+            // VUID-VkRenderingInfo-imageView-06116
+            if (color_attachments.iter().flatten())
+                .chain(depth_attachment.iter())
+                .chain(stencil_attachment.iter())
+                .any(|attachment_info| &attachment_info.image_view == image_view)
+            {
+                return Err(RenderPassError::FragmentShadingRateAttachmentImageViewReused);
+            }
+
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06147
+            if !matches!(
+                image_layout,
+                ImageLayout::General | ImageLayout::FragmentShadingRateAttachmentOptimal
+            ) {
+                return Err(RenderPassError::FragmentShadingRateAttachmentLayoutInvalid);
+            }
+
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06148
+            if !image_view.usage().fragment_shading_rate_attachment {
+                return Err(RenderPassError::FragmentShadingRateAttachmentMissingUsage);
+            }
+
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06149
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06150
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06151
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06152
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06153
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06154
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06155
+            // VUID-VkRenderingFragmentShadingRateAttachmentInfoKHR-imageView-06156
+            if !is_fragment_shading_rate_texel_size_supported(properties, texel_size) {
+                return Err(
+                    RenderPassError::FragmentShadingRateAttachmentTexelSizeNotSupported {
+                        texel_size,
+                    },
+                );
+            }
+        }
+
         Ok(())
     }
 
@@ -1343,7 +1600,8 @@ where
                     };
 
                     // VUID-vkCmdClearAttachments-aspectMask-02501
-                    if !attachment_format.map_or(false, |format| {
+                    // If the color attachment is unused, clearing it has no effect.
+                    if !attachment_format.map_or(true, |format| {
                         matches!(
                             (clear_value, format.type_color().unwrap()),
                             (
@@ -1386,8 +1644,7 @@ where
 
                     // We only know the layer count if we have a known attachment image.
                     if let Some(image_view) = image_view {
-                        let array_layers = &image_view.subresource_range().array_layers;
-                        layer_count = min(layer_count, array_layers.end - array_layers.start);
+                        layer_count = min(layer_count, array_layer_count(image_view.as_ref()));
                     }
                 }
                 ClearAttachment::Depth(_)
@@ -1451,14 +1708,18 @@ where
                         RenderPassStateType::BeginRendering(state) => state
                             .attachments
                             .as_ref()
-                            .and_then(|attachments| attachments.depth_attachment.as_ref())
+                            .and_then(|attachments| {
+                                attachments
+                                    .depth_attachment
+                                    .as_ref()
+                                    .or(attachments.stencil_attachment.as_ref())
+                            })
                             .map(|attachment_info| &attachment_info.image_view),
                     };
 
                     // We only know the layer count if we have a known attachment image.
                     if let Some(image_view) = image_view {
-                        let array_layers = &image_view.subresource_range().array_layers;
-                        layer_count = min(layer_count, array_layers.end - array_layers.start);
+                        layer_count = min(layer_count, array_layer_count(image_view.as_ref()));
                     }
                 }
             }
@@ -1511,6 +1772,31 @@ where
     }
 }
 
+// Returns the number of array layers that `image_view` gives access to.
+fn array_layer_count(image_view: &dyn ImageViewAbstract) -> u32 {
+    let array_layers = &image_view.subresource_range().array_layers;
+    array_layers.end - array_layers.start
+}
+
+// Returns whether `clear_value` can be used to clear a color attachment whose format has the
+// numeric type `numeric_type`.
+fn is_color_clear_value_compatible(numeric_type: NumericType, clear_value: ClearValue) -> bool {
+    matches!(
+        (numeric_type, clear_value),
+        (
+            NumericType::SFLOAT
+                | NumericType::UFLOAT
+                | NumericType::SNORM
+                | NumericType::UNORM
+                | NumericType::SSCALED
+                | NumericType::USCALED
+                | NumericType::SRGB,
+            ClearValue::Float(_),
+        ) | (NumericType::SINT, ClearValue::Int(_))
+            | (NumericType::UINT, ClearValue::Uint(_))
+    )
+}
+
 impl SyncCommandBufferBuilder {
     /// Calls `vkCmdBeginRenderPass` on the builder.
     // TODO: it shouldn't be possible to get an error if the framebuffer checked conflicts already
@@ -1543,6 +1829,8 @@ impl SyncCommandBufferBuilder {
             render_area_offset: _,
             render_area_extent: _,
             clear_values: _,
+            attachment_initial_sample_locations: _,
+            post_subpass_sample_locations: _,
             _ne: _,
         } = &render_pass_begin_info;
 
@@ -1666,6 +1954,7 @@ impl SyncCommandBufferBuilder {
             color_attachments,
             depth_attachment,
             stencil_attachment,
+            fragment_shading_rate_attachment,
             contents: _,
             _ne,
         } = &rendering_info;
@@ -1876,6 +2165,38 @@ impl SyncCommandBufferBuilder {
             .into_iter()
             .flatten()
         }))
+        .chain(
+            fragment_shading_rate_attachment
+                .iter()
+                .map(|attachment_info| {
+                    let &RenderingFragmentShadingRateAttachmentInfo {
+                        ref image_view,
+                        image_layout,
+                        texel_size: _,
+                    } = attachment_info;
+
+                    (
+                        "fragment shading rate attachment".into(),
+                        Resource::Image {
+                            image: image_view.image(),
+                            subresource_range: image_view.subresource_range().clone(),
+                            memory: PipelineMemoryAccess {
+                                stages: PipelineStages {
+                                    fragment_shading_rate_attachment: true,
+                                    ..PipelineStages::empty()
+                                },
+                                access: AccessFlags {
+                                    fragment_shading_rate_attachment_read: true,
+                                    ..AccessFlags::empty()
+                                },
+                                exclusive: false,
+                            },
+                            start_layout: image_layout,
+                            end_layout: image_layout,
+                        },
+                    )
+                }),
+        )
         .collect::<Vec<_>>();
 
         for resource in &resources {
@@ -1957,6 +2278,8 @@ impl UnsafeCommandBufferBuilder {
             render_area_offset,
             render_area_extent,
             ref clear_values,
+            ref attachment_initial_sample_locations,
+            ref post_subpass_sample_locations,
             _ne: _,
         } = render_pass_begin_info;
 
@@ -1966,7 +2289,7 @@ impl UnsafeCommandBufferBuilder {
             .map(|clear_value| clear_value.map(Into::into).unwrap_or_default())
             .collect();
 
-        let render_pass_begin_info = ash::vk::RenderPassBeginInfo {
+        let mut render_pass_begin_info = ash::vk::RenderPassBeginInfo {
             render_pass: render_pass.internal_object(),
             framebuffer: framebuffer.internal_object(),
             render_area: ash::vk::Rect2D {
@@ -1984,6 +2307,52 @@ impl UnsafeCommandBufferBuilder {
             ..Default::default()
         };
 
+        let attachment_initial_locations_vk: SmallVec<[_; 4]> = attachment_initial_sample_locations
+            .iter()
+            .map(|(_, sample_locations)| sample_locations.locations_vk())
+            .collect();
+        let attachment_initial_sample_locations_vk: SmallVec<[_; 4]> =
+            attachment_initial_sample_locations
+                .iter()
+                .zip(&attachment_initial_locations_vk)
+                .map(|((attachment_index, sample_locations), locations_vk)| {
+                    ash::vk::AttachmentSampleLocationsEXT {
+                        attachment_index: *attachment_index,
+                        sample_locations_info: sample_locations.to_vk(locations_vk),
+                    }
+                })
+                .collect();
+        let post_subpass_locations_vk: SmallVec<[_; 4]> = post_subpass_sample_locations
+            .iter()
+            .map(|(_, sample_locations)| sample_locations.locations_vk())
+            .collect();
+        let post_subpass_sample_locations_vk: SmallVec<[_; 4]> = post_subpass_sample_locations
+            .iter()
+            .zip(&post_subpass_locations_vk)
+            .map(|((subpass_index, sample_locations), locations_vk)| {
+                ash::vk::SubpassSampleLocationsEXT {
+                    subpass_index: *subpass_index,
+                    sample_locations_info: sample_locations.to_vk(locations_vk),
+                }
+            })
+            .collect();
+        let sample_locations_begin_info_vk;
+
+        if !(attachment_initial_sample_locations_vk.is_empty()
+            && post_subpass_sample_locations_vk.is_empty())
+        {
+            sample_locations_begin_info_vk = ash::vk::RenderPassSampleLocationsBeginInfoEXT {
+                attachment_initial_sample_locations_count: attachment_initial_sample_locations_vk
+                    .len() as u32,
+                p_attachment_initial_sample_locations: attachment_initial_sample_locations_vk
+                    .as_ptr(),
+                post_subpass_sample_locations_count: post_subpass_sample_locations_vk.len() as u32,
+                p_post_subpass_sample_locations: post_subpass_sample_locations_vk.as_ptr(),
+                ..Default::default()
+            };
+            render_pass_begin_info.p_next = &sample_locations_begin_info_vk as *const _ as *const _;
+        }
+
         let subpass_begin_info = ash::vk::SubpassBeginInfo {
             contents: contents.into(),
             ..Default::default()
@@ -2086,6 +2455,7 @@ impl UnsafeCommandBufferBuilder {
             ref color_attachments,
             ref depth_attachment,
             ref stencil_attachment,
+            ref fragment_shading_rate_attachment,
             contents,
             _ne: _,
         } = rendering_info;
@@ -2146,8 +2516,27 @@ impl UnsafeCommandBufferBuilder {
             color_attachments.iter().map(map_attachment_info).collect();
         let depth_attachment = map_attachment_info(depth_attachment);
         let stencil_attachment = map_attachment_info(stencil_attachment);
+        let mut fragment_shading_rate_attachment_vk = fragment_shading_rate_attachment
+            .as_ref()
+            .map(|attachment_info| {
+                let &RenderingFragmentShadingRateAttachmentInfo {
+                    ref image_view,
+                    image_layout,
+                    texel_size,
+                } = attachment_info;
+
+                ash::vk::RenderingFragmentShadingRateAttachmentInfoKHR {
+                    image_view: image_view.internal_object(),
+                    image_layout: image_layout.into(),
+                    shading_rate_attachment_texel_size: ash::vk::Extent2D {
+                        width: texel_size[0],
+                        height: texel_size[1],
+                    },
+                    ..Default::default()
+                }
+            });
 
-        let rendering_info = ash::vk::RenderingInfo {
+        let mut rendering_info = ash::vk::RenderingInfo {
             flags: contents.into(),
             render_area: ash::vk::Rect2D {
                 offset: ash::vk::Offset2D {
@@ -2168,6 +2557,11 @@ impl UnsafeCommandBufferBuilder {
             ..Default::default()
         };
 
+        if let Some(info) = fragment_shading_rate_attachment_vk.as_mut() {
+            info.p_next = rendering_info.p_next;
+            rendering_info.p_next = info as *const _ as *const _;
+        }
+
         let fns = self.device.fns();
 
         if self.device.api_version() >= Version::V1_3 {
@@ -2272,6 +2666,28 @@ pub struct RenderPassBeginInfo {
     /// The default value is empty, which must be overridden if the framebuffer has attachments.
     pub clear_values: Vec<Option<ClearValue>>,
 
+    /// For depth/stencil attachments that were last written with custom sample locations, the
+    /// attachment index and the sample locations that were used. This allows the implementation to
+    /// correctly perform the initial layout transition of the attachment.
+    ///
+    /// If not empty, the
+    /// [`ext_sample_locations`](crate::device::DeviceExtensions::ext_sample_locations) extension
+    /// must be enabled on the device.
+    ///
+    /// The default value is empty.
+    pub attachment_initial_sample_locations: Vec<(u32, SampleLocations)>,
+
+    /// For subpasses that write depth/stencil attachments with custom sample locations, the
+    /// subpass index and the sample locations that will be used. This allows the implementation
+    /// to correctly perform layout transitions after the subpass.
+    ///
+    /// If not empty, the
+    /// [`ext_sample_locations`](crate::device::DeviceExtensions::ext_sample_locations) extension
+    /// must be enabled on the device.
+    ///
+    /// The default value is empty.
+    pub post_subpass_sample_locations: Vec<(u32, SampleLocations)>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -2286,6 +2702,8 @@ impl RenderPassBeginInfo {
             render_area_offset: [0, 0],
             render_area_extent,
             clear_values: Vec::new(),
+            attachment_initial_sample_locations: Vec::new(),
+            post_subpass_sample_locations: Vec::new(),
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -2359,6 +2777,15 @@ pub struct RenderingInfo {
     /// The default value is `None`.
     pub stencil_attachment: Option<RenderingAttachmentInfo>,
 
+    /// The fragment shading rate attachment to use for rendering.
+    ///
+    /// If set to `Some`, the
+    /// [`attachment_fragment_shading_rate`](crate::device::Features::attachment_fragment_shading_rate)
+    /// feature must be enabled on the device.
+    ///
+    /// The default value is `None`.
+    pub fragment_shading_rate_attachment: Option<RenderingFragmentShadingRateAttachmentInfo>,
+
     /// What kinds of commands will be recorded in the render pass: either inline draw commands, or
     /// executions of secondary command buffers.
     ///
@@ -2381,6 +2808,7 @@ impl Default for RenderingInfo {
             color_attachments: Vec::new(),
             depth_attachment: None,
             stencil_attachment: None,
+            fragment_shading_rate_attachment: None,
             contents: SubpassContents::Inline,
             _ne: crate::NonExhaustive(()),
         }
@@ -2491,6 +2919,39 @@ impl RenderingAttachmentResolveInfo {
     }
 }
 
+/// Parameters to specify a fragment shading rate attachment.
+#[derive(Clone, Debug)]
+pub struct RenderingFragmentShadingRateAttachmentInfo {
+    /// The image view to use as the attachment.
+    ///
+    /// There is no default value.
+    pub image_view: Arc<dyn ImageViewAbstract>,
+
+    /// The image layout that `image_view` should be in during rendering.
+    ///
+    /// The default value is [`ImageLayout::FragmentShadingRateAttachmentOptimal`].
+    pub image_layout: ImageLayout,
+
+    /// The width and height of the area of the render area, in pixels, that each texel of the
+    /// attachment corresponds to.
+    ///
+    /// There is no default value.
+    pub texel_size: [u32; 2],
+}
+
+impl RenderingFragmentShadingRateAttachmentInfo {
+    /// Returns a `RenderingFragmentShadingRateAttachmentInfo` with the specified `image_view` and
+    /// `texel_size`.
+    #[inline]
+    pub fn image_view(image_view: Arc<dyn ImageViewAbstract>, texel_size: [u32; 2]) -> Self {
+        Self {
+            image_view,
+            image_layout: ImageLayout::FragmentShadingRateAttachmentOptimal,
+            texel_size,
+        }
+    }
+}
+
 /// Clear attachment type, used in [`clear_attachments`] command.
 ///
 /// [`clear_attachments`]: crate::command_buffer::AutoCommandBufferBuilder::clear_attachments
@@ -2599,13 +3060,14 @@ pub enum RenderPassError {
         attachment_format: Option<Format>,
     },
 
-    /// A clear value for a render pass attachment is missing.
+    /// A clear value for a render pass attachment, or for a color attachment of
+    /// `begin_rendering`, is missing.
     ClearValueMissing {
         attachment_index: u32,
     },
 
-    /// A clear value provided for a render pass attachment is not compatible with the attachment's
-    /// format.
+    /// A clear value provided for a render pass attachment, or for a color attachment of
+    /// `begin_rendering`, is not compatible with the attachment's format.
     ClearValueNotCompatible {
         clear_value: ClearValue,
         attachment_index: u32,
@@ -2629,6 +3091,14 @@ pub enum RenderPassError {
         attachment_index: u32,
     },
 
+    /// A color attachment image view has fewer array layers than the number of views or layers
+    /// that are rendered to.
+    ColorAttachmentNotEnoughLayers {
+        attachment_index: u32,
+        provided: u32,
+        min: u32,
+    },
+
     /// A color resolve attachment has a `format` value different from the corresponding color
     /// attachment.
     ColorAttachmentResolveFormatMismatch {
@@ -2666,6 +3136,14 @@ pub enum RenderPassError {
     /// command buffer.
     ContentsForbiddenInSecondaryCommandBuffer,
 
+    /// The depth attachment has a `load_op` of [`LoadOp::Clear`], but no clear value was provided.
+    DepthAttachmentClearValueMissing,
+
+    /// The clear value provided for the depth attachment is not a depth or depth/stencil value.
+    DepthAttachmentClearValueNotCompatible {
+        clear_value: ClearValue,
+    },
+
     /// The depth attachment has a format that does not support that usage.
     DepthAttachmentFormatUsageNotSupported,
 
@@ -2675,6 +3153,13 @@ pub enum RenderPassError {
     /// The depth attachment is missing the `depth_stencil_attachment` usage.
     DepthAttachmentMissingUsage,
 
+    /// The depth attachment image view has fewer array layers than the number of views or layers
+    /// that are rendered to.
+    DepthAttachmentNotEnoughLayers {
+        provided: u32,
+        min: u32,
+    },
+
     /// The depth resolve attachment has a `format` value different from the corresponding depth
     /// attachment.
     DepthAttachmentResolveFormatMismatch,
@@ -2726,6 +3211,21 @@ pub enum RenderPassError {
         contents: SubpassContents,
     },
 
+    /// The image view of the fragment shading rate attachment is also used as another attachment.
+    FragmentShadingRateAttachmentImageViewReused,
+
+    /// The fragment shading rate attachment has a layout that is not supported.
+    FragmentShadingRateAttachmentLayoutInvalid,
+
+    /// The fragment shading rate attachment image does not have the
+    /// `fragment_shading_rate_attachment` usage.
+    FragmentShadingRateAttachmentMissingUsage,
+
+    /// The texel size of the fragment shading rate attachment is not supported by the device.
+    FragmentShadingRateAttachmentTexelSizeNotSupported {
+        texel_size: [u32; 2],
+    },
+
     /// The framebuffer is not compatible with the render pass.
     FramebufferNotCompatible,
 
@@ -2785,6 +3285,52 @@ pub enum RenderPassError {
     /// The render area's `offset` and `extent` are outside the extent of the framebuffer.
     RenderAreaOutOfBounds,
 
+    /// An attachment index provided with sample locations is not less than the number of
+    /// attachments in the render pass.
+    SampleLocationsAttachmentIndexOutOfRange {
+        attachment_index: u32,
+        num_attachments: u32,
+    },
+
+    /// The number of provided sample locations does not match the sample count and grid size.
+    /// `required` is `None` if the required number overflows a `u32`.
+    SampleLocationsCountMismatch {
+        provided: u32,
+        required: Option<u32>,
+    },
+
+    /// The grid size of the sample locations does not evenly divide the
+    /// [`max_sample_location_grid_size`](crate::pipeline::graphics::multisample::MultisampleProperties::max_sample_location_grid_size)
+    /// for the number of samples per pixel.
+    SampleLocationsGridSizeNotSupported {
+        grid_size: [u32; 2],
+        max_grid_size: [u32; 2],
+    },
+
+    /// The `per_pixel` value of the sample locations is not included in the
+    /// [`sample_location_sample_counts`](crate::device::Properties::sample_location_sample_counts)
+    /// device property.
+    SampleLocationsPerPixelNotSupported {
+        per_pixel: SampleCount,
+    },
+
+    /// A subpass index provided with sample locations is not less than the number of subpasses
+    /// in the render pass.
+    SampleLocationsSubpassIndexOutOfRange {
+        subpass_index: u32,
+        num_subpasses: u32,
+    },
+
+    /// The stencil attachment has a `load_op` of [`LoadOp::Clear`], but no clear value was
+    /// provided.
+    StencilAttachmentClearValueMissing,
+
+    /// The clear value provided for the stencil attachment is not a stencil or depth/stencil
+    /// value.
+    StencilAttachmentClearValueNotCompatible {
+        clear_value: ClearValue,
+    },
+
     /// The stencil attachment has a format that does not support that usage.
     StencilAttachmentFormatUsageNotSupported,
 
@@ -2794,6 +3340,13 @@ pub enum RenderPassError {
     /// The stencil attachment is missing the `depth_stencil_attachment` usage.
     StencilAttachmentMissingUsage,
 
+    /// The stencil attachment image view has fewer array layers than the number of views or layers
+    /// that are rendered to.
+    StencilAttachmentNotEnoughLayers {
+        provided: u32,
+        min: u32,
+    },
+
     /// The stencil resolve attachment has a `format` value different from the corresponding stencil
     /// attachment.
     StencilAttachmentResolveFormatMismatch,
@@ -2873,7 +3426,7 @@ impl Display for RenderPassError {
             ),
             Self::ClearValueMissing { attachment_index } => write!(
                 f,
-                "a clear value for render pass attachment {} is missing",
+                "a clear value for attachment {} is missing",
                 attachment_index,
             ),
             Self::ClearValueNotCompatible {
@@ -2882,8 +3435,8 @@ impl Display for RenderPassError {
                 attachment_format,
             } => write!(
                 f,
-                "a clear value ({:?}) provided for render pass attachment {} is not compatible \
-                with the attachment's format ({:?})",
+                "a clear value ({:?}) provided for attachment {} is not compatible with the \
+                attachment's format ({:?})",
                 clear_value, attachment_index, attachment_format,
             ),
             Self::ColorAttachmentIndexOutOfRange {
@@ -2905,6 +3458,15 @@ impl Display for RenderPassError {
                 "color attachment {} is missing the `color_attachment` usage",
                 attachment_index,
             ),
+            Self::ColorAttachmentNotEnoughLayers {
+                attachment_index,
+                provided,
+                min,
+            } => write!(
+                f,
+                "color attachment {} has {} array layers, but at least {} are rendered to",
+                attachment_index, provided, min,
+            ),
             Self::ColorAttachmentResolveFormatMismatch { attachment_index } => write!(
                 f,
                 "color attachment {} has a `format` value different from the corresponding color \
@@ -2944,6 +3506,17 @@ impl Display for RenderPassError {
                 "the contents `SubpassContents::SecondaryCommandBuffers` is not allowed inside a \
                 secondary command buffer",
             ),
+            Self::DepthAttachmentClearValueMissing => write!(
+                f,
+                "the depth attachment has a `load_op` of `LoadOp::Clear`, but no clear value was \
+                provided",
+            ),
+            Self::DepthAttachmentClearValueNotCompatible { clear_value } => write!(
+                f,
+                "the clear value ({:?}) provided for the depth attachment is not a depth or \
+                depth/stencil value",
+                clear_value,
+            ),
             Self::DepthAttachmentFormatUsageNotSupported => write!(
                 f,
                 "the depth attachment has a format that does not support that usage",
@@ -2955,6 +3528,11 @@ impl Display for RenderPassError {
                 f,
                 "the depth attachment is missing the `depth_stencil_attachment` usage",
             ),
+            Self::DepthAttachmentNotEnoughLayers { provided, min } => write!(
+                f,
+                "the depth attachment has {} array layers, but at least {} are rendered to",
+                provided, min,
+            ),
             Self::DepthAttachmentResolveFormatMismatch => write!(
                 f,
                 "the depth resolve attachment has a `format` value different from the \
@@ -3023,6 +3601,26 @@ impl Display for RenderPassError {
                 "operation forbidden inside a render subpass with contents {:?}",
                 subpass_contents,
             ),
+            Self::FragmentShadingRateAttachmentImageViewReused => write!(
+                f,
+                "the image view of the fragment shading rate attachment is also used as another \
+                attachment",
+            ),
+            Self::FragmentShadingRateAttachmentLayoutInvalid => write!(
+                f,
+                "the fragment shading rate attachment has a layout that is not supported",
+            ),
+            Self::FragmentShadingRateAttachmentMissingUsage => write!(
+                f,
+                "the fragment shading rate attachment image does not have the \
+                `fragment_shading_rate_attachment` usage",
+            ),
+            Self::FragmentShadingRateAttachmentTexelSizeNotSupported { texel_size } => write!(
+                f,
+                "the texel size {:?} of the fragment shading rate attachment is not supported by \
+                the device",
+                texel_size,
+            ),
             Self::FramebufferNotCompatible => {
                 write!(f, "the framebuffer is not compatible with the render pass")
             }
@@ -3079,6 +3677,54 @@ impl Display for RenderPassError {
                 f,
                 "the render area's `offset` and `extent` are outside the extent of the framebuffer",
             ),
+            Self::SampleLocationsAttachmentIndexOutOfRange {
+                attachment_index,
+                num_attachments,
+            } => write!(
+                f,
+                "an attachment index provided with sample locations ({}) is not less than the \
+                number of attachments in the render pass ({})",
+                attachment_index, num_attachments,
+            ),
+            Self::SampleLocationsCountMismatch { provided, .. } => write!(
+                f,
+                "the number of provided sample locations ({}) does not match the number required \
+                by the sample count and grid size",
+                provided,
+            ),
+            Self::SampleLocationsGridSizeNotSupported {
+                grid_size,
+                max_grid_size,
+            } => write!(
+                f,
+                "the grid size of the sample locations ({:?}) does not evenly divide the maximum \
+                grid size supported by the device ({:?})",
+                grid_size, max_grid_size,
+            ),
+            Self::SampleLocationsPerPixelNotSupported { .. } => write!(
+                f,
+                "the `per_pixel` value of the sample locations is not supported by the device",
+            ),
+            Self::SampleLocationsSubpassIndexOutOfRange {
+                subpass_index,
+                num_subpasses,
+            } => write!(
+                f,
+                "a subpass index provided with sample locations ({}) is not less than the number \
+                of subpasses in the render pass ({})",
+                subpass_index, num_subpasses,
+            ),
+            Self::StencilAttachmentClearValueMissing => write!(
+                f,
+                "the stencil attachment has a `load_op` of `LoadOp::Clear`, but no clear value \
+                was provided",
+            ),
+            Self::StencilAttachmentClearValueNotCompatible { clear_value } => write!(
+                f,
+                "the clear value ({:?}) provided for the stencil attachment is not a stencil or \
+                depth/stencil value",
+                clear_value,
+            ),
             Self::StencilAttachmentFormatUsageNotSupported => write!(
                 f,
                 "the stencil attachment has a format that does not support that usage",
@@ -3091,6 +3737,11 @@ impl Display for RenderPassError {
                 f,
                 "the stencil attachment is missing the `depth_stencil_attachment` usage",
             ),
+            Self::StencilAttachmentNotEnoughLayers { provided, min } => write!(
+                f,
+                "the stencil attachment has {} array layers, but at least {} are rendered to",
+                provided, min,
+            ),
             Self::StencilAttachmentResolveFormatMismatch => write!(
                 f,
                 "the stencil resolve attachment has a `format` value different from the \
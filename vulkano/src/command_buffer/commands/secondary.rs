@@ -26,6 +26,7 @@ use smallvec::SmallVec;
 use std::{
     error::Error,
     fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
 };
 
 /// # Commands to execute a secondary command buffer inside a primary command buffer.
@@ -408,20 +409,20 @@ impl SyncCommandBufferBuilder {
 /// Prototype for a `vkCmdExecuteCommands`.
 pub struct SyncCommandBufferBuilderExecuteCommands<'a> {
     builder: &'a mut SyncCommandBufferBuilder,
-    inner: Vec<Box<dyn SecondaryCommandBuffer>>,
+    inner: Vec<Arc<dyn SecondaryCommandBuffer>>,
 }
 
 impl<'a> SyncCommandBufferBuilderExecuteCommands<'a> {
     /// Adds a command buffer to the list.
     pub fn add(&mut self, command_buffer: impl SecondaryCommandBuffer + 'static) {
-        self.inner.push(Box::new(command_buffer));
+        self.inner.push(Arc::new(command_buffer));
     }
 
     #[inline]
     pub unsafe fn submit(self) -> Result<(), SyncCommandBufferBuilderError> {
-        struct DropUnlock(Box<dyn SecondaryCommandBuffer>);
+        struct DropUnlock(Arc<dyn SecondaryCommandBuffer>);
         impl std::ops::Deref for DropUnlock {
-            type Target = Box<dyn SecondaryCommandBuffer>;
+            type Target = Arc<dyn SecondaryCommandBuffer>;
 
             fn deref(&self) -> &Self::Target {
                 &self.0
@@ -489,6 +490,8 @@ impl<'a> SyncCommandBufferBuilderExecuteCommands<'a> {
             self.builder.check_resource_conflicts(resource)?;
         }
 
+        let command_buffers = self.inner.clone();
+
         self.builder.commands.push(Box::new(Cmd(self
             .inner
             .into_iter()
@@ -502,6 +505,10 @@ impl<'a> SyncCommandBufferBuilderExecuteCommands<'a> {
             self.builder.add_resource(resource);
         }
 
+        self.builder
+            .secondary_command_buffers
+            .extend(command_buffers);
+
         Ok(())
     }
 }
@@ -15,7 +15,7 @@ use crate::{
     image::ImageLayout,
     sync::{
         BufferMemoryBarrier, DependencyInfo, Event, ImageMemoryBarrier, MemoryBarrier,
-        PipelineStages,
+        PipelineStages, QueueFamilyTransfer, Sharing,
     },
     Version, VulkanObject,
 };
@@ -123,6 +123,9 @@ impl UnsafeCommandBufferBuilder {
                         _ne: _,
                     } = barrier;
 
+                    let (src_queue_family_index, dst_queue_family_index) =
+                        queue_family_indices(queue_family_transfer, buffer.sharing());
+
                     debug_assert!(source_stages.supported_access().contains(&source_access));
                     debug_assert!(destination_stages
                         .supported_access()
@@ -135,14 +138,8 @@ impl UnsafeCommandBufferBuilder {
                         src_access_mask: source_access.into(),
                         dst_stage_mask: destination_stages.into(),
                         dst_access_mask: destination_access.into(),
-                        src_queue_family_index: queue_family_transfer
-                            .map_or(ash::vk::QUEUE_FAMILY_IGNORED, |transfer| {
-                                transfer.source_index
-                            }),
-                        dst_queue_family_index: queue_family_transfer
-                            .map_or(ash::vk::QUEUE_FAMILY_IGNORED, |transfer| {
-                                transfer.destination_index
-                            }),
+                        src_queue_family_index,
+                        dst_queue_family_index,
                         buffer: buffer.internal_object(),
                         offset: range.start,
                         size: range.end - range.start,
@@ -167,6 +164,9 @@ impl UnsafeCommandBufferBuilder {
                         _ne: _,
                     } = barrier;
 
+                    let (src_queue_family_index, dst_queue_family_index) =
+                        queue_family_indices(queue_family_transfer, image.sharing());
+
                     debug_assert!(source_stages.supported_access().contains(&source_access));
                     debug_assert!(destination_stages
                         .supported_access()
@@ -194,14 +194,8 @@ impl UnsafeCommandBufferBuilder {
                         dst_access_mask: destination_access.into(),
                         old_layout: old_layout.into(),
                         new_layout: new_layout.into(),
-                        src_queue_family_index: queue_family_transfer
-                            .map_or(ash::vk::QUEUE_FAMILY_IGNORED, |transfer| {
-                                transfer.source_index
-                            }),
-                        dst_queue_family_index: queue_family_transfer
-                            .map_or(ash::vk::QUEUE_FAMILY_IGNORED, |transfer| {
-                                transfer.destination_index
-                            }),
+                        src_queue_family_index,
+                        dst_queue_family_index,
                         image: image.internal_object(),
                         subresource_range: subresource_range.clone().into(),
                         ..Default::default()
@@ -272,6 +266,9 @@ impl UnsafeCommandBufferBuilder {
                         _ne: _,
                     } = barrier;
 
+                    let (src_queue_family_index, dst_queue_family_index) =
+                        queue_family_indices(queue_family_transfer, buffer.sharing());
+
                     debug_assert!(source_stages.supported_access().contains(&source_access));
                     debug_assert!(destination_stages
                         .supported_access()
@@ -285,14 +282,8 @@ impl UnsafeCommandBufferBuilder {
                     ash::vk::BufferMemoryBarrier {
                         src_access_mask: source_access.into(),
                         dst_access_mask: destination_access.into(),
-                        src_queue_family_index: queue_family_transfer
-                            .map_or(ash::vk::QUEUE_FAMILY_IGNORED, |transfer| {
-                                transfer.source_index
-                            }),
-                        dst_queue_family_index: queue_family_transfer
-                            .map_or(ash::vk::QUEUE_FAMILY_IGNORED, |transfer| {
-                                transfer.destination_index
-                            }),
+                        src_queue_family_index,
+                        dst_queue_family_index,
                         buffer: buffer.internal_object(),
                         offset: range.start,
                         size: range.end - range.start,
@@ -317,6 +308,9 @@ impl UnsafeCommandBufferBuilder {
                         _ne: _,
                     } = barrier;
 
+                    let (src_queue_family_index, dst_queue_family_index) =
+                        queue_family_indices(queue_family_transfer, image.sharing());
+
                     debug_assert!(source_stages.supported_access().contains(&source_access));
                     debug_assert!(destination_stages
                         .supported_access()
@@ -345,14 +339,8 @@ impl UnsafeCommandBufferBuilder {
                         dst_access_mask: destination_access.into(),
                         old_layout: old_layout.into(),
                         new_layout: new_layout.into(),
-                        src_queue_family_index: queue_family_transfer
-                            .map_or(ash::vk::QUEUE_FAMILY_IGNORED, |transfer| {
-                                transfer.source_index
-                            }),
-                        dst_queue_family_index: queue_family_transfer
-                            .map_or(ash::vk::QUEUE_FAMILY_IGNORED, |transfer| {
-                                transfer.destination_index
-                            }),
+                        src_queue_family_index,
+                        dst_queue_family_index,
                         image: image.internal_object(),
                         subresource_range: subresource_range.clone().into(),
                         ..Default::default()
@@ -408,3 +396,15 @@ impl UnsafeCommandBufferBuilder {
         (fns.v1_0.cmd_reset_event)(self.handle, event.internal_object(), stages.into());
     }
 }
+
+// Returns the source and destination queue family indices of a barrier. Ownership transfers
+// don't apply to resources with concurrent sharing, so they are ignored for those.
+fn queue_family_indices(
+    queue_family_transfer: Option<QueueFamilyTransfer>,
+    sharing: &Sharing<impl AsRef<[u32]>>,
+) -> (u32, u32) {
+    match (queue_family_transfer, sharing) {
+        (Some(transfer), Sharing::Exclusive) => (transfer.source_index, transfer.destination_index),
+        _ => (ash::vk::QUEUE_FAMILY_IGNORED, ash::vk::QUEUE_FAMILY_IGNORED),
+    }
+}
@@ -8,7 +8,7 @@
 // according to those terms.
 
 use crate::{
-    buffer::{BufferAccess, BufferContents, TypedBufferAccess},
+    buffer::{BufferAccess, BufferContents, BufferUsage, CpuAccessibleBuffer, TypedBufferAccess},
     command_buffer::{
         allocator::CommandBufferAllocator,
         synced::{Command, Resource, SyncCommandBufferBuilder, SyncCommandBufferBuilderError},
@@ -40,6 +40,9 @@ where
 {
     /// Copies data from a buffer to another buffer.
     ///
+    /// `src_buffer` and `dst_buffer` may be the same buffer, as long as no source range of a
+    /// region overlaps with a destination range of any region.
+    ///
     /// # Panics
     ///
     /// - Panics if `src_buffer` or `dst_buffer` were not created from the same device
@@ -106,9 +109,6 @@ where
             });
         }
 
-        let same_buffer = src_buffer_inner.buffer == dst_buffer_inner.buffer;
-        let mut overlap_indices = None;
-
         for (region_index, region) in regions.iter().enumerate() {
             let &BufferCopy {
                 src_offset,
@@ -118,60 +118,60 @@ where
             } = region;
 
             // VUID-VkBufferCopy2-size-01988
-            assert!(size != 0);
+            if size == 0 {
+                return Err(CopyError::RegionSizeZero { region_index });
+            }
 
             // VUID-VkCopyBufferInfo2-srcOffset-00113
             // VUID-VkCopyBufferInfo2-size-00115
-            if src_offset + size > src_buffer.size() {
+            let src_offset_range_end = src_offset.saturating_add(size);
+
+            if src_offset_range_end > src_buffer.size() {
                 return Err(CopyError::RegionOutOfBufferBounds {
                     resource: CopyErrorResource::Source,
                     region_index,
-                    offset_range_end: src_offset + size,
+                    offset_range_end: src_offset_range_end,
                     buffer_size: src_buffer.size(),
                 });
             }
 
             // VUID-VkCopyBufferInfo2-dstOffset-00114
             // VUID-VkCopyBufferInfo2-size-00116
-            if dst_offset + size > dst_buffer.size() {
+            let dst_offset_range_end = dst_offset.saturating_add(size);
+
+            if dst_offset_range_end > dst_buffer.size() {
                 return Err(CopyError::RegionOutOfBufferBounds {
                     resource: CopyErrorResource::Destination,
                     region_index,
-                    offset_range_end: dst_offset + size,
+                    offset_range_end: dst_offset_range_end,
                     buffer_size: dst_buffer.size(),
                 });
             }
+        }
 
-            // VUID-VkCopyBufferInfo2-pRegions-00117
-            if same_buffer {
-                let src_region_index = region_index;
-                let src_range = src_buffer_inner.offset + src_offset
-                    ..src_buffer_inner.offset + src_offset + size;
+        // VUID-VkCopyBufferInfo2-pRegions-00117
+        // Copying within the same buffer is allowed, as long as no source range overlaps with
+        // any destination range. The ranges are compared in the underlying buffer, because
+        // `src_buffer` and `dst_buffer` may be different slices of it.
+        if src_buffer_inner.buffer == dst_buffer_inner.buffer {
+            for (src_region_index, src_region) in regions.iter().enumerate() {
+                let src_start = src_buffer_inner.offset + src_region.src_offset;
+                let src_range = src_start..src_start + src_region.size;
 
                 for (dst_region_index, dst_region) in regions.iter().enumerate() {
-                    let &BufferCopy { dst_offset, .. } = dst_region;
-
-                    let dst_range = dst_buffer_inner.offset + dst_offset
-                        ..dst_buffer_inner.offset + dst_offset + size;
+                    let dst_start = dst_buffer_inner.offset + dst_region.dst_offset;
+                    let dst_range = dst_start..dst_start + dst_region.size;
 
-                    if src_range.start >= dst_range.end || dst_range.start >= src_range.end {
-                        // The regions do not overlap
-                        continue;
+                    if src_range.start < dst_range.end && dst_range.start < src_range.end {
+                        return Err(CopyError::OverlappingRegions {
+                            src_region_index,
+                            dst_region_index,
+                        });
                     }
-
-                    overlap_indices = Some((src_region_index, dst_region_index));
                 }
             }
         }
 
-        // VUID-VkCopyBufferInfo2-pRegions-00117
-        if let Some((src_region_index, dst_region_index)) = overlap_indices {
-            return Err(CopyError::OverlappingRegions {
-                src_region_index,
-                dst_region_index,
-            });
-        }
-
         Ok(())
     }
 
@@ -498,9 +498,6 @@ where
                 });
             }
 
-            // TODO:
-            // "When copying between compressed and uncompressed formats the extent members
-            // represent the texel dimensions of the source image and not the destination."
             let mut src_extent = extent;
             let mut dst_extent = extent;
             let src_layer_count =
@@ -559,6 +556,21 @@ where
                 }
             };
 
+            // "When copying between compressed and uncompressed formats the extent members
+            // represent the texel dimensions of the source image and not the destination."
+            let src_block_extent = src_subresource_format.block_extent();
+            let dst_block_extent = dst_subresource_format.block_extent();
+
+            if src_block_extent != dst_block_extent {
+                dst_extent = convert_block_extent(
+                    dst_extent,
+                    src_block_extent,
+                    dst_block_extent,
+                    dst_offset,
+                    dst_subresource_extent,
+                );
+            }
+
             if let Some((src_extent_alignment, dst_extent_alignment)) = extent_alignment {
                 let check_offset_extent = |resource: CopyErrorResource,
                                            extent_alignment: [u32; 3],
@@ -1942,6 +1954,81 @@ where
 
         Ok(())
     }
+
+    /// Writes data to a region of a buffer, using the command that suits the data best.
+    ///
+    /// - If `dst_offset` and the size of `data` are multiples of 4, and `data` consists of a
+    ///   single repeated 4-byte value, a [`fill_buffer`](Self::fill_buffer) command is recorded.
+    /// - Otherwise, if `dst_offset` and the size of `data` are multiples of 4, and `data` is at
+    ///   most 65536 bytes, an [`update_buffer`](Self::update_buffer) command is recorded.
+    /// - Otherwise, `data` is written to a new staging buffer, and a
+    ///   [`copy_buffer`](Self::copy_buffer) command is recorded that copies it to `dst_buffer`.
+    ///
+    /// Unlike with `update_buffer`, there is no limit on the size of `data`, and `dst_offset`
+    /// doesn't need to be aligned.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `dst_buffer` was not created from the same device as `self`.
+    /// - Panics if `data` is empty.
+    pub fn write_buffer<D>(
+        &mut self,
+        data: &D,
+        dst_buffer: Arc<dyn BufferAccess>,
+        dst_offset: DeviceSize,
+    ) -> Result<&mut Self, CopyError>
+    where
+        D: BufferContents + ?Sized,
+    {
+        let bytes = data.as_bytes();
+        let size = bytes.len() as DeviceSize;
+        assert!(size != 0);
+
+        if (dst_buffer.inner().offset + dst_offset) % 4 == 0 && size % 4 == 0 {
+            let (first, rest) = bytes.split_at(4);
+
+            if rest.chunks_exact(4).all(|word| word == first) {
+                return self.fill_buffer(FillBufferInfo {
+                    data: u32::from_ne_bytes(first.try_into().unwrap()),
+                    dst_offset,
+                    size,
+                    ..FillBufferInfo::dst_buffer(dst_buffer)
+                });
+            }
+
+            if size <= 65536 {
+                let data: Box<[u8]> = bytes.into();
+                self.validate_update_buffer(&*data, &dst_buffer, dst_offset)?;
+
+                unsafe {
+                    self.inner.update_buffer(data, dst_buffer, dst_offset)?;
+                }
+
+                return Ok(self);
+            }
+        }
+
+        let staging_buffer = CpuAccessibleBuffer::from_iter(
+            self.device().clone(),
+            BufferUsage {
+                transfer_src: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            bytes.iter().copied(),
+        )?;
+
+        self.copy_buffer(CopyBufferInfo {
+            regions: [BufferCopy {
+                src_offset: 0,
+                dst_offset,
+                size,
+                ..Default::default()
+            }]
+            .into(),
+            ..CopyBufferInfo::buffers(staging_buffer, dst_buffer)
+        })
+    }
 }
 
 impl SyncCommandBufferBuilder {
@@ -3122,10 +3209,16 @@ where
             _ne: _,
         } = typed;
 
+        // Saturating, so that regions that are too large in elements are reported as out of bounds
+        // when the copy is validated, instead of overflowing.
         for region in &mut regions {
-            region.src_offset *= size_of::<T>() as DeviceSize;
-            region.dst_offset *= size_of::<T>() as DeviceSize;
-            region.size *= size_of::<T>() as DeviceSize;
+            region.src_offset = region
+                .src_offset
+                .saturating_mul(size_of::<T>() as DeviceSize);
+            region.dst_offset = region
+                .dst_offset
+                .saturating_mul(size_of::<T>() as DeviceSize);
+            region.size = region.size.saturating_mul(size_of::<T>() as DeviceSize);
         }
 
         Self {
@@ -3540,6 +3633,37 @@ impl BufferImageCopy {
     }
 }
 
+/// Converts an extent in texels of a format with `src_block_extent`, into the extent in texels
+/// that covers the same number of texel blocks of a format with `dst_block_extent`.
+///
+/// In the smallest mip levels of a block-compressed image, the last texel block in a dimension
+/// can extend beyond the edge of the subresource. If the converted extent ends in such a block, it
+/// is clamped to the edge of the subresource.
+fn convert_block_extent(
+    extent: [u32; 3],
+    src_block_extent: [u32; 3],
+    dst_block_extent: [u32; 3],
+    dst_offset: [u32; 3],
+    dst_subresource_extent: [u32; 3],
+) -> [u32; 3] {
+    let mut dst_extent = extent;
+
+    // Texel blocks always have a depth of 1.
+    for i in 0..2 {
+        let block_count = (extent[i] + src_block_extent[i] - 1) / src_block_extent[i];
+        dst_extent[i] = block_count * dst_block_extent[i];
+
+        let end = dst_offset[i] + dst_extent[i];
+
+        if end > dst_subresource_extent[i] && end - dst_block_extent[i] < dst_subresource_extent[i]
+        {
+            dst_extent[i] = dst_subresource_extent[i] - dst_offset[i];
+        }
+    }
+
+    dst_extent
+}
+
 /// Parameters to fill a region of a buffer with repeated copies of a value.
 #[derive(Clone, Debug)]
 pub struct FillBufferInfo {
@@ -3648,4 +3772,36 @@ mod tests {
             29584
         );
     }
+
+    #[test]
+    fn convert_block_extent_mip_tail() {
+        let bc1 = Format::BC1_RGB_UNORM_BLOCK.block_extent();
+        let r32g32 = Format::R32G32_UINT.block_extent();
+
+        // Uncompressed to compressed, full blocks.
+        assert_eq!(
+            convert_block_extent([2, 2, 1], r32g32, bc1, [0, 0, 0], [16, 16, 1]),
+            [8, 8, 1]
+        );
+        // Uncompressed to compressed, single partial block at a 2x2 mip level.
+        assert_eq!(
+            convert_block_extent([1, 1, 1], r32g32, bc1, [0, 0, 0], [2, 2, 1]),
+            [2, 2, 1]
+        );
+        // Uncompressed to compressed, partial block at the edge of a 6x6 mip level.
+        assert_eq!(
+            convert_block_extent([1, 1, 1], r32g32, bc1, [4, 4, 0], [6, 6, 1]),
+            [2, 2, 1]
+        );
+        // Compressed to uncompressed, partial block at a 2x2 mip level.
+        assert_eq!(
+            convert_block_extent([2, 2, 1], bc1, r32g32, [0, 0, 0], [1, 1, 1]),
+            [1, 1, 1]
+        );
+        // Out of bounds by more than a partial block is not clamped.
+        assert_eq!(
+            convert_block_extent([2, 2, 1], r32g32, bc1, [0, 0, 0], [2, 2, 1]),
+            [8, 8, 1]
+        );
+    }
 }
@@ -96,7 +96,7 @@ pub use self::{
         SecondaryAutoCommandBuffer,
     },
     commands::{
-        debug::DebugUtilsError,
+        debug::{DebugUtilsError, DebugUtilsLabelScope},
         image::{
             BlitImageInfo, ClearColorImageInfo, ClearDepthStencilImageInfo, ImageBlit,
             ImageResolve, ResolveImageInfo,
@@ -105,7 +105,8 @@ pub use self::{
         query::QueryError,
         render_pass::{
             ClearAttachment, ClearRect, RenderPassBeginInfo, RenderPassError,
-            RenderingAttachmentInfo, RenderingAttachmentResolveInfo, RenderingInfo,
+            RenderingAttachmentInfo, RenderingAttachmentResolveInfo,
+            RenderingFragmentShadingRateAttachmentInfo, RenderingInfo,
         },
         secondary::{ExecuteCommandsError, UnsafeCommandBufferBuilderExecuteCommands},
         transfer::{
@@ -123,12 +124,13 @@ use crate::{
     format::Format,
     image::SampleCount,
     macros::vulkan_enum,
+    memory::DeviceMemory,
     query::{QueryControlFlags, QueryPipelineStatisticFlags},
     render_pass::{Framebuffer, Subpass},
     sync::{PipelineStages, Semaphore},
 };
 use bytemuck::{Pod, Zeroable};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 pub mod allocator;
 mod auto;
@@ -138,6 +140,10 @@ pub mod synced;
 pub mod sys;
 mod traits;
 
+/// The arguments of a single draw in the indirect buffer of
+/// [`draw_indirect`](AutoCommandBufferBuilder::draw_indirect).
+///
+/// The layout matches `VkDrawIndirectCommand`, so the struct can also be written by a shader.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq)]
 pub struct DrawIndirectCommand {
@@ -147,16 +153,26 @@ pub struct DrawIndirectCommand {
     pub first_instance: u32,
 }
 
+/// The arguments of a single draw in the indirect buffer of
+/// [`draw_indexed_indirect`](AutoCommandBufferBuilder::draw_indexed_indirect).
+///
+/// The layout matches `VkDrawIndexedIndirectCommand`, so the struct can also be written by a
+/// shader.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq)]
 pub struct DrawIndexedIndirectCommand {
     pub index_count: u32,
     pub instance_count: u32,
     pub first_index: u32,
-    pub vertex_offset: u32,
+    /// The value added to each index before indexing into the vertex buffers. It can be negative.
+    pub vertex_offset: i32,
     pub first_instance: u32,
 }
 
+/// The arguments of a single dispatch in the indirect buffer of
+/// [`dispatch_indirect`](AutoCommandBufferBuilder::dispatch_indirect).
+///
+/// The layout matches `VkDispatchIndirectCommand`, so the struct can also be written by a shader.
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, Zeroable, Pod, PartialEq, Eq)]
 pub struct DispatchIndirectCommand {
@@ -388,6 +404,9 @@ pub enum CommandBufferUsage {
     /// The command buffer can be executed multiple times in parallel on different queues.
     /// If it's a secondary command buffer, it can be recorded to multiple primary command buffers
     /// at once.
+    ///
+    /// A primary command buffer that executes a secondary command buffer without this usage
+    /// loses its simultaneous use, and behaves as `MultipleSubmit` instead.
     SimultaneousUse = ash::vk::CommandBufferUsageFlags::SIMULTANEOUS_USE.as_raw(),
 }
 
@@ -418,6 +437,34 @@ pub struct SubmitInfo {
     /// The default value is empty.
     pub signal_semaphores: Vec<SemaphoreSubmitInfo>,
 
+    /// Whether this is a protected submission. If set, all command buffers must have been
+    /// allocated from a [`protected`](pool::CommandPoolCreateInfo::protected) command pool, and the
+    /// queue must have been created with the [`protected`](crate::device::QueueCreateFlags::protected)
+    /// flag. If not set, none of the command buffers may be protected.
+    ///
+    /// The default value is `false`.
+    pub protected_submit: bool,
+
+    /// The keyed mutexes of Win32 memory objects to acquire before beginning the execution of
+    /// this batch of command buffer operations.
+    ///
+    /// If not empty, the
+    /// [`khr_win32_keyed_mutex`](crate::device::DeviceExtensions::khr_win32_keyed_mutex)
+    /// extension must be enabled on the device.
+    ///
+    /// The default value is empty.
+    pub win32_keyed_mutex_acquires: Vec<Win32KeyedMutexAcquireInfo>,
+
+    /// The keyed mutexes of Win32 memory objects to release after the execution of this batch of
+    /// command buffer operations has completed.
+    ///
+    /// If not empty, the
+    /// [`khr_win32_keyed_mutex`](crate::device::DeviceExtensions::khr_win32_keyed_mutex)
+    /// extension must be enabled on the device.
+    ///
+    /// The default value is empty.
+    pub win32_keyed_mutex_releases: Vec<Win32KeyedMutexReleaseInfo>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -428,6 +475,9 @@ impl Default for SubmitInfo {
             wait_semaphores: Vec::new(),
             command_buffers: Vec::new(),
             signal_semaphores: Vec::new(),
+            protected_submit: false,
+            win32_keyed_mutex_acquires: Vec::new(),
+            win32_keyed_mutex_releases: Vec::new(),
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -439,6 +489,15 @@ pub struct SemaphoreSubmitInfo {
     /// The semaphore to signal or wait for.
     pub semaphore: Arc<Semaphore>,
 
+    /// If `semaphore` is a [timeline semaphore](crate::sync::SemaphoreType::Timeline), specifies
+    /// the value that the semaphore's payload must reach for a wait operation, or the value that
+    /// the payload will be set to by a signal operation.
+    ///
+    /// For a binary semaphore, this value is ignored.
+    ///
+    /// The default value is `0`.
+    pub value: u64,
+
     /// For a semaphore wait operation, specifies the pipeline stages in the second synchronization
     /// scope: stages of queue operations following the wait operation that can start executing
     /// after the semaphore is signalled.
@@ -446,9 +505,10 @@ pub struct SemaphoreSubmitInfo {
     /// For a semaphore signal operation, specifies the pipeline stages in the first synchronization
     /// scope: stages of queue operations preceding the signal operation that must complete before
     /// the semaphore is signalled.
-    /// If not set to `all_commands` only, the
-    /// [`synchronization2`](crate::device::Features::synchronization2) feature must be enabled
-    /// on the device.
+    ///
+    /// If the [`synchronization2`](crate::device::Features::synchronization2) feature is not
+    /// enabled on the device, the stages of a signal operation are ignored and treated as
+    /// `all_commands`, and the stages of a wait operation must not be empty.
     ///
     /// The default value has only `all_commands` set.
     pub stages: PipelineStages,
@@ -462,6 +522,7 @@ impl SemaphoreSubmitInfo {
     pub fn semaphore(semaphore: Arc<Semaphore>) -> Self {
         Self {
             semaphore,
+            value: 0,
             stages: PipelineStages {
                 all_commands: true,
                 ..PipelineStages::empty()
@@ -470,3 +531,70 @@ impl SemaphoreSubmitInfo {
         }
     }
 }
+
+/// Parameters to acquire the keyed mutex of a Win32 memory object in a command buffer
+/// submission.
+///
+/// The memory must have been imported from, or exported to, a handle of type
+/// [`OpaqueWin32`](crate::memory::ExternalMemoryHandleType::OpaqueWin32),
+/// [`OpaqueWin32Kmt`](crate::memory::ExternalMemoryHandleType::OpaqueWin32Kmt),
+/// [`D3D11Texture`](crate::memory::ExternalMemoryHandleType::D3D11Texture) or
+/// [`D3D11TextureKmt`](crate::memory::ExternalMemoryHandleType::D3D11TextureKmt), whose
+/// underlying resource has a keyed mutex, for example a D3D11 texture that was created with the
+/// `D3D11_RESOURCE_MISC_SHARED_KEYEDMUTEX` flag.
+#[derive(Clone, Debug)]
+pub struct Win32KeyedMutexAcquireInfo {
+    /// The memory whose keyed mutex to acquire.
+    pub memory: Arc<DeviceMemory>,
+
+    /// The key that the mutex must have been released with.
+    pub key: u64,
+
+    /// How long to wait for the mutex to be released with `key`, with a precision of
+    /// milliseconds. If `None`, waits indefinitely.
+    ///
+    /// The default value is `None`.
+    pub timeout: Option<Duration>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Win32KeyedMutexAcquireInfo {
+    /// Returns a `Win32KeyedMutexAcquireInfo` with the specified `memory` and `key`.
+    #[inline]
+    pub fn memory(memory: Arc<DeviceMemory>, key: u64) -> Self {
+        Self {
+            memory,
+            key,
+            timeout: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// Parameters to release the keyed mutex of a Win32 memory object in a command buffer
+/// submission.
+///
+/// The same requirements apply to the memory as for [`Win32KeyedMutexAcquireInfo`].
+#[derive(Clone, Debug)]
+pub struct Win32KeyedMutexReleaseInfo {
+    /// The memory whose keyed mutex to release.
+    pub memory: Arc<DeviceMemory>,
+
+    /// The key to release the mutex with.
+    pub key: u64,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Win32KeyedMutexReleaseInfo {
+    /// Returns a `Win32KeyedMutexReleaseInfo` with the specified `memory` and `key`.
+    #[inline]
+    pub fn memory(memory: Arc<DeviceMemory>, key: u64) -> Self {
+        Self {
+            memory,
+            key,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
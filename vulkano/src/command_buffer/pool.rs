@@ -39,6 +39,7 @@ pub struct CommandPool {
     queue_family_index: u32,
     _transient: bool,
     _reset_command_buffer: bool,
+    protected: bool,
     // Unimplement `Sync`, as Vulkan command pools are not thread-safe.
     _marker: PhantomData<Cell<ash::vk::CommandPool>>,
 }
@@ -56,6 +57,7 @@ impl CommandPool {
             queue_family_index,
             transient,
             reset_command_buffer,
+            protected,
             _ne: _,
         } = create_info;
 
@@ -65,6 +67,7 @@ impl CommandPool {
             queue_family_index,
             _transient: transient,
             _reset_command_buffer: reset_command_buffer,
+            protected,
             _marker: PhantomData,
         })
     }
@@ -85,6 +88,7 @@ impl CommandPool {
             queue_family_index,
             transient,
             reset_command_buffer,
+            protected,
             _ne: _,
         } = create_info;
 
@@ -94,6 +98,7 @@ impl CommandPool {
             queue_family_index,
             _transient: transient,
             _reset_command_buffer: reset_command_buffer,
+            protected,
             _marker: PhantomData,
         }
     }
@@ -106,6 +111,7 @@ impl CommandPool {
             queue_family_index,
             transient: _,
             reset_command_buffer: _,
+            protected,
             _ne: _,
         } = create_info;
 
@@ -117,6 +123,17 @@ impl CommandPool {
             });
         }
 
+        // VUID-VkCommandPoolCreateInfo-flags-02860
+        if protected && !device.enabled_features().protected_memory {
+            return Err(CommandPoolCreationError::RequirementNotMet {
+                required_for: "`create_info.protected` is set",
+                requires_one_of: RequiresOneOf {
+                    features: &["protected_memory"],
+                    ..Default::default()
+                },
+            });
+        }
+
         Ok(())
     }
 
@@ -128,6 +145,7 @@ impl CommandPool {
             queue_family_index,
             transient,
             reset_command_buffer,
+            protected,
             _ne: _,
         } = create_info;
 
@@ -141,6 +159,10 @@ impl CommandPool {
             flags |= ash::vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER;
         }
 
+        if protected {
+            flags |= ash::vk::CommandPoolCreateFlags::PROTECTED;
+        }
+
         let create_info = ash::vk::CommandPoolCreateInfo {
             flags,
             queue_family_index,
@@ -227,12 +249,14 @@ impl CommandPool {
         };
 
         let device = self.device.clone();
+        let protected = self.protected;
 
         Ok(out.into_iter().map(move |command_buffer| CommandPoolAlloc {
             handle: command_buffer,
             device: device.clone(),
 
             level,
+            protected,
         }))
     }
 
@@ -308,6 +332,12 @@ impl CommandPool {
     pub fn queue_family_index(&self) -> u32 {
         self.queue_family_index
     }
+
+    /// Returns whether the command buffers of this pool are protected command buffers.
+    #[inline]
+    pub fn protected(&self) -> bool {
+        self.protected
+    }
 }
 
 impl Drop for CommandPool {
@@ -362,6 +392,11 @@ pub enum CommandPoolCreationError {
     /// Not enough memory.
     OomError(OomError),
 
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
     /// The provided `queue_family_index` was not less than the number of queue families in the
     /// physical device.
     QueueFamilyIndexOutOfRange {
@@ -383,6 +418,14 @@ impl Display for CommandPoolCreationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         match self {
             Self::OomError(_) => write!(f, "not enough memory",),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
             Self::QueueFamilyIndexOutOfRange {
                 queue_family_index,
                 queue_family_count,
@@ -425,6 +468,15 @@ pub struct CommandPoolCreateInfo {
     /// The default value is `false`.
     pub reset_command_buffer: bool,
 
+    /// Whether the command buffers allocated from this pool are protected command buffers, which
+    /// can only be submitted in a protected submission.
+    ///
+    /// If set, the [`protected_memory`](crate::device::Features::protected_memory) feature must
+    /// be enabled on the device.
+    ///
+    /// The default value is `false`.
+    pub protected: bool,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -435,6 +487,7 @@ impl Default for CommandPoolCreateInfo {
             queue_family_index: u32::MAX,
             transient: false,
             reset_command_buffer: false,
+            protected: false,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -473,6 +526,7 @@ pub struct CommandPoolAlloc {
     handle: ash::vk::CommandBuffer,
     device: Arc<Device>,
     level: CommandBufferLevel,
+    protected: bool,
 }
 
 impl CommandPoolAlloc {
@@ -481,6 +535,12 @@ impl CommandPoolAlloc {
     pub fn level(&self) -> CommandBufferLevel {
         self.level
     }
+
+    /// Returns whether the command buffer is a protected command buffer.
+    #[inline]
+    pub fn protected(&self) -> bool {
+        self.protected
+    }
 }
 
 unsafe impl VulkanObject for CommandPoolAlloc {
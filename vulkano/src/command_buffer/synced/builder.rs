@@ -20,7 +20,7 @@ use crate::{
         pool::CommandPoolAlloc,
         synced::{BufferFinalState, BufferUse, ImageFinalState, ImageUse},
         sys::{CommandBufferBeginInfo, UnsafeCommandBufferBuilder},
-        CommandBufferExecError, CommandBufferLevel,
+        CommandBufferExecError, CommandBufferLevel, SecondaryCommandBuffer,
     },
     descriptor_set::{DescriptorSetResources, DescriptorSetWithOffsets},
     device::{Device, DeviceOwned},
@@ -29,24 +29,29 @@ use crate::{
         graphics::{
             color_blend::LogicOp,
             depth_stencil::{CompareOp, StencilOps},
+            fragment_shading_rate::FragmentShadingRate,
             input_assembly::{IndexType, PrimitiveTopology},
-            rasterization::{CullMode, DepthBias, FrontFace, LineStipple},
+            multisample::SampleLocations,
+            rasterization::{CullMode, DepthBias, FrontFace, LineStipple, PolygonMode},
             viewport::{Scissor, Viewport},
         },
-        ComputePipeline, DynamicState, GraphicsPipeline, PipelineBindPoint, PipelineLayout,
+        ComputePipeline, DynamicState, GraphicsPipeline, Pipeline, PipelineBindPoint,
+        PipelineLayout,
     },
     range_map::RangeMap,
     range_set::RangeSet,
     sync::{
-        AccessFlags, BufferMemoryBarrier, DependencyInfo, ImageMemoryBarrier, PipelineMemoryAccess,
-        PipelineStages,
+        AccessFlags, BufferMemoryBarrier, DependencyInfo, ImageMemoryBarrier, MemoryBarrier,
+        PipelineMemoryAccess, PipelineStages,
     },
     DeviceSize, OomError, VulkanObject,
 };
-use ahash::HashMap;
+use ahash::{HashMap, HashSet};
+use ash::vk::Handle;
 use smallvec::SmallVec;
 use std::{
     borrow::Cow,
+    cmp::{max, min},
     collections::hash_map::Entry,
     error::Error,
     fmt::{Debug, Display, Error as FmtError, Formatter},
@@ -78,10 +83,18 @@ pub struct SyncCommandBufferBuilder {
     // in `commands`.
     pending_barrier: DependencyInfo,
 
-    // Locations within commands that pipeline barriers were inserted. For debugging purposes.
-    // TODO: present only in cfg(debug_assertions)?
+    // Locations within commands that pipeline barriers were inserted.
     barriers: Vec<usize>,
 
+    // Whether `pending_barrier` is optimized before it is recorded.
+    optimize_barriers: bool,
+
+    // Pipelines that were bound, in the order they were bound.
+    pub(in crate::command_buffer) bound_pipelines: Vec<Arc<dyn Pipeline + Send + Sync>>,
+
+    // Secondary command buffers that were executed, in the order they were executed.
+    pub(in crate::command_buffer) secondary_command_buffers: Vec<Arc<dyn SecondaryCommandBuffer>>,
+
     // Only the commands before `first_unflushed` have already been sent to the inner
     // `UnsafeCommandBufferBuilder`.
     first_unflushed: usize,
@@ -94,6 +107,10 @@ pub struct SyncCommandBufferBuilder {
     buffers2: HashMap<Arc<UnsafeBuffer>, RangeMap<DeviceSize, BufferState>>,
     images2: HashMap<Arc<UnsafeImage>, RangeMap<DeviceSize, ImageState>>,
 
+    // Stores the latest uses of each memory object that buffers and images are bound to, so that
+    // accesses to resources that alias the same memory can be synchronized with each other.
+    memory_uses: HashMap<ash::vk::DeviceMemory, Vec<MemoryUse>>,
+
     // Resources and their accesses. Used for executing secondary command buffers in a primary.
     buffers: Vec<(
         Arc<dyn BufferAccess>,
@@ -164,10 +181,14 @@ impl SyncCommandBufferBuilder {
             commands: Vec::new(),
             pending_barrier: DependencyInfo::default(),
             barriers: Vec::new(),
+            optimize_barriers: false,
+            bound_pipelines: Vec::new(),
+            secondary_command_buffers: Vec::new(),
             first_unflushed: 0,
             latest_render_pass_enter,
             buffers2: HashMap::default(),
             images2: HashMap::default(),
+            memory_uses: HashMap::default(),
             buffers: Vec::new(),
             images: Vec::new(),
             current_state: Default::default(),
@@ -182,6 +203,15 @@ impl SyncCommandBufferBuilder {
         }
     }
 
+    /// Sets whether the pipeline barriers that are inserted are optimized with
+    /// [`DependencyInfo::optimize`] before they are recorded. When enabled, barriers that would be
+    /// recorded directly after each other are also merged, and access types and stages that have
+    /// no effect are removed.
+    #[inline]
+    pub fn set_optimize_barriers(&mut self, enabled: bool) {
+        self.optimize_barriers = enabled;
+    }
+
     /// Resets the binding/setting state.
     ///
     /// This must be called after any command that changes the state in an undefined way, e.g.
@@ -215,6 +245,20 @@ impl SyncCommandBufferBuilder {
                         previous_command_param: conflicting_use.name.clone(),
                     });
                 }
+
+                let inner = buffer.inner().buffer;
+
+                if let Some(conflicting_use) = self.find_memory_alias_conflict(
+                    buffer_memory_resource(inner),
+                    inner.memory_binding(),
+                ) {
+                    return Err(SyncCommandBufferBuilderError::Conflict {
+                        command_param: resource_name.clone(),
+                        previous_command_name: self.commands[conflicting_use.command_index].name(),
+                        previous_command_offset: conflicting_use.command_index,
+                        previous_command_param: conflicting_use.name.clone(),
+                    });
+                }
             }
             Resource::Image {
                 ref image,
@@ -242,6 +286,20 @@ impl SyncCommandBufferBuilder {
                         previous_command_param: conflicting_use.name.clone(),
                     });
                 }
+
+                let inner = image.inner().image;
+
+                if let Some(conflicting_use) = self.find_memory_alias_conflict(
+                    image_memory_resource(inner),
+                    inner.memory_binding(),
+                ) {
+                    return Err(SyncCommandBufferBuilderError::Conflict {
+                        command_param: resource_name.clone(),
+                        previous_command_name: self.commands[conflicting_use.command_index].name(),
+                        previous_command_offset: conflicting_use.command_index,
+                        previous_command_param: conflicting_use.name.clone(),
+                    });
+                }
             }
         }
 
@@ -347,6 +405,31 @@ impl SyncCommandBufferBuilder {
         None
     }
 
+    fn find_memory_alias_conflict(
+        &self,
+        resource: MemoryResource,
+        memory_binding: Option<(ash::vk::DeviceMemory, Range<DeviceSize>)>,
+    ) -> Option<&MemoryUse> {
+        let (device_memory, memory_range) = memory_binding?;
+
+        // Barriers work differently in render passes, so if we're in one, we can only insert a
+        // barrier before the start of the render pass.
+        let last_allowed_barrier_index =
+            self.latest_render_pass_enter.unwrap_or(self.commands.len());
+
+        // Accesses through another resource that aliases the same memory always conflict, so a
+        // barrier must be inserted between them.
+        self.memory_uses
+            .get(&device_memory)?
+            .iter()
+            .find(|memory_use| {
+                memory_use.resource != resource
+                    && memory_use.range.start < memory_range.end
+                    && memory_range.start < memory_use.range.end
+                    && memory_use.command_index >= last_allowed_barrier_index
+            })
+    }
+
     /// Adds a command to be processed by the builder.
     ///
     /// The `resources` argument should contain each buffer or image used by the command.
@@ -410,6 +493,13 @@ impl SyncCommandBufferBuilder {
         range.start += inner.offset;
         range.end += inner.offset;
 
+        self.add_memory_use(
+            buffer_memory_resource(inner.buffer),
+            inner.buffer.memory_binding(),
+            &resource_name,
+            memory,
+        );
+
         let range_map = self
             .buffers2
             .entry(inner.buffer.clone())
@@ -458,18 +548,7 @@ impl SyncCommandBufferBuilder {
                         .any(|resource_use| resource_use.command_index >= self.first_unflushed)
                     {
                         unsafe {
-                            // Flush the pending barrier.
-                            self.inner.pipeline_barrier(&self.pending_barrier);
-                            self.pending_barrier.clear();
-                            self.barriers.push(self.first_unflushed); // Track inserted barriers
-
-                            for command in
-                                &mut self.commands[self.first_unflushed..last_allowed_barrier_index]
-                            {
-                                command.send(&mut self.inner);
-                            }
-
-                            self.first_unflushed = last_allowed_barrier_index;
+                            self.flush(last_allowed_barrier_index);
                         }
                     }
 
@@ -531,6 +610,13 @@ impl SyncCommandBufferBuilder {
         subresource_range.mip_levels.start += inner.first_mipmap_level;
         subresource_range.mip_levels.end += inner.first_mipmap_level;
 
+        self.add_memory_use(
+            image_memory_resource(inner.image),
+            inner.image.memory_binding(),
+            &resource_name,
+            memory,
+        );
+
         let range_map = self.images2.entry(inner.image.clone()).or_insert_with(|| {
             [(
                 0..inner.image.range_size(),
@@ -634,22 +720,22 @@ impl SyncCommandBufferBuilder {
                                         //   pipeline is going to be merged with an existing
                                         //   barrier. While it may still be suboptimal in some
                                         //   cases, in the general situation it will be ok.
-                                        self.pending_barrier.image_memory_barriers.push(
-                                            ImageMemoryBarrier {
-                                                source_stages: PipelineStages {
-                                                    bottom_of_pipe: true,
-                                                    ..PipelineStages::empty()
+                                        self.pending_barrier.image_memory_barriers.extend(
+                                            inner.image.range_to_subresources(range.clone()).map(
+                                                |subresource_range| ImageMemoryBarrier {
+                                                    source_stages: PipelineStages {
+                                                        bottom_of_pipe: true,
+                                                        ..PipelineStages::empty()
+                                                    },
+                                                    source_access: AccessFlags::empty(),
+                                                    destination_stages: memory.stages,
+                                                    destination_access: memory.access,
+                                                    old_layout: state.initial_layout,
+                                                    new_layout: start_layout,
+                                                    subresource_range,
+                                                    ..ImageMemoryBarrier::image(inner.image.clone())
                                                 },
-                                                source_access: AccessFlags::empty(),
-                                                destination_stages: memory.stages,
-                                                destination_access: memory.access,
-                                                old_layout: state.initial_layout,
-                                                new_layout: start_layout,
-                                                subresource_range: inner
-                                                    .image
-                                                    .range_to_subresources(range.clone()),
-                                                ..ImageMemoryBarrier::image(inner.image.clone())
-                                            },
+                                            ),
                                         );
                                     }
                                 }
@@ -688,33 +774,25 @@ impl SyncCommandBufferBuilder {
                             || state.current_layout != start_layout
                         {
                             unsafe {
-                                // Flush the pending barrier.
-                                self.inner.pipeline_barrier(&self.pending_barrier);
-                                self.pending_barrier.clear();
-                                self.barriers.push(self.first_unflushed); // Track inserted barriers
-
-                                for command in &mut self.commands
-                                    [self.first_unflushed..last_allowed_barrier_index]
-                                {
-                                    command.send(&mut self.inner);
-                                }
-                                self.first_unflushed = last_allowed_barrier_index;
+                                self.flush(last_allowed_barrier_index);
                             }
                         }
 
                         // Modify the pipeline barrier to handle the collision.
-                        self.pending_barrier
-                            .image_memory_barriers
-                            .push(ImageMemoryBarrier {
-                                source_stages: state.memory.stages,
-                                source_access: state.memory.access,
-                                destination_stages: memory.stages,
-                                destination_access: memory.access,
-                                old_layout: state.current_layout,
-                                new_layout: start_layout,
-                                subresource_range: inner.image.range_to_subresources(range.clone()),
-                                ..ImageMemoryBarrier::image(inner.image.clone())
-                            });
+                        self.pending_barrier.image_memory_barriers.extend(
+                            inner.image.range_to_subresources(range.clone()).map(
+                                |subresource_range| ImageMemoryBarrier {
+                                    source_stages: state.memory.stages,
+                                    source_access: state.memory.access,
+                                    destination_stages: memory.stages,
+                                    destination_access: memory.access,
+                                    old_layout: state.current_layout,
+                                    new_layout: start_layout,
+                                    subresource_range,
+                                    ..ImageMemoryBarrier::image(inner.image.clone())
+                                },
+                            ),
+                        );
 
                         // Update state.
                         state.memory = memory;
@@ -740,6 +818,127 @@ impl SyncCommandBufferBuilder {
         }
     }
 
+    // Records a use of the memory that a buffer or image is bound to. If the memory was previously
+    // used through another resource that aliases it, a global memory barrier is added between the
+    // two uses.
+    fn add_memory_use(
+        &mut self,
+        resource: MemoryResource,
+        memory_binding: Option<(ash::vk::DeviceMemory, Range<DeviceSize>)>,
+        resource_name: &Cow<'static, str>,
+        memory: PipelineMemoryAccess,
+    ) {
+        let (device_memory, memory_range) = match memory_binding {
+            Some(x) => x,
+            None => return,
+        };
+
+        // Barriers work differently in render passes, so if we're in one, we can only insert a
+        // barrier before the start of the render pass.
+        let last_allowed_barrier_index = self
+            .latest_render_pass_enter
+            .unwrap_or(self.commands.len() - 1);
+        let command_index = self.commands.len() - 1;
+        let first_unflushed = self.first_unflushed;
+
+        let memory_uses = self.memory_uses.entry(device_memory).or_default();
+        let mut source_stages = PipelineStages::empty();
+        let mut source_access = AccessFlags::empty();
+        let mut has_alias = false;
+        let mut needs_flush = false;
+
+        // Once the barrier is added, the previous uses through other resources no longer need to
+        // be tracked.
+        memory_uses.retain(|memory_use| {
+            if memory_use.resource == resource
+                || memory_use.range.end <= memory_range.start
+                || memory_range.end <= memory_use.range.start
+            {
+                return true;
+            }
+
+            source_stages |= memory_use.memory.stages;
+            source_access |= memory_use.memory.access;
+            has_alias = true;
+            needs_flush |= memory_use.command_index >= first_unflushed;
+
+            false
+        });
+
+        if let Some(memory_use) = memory_uses
+            .iter_mut()
+            .find(|memory_use| memory_use.resource == resource)
+        {
+            memory_use.range.start = min(memory_use.range.start, memory_range.start);
+            memory_use.range.end = max(memory_use.range.end, memory_range.end);
+            memory_use.memory.stages |= memory.stages;
+            memory_use.memory.access |= memory.access;
+            memory_use.command_index = command_index;
+            memory_use.name = resource_name.clone();
+        } else {
+            memory_uses.push(MemoryUse {
+                resource,
+                range: memory_range,
+                memory,
+                command_index,
+                name: resource_name.clone(),
+            });
+        }
+
+        if !has_alias {
+            return;
+        }
+
+        // The barrier is going to be submitted before the unflushed commands, so if one of the
+        // previous uses hasn't been flushed yet, flush it first.
+        if needs_flush {
+            unsafe {
+                self.flush(last_allowed_barrier_index);
+            }
+        }
+
+        self.pending_barrier.memory_barriers.push(MemoryBarrier {
+            source_stages,
+            source_access,
+            destination_stages: memory.stages,
+            destination_access: memory.access,
+            ..Default::default()
+        });
+    }
+
+    // Sends the commands before `end` that haven't been sent to the inner builder yet, preceded by
+    // the pending barrier.
+    unsafe fn flush(&mut self, end: usize) {
+        // If no commands follow the pending barrier, it can be recorded together with the barrier
+        // that is added next instead. The builder never puts two dependencies for the same
+        // subresource at the same location, so the two don't need to be ordered.
+        if !(self.optimize_barriers && end == self.first_unflushed) {
+            self.record_pending_barrier();
+        }
+
+        for command in &mut self.commands[self.first_unflushed..end] {
+            command.send(&mut self.inner);
+        }
+
+        self.first_unflushed = end;
+    }
+
+    unsafe fn record_pending_barrier(&mut self) {
+        if self.pending_barrier.is_empty() {
+            return;
+        }
+
+        self.barriers.push(self.first_unflushed); // Track inserted barriers
+
+        if self.optimize_barriers {
+            relax_barrier(&mut self.pending_barrier);
+            self.pending_barrier.optimize();
+        }
+
+        self.inner.pipeline_barrier(&self.pending_barrier);
+        self.pending_barrier.clear();
+    }
+
     /// Builds the command buffer and turns it into a `SyncCommandBuffer`.
     #[inline]
     pub fn build(mut self) -> Result<SyncCommandBuffer, OomError> {
@@ -747,14 +946,8 @@ impl SyncCommandBufferBuilder {
 
         // The commands that haven't been sent to the inner command buffer yet need to be sent.
         unsafe {
-            self.inner.pipeline_barrier(&self.pending_barrier);
-            self.pending_barrier.clear();
-            let start = self.first_unflushed;
-            self.barriers.push(start); // Track inserted barriers
-
-            for command in &mut self.commands[start..] {
-                command.send(&mut self.inner);
-            }
+            self.flush(self.commands.len());
+            self.record_pending_barrier();
         }
 
         // Transition images to their desired final layout.
@@ -765,27 +958,29 @@ impl SyncCommandBufferBuilder {
                         .iter_mut()
                         .filter(|(_range, state)| state.final_layout != state.current_layout)
                     {
-                        self.pending_barrier
-                            .image_memory_barriers
-                            .push(ImageMemoryBarrier {
-                                source_stages: state.memory.stages,
-                                source_access: state.memory.access,
-                                destination_stages: PipelineStages {
-                                    top_of_pipe: true,
-                                    ..PipelineStages::empty()
-                                },
-                                destination_access: AccessFlags::empty(),
-                                old_layout: state.current_layout,
-                                new_layout: state.final_layout,
-                                subresource_range: image.range_to_subresources(range.clone()),
-                                ..ImageMemoryBarrier::image(image.clone())
-                            });
+                        self.pending_barrier.image_memory_barriers.extend(
+                            image
+                                .range_to_subresources(range.clone())
+                                .map(|subresource_range| ImageMemoryBarrier {
+                                    source_stages: state.memory.stages,
+                                    source_access: state.memory.access,
+                                    destination_stages: PipelineStages {
+                                        top_of_pipe: true,
+                                        ..PipelineStages::empty()
+                                    },
+                                    destination_access: AccessFlags::empty(),
+                                    old_layout: state.current_layout,
+                                    new_layout: state.final_layout,
+                                    subresource_range,
+                                    ..ImageMemoryBarrier::image(image.clone())
+                                }),
+                        );
 
                         state.exclusive_any = true;
                     }
                 }
 
-                self.inner.pipeline_barrier(&self.pending_barrier);
+                self.record_pending_barrier();
             }
         }
 
@@ -853,7 +1048,9 @@ impl SyncCommandBufferBuilder {
             buffers2,
             images2,
             commands: self.commands,
-            _barriers: self.barriers,
+            barriers: self.barriers,
+            bound_pipelines: self.bound_pipelines,
+            secondary_command_buffers: self.secondary_command_buffers,
         })
     }
 }
@@ -902,6 +1099,105 @@ impl From<CommandBufferExecError> for SyncCommandBufferBuilderError {
     }
 }
 
+// Identifies the buffer or image that device memory was accessed through.
+type MemoryResource = (ash::vk::ObjectType, u64);
+
+// Removes the parts of a barrier that the builder adds conservatively, but that have no effect.
+//
+// Only writes need to be made available, so read accesses are removed from the source scope. If
+// no writes are left, the barrier only guards against write-after-read hazards, which need just an
+// execution dependency, unless it performs a layout transition or an ownership transfer. The top
+// of the pipe in the source scope and the bottom of the pipe in the destination scope don't
+// contain any work, so they are removed when there are other stages.
+fn relax_barrier(dependency_info: &mut DependencyInfo) {
+    let writes = AccessFlags::writes();
+    let relax = |source_stages: &mut PipelineStages,
+                 source_access: &mut AccessFlags,
+                 destination_stages: &mut PipelineStages,
+                 destination_access: &mut AccessFlags,
+                 keep_destination_access: bool| {
+        *source_access = source_access.intersection(&writes);
+
+        if source_access.is_empty() && !keep_destination_access {
+            *destination_access = AccessFlags::empty();
+        }
+
+        let top_of_pipe = PipelineStages {
+            top_of_pipe: true,
+            ..PipelineStages::empty()
+        };
+        let bottom_of_pipe = PipelineStages {
+            bottom_of_pipe: true,
+            ..PipelineStages::empty()
+        };
+
+        if !source_stages.difference(&top_of_pipe).is_empty() {
+            source_stages.top_of_pipe = false;
+        }
+
+        if !destination_stages.difference(&bottom_of_pipe).is_empty() {
+            destination_stages.bottom_of_pipe = false;
+        }
+    };
+
+    for barrier in &mut dependency_info.memory_barriers {
+        relax(
+            &mut barrier.source_stages,
+            &mut barrier.source_access,
+            &mut barrier.destination_stages,
+            &mut barrier.destination_access,
+            false,
+        );
+    }
+
+    for barrier in &mut dependency_info.buffer_memory_barriers {
+        relax(
+            &mut barrier.source_stages,
+            &mut barrier.source_access,
+            &mut barrier.destination_stages,
+            &mut barrier.destination_access,
+            barrier.queue_family_transfer.is_some(),
+        );
+    }
+
+    for barrier in &mut dependency_info.image_memory_barriers {
+        let keep_destination_access =
+            barrier.queue_family_transfer.is_some() || barrier.old_layout != barrier.new_layout;
+        relax(
+            &mut barrier.source_stages,
+            &mut barrier.source_access,
+            &mut barrier.destination_stages,
+            &mut barrier.destination_access,
+            keep_destination_access,
+        );
+    }
+}
+
+fn buffer_memory_resource(buffer: &UnsafeBuffer) -> MemoryResource {
+    (
+        ash::vk::ObjectType::BUFFER,
+        buffer.internal_object().as_raw(),
+    )
+}
+
+fn image_memory_resource(image: &UnsafeImage) -> MemoryResource {
+    (ash::vk::ObjectType::IMAGE, image.internal_object().as_raw())
+}
+
+// A use of a range of device memory through a buffer or image.
+#[derive(Clone, PartialEq, Eq)]
+struct MemoryUse {
+    resource: MemoryResource,
+    range: Range<DeviceSize>,
+
+    // Memory access of the commands that used the memory through `resource`.
+    memory: PipelineMemoryAccess,
+
+    // The latest command that used the memory through `resource`.
+    command_index: usize,
+    name: Cow<'static, str>,
+}
+
 // State of a resource during the building of the command buffer.
 #[derive(Clone, PartialEq, Eq)]
 struct BufferState {
@@ -949,6 +1245,8 @@ pub(in crate::command_buffer) struct CurrentState {
     pub(in crate::command_buffer) pipeline_compute: Option<Arc<ComputePipeline>>,
     pub(in crate::command_buffer) pipeline_graphics: Option<Arc<GraphicsPipeline>>,
     pub(in crate::command_buffer) vertex_buffers: HashMap<u32, Arc<dyn BufferAccess>>,
+    pub(in crate::command_buffer) vertex_buffer_strides: HashMap<u32, DeviceSize>,
+    pub(in crate::command_buffer) null_vertex_buffers: HashSet<u32>,
 
     pub(in crate::command_buffer) push_constants: RangeSet<u32>,
     pub(in crate::command_buffer) push_constants_pipeline_layout: Option<Arc<PipelineLayout>>,
@@ -960,18 +1258,22 @@ pub(in crate::command_buffer) struct CurrentState {
     pub(in crate::command_buffer) depth_bias_enable: Option<bool>,
     pub(in crate::command_buffer) depth_bounds: Option<RangeInclusive<f32>>,
     pub(in crate::command_buffer) depth_bounds_test_enable: Option<bool>,
+    pub(in crate::command_buffer) depth_clamp_enable: Option<bool>,
     pub(in crate::command_buffer) depth_compare_op: Option<CompareOp>,
     pub(in crate::command_buffer) depth_test_enable: Option<bool>,
     pub(in crate::command_buffer) depth_write_enable: Option<bool>,
     pub(in crate::command_buffer) discard_rectangle: HashMap<u32, Scissor>,
+    pub(in crate::command_buffer) fragment_shading_rate: Option<FragmentShadingRate>,
     pub(in crate::command_buffer) front_face: Option<FrontFace>,
     pub(in crate::command_buffer) line_stipple: Option<LineStipple>,
     pub(in crate::command_buffer) line_width: Option<f32>,
     pub(in crate::command_buffer) logic_op: Option<LogicOp>,
     pub(in crate::command_buffer) patch_control_points: Option<u32>,
+    pub(in crate::command_buffer) polygon_mode: Option<PolygonMode>,
     pub(in crate::command_buffer) primitive_restart_enable: Option<bool>,
     pub(in crate::command_buffer) primitive_topology: Option<PrimitiveTopology>,
     pub(in crate::command_buffer) rasterizer_discard_enable: Option<bool>,
+    pub(in crate::command_buffer) sample_locations: Option<SampleLocations>,
     pub(in crate::command_buffer) scissor: HashMap<u32, Scissor>,
     pub(in crate::command_buffer) scissor_with_count: Option<SmallVec<[Scissor; 2]>>,
     pub(in crate::command_buffer) stencil_compare_mask: StencilStateDynamic,
@@ -997,22 +1299,24 @@ impl CurrentState {
                 DynamicState::DepthBiasEnable => self.depth_bias_enable = None,
                 DynamicState::DepthBounds => self.depth_bounds = None,
                 DynamicState::DepthBoundsTestEnable => self.depth_bounds_test_enable = None,
+                DynamicState::DepthClampEnable => self.depth_clamp_enable = None,
                 DynamicState::DepthCompareOp => self.depth_compare_op = None,
                 DynamicState::DepthTestEnable => self.depth_test_enable = None,
                 DynamicState::DepthWriteEnable => self.depth_write_enable = None,
                 DynamicState::DiscardRectangle => self.discard_rectangle.clear(),
                 DynamicState::ExclusiveScissor => (), // TODO;
-                DynamicState::FragmentShadingRate => (), // TODO:
+                DynamicState::FragmentShadingRate => self.fragment_shading_rate = None,
                 DynamicState::FrontFace => self.front_face = None,
                 DynamicState::LineStipple => self.line_stipple = None,
                 DynamicState::LineWidth => self.line_width = None,
                 DynamicState::LogicOp => self.logic_op = None,
                 DynamicState::PatchControlPoints => self.patch_control_points = None,
+                DynamicState::PolygonMode => self.polygon_mode = None,
                 DynamicState::PrimitiveRestartEnable => self.primitive_restart_enable = None,
                 DynamicState::PrimitiveTopology => self.primitive_topology = None,
                 DynamicState::RasterizerDiscardEnable => self.rasterizer_discard_enable = None,
                 DynamicState::RayTracingPipelineStackSize => (), // TODO:
-                DynamicState::SampleLocations => (),             // TODO:
+                DynamicState::SampleLocations => self.sample_locations = None,
                 DynamicState::Scissor => self.scissor.clear(),
                 DynamicState::ScissorWithCount => self.scissor_with_count = None,
                 DynamicState::StencilCompareMask => self.stencil_compare_mask = Default::default(),
@@ -1021,12 +1325,41 @@ impl CurrentState {
                 DynamicState::StencilTestEnable => self.stencil_test_enable = None,
                 DynamicState::StencilWriteMask => self.stencil_write_mask = Default::default(),
                 DynamicState::VertexInput => (), // TODO:
-                DynamicState::VertexInputBindingStride => (), // TODO:
+                DynamicState::VertexInputBindingStride => self.vertex_buffer_strides.clear(),
                 DynamicState::Viewport => self.viewport.clear(),
                 DynamicState::ViewportCoarseSampleOrder => (), // TODO:
                 DynamicState::ViewportShadingRatePalette => (), // TODO:
                 DynamicState::ViewportWScaling => (),          // TODO:
                 DynamicState::ViewportWithCount => self.viewport_with_count = None,
+                DynamicState::TessellationDomainOrigin => (), // TODO:
+                DynamicState::RasterizationSamples => (),     // TODO:
+                DynamicState::SampleMask => (),               // TODO:
+                DynamicState::AlphaToCoverageEnable => (),    // TODO:
+                DynamicState::AlphaToOneEnable => (),         // TODO:
+                DynamicState::LogicOpEnable => (),            // TODO:
+                DynamicState::ColorBlendEnable => (),         // TODO:
+                DynamicState::ColorBlendEquation => (),       // TODO:
+                DynamicState::ColorWriteMask => (),           // TODO:
+                DynamicState::RasterizationStream => (),      // TODO:
+                DynamicState::ConservativeRasterizationMode => (), // TODO:
+                DynamicState::ExtraPrimitiveOverestimationSize => (), // TODO:
+                DynamicState::DepthClipEnable => (),          // TODO:
+                DynamicState::SampleLocationsEnable => (),    // TODO:
+                DynamicState::ColorBlendAdvanced => (),       // TODO:
+                DynamicState::ProvokingVertexMode => (),      // TODO:
+                DynamicState::LineRasterizationMode => (),    // TODO:
+                DynamicState::LineStippleEnable => (),        // TODO:
+                DynamicState::DepthClipNegativeOneToOne => (), // TODO:
+                DynamicState::ViewportWScalingEnable => (),   // TODO:
+                DynamicState::ViewportSwizzle => (),          // TODO:
+                DynamicState::CoverageToColorEnable => (),    // TODO:
+                DynamicState::CoverageToColorLocation => (),  // TODO:
+                DynamicState::CoverageModulationMode => (),   // TODO:
+                DynamicState::CoverageModulationTableEnable => (), // TODO:
+                DynamicState::CoverageModulationTable => (),  // TODO:
+                DynamicState::ShadingRateImageEnable => (),   // TODO:
+                DynamicState::RepresentativeFragmentTestEnable => (), // TODO:
+                DynamicState::CoverageReductionMode => (),    // TODO:
             }
         }
     }
@@ -1175,6 +1508,24 @@ impl<'a> CommandBufferState<'a> {
         self.current_state.vertex_buffers.get(&binding_num)
     }
 
+    /// Returns whether a null vertex buffer is currently bound to a given binding slot number.
+    #[inline]
+    pub fn vertex_buffer_is_null(&self, binding_num: u32) -> bool {
+        self.current_state
+            .null_vertex_buffers
+            .contains(&binding_num)
+    }
+
+    /// Returns the dynamic stride of the vertex buffer binding slot number, or `None` if it has
+    /// not been set.
+    #[inline]
+    pub fn vertex_buffer_stride(&self, binding_num: u32) -> Option<DeviceSize> {
+        self.current_state
+            .vertex_buffer_strides
+            .get(&binding_num)
+            .copied()
+    }
+
     /// Returns a set containing push constant bytes that have been set.
     #[inline]
     pub fn push_constants(&self) -> &'a RangeSet<u32> {
@@ -1234,6 +1585,12 @@ impl<'a> CommandBufferState<'a> {
         self.current_state.depth_bias_enable
     }
 
+    /// Returns whether depth clamping is enabled, or `None` if nothing has been set yet.
+    #[inline]
+    pub fn depth_clamp_enable(&self) -> Option<bool> {
+        self.current_state.depth_clamp_enable
+    }
+
     /// Returns the current depth compare op, or `None` if nothing has been set yet.
     #[inline]
     pub fn depth_compare_op(&self) -> Option<CompareOp> {
@@ -1258,6 +1615,12 @@ impl<'a> CommandBufferState<'a> {
         self.current_state.discard_rectangle.get(&num)
     }
 
+    /// Returns the current fragment shading rate, or `None` if nothing has been set yet.
+    #[inline]
+    pub fn fragment_shading_rate(&self) -> Option<FragmentShadingRate> {
+        self.current_state.fragment_shading_rate
+    }
+
     /// Returns the current front face, or `None` if nothing has been set yet.
     #[inline]
     pub fn front_face(&self) -> Option<FrontFace> {
@@ -1288,6 +1651,12 @@ impl<'a> CommandBufferState<'a> {
         self.current_state.patch_control_points
     }
 
+    /// Returns the current polygon mode, or `None` if nothing has been set yet.
+    #[inline]
+    pub fn polygon_mode(&self) -> Option<PolygonMode> {
+        self.current_state.polygon_mode
+    }
+
     /// Returns whether primitive restart is enabled, or `None` if nothing has been set yet.
     #[inline]
     pub fn primitive_restart_enable(&self) -> Option<bool> {
@@ -1306,6 +1675,12 @@ impl<'a> CommandBufferState<'a> {
         self.current_state.rasterizer_discard_enable
     }
 
+    /// Returns the current sample locations, or `None` if nothing has been set yet.
+    #[inline]
+    pub fn sample_locations(&self) -> Option<&SampleLocations> {
+        self.current_state.sample_locations.as_ref()
+    }
+
     /// Returns the current scissor for a given viewport slot, or `None` if nothing has been set
     /// yet.
     #[inline]
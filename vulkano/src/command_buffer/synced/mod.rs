@@ -72,15 +72,17 @@ pub use self::builder::{
 };
 use super::{
     sys::{UnsafeCommandBuffer, UnsafeCommandBufferBuilder},
-    CommandBufferExecError,
+    CommandBufferExecError, SecondaryCommandBuffer,
 };
 use crate::{
     buffer::{sys::UnsafeBuffer, BufferAccess},
     device::{Device, DeviceOwned, Queue},
     image::{sys::UnsafeImage, ImageAccess, ImageLayout, ImageSubresourceRange},
+    pipeline::Pipeline,
     range_map::RangeMap,
     sync::{
-        AccessCheckError, AccessError, AccessFlags, GpuFuture, PipelineMemoryAccess, PipelineStages,
+        AccessCheckError, AccessError, AccessFlags, GpuFuture, PipelineMemoryAccess,
+        PipelineStages, Sharing,
     },
     DeviceSize,
 };
@@ -104,9 +106,14 @@ pub struct SyncCommandBuffer {
     // being used.
     commands: Vec<Box<dyn Command>>,
 
-    // Locations within commands that pipeline barriers were inserted. For debugging purposes.
-    // TODO: present only in cfg(debug_assertions)?
-    _barriers: Vec<usize>,
+    // Locations within commands that pipeline barriers were inserted.
+    barriers: Vec<usize>,
+
+    // Pipelines that were bound, in the order they were bound.
+    bound_pipelines: Vec<Arc<dyn Pipeline + Send + Sync>>,
+
+    // Secondary command buffers that were executed, in the order they were executed.
+    secondary_command_buffers: Vec<Arc<dyn SecondaryCommandBuffer>>,
 
     // State of all the resources used by this command buffer.
     buffers2: HashMap<Arc<UnsafeBuffer>, RangeMap<DeviceSize, BufferFinalState>>,
@@ -148,6 +155,17 @@ impl SyncCommandBuffer {
                 let mut buffer_state = buffer.state();
 
                 for (range, state) in range_map.iter() {
+                    if let Err(err) = check_queue_family_shared(buffer.sharing(), queue) {
+                        let resource_use = &state.resource_uses[0];
+
+                        return Err(CommandBufferExecError::AccessError {
+                            error: err,
+                            command_name: self.commands[resource_use.command_index].name().into(),
+                            command_param: resource_use.name.clone(),
+                            command_offset: resource_use.command_index,
+                        });
+                    }
+
                     match future.check_buffer_access(buffer, range.clone(), state.exclusive, queue)
                     {
                         Err(AccessCheckError::Denied(err)) => {
@@ -197,6 +215,17 @@ impl SyncCommandBuffer {
                 let mut image_state = image.state();
 
                 for (range, state) in range_map.iter() {
+                    if let Err(err) = check_queue_family_shared(image.sharing(), queue) {
+                        let resource_use = &state.resource_uses[0];
+
+                        return Err(CommandBufferExecError::AccessError {
+                            error: err,
+                            command_name: self.commands[resource_use.command_index].name().into(),
+                            command_param: resource_use.name.clone(),
+                            command_offset: resource_use.command_index,
+                        });
+                    }
+
                     match future.check_image_access(
                         image,
                         range.clone(),
@@ -428,6 +457,29 @@ impl SyncCommandBuffer {
                 (image, range, *memory, *start_layout, *end_layout)
             })
     }
+
+    /// Returns the number of pipeline barriers that were inserted to synchronize the commands.
+    ///
+    /// This includes the barriers that transition images to their final layout at the end of a
+    /// primary command buffer.
+    #[inline]
+    pub fn num_barriers(&self) -> usize {
+        self.barriers.len()
+    }
+
+    /// Returns the pipelines that were bound, in the order they were bound.
+    ///
+    /// A pipeline that was bound several times appears once for each time.
+    #[inline]
+    pub fn bound_pipelines(&self) -> &[Arc<dyn Pipeline + Send + Sync>] {
+        &self.bound_pipelines
+    }
+
+    /// Returns the secondary command buffers that were executed, in the order they were executed.
+    #[inline]
+    pub fn secondary_command_buffers(&self) -> &[Arc<dyn SecondaryCommandBuffer>] {
+        &self.secondary_command_buffers
+    }
 }
 
 impl AsRef<UnsafeCommandBuffer> for SyncCommandBuffer {
@@ -444,6 +496,25 @@ unsafe impl DeviceOwned for SyncCommandBuffer {
     }
 }
 
+// Checks that a resource with concurrent sharing is shared with the queue family of `queue`.
+fn check_queue_family_shared(
+    sharing: &Sharing<impl AsRef<[u32]>>,
+    queue: &Queue,
+) -> Result<(), AccessError> {
+    match sharing {
+        Sharing::Exclusive => Ok(()),
+        Sharing::Concurrent(queue_family_indices) => {
+            let queue_family_index = queue.queue_family_index();
+
+            if queue_family_indices.as_ref().contains(&queue_family_index) {
+                Ok(())
+            } else {
+                Err(AccessError::QueueFamilyNotShared { queue_family_index })
+            }
+        }
+    }
+}
+
 // Usage of a resource in a finished command buffer.
 #[derive(Clone, PartialEq, Eq)]
 struct BufferFinalState {
@@ -530,7 +601,10 @@ impl Debug for dyn Command {
 mod tests {
     use super::*;
     use crate::{
-        buffer::{BufferUsage, CpuAccessibleBuffer, DeviceLocalBuffer},
+        buffer::{
+            sys::UnsafeBufferCreateInfo, BufferInner, BufferUsage, CpuAccessibleBuffer,
+            DeviceLocalBuffer,
+        },
         command_buffer::{
             allocator::{
                 CommandBufferAllocator, CommandBufferBuilderAlloc, StandardCommandBufferAllocator,
@@ -547,6 +621,7 @@ mod tests {
             },
             PersistentDescriptorSet, WriteDescriptorSet,
         },
+        memory::{DeviceMemory, MemoryAllocateInfo},
         pipeline::{layout::PipelineLayoutCreateInfo, PipelineBindPoint, PipelineLayout},
         sampler::{Sampler, SamplerCreateInfo},
         shader::ShaderStages,
@@ -661,7 +736,7 @@ mod tests {
 
                 // Ensure that the builder added a barrier between the two writes
                 assert_eq!(&names, &["execute_commands", "execute_commands"]);
-                assert_eq!(&primary._barriers, &[0, 1]);
+                assert_eq!(&primary.barriers, &[1]);
             }
 
             {
@@ -684,6 +759,93 @@ mod tests {
         }
     }
 
+    #[test]
+    fn aliased_buffers() {
+        struct AliasedBuffer(Arc<UnsafeBuffer>);
+
+        unsafe impl BufferAccess for AliasedBuffer {
+            fn inner(&self) -> BufferInner<'_> {
+                BufferInner {
+                    buffer: &self.0,
+                    offset: 0,
+                }
+            }
+
+            fn size(&self) -> DeviceSize {
+                self.0.size()
+            }
+        }
+
+        unsafe impl DeviceOwned for AliasedBuffer {
+            fn device(&self) -> &Arc<Device> {
+                self.0.device()
+            }
+        }
+
+        unsafe {
+            let (device, queue) = gfx_dev_and_queue!();
+
+            // Two buffers that are bound to the same memory.
+            let buffers = [(), ()].map(|_| {
+                UnsafeBuffer::new(
+                    device.clone(),
+                    UnsafeBufferCreateInfo {
+                        size: 64,
+                        usage: BufferUsage {
+                            transfer_dst: true,
+                            ..BufferUsage::empty()
+                        },
+                        ..Default::default()
+                    },
+                )
+                .unwrap()
+            });
+            let requirements = buffers[0].memory_requirements();
+            let memory = DeviceMemory::allocate(
+                device.clone(),
+                MemoryAllocateInfo {
+                    allocation_size: requirements.size,
+                    memory_type_index: requirements.memory_type_bits.trailing_zeros(),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            for buffer in &buffers {
+                buffer.bind_memory(&memory, 0).unwrap();
+            }
+
+            let allocator = StandardCommandBufferAllocator::new(device);
+            let builder_alloc = allocator
+                .allocate(queue.queue_family_index(), CommandBufferLevel::Primary, 1)
+                .unwrap()
+                .next()
+                .unwrap();
+            let mut builder = SyncCommandBufferBuilder::new(
+                builder_alloc.inner(),
+                CommandBufferBeginInfo {
+                    usage: CommandBufferUsage::OneTimeSubmit,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            for buffer in buffers {
+                builder
+                    .fill_buffer(FillBufferInfo {
+                        data: 42u32,
+                        ..FillBufferInfo::dst_buffer(Arc::new(AliasedBuffer(buffer)))
+                    })
+                    .unwrap();
+            }
+
+            let primary = builder.build().unwrap();
+
+            // Ensure that the builder added a barrier between the writes to the aliased memory.
+            assert_eq!(&primary.barriers, &[1]);
+        }
+    }
+
     #[test]
     fn vertex_buffer_binding() {
         unsafe {
@@ -40,6 +40,7 @@ pub struct UnsafeCommandBufferBuilder {
     pub(super) handle: ash::vk::CommandBuffer,
     pub(super) device: Arc<Device>,
     usage: CommandBufferUsage,
+    protected: bool,
 }
 
 impl UnsafeCommandBufferBuilder {
@@ -186,6 +187,7 @@ impl UnsafeCommandBufferBuilder {
             handle: pool_alloc.internal_object(),
             device,
             usage,
+            protected: pool_alloc.protected(),
         })
     }
 
@@ -202,6 +204,7 @@ impl UnsafeCommandBufferBuilder {
                 command_buffer: self.handle,
                 device: self.device.clone(),
                 usage: self.usage,
+                protected: self.protected,
             })
         }
     }
@@ -263,6 +266,7 @@ pub struct UnsafeCommandBuffer {
     command_buffer: ash::vk::CommandBuffer,
     device: Arc<Device>,
     usage: CommandBufferUsage,
+    protected: bool,
 }
 
 impl UnsafeCommandBuffer {
@@ -270,6 +274,12 @@ impl UnsafeCommandBuffer {
     pub fn usage(&self) -> CommandBufferUsage {
         self.usage
     }
+
+    /// Returns whether the command buffer was allocated from a protected command pool.
+    #[inline]
+    pub fn protected(&self) -> bool {
+        self.protected
+    }
 }
 
 unsafe impl DeviceOwned for UnsafeCommandBuffer {
@@ -21,6 +21,7 @@ use crate::{
     DeviceSize, SafeDeref, VulkanObject,
 };
 use parking_lot::Mutex;
+use smallvec::{smallvec, SmallVec};
 use std::{
     borrow::Cow,
     error::Error,
@@ -123,11 +124,16 @@ pub unsafe trait PrimaryCommandBuffer: DeviceOwned + Send + Sync {
             assert!(future.queue().unwrap() == queue);
         }
 
+        // VUID-vkQueueSubmit-queue-06448
+        if self.inner().protected() && !queue.flags().protected {
+            return Err(CommandBufferExecError::ProtectedQueueRequired);
+        }
+
         self.lock_submit(&future, &queue)?;
 
         Ok(CommandBufferExecFuture {
             previous: future,
-            command_buffer: Arc::new(self),
+            command_buffers: smallvec![Arc::new(self) as Arc<dyn PrimaryCommandBuffer>],
             queue,
             submitted: Mutex::new(false),
             finished: AtomicBool::new(false),
@@ -318,7 +324,8 @@ where
     F: GpuFuture,
 {
     previous: F,
-    command_buffer: Arc<dyn PrimaryCommandBuffer>,
+    // The command buffers are submitted in order, in a single batch.
+    command_buffers: SmallVec<[Arc<dyn PrimaryCommandBuffer>; 1]>,
     queue: Arc<Queue>,
     // True if the command buffer has already been submitted.
     // If flush is called multiple times, we want to block so that only one flushing is executed.
@@ -331,20 +338,105 @@ impl<F> CommandBufferExecFuture<F>
 where
     F: GpuFuture,
 {
+    /// Executes several command buffers after `future`, in a single batch of a queue submission.
+    ///
+    /// The command buffers are executed in the order they are given, and each one is checked
+    /// against the command buffers before it as well as against `future`. This is equivalent to
+    /// calling [`then_execute`](GpuFuture::then_execute) for each command buffer in turn, but
+    /// works with a number of command buffers that is only known at runtime.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the device of a command buffer is not the same as the device of the future.
+    pub fn new_batch(
+        future: F,
+        queue: Arc<Queue>,
+        command_buffers: impl IntoIterator<Item = Arc<dyn PrimaryCommandBuffer>>,
+    ) -> Result<Self, CommandBufferExecError> {
+        if !future.queue_change_allowed() {
+            assert!(future.queue().unwrap() == queue);
+        }
+
+        let mut exec_future = CommandBufferExecFuture {
+            previous: future,
+            command_buffers: SmallVec::new(),
+            queue,
+            submitted: Mutex::new(false),
+            finished: AtomicBool::new(false),
+        };
+
+        for command_buffer in command_buffers {
+            assert_eq!(
+                command_buffer.device().internal_object(),
+                exec_future.previous.device().internal_object()
+            );
+
+            let protected = command_buffer.inner().protected();
+
+            let result = if protected && !exec_future.queue.flags().protected {
+                // VUID-vkQueueSubmit-queue-06448
+                Err(CommandBufferExecError::ProtectedQueueRequired)
+            } else if exec_future
+                .command_buffers
+                .first()
+                .map_or(false, |first| first.inner().protected() != protected)
+            {
+                // VUID-VkSubmitInfo-pNext-04120
+                // VUID-VkSubmitInfo-pNext-04148
+                Err(CommandBufferExecError::ProtectedMismatch)
+            } else {
+                // The future checks the command buffers that were already added before
+                // `previous`.
+                command_buffer.lock_submit(&exec_future, &exec_future.queue)
+            };
+
+            if let Err(err) = result {
+                unsafe {
+                    for command_buffer in &exec_future.command_buffers {
+                        command_buffer.unlock();
+                    }
+                }
+
+                // Nothing has been submitted, so there is nothing to wait for when dropping.
+                *exec_future.finished.get_mut() = true;
+
+                return Err(err);
+            }
+
+            exec_future.command_buffers.push(command_buffer);
+        }
+
+        Ok(exec_future)
+    }
+
+    /// Returns the command buffers that are executed, in order.
+    #[inline]
+    pub fn command_buffers(&self) -> &[Arc<dyn PrimaryCommandBuffer>] {
+        &self.command_buffers
+    }
+
     // Implementation of `build_submission`. Doesn't check whenever the future was already flushed.
     // You must make sure to not submit same command buffer multiple times.
     unsafe fn build_submission_impl(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        // All command buffers of a batch have the same protectedness, which is checked when
+        // they're added.
+        let protected_submit = self
+            .command_buffers
+            .first()
+            .map_or(false, |command_buffer| command_buffer.inner().protected());
+
         Ok(match self.previous.build_submission()? {
             SubmitAnyBuilder::Empty => SubmitAnyBuilder::CommandBuffer(
-                SubmitInfo {
-                    command_buffers: vec![self.command_buffer.clone()],
+                smallvec![SubmitInfo {
+                    command_buffers: self.command_buffers.iter().cloned().collect(),
+                    protected_submit,
                     ..Default::default()
-                },
+                }],
                 None,
             ),
             SubmitAnyBuilder::SemaphoresWait(semaphores) => {
                 SubmitAnyBuilder::CommandBuffer(
-                    SubmitInfo {
+                    smallvec![SubmitInfo {
                         wait_semaphores: semaphores
                             .into_iter()
                             .map(|semaphore| {
@@ -358,24 +450,41 @@ where
                                 }
                             })
                             .collect(),
-                        command_buffers: vec![self.command_buffer.clone()],
+                        command_buffers: self.command_buffers.iter().cloned().collect(),
+                        protected_submit,
                         ..Default::default()
-                    },
+                    }],
                     None,
                 )
             }
-            SubmitAnyBuilder::CommandBuffer(mut submit_info, fence) => {
-                // FIXME: add pipeline barrier
-                submit_info
-                    .command_buffers
-                    .push(self.command_buffer.clone());
-                SubmitAnyBuilder::CommandBuffer(submit_info, fence)
+            SubmitAnyBuilder::CommandBuffer(mut submit_infos, fence) => {
+                let submit_info = submit_infos.last_mut().unwrap();
+
+                if submit_info.command_buffers.is_empty()
+                    || submit_info.protected_submit == protected_submit
+                {
+                    // FIXME: add pipeline barrier
+                    submit_info
+                        .command_buffers
+                        .extend(self.command_buffers.iter().cloned());
+                    submit_info.protected_submit = protected_submit;
+                } else {
+                    // Protected and unprotected command buffers can't be in the same batch, so
+                    // they go in a batch of their own, in the same submission.
+                    submit_infos.push(SubmitInfo {
+                        command_buffers: self.command_buffers.iter().cloned().collect(),
+                        protected_submit,
+                        ..Default::default()
+                    });
+                }
+
+                SubmitAnyBuilder::CommandBuffer(submit_infos, fence)
             }
             SubmitAnyBuilder::QueuePresent(_) | SubmitAnyBuilder::BindSparse(_, _) => {
                 unimplemented!() // TODO:
                                  /*present.submit();     // TODO: wrong
                                  let mut builder = SubmitCommandBufferBuilder::new();
-                                 builder.add_command_buffer(self.command_buffer.inner());
+                                 builder.add_command_buffer(self.command_buffers[0].inner());
                                  SubmitAnyBuilder::CommandBuffer(builder)*/
             }
         })
@@ -409,8 +518,8 @@ where
 
             match self.build_submission_impl()? {
                 SubmitAnyBuilder::Empty => {}
-                SubmitAnyBuilder::CommandBuffer(submit_info, fence) => {
-                    queue.with(|mut q| q.submit_unchecked([submit_info], fence))?;
+                SubmitAnyBuilder::CommandBuffer(submit_infos, fence) => {
+                    queue.with(|mut q| q.submit_unchecked(submit_infos, fence))?;
                 }
                 _ => unreachable!(),
             };
@@ -441,16 +550,16 @@ where
         exclusive: bool,
         queue: &Queue,
     ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
-        match self
-            .command_buffer
-            .check_buffer_access(buffer, range.clone(), exclusive, queue)
-        {
-            Ok(v) => Ok(v),
-            Err(AccessCheckError::Denied(err)) => Err(AccessCheckError::Denied(err)),
-            Err(AccessCheckError::Unknown) => self
-                .previous
-                .check_buffer_access(buffer, range, exclusive, queue),
+        // Later command buffers in the batch override the accesses of earlier ones.
+        for command_buffer in self.command_buffers.iter().rev() {
+            match command_buffer.check_buffer_access(buffer, range.clone(), exclusive, queue) {
+                Err(AccessCheckError::Unknown) => (),
+                result => return result,
+            }
         }
+
+        self.previous
+            .check_buffer_access(buffer, range, exclusive, queue)
     }
 
     fn check_image_access(
@@ -461,20 +570,22 @@ where
         expected_layout: ImageLayout,
         queue: &Queue,
     ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
-        match self.command_buffer.check_image_access(
-            image,
-            range.clone(),
-            exclusive,
-            expected_layout,
-            queue,
-        ) {
-            Ok(v) => Ok(v),
-            Err(AccessCheckError::Denied(err)) => Err(AccessCheckError::Denied(err)),
-            Err(AccessCheckError::Unknown) => {
-                self.previous
-                    .check_image_access(image, range, exclusive, expected_layout, queue)
+        // Later command buffers in the batch override the accesses of earlier ones.
+        for command_buffer in self.command_buffers.iter().rev() {
+            match command_buffer.check_image_access(
+                image,
+                range.clone(),
+                exclusive,
+                expected_layout,
+                queue,
+            ) {
+                Err(AccessCheckError::Unknown) => (),
+                result => return result,
             }
         }
+
+        self.previous
+            .check_image_access(image, range, exclusive, expected_layout, queue)
     }
 
     #[inline]
@@ -492,7 +603,7 @@ where
     F: GpuFuture,
 {
     fn device(&self) -> &Arc<Device> {
-        self.command_buffer.device()
+        self.queue.device()
     }
 }
 
@@ -531,6 +642,13 @@ pub enum CommandBufferExecError {
     /// The command buffer or one of the secondary command buffers it executes is already in use by
     /// the GPU and was not created with the "concurrent" flag.
     ExclusiveAlreadyInUse,
+
+    /// A batch of command buffers contains both protected and unprotected command buffers.
+    ProtectedMismatch,
+
+    /// The command buffer is a protected command buffer, but the queue was not created with the
+    /// `protected` flag.
+    ProtectedQueueRequired,
     // TODO: missing entries (eg. wrong queue family, secondary command buffer)
 }
 
@@ -560,7 +678,100 @@ impl Display for CommandBufferExecError {
                     "the command buffer or one of the secondary command buffers it executes is \
                     already in use was not created with the \"concurrent\" flag"
                 }
+                CommandBufferExecError::ProtectedMismatch => {
+                    "a batch of command buffers contains both protected and unprotected command \
+                    buffers"
+                }
+                CommandBufferExecError::ProtectedQueueRequired => {
+                    "the command buffer is a protected command buffer, but the queue was not \
+                    created with the `protected` flag"
+                }
             }
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+            CommandBufferUsage, PrimaryCommandBuffer,
+        },
+        device::{Device, DeviceCreateInfo, Features, QueueCreateFlags, QueueCreateInfo},
+        sync::{GpuFuture, SubmitAnyBuilder},
+    };
+
+    #[test]
+    fn protected_and_unprotected_batches() {
+        let instance = instance!();
+
+        let enabled_features = Features {
+            protected_memory: true,
+            ..Features::empty()
+        };
+        let (physical_device, queue_family_index) = match instance
+            .enumerate_physical_devices()
+            .unwrap()
+            .filter(|p| p.supported_features().contains(&enabled_features))
+            .find_map(|p| {
+                let queue_family_index = p
+                    .queue_family_properties()
+                    .iter()
+                    .position(|q| q.queue_flags.protected)?;
+
+                Some((p, queue_family_index as u32))
+            }) {
+            Some(x) => x,
+            None => return, // test ignored
+        };
+
+        let (device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    flags: QueueCreateFlags {
+                        protected: true,
+                        ..QueueCreateFlags::empty()
+                    },
+                    queue_family_index,
+                    ..Default::default()
+                }],
+                enabled_features,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let queue = queues.next().unwrap();
+
+        let build = |allocator: &StandardCommandBufferAllocator| {
+            AutoCommandBufferBuilder::primary(
+                allocator,
+                queue_family_index,
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .unwrap()
+            .build()
+            .unwrap()
+        };
+        let allocator = StandardCommandBufferAllocator::new(device.clone());
+        let protected_allocator = StandardCommandBufferAllocator::new_protected(device);
+
+        let future = build(&allocator)
+            .execute(queue.clone())
+            .unwrap()
+            .then_execute(queue, build(&protected_allocator))
+            .unwrap();
+
+        // The unprotected and protected command buffers can't share a batch, but they are still
+        // submitted together.
+        match unsafe { future.build_submission() }.unwrap() {
+            SubmitAnyBuilder::CommandBuffer(submit_infos, None) => {
+                assert_eq!(submit_infos.len(), 2);
+                assert!(!submit_infos[0].protected_submit);
+                assert!(submit_infos[1].protected_submit);
+            }
+            _ => panic!(),
+        }
+    }
+}
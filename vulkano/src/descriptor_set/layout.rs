@@ -14,9 +14,9 @@
 use crate::{
     device::{Device, DeviceOwned},
     macros::vulkan_enum,
-    sampler::Sampler,
-    shader::{DescriptorRequirements, ShaderStages},
-    OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
+    sampler::{ycbcr::SamplerYcbcrConversion, Sampler, SamplerCreateInfo, SamplerCreationError},
+    shader::{DescriptorBindingRequirements, ShaderStages},
+    DeviceSize, OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
 use ahash::HashMap;
 use std::{
@@ -139,6 +139,7 @@ impl DescriptorSetLayout {
                 variable_descriptor_count,
                 stages,
                 ref immutable_samplers,
+                buffer_block_size: _,
                 _ne: _,
             } = binding;
 
@@ -154,9 +155,12 @@ impl DescriptorSetLayout {
 
             if push_descriptor {
                 // VUID-VkDescriptorSetLayoutCreateInfo-flags-00280
+                // VUID-VkDescriptorSetLayoutCreateInfo-flags-02208
                 if matches!(
                     descriptor_type,
-                    DescriptorType::StorageBufferDynamic | DescriptorType::UniformBufferDynamic
+                    DescriptorType::StorageBufferDynamic
+                        | DescriptorType::UniformBufferDynamic
+                        | DescriptorType::InlineUniformBlock
                 ) {
                     return Err(
                         DescriptorSetLayoutCreationError::PushDescriptorDescriptorTypeIncompatible {
@@ -212,6 +216,47 @@ impl DescriptorSetLayout {
                 }
             }
 
+            if descriptor_type == DescriptorType::InlineUniformBlock {
+                // VUID-VkDescriptorSetLayoutBinding-descriptorType-04604
+                if !device.enabled_features().inline_uniform_block {
+                    return Err(DescriptorSetLayoutCreationError::RequirementNotMet {
+                        required_for: "`create_info.bindings` has an element where \
+                            `descriptor_type` is `DescriptorType::InlineUniformBlock`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["inline_uniform_block"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkDescriptorSetLayoutBinding-descriptorType-02209
+                if descriptor_count % 4 != 0 {
+                    return Err(
+                        DescriptorSetLayoutCreationError::InlineUniformBlockSizeNotAligned {
+                            binding_num,
+                            size: descriptor_count,
+                        },
+                    );
+                }
+
+                // VUID-VkDescriptorSetLayoutBinding-descriptorType-08004
+                let max_size = device
+                    .physical_device()
+                    .properties()
+                    .max_inline_uniform_block_size
+                    .unwrap_or(0);
+
+                if descriptor_count > max_size {
+                    return Err(
+                        DescriptorSetLayoutCreationError::MaxInlineUniformBlockSizeExceeded {
+                            binding_num,
+                            size: descriptor_count,
+                            max: max_size,
+                        },
+                    );
+                }
+            }
+
             // VUID-VkDescriptorSetLayoutBinding-descriptorType-01510
             // If descriptorType is VK_DESCRIPTOR_TYPE_INPUT_ATTACHMENT and descriptorCount is not 0, then stageFlags must be 0 or VK_SHADER_STAGE_FRAGMENT_BIT
 
@@ -419,7 +464,14 @@ impl DescriptorSetLayout {
     #[inline]
     pub fn is_compatible_with(&self, other: &DescriptorSetLayout) -> bool {
         self == other
-            || (self.bindings == other.bindings && self.push_descriptor == other.push_descriptor)
+            || (self.push_descriptor == other.push_descriptor
+                && self.bindings.len() == other.bindings.len()
+                && self.bindings.iter().all(|(binding_num, binding)| {
+                    other
+                        .bindings
+                        .get(binding_num)
+                        .map_or(false, |other| binding.is_identically_defined(other))
+                }))
     }
 }
 
@@ -480,6 +532,10 @@ pub enum DescriptorSetLayoutCreationError {
         requires_one_of: RequiresOneOf,
     },
 
+    /// A binding has the `InlineUniformBlock` descriptor type, but its `descriptor_count` is not
+    /// a multiple of 4.
+    InlineUniformBlockSizeNotAligned { binding_num: u32, size: u32 },
+
     /// A binding includes immutable samplers but their number differs from  `descriptor_count`.
     ImmutableSamplersCountMismatch {
         binding_num: u32,
@@ -490,6 +546,16 @@ pub enum DescriptorSetLayoutCreationError {
     /// A binding includes immutable samplers but it has an incompatible `descriptor_type`.
     ImmutableSamplersDescriptorTypeIncompatible { binding_num: u32 },
 
+    /// A binding has the `InlineUniformBlock` descriptor type, but its `descriptor_count` is
+    /// greater than the
+    /// [`max_inline_uniform_block_size`](crate::device::Properties::max_inline_uniform_block_size)
+    /// limit.
+    MaxInlineUniformBlockSizeExceeded {
+        binding_num: u32,
+        size: u32,
+        max: u32,
+    },
+
     /// More descriptors were provided in all bindings than the
     /// [`max_push_descriptors`](crate::device::Properties::max_push_descriptors) limit.
     MaxPushDescriptorsExceeded { provided: u32, max_supported: u32 },
@@ -534,6 +600,12 @@ impl Display for DescriptorSetLayoutCreationError {
                 "a requirement was not met for: {}; requires one of: {}",
                 required_for, requires_one_of,
             ),
+            Self::InlineUniformBlockSizeNotAligned { binding_num, size } => write!(
+                f,
+                "binding {} has the `InlineUniformBlock` descriptor type, but its size ({}) is \
+                not a multiple of 4",
+                binding_num, size,
+            ),
             Self::ImmutableSamplersCountMismatch {
                 binding_num,
                 sampler_count,
@@ -550,6 +622,16 @@ impl Display for DescriptorSetLayoutCreationError {
                 `descriptor_type`",
                 binding_num,
             ),
+            Self::MaxInlineUniformBlockSizeExceeded {
+                binding_num,
+                size,
+                max,
+            } => write!(
+                f,
+                "binding {} has the `InlineUniformBlock` descriptor type, but its size ({}) is \
+                greater than the `max_inline_uniform_block_size` limit ({})",
+                binding_num, size, max,
+            ),
             Self::MaxPushDescriptorsExceeded {
                 provided,
                 max_supported,
@@ -645,7 +727,9 @@ impl DescriptorSetLayoutCreateInfo {
     /// Builds a list of `DescriptorSetLayoutCreateInfo` from an iterator of `DescriptorRequirement`
     /// originating from a shader.
     pub fn from_requirements<'a>(
-        descriptor_requirements: impl IntoIterator<Item = ((u32, u32), &'a DescriptorRequirements)>,
+        descriptor_requirements: impl IntoIterator<
+            Item = ((u32, u32), &'a DescriptorBindingRequirements),
+        >,
     ) -> Vec<Self> {
         let mut create_infos: Vec<Self> = Vec::new();
 
@@ -662,6 +746,130 @@ impl DescriptorSetLayoutCreateInfo {
 
         create_infos
     }
+
+    /// Builds a list of `DescriptorSetLayoutCreateInfo` like [`from_requirements`], but gives
+    /// runtime-sized arrays a descriptor count based on the limits of `device`, instead of `0`.
+    ///
+    /// The descriptors that are left over after all fixed-size bindings have been counted are
+    /// divided evenly between the runtime-sized arrays that count towards the same limit. If the
+    /// [`descriptor_binding_variable_descriptor_count`] feature is enabled on the device, a
+    /// runtime-sized array that is the highest binding in its set also has
+    /// `variable_descriptor_count` enabled.
+    ///
+    /// Runtime-sized arrays require the [`runtime_descriptor_array`] feature. If it is not enabled
+    /// on the device, this returns the same result as `from_requirements`.
+    ///
+    /// [`from_requirements`]: Self::from_requirements
+    /// [`descriptor_binding_variable_descriptor_count`]: crate::device::Features::descriptor_binding_variable_descriptor_count
+    /// [`runtime_descriptor_array`]: crate::device::Features::runtime_descriptor_array
+    pub fn from_requirements_with_device<'a>(
+        device: &Device,
+        descriptor_requirements: impl IntoIterator<
+            Item = ((u32, u32), &'a DescriptorBindingRequirements),
+        >,
+    ) -> Vec<Self> {
+        let descriptor_requirements: Vec<_> = descriptor_requirements.into_iter().collect();
+        let mut create_infos = Self::from_requirements(descriptor_requirements.iter().copied());
+
+        if !device.enabled_features().runtime_descriptor_array {
+            return create_infos;
+        }
+
+        let properties = device.physical_device().properties();
+        let limits = [
+            properties
+                .max_per_stage_descriptor_samplers
+                .min(properties.max_descriptor_set_samplers),
+            properties
+                .max_per_stage_descriptor_uniform_buffers
+                .min(properties.max_descriptor_set_uniform_buffers),
+            properties
+                .max_per_stage_descriptor_storage_buffers
+                .min(properties.max_descriptor_set_storage_buffers),
+            properties
+                .max_per_stage_descriptor_sampled_images
+                .min(properties.max_descriptor_set_sampled_images),
+            properties
+                .max_per_stage_descriptor_storage_images
+                .min(properties.max_descriptor_set_storage_images),
+            properties
+                .max_per_stage_descriptor_input_attachments
+                .min(properties.max_descriptor_set_input_attachments),
+            properties.max_per_stage_resources,
+        ];
+        let mut num_fixed = [0u32; 7];
+        let mut num_runtime = [0u32; 7];
+
+        for (_, reqs) in &descriptor_requirements {
+            for &limit in descriptor_type_limits(reqs.descriptor_types[0]) {
+                match reqs.descriptor_count {
+                    Some(count) => num_fixed[limit] = num_fixed[limit].saturating_add(count),
+                    None => num_runtime[limit] += 1,
+                }
+            }
+        }
+
+        let variable_descriptor_count_enabled = device
+            .enabled_features()
+            .descriptor_binding_variable_descriptor_count;
+
+        for &((set_num, binding_num), reqs) in &descriptor_requirements {
+            if reqs.descriptor_count.is_some() {
+                continue;
+            }
+
+            let create_info = &mut create_infos[set_num as usize];
+            let is_last_binding = create_info.bindings.keys().next_back() == Some(&binding_num);
+            let binding = create_info.bindings.get_mut(&binding_num).unwrap();
+
+            binding.descriptor_count = descriptor_type_limits(binding.descriptor_type)
+                .iter()
+                .map(|&limit| limits[limit].saturating_sub(num_fixed[limit]) / num_runtime[limit])
+                .min()
+                .unwrap_or(0)
+                .max(1);
+            binding.variable_descriptor_count = variable_descriptor_count_enabled
+                && is_last_binding
+                && !matches!(
+                    binding.descriptor_type,
+                    DescriptorType::UniformBufferDynamic | DescriptorType::StorageBufferDynamic
+                );
+        }
+
+        create_infos
+    }
+}
+
+/// Returns the indices of the device limits that a descriptor of type `descriptor_type` counts
+/// towards, in the order used by `DescriptorSetLayoutCreateInfo::from_requirements_with_device`.
+fn descriptor_type_limits(descriptor_type: DescriptorType) -> &'static [usize] {
+    const SAMPLERS: usize = 0;
+    const UNIFORM_BUFFERS: usize = 1;
+    const STORAGE_BUFFERS: usize = 2;
+    const SAMPLED_IMAGES: usize = 3;
+    const STORAGE_IMAGES: usize = 4;
+    const INPUT_ATTACHMENTS: usize = 5;
+    const RESOURCES: usize = 6;
+
+    match descriptor_type {
+        DescriptorType::Sampler => &[SAMPLERS, RESOURCES],
+        DescriptorType::CombinedImageSampler => &[SAMPLERS, SAMPLED_IMAGES, RESOURCES],
+        DescriptorType::SampledImage | DescriptorType::UniformTexelBuffer => {
+            &[SAMPLED_IMAGES, RESOURCES]
+        }
+        DescriptorType::StorageImage | DescriptorType::StorageTexelBuffer => {
+            &[STORAGE_IMAGES, RESOURCES]
+        }
+        DescriptorType::UniformBuffer | DescriptorType::UniformBufferDynamic => {
+            &[UNIFORM_BUFFERS, RESOURCES]
+        }
+        DescriptorType::StorageBuffer | DescriptorType::StorageBufferDynamic => {
+            &[STORAGE_BUFFERS, RESOURCES]
+        }
+        DescriptorType::InputAttachment => &[INPUT_ATTACHMENTS, RESOURCES],
+        // The descriptor count is a size in bytes, which is limited separately.
+        DescriptorType::InlineUniformBlock => &[],
+    }
 }
 
 /// A binding in a descriptor set layout.
@@ -676,6 +884,9 @@ pub struct DescriptorSetLayoutBinding {
     ///
     /// If the binding is a single element rather than an array, then you must specify `1`.
     ///
+    /// For the [`InlineUniformBlock`](DescriptorType::InlineUniformBlock) descriptor type, this
+    /// is the size of the block in bytes instead.
+    ///
     /// The default value is `1`.
     pub descriptor_count: u32,
 
@@ -710,6 +921,17 @@ pub struct DescriptorSetLayoutBinding {
     /// The default value is empty.
     pub immutable_samplers: Vec<Arc<Sampler>>,
 
+    /// For uniform and storage buffer bindings, the minimum size in bytes of the buffers that can
+    /// be written to the binding.
+    ///
+    /// This is not part of the Vulkan definition of the layout, and doesn't affect compatibility
+    /// with other layouts. It lets vulkano check the size of buffers when they are written to a
+    /// descriptor set, instead of only when the set is used in a draw or dispatch. Layouts that
+    /// are created from shader requirements set it to the size of the block in the shader.
+    ///
+    /// The default value is `None`.
+    pub buffer_block_size: Option<DeviceSize>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -723,20 +945,72 @@ impl DescriptorSetLayoutBinding {
             variable_descriptor_count: false,
             stages: ShaderStages::empty(),
             immutable_samplers: Vec::new(),
+            buffer_block_size: None,
             _ne: crate::NonExhaustive(()),
         }
     }
 
+    /// Returns a `DescriptorSetLayoutBinding` for a single combined image sampler, whose
+    /// immutable sampler is created from `sampler_create_info` with `conversion` attached.
+    ///
+    /// Image views written to the binding must be created with the same `conversion`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vulkano::{
+    ///     descriptor_set::layout::DescriptorSetLayoutBinding, sampler::SamplerCreateInfo,
+    ///     shader::ShaderStage,
+    /// };
+    /// # let conversion: std::sync::Arc<vulkano::sampler::ycbcr::SamplerYcbcrConversion> = return;
+    ///
+    /// let binding = DescriptorSetLayoutBinding {
+    ///     stages: ShaderStage::Fragment.into(),
+    ///     ..DescriptorSetLayoutBinding::immutable_ycbcr_sampler(
+    ///         conversion,
+    ///         SamplerCreateInfo::default(),
+    ///     )
+    ///     .unwrap()
+    /// };
+    /// ```
+    pub fn immutable_ycbcr_sampler(
+        conversion: Arc<SamplerYcbcrConversion>,
+        sampler_create_info: SamplerCreateInfo,
+    ) -> Result<Self, SamplerCreationError> {
+        let sampler = Sampler::new(
+            conversion.device().clone(),
+            SamplerCreateInfo {
+                sampler_ycbcr_conversion: Some(conversion),
+                ..sampler_create_info
+            },
+        )?;
+
+        Ok(Self {
+            immutable_samplers: vec![sampler],
+            ..Self::descriptor_type(DescriptorType::CombinedImageSampler)
+        })
+    }
+
+    // Returns whether `self` and `other` are identically defined to the Vulkan API.
+    fn is_identically_defined(&self, other: &Self) -> bool {
+        self.descriptor_type == other.descriptor_type
+            && self.descriptor_count == other.descriptor_count
+            && self.variable_descriptor_count == other.variable_descriptor_count
+            && self.stages == other.stages
+            && self.immutable_samplers == other.immutable_samplers
+    }
+
     /// Checks whether the descriptor of a pipeline layout `self` is compatible with the
     /// requirements of a shader `other`.
     #[inline]
     pub fn ensure_compatible_with_shader(
         &self,
-        descriptor_requirements: &DescriptorRequirements,
+        descriptor_requirements: &DescriptorBindingRequirements,
     ) -> Result<(), DescriptorRequirementsNotMet> {
-        let &DescriptorRequirements {
+        let &DescriptorBindingRequirements {
             ref descriptor_types,
             descriptor_count,
+            buffer_block_size: _,
             image_format: _,
             image_multisampled: _,
             image_scalar_type: _,
@@ -758,7 +1032,10 @@ impl DescriptorSetLayoutBinding {
             });
         }
 
-        if let Some(required) = descriptor_count {
+        // For inline uniform blocks, `self.descriptor_count` is a size in bytes instead.
+        if let Some(required) =
+            descriptor_count.filter(|_| self.descriptor_type != DescriptorType::InlineUniformBlock)
+        {
             if self.descriptor_count < required {
                 return Err(DescriptorRequirementsNotMet::DescriptorCount {
                     required,
@@ -778,15 +1055,16 @@ impl DescriptorSetLayoutBinding {
     }
 }
 
-impl From<&DescriptorRequirements> for DescriptorSetLayoutBinding {
+impl From<&DescriptorBindingRequirements> for DescriptorSetLayoutBinding {
     #[inline]
-    fn from(reqs: &DescriptorRequirements) -> Self {
+    fn from(reqs: &DescriptorBindingRequirements) -> Self {
         Self {
             descriptor_type: reqs.descriptor_types[0],
             descriptor_count: reqs.descriptor_count.unwrap_or(0),
             variable_descriptor_count: false,
             stages: reqs.stages,
             immutable_samplers: Vec::new(),
+            buffer_block_size: reqs.buffer_block_size,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -879,13 +1157,19 @@ vulkan_enum! {
     /// pixel that is currently being processed by the fragment shader.
     InputAttachment = INPUT_ATTACHMENT,
 
-    /*
-    // TODO: document
+    /// Gives read-only access to a block of data that is stored in the descriptor set itself,
+    /// interpreted as a structure. No buffer is needed.
+    ///
+    /// For this descriptor type, `descriptor_count` in the layout is the size of the block in
+    /// bytes, and the array elements of a write are individual bytes. The size must be a multiple
+    /// of 4, and the [`inline_uniform_block`](crate::device::Features::inline_uniform_block)
+    /// feature must be enabled on the device.
     InlineUniformBlock = INLINE_UNIFORM_BLOCK {
         api_version: V1_3,
         device_extensions: [ext_inline_uniform_block],
     },
 
+    /*
     // TODO: document
     AccelerationStructure = ACCELERATION_STRUCTURE_KHR {
         device_extensions: [khr_acceleration_structure],
@@ -908,9 +1192,9 @@ mod tests {
     use crate::{
         descriptor_set::layout::{
             DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
-            DescriptorType,
+            DescriptorSetLayoutCreationError, DescriptorType,
         },
-        shader::ShaderStages,
+        shader::{DescriptorBindingRequirements, ShaderStages},
     };
     use ahash::HashMap;
 
@@ -947,4 +1231,95 @@ mod tests {
                 .collect::<HashMap<_, _>>(),
         );
     }
+
+    #[test]
+    fn runtime_array_from_requirements() {
+        let (device, _) = gfx_dev_and_queue!(runtime_descriptor_array);
+
+        let fixed = DescriptorBindingRequirements {
+            descriptor_types: vec![DescriptorType::StorageBuffer],
+            descriptor_count: Some(2),
+            stages: ShaderStages {
+                compute: true,
+                ..ShaderStages::empty()
+            },
+            ..Default::default()
+        };
+        let runtime = DescriptorBindingRequirements {
+            descriptor_types: vec![DescriptorType::StorageBuffer],
+            descriptor_count: None,
+            stages: ShaderStages {
+                compute: true,
+                ..ShaderStages::empty()
+            },
+            ..Default::default()
+        };
+
+        let create_infos = DescriptorSetLayoutCreateInfo::from_requirements_with_device(
+            &device,
+            [((0, 0), &fixed), ((0, 1), &runtime)],
+        );
+        let bindings = &create_infos[0].bindings;
+        let properties = device.physical_device().properties();
+
+        assert_eq!(bindings[&0].descriptor_count, 2);
+        assert!(bindings[&1].descriptor_count >= 1);
+        assert!(
+            bindings[&1].descriptor_count + 2
+                <= properties.max_per_stage_descriptor_storage_buffers
+        );
+        assert!(DescriptorSetLayout::new(device, create_infos[0].clone()).is_ok());
+    }
+
+    #[test]
+    fn runtime_array_from_requirements_without_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let runtime = DescriptorBindingRequirements {
+            descriptor_types: vec![DescriptorType::StorageBuffer],
+            descriptor_count: None,
+            stages: ShaderStages {
+                compute: true,
+                ..ShaderStages::empty()
+            },
+            ..Default::default()
+        };
+
+        let create_infos = DescriptorSetLayoutCreateInfo::from_requirements_with_device(
+            &device,
+            [((0, 0), &runtime)],
+        );
+        let binding = &create_infos[0].bindings[&0];
+
+        assert_eq!(binding.descriptor_count, 0);
+        assert!(!binding.variable_descriptor_count);
+    }
+
+    #[test]
+    fn inline_uniform_block_without_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let result = DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        descriptor_count: 16,
+                        stages: ShaderStages::all_graphics(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(
+                            DescriptorType::InlineUniformBlock,
+                        )
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        );
+
+        assert!(matches!(
+            result,
+            Err(DescriptorSetLayoutCreationError::RequirementNotMet { .. })
+        ));
+    }
 }
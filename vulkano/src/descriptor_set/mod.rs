@@ -78,7 +78,7 @@
 //! [`DescriptorSetAllocator`]: allocator::DescriptorSetAllocator
 //! [`StandardDescriptorSetAllocator`]: allocator::StandardDescriptorSetAllocator
 
-pub(crate) use self::update::{check_descriptor_write, DescriptorWriteInfo};
+pub(crate) use self::update::check_descriptor_write;
 pub use self::{
     collection::DescriptorSetsCollection,
     persistent::PersistentDescriptorSet,
@@ -94,9 +94,10 @@ use crate::{
     sampler::Sampler,
     OomError, VulkanObject,
 };
-use ahash::HashMap;
+use ahash::{HashMap, HashSet};
 use smallvec::{smallvec, SmallVec};
 use std::{
+    collections::BTreeMap,
     error::Error,
     fmt::{Display, Error as FmtError, Formatter},
     hash::{Hash, Hasher},
@@ -201,23 +202,10 @@ impl DescriptorSetInner {
 
         if !write_descriptor_set.is_empty() {
             for (info, write) in descriptor_write_info
-                .iter()
+                .iter_mut()
                 .zip(write_descriptor_set.iter_mut())
             {
-                match info {
-                    DescriptorWriteInfo::Image(info) => {
-                        write.descriptor_count = info.len() as u32;
-                        write.p_image_info = info.as_ptr();
-                    }
-                    DescriptorWriteInfo::Buffer(info) => {
-                        write.descriptor_count = info.len() as u32;
-                        write.p_buffer_info = info.as_ptr();
-                    }
-                    DescriptorWriteInfo::BufferView(info) => {
-                        write.descriptor_count = info.len() as u32;
-                        write.p_texel_buffer_view = info.as_ptr();
-                    }
-                }
+                info.set_pointers(write);
             }
         }
 
@@ -249,6 +237,7 @@ impl DescriptorSetInner {
 #[derive(Clone)]
 pub struct DescriptorSetResources {
     binding_resources: HashMap<u32, DescriptorBindingResources>,
+    null_descriptors: HashSet<(u32, u32)>,
 }
 
 impl DescriptorSetResources {
@@ -303,12 +292,18 @@ impl DescriptorSetResources {
                             DescriptorBindingResources::None(smallvec![Some(()); count])
                         }
                     }
+                    DescriptorType::InlineUniformBlock => {
+                        DescriptorBindingResources::InlineUniformBlock
+                    }
                 };
                 (binding_num, binding_resources)
             })
             .collect();
 
-        Self { binding_resources }
+        Self {
+            binding_resources,
+            null_descriptors: HashSet::default(),
+        }
     }
 
     /// Applies a descriptor write to the resources.
@@ -319,10 +314,25 @@ impl DescriptorSetResources {
     /// - See also [`DescriptorBindingResources::update`].
     #[inline]
     pub fn update(&mut self, write: &WriteDescriptorSet) {
-        self.binding_resources
+        let binding_resources = self
+            .binding_resources
             .get_mut(&write.binding())
-            .expect("descriptor write has invalid binding number")
-            .update(write)
+            .expect("descriptor write has invalid binding number");
+        binding_resources.update(write);
+
+        let indices =
+            write.first_array_element()..write.first_array_element() + write.elements().len();
+
+        if matches!(write.elements(), WriteDescriptorSetElements::None(_))
+            && !matches!(binding_resources, DescriptorBindingResources::None(_))
+        {
+            self.null_descriptors
+                .extend(indices.map(|index| (write.binding(), index)));
+        } else {
+            for index in indices {
+                self.null_descriptors.remove(&(write.binding(), index));
+            }
+        }
     }
 
     /// Returns a reference to the bound resources for `binding`. Returns `None` if the binding
@@ -331,6 +341,13 @@ impl DescriptorSetResources {
     pub fn binding(&self, binding: u32) -> Option<&DescriptorBindingResources> {
         self.binding_resources.get(&binding)
     }
+
+    /// Returns whether a null descriptor has been written to array element `index` of
+    /// `binding`. The element has no resource in that case.
+    #[inline]
+    pub fn is_null(&self, binding: u32, index: u32) -> bool {
+        self.null_descriptors.contains(&(binding, index))
+    }
 }
 
 /// The resources that are bound to a single descriptor set binding.
@@ -342,6 +359,9 @@ pub enum DescriptorBindingResources {
     ImageView(Elements<Arc<dyn ImageViewAbstract>>),
     ImageViewSampler(Elements<(Arc<dyn ImageViewAbstract>, Arc<Sampler>)>),
     Sampler(Elements<Arc<Sampler>>),
+    /// The data of an inline uniform block is stored in the descriptor set, so there are no
+    /// resources.
+    InlineUniformBlock,
 }
 
 type Elements<T> = SmallVec<[Option<T>; 1]>;
@@ -353,6 +373,9 @@ impl DescriptorBindingResources {
     ///
     /// - Panics if the resource types do not match.
     /// - Panics if the write goes out of bounds.
+    ///
+    /// An empty write to a binding that holds resources is a null descriptor write, and removes
+    /// the resources of the written elements.
     #[inline]
     pub fn update(&mut self, write: &WriteDescriptorSet) {
         fn write_resources<T: Clone>(first: usize, resources: &mut [Option<T>], elements: &[T]) {
@@ -366,6 +389,16 @@ impl DescriptorBindingResources {
                 });
         }
 
+        fn clear_resources<T>(first: usize, resources: &mut [Option<T>], num_elements: u32) {
+            resources
+                .get_mut(first..first + num_elements as usize)
+                .expect("descriptor write for binding out of bounds")
+                .iter_mut()
+                .for_each(|resource| {
+                    *resource = None;
+                });
+        }
+
         let first = write.first_array_element() as usize;
 
         match (self, write.elements()) {
@@ -401,6 +434,22 @@ impl DescriptorBindingResources {
                 DescriptorBindingResources::Sampler(resources),
                 WriteDescriptorSetElements::Sampler(elements),
             ) => write_resources(first, resources, elements),
+            (
+                DescriptorBindingResources::InlineUniformBlock,
+                WriteDescriptorSetElements::InlineUniformBlock(_),
+            ) => (),
+            (
+                DescriptorBindingResources::Buffer(resources),
+                WriteDescriptorSetElements::None(num_elements),
+            ) => clear_resources(first, resources, *num_elements),
+            (
+                DescriptorBindingResources::BufferView(resources),
+                WriteDescriptorSetElements::None(num_elements),
+            ) => clear_resources(first, resources, *num_elements),
+            (
+                DescriptorBindingResources::ImageView(resources),
+                WriteDescriptorSetElements::None(num_elements),
+            ) => clear_resources(first, resources, *num_elements),
             _ => panic!(
                 "descriptor write for binding {} has wrong resource type",
                 write.binding(),
@@ -429,34 +478,40 @@ impl DescriptorSetWithOffsets {
 
         // Ensure that the number of dynamic_offsets is correct and that each
         // dynamic offset is a multiple of the minimum offset alignment specified
-        // by the physical device.
+        // by the physical device. Every array element of a dynamic binding takes one offset.
         for binding in layout.bindings().values() {
             match binding.descriptor_type {
                 DescriptorType::StorageBufferDynamic => {
-                    // Don't check alignment if there are not enough offsets anyway
-                    if dynamic_offsets.len() > dynamic_offset_index {
-                        assert!(
-                            dynamic_offsets[dynamic_offset_index] % min_storage_off_align == 0,
-                            "Dynamic storage buffer offset must be a multiple of \
-                            min_storage_buffer_offset_alignment: got {}, expected a multiple of {}",
-                            dynamic_offsets[dynamic_offset_index],
-                            min_storage_off_align,
-                        );
+                    for _ in 0..binding.descriptor_count {
+                        // Don't check alignment if there are not enough offsets anyway
+                        if dynamic_offsets.len() > dynamic_offset_index {
+                            assert!(
+                                dynamic_offsets[dynamic_offset_index] % min_storage_off_align == 0,
+                                "Dynamic storage buffer offset must be a multiple of \
+                                min_storage_buffer_offset_alignment: got {}, expected a multiple \
+                                of {}",
+                                dynamic_offsets[dynamic_offset_index],
+                                min_storage_off_align,
+                            );
+                        }
+                        dynamic_offset_index += 1;
                     }
-                    dynamic_offset_index += 1;
                 }
                 DescriptorType::UniformBufferDynamic => {
-                    // Don't check alignment if there are not enough offsets anyway
-                    if dynamic_offsets.len() > dynamic_offset_index {
-                        assert!(
-                            dynamic_offsets[dynamic_offset_index] % min_uniform_off_align == 0,
-                            "Dynamic uniform buffer offset must be a multiple of \
-                            min_uniform_buffer_offset_alignment: got {}, expected a multiple of {}",
-                            dynamic_offsets[dynamic_offset_index],
-                            min_uniform_off_align,
-                        );
+                    for _ in 0..binding.descriptor_count {
+                        // Don't check alignment if there are not enough offsets anyway
+                        if dynamic_offsets.len() > dynamic_offset_index {
+                            assert!(
+                                dynamic_offsets[dynamic_offset_index] % min_uniform_off_align == 0,
+                                "Dynamic uniform buffer offset must be a multiple of \
+                                min_uniform_buffer_offset_alignment: got {}, expected a multiple \
+                                of {}",
+                                dynamic_offsets[dynamic_offset_index],
+                                min_uniform_off_align,
+                            );
+                        }
+                        dynamic_offset_index += 1;
                     }
-                    dynamic_offset_index += 1;
                 }
                 _ => (),
             }
@@ -481,6 +536,19 @@ impl DescriptorSetWithOffsets {
         }
     }
 
+    /// Creates a `DescriptorSetWithOffsets` without checking the dynamic offsets. They must be
+    /// validated when the set is bound.
+    #[inline]
+    pub(crate) fn new_unchecked(
+        descriptor_set: Arc<dyn DescriptorSet>,
+        dynamic_offsets: SmallVec<[u32; 4]>,
+    ) -> Self {
+        DescriptorSetWithOffsets {
+            descriptor_set,
+            dynamic_offsets,
+        }
+    }
+
     #[inline]
     pub fn as_ref(&self) -> (&Arc<dyn DescriptorSet>, &[u32]) {
         (&self.descriptor_set, &self.dynamic_offsets)
@@ -501,6 +569,107 @@ where
     }
 }
 
+/// The dynamic offsets of the dynamic uniform and storage buffer descriptors of one or more
+/// descriptor sets.
+///
+/// Vulkan takes dynamic offsets as a flat list, ordered by set number, then binding number, then
+/// array element. Instead, `DynamicOffsets` maps each offset to the set number, binding number
+/// and array element of its descriptor, and the list is built when the sets are bound with
+/// [`bind_descriptor_sets_with_offsets`]. At that point, every dynamic descriptor of the bound
+/// sets must have an offset, and each offset must respect the alignment limits of the device.
+///
+/// # Examples
+///
+/// ```
+/// use vulkano::{descriptor_set::DynamicOffsets, pipeline::PipelineBindPoint};
+/// # let mut builder: vulkano::command_buffer::AutoCommandBufferBuilder<vulkano::command_buffer::PrimaryAutoCommandBuffer> = return;
+/// # let pipeline_layout: std::sync::Arc<vulkano::pipeline::layout::PipelineLayout> = return;
+/// # let descriptor_set: std::sync::Arc<vulkano::descriptor_set::PersistentDescriptorSet> = return;
+/// # let uniform_buffer_pool: vulkano::buffer::CpuBufferPool<[f32; 4]> = return;
+///
+/// // Binding 1 of the set was written with an earlier subbuffer of `uniform_buffer_pool`.
+/// let subbuffer = uniform_buffer_pool.from_data([1.0, 0.0, 0.0, 1.0]).unwrap();
+///
+/// let dynamic_offsets = DynamicOffsets::new()
+///     .offset(0, 0, 0, 256)
+///     .buffer(0, 1, 0, subbuffer);
+///
+/// builder.bind_descriptor_sets_with_offsets(
+///     PipelineBindPoint::Graphics,
+///     pipeline_layout,
+///     0,
+///     [descriptor_set as _],
+///     &dynamic_offsets,
+/// );
+/// ```
+///
+/// [`bind_descriptor_sets_with_offsets`]: crate::command_buffer::AutoCommandBufferBuilder::bind_descriptor_sets_with_offsets
+#[derive(Clone, Default)]
+pub struct DynamicOffsets {
+    offsets: BTreeMap<(u32, u32, u32), DynamicOffset>,
+}
+
+impl DynamicOffsets {
+    /// Creates a new `DynamicOffsets` with no offsets.
+    #[inline]
+    pub fn new() -> Self {
+        DynamicOffsets {
+            offsets: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the dynamic offset of array element `index` of `binding` in descriptor set
+    /// `set_num`.
+    #[inline]
+    pub fn offset(mut self, set_num: u32, binding: u32, index: u32, offset: u32) -> Self {
+        self.offsets
+            .insert((set_num, binding, index), DynamicOffset::Offset(offset));
+        self
+    }
+
+    /// Sets the dynamic offset of array element `index` of `binding` in descriptor set
+    /// `set_num`, so that the descriptor points to `buffer`.
+    ///
+    /// The offset is the distance from the start of the buffer that was written to the
+    /// descriptor to the start of `buffer`, which must be within the same underlying buffer.
+    /// This fits suballocators such as [`CpuBufferPool`]: write one chunk to the descriptor set
+    /// once, then bind later chunks of the same size by passing them here.
+    ///
+    /// [`CpuBufferPool`]: crate::buffer::CpuBufferPool
+    #[inline]
+    pub fn buffer(
+        mut self,
+        set_num: u32,
+        binding: u32,
+        index: u32,
+        buffer: Arc<dyn BufferAccess>,
+    ) -> Self {
+        self.offsets
+            .insert((set_num, binding, index), DynamicOffset::Buffer(buffer));
+        self
+    }
+
+    /// Returns whether no offsets have been set.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    pub(crate) fn get(&self, set_num: u32, binding: u32, index: u32) -> Option<&DynamicOffset> {
+        self.offsets.get(&(set_num, binding, index))
+    }
+
+    pub(crate) fn keys(&self) -> impl Iterator<Item = (u32, u32, u32)> + '_ {
+        self.offsets.keys().copied()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) enum DynamicOffset {
+    Offset(u32),
+    Buffer(Arc<dyn BufferAccess>),
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum DescriptorSetCreationError {
     DescriptorSetUpdateError(DescriptorSetUpdateError),
@@ -128,6 +128,18 @@ where
     }
 }
 
+unsafe impl<P> VulkanObject for PersistentDescriptorSet<P>
+where
+    P: DescriptorSetAlloc,
+{
+    type Object = ash::vk::DescriptorSet;
+
+    #[inline]
+    fn internal_object(&self) -> ash::vk::DescriptorSet {
+        self.alloc.inner().internal_object()
+    }
+}
+
 impl<P> PartialEq for PersistentDescriptorSet<P>
 where
     P: DescriptorSetAlloc,
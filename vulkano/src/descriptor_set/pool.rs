@@ -39,6 +39,7 @@ pub struct DescriptorPool {
 
     max_sets: u32,
     pool_sizes: HashMap<DescriptorType, u32>,
+    max_inline_uniform_block_bindings: u32,
     can_free_descriptor_sets: bool,
     // Unimplement `Sync`, as Vulkan descriptor pools are not thread safe.
     _marker: PhantomData<Cell<ash::vk::DescriptorPool>>,
@@ -52,6 +53,8 @@ impl DescriptorPool {
     /// - Panics if `create_info.max_sets` is `0`.
     /// - Panics if `create_info.pool_sizes` is empty.
     /// - Panics if `create_info.pool_sizes` contains a descriptor type with a count of `0`.
+    /// - Panics if `create_info.pool_sizes` contains
+    ///   [`DescriptorType::InlineUniformBlock`] with a count that is not a multiple of 4.
     pub fn new(
         device: Arc<Device>,
         create_info: DescriptorPoolCreateInfo,
@@ -59,6 +62,7 @@ impl DescriptorPool {
         let DescriptorPoolCreateInfo {
             max_sets,
             pool_sizes,
+            max_inline_uniform_block_bindings,
             can_free_descriptor_sets,
             _ne: _,
         } = create_info;
@@ -76,6 +80,9 @@ impl DescriptorPool {
                     // VUID-VkDescriptorPoolSize-descriptorCount-00302
                     assert!(descriptor_count != 0);
 
+                    // VUID-VkDescriptorPoolSize-type-02218
+                    assert!(ty != DescriptorType::InlineUniformBlock || descriptor_count % 4 == 0);
+
                     ash::vk::DescriptorPoolSize {
                         ty: ty.into(),
                         descriptor_count,
@@ -89,7 +96,7 @@ impl DescriptorPool {
                 flags |= ash::vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET;
             }
 
-            let create_info = ash::vk::DescriptorPoolCreateInfo {
+            let mut create_info = ash::vk::DescriptorPoolCreateInfo {
                 flags,
                 max_sets,
                 pool_size_count: pool_sizes.len() as u32,
@@ -97,6 +104,22 @@ impl DescriptorPool {
                 ..Default::default()
             };
 
+            let mut inline_uniform_block_create_info = if max_inline_uniform_block_bindings != 0 {
+                Some(ash::vk::DescriptorPoolInlineUniformBlockCreateInfo {
+                    max_inline_uniform_block_bindings,
+                    ..Default::default()
+                })
+            } else {
+                None
+            };
+
+            if let Some(inline_uniform_block_create_info) =
+                inline_uniform_block_create_info.as_mut()
+            {
+                inline_uniform_block_create_info.p_next = create_info.p_next;
+                create_info.p_next = inline_uniform_block_create_info as *const _ as *const _;
+            }
+
             unsafe {
                 let fns = device.fns();
                 let mut output = MaybeUninit::uninit();
@@ -117,6 +140,7 @@ impl DescriptorPool {
             device,
             max_sets,
             pool_sizes,
+            max_inline_uniform_block_bindings,
             can_free_descriptor_sets,
             _marker: PhantomData,
         })
@@ -137,6 +161,7 @@ impl DescriptorPool {
         let DescriptorPoolCreateInfo {
             max_sets,
             pool_sizes,
+            max_inline_uniform_block_bindings,
             can_free_descriptor_sets,
             _ne: _,
         } = create_info;
@@ -146,6 +171,7 @@ impl DescriptorPool {
             device,
             max_sets,
             pool_sizes,
+            max_inline_uniform_block_bindings,
             can_free_descriptor_sets,
             _marker: PhantomData,
         }
@@ -157,6 +183,12 @@ impl DescriptorPool {
         self.max_sets
     }
 
+    /// Returns the maximum number of inline uniform block bindings that the pool was created with.
+    #[inline]
+    pub fn max_inline_uniform_block_bindings(&self) -> u32 {
+        self.max_inline_uniform_block_bindings
+    }
+
     /// Returns the number of descriptors of each type that the pool was created with.
     #[inline]
     pub fn pool_sizes(&self) -> &HashMap<DescriptorType, u32> {
@@ -385,6 +417,16 @@ pub struct DescriptorPoolCreateInfo {
     /// The default value is empty, which must be overridden.
     pub pool_sizes: HashMap<DescriptorType, u32>,
 
+    /// The maximum number of inline uniform block bindings that can be allocated from the pool,
+    /// summed over all descriptor sets.
+    ///
+    /// For the [`InlineUniformBlock`](DescriptorType::InlineUniformBlock) descriptor type,
+    /// `pool_sizes` specifies the total number of bytes of inline uniform block data instead of a
+    /// number of descriptors. This value must be nonzero if `pool_sizes` contains that type.
+    ///
+    /// The default value is `0`.
+    pub max_inline_uniform_block_bindings: u32,
+
     /// Whether individual descriptor sets can be freed from the pool. Otherwise you must reset or
     /// destroy the whole pool at once.
     ///
@@ -400,6 +442,7 @@ impl Default for DescriptorPoolCreateInfo {
         Self {
             max_sets: 0,
             pool_sizes: HashMap::default(),
+            max_inline_uniform_block_bindings: 0,
             can_free_descriptor_sets: false,
             _ne: crate::NonExhaustive(()),
         }
@@ -9,7 +9,7 @@
 
 use super::{
     allocator::DescriptorSetAlloc,
-    layout::DescriptorSetLayout,
+    layout::{DescriptorSetLayout, DescriptorType},
     pool::{
         DescriptorPool, DescriptorPoolAllocError, DescriptorPoolCreateInfo,
         DescriptorSetAllocateInfo,
@@ -140,6 +140,8 @@ impl SingleLayoutPool {
                     .iter()
                     .map(|(&ty, &count)| (ty, count * set_count as u32))
                     .collect(),
+                max_inline_uniform_block_bindings: inline_uniform_block_bindings(layout)
+                    * set_count as u32,
                 ..Default::default()
             },
         )?;
@@ -409,6 +411,8 @@ impl SingleLayoutVariablePool {
                     .iter()
                     .map(|(&ty, &count)| (ty, count * MAX_SETS as u32))
                     .collect(),
+                max_inline_uniform_block_bindings: inline_uniform_block_bindings(layout)
+                    * MAX_SETS as u32,
                 ..Default::default()
             },
         )?;
@@ -504,3 +508,16 @@ impl Hash for SingleLayoutVariableDescSet {
         self.device().hash(state);
     }
 }
+
+/// Returns the number of inline uniform block bindings in `layout`, which need to be accounted
+/// for separately in the pool.
+fn inline_uniform_block_bindings(layout: &DescriptorSetLayout) -> u32 {
+    layout
+        .bindings()
+        .values()
+        .filter(|binding| {
+            binding.descriptor_type == DescriptorType::InlineUniformBlock
+                && binding.descriptor_count != 0
+        })
+        .count() as u32
+}
@@ -10,10 +10,7 @@
 //! Low-level descriptor set.
 
 use crate::{
-    descriptor_set::{
-        layout::DescriptorSetLayout,
-        update::{DescriptorWriteInfo, WriteDescriptorSet},
-    },
+    descriptor_set::{layout::DescriptorSetLayout, update::WriteDescriptorSet},
     device::DeviceOwned,
     VulkanObject,
 };
@@ -53,7 +50,7 @@ impl UnsafeDescriptorSet {
         layout: &DescriptorSetLayout,
         writes: impl IntoIterator<Item = &'a WriteDescriptorSet>,
     ) {
-        let (infos, mut writes): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = writes
+        let (mut infos, mut writes): (SmallVec<[_; 8]>, SmallVec<[_; 8]>) = writes
             .into_iter()
             .map(|write| {
                 let descriptor_type = layout.bindings()[&write.binding()].descriptor_type;
@@ -72,21 +69,8 @@ impl UnsafeDescriptorSet {
         }
 
         // Set the info pointers separately.
-        for (info, write) in infos.iter().zip(writes.iter_mut()) {
-            match info {
-                DescriptorWriteInfo::Image(info) => {
-                    write.descriptor_count = info.len() as u32;
-                    write.p_image_info = info.as_ptr();
-                }
-                DescriptorWriteInfo::Buffer(info) => {
-                    write.descriptor_count = info.len() as u32;
-                    write.p_buffer_info = info.as_ptr();
-                }
-                DescriptorWriteInfo::BufferView(info) => {
-                    write.descriptor_count = info.len() as u32;
-                    write.p_texel_buffer_view = info.as_ptr();
-                }
-            }
+        for (info, write) in infos.iter_mut().zip(writes.iter_mut()) {
+            info.set_pointers(write);
 
             debug_assert!(write.descriptor_count != 0);
         }
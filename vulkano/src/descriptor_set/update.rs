@@ -9,11 +9,11 @@
 
 use super::layout::{DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorType};
 use crate::{
-    buffer::{view::BufferViewAbstract, BufferAccess, BufferInner},
+    buffer::{view::BufferViewAbstract, BufferAccess, BufferContents, BufferInner},
     device::DeviceOwned,
     image::{view::ImageViewType, ImageType, ImageViewAbstract},
     sampler::{Sampler, SamplerImageViewIncompatibleError},
-    DeviceSize, VulkanObject,
+    DeviceSize, RequiresOneOf, VulkanObject,
 };
 use smallvec::SmallVec;
 use std::{
@@ -55,6 +55,12 @@ impl WriteDescriptorSet {
     ///
     /// For regular descriptor sets, the data for such descriptors is automatically valid, and dummy
     /// writes are not allowed.
+    ///
+    /// For buffer, texel buffer, sampled image and storage image descriptors, and combined image
+    /// sampler descriptors with immutable samplers, this writes null descriptors. This requires
+    /// the [`null_descriptor`](crate::device::Features::null_descriptor) feature to be enabled on
+    /// the device. A shader can access a null descriptor: reads return zero and writes are
+    /// discarded.
     #[inline]
     pub fn none_array(binding: u32, first_array_element: u32, num_elements: u32) -> Self {
         assert!(num_elements != 0);
@@ -174,6 +180,28 @@ impl WriteDescriptorSet {
         }
     }
 
+    /// Write data into an inline uniform block, starting at byte `offset`.
+    ///
+    /// The binding must have the
+    /// [`InlineUniformBlock`](crate::descriptor_set::layout::DescriptorType::InlineUniformBlock)
+    /// descriptor type. `offset` and the size of `data` must both be multiples of 4.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `data` is empty.
+    pub fn inline_uniform_block<D>(binding: u32, offset: u32, data: &D) -> Self
+    where
+        D: BufferContents + ?Sized,
+    {
+        let data = data.as_bytes().to_vec();
+        assert!(!data.is_empty());
+        Self {
+            binding,
+            first_array_element: offset,
+            elements: WriteDescriptorSetElements::InlineUniformBlock(data),
+        }
+    }
+
     /// Returns the binding number that is updated by this descriptor write.
     #[inline]
     pub fn binding(&self) -> u32 {
@@ -194,18 +222,45 @@ impl WriteDescriptorSet {
 
     pub(crate) fn to_vulkan_info(&self, descriptor_type: DescriptorType) -> DescriptorWriteInfo {
         match &self.elements {
-            WriteDescriptorSetElements::None(num_elements) => {
-                debug_assert!(matches!(descriptor_type, DescriptorType::Sampler));
-                DescriptorWriteInfo::Image(
-                    std::iter::repeat_with(|| ash::vk::DescriptorImageInfo {
-                        sampler: ash::vk::Sampler::null(),
-                        image_view: ash::vk::ImageView::null(),
-                        image_layout: ash::vk::ImageLayout::UNDEFINED,
+            WriteDescriptorSetElements::None(num_elements) => match descriptor_type {
+                DescriptorType::UniformBuffer
+                | DescriptorType::StorageBuffer
+                | DescriptorType::UniformBufferDynamic
+                | DescriptorType::StorageBufferDynamic => DescriptorWriteInfo::Buffer(
+                    std::iter::repeat_with(|| ash::vk::DescriptorBufferInfo {
+                        buffer: ash::vk::Buffer::null(),
+                        offset: 0,
+                        range: ash::vk::WHOLE_SIZE,
                     })
                     .take(*num_elements as usize)
                     .collect(),
-                )
-            }
+                ),
+                DescriptorType::UniformTexelBuffer | DescriptorType::StorageTexelBuffer => {
+                    DescriptorWriteInfo::BufferView(
+                        std::iter::repeat(ash::vk::BufferView::null())
+                            .take(*num_elements as usize)
+                            .collect(),
+                    )
+                }
+                _ => {
+                    debug_assert!(matches!(
+                        descriptor_type,
+                        DescriptorType::Sampler
+                            | DescriptorType::CombinedImageSampler
+                            | DescriptorType::SampledImage
+                            | DescriptorType::StorageImage
+                    ));
+                    DescriptorWriteInfo::Image(
+                        std::iter::repeat_with(|| ash::vk::DescriptorImageInfo {
+                            sampler: ash::vk::Sampler::null(),
+                            image_view: ash::vk::ImageView::null(),
+                            image_layout: ash::vk::ImageLayout::UNDEFINED,
+                        })
+                        .take(*num_elements as usize)
+                        .collect(),
+                    )
+                }
+            },
             WriteDescriptorSetElements::Buffer(elements) => {
                 debug_assert!(matches!(
                     descriptor_type,
@@ -305,6 +360,16 @@ impl WriteDescriptorSet {
                         .collect(),
                 )
             }
+            WriteDescriptorSetElements::InlineUniformBlock(data) => {
+                debug_assert!(matches!(
+                    descriptor_type,
+                    DescriptorType::InlineUniformBlock
+                ));
+                DescriptorWriteInfo::InlineUniformBlock(
+                    data.clone(),
+                    ash::vk::WriteDescriptorSetInlineUniformBlock::default(),
+                )
+            }
             WriteDescriptorSetElements::Sampler(elements) => {
                 debug_assert!(matches!(descriptor_type, DescriptorType::Sampler));
                 DescriptorWriteInfo::Image(
@@ -348,10 +413,11 @@ pub enum WriteDescriptorSetElements {
     ImageView(SmallVec<[Arc<dyn ImageViewAbstract>; 1]>),
     ImageViewSampler(SmallVec<[(Arc<dyn ImageViewAbstract>, Arc<Sampler>); 1]>),
     Sampler(SmallVec<[Arc<Sampler>; 1]>),
+    InlineUniformBlock(Vec<u8>),
 }
 
 impl WriteDescriptorSetElements {
-    /// Returns the number of elements.
+    /// Returns the number of elements. For `InlineUniformBlock`, this is the number of bytes.
     #[inline]
     pub fn len(&self) -> u32 {
         match self {
@@ -361,6 +427,7 @@ impl WriteDescriptorSetElements {
             Self::ImageView(elements) => elements.len() as u32,
             Self::ImageViewSampler(elements) => elements.len() as u32,
             Self::Sampler(elements) => elements.len() as u32,
+            Self::InlineUniformBlock(data) => data.len() as u32,
         }
     }
 }
@@ -370,6 +437,35 @@ pub(crate) enum DescriptorWriteInfo {
     Image(SmallVec<[ash::vk::DescriptorImageInfo; 1]>),
     Buffer(SmallVec<[ash::vk::DescriptorBufferInfo; 1]>),
     BufferView(SmallVec<[ash::vk::BufferView; 1]>),
+    // The `p_data` pointer of the Vulkan struct is filled in from the data, once the info is at
+    // its final location.
+    InlineUniformBlock(Vec<u8>, ash::vk::WriteDescriptorSetInlineUniformBlock),
+}
+
+impl DescriptorWriteInfo {
+    /// Sets the info pointers of `write` to point into `self`.
+    pub(crate) fn set_pointers(&mut self, write: &mut ash::vk::WriteDescriptorSet) {
+        match self {
+            DescriptorWriteInfo::Image(info) => {
+                write.descriptor_count = info.len() as u32;
+                write.p_image_info = info.as_ptr();
+            }
+            DescriptorWriteInfo::Buffer(info) => {
+                write.descriptor_count = info.len() as u32;
+                write.p_buffer_info = info.as_ptr();
+            }
+            DescriptorWriteInfo::BufferView(info) => {
+                write.descriptor_count = info.len() as u32;
+                write.p_texel_buffer_view = info.as_ptr();
+            }
+            DescriptorWriteInfo::InlineUniformBlock(data, info) => {
+                info.data_size = data.len() as u32;
+                info.p_data = data.as_ptr() as *const _;
+                write.descriptor_count = data.len() as u32;
+                write.p_next = info as *const _ as *const _;
+            }
+        }
+    }
 }
 
 pub(crate) fn check_descriptor_write<'a>(
@@ -411,6 +507,19 @@ pub(crate) fn check_descriptor_write<'a>(
         WriteDescriptorSetElements::None(_num_elements) => match layout_binding.descriptor_type {
             DescriptorType::Sampler
                 if layout.push_descriptor() && !layout_binding.immutable_samplers.is_empty() => {}
+            DescriptorType::UniformBuffer
+            | DescriptorType::StorageBuffer
+            | DescriptorType::UniformBufferDynamic
+            | DescriptorType::StorageBufferDynamic
+            | DescriptorType::UniformTexelBuffer
+            | DescriptorType::StorageTexelBuffer
+            | DescriptorType::SampledImage
+            | DescriptorType::StorageImage => check_null_descriptor(write, layout)?,
+            DescriptorType::CombinedImageSampler
+                if !layout_binding.immutable_samplers.is_empty() =>
+            {
+                check_null_descriptor(write, layout)?
+            }
             _ => {
                 return Err(DescriptorSetUpdateError::IncompatibleDescriptorType {
                     binding: write.binding(),
@@ -418,6 +527,8 @@ pub(crate) fn check_descriptor_write<'a>(
             }
         },
         WriteDescriptorSetElements::Buffer(elements) => {
+            let properties = layout.device().physical_device().properties();
+
             match layout_binding.descriptor_type {
                 DescriptorType::StorageBuffer | DescriptorType::StorageBufferDynamic => {
                     for (index, buffer) in elements.iter().enumerate() {
@@ -433,6 +544,19 @@ pub(crate) fn check_descriptor_write<'a>(
                                 usage: "storage_buffer",
                             });
                         }
+
+                        // VUID-VkWriteDescriptorSet-descriptorType-00328
+                        let offset = buffer.inner().offset;
+                        let required_alignment = properties.min_storage_buffer_offset_alignment;
+
+                        if offset % required_alignment != 0 {
+                            return Err(DescriptorSetUpdateError::BufferOffsetNotAligned {
+                                binding: write.binding(),
+                                index: descriptor_range_start + index as u32,
+                                offset,
+                                required_alignment,
+                            });
+                        }
                     }
                 }
                 DescriptorType::UniformBuffer | DescriptorType::UniformBufferDynamic => {
@@ -449,6 +573,19 @@ pub(crate) fn check_descriptor_write<'a>(
                                 usage: "uniform_buffer",
                             });
                         }
+
+                        // VUID-VkWriteDescriptorSet-descriptorType-00327
+                        let offset = buffer.inner().offset;
+                        let required_alignment = properties.min_uniform_buffer_offset_alignment;
+
+                        if offset % required_alignment != 0 {
+                            return Err(DescriptorSetUpdateError::BufferOffsetNotAligned {
+                                binding: write.binding(),
+                                index: descriptor_range_start + index as u32,
+                                offset,
+                                required_alignment,
+                            });
+                        }
                     }
                 }
                 _ => {
@@ -458,6 +595,19 @@ pub(crate) fn check_descriptor_write<'a>(
                 }
             }
 
+            if let Some(required_size) = layout_binding.buffer_block_size {
+                for (index, buffer) in elements.iter().enumerate() {
+                    if buffer.size() < required_size {
+                        return Err(DescriptorSetUpdateError::BufferTooSmall {
+                            binding: write.binding(),
+                            index: descriptor_range_start + index as u32,
+                            required_size,
+                            provided_size: buffer.size(),
+                        });
+                    }
+                }
+            }
+
             // Note that the buffer content is not checked. This is technically not unsafe as
             // long as the data in the buffer has no invalid memory representation (ie. no
             // bool, no enum, no pointer, no str) and as long as the robust buffer access
@@ -547,18 +697,12 @@ pub(crate) fn check_descriptor_write<'a>(
                         });
                     }
 
-                    // VUID-VkDescriptorImageInfo-imageView-00343
-                    if matches!(
-                        image_view.view_type(),
-                        ImageViewType::Dim2d | ImageViewType::Dim2dArray
-                    ) && image_view.image().inner().image.dimensions().image_type()
-                        == ImageType::Dim3d
-                    {
-                        return Err(DescriptorSetUpdateError::ImageView2dFrom3d {
-                            binding: write.binding(),
-                            index: descriptor_range_start + index as u32,
-                        });
-                    }
+                    check_image_view_2d_from_3d(
+                        write,
+                        layout_binding.descriptor_type,
+                        image_view.as_ref(),
+                        descriptor_range_start + index as u32,
+                    )?;
 
                     // VUID-VkDescriptorImageInfo-imageView-01976
                     if image_view.subresource_range().aspects.depth
@@ -595,18 +739,12 @@ pub(crate) fn check_descriptor_write<'a>(
                         });
                     }
 
-                    // VUID-VkDescriptorImageInfo-imageView-00343
-                    if matches!(
-                        image_view.view_type(),
-                        ImageViewType::Dim2d | ImageViewType::Dim2dArray
-                    ) && image_view.image().inner().image.dimensions().image_type()
-                        == ImageType::Dim3d
-                    {
-                        return Err(DescriptorSetUpdateError::ImageView2dFrom3d {
-                            binding: write.binding(),
-                            index: descriptor_range_start + index as u32,
-                        });
-                    }
+                    check_image_view_2d_from_3d(
+                        write,
+                        layout_binding.descriptor_type,
+                        image_view.as_ref(),
+                        descriptor_range_start + index as u32,
+                    )?;
 
                     // VUID-VkDescriptorImageInfo-imageView-01976
                     if image_view.subresource_range().aspects.depth
@@ -645,18 +783,12 @@ pub(crate) fn check_descriptor_write<'a>(
                         });
                     }
 
-                    // VUID-VkDescriptorImageInfo-imageView-00343
-                    if matches!(
-                        image_view.view_type(),
-                        ImageViewType::Dim2d | ImageViewType::Dim2dArray
-                    ) && image_view.image().inner().image.dimensions().image_type()
-                        == ImageType::Dim3d
-                    {
-                        return Err(DescriptorSetUpdateError::ImageView2dFrom3d {
-                            binding: write.binding(),
-                            index: descriptor_range_start + index as u32,
-                        });
-                    }
+                    check_image_view_2d_from_3d(
+                        write,
+                        layout_binding.descriptor_type,
+                        image_view.as_ref(),
+                        descriptor_range_start + index as u32,
+                    )?;
 
                     // VUID-VkDescriptorImageInfo-imageView-01976
                     if image_view.subresource_range().aspects.depth
@@ -703,18 +835,12 @@ pub(crate) fn check_descriptor_write<'a>(
                         });
                     }
 
-                    // VUID-VkDescriptorImageInfo-imageView-00343
-                    if matches!(
-                        image_view.view_type(),
-                        ImageViewType::Dim2d | ImageViewType::Dim2dArray
-                    ) && image_view.image().inner().image.dimensions().image_type()
-                        == ImageType::Dim3d
-                    {
-                        return Err(DescriptorSetUpdateError::ImageView2dFrom3d {
-                            binding: write.binding(),
-                            index: descriptor_range_start + index as u32,
-                        });
-                    }
+                    check_image_view_2d_from_3d(
+                        write,
+                        layout_binding.descriptor_type,
+                        image_view.as_ref(),
+                        descriptor_range_start + index as u32,
+                    )?;
 
                     // VUID-VkDescriptorImageInfo-imageView-01976
                     if image_view.subresource_range().aspects.depth
@@ -788,18 +914,12 @@ pub(crate) fn check_descriptor_write<'a>(
                         });
                     }
 
-                    // VUID-VkDescriptorImageInfo-imageView-00343
-                    if matches!(
-                        image_view.view_type(),
-                        ImageViewType::Dim2d | ImageViewType::Dim2dArray
-                    ) && image_view.image().inner().image.dimensions().image_type()
-                        == ImageType::Dim3d
-                    {
-                        return Err(DescriptorSetUpdateError::ImageView2dFrom3d {
-                            binding: write.binding(),
-                            index: descriptor_range_start + index as u32,
-                        });
-                    }
+                    check_image_view_2d_from_3d(
+                        write,
+                        layout_binding.descriptor_type,
+                        image_view.as_ref(),
+                        descriptor_range_start + index as u32,
+                    )?;
 
                     // VUID-VkDescriptorImageInfo-imageView-01976
                     if image_view.subresource_range().aspects.depth
@@ -870,13 +990,134 @@ pub(crate) fn check_descriptor_write<'a>(
                 })
             }
         },
+        WriteDescriptorSetElements::InlineUniformBlock(data) => {
+            match layout_binding.descriptor_type {
+                DescriptorType::InlineUniformBlock => {
+                    // VUID-VkWriteDescriptorSet-descriptorType-02219
+                    // VUID-VkWriteDescriptorSet-descriptorType-02220
+                    if descriptor_range_start % 4 != 0 || data.len() % 4 != 0 {
+                        return Err(DescriptorSetUpdateError::InlineUniformBlockNotAligned {
+                            binding: write.binding(),
+                            offset: descriptor_range_start,
+                            size: data.len() as u32,
+                        });
+                    }
+                }
+                _ => {
+                    return Err(DescriptorSetUpdateError::IncompatibleDescriptorType {
+                        binding: write.binding(),
+                    })
+                }
+            }
+        }
     }
 
     Ok(layout_binding)
 }
 
+fn check_image_view_2d_from_3d(
+    write: &WriteDescriptorSet,
+    descriptor_type: DescriptorType,
+    image_view: &dyn ImageViewAbstract,
+    index: u32,
+) -> Result<(), DescriptorSetUpdateError> {
+    let image = image_view.image();
+    let image = image.inner().image;
+
+    if image.dimensions().image_type() != ImageType::Dim3d {
+        return Ok(());
+    }
+
+    match image_view.view_type() {
+        ImageViewType::Dim2d => {
+            // VUID-VkDescriptorImageInfo-imageView-06711
+            if !image.view_2d_compatible() {
+                return Err(DescriptorSetUpdateError::ImageView2dFrom3d {
+                    binding: write.binding(),
+                    index,
+                });
+            }
+
+            match descriptor_type {
+                DescriptorType::StorageImage => {
+                    // VUID-VkDescriptorImageInfo-descriptorType-06713
+                    if !image.device().enabled_features().image2_d_view_of3_d {
+                        return Err(DescriptorSetUpdateError::RequirementNotMet {
+                            binding: write.binding(),
+                            required_for: "a 2D image view of a 3D image is written to a \
+                                `DescriptorType::StorageImage` binding",
+                            requires_one_of: RequiresOneOf {
+                                features: &["image2_d_view_of3_d"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+                }
+                DescriptorType::SampledImage | DescriptorType::CombinedImageSampler => {
+                    // VUID-VkDescriptorImageInfo-descriptorType-06714
+                    if !image.device().enabled_features().sampler2_d_view_of3_d {
+                        return Err(DescriptorSetUpdateError::RequirementNotMet {
+                            binding: write.binding(),
+                            required_for: "a 2D image view of a 3D image is written to a \
+                                `DescriptorType::SampledImage` or \
+                                `DescriptorType::CombinedImageSampler` binding",
+                            requires_one_of: RequiresOneOf {
+                                features: &["sampler2_d_view_of3_d"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+                }
+                _ => {
+                    return Err(DescriptorSetUpdateError::ImageView2dFrom3d {
+                        binding: write.binding(),
+                        index,
+                    })
+                }
+            }
+        }
+        // VUID-VkDescriptorImageInfo-imageView-06712
+        ImageViewType::Dim2dArray => {
+            return Err(DescriptorSetUpdateError::ImageView2dFrom3d {
+                binding: write.binding(),
+                index,
+            })
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+fn check_null_descriptor(
+    write: &WriteDescriptorSet,
+    layout: &DescriptorSetLayout,
+) -> Result<(), DescriptorSetUpdateError> {
+    // VUID-VkWriteDescriptorSet-descriptorType-02997
+    // VUID-VkWriteDescriptorSet-descriptorType-02998
+    // VUID-VkDescriptorBufferInfo-buffer-02998
+    if !layout.device().enabled_features().null_descriptor {
+        return Err(DescriptorSetUpdateError::RequirementNotMet {
+            binding: write.binding(),
+            required_for: "`write.elements()` is `WriteDescriptorSetElements::None`",
+            requires_one_of: RequiresOneOf {
+                features: &["null_descriptor"],
+                ..Default::default()
+            },
+        });
+    }
+
+    Ok(())
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum DescriptorSetUpdateError {
+    RequirementNotMet {
+        binding: u32,
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
     /// Tried to write more elements than were available in a binding.
     ArrayIndexOutOfBounds {
         /// Binding that is affected.
@@ -887,7 +1128,29 @@ pub enum DescriptorSetUpdateError {
         written_count: u32,
     },
 
-    /// Tried to write an image view with a 2D type and a 3D underlying image.
+    /// Tried to write a buffer whose offset is not a multiple of the
+    /// [`min_uniform_buffer_offset_alignment`] or [`min_storage_buffer_offset_alignment`] limit.
+    ///
+    /// [`min_uniform_buffer_offset_alignment`]: crate::device::Properties::min_uniform_buffer_offset_alignment
+    /// [`min_storage_buffer_offset_alignment`]: crate::device::Properties::min_storage_buffer_offset_alignment
+    BufferOffsetNotAligned {
+        binding: u32,
+        index: u32,
+        offset: DeviceSize,
+        required_alignment: DeviceSize,
+    },
+
+    /// Tried to write a buffer that is smaller than the block that the descriptor set layout
+    /// requires.
+    BufferTooSmall {
+        binding: u32,
+        index: u32,
+        required_size: DeviceSize,
+        provided_size: DeviceSize,
+    },
+
+    /// Tried to write an image view with a 2D type and a 3D underlying image, to a binding or
+    /// from an image that does not allow it.
     ImageView2dFrom3d { binding: u32, index: u32 },
 
     /// Tried to write an image view that has both the `depth` and `stencil` aspects.
@@ -917,6 +1180,14 @@ pub enum DescriptorSetUpdateError {
     /// layout.
     IncompatibleDescriptorType { binding: u32 },
 
+    /// Tried to write data to an inline uniform block with an offset or size that is not a
+    /// multiple of 4.
+    InlineUniformBlockNotAligned {
+        binding: u32,
+        offset: u32,
+        size: u32,
+    },
+
     /// Tried to write to a nonexistent binding.
     InvalidBinding { binding: u32 },
 
@@ -946,6 +1217,15 @@ impl Error for DescriptorSetUpdateError {
 impl Display for DescriptorSetUpdateError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         match self {
+            Self::RequirementNotMet {
+                binding,
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for binding {}: {}; requires one of: {}",
+                binding, required_for, requires_one_of,
+            ),
             Self::ArrayIndexOutOfBounds {
                 binding,
                 available_count,
@@ -956,6 +1236,28 @@ impl Display for DescriptorSetUpdateError {
                 available",
                 written_count, binding, available_count,
             ),
+            Self::BufferOffsetNotAligned {
+                binding,
+                index,
+                offset,
+                required_alignment,
+            } => write!(
+                f,
+                "tried to write a buffer to binding {} index {} with an offset of {}, but the \
+                offset must be a multiple of {}",
+                binding, index, offset, required_alignment,
+            ),
+            Self::BufferTooSmall {
+                binding,
+                index,
+                required_size,
+                provided_size,
+            } => write!(
+                f,
+                "tried to write a buffer of {} bytes to binding {} index {}, but the shader \
+                requires at least {} bytes",
+                provided_size, binding, index, required_size,
+            ),
             Self::ImageView2dFrom3d { binding, index } => write!(
                 f,
                 "tried to write an image view to binding {} index {} with a 2D type and a 3D \
@@ -998,6 +1300,16 @@ impl Display for DescriptorSetUpdateError {
                 descriptor type",
                 binding,
             ),
+            Self::InlineUniformBlockNotAligned {
+                binding,
+                offset,
+                size,
+            } => write!(
+                f,
+                "tried to write {} bytes at offset {} to the inline uniform block of binding {}, \
+                but the offset and size must be multiples of 4",
+                size, offset, binding,
+            ),
             Self::InvalidBinding { binding } => {
                 write!(f, "tried to write to a nonexistent binding {}", binding,)
             }
@@ -1026,3 +1338,211 @@ impl Display for DescriptorSetUpdateError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DescriptorSetUpdateError;
+    use crate::{
+        buffer::{BufferUsage, CpuAccessibleBuffer},
+        descriptor_set::{
+            allocator::StandardDescriptorSetAllocator,
+            layout::{
+                DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo,
+                DescriptorType,
+            },
+            DescriptorSetCreationError, PersistentDescriptorSet, WriteDescriptorSet,
+        },
+        device::{Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo},
+        format::Format,
+        image::{view::ImageView, ImageCreateFlags, ImageDimensions, ImageUsage, StorageImage},
+        shader::ShaderStages,
+        Version,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn buffer_too_small() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let layout = DescriptorSetLayout::new(
+            device.clone(),
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        stages: ShaderStages::all(),
+                        buffer_block_size: Some(64),
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::UniformBuffer)
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let buffer = CpuAccessibleBuffer::from_iter(
+            device.clone(),
+            BufferUsage {
+                uniform_buffer: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            [0u32; 4],
+        )
+        .unwrap();
+
+        let ds_allocator = StandardDescriptorSetAllocator::new(device);
+
+        match PersistentDescriptorSet::new(
+            &ds_allocator,
+            layout,
+            [WriteDescriptorSet::buffer(0, buffer)],
+        ) {
+            Err(DescriptorSetCreationError::DescriptorSetUpdateError(
+                DescriptorSetUpdateError::BufferTooSmall {
+                    binding: 0,
+                    index: 0,
+                    required_size: 64,
+                    provided_size: 16,
+                },
+            )) => (),
+            _ => panic!(),
+        }
+    }
+
+    fn storage_image_layout(device: Arc<Device>) -> Arc<DescriptorSetLayout> {
+        DescriptorSetLayout::new(
+            device,
+            DescriptorSetLayoutCreateInfo {
+                bindings: [(
+                    0,
+                    DescriptorSetLayoutBinding {
+                        stages: ShaderStages::all(),
+                        ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::StorageImage)
+                    },
+                )]
+                .into(),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn image_view_2d_from_3d_not_view_2d_compatible() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let layout = storage_image_layout(device.clone());
+        let image = StorageImage::with_usage(
+            device.clone(),
+            ImageDimensions::Dim3d {
+                width: 32,
+                height: 32,
+                depth: 4,
+            },
+            Format::R8G8B8A8_UNORM,
+            ImageUsage {
+                storage: true,
+                ..ImageUsage::empty()
+            },
+            ImageCreateFlags {
+                array_2d_compatible: true,
+                ..ImageCreateFlags::empty()
+            },
+            Some(queue.queue_family_index()),
+        )
+        .unwrap();
+        let view = ImageView::new_2d_slice_of_3d(image, 1).unwrap();
+
+        let ds_allocator = StandardDescriptorSetAllocator::new(device);
+
+        match PersistentDescriptorSet::new(
+            &ds_allocator,
+            layout,
+            [WriteDescriptorSet::image_view(0, view)],
+        ) {
+            Err(DescriptorSetCreationError::DescriptorSetUpdateError(
+                DescriptorSetUpdateError::ImageView2dFrom3d {
+                    binding: 0,
+                    index: 0,
+                },
+            )) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn image_view_2d_from_3d_feature_not_enabled() {
+        let instance = instance!();
+
+        let enabled_extensions = DeviceExtensions {
+            ext_image_2d_view_of_3d: true,
+            ..DeviceExtensions::empty()
+        };
+        let (physical_device, queue_family_index) = match instance
+            .enumerate_physical_devices()
+            .unwrap()
+            .filter(|p| {
+                p.api_version() >= Version::V1_1
+                    && p.supported_extensions().contains(&enabled_extensions)
+            })
+            .find_map(|p| {
+                p.queue_family_properties()
+                    .iter()
+                    .position(|q| q.queue_flags.graphics)
+                    .map(|i| (p, i as u32))
+            }) {
+            Some(x) => x,
+            None => return, // test ignored
+        };
+
+        let (device, _) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    ..Default::default()
+                }],
+                enabled_extensions,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let layout = storage_image_layout(device.clone());
+        let image = StorageImage::with_usage(
+            device.clone(),
+            ImageDimensions::Dim3d {
+                width: 32,
+                height: 32,
+                depth: 4,
+            },
+            Format::R8G8B8A8_UNORM,
+            ImageUsage {
+                storage: true,
+                ..ImageUsage::empty()
+            },
+            ImageCreateFlags {
+                view_2d_compatible: true,
+                ..ImageCreateFlags::empty()
+            },
+            Some(queue_family_index),
+        )
+        .unwrap();
+        let view = ImageView::new_2d_slice_of_3d(image, 1).unwrap();
+
+        let ds_allocator = StandardDescriptorSetAllocator::new(device);
+
+        match PersistentDescriptorSet::new(
+            &ds_allocator,
+            layout,
+            [WriteDescriptorSet::image_view(0, view)],
+        ) {
+            Err(DescriptorSetCreationError::DescriptorSetUpdateError(
+                DescriptorSetUpdateError::RequirementNotMet { binding: 0, .. },
+            )) => (),
+            _ => panic!(),
+        }
+    }
+}
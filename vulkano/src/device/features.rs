@@ -7,6 +7,7 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use crate::shader::{spirv_capability_features, EntryPoint};
 use std::{
     error::Error,
     fmt::{Display, Error as FmtError, Formatter},
@@ -15,6 +16,28 @@ use std::{
 // Generated by build.rs
 include!(concat!(env!("OUT_DIR"), "/features.rs"));
 
+impl Features {
+    /// Returns the features that must be enabled on a device in order to use the SPIR-V
+    /// capabilities of the shader module that `entry_point` belongs to.
+    ///
+    /// Capabilities that are provided by a Vulkan version, an extension or a device property are
+    /// not included. If a capability can be enabled by any one of several features, only the
+    /// first of them is included.
+    ///
+    /// To find out which of these features a physical device is missing, take the
+    /// [`difference`](Self::difference) with its
+    /// [`supported_features`](crate::device::physical::PhysicalDevice::supported_features).
+    pub fn from_shader_requirements(entry_point: &EntryPoint<'_>) -> Features {
+        entry_point
+            .module()
+            .spirv_capabilities()
+            .iter()
+            .fold(Features::empty(), |features, &capability| {
+                features.union(&spirv_capability_features(capability))
+            })
+    }
+}
+
 /// An error that can happen when enabling a feature on a device.
 #[derive(Clone, Copy, Debug)]
 pub struct FeatureRestrictionError {
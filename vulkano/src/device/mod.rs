@@ -101,12 +101,15 @@
 //!
 //! TODO: write
 
-use self::physical::PhysicalDevice;
+use self::physical::{PhysicalDevice, TimeDomain};
 pub(crate) use self::{features::FeaturesFfi, properties::PropertiesFfi};
 pub use self::{
     features::{FeatureRestriction, FeatureRestrictionError, Features},
     properties::Properties,
-    queue::{Queue, QueueError, QueueFamilyProperties, QueueFlags, QueueGuard},
+    queue::{
+        Queue, QueueCreateFlags, QueueDebugUtilsLabelScope, QueueError, QueueFamilyProperties,
+        QueueFlags, QueueGlobalPriority, QueueGuard,
+    },
 };
 pub use crate::{
     device::extensions::DeviceExtensions,
@@ -114,14 +117,25 @@ pub use crate::{
     fns::DeviceFunctions,
 };
 use crate::{
+    format::{Format, FormatFeatures},
     instance::Instance,
-    memory::{pool::StandardMemoryPool, ExternalMemoryHandleType},
-    OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
+    memory::{
+        pool::StandardMemoryPool, ExternalMemoryHandleType, MemoryBudgetLimit, MemoryTypeStatistics,
+    },
+    sampler::{
+        ycbcr::{ChromaLocation, SamplerYcbcrModelConversion, SamplerYcbcrRange},
+        ComponentMapping, ComponentSwizzle,
+    },
+    sync::Fence,
+    DeviceSize, OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
+#[cfg(feature = "tracing")]
+use ahash::HashMap;
 use ash::vk::Handle;
 use parking_lot::Mutex;
-use smallvec::SmallVec;
+use smallvec::{smallvec, SmallVec};
 use std::{
+    collections::VecDeque,
     error::Error,
     ffi::CString,
     fmt::{Display, Error as FmtError, Formatter},
@@ -134,6 +148,7 @@ use std::{
         atomic::{AtomicU32, Ordering},
         Arc, Weak,
     },
+    time::{Duration, Instant},
 };
 
 pub(crate) mod extensions;
@@ -141,12 +156,14 @@ pub(crate) mod features;
 pub mod physical;
 pub(crate) mod properties;
 mod queue;
+pub mod selection;
 
 /// Represents a Vulkan context.
 #[derive(Debug)]
 pub struct Device {
     handle: ash::vk::Device,
     physical_device: Arc<PhysicalDevice>,
+    physical_devices: SmallVec<[Arc<PhysicalDevice>; 2]>,
 
     // The highest version that is supported for this device.
     // This is the minimum of Instance::max_api_version and PhysicalDevice::api_version.
@@ -155,14 +172,28 @@ pub struct Device {
     fns: DeviceFunctions,
     standard_memory_pool: Mutex<Weak<StandardMemoryPool>>,
     enabled_extensions: DeviceExtensions,
+    implicitly_enabled_extensions: DeviceExtensions,
+    promoted_extensions: DeviceExtensions,
     enabled_features: Features,
     active_queue_family_indices: SmallVec<[u32; 2]>,
     // This is required for validation in `memory::device_memory`, the count must only be modified
     // in that module.
     pub(crate) allocation_count: AtomicU32,
+    // This is required for validation in `sampler`, the count must only be modified in that
+    // module.
+    pub(crate) custom_border_color_sampler_count: AtomicU32,
+    // Reported by `memory_statistics`, and used as the heap usage for the budget limit if
+    // `ext_memory_budget` is not enabled. The statistics must only be modified in
+    // `memory::device_memory`.
+    pub(crate) memory_statistics: Mutex<Vec<MemoryTypeStatistics>>,
+    memory_budget_limit: Mutex<Option<MemoryBudgetLimit>>,
     fence_pool: Mutex<Vec<ash::vk::Fence>>,
     semaphore_pool: Mutex<Vec<ash::vk::Semaphore>>,
     event_pool: Mutex<Vec<ash::vk::Event>>,
+    outstanding_fences: Mutex<OutstandingFences>,
+    // Debug names of the objects that are traced, so that they can be included in trace events.
+    #[cfg(feature = "tracing")]
+    traced_object_names: Mutex<HashMap<(ash::vk::ObjectType, u64), String>>,
 }
 
 impl Device {
@@ -175,8 +206,6 @@ impl Device {
     ///   physical device.
     /// - Panics if `create_info.queues` contains multiple elements for the same queue family.
     /// - Panics if `create_info.queues` contains an element where `queues` is empty.
-    /// - Panics if `create_info.queues` contains an element where `queues` contains a value that is
-    ///   not between 0.0 and 1.0 inclusive.
     pub fn new(
         physical_device: Arc<PhysicalDevice>,
         create_info: DeviceCreateInfo,
@@ -185,6 +214,7 @@ impl Device {
             mut enabled_extensions,
             mut enabled_features,
             queue_create_infos,
+            mut physical_devices,
             _ne: _,
         } = create_info;
 
@@ -192,11 +222,42 @@ impl Device {
         let fns_i = instance.fns();
         let api_version = physical_device.api_version();
 
+        /*
+            Device group
+        */
+
+        if physical_devices.is_empty() {
+            physical_devices = smallvec![physical_device.clone()];
+        }
+
+        // VUID-VkDeviceGroupDeviceCreateInfo-pPhysicalDevices-00375
+        // VUID-VkDeviceGroupDeviceCreateInfo-physicalDeviceCount-00377
+        if !physical_devices.contains(&physical_device)
+            || physical_devices
+                .iter()
+                .enumerate()
+                .any(|(index, pd)| physical_devices[..index].contains(pd))
+        {
+            return Err(DeviceCreationError::PhysicalDevicesNotInGroup);
+        }
+
+        if physical_devices.len() > 1 {
+            // VUID-VkDeviceGroupDeviceCreateInfo-pPhysicalDevices-00376
+            if !instance.enumerate_physical_device_groups()?.any(|group| {
+                physical_devices
+                    .iter()
+                    .all(|pd| group.physical_devices.contains(pd))
+            }) {
+                return Err(DeviceCreationError::PhysicalDevicesNotInGroup);
+            }
+        }
+
         /*
             Queues
         */
 
         struct QueueToGet {
+            flags: QueueCreateFlags,
             queue_family_index: u32,
             id: u32,
         }
@@ -206,22 +267,50 @@ impl Device {
 
         let mut queue_create_infos_vk: SmallVec<[_; 2]> =
             SmallVec::with_capacity(queue_create_infos.len());
+        let mut global_priority_infos_vk: SmallVec<[_; 2]> =
+            SmallVec::with_capacity(queue_create_infos.len());
         let mut active_queue_family_indices: SmallVec<[_; 2]> =
             SmallVec::with_capacity(queue_create_infos.len());
         let mut queues_to_get: SmallVec<[_; 2]> = SmallVec::with_capacity(queue_create_infos.len());
 
         for queue_create_info in &queue_create_infos {
             let &QueueCreateInfo {
+                flags,
                 queue_family_index,
                 ref queues,
+                global_priority,
                 _ne: _,
             } = queue_create_info;
 
+            // VUID-VkDeviceQueueCreateInfo-flags-parameter
+            flags.validate_physical_device(&physical_device)?;
+
             // VUID-VkDeviceQueueCreateInfo-queueFamilyIndex-00381
             // TODO: return error instead of panicking?
             let queue_family_properties =
                 &physical_device.queue_family_properties()[queue_family_index as usize];
 
+            if flags.protected {
+                // VUID-VkDeviceQueueCreateInfo-flags-02861
+                if !enabled_features.protected_memory {
+                    return Err(DeviceCreationError::RequirementNotMet {
+                        required_for: "`create_info.queue_create_infos` has an element where \
+                            `flags.protected` is set",
+                        requires_one_of: RequiresOneOf {
+                            features: &["protected_memory"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkDeviceQueueCreateInfo-flags-06449
+                if !queue_family_properties.queue_flags.protected {
+                    return Err(DeviceCreationError::QueueFamilyProtectedNotSupported {
+                        queue_family_index,
+                    });
+                }
+            }
+
             // VUID-VkDeviceCreateInfo-queueFamilyIndex-02802
             assert!(
                 queue_create_infos
@@ -235,16 +324,44 @@ impl Device {
             assert!(!queues.is_empty());
 
             // VUID-VkDeviceQueueCreateInfo-pQueuePriorities-00383
-            assert!(queues
+            if !queues
                 .iter()
-                .all(|&priority| (0.0..=1.0).contains(&priority)));
+                .all(|&priority| (0.0..=1.0).contains(&priority))
+            {
+                return Err(DeviceCreationError::PriorityOutOfRange);
+            }
 
             if queues.len() > queue_family_properties.queue_count as usize {
                 return Err(DeviceCreationError::TooManyQueuesForFamily);
             }
 
+            if let Some(global_priority) = global_priority {
+                if !(enabled_extensions.khr_global_priority
+                    || enabled_extensions.ext_global_priority)
+                {
+                    return Err(DeviceCreationError::RequirementNotMet {
+                        required_for: "`create_info.queue_create_infos` has an element where \
+                            `global_priority` is `Some`",
+                        requires_one_of: RequiresOneOf {
+                            device_extensions: &["khr_global_priority", "ext_global_priority"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkDeviceQueueGlobalPriorityCreateInfoKHR-globalPriority-parameter
+                global_priority.validate_physical_device(&physical_device)?;
+            }
+
+            global_priority_infos_vk.push(global_priority.map(|global_priority| {
+                ash::vk::DeviceQueueGlobalPriorityCreateInfoKHR {
+                    global_priority: global_priority.into(),
+                    ..Default::default()
+                }
+            }));
+
             queue_create_infos_vk.push(ash::vk::DeviceQueueCreateInfo {
-                flags: ash::vk::DeviceQueueCreateFlags::empty(),
+                flags: flags.into(),
                 queue_family_index,
                 queue_count: queues.len() as u32,
                 p_queue_priorities: queues.as_ptr(), // borrows from queue_create
@@ -252,11 +369,22 @@ impl Device {
             });
             active_queue_family_indices.push(queue_family_index);
             queues_to_get.extend((0..queues.len() as u32).map(move |id| QueueToGet {
+                flags,
                 queue_family_index,
                 id,
             }));
         }
 
+        for (queue_create_info_vk, global_priority_info_vk) in queue_create_infos_vk
+            .iter_mut()
+            .zip(global_priority_infos_vk.iter_mut())
+        {
+            if let Some(next) = global_priority_info_vk {
+                next.p_next = queue_create_info_vk.p_next;
+                queue_create_info_vk.p_next = next as *const _ as *const _;
+            }
+        }
+
         active_queue_family_indices.sort_unstable();
         active_queue_family_indices.dedup();
         let supported_extensions = physical_device.supported_extensions();
@@ -269,6 +397,14 @@ impl Device {
             Extensions
         */
 
+        let promoted_extensions =
+            enabled_extensions.promote_to_core(supported_extensions, api_version);
+        let implicitly_enabled_extensions = enabled_extensions.enable_dependencies(
+            supported_extensions,
+            api_version,
+            instance.enabled_extensions(),
+        );
+
         // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-01840
         // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-03328
         // VUID-VkDeviceCreateInfo-pProperties-04451
@@ -306,6 +442,20 @@ impl Device {
         //       `Device`'s construction below.
         enabled_features.robust_buffer_access = true;
 
+        // Extensions that were promoted instead of enabled don't provide their functionality
+        // anymore, so the core features that replace them must be enabled instead.
+        enabled_features
+            .enable_promoted(physical_device.supported_features(), &promoted_extensions);
+
+        let unsupported_features =
+            enabled_features.difference(physical_device.supported_features());
+
+        if unsupported_features != Features::empty() {
+            return Err(DeviceCreationError::FeaturesNotSupported {
+                unsupported_features,
+            });
+        }
+
         // VUID-VkDeviceCreateInfo-pNext-04748
         // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-04476
         // VUID-VkDeviceCreateInfo-ppEnabledExtensionNames-02831
@@ -386,6 +536,23 @@ impl Device {
             create_info.p_enabled_features = &features_ffi.head_as_ref().features;
         }
 
+        let physical_devices_vk: SmallVec<[_; 2]> = physical_devices
+            .iter()
+            .map(|pd| pd.internal_object())
+            .collect();
+        let mut device_group_create_info = None;
+
+        if physical_devices_vk.len() > 1 {
+            let next = device_group_create_info.insert(ash::vk::DeviceGroupDeviceCreateInfo {
+                physical_device_count: physical_devices_vk.len() as u32,
+                p_physical_devices: physical_devices_vk.as_ptr(),
+                ..Default::default()
+            });
+
+            next.p_next = create_info.p_next;
+            create_info.p_next = next as *const _ as *const _;
+        }
+
         let handle = unsafe {
             let mut output = MaybeUninit::uninit();
             (fns_i.v1_0.create_device)(
@@ -405,19 +572,30 @@ impl Device {
                 .map_or(ptr::null(), |func| func as _)
         });
 
+        let memory_type_count = physical_device.memory_properties().memory_types.len();
+
         let device = Arc::new(Device {
             handle,
             physical_device,
+            physical_devices,
             api_version,
             fns,
             standard_memory_pool: Mutex::new(Weak::new()),
             enabled_extensions,
+            implicitly_enabled_extensions,
+            promoted_extensions,
             enabled_features,
             active_queue_family_indices,
             allocation_count: AtomicU32::new(0),
+            custom_border_color_sampler_count: AtomicU32::new(0),
+            memory_statistics: Mutex::new(vec![Default::default(); memory_type_count]),
+            memory_budget_limit: Mutex::new(None),
             fence_pool: Mutex::new(Vec::new()),
+            outstanding_fences: Mutex::new(Default::default()),
             semaphore_pool: Mutex::new(Vec::new()),
             event_pool: Mutex::new(Vec::new()),
+            #[cfg(feature = "tracing")]
+            traced_object_names: Mutex::new(HashMap::default()),
         });
 
         // Iterator to return the queues
@@ -425,19 +603,39 @@ impl Device {
             let device = device.clone();
             queues_to_get.into_iter().map(
                 move |QueueToGet {
+                          flags,
                           queue_family_index,
                           id,
                       }| unsafe {
                     let fns = device.fns();
                     let mut output = MaybeUninit::uninit();
-                    (fns.v1_0.get_device_queue)(
-                        handle,
+
+                    // VUID-vkGetDeviceQueue-flags-01841
+                    if flags.is_empty() {
+                        (fns.v1_0.get_device_queue)(
+                            handle,
+                            queue_family_index,
+                            id,
+                            output.as_mut_ptr(),
+                        );
+                    } else {
+                        let queue_info = ash::vk::DeviceQueueInfo2 {
+                            flags: flags.into(),
+                            queue_family_index,
+                            queue_index: id,
+                            ..Default::default()
+                        };
+
+                        (fns.v1_1.get_device_queue2)(handle, &queue_info, output.as_mut_ptr());
+                    }
+
+                    Queue::from_handle(
+                        device.clone(),
+                        output.assume_init(),
+                        flags,
                         queue_family_index,
                         id,
-                        output.as_mut_ptr(),
-                    );
-
-                    Queue::from_handle(device.clone(), output.assume_init(), queue_family_index, id)
+                    )
                 },
             )
         };
@@ -445,6 +643,45 @@ impl Device {
         Ok((device, queues_iter))
     }
 
+    /// Creates a new `Device` for compute work only, with a single queue.
+    ///
+    /// A queue family that supports compute but not graphics operations is preferred, since
+    /// such families often map to dedicated compute hardware. If there is none, the first queue
+    /// family that supports compute operations is used.
+    ///
+    /// No extensions or features are enabled, other than those that vulkano enables
+    /// automatically. In particular, no windowing or surface extensions are needed, either on
+    /// the device or on the instance.
+    pub fn new_compute(
+        physical_device: Arc<PhysicalDevice>,
+    ) -> Result<(Arc<Device>, Arc<Queue>), DeviceCreationError> {
+        let queue_family_properties = physical_device.queue_family_properties();
+        let queue_family_index = queue_family_properties
+            .iter()
+            .position(|properties| {
+                properties.queue_flags.compute && !properties.queue_flags.graphics
+            })
+            .or_else(|| {
+                queue_family_properties
+                    .iter()
+                    .position(|properties| properties.queue_flags.compute)
+            })
+            .ok_or(DeviceCreationError::ComputeQueueFamilyNotFound)?;
+
+        let (device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index: queue_family_index as u32,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )?;
+
+        Ok((device, queues.next().unwrap()))
+    }
+
     /// Returns the Vulkan version supported by the device.
     ///
     /// This is the lower of the
@@ -467,6 +704,22 @@ impl Device {
         &self.physical_device
     }
 
+    /// Returns the physical devices that the device was created from, in the order of their
+    /// device index.
+    ///
+    /// If the device was not created from a device group, this contains only
+    /// [`physical_device`](Self::physical_device).
+    #[inline]
+    pub fn physical_devices(&self) -> &[Arc<PhysicalDevice>] {
+        &self.physical_devices
+    }
+
+    /// Returns a device mask that contains all of the physical devices of the device.
+    #[inline]
+    pub fn all_devices_mask(&self) -> u32 {
+        ((1u64 << self.physical_devices.len()) - 1) as u32
+    }
+
     /// Returns the instance used to create this device.
     #[inline]
     pub fn instance(&self) -> &Arc<Instance> {
@@ -485,6 +738,29 @@ impl Device {
         &self.enabled_extensions
     }
 
+    /// Returns the extensions that were not in
+    /// [`DeviceCreateInfo::enabled_extensions`], but were enabled automatically because an
+    /// extension that was requested depends on them.
+    ///
+    /// These are included in [`enabled_extensions`](Self::enabled_extensions).
+    #[inline]
+    pub fn implicitly_enabled_extensions(&self) -> &DeviceExtensions {
+        &self.implicitly_enabled_extensions
+    }
+
+    /// Returns the extensions that were in [`DeviceCreateInfo::enabled_extensions`], but were
+    /// not enabled because the device does not support them, and their functionality is
+    /// provided by the core API version of the device instead. Core features that replace the
+    /// functionality of these extensions, such as
+    /// [`sampler_mirror_clamp_to_edge`](Features::sampler_mirror_clamp_to_edge), are enabled
+    /// automatically if they are supported.
+    ///
+    /// These are not included in [`enabled_extensions`](Self::enabled_extensions).
+    #[inline]
+    pub fn promoted_extensions(&self) -> &DeviceExtensions {
+        &self.promoted_extensions
+    }
+
     /// Returns the features that have been enabled on the device.
     #[inline]
     pub fn enabled_features(&self) -> &Features {
@@ -514,6 +790,46 @@ impl Device {
         self.allocation_count.load(Ordering::Acquire)
     }
 
+    /// Returns statistics about the [`DeviceMemory`] allocations of the device, for each memory
+    /// type of the physical device.
+    ///
+    /// [`DeviceMemory`]: crate::memory::DeviceMemory
+    #[inline]
+    pub fn memory_statistics(&self) -> Vec<MemoryTypeStatistics> {
+        self.memory_statistics.lock().clone()
+    }
+
+    /// Returns the current memory budget limit of the device.
+    #[inline]
+    pub fn memory_budget_limit(&self) -> Option<MemoryBudgetLimit> {
+        self.memory_budget_limit.lock().clone()
+    }
+
+    /// Sets a limit on how much memory can be allocated from each memory heap, relative to its
+    /// budget. The limit is checked whenever a [`DeviceMemory`] is allocated or imported.
+    ///
+    /// If `budget_limit` is `None`, the limit is removed. The default is `None`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `budget_limit.fraction` is not finite, or not greater than 0.0 and less than or
+    ///   equal to 1.0.
+    ///
+    /// [`DeviceMemory`]: crate::memory::DeviceMemory
+    #[inline]
+    pub fn set_memory_budget_limit(&self, budget_limit: Option<MemoryBudgetLimit>) {
+        if let Some(budget_limit) = &budget_limit {
+            assert!(
+                budget_limit.fraction.is_finite()
+                    && budget_limit.fraction > 0.0
+                    && budget_limit.fraction <= 1.0,
+                "`budget_limit.fraction` must be greater than 0.0 and less than or equal to 1.0",
+            );
+        }
+
+        *self.memory_budget_limit.lock() = budget_limit;
+    }
+
     pub(crate) fn fence_pool(&self) -> &Mutex<Vec<ash::vk::Fence>> {
         &self.fence_pool
     }
@@ -526,6 +842,269 @@ impl Device {
         &self.event_pool
     }
 
+    /// Registers a fence that has been submitted to a queue by a `FenceSignalFuture`, so that
+    /// `collect_finished` can check it.
+    pub(crate) fn register_outstanding_fence(&self, fence: &Arc<Fence>) {
+        let mut outstanding_fences = self.outstanding_fences.lock();
+
+        // Fences that are no longer referenced belong to futures that have been cleaned up or
+        // dropped, so they have finished and don't need to be checked.
+        let len = outstanding_fences.entries.len();
+        outstanding_fences
+            .entries
+            .retain(|(fence, _)| fence.strong_count() != 0);
+        outstanding_fences.collected_count += (len - outstanding_fences.entries.len()) as u64;
+
+        // Don't grow without limit if `collect_finished` is never called.
+        while outstanding_fences.entries.len() >= MAX_OUTSTANDING_FENCES {
+            outstanding_fences.entries.pop_front();
+        }
+
+        outstanding_fences
+            .entries
+            .push_back((Arc::downgrade(fence), Instant::now()));
+    }
+
+    /// Checks which of the fences that were submitted by
+    /// [`FenceSignalFuture`](crate::sync::FenceSignalFuture)s on this device are signaled, and
+    /// releases the resources of the submissions that have finished. Returns the number of fences
+    /// that were found to be signaled.
+    ///
+    /// The futures themselves still have to be cleaned up with `cleanup_finished` or dropped, but
+    /// they no longer need to query the device to do so.
+    ///
+    /// Each fence that has not been found to be signaled yet is queried, from the oldest to the
+    /// most recently submitted one. Fences whose future has been dropped are not queried, because
+    /// dropping the future waits for its fence. If querying a fence fails, the fences that were
+    /// found to be signaled before the failure are still collected.
+    ///
+    /// At most the 1024 most recently submitted fences are tracked. Fences whose future has been
+    /// cleaned up or dropped are also collected when a new fence is submitted.
+    pub fn collect_finished(&self) -> Result<usize, OomError> {
+        let mut outstanding_fences = self.outstanding_fences.lock();
+        let mut signaled = vec![false; outstanding_fences.entries.len()];
+        let mut result = Ok(());
+
+        for (index, (fence, _)) in outstanding_fences.entries.iter().enumerate() {
+            signaled[index] = match fence.upgrade() {
+                Some(fence) => match fence.is_signaled() {
+                    Ok(signaled) => signaled,
+                    Err(err) => {
+                        result = Err(err);
+                        break;
+                    }
+                },
+                // The future was dropped, which waits for the fence.
+                None => true,
+            };
+        }
+
+        let collected = signaled.iter().filter(|&&signaled| signaled).count();
+        let mut signaled = signaled.into_iter();
+        outstanding_fences
+            .entries
+            .retain(|_| !signaled.next().unwrap());
+
+        outstanding_fences.collected_count += collected as u64;
+        result.map(|()| collected)
+    }
+
+    /// Returns statistics about the fences that were submitted by
+    /// [`FenceSignalFuture`](crate::sync::FenceSignalFuture)s on this device, and that
+    /// [`collect_finished`](Device::collect_finished) has not found to be signaled yet.
+    ///
+    /// This can be used to detect a growing backlog of submissions.
+    pub fn outstanding_fence_stats(&self) -> OutstandingFenceStats {
+        let outstanding_fences = self.outstanding_fences.lock();
+
+        OutstandingFenceStats {
+            pending_count: outstanding_fences.entries.len(),
+            oldest_pending_age: outstanding_fences
+                .entries
+                .front()
+                .map(|(_, submitted_at)| submitted_at.elapsed()),
+            collected_count: outstanding_fences.collected_count,
+        }
+    }
+
+    /// Reads the current value of several clocks at the same time, so that timestamps taken from
+    /// one clock can be converted to another.
+    ///
+    /// Returns one timestamp for each element of `time_domains`, and the maximum deviation
+    /// between the moments that the clocks were sampled, in nanoseconds.
+    ///
+    /// The [`ext_calibrated_timestamps`](DeviceExtensions::ext_calibrated_timestamps) extension
+    /// must be enabled on the device, and every element of `time_domains` must be returned by
+    /// [`PhysicalDevice::calibrateable_time_domains`].
+    #[inline]
+    pub fn calibrated_timestamps(
+        &self,
+        time_domains: impl IntoIterator<Item = TimeDomain>,
+    ) -> Result<(Vec<u64>, u64), CalibratedTimestampsError> {
+        let time_domains: SmallVec<[_; 4]> = time_domains.into_iter().collect();
+        self.validate_calibrated_timestamps(&time_domains)?;
+
+        unsafe { Ok(self.calibrated_timestamps_unchecked(time_domains)?) }
+    }
+
+    fn validate_calibrated_timestamps(
+        &self,
+        time_domains: &[TimeDomain],
+    ) -> Result<(), CalibratedTimestampsError> {
+        if !self.enabled_extensions().ext_calibrated_timestamps {
+            return Err(CalibratedTimestampsError::RequirementNotMet {
+                required_for: "`calibrated_timestamps`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_calibrated_timestamps"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let supported_time_domains = unsafe {
+            self.physical_device
+                .calibrateable_time_domains_unchecked()?
+        };
+
+        for &time_domain in time_domains {
+            // VUID-VkCalibratedTimestampInfoEXT-timeDomain-02354
+            if !supported_time_domains.contains(&time_domain) {
+                return Err(CalibratedTimestampsError::TimeDomainNotSupported { time_domain });
+            }
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn calibrated_timestamps_unchecked(
+        &self,
+        time_domains: impl IntoIterator<Item = TimeDomain>,
+    ) -> Result<(Vec<u64>, u64), VulkanError> {
+        let timestamp_infos_vk: SmallVec<[_; 4]> = time_domains
+            .into_iter()
+            .map(|time_domain| ash::vk::CalibratedTimestampInfoEXT {
+                time_domain: time_domain.into(),
+                ..Default::default()
+            })
+            .collect();
+
+        let mut timestamps = vec![0; timestamp_infos_vk.len()];
+        let mut max_deviation = 0;
+
+        let fns = self.fns();
+        (fns.ext_calibrated_timestamps.get_calibrated_timestamps_ext)(
+            self.handle,
+            timestamp_infos_vk.len() as u32,
+            timestamp_infos_vk.as_ptr(),
+            timestamps.as_mut_ptr(),
+            &mut max_deviation,
+        )
+        .result()
+        .map_err(VulkanError::from)?;
+
+        Ok((timestamps, max_deviation))
+    }
+
+    /// Retrieves the properties of an Android hardware buffer when it is imported, including the
+    /// allocation size and memory types to use with
+    /// [`MemoryImportInfo::AndroidHardwareBuffer`](crate::memory::MemoryImportInfo::AndroidHardwareBuffer),
+    /// and the format or external format of the buffer.
+    ///
+    /// The
+    /// [`android_external_memory_android_hardware_buffer`](DeviceExtensions::android_external_memory_android_hardware_buffer)
+    /// extension must be enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// - `buffer` must be a valid `AHardwareBuffer`, and its usage flags must include at least
+    ///   one of `AHARDWAREBUFFER_USAGE_GPU_*`.
+    #[inline]
+    pub unsafe fn android_hardware_buffer_properties(
+        &self,
+        buffer: *const ash::vk::AHardwareBuffer,
+    ) -> Result<AndroidHardwareBufferProperties, AndroidHardwareBufferPropertiesError> {
+        self.validate_android_hardware_buffer_properties()?;
+
+        Ok(self.android_hardware_buffer_properties_unchecked(buffer)?)
+    }
+
+    fn validate_android_hardware_buffer_properties(
+        &self,
+    ) -> Result<(), AndroidHardwareBufferPropertiesError> {
+        if !self
+            .enabled_extensions()
+            .android_external_memory_android_hardware_buffer
+        {
+            return Err(AndroidHardwareBufferPropertiesError::RequirementNotMet {
+                required_for: "`android_hardware_buffer_properties`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["android_external_memory_android_hardware_buffer"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkGetAndroidHardwareBufferPropertiesANDROID-buffer-01884
+        // Can't validate, must be ensured by user
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn android_hardware_buffer_properties_unchecked(
+        &self,
+        buffer: *const ash::vk::AHardwareBuffer,
+    ) -> Result<AndroidHardwareBufferProperties, VulkanError> {
+        let mut format_properties_vk =
+            ash::vk::AndroidHardwareBufferFormatPropertiesANDROID::default();
+        let mut properties_vk = ash::vk::AndroidHardwareBufferPropertiesANDROID {
+            p_next: &mut format_properties_vk as *mut _ as *mut _,
+            ..Default::default()
+        };
+
+        let fns = self.fns();
+        (fns.android_external_memory_android_hardware_buffer
+            .get_android_hardware_buffer_properties_android)(
+            self.handle,
+            buffer,
+            &mut properties_vk,
+        )
+        .result()
+        .map_err(VulkanError::from)?;
+
+        let component_swizzle =
+            |swizzle: ash::vk::ComponentSwizzle| ComponentSwizzle::try_from(swizzle).unwrap();
+
+        Ok(AndroidHardwareBufferProperties {
+            allocation_size: properties_vk.allocation_size,
+            memory_type_bits: properties_vk.memory_type_bits,
+            format: Format::try_from(format_properties_vk.format).ok(),
+            external_format: format_properties_vk.external_format,
+            format_features: format_properties_vk.format_features.into(),
+            sampler_ycbcr_conversion_components: ComponentMapping {
+                r: component_swizzle(format_properties_vk.sampler_ycbcr_conversion_components.r),
+                g: component_swizzle(format_properties_vk.sampler_ycbcr_conversion_components.g),
+                b: component_swizzle(format_properties_vk.sampler_ycbcr_conversion_components.b),
+                a: component_swizzle(format_properties_vk.sampler_ycbcr_conversion_components.a),
+            },
+            suggested_ycbcr_model: SamplerYcbcrModelConversion::try_from(
+                format_properties_vk.suggested_ycbcr_model,
+            )
+            .unwrap(),
+            suggested_ycbcr_range: SamplerYcbcrRange::try_from(
+                format_properties_vk.suggested_ycbcr_range,
+            )
+            .unwrap(),
+            suggested_chroma_offset: [
+                ChromaLocation::try_from(format_properties_vk.suggested_x_chroma_offset).unwrap(),
+                ChromaLocation::try_from(format_properties_vk.suggested_y_chroma_offset).unwrap(),
+            ],
+        })
+    }
+
     /// Retrieves the properties of an external file descriptor when imported as a given external
     /// handle type.
     ///
@@ -584,14 +1163,20 @@ impl Device {
     ///
     /// If `object_name` is `None`, a previously set object name is removed.
     ///
+    /// With the `tracing` cargo feature, the names of queues, swapchains and device memory
+    /// allocations are also included in the events and spans that vulkano emits for them.
+    ///
     /// # Panics
     /// - If `object` is not owned by this device.
+    /// - If the [`ext_debug_utils`](crate::instance::InstanceExtensions::ext_debug_utils)
+    ///   extension was not enabled on the instance.
     pub fn set_debug_utils_object_name<T: VulkanObject + DeviceOwned>(
         &self,
         object: &T,
         object_name: Option<&str>,
     ) -> Result<(), OomError> {
         assert!(object.device().internal_object() == self.internal_object());
+        assert!(self.instance().enabled_extensions().ext_debug_utils);
 
         let object_name_vk = object_name.map(|object_name| CString::new(object_name).unwrap());
         let info = ash::vk::DebugUtilsObjectNameInfoEXT {
@@ -608,9 +1193,45 @@ impl Device {
                 .map_err(VulkanError::from)?;
         }
 
+        #[cfg(feature = "tracing")]
+        if matches!(
+            T::Object::TYPE,
+            ash::vk::ObjectType::QUEUE
+                | ash::vk::ObjectType::SWAPCHAIN_KHR
+                | ash::vk::ObjectType::DEVICE_MEMORY
+        ) {
+            let key = (T::Object::TYPE, object.internal_object().as_raw());
+            let mut traced_object_names = self.traced_object_names.lock();
+
+            if let Some(object_name) = object_name {
+                traced_object_names.insert(key, object_name.to_owned());
+            } else {
+                traced_object_names.remove(&key);
+            }
+        }
+
         Ok(())
     }
 
+    /// Returns the debug name of a traced object, or an empty string if it has none.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn traced_object_name<T: VulkanObject + ?Sized>(&self, object: &T) -> String {
+        self.traced_object_names
+            .lock()
+            .get(&(T::Object::TYPE, object.internal_object().as_raw()))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Forgets the debug name of a traced object that is being destroyed, so that it is not
+    /// reported for a new object that reuses the handle.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn forget_traced_object_name<T: VulkanObject + ?Sized>(&self, object: &T) {
+        self.traced_object_names
+            .lock()
+            .remove(&(T::Object::TYPE, object.internal_object().as_raw()));
+    }
+
     /// Waits until all work on this device has finished. You should never need to call
     /// this function, but it can be useful for debugging or benchmarking purposes.
     ///
@@ -623,6 +1244,9 @@ impl Device {
     /// while this function is waiting.
     #[inline]
     pub unsafe fn wait_idle(&self) -> Result<(), OomError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("device_wait_idle").entered();
+
         let fns = self.fns();
         (fns.v1_0.device_wait_idle)(self.handle)
             .result()
@@ -703,6 +1327,26 @@ pub enum DeviceCreationError {
     ExtensionRestrictionNotMet(ExtensionRestrictionError),
     /// A restriction for a feature was not met.
     FeatureRestrictionNotMet(FeatureRestrictionError),
+    /// Some of the requested features are not supported by the physical device.
+    FeaturesNotSupported {
+        /// The requested features that are not supported.
+        unsupported_features: Features,
+    },
+    /// The physical devices in `physical_devices` don't contain the physical device that the
+    /// device is created from, contain duplicates, or don't belong to the same device group.
+    PhysicalDevicesNotInGroup,
+    /// The physical device has no queue family that supports compute operations.
+    ComputeQueueFamilyNotFound,
+    /// The caller does not have sufficient privileges to create a queue with the requested
+    /// global priority.
+    NotPermitted,
+    /// A queue was created with the `protected` flag, but its queue family does not support
+    /// protected queues.
+    QueueFamilyProtectedNotSupported { queue_family_index: u32 },
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
 }
 
 impl Error for DeviceCreationError {}
@@ -741,6 +1385,41 @@ impl Display for DeviceCreationError {
             ),
             Self::ExtensionRestrictionNotMet(err) => err.fmt(f),
             Self::FeatureRestrictionNotMet(err) => err.fmt(f),
+            Self::FeaturesNotSupported {
+                unsupported_features,
+            } => write!(
+                f,
+                "the requested features {:?} are not supported by the physical device",
+                unsupported_features,
+            ),
+            Self::PhysicalDevicesNotInGroup => write!(
+                f,
+                "the provided physical devices don't form a valid subset of a physical device \
+                group",
+            ),
+            Self::ComputeQueueFamilyNotFound => write!(
+                f,
+                "the physical device has no queue family that supports compute operations",
+            ),
+            Self::NotPermitted => write!(
+                f,
+                "the caller does not have sufficient privileges to create a queue with the \
+                requested global priority",
+            ),
+            Self::QueueFamilyProtectedNotSupported { queue_family_index } => write!(
+                f,
+                "a queue was created with the `protected` flag, but its queue family ({}) does \
+                not support protected queues",
+                queue_family_index,
+            ),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
         }
     }
 }
@@ -755,11 +1434,21 @@ impl From<VulkanError> for DeviceCreationError {
             VulkanError::ExtensionNotPresent => Self::ExtensionNotPresent,
             VulkanError::FeatureNotPresent => Self::FeatureNotPresent,
             VulkanError::TooManyObjects => Self::TooManyObjects,
+            VulkanError::NotPermitted => Self::NotPermitted,
             _ => panic!("Unexpected error value"),
         }
     }
 }
 
+impl From<RequirementNotMet> for DeviceCreationError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
 impl From<ExtensionRestrictionError> for DeviceCreationError {
     fn from(err: ExtensionRestrictionError) -> Self {
         Self::ExtensionRestrictionNotMet(err)
@@ -772,11 +1461,44 @@ impl From<FeatureRestrictionError> for DeviceCreationError {
     }
 }
 
+// The maximum number of fences in `OutstandingFences`. When more fences are submitted, the oldest
+// ones are no longer tracked.
+const MAX_OUTSTANDING_FENCES: usize = 1024;
+
+#[derive(Debug, Default)]
+struct OutstandingFences {
+    // Weak references to the fences, with the time they were submitted, oldest first.
+    entries: VecDeque<(Weak<Fence>, Instant)>,
+    collected_count: u64,
+}
+
+/// Statistics about the fences that are waiting to be collected by
+/// [`Device::collect_finished`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutstandingFenceStats {
+    /// The number of fences that have not been found to be signaled yet.
+    pub pending_count: usize,
+
+    /// How long ago the oldest of these fences was submitted, or `None` if there are none.
+    pub oldest_pending_age: Option<Duration>,
+
+    /// The total number of fences that have been collected since the device was created,
+    /// either by `collect_finished` or because their future was cleaned up or dropped.
+    pub collected_count: u64,
+}
+
 /// Parameters to create a new `Device`.
 #[derive(Clone, Debug)]
 pub struct DeviceCreateInfo {
     /// The extensions to enable on the device.
     ///
+    /// Device extensions that these extensions depend on are enabled automatically, if they are
+    /// supported and the dependency is not already met by the API version. Extensions that are
+    /// not supported by the physical device, but that have been promoted to its API version, are
+    /// left disabled and the core functionality is used instead, enabling the core features that
+    /// replace them. See
+    /// [`Device::implicitly_enabled_extensions`] and [`Device::promoted_extensions`].
+    ///
     /// The default value is [`DeviceExtensions::empty()`].
     pub enabled_extensions: DeviceExtensions,
 
@@ -790,6 +1512,21 @@ pub struct DeviceCreateInfo {
     /// The default value is empty, which must be overridden.
     pub queue_create_infos: Vec<QueueCreateInfo>,
 
+    /// The physical devices to create the device from, if the device is to be created from a
+    /// group of physical devices.
+    ///
+    /// If not empty, the list must contain the physical device that is passed to
+    /// [`Device::new`], and all elements must be unique and belong to the same
+    /// [physical device group](crate::instance::Instance::enumerate_physical_device_groups).
+    /// The index of a physical device in this list is its device index, which is used in device
+    /// masks.
+    ///
+    /// If empty, or if it contains only the physical device that is passed to `Device::new`, the
+    /// device is created from a single physical device.
+    ///
+    /// The default value is empty.
+    pub physical_devices: SmallVec<[Arc<PhysicalDevice>; 2]>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -800,6 +1537,7 @@ impl Default for DeviceCreateInfo {
             enabled_extensions: DeviceExtensions::empty(),
             enabled_features: Features::empty(),
             queue_create_infos: Vec::new(),
+            physical_devices: SmallVec::new(),
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -808,6 +1546,11 @@ impl Default for DeviceCreateInfo {
 /// Parameters to create queues in a new `Device`.
 #[derive(Clone, Debug)]
 pub struct QueueCreateInfo {
+    /// Additional properties of the queues.
+    ///
+    /// The default value is empty.
+    pub flags: QueueCreateFlags,
+
     /// The index of the queue family to create queues for.
     ///
     /// The default value is `0`.
@@ -823,6 +1566,18 @@ pub struct QueueCreateInfo {
     /// The default value is a single queue with a priority of 0.5.
     pub queues: Vec<f32>,
 
+    /// The system-wide priority of the queues, relative to queues of other devices and processes.
+    ///
+    /// If set to `Some`, the
+    /// [`khr_global_priority`](crate::device::DeviceExtensions::khr_global_priority) or
+    /// [`ext_global_priority`](crate::device::DeviceExtensions::ext_global_priority) extension
+    /// must be enabled on the device. Priorities higher than [`QueueGlobalPriority::Medium`]
+    /// may require elevated privileges, and device creation will fail with
+    /// [`DeviceCreationError::NotPermitted`] if the caller doesn't have them.
+    ///
+    /// The default value is `None`, which uses the default priority of the implementation.
+    pub global_priority: Option<QueueGlobalPriority>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -830,8 +1585,10 @@ impl Default for QueueCreateInfo {
     #[inline]
     fn default() -> Self {
         Self {
+            flags: QueueCreateFlags::empty(),
             queue_family_index: 0,
             queues: vec![0.5],
+            global_priority: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -857,6 +1614,33 @@ where
     }
 }
 
+/// Assigns human-readable names to objects owned by a device, for debugging purposes.
+///
+/// This is implemented for every object that implements both [`VulkanObject`] and
+/// [`DeviceOwned`], including buffers, images, image views, pipelines, descriptor sets and
+/// queues. The names show up in validation layer messages and in debugging tools such as
+/// RenderDoc.
+pub trait DebugUtilsObjectName {
+    /// Assigns a human-readable name to `self`.
+    ///
+    /// If `object_name` is `None`, a previously set object name is removed.
+    ///
+    /// # Panics
+    /// - If the [`ext_debug_utils`](crate::instance::InstanceExtensions::ext_debug_utils)
+    ///   extension was not enabled on the instance.
+    fn set_debug_utils_object_name(&self, object_name: Option<&str>) -> Result<(), OomError>;
+}
+
+impl<T> DebugUtilsObjectName for T
+where
+    T: VulkanObject + DeviceOwned,
+{
+    #[inline]
+    fn set_debug_utils_object_name(&self, object_name: Option<&str>) -> Result<(), OomError> {
+        self.device().set_debug_utils_object_name(self, object_name)
+    }
+}
+
 /// The properties of a Unix file descriptor when it is imported.
 #[derive(Clone, Debug)]
 #[non_exhaustive]
@@ -865,6 +1649,45 @@ pub struct MemoryFdProperties {
     pub memory_type_bits: u32,
 }
 
+/// The properties of an Android hardware buffer when it is imported.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct AndroidHardwareBufferProperties {
+    /// The size of the memory allocation that must be used to import the buffer.
+    pub allocation_size: DeviceSize,
+
+    /// A bitmask of the indices of memory types that can be used to import the buffer.
+    pub memory_type_bits: u32,
+
+    /// The Vulkan format that corresponds to the format of the buffer, or `None` if there is
+    /// no such format.
+    pub format: Option<Format>,
+
+    /// An implementation-defined external format of the buffer, or 0 if there is none.
+    ///
+    /// If this is not 0, it can be used to create a sampler YCbCr conversion with
+    /// [`SamplerYcbcrConversionCreateInfo::external_format`].
+    ///
+    /// [`SamplerYcbcrConversionCreateInfo::external_format`]: crate::sampler::ycbcr::SamplerYcbcrConversionCreateInfo::external_format
+    pub external_format: u64,
+
+    /// The features supported by the format or external format of the buffer.
+    pub format_features: FormatFeatures,
+
+    /// The component mapping that should be used in a sampler YCbCr conversion for the buffer.
+    pub sampler_ycbcr_conversion_components: ComponentMapping,
+
+    /// The YCbCr model that should be used in a sampler YCbCr conversion for the buffer.
+    pub suggested_ycbcr_model: SamplerYcbcrModelConversion,
+
+    /// The YCbCr range that should be used in a sampler YCbCr conversion for the buffer.
+    pub suggested_ycbcr_range: SamplerYcbcrRange,
+
+    /// The chroma offsets that should be used in a sampler YCbCr conversion for the buffer, in
+    /// the x and y direction.
+    pub suggested_chroma_offset: [ChromaLocation; 2],
+}
+
 /// Error that can happen when calling `memory_fd_properties`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum MemoryFdPropertiesError {
@@ -933,12 +1756,152 @@ impl From<RequirementNotMet> for MemoryFdPropertiesError {
     }
 }
 
+/// Error that can happen when calling [`Device::calibrated_timestamps`].
+#[derive(Clone, Debug)]
+pub enum CalibratedTimestampsError {
+    VulkanError(VulkanError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// The physical device can't read timestamps from the given time domain.
+    TimeDomainNotSupported {
+        time_domain: TimeDomain,
+    },
+}
+
+impl Error for CalibratedTimestampsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::VulkanError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for CalibratedTimestampsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::VulkanError(_) => write!(f, "a runtime error occurred"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::TimeDomainNotSupported { time_domain } => write!(
+                f,
+                "the physical device can't read timestamps from the time domain {:?}",
+                time_domain,
+            ),
+        }
+    }
+}
+
+impl From<VulkanError> for CalibratedTimestampsError {
+    fn from(err: VulkanError) -> Self {
+        Self::VulkanError(err)
+    }
+}
+
+/// Error that can happen when calling [`Device::android_hardware_buffer_properties`].
+#[derive(Clone, Debug)]
+pub enum AndroidHardwareBufferPropertiesError {
+    VulkanError(VulkanError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+}
+
+impl Error for AndroidHardwareBufferPropertiesError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::VulkanError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AndroidHardwareBufferPropertiesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::VulkanError(_) => write!(f, "a runtime error occurred"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+        }
+    }
+}
+
+impl From<VulkanError> for AndroidHardwareBufferPropertiesError {
+    fn from(err: VulkanError) -> Self {
+        Self::VulkanError(err)
+    }
+}
+
+/// Error that can happen when exporting the underlying Metal object of a Vulkan object.
+#[derive(Clone, Debug)]
+pub enum MetalObjectExportError {
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// Exporting this type of Metal object was not enabled when the Vulkan object was created.
+    ExportNotEnabled,
+
+    /// The provided plane index is not less than the number of planes in the image format.
+    PlaneOutOfRange { plane: usize, plane_count: usize },
+}
+
+impl Error for MetalObjectExportError {}
+
+impl Display for MetalObjectExportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::ExportNotEnabled => write!(
+                f,
+                "exporting this type of Metal object was not enabled when the Vulkan object was \
+                created",
+            ),
+            Self::PlaneOutOfRange { plane, plane_count } => write!(
+                f,
+                "the provided plane index ({}) is not less than the number of planes in the image \
+                format ({})",
+                plane, plane_count,
+            ),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::device::{
-        Device, DeviceCreateInfo, DeviceCreationError, FeatureRestriction, FeatureRestrictionError,
+        physical::{PhysicalDeviceError, TimeDomain},
+        CalibratedTimestampsError, Device, DeviceCreateInfo, DeviceCreationError, DeviceExtensions,
         Features, QueueCreateInfo,
     };
+    use crate::Version;
+    use smallvec::{smallvec, SmallVec};
     use std::sync::Arc;
 
     #[test]
@@ -947,6 +1910,53 @@ mod tests {
         assert!(Arc::get_mut(&mut device).is_some());
     }
 
+    #[test]
+    fn physical_devices() {
+        let instance = instance!();
+        let physical_devices: Vec<_> = instance.enumerate_physical_devices().unwrap().collect();
+        let physical_device = match physical_devices.first() {
+            Some(p) => p.clone(),
+            None => return,
+        };
+
+        let create_info = |physical_devices: SmallVec<[_; 2]>| DeviceCreateInfo {
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index: 0,
+                ..Default::default()
+            }],
+            physical_devices,
+            ..Default::default()
+        };
+
+        // A group with only `physical_device` is the same as not using a group.
+        let (device, _) = Device::new(
+            physical_device.clone(),
+            create_info(smallvec![physical_device.clone()]),
+        )
+        .unwrap();
+        assert_eq!(device.physical_devices().len(), 1);
+        assert_eq!(device.all_devices_mask(), 0b1);
+
+        match Device::new(
+            physical_device.clone(),
+            create_info(smallvec![physical_device.clone(), physical_device.clone()]),
+        ) {
+            Err(DeviceCreationError::PhysicalDevicesNotInGroup) => (),
+            _ => panic!(),
+        }
+
+        // The group must contain `physical_device`, even if it has only one element.
+        if let Some(other_physical_device) = physical_devices.get(1) {
+            match Device::new(
+                physical_device.clone(),
+                create_info(smallvec![other_physical_device.clone()]),
+            ) {
+                Err(DeviceCreationError::PhysicalDevicesNotInGroup) => (),
+                _ => panic!(),
+            }
+        }
+    }
+
     #[test]
     fn too_many_queues() {
         let instance = instance!();
@@ -992,6 +2002,8 @@ mod tests {
             return;
         }
 
+        let expected_unsupported = features.difference(physical_device.supported_features());
+
         match Device::new(
             physical_device,
             DeviceCreateInfo {
@@ -1003,10 +2015,11 @@ mod tests {
                 ..Default::default()
             },
         ) {
-            Err(DeviceCreationError::FeatureRestrictionNotMet(FeatureRestrictionError {
-                restriction: FeatureRestriction::NotSupported,
-                ..
-            })) => (), // Success
+            Err(DeviceCreationError::FeaturesNotSupported {
+                unsupported_features,
+            }) => {
+                assert_eq!(unsupported_features, expected_unsupported);
+            }
             _ => panic!(),
         };
     }
@@ -1047,4 +2060,269 @@ mod tests {
             )
         });
     }
+
+    #[test]
+    fn debug_utils_object_name() {
+        use crate::{
+            device::DebugUtilsObjectName,
+            instance::{Instance, InstanceCreateInfo, InstanceExtensions},
+            VulkanLibrary,
+        };
+
+        let library = match VulkanLibrary::new() {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        let instance = match Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_extensions: InstanceExtensions {
+                    ext_debug_utils: true,
+                    ..InstanceExtensions::empty()
+                },
+                ..Default::default()
+            },
+        ) {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let (_device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index: 0,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let queue = queues.next().unwrap();
+
+        queue.set_debug_utils_object_name(Some("queue")).unwrap();
+        queue.set_debug_utils_object_name(None).unwrap();
+    }
+
+    #[test]
+    fn debug_utils_object_name_without_extension() {
+        use crate::device::DebugUtilsObjectName;
+
+        let (_device, queue) = gfx_dev_and_queue!();
+
+        assert_should_panic!({
+            let _ = queue.set_debug_utils_object_name(Some("queue"));
+        });
+    }
+
+    #[test]
+    fn collect_finished_fences() {
+        use crate::{
+            command_buffer::{
+                allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+                CommandBufferUsage,
+            },
+            sync::{now, GpuFuture},
+        };
+
+        let (device, queue) = gfx_dev_and_queue!();
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device.clone());
+        let command_buffer = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let future = now(device.clone())
+            .then_execute(queue, command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+        assert_eq!(device.outstanding_fence_stats().pending_count, 1);
+
+        future.wait(None).unwrap();
+        assert_eq!(device.collect_finished().unwrap(), 1);
+
+        let stats = device.outstanding_fence_stats();
+        assert_eq!(stats.pending_count, 0);
+        assert_eq!(stats.oldest_pending_age, None);
+        assert_eq!(stats.collected_count, 1);
+    }
+
+    #[test]
+    fn outstanding_fences_pruned() {
+        use crate::{
+            command_buffer::{
+                allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+                CommandBufferUsage,
+            },
+            sync::{now, GpuFuture},
+        };
+
+        let (device, queue) = gfx_dev_and_queue!();
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device.clone());
+        let flush = || {
+            let command_buffer = AutoCommandBufferBuilder::primary(
+                &command_buffer_allocator,
+                queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+
+            now(device.clone())
+                .then_execute(queue.clone(), command_buffer)
+                .unwrap()
+                .then_signal_fence_and_flush()
+                .unwrap()
+        };
+
+        // Without calling `collect_finished`, the fences of cleaned up futures are dropped when
+        // the next fence is submitted.
+        for _ in 0..3 {
+            flush().wait(None).unwrap();
+        }
+
+        let future = flush();
+        let stats = device.outstanding_fence_stats();
+        assert_eq!(stats.pending_count, 1);
+        assert_eq!(stats.collected_count, 3);
+        future.wait(None).unwrap();
+    }
+
+    #[test]
+    fn calibrated_timestamps_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        if device.enabled_extensions().ext_calibrated_timestamps {
+            return;
+        }
+
+        assert!(matches!(
+            device.calibrated_timestamps([TimeDomain::Device]),
+            Err(CalibratedTimestampsError::RequirementNotMet { .. })
+        ));
+    }
+
+    #[test]
+    fn fragment_shading_rates() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        if !physical_device
+            .supported_extensions()
+            .khr_fragment_shading_rate
+        {
+            assert!(matches!(
+                physical_device.fragment_shading_rates(),
+                Err(PhysicalDeviceError::RequirementNotMet { .. })
+            ));
+            return;
+        }
+
+        // The 1x1 fragment size must always be supported.
+        let fragment_shading_rates = physical_device.fragment_shading_rates().unwrap();
+        assert!(fragment_shading_rates
+            .iter()
+            .any(|fragment_shading_rate| fragment_shading_rate.fragment_size == [1, 1]));
+    }
+
+    #[test]
+    fn new_compute() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let (device, queue) = match Device::new_compute(physical_device) {
+            Ok(x) => x,
+            Err(DeviceCreationError::ComputeQueueFamilyNotFound) => return,
+            Err(_) => panic!(),
+        };
+
+        assert!(!device.enabled_extensions().khr_swapchain);
+        assert!(
+            device.physical_device().queue_family_properties()[queue.queue_family_index() as usize]
+                .queue_flags
+                .compute
+        );
+    }
+
+    #[test]
+    fn enable_promoted_features() {
+        let promoted = DeviceExtensions {
+            khr_sampler_mirror_clamp_to_edge: true,
+            ext_shader_viewport_index_layer: true,
+            ..DeviceExtensions::empty()
+        };
+        let supported = Features {
+            sampler_mirror_clamp_to_edge: true,
+            shader_output_viewport_index: true,
+            draw_indirect_count: true,
+            ..Features::empty()
+        };
+
+        let mut features = Features::empty();
+        features.enable_promoted(&supported, &promoted);
+
+        // `shader_output_layer` is not supported, and `draw_indirect_count` doesn't replace a
+        // promoted extension.
+        assert_eq!(
+            features,
+            Features {
+                sampler_mirror_clamp_to_edge: true,
+                shader_output_viewport_index: true,
+                ..Features::empty()
+            }
+        );
+    }
+
+    #[test]
+    fn promoted_extension_enables_feature() {
+        let instance = instance!();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().find(|p| {
+            p.api_version() >= Version::V1_2
+                && !p.supported_extensions().khr_sampler_mirror_clamp_to_edge
+                && p.supported_features().sampler_mirror_clamp_to_edge
+        }) {
+            Some(p) => p,
+            None => return, // test ignored
+        };
+
+        let (device, _) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index: 0,
+                    ..Default::default()
+                }],
+                enabled_extensions: DeviceExtensions {
+                    khr_sampler_mirror_clamp_to_edge: true,
+                    ..DeviceExtensions::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert!(
+            device
+                .promoted_extensions()
+                .khr_sampler_mirror_clamp_to_edge
+        );
+        assert!(!device.enabled_extensions().khr_sampler_mirror_clamp_to_edge);
+        assert!(device.enabled_features().sampler_mirror_clamp_to_edge);
+    }
 }
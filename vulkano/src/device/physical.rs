@@ -12,21 +12,24 @@ use crate::{
     buffer::{ExternalBufferInfo, ExternalBufferProperties},
     cache::OnceCache,
     device::{properties::Properties, DeviceExtensions, Features, FeaturesFfi, PropertiesFfi},
-    format::{Format, FormatProperties},
+    format::{DrmFormatModifierProperties, Format, FormatProperties},
     image::{
-        ImageCreateFlags, ImageFormatInfo, ImageFormatProperties, ImageUsage,
-        SparseImageFormatInfo, SparseImageFormatProperties,
+        ImageCreateFlags, ImageFormatInfo, ImageFormatProperties, ImageTiling, ImageUsage,
+        SampleCount, SparseImageFormatInfo, SparseImageFormatProperties,
     },
     instance::Instance,
     macros::{vulkan_bitflags, vulkan_enum},
-    memory::MemoryProperties,
+    memory::{MemoryHeapBudget, MemoryProperties},
+    pipeline::graphics::{
+        fragment_shading_rate::SupportedFragmentShadingRate, multisample::MultisampleProperties,
+    },
     swapchain::{
         ColorSpace, FullScreenExclusive, PresentMode, SupportedSurfaceTransforms, Surface,
         SurfaceApi, SurfaceCapabilities, SurfaceInfo,
     },
     sync::{
         ExternalFenceInfo, ExternalFenceProperties, ExternalSemaphoreInfo,
-        ExternalSemaphoreProperties,
+        ExternalSemaphoreProperties, Sharing,
     },
     ExtensionProperties, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
@@ -76,11 +79,14 @@ pub struct PhysicalDevice {
     queue_family_properties: Vec<QueueFamilyProperties>,
 
     // Data queried by the user at runtime, cached for faster lookups.
+    calibrateable_time_domains: OnceCache<(), Vec<TimeDomain>>,
     external_buffer_properties: OnceCache<ExternalBufferInfo, ExternalBufferProperties>,
     external_fence_properties: OnceCache<ExternalFenceInfo, ExternalFenceProperties>,
     external_semaphore_properties: OnceCache<ExternalSemaphoreInfo, ExternalSemaphoreProperties>,
     format_properties: OnceCache<Format, FormatProperties>,
+    drm_format_modifier_properties: OnceCache<Format, Vec<DrmFormatModifierProperties>>,
     image_format_properties: OnceCache<ImageFormatInfo, Option<ImageFormatProperties>>,
+    multisample_properties: OnceCache<SampleCount, MultisampleProperties>,
     sparse_image_format_properties:
         OnceCache<SparseImageFormatInfo, Vec<SparseImageFormatProperties>>,
 }
@@ -140,11 +146,14 @@ impl PhysicalDevice {
             memory_properties,
             queue_family_properties,
 
+            calibrateable_time_domains: OnceCache::new(),
             external_buffer_properties: OnceCache::new(),
             external_fence_properties: OnceCache::new(),
             external_semaphore_properties: OnceCache::new(),
             format_properties: OnceCache::new(),
+            drm_format_modifier_properties: OnceCache::new(),
             image_format_properties: OnceCache::new(),
+            multisample_properties: OnceCache::new(),
             sparse_image_format_properties: OnceCache::new(),
         }))
     }
@@ -388,6 +397,70 @@ impl PhysicalDevice {
         &self.properties
     }
 
+    /// Returns the universally unique identifier of the physical device.
+    ///
+    /// The UUID is the same across instances, processes and APIs that support it, such as CUDA,
+    /// OpenGL and Direct3D 12. It can be used to find the physical device that corresponds to an
+    /// adapter in another API when sharing memory or semaphores with it.
+    ///
+    /// Returns `None` if the physical device does not report IDs, which requires Vulkan 1.1 or the
+    /// [`khr_external_memory_capabilities`](crate::instance::InstanceExtensions::khr_external_memory_capabilities)
+    /// family of extensions.
+    #[inline]
+    pub fn device_uuid(&self) -> Option<[u8; 16]> {
+        self.properties.device_uuid
+    }
+
+    /// Returns the universally unique identifier of the driver build in use by the physical
+    /// device.
+    ///
+    /// Memory and semaphores can only be shared between instances or APIs whose physical devices
+    /// have the same device UUID and driver UUID.
+    ///
+    /// Returns `None` under the same conditions as [`device_uuid`](Self::device_uuid).
+    #[inline]
+    pub fn driver_uuid(&self) -> Option<[u8; 16]> {
+        self.properties.driver_uuid
+    }
+
+    /// Returns the locally unique identifier of the physical device.
+    ///
+    /// On Windows, this matches the LUID of the corresponding Direct3D 12 adapter.
+    ///
+    /// Returns `None` under the same conditions as [`device_uuid`](Self::device_uuid), or if the
+    /// physical device does not report a valid LUID, which is the case on most platforms other
+    /// than Windows.
+    #[inline]
+    pub fn device_luid(&self) -> Option<[u8; 8]> {
+        if self.properties.device_luid_valid == Some(true) {
+            self.properties.device_luid
+        } else {
+            None
+        }
+    }
+
+    /// Returns the physical device of `instance` whose [`device_uuid`](Self::device_uuid) is
+    /// `device_uuid`, or `None` if there is none.
+    pub fn find_by_uuid(
+        instance: &Arc<Instance>,
+        device_uuid: [u8; 16],
+    ) -> Result<Option<Arc<PhysicalDevice>>, VulkanError> {
+        Ok(instance
+            .enumerate_physical_devices()?
+            .find(|physical_device| physical_device.device_uuid() == Some(device_uuid)))
+    }
+
+    /// Returns the physical device of `instance` whose [`device_luid`](Self::device_luid) is
+    /// `device_luid`, or `None` if there is none.
+    pub fn find_by_luid(
+        instance: &Arc<Instance>,
+        device_luid: [u8; 8],
+    ) -> Result<Option<Arc<PhysicalDevice>>, VulkanError> {
+        Ok(instance
+            .enumerate_physical_devices()?
+            .find(|physical_device| physical_device.device_luid() == Some(device_luid)))
+    }
+
     /// Returns the extension properties reported by the physical device.
     #[inline]
     pub fn extension_properties(&self) -> &[ExtensionProperties] {
@@ -418,6 +491,159 @@ impl PhysicalDevice {
         &self.queue_family_properties
     }
 
+    /// Returns the time domains that can be used with
+    /// [`Device::calibrated_timestamps`](crate::device::Device::calibrated_timestamps).
+    ///
+    /// The [`ext_calibrated_timestamps`](crate::device::DeviceExtensions::ext_calibrated_timestamps)
+    /// extension must be supported by the physical device.
+    #[inline]
+    pub fn calibrateable_time_domains(&self) -> Result<Vec<TimeDomain>, PhysicalDeviceError> {
+        self.validate_calibrateable_time_domains()?;
+
+        unsafe { Ok(self.calibrateable_time_domains_unchecked()?) }
+    }
+
+    fn validate_calibrateable_time_domains(&self) -> Result<(), PhysicalDeviceError> {
+        if !self.supported_extensions().ext_calibrated_timestamps {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`calibrateable_time_domains`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_calibrated_timestamps"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn calibrateable_time_domains_unchecked(
+        &self,
+    ) -> Result<Vec<TimeDomain>, VulkanError> {
+        self.calibrateable_time_domains.get_or_try_insert((), |_| {
+            let fns = self.instance.fns();
+
+            loop {
+                let mut count = 0;
+                (fns.ext_calibrated_timestamps
+                    .get_physical_device_calibrateable_time_domains_ext)(
+                    self.internal_object(),
+                    &mut count,
+                    ptr::null_mut(),
+                )
+                .result()
+                .map_err(VulkanError::from)?;
+
+                let mut time_domains = Vec::with_capacity(count as usize);
+                let result = (fns
+                    .ext_calibrated_timestamps
+                    .get_physical_device_calibrateable_time_domains_ext)(
+                    self.internal_object(),
+                    &mut count,
+                    time_domains.as_mut_ptr(),
+                );
+
+                match result {
+                    ash::vk::Result::INCOMPLETE => (),
+                    ash::vk::Result::SUCCESS => {
+                        time_domains.set_len(count as usize);
+
+                        return Ok(time_domains
+                            .into_iter()
+                            .filter_map(|time_domain| time_domain.try_into().ok())
+                            .collect());
+                    }
+                    err => return Err(VulkanError::from(err)),
+                }
+            }
+        })
+    }
+
+    /// Returns the cooperative matrix types that are supported by the physical device, for use
+    /// with the `CooperativeMatrixNV` SPIR-V capability.
+    ///
+    /// The [`nv_cooperative_matrix`](crate::device::DeviceExtensions::nv_cooperative_matrix)
+    /// extension must be supported by the physical device.
+    #[inline]
+    pub fn cooperative_matrix_properties(
+        &self,
+    ) -> Result<Vec<CooperativeMatrixProperties>, PhysicalDeviceError> {
+        self.validate_cooperative_matrix_properties()?;
+
+        unsafe { Ok(self.cooperative_matrix_properties_unchecked()?) }
+    }
+
+    fn validate_cooperative_matrix_properties(&self) -> Result<(), PhysicalDeviceError> {
+        if !self.supported_extensions().nv_cooperative_matrix {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`cooperative_matrix_properties`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["nv_cooperative_matrix"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn cooperative_matrix_properties_unchecked(
+        &self,
+    ) -> Result<Vec<CooperativeMatrixProperties>, VulkanError> {
+        let fns = self.instance.fns();
+
+        loop {
+            let mut count = 0;
+            (fns.nv_cooperative_matrix
+                .get_physical_device_cooperative_matrix_properties_nv)(
+                self.internal_object(),
+                &mut count,
+                ptr::null_mut(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+
+            let mut properties =
+                vec![ash::vk::CooperativeMatrixPropertiesNV::default(); count as usize];
+            let result = (fns
+                .nv_cooperative_matrix
+                .get_physical_device_cooperative_matrix_properties_nv)(
+                self.internal_object(),
+                &mut count,
+                properties.as_mut_ptr(),
+            );
+
+            match result {
+                ash::vk::Result::INCOMPLETE => (),
+                ash::vk::Result::SUCCESS => {
+                    properties.truncate(count as usize);
+
+                    return Ok(properties
+                        .into_iter()
+                        .filter_map(|properties| {
+                            Some(CooperativeMatrixProperties {
+                                m_size: properties.m_size,
+                                n_size: properties.n_size,
+                                k_size: properties.k_size,
+                                a_type: properties.a_type.try_into().ok()?,
+                                b_type: properties.b_type.try_into().ok()?,
+                                c_type: properties.c_type.try_into().ok()?,
+                                d_type: properties.d_type.try_into().ok()?,
+                                scope: properties.scope.try_into().ok()?,
+                                _ne: crate::NonExhaustive(()),
+                            })
+                        })
+                        .collect());
+                }
+                err => return Err(VulkanError::from(err)),
+            }
+        }
+    }
+
     /// Queries whether the physical device supports presenting to DirectFB surfaces from queues of
     /// the given queue family.
     ///
@@ -901,6 +1127,197 @@ impl PhysicalDevice {
         })
     }
 
+    /// Retrieves the DRM format modifiers that are supported for a format, along with the
+    /// properties of the format when used with each modifier.
+    ///
+    /// The [`ext_image_drm_format_modifier`](crate::device::DeviceExtensions::ext_image_drm_format_modifier)
+    /// extension must be supported by the physical device.
+    ///
+    /// The results of this function are cached, so that future calls with the same arguments
+    /// do not need to make a call to the Vulkan API again.
+    #[inline]
+    pub fn drm_format_modifier_properties(
+        &self,
+        format: Format,
+    ) -> Result<Vec<DrmFormatModifierProperties>, PhysicalDeviceError> {
+        self.validate_drm_format_modifier_properties(format)?;
+
+        unsafe { Ok(self.drm_format_modifier_properties_unchecked(format)) }
+    }
+
+    fn validate_drm_format_modifier_properties(
+        &self,
+        format: Format,
+    ) -> Result<(), PhysicalDeviceError> {
+        if !self.supported_extensions().ext_image_drm_format_modifier {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`drm_format_modifier_properties`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_image_drm_format_modifier"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if !(self.instance.api_version() >= Version::V1_1
+            || self
+                .instance
+                .enabled_extensions()
+                .khr_get_physical_device_properties2)
+        {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`drm_format_modifier_properties`",
+                requires_one_of: RequiresOneOf {
+                    api_version: Some(Version::V1_1),
+                    instance_extensions: &["khr_get_physical_device_properties2"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkGetPhysicalDeviceFormatProperties2-format-parameter
+        format.validate_physical_device(self)?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn drm_format_modifier_properties_unchecked(
+        &self,
+        format: Format,
+    ) -> Vec<DrmFormatModifierProperties> {
+        self.drm_format_modifier_properties
+            .get_or_insert(format, |&format| {
+                let fns = self.instance.fns();
+                let get_format_properties2 =
+                    |format_properties2: &mut ash::vk::FormatProperties2| {
+                        if self.api_version() >= Version::V1_1 {
+                            (fns.v1_1.get_physical_device_format_properties2)(
+                                self.handle,
+                                format.into(),
+                                format_properties2,
+                            );
+                        } else {
+                            (fns.khr_get_physical_device_properties2
+                                .get_physical_device_format_properties2_khr)(
+                                self.handle,
+                                format.into(),
+                                format_properties2,
+                            );
+                        }
+                    };
+
+                let mut modifier_properties_list =
+                    ash::vk::DrmFormatModifierPropertiesListEXT::default();
+                let mut format_properties2 = ash::vk::FormatProperties2 {
+                    p_next: &mut modifier_properties_list as *mut _ as *mut _,
+                    ..Default::default()
+                };
+                get_format_properties2(&mut format_properties2);
+
+                let mut modifier_properties =
+                    Vec::with_capacity(modifier_properties_list.drm_format_modifier_count as usize);
+                modifier_properties_list.p_drm_format_modifier_properties =
+                    modifier_properties.as_mut_ptr();
+                format_properties2.p_next = &mut modifier_properties_list as *mut _ as *mut _;
+                get_format_properties2(&mut format_properties2);
+                modifier_properties
+                    .set_len(modifier_properties_list.drm_format_modifier_count as usize);
+
+                modifier_properties
+                    .into_iter()
+                    .map(|properties: ash::vk::DrmFormatModifierPropertiesEXT| {
+                        DrmFormatModifierProperties {
+                            drm_format_modifier: properties.drm_format_modifier,
+                            drm_format_modifier_plane_count: properties
+                                .drm_format_modifier_plane_count,
+                            drm_format_modifier_tiling_features: properties
+                                .drm_format_modifier_tiling_features
+                                .into(),
+                        }
+                    })
+                    .collect()
+            })
+    }
+
+    /// Returns the fragment sizes that are supported for the fragment shading rate, and the sample
+    /// counts that are supported with each of them.
+    ///
+    /// The [`khr_fragment_shading_rate`](crate::device::DeviceExtensions::khr_fragment_shading_rate)
+    /// extension must be supported by the physical device.
+    #[inline]
+    pub fn fragment_shading_rates(
+        &self,
+    ) -> Result<Vec<SupportedFragmentShadingRate>, PhysicalDeviceError> {
+        self.validate_fragment_shading_rates()?;
+
+        unsafe { Ok(self.fragment_shading_rates_unchecked()?) }
+    }
+
+    fn validate_fragment_shading_rates(&self) -> Result<(), PhysicalDeviceError> {
+        if !self.supported_extensions().khr_fragment_shading_rate {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`fragment_shading_rates`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["khr_fragment_shading_rate"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn fragment_shading_rates_unchecked(
+        &self,
+    ) -> Result<Vec<SupportedFragmentShadingRate>, VulkanError> {
+        let fns = self.instance.fns();
+
+        loop {
+            let mut count = 0;
+            (fns.khr_fragment_shading_rate
+                .get_physical_device_fragment_shading_rates_khr)(
+                self.internal_object(),
+                &mut count,
+                ptr::null_mut(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+
+            let mut fragment_shading_rates =
+                vec![ash::vk::PhysicalDeviceFragmentShadingRateKHR::default(); count as usize];
+            let result = (fns
+                .khr_fragment_shading_rate
+                .get_physical_device_fragment_shading_rates_khr)(
+                self.internal_object(),
+                &mut count,
+                fragment_shading_rates.as_mut_ptr(),
+            );
+
+            match result {
+                ash::vk::Result::INCOMPLETE => (),
+                ash::vk::Result::SUCCESS => {
+                    fragment_shading_rates.truncate(count as usize);
+
+                    return Ok(fragment_shading_rates
+                        .into_iter()
+                        .map(|fragment_shading_rate| SupportedFragmentShadingRate {
+                            fragment_size: [
+                                fragment_shading_rate.fragment_size.width,
+                                fragment_shading_rate.fragment_size.height,
+                            ],
+                            sample_counts: fragment_shading_rate.sample_counts.into(),
+                        })
+                        .collect());
+                }
+                err => return Err(VulkanError::from(err)),
+            }
+        }
+    }
+
     /// Returns the properties supported for images with a given image configuration.
     ///
     /// `Some` is returned if the configuration is supported, `None` if it is not.
@@ -934,10 +1351,14 @@ impl PhysicalDevice {
             mut stencil_usage,
             external_memory_handle_type,
             image_view_type,
+            ref drm_format_modifier_info,
             mutable_format: _,
             cube_compatible: _,
             array_2d_compatible: _,
+            view_2d_compatible: _,
             block_texel_view_compatible: _,
+            subsampled: _,
+            protected: _,
             _ne: _,
         } = image_format_info;
 
@@ -1024,6 +1445,24 @@ impl PhysicalDevice {
             image_view_type.validate_physical_device(self)?;
         }
 
+        // VUID-VkPhysicalDeviceImageFormatInfo2-tiling-02249
+        assert_eq!(
+            tiling == ImageTiling::DrmFormatModifier,
+            drm_format_modifier_info.is_some()
+        );
+
+        if let Some(drm_format_modifier_info) = drm_format_modifier_info {
+            if let Sharing::Concurrent(queue_family_indices) = &drm_format_modifier_info.sharing {
+                // VUID-VkPhysicalDeviceImageDrmFormatModifierInfoEXT-sharingMode-02315
+                assert!(queue_family_indices.len() >= 2);
+
+                // VUID-VkPhysicalDeviceImageDrmFormatModifierInfoEXT-sharingMode-02316
+                assert!(queue_family_indices
+                    .iter()
+                    .all(|&index| (index as usize) < self.queue_family_properties().len()));
+            }
+        }
+
         Ok(())
     }
 
@@ -1059,10 +1498,14 @@ impl PhysicalDevice {
                     stencil_usage,
                     external_memory_handle_type,
                     image_view_type,
+                    ref drm_format_modifier_info,
                     mutable_format,
                     cube_compatible,
                     array_2d_compatible,
+                    view_2d_compatible,
                     block_texel_view_compatible,
+                    subsampled,
+                    protected,
                     _ne: _,
                 } = image_format_info;
 
@@ -1072,7 +1515,10 @@ impl PhysicalDevice {
                     mutable_format,
                     cube_compatible,
                     array_2d_compatible,
+                    view_2d_compatible,
                     block_texel_view_compatible,
+                    subsampled,
+                    protected,
                     ..ImageCreateFlags::empty()
                 };
 
@@ -1087,6 +1533,7 @@ impl PhysicalDevice {
                 let mut external_info_vk = None;
                 let mut image_view_info_vk = None;
                 let mut stencil_usage_info_vk = None;
+                let mut drm_format_modifier_info_vk = None;
 
                 if let Some(handle_type) = external_memory_handle_type {
                     let next =
@@ -1121,6 +1568,31 @@ impl PhysicalDevice {
                     info2_vk.p_next = next as *const _ as *const _;
                 }
 
+                if let Some(drm_format_modifier_info) = drm_format_modifier_info {
+                    let (sharing_mode, queue_family_index_count, p_queue_family_indices) =
+                        match &drm_format_modifier_info.sharing {
+                            Sharing::Exclusive => (ash::vk::SharingMode::EXCLUSIVE, 0, ptr::null()),
+                            Sharing::Concurrent(queue_family_indices) => (
+                                ash::vk::SharingMode::CONCURRENT,
+                                queue_family_indices.len() as u32,
+                                queue_family_indices.as_ptr(),
+                            ),
+                        };
+
+                    let next = drm_format_modifier_info_vk.insert(
+                        ash::vk::PhysicalDeviceImageDrmFormatModifierInfoEXT {
+                            drm_format_modifier: drm_format_modifier_info.drm_format_modifier,
+                            sharing_mode,
+                            queue_family_index_count,
+                            p_queue_family_indices,
+                            ..Default::default()
+                        },
+                    );
+
+                    next.p_next = info2_vk.p_next;
+                    info2_vk.p_next = next as *const _ as *const _;
+                }
+
                 /* Output */
 
                 let mut properties2_vk = ash::vk::ImageFormatProperties2::default();
@@ -1204,6 +1676,152 @@ impl PhysicalDevice {
             })
     }
 
+    /// Queries the current memory budget and usage of each memory heap of the physical device.
+    ///
+    /// The returned list has the same length and order as
+    /// [`memory_properties().memory_heaps`](Self::memory_properties). The values are estimates
+    /// that can change at any time, so they should be queried again when they are needed.
+    ///
+    /// The [`ext_memory_budget`](crate::device::DeviceExtensions::ext_memory_budget) extension
+    /// must be supported by the physical device.
+    #[inline]
+    pub fn memory_budget(&self) -> Result<Vec<MemoryHeapBudget>, PhysicalDeviceError> {
+        self.validate_memory_budget()?;
+
+        unsafe { Ok(self.memory_budget_unchecked()) }
+    }
+
+    fn validate_memory_budget(&self) -> Result<(), PhysicalDeviceError> {
+        if !self.supported_extensions().ext_memory_budget {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`memory_budget`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_memory_budget"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if !(self.instance.api_version() >= Version::V1_1
+            || self
+                .instance
+                .enabled_extensions()
+                .khr_get_physical_device_properties2)
+        {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`memory_budget`",
+                requires_one_of: RequiresOneOf {
+                    api_version: Some(Version::V1_1),
+                    instance_extensions: &["khr_get_physical_device_properties2"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn memory_budget_unchecked(&self) -> Vec<MemoryHeapBudget> {
+        let mut memory_budget_vk = ash::vk::PhysicalDeviceMemoryBudgetPropertiesEXT::default();
+        let mut memory_properties2_vk = ash::vk::PhysicalDeviceMemoryProperties2 {
+            p_next: &mut memory_budget_vk as *mut _ as *mut _,
+            ..Default::default()
+        };
+
+        let fns = self.instance.fns();
+
+        if self.instance.api_version() >= Version::V1_1 {
+            (fns.v1_1.get_physical_device_memory_properties2)(
+                self.handle,
+                &mut memory_properties2_vk,
+            );
+        } else {
+            (fns.khr_get_physical_device_properties2
+                .get_physical_device_memory_properties2_khr)(
+                self.handle,
+                &mut memory_properties2_vk,
+            );
+        }
+
+        let heap_count = memory_properties2_vk.memory_properties.memory_heap_count as usize;
+
+        memory_budget_vk.heap_budget[..heap_count]
+            .iter()
+            .zip(&memory_budget_vk.heap_usage[..heap_count])
+            .map(|(&budget, &usage)| MemoryHeapBudget { budget, usage })
+            .collect()
+    }
+
+    /// Retrieves the properties of custom sample locations when rendering with `samples` samples
+    /// per pixel.
+    ///
+    /// The [`ext_sample_locations`](crate::device::DeviceExtensions::ext_sample_locations)
+    /// extension must be supported by the physical device.
+    ///
+    /// The results of this function are cached, so that future calls with the same arguments
+    /// do not need to make a call to the Vulkan API again.
+    #[inline]
+    pub fn multisample_properties(
+        &self,
+        samples: SampleCount,
+    ) -> Result<MultisampleProperties, PhysicalDeviceError> {
+        self.validate_multisample_properties(samples)?;
+
+        unsafe { Ok(self.multisample_properties_unchecked(samples)) }
+    }
+
+    fn validate_multisample_properties(
+        &self,
+        samples: SampleCount,
+    ) -> Result<(), PhysicalDeviceError> {
+        if !self.supported_extensions().ext_sample_locations {
+            return Err(PhysicalDeviceError::RequirementNotMet {
+                required_for: "`multisample_properties`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_sample_locations"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkGetPhysicalDeviceMultisamplePropertiesEXT-samples-parameter
+        samples.validate_physical_device(self)?;
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn multisample_properties_unchecked(
+        &self,
+        samples: SampleCount,
+    ) -> MultisampleProperties {
+        self.multisample_properties
+            .get_or_insert(samples, |&samples| {
+                let fns = self.instance.fns();
+                let mut multisample_properties_vk = ash::vk::MultisamplePropertiesEXT::default();
+                (fns.ext_sample_locations
+                    .get_physical_device_multisample_properties_ext)(
+                    self.handle,
+                    samples.into(),
+                    &mut multisample_properties_vk,
+                );
+
+                MultisampleProperties {
+                    max_sample_location_grid_size: [
+                        multisample_properties_vk
+                            .max_sample_location_grid_size
+                            .width,
+                        multisample_properties_vk
+                            .max_sample_location_grid_size
+                            .height,
+                    ],
+                }
+            })
+    }
+
     /// Queries whether the physical device supports presenting to QNX Screen surfaces from queues
     /// of the given queue family.
     ///
@@ -1596,8 +2214,8 @@ impl PhysicalDevice {
             let next = capabilities_full_screen_exclusive
                 .insert(ash::vk::SurfaceCapabilitiesFullScreenExclusiveEXT::default());
 
-            next.p_next = info2.p_next as *mut _;
-            info2.p_next = next as *const _ as *const _;
+            next.p_next = capabilities2.p_next;
+            capabilities2.p_next = next as *mut _ as *mut _;
         }
 
         if self
@@ -1608,8 +2226,8 @@ impl PhysicalDevice {
             let next =
                 protected_capabilities.insert(ash::vk::SurfaceProtectedCapabilitiesKHR::default());
 
-            next.p_next = info2.p_next as *mut _;
-            info2.p_next = next as *const _ as *const _;
+            next.p_next = capabilities2.p_next;
+            capabilities2.p_next = next as *mut _ as *mut _;
         }
 
         let fns = self.instance.fns();
@@ -1922,6 +2540,13 @@ impl PhysicalDevice {
 
     /// Returns the present modes that are supported by the physical device for the given surface.
     ///
+    /// The supported present modes can depend on the full-screen exclusive mode given in
+    /// `surface_info`. If `surface_info` is not the default value, then the
+    /// [`khr_get_surface_capabilities2`](crate::instance::InstanceExtensions::khr_get_surface_capabilities2)
+    /// instance extension must be enabled, and the
+    /// [`ext_full_screen_exclusive`](crate::device::DeviceExtensions::ext_full_screen_exclusive)
+    /// extension must be supported by the physical device.
+    ///
     /// The results of this function are cached, so that future calls with the same arguments
     /// do not need to make a call to the Vulkan API again.
     ///
@@ -1931,15 +2556,17 @@ impl PhysicalDevice {
     pub fn surface_present_modes<W>(
         &self,
         surface: &Surface<W>,
+        surface_info: SurfaceInfo,
     ) -> Result<impl Iterator<Item = PresentMode>, PhysicalDeviceError> {
-        self.validate_surface_present_modes(surface)?;
+        self.validate_surface_present_modes(surface, &surface_info)?;
 
-        unsafe { Ok(self.surface_present_modes_unchecked(surface)?) }
+        unsafe { Ok(self.surface_present_modes_unchecked(surface, surface_info)?) }
     }
 
     fn validate_surface_present_modes<W>(
         &self,
         surface: &Surface<W>,
+        surface_info: &SurfaceInfo,
     ) -> Result<(), PhysicalDeviceError> {
         if !self.instance.enabled_extensions().khr_surface {
             return Err(PhysicalDeviceError::RequirementNotMet {
@@ -1962,6 +2589,35 @@ impl PhysicalDevice {
             return Err(PhysicalDeviceError::SurfaceNotSupported);
         }
 
+        let &SurfaceInfo {
+            full_screen_exclusive,
+            win32_monitor,
+            _ne: _,
+        } = surface_info;
+
+        if self
+            .instance
+            .enabled_extensions()
+            .khr_get_surface_capabilities2
+            && self.supported_extensions().ext_full_screen_exclusive
+        {
+            // VUID-VkPhysicalDeviceSurfaceInfo2KHR-pNext-02672
+            if (surface.api() == SurfaceApi::Win32
+                && full_screen_exclusive == FullScreenExclusive::ApplicationControlled)
+                != win32_monitor.is_some()
+            {
+                return Err(PhysicalDeviceError::NotSupported);
+            }
+        } else {
+            if full_screen_exclusive != FullScreenExclusive::Default {
+                return Err(PhysicalDeviceError::NotSupported);
+            }
+
+            if win32_monitor.is_some() {
+                return Err(PhysicalDeviceError::NotSupported);
+            }
+        }
+
         Ok(())
     }
 
@@ -1969,41 +2625,119 @@ impl PhysicalDevice {
     pub unsafe fn surface_present_modes_unchecked<W>(
         &self,
         surface: &Surface<W>,
+        surface_info: SurfaceInfo,
     ) -> Result<impl Iterator<Item = PresentMode>, VulkanError> {
         surface
             .surface_present_modes
-            .get_or_try_insert(self.handle, |_| {
+            .get_or_try_insert((self.handle, surface_info), |(_, surface_info)| {
+                let &SurfaceInfo {
+                    full_screen_exclusive,
+                    win32_monitor,
+                    _ne: _,
+                } = surface_info;
+
                 let fns = self.instance.fns();
 
-                let modes = loop {
-                    let mut count = 0;
-                    (fns.khr_surface
-                        .get_physical_device_surface_present_modes_khr)(
-                        self.internal_object(),
-                        surface.internal_object(),
-                        &mut count,
-                        ptr::null_mut(),
-                    )
-                    .result()
-                    .map_err(VulkanError::from)?;
-
-                    let mut modes = Vec::with_capacity(count as usize);
-                    let result = (fns
-                        .khr_surface
-                        .get_physical_device_surface_present_modes_khr)(
-                        self.internal_object(),
-                        surface.internal_object(),
-                        &mut count,
-                        modes.as_mut_ptr(),
-                    );
+                let modes = if self
+                    .instance
+                    .enabled_extensions()
+                    .khr_get_surface_capabilities2
+                    && self.supported_extensions().ext_full_screen_exclusive
+                {
+                    let mut surface_full_screen_exclusive_info =
+                        ash::vk::SurfaceFullScreenExclusiveInfoEXT {
+                            full_screen_exclusive: full_screen_exclusive.into(),
+                            ..Default::default()
+                        };
+
+                    let mut surface_full_screen_exclusive_win32_info =
+                        win32_monitor.map(|win32_monitor| {
+                            ash::vk::SurfaceFullScreenExclusiveWin32InfoEXT {
+                                hmonitor: win32_monitor.0,
+                                ..Default::default()
+                            }
+                        });
+
+                    let mut surface_info2 = ash::vk::PhysicalDeviceSurfaceInfo2KHR {
+                        surface: surface.internal_object(),
+                        ..Default::default()
+                    };
+
+                    surface_full_screen_exclusive_info.p_next = surface_info2.p_next as *mut _;
+                    surface_info2.p_next =
+                        &surface_full_screen_exclusive_info as *const _ as *const _;
+
+                    if let Some(surface_full_screen_exclusive_win32_info) =
+                        surface_full_screen_exclusive_win32_info.as_mut()
+                    {
+                        surface_full_screen_exclusive_win32_info.p_next =
+                            surface_info2.p_next as *mut _;
+                        surface_info2.p_next =
+                            surface_full_screen_exclusive_win32_info as *const _ as *const _;
+                    }
+
+                    loop {
+                        let mut count = 0;
+                        (fns.ext_full_screen_exclusive
+                            .get_physical_device_surface_present_modes2_ext)(
+                            self.internal_object(),
+                            &surface_info2,
+                            &mut count,
+                            ptr::null_mut(),
+                        )
+                        .result()
+                        .map_err(VulkanError::from)?;
 
-                    match result {
-                        ash::vk::Result::SUCCESS => {
-                            modes.set_len(count as usize);
-                            break modes;
+                        let mut modes = Vec::with_capacity(count as usize);
+                        let result = (fns
+                            .ext_full_screen_exclusive
+                            .get_physical_device_surface_present_modes2_ext)(
+                            self.internal_object(),
+                            &surface_info2,
+                            &mut count,
+                            modes.as_mut_ptr(),
+                        );
+
+                        match result {
+                            ash::vk::Result::SUCCESS => {
+                                modes.set_len(count as usize);
+                                break modes;
+                            }
+                            ash::vk::Result::INCOMPLETE => (),
+                            err => return Err(VulkanError::from(err)),
+                        }
+                    }
+                } else {
+                    loop {
+                        let mut count = 0;
+                        (fns.khr_surface
+                            .get_physical_device_surface_present_modes_khr)(
+                            self.internal_object(),
+                            surface.internal_object(),
+                            &mut count,
+                            ptr::null_mut(),
+                        )
+                        .result()
+                        .map_err(VulkanError::from)?;
+
+                        let mut modes = Vec::with_capacity(count as usize);
+                        let result = (fns
+                            .khr_surface
+                            .get_physical_device_surface_present_modes_khr)(
+                            self.internal_object(),
+                            surface.internal_object(),
+                            &mut count,
+                            modes.as_mut_ptr(),
+                        );
+
+                        match result {
+                            ash::vk::Result::SUCCESS => {
+                                modes.set_len(count as usize);
+                                break modes;
+                            }
+                            ash::vk::Result::INCOMPLETE => (),
+                            err => return Err(VulkanError::from(err)),
                         }
-                        ash::vk::Result::INCOMPLETE => (),
-                        err => return Err(VulkanError::from(err)),
                     }
                 };
 
@@ -2648,6 +3382,118 @@ vulkan_bitflags! {
     },
 }
 
+/// A cooperative matrix type that is supported by a physical device, as returned by
+/// [`PhysicalDevice::cooperative_matrix_properties`].
+///
+/// A matrix multiply-add operation computes `D = A * B + C`, where `A` is an `m_size` by `k_size`
+/// matrix, `B` is a `k_size` by `n_size` matrix, and `C` and `D` are `m_size` by `n_size`
+/// matrices.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct CooperativeMatrixProperties {
+    /// The number of rows in matrices `A`, `C` and `D`.
+    pub m_size: u32,
+
+    /// The number of columns in matrices `B`, `C` and `D`.
+    pub n_size: u32,
+
+    /// The number of columns in matrix `A` and rows in matrix `B`.
+    pub k_size: u32,
+
+    /// The component type of matrix `A`.
+    pub a_type: ComponentType,
+
+    /// The component type of matrix `B`.
+    pub b_type: ComponentType,
+
+    /// The component type of matrix `C`.
+    pub c_type: ComponentType,
+
+    /// The component type of matrix `D`.
+    pub d_type: ComponentType,
+
+    /// The scope of all the matrix types.
+    pub scope: Scope,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+vulkan_enum! {
+    /// The type of the components of a cooperative matrix.
+    #[non_exhaustive]
+    ComponentType = ComponentTypeNV(i32);
+
+    /// 16-bit floating point.
+    Float16 = FLOAT16,
+
+    /// 32-bit floating point.
+    Float32 = FLOAT32,
+
+    /// 64-bit floating point.
+    Float64 = FLOAT64,
+
+    /// 8-bit signed integer.
+    Sint8 = SINT8,
+
+    /// 16-bit signed integer.
+    Sint16 = SINT16,
+
+    /// 32-bit signed integer.
+    Sint32 = SINT32,
+
+    /// 64-bit signed integer.
+    Sint64 = SINT64,
+
+    /// 8-bit unsigned integer.
+    Uint8 = UINT8,
+
+    /// 16-bit unsigned integer.
+    Uint16 = UINT16,
+
+    /// 32-bit unsigned integer.
+    Uint32 = UINT32,
+
+    /// 64-bit unsigned integer.
+    Uint64 = UINT64,
+}
+
+vulkan_enum! {
+    /// The set of shader invocations that operate together on a cooperative matrix.
+    #[non_exhaustive]
+    Scope = ScopeNV(i32);
+
+    /// All invocations on the device.
+    Device = DEVICE,
+
+    /// The invocations of a local workgroup.
+    Workgroup = WORKGROUP,
+
+    /// The invocations of a subgroup.
+    Subgroup = SUBGROUP,
+
+    /// All invocations in the queue family.
+    QueueFamily = QUEUE_FAMILY,
+}
+
+vulkan_enum! {
+    /// A clock that timestamps can be read from.
+    #[non_exhaustive]
+    TimeDomain = TimeDomainEXT(i32);
+
+    /// The clock used by timestamp queries, measured in units of
+    /// [`timestamp_period`](crate::device::Properties::timestamp_period) nanoseconds.
+    Device = DEVICE,
+
+    /// The `CLOCK_MONOTONIC` clock of the host, as returned by `clock_gettime`.
+    ClockMonotonic = CLOCK_MONOTONIC,
+
+    /// The `CLOCK_MONOTONIC_RAW` clock of the host, as returned by `clock_gettime`.
+    ClockMonotonicRaw = CLOCK_MONOTONIC_RAW,
+
+    /// The performance counter of the host, as returned by `QueryPerformanceCounter` on Windows.
+    QueryPerformanceCounter = QUERY_PERFORMANCE_COUNTER,
+}
+
 vulkan_enum! {
     /// Specifies how the device clips single point primitives.
     #[non_exhaustive]
@@ -7,18 +7,20 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use super::{Device, DeviceOwned};
+use super::{Device, DeviceOwned, MetalObjectExportError};
 use crate::{
     buffer::BufferAccess,
-    command_buffer::{SemaphoreSubmitInfo, SubmitInfo},
+    command_buffer::{
+        SemaphoreSubmitInfo, SubmitInfo, Win32KeyedMutexAcquireInfo, Win32KeyedMutexReleaseInfo,
+    },
     image::ImageAccess,
     instance::debug::DebugUtilsLabel,
-    macros::vulkan_bitflags,
+    macros::{vulkan_bitflags, vulkan_enum},
     memory::{
         BindSparseInfo, SparseBufferMemoryBind, SparseImageMemoryBind, SparseImageOpaqueMemoryBind,
     },
     swapchain::{PresentInfo, SwapchainPresentInfo},
-    sync::{Fence, FenceState, PipelineStage, SemaphoreState},
+    sync::{Fence, FenceState, PipelineStage, Semaphore, SemaphoreState, SemaphoreType},
     OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
 use parking_lot::{Mutex, MutexGuard};
@@ -26,10 +28,11 @@ use smallvec::SmallVec;
 use std::{
     collections::VecDeque,
     error::Error,
-    ffi::CString,
+    ffi::{c_void, CString},
     fmt::{Display, Error as FmtError, Formatter},
     hash::{Hash, Hasher},
     mem::take,
+    ops::{Deref, DerefMut},
     ptr,
     sync::{atomic::Ordering, Arc},
 };
@@ -40,6 +43,7 @@ use std::{
 pub struct Queue {
     handle: ash::vk::Queue,
     device: Arc<Device>,
+    flags: QueueCreateFlags,
     queue_family_index: u32,
     id: u32, // id within family
 
@@ -52,12 +56,14 @@ impl Queue {
     pub(super) fn from_handle(
         device: Arc<Device>,
         handle: ash::vk::Queue,
+        flags: QueueCreateFlags,
         queue_family_index: u32,
         id: u32,
     ) -> Arc<Self> {
         Arc::new(Queue {
             handle,
             device,
+            flags,
             queue_family_index,
             id,
             state: Mutex::new(Default::default()),
@@ -70,6 +76,12 @@ impl Queue {
         &self.device
     }
 
+    /// Returns the flags that the queue was created with.
+    #[inline]
+    pub fn flags(&self) -> QueueCreateFlags {
+        self.flags
+    }
+
     /// Returns the index of the queue family that this queue belongs to.
     #[inline]
     pub fn queue_family_index(&self) -> u32 {
@@ -91,6 +103,63 @@ impl Queue {
             state: self.state.lock(),
         })
     }
+
+    /// Returns the `MTLCommandQueue` that underlies this queue, when running on top of Metal.
+    ///
+    /// The [`ext_metal_objects`](crate::device::DeviceExtensions::ext_metal_objects) extension
+    /// must be enabled on the device, and the instance must have been created with
+    /// [`export_metal_command_queues`](crate::instance::InstanceCreateInfo::export_metal_command_queues)
+    /// enabled.
+    ///
+    /// The returned object is owned by the queue, and must not be used after the queue has been
+    /// destroyed.
+    #[inline]
+    pub fn export_metal_command_queue(
+        &self,
+    ) -> Result<ash::vk::MTLCommandQueue_id, MetalObjectExportError> {
+        self.validate_export_metal_command_queue()?;
+
+        unsafe { Ok(self.export_metal_command_queue_unchecked()) }
+    }
+
+    fn validate_export_metal_command_queue(&self) -> Result<(), MetalObjectExportError> {
+        if !self.device.enabled_extensions().ext_metal_objects {
+            return Err(MetalObjectExportError::RequirementNotMet {
+                required_for: "`export_metal_command_queue`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_metal_objects"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if !self.device.instance().export_metal_command_queues() {
+            return Err(MetalObjectExportError::ExportNotEnabled);
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn export_metal_command_queue_unchecked(&self) -> ash::vk::MTLCommandQueue_id {
+        let mut export_info_vk = ash::vk::ExportMetalCommandQueueInfoEXT {
+            queue: self.handle,
+            ..Default::default()
+        };
+        let mut info_vk = ash::vk::ExportMetalObjectsInfoEXT {
+            p_next: &mut export_info_vk as *mut _ as *const _,
+            ..Default::default()
+        };
+
+        let fns = self.device.fns();
+        (fns.ext_metal_objects.export_metal_objects_ext)(
+            self.device.internal_object(),
+            &mut info_vk,
+        );
+
+        export_info_vk.mtl_command_queue
+    }
 }
 
 impl Drop for Queue {
@@ -156,6 +225,13 @@ impl<'a> QueueGuard<'a> {
     /// program.
     #[inline]
     pub fn wait_idle(&mut self) -> Result<(), OomError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "queue_wait_idle",
+            queue = %self.queue.device.traced_object_name(&**self.queue),
+        )
+        .entered();
+
         self.state.wait_idle(&self.queue.device, self.queue.handle)
     }
 
@@ -493,6 +569,25 @@ impl<'a> QueueGuard<'a> {
         &mut self,
         present_info: PresentInfo,
     ) -> Result<impl ExactSizeIterator<Item = Result<bool, VulkanError>>, VulkanError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "queue_present",
+            queue = %self.queue.device.traced_object_name(&**self.queue),
+            swapchains = ?present_info
+                .swapchain_infos
+                .iter()
+                .map(|swapchain_info| {
+                    (
+                        self.queue
+                            .device
+                            .traced_object_name(&*swapchain_info.swapchain),
+                        swapchain_info.image_index,
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+        .entered();
+
         let mut wait_semaphores_state = present_info
             .wait_semaphores
             .iter()
@@ -523,9 +618,11 @@ impl<'a> QueueGuard<'a> {
         let mut present_regions_vk: SmallVec<[_; 4]> =
             SmallVec::with_capacity(swapchain_infos.len());
         let mut rectangles_vk: SmallVec<[_; 4]> = SmallVec::with_capacity(swapchain_infos.len());
+        let mut device_masks_vk: SmallVec<[_; 4]> = SmallVec::with_capacity(swapchain_infos.len());
 
         let mut has_present_ids = false;
         let mut has_present_regions = false;
+        let mut has_device_masks = false;
 
         for swapchain_info in swapchain_infos {
             let &SwapchainPresentInfo {
@@ -533,10 +630,12 @@ impl<'a> QueueGuard<'a> {
                 image_index,
                 present_id,
                 ref present_regions,
+                device_mask,
                 _ne: _,
             } = swapchain_info;
 
             swapchains_vk.push(swapchain.internal_object());
+            device_masks_vk.push(if device_mask == 0 { 1 } else { device_mask });
             image_indices_vk.push(image_index);
             present_ids_vk.push(present_id.map_or(0, u64::from));
             present_regions_vk.push(ash::vk::PresentRegionKHR::default());
@@ -554,6 +653,10 @@ impl<'a> QueueGuard<'a> {
             if !present_regions.is_empty() {
                 has_present_regions = true;
             }
+
+            if device_mask != 0 {
+                has_device_masks = true;
+            }
         }
 
         let mut results = vec![ash::vk::Result::SUCCESS; swapchain_infos.len()];
@@ -568,6 +671,7 @@ impl<'a> QueueGuard<'a> {
         };
         let mut present_id_info_vk = None;
         let mut present_region_info_vk = None;
+        let mut device_group_present_info_vk = None;
 
         if has_present_ids {
             let next = present_id_info_vk.insert(ash::vk::PresentIdKHR {
@@ -600,6 +704,18 @@ impl<'a> QueueGuard<'a> {
             info_vk.p_next = next as *const _ as *const _;
         }
 
+        if has_device_masks {
+            let next = device_group_present_info_vk.insert(ash::vk::DeviceGroupPresentInfoKHR {
+                swapchain_count: device_masks_vk.len() as u32,
+                p_device_masks: device_masks_vk.as_ptr(),
+                mode: ash::vk::DeviceGroupPresentModeFlagsKHR::LOCAL,
+                ..Default::default()
+            });
+
+            next.p_next = info_vk.p_next;
+            info_vk.p_next = next as *const _ as *const _;
+        }
+
         let fns = self.queue.device().fns();
         let result = (fns.khr_swapchain.queue_present_khr)(self.queue.handle, &info_vk);
 
@@ -618,14 +734,24 @@ impl<'a> QueueGuard<'a> {
             return Err(VulkanError::from(result));
         }
 
-        // If a presentation results in a loss of full-screen exclusive mode,
-        // signal that to the relevant swapchain.
+        // If a presentation results in a loss of full-screen exclusive mode, or means that the
+        // swapchain should be recreated, signal that to the relevant swapchain.
         for (&result, swapchain_info) in results.iter().zip(&present_info.swapchain_infos) {
-            if result == ash::vk::Result::ERROR_FULL_SCREEN_EXCLUSIVE_MODE_LOST_EXT {
-                swapchain_info
-                    .swapchain
-                    .full_screen_exclusive_held()
-                    .store(false, Ordering::SeqCst);
+            let swapchain = &swapchain_info.swapchain;
+
+            match result {
+                ash::vk::Result::ERROR_FULL_SCREEN_EXCLUSIVE_MODE_LOST_EXT => {
+                    swapchain
+                        .full_screen_exclusive_held()
+                        .store(false, Ordering::SeqCst);
+                }
+                ash::vk::Result::ERROR_OUT_OF_DATE_KHR => {
+                    swapchain.update_needs_recreation(true, false);
+                }
+                ash::vk::Result::SUBOPTIMAL_KHR => {
+                    swapchain.update_needs_recreation(false, true);
+                }
+                _ => (),
             }
         }
 
@@ -644,6 +770,176 @@ impl<'a> QueueGuard<'a> {
         }))
     }
 
+    /// Submits batches of command buffers to the queue, with semaphore wait and signal
+    /// operations.
+    ///
+    /// If the [`synchronization2`](crate::device::Features::synchronization2) feature is enabled
+    /// on the device, the submission is performed with `vkQueueSubmit2`, and the stages of each
+    /// semaphore wait and signal operation are passed through as-is. Otherwise, the stages of
+    /// signal operations are ignored.
+    ///
+    /// # Safety
+    ///
+    /// - The command buffers must not be in use by another submission, unless they were recorded
+    ///   with [`CommandBufferUsage::SimultaneousUse`].
+    /// - Every binary semaphore that is waited on must have a signal operation pending or
+    ///   already be signaled, and must not be waited on by another operation.
+    /// - Every binary semaphore that is signaled must be unsignaled and have no signal operation
+    ///   pending.
+    /// - For timeline semaphores, the signal values must be greater than the current value of
+    ///   the semaphore and the values of all pending signal operations, and must not exceed the
+    ///   [`max_timeline_semaphore_value_difference`] device limit. Values that are not greater
+    ///   than those of earlier signal operations submitted through vulkano are rejected, but the
+    ///   payload may also have been changed outside of vulkano.
+    /// - All resources accessed by the command buffers must be safe to access on this queue.
+    /// - `fence`, if provided, must be unsignaled and not be associated with any other pending
+    ///   queue operation.
+    ///
+    /// [`CommandBufferUsage::SimultaneousUse`]: crate::command_buffer::CommandBufferUsage::SimultaneousUse
+    /// [`max_timeline_semaphore_value_difference`]: crate::device::Properties::max_timeline_semaphore_value_difference
+    #[inline]
+    pub unsafe fn submit(
+        &mut self,
+        submit_infos: impl IntoIterator<Item = SubmitInfo>,
+        fence: Option<Arc<Fence>>,
+    ) -> Result<(), QueueError> {
+        let submit_infos: SmallVec<[_; 4]> = submit_infos.into_iter().collect();
+        self.validate_submit(&submit_infos, fence.as_deref())?;
+
+        Ok(self.submit_unchecked(submit_infos, fence)?)
+    }
+
+    fn validate_submit(
+        &self,
+        submit_infos: &[SubmitInfo],
+        fence: Option<&Fence>,
+    ) -> Result<(), QueueError> {
+        let device = &self.queue.device;
+
+        if let Some(fence) = fence {
+            // VUID-vkQueueSubmit-commonparent
+            assert_eq!(device, fence.device());
+        }
+
+        // The highest value that each timeline semaphore is signaled to by the earlier batches.
+        let mut timeline_signal_values: SmallVec<[(&Arc<Semaphore>, u64); 4]> = SmallVec::new();
+
+        for submit_info in submit_infos {
+            let &SubmitInfo {
+                ref wait_semaphores,
+                ref command_buffers,
+                ref signal_semaphores,
+                protected_submit,
+                ref win32_keyed_mutex_acquires,
+                ref win32_keyed_mutex_releases,
+                _ne: _,
+            } = submit_info;
+
+            // VUID-VkProtectedSubmitInfo-protectedSubmit-01816
+            if protected_submit && !device.enabled_features().protected_memory {
+                return Err(QueueError::RequirementNotMet {
+                    required_for: "`submit_infos.protected_submit` is set",
+                    requires_one_of: RequiresOneOf {
+                        features: &["protected_memory"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            if !(win32_keyed_mutex_acquires.is_empty() && win32_keyed_mutex_releases.is_empty())
+                && !device.enabled_extensions().khr_win32_keyed_mutex
+            {
+                return Err(QueueError::RequirementNotMet {
+                    required_for: "`submit_infos.win32_keyed_mutex_acquires` or \
+                        `submit_infos.win32_keyed_mutex_releases` is not empty",
+                    requires_one_of: RequiresOneOf {
+                        device_extensions: &["khr_win32_keyed_mutex"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            for command_buffer in command_buffers {
+                // VUID-VkSubmitInfo-commonparent
+                assert_eq!(device, command_buffer.device());
+            }
+
+            for semaphore_submit_info in wait_semaphores {
+                let &SemaphoreSubmitInfo {
+                    ref semaphore,
+                    value: _,
+                    stages,
+                    _ne: _,
+                } = semaphore_submit_info;
+
+                // VUID-VkSubmitInfo-commonparent
+                assert_eq!(device, semaphore.device());
+
+                // VUID-VkSemaphoreSubmitInfo-stageMask-parameter
+                stages.validate_device(device)?;
+
+                if !device.enabled_features().synchronization2 {
+                    // VUID-VkSubmitInfo-pWaitDstStageMask-requiredbitmask
+                    if stages.is_empty() {
+                        return Err(QueueError::WaitStagesEmpty);
+                    }
+
+                    // VUID-VkSubmitInfo-pWaitDstStageMask-00078
+                    if stages.host {
+                        return Err(QueueError::WaitStagesContainHost);
+                    }
+                }
+            }
+
+            for semaphore_submit_info in signal_semaphores {
+                let &SemaphoreSubmitInfo {
+                    ref semaphore,
+                    value,
+                    stages,
+                    _ne: _,
+                } = semaphore_submit_info;
+
+                // VUID-VkSubmitInfo-commonparent
+                assert_eq!(device, semaphore.device());
+
+                // VUID-VkSemaphoreSubmitInfo-stageMask-parameter
+                stages.validate_device(device)?;
+
+                if semaphore.semaphore_type() == SemaphoreType::Timeline {
+                    let index = match timeline_signal_values
+                        .iter()
+                        .position(|(s, _)| Arc::ptr_eq(s, semaphore))
+                    {
+                        Some(index) => index,
+                        None => {
+                            let last_value = semaphore.state().last_signal_value();
+                            timeline_signal_values.push((semaphore, last_value));
+                            timeline_signal_values.len() - 1
+                        }
+                    };
+                    let last_value = &mut timeline_signal_values[index].1;
+
+                    // VUID-VkSubmitInfo-pSignalSemaphores-03242
+                    if value <= *last_value {
+                        return Err(QueueError::TimelineSignalValueNotGreater {
+                            value,
+                            last_value: *last_value,
+                        });
+                    }
+
+                    *last_value = value;
+                }
+            }
+
+            // VUID-VkSubmitInfo-pWaitSemaphores-03243
+            // VUID-VkSubmitInfo-pSignalSemaphores-03244
+            // VUID-VkSubmitInfo-pSignalSemaphores-03242
+            // Can't validate, must be ensured by user
+        }
+
+        Ok(())
+    }
+
     #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
     pub unsafe fn submit_unchecked(
         &mut self,
@@ -651,6 +947,18 @@ impl<'a> QueueGuard<'a> {
         fence: Option<Arc<Fence>>,
     ) -> Result<(), VulkanError> {
         let submit_infos: SmallVec<[_; 4]> = submit_infos.into_iter().collect();
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!(
+            "queue_submit",
+            queue = %self.queue.device.traced_object_name(&**self.queue),
+            submit_count = submit_infos.len(),
+            command_buffer_count = submit_infos
+                .iter()
+                .map(|submit_info| submit_info.command_buffers.len())
+                .sum::<usize>(),
+        )
+        .entered();
         let mut submit_infos_state = submit_infos
             .iter()
             .map(|submit_info| {
@@ -695,6 +1003,7 @@ impl<'a> QueueGuard<'a> {
                 wait_semaphore_infos_vk: SmallVec<[ash::vk::SemaphoreSubmitInfo; 4]>,
                 command_buffer_infos_vk: SmallVec<[ash::vk::CommandBufferSubmitInfo; 4]>,
                 signal_semaphore_infos_vk: SmallVec<[ash::vk::SemaphoreSubmitInfo; 4]>,
+                win32_keyed_mutex_info_vk: Option<Win32KeyedMutexInfoVk>,
             }
 
             let (mut submit_info_vk, per_submit_vk): (SmallVec<[_; 4]>, SmallVec<[_; 4]>) =
@@ -705,6 +1014,9 @@ impl<'a> QueueGuard<'a> {
                             ref wait_semaphores,
                             ref command_buffers,
                             ref signal_semaphores,
+                            protected_submit,
+                            ref win32_keyed_mutex_acquires,
+                            ref win32_keyed_mutex_releases,
                             _ne: _,
                         } = submit_info;
 
@@ -713,13 +1025,14 @@ impl<'a> QueueGuard<'a> {
                             .map(|semaphore_submit_info| {
                                 let &SemaphoreSubmitInfo {
                                     ref semaphore,
+                                    value,
                                     stages,
                                     _ne: _,
                                 } = semaphore_submit_info;
 
                                 ash::vk::SemaphoreSubmitInfo {
                                     semaphore: semaphore.internal_object(),
-                                    value: 0, // TODO:
+                                    value,
                                     stage_mask: stages.into(),
                                     device_index: 0, // TODO:
                                     ..Default::default()
@@ -741,13 +1054,14 @@ impl<'a> QueueGuard<'a> {
                             .map(|semaphore_submit_info| {
                                 let &SemaphoreSubmitInfo {
                                     ref semaphore,
+                                    value,
                                     stages,
                                     _ne: _,
                                 } = semaphore_submit_info;
 
                                 ash::vk::SemaphoreSubmitInfo {
                                     semaphore: semaphore.internal_object(),
-                                    value: 0, // TODO:
+                                    value,
                                     stage_mask: stages.into(),
                                     device_index: 0, // TODO:
                                     ..Default::default()
@@ -755,9 +1069,15 @@ impl<'a> QueueGuard<'a> {
                             })
                             .collect();
 
+                        let mut flags = ash::vk::SubmitFlags::empty();
+
+                        if protected_submit {
+                            flags |= ash::vk::SubmitFlags::PROTECTED;
+                        }
+
                         (
                             ash::vk::SubmitInfo2 {
-                                flags: ash::vk::SubmitFlags::empty(), // TODO:
+                                flags,
                                 wait_semaphore_info_count: 0,
                                 p_wait_semaphore_infos: ptr::null(),
                                 command_buffer_info_count: 0,
@@ -770,6 +1090,10 @@ impl<'a> QueueGuard<'a> {
                                 wait_semaphore_infos_vk,
                                 command_buffer_infos_vk,
                                 signal_semaphore_infos_vk,
+                                win32_keyed_mutex_info_vk: Win32KeyedMutexInfoVk::new(
+                                    win32_keyed_mutex_acquires,
+                                    win32_keyed_mutex_releases,
+                                ),
                             },
                         )
                     })
@@ -781,10 +1105,14 @@ impl<'a> QueueGuard<'a> {
                     wait_semaphore_infos_vk,
                     command_buffer_infos_vk,
                     signal_semaphore_infos_vk,
+                    win32_keyed_mutex_info_vk,
                 },
-            ) in (submit_info_vk.iter_mut()).zip(per_submit_vk.iter())
+            ) in (submit_info_vk.iter_mut()).zip(per_submit_vk.iter_mut())
             {
                 *submit_info_vk = ash::vk::SubmitInfo2 {
+                    p_next: win32_keyed_mutex_info_vk
+                        .as_mut()
+                        .map_or(ptr::null(), |info| info.chain(ptr::null())),
                     wait_semaphore_info_count: wait_semaphore_infos_vk.len() as u32,
                     p_wait_semaphore_infos: wait_semaphore_infos_vk.as_ptr(),
                     command_buffer_info_count: command_buffer_infos_vk.len() as u32,
@@ -825,6 +1153,11 @@ impl<'a> QueueGuard<'a> {
                 wait_dst_stage_mask_vk: SmallVec<[ash::vk::PipelineStageFlags; 4]>,
                 command_buffers_vk: SmallVec<[ash::vk::CommandBuffer; 4]>,
                 signal_semaphores_vk: SmallVec<[ash::vk::Semaphore; 4]>,
+                wait_semaphore_values_vk: SmallVec<[u64; 4]>,
+                signal_semaphore_values_vk: SmallVec<[u64; 4]>,
+                timeline_semaphore_submit_info_vk: Option<ash::vk::TimelineSemaphoreSubmitInfo>,
+                protected_submit_info_vk: Option<ash::vk::ProtectedSubmitInfo>,
+                win32_keyed_mutex_info_vk: Option<Win32KeyedMutexInfoVk>,
             }
 
             let (mut submit_info_vk, per_submit_vk): (SmallVec<[_; 4]>, SmallVec<[_; 4]>) =
@@ -835,6 +1168,9 @@ impl<'a> QueueGuard<'a> {
                             ref wait_semaphores,
                             ref command_buffers,
                             ref signal_semaphores,
+                            protected_submit,
+                            ref win32_keyed_mutex_acquires,
+                            ref win32_keyed_mutex_releases,
                             _ne: _,
                         } = submit_info;
 
@@ -843,6 +1179,7 @@ impl<'a> QueueGuard<'a> {
                             .map(|semaphore_submit_info| {
                                 let &SemaphoreSubmitInfo {
                                     ref semaphore,
+                                    value: _,
                                     stages,
                                     _ne: _,
                                 } = semaphore_submit_info;
@@ -861,6 +1198,7 @@ impl<'a> QueueGuard<'a> {
                             .map(|semaphore_submit_info| {
                                 let &SemaphoreSubmitInfo {
                                     ref semaphore,
+                                    value: _,
                                     stages: _,
                                     _ne: _,
                                 } = semaphore_submit_info;
@@ -869,6 +1207,23 @@ impl<'a> QueueGuard<'a> {
                             })
                             .collect();
 
+                        let has_timeline_semaphores = wait_semaphores
+                            .iter()
+                            .chain(signal_semaphores)
+                            .any(|semaphore_submit_info| {
+                                semaphore_submit_info.semaphore.semaphore_type()
+                                    == SemaphoreType::Timeline
+                            });
+                        let (wait_semaphore_values_vk, signal_semaphore_values_vk) =
+                            if has_timeline_semaphores {
+                                (
+                                    wait_semaphores.iter().map(|info| info.value).collect(),
+                                    signal_semaphores.iter().map(|info| info.value).collect(),
+                                )
+                            } else {
+                                (SmallVec::new(), SmallVec::new())
+                            };
+
                         (
                             ash::vk::SubmitInfo {
                                 wait_semaphore_count: 0,
@@ -885,6 +1240,20 @@ impl<'a> QueueGuard<'a> {
                                 wait_dst_stage_mask_vk,
                                 command_buffers_vk,
                                 signal_semaphores_vk,
+                                wait_semaphore_values_vk,
+                                signal_semaphore_values_vk,
+                                timeline_semaphore_submit_info_vk: has_timeline_semaphores
+                                    .then(Default::default),
+                                protected_submit_info_vk: protected_submit.then_some(
+                                    ash::vk::ProtectedSubmitInfo {
+                                        protected_submit: ash::vk::TRUE,
+                                        ..Default::default()
+                                    },
+                                ),
+                                win32_keyed_mutex_info_vk: Win32KeyedMutexInfoVk::new(
+                                    win32_keyed_mutex_acquires,
+                                    win32_keyed_mutex_releases,
+                                ),
                             },
                         )
                     })
@@ -897,10 +1266,36 @@ impl<'a> QueueGuard<'a> {
                     wait_dst_stage_mask_vk,
                     command_buffers_vk,
                     signal_semaphores_vk,
+                    wait_semaphore_values_vk,
+                    signal_semaphore_values_vk,
+                    timeline_semaphore_submit_info_vk,
+                    protected_submit_info_vk,
+                    win32_keyed_mutex_info_vk,
                 },
-            ) in (submit_info_vk.iter_mut()).zip(per_submit_vk.iter())
+            ) in (submit_info_vk.iter_mut()).zip(per_submit_vk.iter_mut())
             {
+                let mut p_next = protected_submit_info_vk
+                    .as_ref()
+                    .map_or(ptr::null(), |info| info as *const _ as *const _);
+
+                if let Some(info) = timeline_semaphore_submit_info_vk.as_mut() {
+                    *info = ash::vk::TimelineSemaphoreSubmitInfo {
+                        p_next,
+                        wait_semaphore_value_count: wait_semaphore_values_vk.len() as u32,
+                        p_wait_semaphore_values: wait_semaphore_values_vk.as_ptr(),
+                        signal_semaphore_value_count: signal_semaphore_values_vk.len() as u32,
+                        p_signal_semaphore_values: signal_semaphore_values_vk.as_ptr(),
+                        ..Default::default()
+                    };
+                    p_next = info as *const _ as *const _;
+                }
+
+                if let Some(info) = win32_keyed_mutex_info_vk.as_mut() {
+                    p_next = info.chain(p_next);
+                }
+
                 *submit_info_vk = ash::vk::SubmitInfo {
+                    p_next,
                     wait_semaphore_count: wait_semaphores_vk.len() as u32,
                     p_wait_semaphores: wait_semaphores_vk.as_ptr(),
                     p_wait_dst_stage_mask: wait_dst_stage_mask_vk.as_ptr(),
@@ -925,13 +1320,33 @@ impl<'a> QueueGuard<'a> {
             .map_err(VulkanError::from)?;
         }
 
-        for (wait_semaphores_state, signal_semaphores_state) in submit_infos_state {
-            for semaphore in wait_semaphores_state {
-                semaphore.add_queue_wait(self.queue);
+        for (submit_info, (wait_semaphores_state, signal_semaphores_state)) in
+            submit_infos.iter().zip(submit_infos_state)
+        {
+            for (semaphore_submit_info, semaphore) in submit_info
+                .wait_semaphores
+                .iter()
+                .zip(wait_semaphores_state)
+            {
+                match semaphore_submit_info.semaphore.semaphore_type() {
+                    SemaphoreType::Binary => semaphore.add_queue_wait(self.queue),
+                    SemaphoreType::Timeline => {
+                        semaphore.add_queue_wait_value(semaphore_submit_info.value)
+                    }
+                }
             }
 
-            for semaphore in signal_semaphores_state {
-                semaphore.add_queue_signal(self.queue);
+            for (semaphore_submit_info, semaphore) in submit_info
+                .signal_semaphores
+                .iter()
+                .zip(signal_semaphores_state)
+            {
+                match semaphore_submit_info.semaphore.semaphore_type() {
+                    SemaphoreType::Binary => semaphore.add_queue_signal(self.queue),
+                    SemaphoreType::Timeline => {
+                        semaphore.add_queue_signal_value(semaphore_submit_info.value)
+                    }
+                }
             }
         }
 
@@ -1114,6 +1529,119 @@ impl<'a> QueueGuard<'a> {
         let fns = self.queue.device.instance().fns();
         (fns.ext_debug_utils.queue_insert_debug_utils_label_ext)(self.queue.handle, &label_info);
     }
+
+    /// Opens a queue debug label region that is closed again when the returned scope is dropped.
+    ///
+    /// The scope dereferences to the queue guard, so work can be submitted within the region
+    /// through it.
+    ///
+    /// The [`ext_debug_utils`](crate::instance::InstanceExtensions::ext_debug_utils) must be
+    /// enabled on the instance.
+    #[inline]
+    pub fn debug_utils_label_scope(
+        &mut self,
+        label_info: DebugUtilsLabel,
+    ) -> Result<QueueDebugUtilsLabelScope<'_, 'a>, QueueError> {
+        self.begin_debug_utils_label(label_info)?;
+
+        Ok(QueueDebugUtilsLabelScope { guard: self })
+    }
+}
+
+/// A queue debug label region, opened by
+/// [`debug_utils_label_scope`](QueueGuard::debug_utils_label_scope), that is closed when it is
+/// dropped.
+pub struct QueueDebugUtilsLabelScope<'g, 'a> {
+    guard: &'g mut QueueGuard<'a>,
+}
+
+impl<'g, 'a> Deref for QueueDebugUtilsLabelScope<'g, 'a> {
+    type Target = QueueGuard<'a>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.guard
+    }
+}
+
+impl<'g, 'a> DerefMut for QueueDebugUtilsLabelScope<'g, 'a> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.guard
+    }
+}
+
+impl<'g, 'a> Drop for QueueDebugUtilsLabelScope<'g, 'a> {
+    #[inline]
+    fn drop(&mut self) {
+        // The region was opened when the scope was created, and the requirements were validated
+        // at that point.
+        unsafe {
+            self.guard.end_debug_utils_label_unchecked();
+        }
+    }
+}
+
+// The arrays of a `VkWin32KeyedMutexAcquireReleaseInfoKHR`, which must not move while the
+// structure points to them.
+struct Win32KeyedMutexInfoVk {
+    acquire_syncs_vk: SmallVec<[ash::vk::DeviceMemory; 4]>,
+    acquire_keys_vk: SmallVec<[u64; 4]>,
+    acquire_timeouts_vk: SmallVec<[u32; 4]>,
+    release_syncs_vk: SmallVec<[ash::vk::DeviceMemory; 4]>,
+    release_keys_vk: SmallVec<[u64; 4]>,
+    info_vk: ash::vk::Win32KeyedMutexAcquireReleaseInfoKHR,
+}
+
+impl Win32KeyedMutexInfoVk {
+    fn new(
+        acquires: &[Win32KeyedMutexAcquireInfo],
+        releases: &[Win32KeyedMutexReleaseInfo],
+    ) -> Option<Self> {
+        if acquires.is_empty() && releases.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            acquire_syncs_vk: acquires
+                .iter()
+                .map(|acquire| acquire.memory.internal_object())
+                .collect(),
+            acquire_keys_vk: acquires.iter().map(|acquire| acquire.key).collect(),
+            acquire_timeouts_vk: acquires
+                .iter()
+                .map(|acquire| {
+                    // `u32::MAX` is `INFINITE`.
+                    acquire.timeout.map_or(u32::MAX, |timeout| {
+                        timeout.as_millis().try_into().unwrap_or(u32::MAX - 1)
+                    })
+                })
+                .collect(),
+            release_syncs_vk: releases
+                .iter()
+                .map(|release| release.memory.internal_object())
+                .collect(),
+            release_keys_vk: releases.iter().map(|release| release.key).collect(),
+            info_vk: Default::default(),
+        })
+    }
+
+    // Fills in the structure and inserts it in front of `p_next`. `self` must not move afterwards.
+    fn chain(&mut self, p_next: *const c_void) -> *const c_void {
+        self.info_vk = ash::vk::Win32KeyedMutexAcquireReleaseInfoKHR {
+            p_next,
+            acquire_count: self.acquire_syncs_vk.len() as u32,
+            p_acquire_syncs: self.acquire_syncs_vk.as_ptr(),
+            p_acquire_keys: self.acquire_keys_vk.as_ptr(),
+            p_acquire_timeouts: self.acquire_timeouts_vk.as_ptr(),
+            release_count: self.release_syncs_vk.len() as u32,
+            p_release_syncs: self.release_syncs_vk.as_ptr(),
+            p_release_keys: self.release_keys_vk.as_ptr(),
+            ..Default::default()
+        };
+
+        &self.info_vk as *const _ as *const _
+    }
 }
 
 #[derive(Debug, Default)]
@@ -1197,14 +1725,25 @@ impl QueueOperation {
             QueueOperation::Submit(submit_infos) => {
                 for submit_info in submit_infos {
                     for semaphore_submit_info in submit_info.wait_semaphores {
-                        semaphore_submit_info.semaphore.state().set_wait_finished();
+                        let mut state = semaphore_submit_info.semaphore.state();
+
+                        match semaphore_submit_info.semaphore.semaphore_type() {
+                            SemaphoreType::Binary => state.set_wait_finished(),
+                            SemaphoreType::Timeline => {
+                                state.set_wait_value_finished(semaphore_submit_info.value)
+                            }
+                        }
                     }
 
                     for semaphore_submit_info in submit_info.signal_semaphores {
-                        semaphore_submit_info
-                            .semaphore
-                            .state()
-                            .set_signal_finished();
+                        let mut state = semaphore_submit_info.semaphore.state();
+
+                        match semaphore_submit_info.semaphore.semaphore_type() {
+                            SemaphoreType::Binary => state.set_signal_finished(),
+                            SemaphoreType::Timeline => {
+                                state.set_signal_value_finished(semaphore_submit_info.value)
+                            }
+                        }
                     }
 
                     for command_buffer in submit_info.command_buffers {
@@ -1317,6 +1856,40 @@ vulkan_bitflags! {
     },
 }
 
+vulkan_bitflags! {
+    /// Flags specifying additional properties of a queue.
+    #[non_exhaustive]
+    QueueCreateFlags = DeviceQueueCreateFlags(u32);
+
+    /// The queue can be used for protected submissions, which operate on protected memory.
+    ///
+    /// The queue family must support the [`protected`](QueueFlags::protected) queue flag, and the
+    /// [`protected_memory`](crate::device::Features::protected_memory) feature must be enabled on
+    /// the device.
+    protected = PROTECTED {
+        api_version: V1_1,
+    },
+}
+
+vulkan_enum! {
+    /// The system-wide priority of a queue, relative to queues of other devices and processes.
+    #[non_exhaustive]
+    QueueGlobalPriority = QueueGlobalPriorityKHR(i32);
+
+    /// The queue has a lower priority than the default.
+    Low = LOW,
+
+    /// The default priority of a queue.
+    Medium = MEDIUM,
+
+    /// The queue has a higher priority than the default.
+    High = HIGH,
+
+    /// The queue has the highest possible priority. This is usually only allowed for
+    /// applications with elevated privileges.
+    Realtime = REALTIME,
+}
+
 /// Error that can happen when submitting work to a queue.
 #[derive(Clone, Debug)]
 pub enum QueueError {
@@ -1326,6 +1899,21 @@ pub enum QueueError {
         required_for: &'static str,
         requires_one_of: RequiresOneOf,
     },
+
+    /// The `synchronization2` feature is not enabled, and the stages of a semaphore wait
+    /// operation are empty.
+    WaitStagesEmpty,
+
+    /// The `synchronization2` feature is not enabled, and the stages of a semaphore wait
+    /// operation contain `host`.
+    WaitStagesContainHost,
+
+    /// A timeline semaphore is signaled with a value that is not greater than the value of the
+    /// semaphore, or of an earlier signal operation.
+    TimelineSignalValueNotGreater {
+        value: u64,
+        last_value: u64,
+    },
 }
 
 impl Error for QueueError {
@@ -1349,6 +1937,22 @@ impl Display for QueueError {
                 "a requirement was not met for: {}; requires one of: {}",
                 required_for, requires_one_of,
             ),
+            Self::WaitStagesEmpty => write!(
+                f,
+                "the `synchronization2` feature is not enabled, and the stages of a semaphore \
+                wait operation are empty",
+            ),
+            Self::WaitStagesContainHost => write!(
+                f,
+                "the `synchronization2` feature is not enabled, and the stages of a semaphore \
+                wait operation contain `host`",
+            ),
+            Self::TimelineSignalValueNotGreater { value, last_value } => write!(
+                f,
+                "a timeline semaphore is signaled with the value {}, which is not greater than \
+                the value {} of the semaphore or of an earlier signal operation",
+                value, last_value,
+            ),
         }
     }
 }
@@ -1370,7 +1974,11 @@ impl From<RequirementNotMet> for QueueError {
 
 #[cfg(test)]
 mod tests {
-    use crate::sync::Fence;
+    use super::QueueError;
+    use crate::{
+        command_buffer::{SemaphoreSubmitInfo, SubmitInfo},
+        sync::{Fence, PipelineStages, Semaphore, SemaphoreCreateInfo, SemaphoreType},
+    };
     use std::{sync::Arc, time::Duration};
 
     #[test]
@@ -1398,4 +2006,101 @@ mod tests {
             assert!(fence.is_signaled().unwrap());
         }
     }
+
+    #[test]
+    fn submit_wait_stages_empty() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let semaphore = Arc::new(Semaphore::from_pool(device).unwrap());
+
+        let res = queue.with(|mut q| unsafe {
+            q.submit(
+                [SubmitInfo {
+                    wait_semaphores: vec![SemaphoreSubmitInfo {
+                        stages: PipelineStages::empty(),
+                        ..SemaphoreSubmitInfo::semaphore(semaphore)
+                    }],
+                    ..Default::default()
+                }],
+                None,
+            )
+        });
+
+        match res {
+            Err(QueueError::WaitStagesEmpty) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn submit_timeline_semaphore() {
+        let (device, queue) = gfx_dev_and_queue!(timeline_semaphore);
+        let semaphore = Arc::new(
+            Semaphore::new(
+                device,
+                SemaphoreCreateInfo {
+                    semaphore_type: SemaphoreType::Timeline,
+                    initial_value: 1,
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        );
+        let signal = |value| SubmitInfo {
+            signal_semaphores: vec![SemaphoreSubmitInfo {
+                value,
+                ..SemaphoreSubmitInfo::semaphore(semaphore.clone())
+            }],
+            ..Default::default()
+        };
+        let wait = |value| SubmitInfo {
+            wait_semaphores: vec![SemaphoreSubmitInfo {
+                value,
+                ..SemaphoreSubmitInfo::semaphore(semaphore.clone())
+            }],
+            ..Default::default()
+        };
+
+        // The signal value must be greater than the initial value.
+        match queue.with(|mut q| unsafe { q.submit([signal(1)], None) }) {
+            Err(QueueError::TimelineSignalValueNotGreater {
+                value: 1,
+                last_value: 1,
+            }) => (),
+            _ => panic!(),
+        }
+
+        // It must also be greater than the value of an earlier batch in the same submission.
+        match queue.with(|mut q| unsafe { q.submit([signal(3), signal(2)], None) }) {
+            Err(QueueError::TimelineSignalValueNotGreater {
+                value: 2,
+                last_value: 3,
+            }) => (),
+            _ => panic!(),
+        }
+
+        // Timeline semaphores can be waited on and signaled multiple times, without the binary
+        // signaled state getting in the way.
+        queue
+            .with(|mut q| unsafe { q.submit([signal(2), wait(2), signal(3), wait(3)], None) })
+            .unwrap();
+        assert_eq!(semaphore.state().last_signal_value(), 3);
+
+        // A pending signal operation counts as well.
+        match queue.with(|mut q| unsafe { q.submit([signal(3)], None) }) {
+            Err(QueueError::TimelineSignalValueNotGreater {
+                value: 3,
+                last_value: 3,
+            }) => (),
+            _ => panic!(),
+        }
+
+        queue.with(|mut q| q.wait_idle()).unwrap();
+        assert_eq!(semaphore.state().last_signal_value(), 3);
+
+        queue
+            .with(|mut q| unsafe { q.submit([signal(4)], None) })
+            .unwrap();
+        queue.with(|mut q| q.wait_idle()).unwrap();
+        assert_eq!(semaphore.state().last_signal_value(), 4);
+    }
 }
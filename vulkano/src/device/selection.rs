@@ -0,0 +1,310 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Choosing a physical device that meets the requirements of an application.
+//!
+//! Most applications don't care much about which physical device they run on, as long as it
+//! supports what they need. [`select_physical_device`] filters the physical devices of an instance
+//! by a set of [`DeviceRequirements`], ranks the remaining ones, and returns the best one along
+//! with the queue families to use.
+//!
+//! Physical devices are ranked by their type first (discrete GPUs before integrated GPUs, before
+//! virtual GPUs, before CPUs), then by the total size of their device-local memory heaps, and
+//! finally by their API version.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use vulkano::{instance::Instance, swapchain::Surface};
+//! use vulkano::device::{
+//!     selection::{select_physical_device_for_surface, DeviceRequirements},
+//!     Device, DeviceCreateInfo, DeviceExtensions,
+//! };
+//!
+//! # let instance: Arc<Instance> = return;
+//! # let surface: Arc<Surface<()>> = return;
+//! let requirements = DeviceRequirements {
+//!     extensions: DeviceExtensions {
+//!         khr_swapchain: true,
+//!         ..DeviceExtensions::empty()
+//!     },
+//!     ..Default::default()
+//! };
+//!
+//! let selected = select_physical_device_for_surface(&instance, &requirements, &surface)
+//!     .expect("no suitable physical device found");
+//!
+//! let (device, queues) = Device::new(
+//!     selected.physical_device.clone(),
+//!     DeviceCreateInfo {
+//!         enabled_extensions: requirements.extensions,
+//!         enabled_features: requirements.features,
+//!         queue_create_infos: selected.queue_create_infos(),
+//!         ..Default::default()
+//!     },
+//! )
+//! .unwrap();
+//! ```
+
+use super::{
+    physical::{PhysicalDevice, PhysicalDeviceType},
+    DeviceExtensions, Features, QueueCreateInfo, QueueFlags,
+};
+use crate::{instance::Instance, swapchain::Surface, DeviceSize, Version, VulkanError};
+use std::{
+    cmp::Reverse,
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+
+/// The requirements that a physical device must meet to be selected.
+#[derive(Clone, Debug)]
+pub struct DeviceRequirements {
+    /// The device extensions that must be supported.
+    ///
+    /// The default value is [`DeviceExtensions::empty()`].
+    pub extensions: DeviceExtensions,
+
+    /// The features that must be supported.
+    ///
+    /// The default value is [`Features::empty()`].
+    pub features: Features,
+
+    /// The operations that must be supported by a single queue family.
+    ///
+    /// The default value has only `graphics` set.
+    pub queue_flags: QueueFlags,
+
+    /// The minimum API version that must be supported.
+    ///
+    /// The default value is [`Version::V1_0`].
+    pub api_version: Version,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for DeviceRequirements {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            extensions: DeviceExtensions::empty(),
+            features: Features::empty(),
+            queue_flags: QueueFlags {
+                graphics: true,
+                ..QueueFlags::empty()
+            },
+            api_version: Version::V1_0,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// A physical device that was selected, along with the queue families to use.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct SelectedPhysicalDevice {
+    /// The physical device that was selected.
+    pub physical_device: Arc<PhysicalDevice>,
+
+    /// The index of a queue family that supports `queue_flags` of the requirements.
+    ///
+    /// If a surface was given, a queue family that also supports presenting to the surface is
+    /// preferred.
+    pub queue_family_index: u32,
+
+    /// If a surface was given, the index of a queue family that supports presenting to it. This
+    /// is equal to `queue_family_index` if that queue family supports presentation.
+    pub present_queue_family_index: Option<u32>,
+}
+
+impl SelectedPhysicalDevice {
+    /// Returns the queue create infos for a single queue of each of the selected queue families,
+    /// to use in [`DeviceCreateInfo::queue_create_infos`](super::DeviceCreateInfo).
+    pub fn queue_create_infos(&self) -> Vec<QueueCreateInfo> {
+        let mut queue_family_indices = vec![self.queue_family_index];
+
+        if let Some(present_queue_family_index) = self.present_queue_family_index {
+            if present_queue_family_index != self.queue_family_index {
+                queue_family_indices.push(present_queue_family_index);
+            }
+        }
+
+        queue_family_indices
+            .into_iter()
+            .map(|queue_family_index| QueueCreateInfo {
+                queue_family_index,
+                ..Default::default()
+            })
+            .collect()
+    }
+}
+
+/// Selects the best physical device of `instance` that meets `requirements`.
+pub fn select_physical_device(
+    instance: &Arc<Instance>,
+    requirements: &DeviceRequirements,
+) -> Result<SelectedPhysicalDevice, DeviceSelectionError> {
+    select(instance, requirements, None::<&Surface<()>>)
+}
+
+/// Selects the best physical device of `instance` that meets `requirements`, and that has a queue
+/// family that can present to `surface`.
+pub fn select_physical_device_for_surface<W>(
+    instance: &Arc<Instance>,
+    requirements: &DeviceRequirements,
+    surface: &Surface<W>,
+) -> Result<SelectedPhysicalDevice, DeviceSelectionError> {
+    select(instance, requirements, Some(surface))
+}
+
+fn select<W>(
+    instance: &Arc<Instance>,
+    requirements: &DeviceRequirements,
+    surface: Option<&Surface<W>>,
+) -> Result<SelectedPhysicalDevice, DeviceSelectionError> {
+    let &DeviceRequirements {
+        ref extensions,
+        ref features,
+        ref queue_flags,
+        api_version,
+        _ne: _,
+    } = requirements;
+
+    instance
+        .enumerate_physical_devices()?
+        .filter(|physical_device| {
+            physical_device.api_version() >= api_version
+                && physical_device.supported_extensions().contains(extensions)
+                && physical_device.supported_features().contains(features)
+        })
+        .filter_map(|physical_device| {
+            let queue_family_properties = physical_device.queue_family_properties();
+            let supports_present = |index: u32| {
+                surface.map_or(false, |surface| {
+                    physical_device
+                        .surface_support(index, surface)
+                        .unwrap_or(false)
+                })
+            };
+
+            let candidates = (0..queue_family_properties.len() as u32).filter(|&index| {
+                queue_family_properties[index as usize]
+                    .queue_flags
+                    .contains(queue_flags)
+            });
+
+            let (queue_family_index, present_queue_family_index) = if surface.is_some() {
+                match candidates.clone().find(|&index| supports_present(index)) {
+                    Some(index) => (index, Some(index)),
+                    None => (
+                        candidates.clone().next()?,
+                        Some(
+                            (0..queue_family_properties.len() as u32)
+                                .find(|&index| supports_present(index))?,
+                        ),
+                    ),
+                }
+            } else {
+                (candidates.clone().next()?, None)
+            };
+
+            Some(SelectedPhysicalDevice {
+                physical_device,
+                queue_family_index,
+                present_queue_family_index,
+            })
+        })
+        .min_by_key(|selected| score(&selected.physical_device))
+        .ok_or(DeviceSelectionError::NoSuitableDevice)
+}
+
+// Lower is better.
+fn score(physical_device: &PhysicalDevice) -> (u32, Reverse<DeviceSize>, Reverse<Version>) {
+    let device_type = match physical_device.properties().device_type {
+        PhysicalDeviceType::DiscreteGpu => 0,
+        PhysicalDeviceType::IntegratedGpu => 1,
+        PhysicalDeviceType::VirtualGpu => 2,
+        PhysicalDeviceType::Cpu => 3,
+        PhysicalDeviceType::Other => 4,
+    };
+
+    let device_local_memory = physical_device
+        .memory_properties()
+        .memory_heaps
+        .iter()
+        .filter(|heap| heap.flags.device_local)
+        .map(|heap| heap.size)
+        .sum();
+
+    (
+        device_type,
+        Reverse(device_local_memory),
+        Reverse(physical_device.api_version()),
+    )
+}
+
+/// Error that can happen when selecting a physical device.
+#[derive(Clone, Debug)]
+pub enum DeviceSelectionError {
+    /// Enumerating the physical devices failed.
+    VulkanError(VulkanError),
+
+    /// None of the physical devices meet the requirements.
+    NoSuitableDevice,
+}
+
+impl Error for DeviceSelectionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::VulkanError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for DeviceSelectionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::VulkanError(_) => write!(f, "enumerating the physical devices failed"),
+            Self::NoSuitableDevice => {
+                write!(f, "none of the physical devices meet the requirements")
+            }
+        }
+    }
+}
+
+impl From<VulkanError> for DeviceSelectionError {
+    fn from(err: VulkanError) -> Self {
+        Self::VulkanError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{select_physical_device, DeviceRequirements, DeviceSelectionError};
+    use crate::Version;
+
+    #[test]
+    fn unsupported_api_version() {
+        let instance = instance!();
+
+        match select_physical_device(
+            &instance,
+            &DeviceRequirements {
+                api_version: Version::major_minor(u32::MAX, 0),
+                ..Default::default()
+            },
+        ) {
+            Err(DeviceSelectionError::NoSuitableDevice) => (),
+            _ => panic!(),
+        }
+    }
+}
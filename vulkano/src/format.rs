@@ -742,6 +742,24 @@ impl FormatProperties {
     }
 }
 
+/// The properties of a format when used with a particular DRM format modifier.
+///
+/// Obtained with
+/// [`PhysicalDevice::drm_format_modifier_properties`](crate::device::physical::PhysicalDevice::drm_format_modifier_properties).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct DrmFormatModifierProperties {
+    /// The DRM format modifier, as defined in the Linux `drm_fourcc.h` header.
+    pub drm_format_modifier: u64,
+
+    /// The number of memory planes that an image with this format and modifier has. This can be
+    /// different from the number of format planes.
+    pub drm_format_modifier_plane_count: u32,
+
+    /// Features available for images with this format and modifier.
+    pub drm_format_modifier_tiling_features: FormatFeatures,
+}
+
 vulkan_bitflags! {
     /// The features supported by a device for an image or buffer with a particular format.
     #[non_exhaustive]
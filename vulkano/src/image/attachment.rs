@@ -24,8 +24,10 @@ use crate::{
         DedicatedAllocation, DeviceMemoryError, ExternalMemoryHandleType,
         ExternalMemoryHandleTypes, MemoryPool,
     },
-    DeviceSize,
+    sync::Sharing,
+    DeviceSize, VulkanObject,
 };
+use smallvec::SmallVec;
 use std::{
     fs::File,
     hash::{Hash, Hasher},
@@ -58,6 +60,12 @@ use std::{
 /// This gives a hint to the Vulkan implementation that it is possible for the image's content to
 /// live exclusively in some cache memory, and that no real memory has to be allocated for it.
 ///
+/// Transient images are allocated from memory with the
+/// [`lazily_allocated`](crate::memory::MemoryPropertyFlags::lazily_allocated) property if the
+/// device has such memory, which is common on tile-based GPUs. Memory of this kind is only
+/// committed as the implementation needs it, which may be never. The amount that is actually
+/// committed can be queried with [`memory_commitment`](AttachmentImage::memory_commitment).
+///
 /// In other words, if you are going to read from the image after drawing to it, use a regular
 /// image. If you don't need to read from it (for example if it's some kind of intermediary color,
 /// or a depth buffer that is only used once) then use a transient image as it may improve
@@ -99,6 +107,7 @@ impl AttachmentImage {
             format,
             ImageUsage::empty(),
             SampleCount::Sample1,
+            SmallVec::new(),
         )
     }
 
@@ -123,6 +132,7 @@ impl AttachmentImage {
             format,
             base_usage,
             SampleCount::Sample1,
+            SmallVec::new(),
         )
     }
 
@@ -137,7 +147,15 @@ impl AttachmentImage {
         samples: SampleCount,
         format: Format,
     ) -> Result<Arc<AttachmentImage>, ImageCreationError> {
-        AttachmentImage::new_impl(device, dimensions, 1, format, ImageUsage::empty(), samples)
+        AttachmentImage::new_impl(
+            device,
+            dimensions,
+            1,
+            format,
+            ImageUsage::empty(),
+            samples,
+            SmallVec::new(),
+        )
     }
 
     /// Same as `multisampled`, but creates an image that can be used as an input attachment.
@@ -155,7 +173,15 @@ impl AttachmentImage {
             ..ImageUsage::empty()
         };
 
-        AttachmentImage::new_impl(device, dimensions, 1, format, base_usage, samples)
+        AttachmentImage::new_impl(
+            device,
+            dimensions,
+            1,
+            format,
+            base_usage,
+            samples,
+            SmallVec::new(),
+        )
     }
 
     /// Same as `new`, but lets you specify additional usages.
@@ -170,7 +196,15 @@ impl AttachmentImage {
         format: Format,
         usage: ImageUsage,
     ) -> Result<Arc<AttachmentImage>, ImageCreationError> {
-        AttachmentImage::new_impl(device, dimensions, 1, format, usage, SampleCount::Sample1)
+        AttachmentImage::new_impl(
+            device,
+            dimensions,
+            1,
+            format,
+            usage,
+            SampleCount::Sample1,
+            SmallVec::new(),
+        )
     }
 
     /// Same as `with_usage`, but creates a multisampled image.
@@ -185,7 +219,15 @@ impl AttachmentImage {
         format: Format,
         usage: ImageUsage,
     ) -> Result<Arc<AttachmentImage>, ImageCreationError> {
-        AttachmentImage::new_impl(device, dimensions, 1, format, usage, samples)
+        AttachmentImage::new_impl(
+            device,
+            dimensions,
+            1,
+            format,
+            usage,
+            samples,
+            SmallVec::new(),
+        )
     }
 
     /// Same as `multisampled_with_usage`, but creates an image with multiple layers.
@@ -201,7 +243,15 @@ impl AttachmentImage {
         format: Format,
         usage: ImageUsage,
     ) -> Result<Arc<AttachmentImage>, ImageCreationError> {
-        AttachmentImage::new_impl(device, dimensions, array_layers, format, usage, samples)
+        AttachmentImage::new_impl(
+            device,
+            dimensions,
+            array_layers,
+            format,
+            usage,
+            samples,
+            SmallVec::new(),
+        )
     }
 
     /// Same as `new`, except that the image can later be sampled.
@@ -225,6 +275,7 @@ impl AttachmentImage {
             format,
             base_usage,
             SampleCount::Sample1,
+            SmallVec::new(),
         )
     }
 
@@ -250,6 +301,7 @@ impl AttachmentImage {
             format,
             base_usage,
             SampleCount::Sample1,
+            SmallVec::new(),
         )
     }
 
@@ -271,7 +323,15 @@ impl AttachmentImage {
             ..ImageUsage::empty()
         };
 
-        AttachmentImage::new_impl(device, dimensions, 1, format, base_usage, samples)
+        AttachmentImage::new_impl(
+            device,
+            dimensions,
+            1,
+            format,
+            base_usage,
+            samples,
+            SmallVec::new(),
+        )
     }
 
     /// Same as `sampled_multisampled`, but creates an image that can be used as an input
@@ -291,7 +351,15 @@ impl AttachmentImage {
             ..ImageUsage::empty()
         };
 
-        AttachmentImage::new_impl(device, dimensions, 1, format, base_usage, samples)
+        AttachmentImage::new_impl(
+            device,
+            dimensions,
+            1,
+            format,
+            base_usage,
+            samples,
+            SmallVec::new(),
+        )
     }
 
     /// Same as `new`, except that the image will be transient.
@@ -318,6 +386,7 @@ impl AttachmentImage {
             format,
             base_usage,
             SampleCount::Sample1,
+            SmallVec::new(),
         )
     }
 
@@ -343,6 +412,7 @@ impl AttachmentImage {
             format,
             base_usage,
             SampleCount::Sample1,
+            SmallVec::new(),
         )
     }
 
@@ -364,7 +434,15 @@ impl AttachmentImage {
             ..ImageUsage::empty()
         };
 
-        AttachmentImage::new_impl(device, dimensions, 1, format, base_usage, samples)
+        AttachmentImage::new_impl(
+            device,
+            dimensions,
+            1,
+            format,
+            base_usage,
+            samples,
+            SmallVec::new(),
+        )
     }
 
     /// Same as `transient_multisampled`, but creates an image that can be used as an input
@@ -384,7 +462,45 @@ impl AttachmentImage {
             ..ImageUsage::empty()
         };
 
-        AttachmentImage::new_impl(device, dimensions, 1, format, base_usage, samples)
+        AttachmentImage::new_impl(
+            device,
+            dimensions,
+            1,
+            format,
+            base_usage,
+            samples,
+            SmallVec::new(),
+        )
+    }
+
+    /// Same as `multisampled_with_usage_with_layers`, but the image can be used concurrently by
+    /// all of the queue families in `queue_family_indices`.
+    ///
+    /// If fewer than two distinct queue families are given, the image is created with exclusive
+    /// sharing instead.
+    #[inline]
+    pub fn with_queue_family_indices(
+        device: Arc<Device>,
+        dimensions: [u32; 2],
+        array_layers: u32,
+        samples: SampleCount,
+        format: Format,
+        usage: ImageUsage,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+    ) -> Result<Arc<AttachmentImage>, ImageCreationError> {
+        let mut queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        queue_family_indices.sort_unstable();
+        queue_family_indices.dedup();
+
+        AttachmentImage::new_impl(
+            device,
+            dimensions,
+            array_layers,
+            format,
+            usage,
+            samples,
+            queue_family_indices,
+        )
     }
 
     // All constructors dispatch to this one.
@@ -395,6 +511,7 @@ impl AttachmentImage {
         format: Format,
         base_usage: ImageUsage,
         samples: SampleCount,
+        queue_family_indices: SmallVec<[u32; 4]>,
     ) -> Result<Arc<AttachmentImage>, ImageCreationError> {
         let physical_device = device.physical_device();
         let device_properties = physical_device.properties();
@@ -431,10 +548,17 @@ impl AttachmentImage {
                     depth_stencil_attachment: is_depth,
                     ..base_usage
                 },
+                sharing: if queue_family_indices.len() >= 2 {
+                    Sharing::Concurrent(queue_family_indices)
+                } else {
+                    Sharing::Exclusive
+                },
                 ..Default::default()
             },
         )?;
 
+        let is_transient = base_usage.transient_attachment;
+
         let mem_reqs = image.memory_requirements();
         let memory = MemoryPool::alloc_from_requirements(
             &device.standard_memory_pool(),
@@ -443,7 +567,15 @@ impl AttachmentImage {
             MappingRequirement::DoNotMap,
             Some(DedicatedAllocation::Image(&image)),
             |t| {
-                if t.property_flags.device_local {
+                if is_transient {
+                    // Lazily allocated memory is only ever offered for transient images, and
+                    // may never need to be backed by actual memory.
+                    if t.property_flags.lazily_allocated {
+                        AllocFromRequirementsFilter::Preferred
+                    } else {
+                        AllocFromRequirementsFilter::Allowed
+                    }
+                } else if t.property_flags.device_local {
                     AllocFromRequirementsFilter::Preferred
                 } else {
                     AllocFromRequirementsFilter::Allowed
@@ -562,6 +694,16 @@ impl AttachmentImage {
     pub fn mem_size(&self) -> DeviceSize {
         self.memory.memory().allocation_size()
     }
+
+    /// Returns the amount of memory that is currently committed to the memory object backing the
+    /// image.
+    ///
+    /// Returns an error if the image was not allocated from lazily allocated memory, which can
+    /// only be the case for transient images.
+    #[inline]
+    pub fn memory_commitment(&self) -> Result<DeviceSize, DeviceMemoryError> {
+        self.memory.memory().commitment()
+    }
 }
 
 unsafe impl<A> ImageAccess for AttachmentImage<A>
@@ -610,6 +752,15 @@ unsafe impl<A> DeviceOwned for AttachmentImage<A> {
     }
 }
 
+unsafe impl<A> VulkanObject for AttachmentImage<A> {
+    type Object = ash::vk::Image;
+
+    #[inline]
+    fn internal_object(&self) -> ash::vk::Image {
+        self.image.internal_object()
+    }
+}
+
 unsafe impl<P, A> ImageContent<P> for AttachmentImage<A>
 where
     A: MemoryPoolAlloc,
@@ -642,7 +793,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::AttachmentImage;
-    use crate::format::Format;
+    use crate::{format::Format, memory::DeviceMemoryError};
 
     #[test]
     fn create_regular() {
@@ -656,6 +807,16 @@ mod tests {
         let _img = AttachmentImage::transient(device, [32, 32], Format::R8G8B8A8_UNORM).unwrap();
     }
 
+    #[test]
+    fn memory_commitment_not_lazily_allocated() {
+        let (device, _) = gfx_dev_and_queue!();
+        let img = AttachmentImage::new(device, [32, 32], Format::R8G8B8A8_UNORM).unwrap();
+        assert!(matches!(
+            img.memory_commitment(),
+            Err(DeviceMemoryError::NotLazilyAllocated)
+        ));
+    }
+
     #[test]
     fn d16_unorm_always_supported() {
         let (device, _) = gfx_dev_and_queue!();
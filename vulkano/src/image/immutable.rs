@@ -7,6 +7,8 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+#[cfg(feature = "transcode")]
+use super::transcode::{needs_transcode, Transcoder};
 use super::{
     sys::UnsafeImage, traits::ImageContent, ImageAccess, ImageCreateFlags, ImageCreationError,
     ImageDescriptorLayouts, ImageDimensions, ImageInner, ImageLayout, ImageSubresourceLayers,
@@ -30,7 +32,7 @@ use crate::{
     },
     sampler::Filter,
     sync::Sharing,
-    DeviceSize, OomError,
+    DeviceSize, OomError, VulkanObject,
 };
 use smallvec::{smallvec, SmallVec};
 use std::{
@@ -115,7 +117,9 @@ impl ImmutableImage {
         queue_family_indices: impl IntoIterator<Item = u32>,
     ) -> Result<(Arc<ImmutableImage>, Arc<ImmutableImageInitialization>), ImmutableImageCreationError>
     {
-        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        let mut queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        queue_family_indices.sort_unstable();
+        queue_family_indices.dedup();
 
         let image = UnsafeImage::new(
             device.clone(),
@@ -137,6 +141,8 @@ impl ImmutableImage {
                 cube_compatible: flags.cube_compatible,
                 array_2d_compatible: flags.array_2d_compatible,
                 block_texel_view_compatible: flags.block_texel_view_compatible,
+                alias: flags.alias,
+                subsampled: flags.subsampled,
                 ..Default::default()
             },
         )?;
@@ -290,6 +296,78 @@ impl ImmutableImage {
 
         Ok(image)
     }
+
+    /// Construct an ImmutableImage from compressed image data, decoding it on the CPU first if
+    /// the device can't sample from images of `format`.
+    ///
+    /// If [`needs_transcode`] returns `false`, this is equivalent to
+    /// [`from_iter`](ImmutableImage::from_iter). Otherwise, `data` is decoded with `transcoder`,
+    /// and the image is created with the format returned by [`Transcoder::target_format`]
+    /// instead of `format`.
+    ///
+    /// `data` must contain only the first mip level; if `mip_levels` specifies more than one,
+    /// the remaining levels are generated after upload, as with `from_buffer`.
+    #[cfg(feature = "transcode")]
+    pub fn from_compressed_data<L, A>(
+        data: &[u8],
+        dimensions: ImageDimensions,
+        mip_levels: MipmapsCount,
+        format: Format,
+        transcoder: &dyn Transcoder,
+        command_buffer_builder: &mut AutoCommandBufferBuilder<L, A>,
+    ) -> Result<Arc<Self>, ImmutableImageCreationError>
+    where
+        A: CommandBufferAllocator,
+    {
+        if !needs_transcode(command_buffer_builder.device(), format) {
+            return ImmutableImage::from_iter(
+                data.iter().copied(),
+                dimensions,
+                mip_levels,
+                format,
+                command_buffer_builder,
+            );
+        }
+
+        let target_format = transcoder
+            .target_format(format)
+            .ok_or(ImmutableImageCreationError::TranscodeNotSupported { format })?;
+
+        let required_size = BufferImageCopy {
+            image_subresource: ImageSubresourceLayers::from_parameters(
+                format,
+                dimensions.array_layers(),
+            ),
+            image_extent: dimensions.width_height_depth(),
+            ..Default::default()
+        }
+        .buffer_copy_size(format);
+
+        if (data.len() as DeviceSize) < required_size {
+            return Err(ImmutableImageCreationError::SourceTooSmall {
+                source_size: data.len() as DeviceSize,
+                required_size,
+            });
+        }
+
+        let decoded = transcoder
+            .transcode(
+                data,
+                format,
+                dimensions.width_height_depth(),
+                dimensions.array_layers(),
+                target_format,
+            )
+            .map_err(|err| ImmutableImageCreationError::TranscodeFailed(err.into()))?;
+
+        ImmutableImage::from_iter(
+            decoded,
+            dimensions,
+            mip_levels,
+            target_format,
+            command_buffer_builder,
+        )
+    }
 }
 
 unsafe impl<A> DeviceOwned for ImmutableImage<A> {
@@ -298,6 +376,15 @@ unsafe impl<A> DeviceOwned for ImmutableImage<A> {
     }
 }
 
+unsafe impl<A> VulkanObject for ImmutableImage<A> {
+    type Object = ash::vk::Image;
+
+    #[inline]
+    fn internal_object(&self) -> ash::vk::Image {
+        self.image.internal_object()
+    }
+}
+
 unsafe impl<A> ImageAccess for ImmutableImage<A>
 where
     A: MemoryPoolAlloc,
@@ -428,6 +515,18 @@ pub enum ImmutableImageCreationError {
         source_size: DeviceSize,
         required_size: DeviceSize,
     },
+
+    /// The device does not support the format of the source data, and decoding it on the CPU
+    /// failed.
+    #[cfg(feature = "transcode")]
+    TranscodeFailed(Arc<dyn Error + Send + Sync>),
+
+    /// The device does not support the format of the source data, and the transcoder can't decode
+    /// it.
+    #[cfg(feature = "transcode")]
+    TranscodeNotSupported {
+        format: Format,
+    },
 }
 
 impl Error for ImmutableImageCreationError {
@@ -436,6 +535,8 @@ impl Error for ImmutableImageCreationError {
             Self::ImageCreationError(err) => Some(err),
             Self::DeviceMemoryAllocationError(err) => Some(err),
             Self::CommandBufferBeginError(err) => Some(err),
+            #[cfg(feature = "transcode")]
+            Self::TranscodeFailed(err) => Some(err.as_ref()),
             _ => None,
         }
     }
@@ -456,6 +557,19 @@ impl Display for ImmutableImageCreationError {
                 "the size of the provided source data ({} bytes) is less than the required size for an image of the given format and dimensions ({} bytes)",
                 source_size, required_size,
             ),
+            #[cfg(feature = "transcode")]
+            Self::TranscodeFailed(_) => write!(
+                f,
+                "the device does not support the format of the source data, and decoding it on \
+                the CPU failed",
+            ),
+            #[cfg(feature = "transcode")]
+            Self::TranscodeNotSupported { format } => write!(
+                f,
+                "the device does not support the format of the source data ({:?}), and the \
+                transcoder can't decode it",
+                format,
+            ),
         }
     }
 }
@@ -62,8 +62,10 @@ use crate::{
     format::Format,
     macros::{vulkan_bitflags, vulkan_enum},
     memory::{ExternalMemoryHandleType, ExternalMemoryProperties},
+    sync::Sharing,
     DeviceSize,
 };
+use smallvec::SmallVec;
 use std::{cmp, ops::Range};
 
 mod aspect;
@@ -74,6 +76,8 @@ mod storage;
 pub mod swapchain; // TODO: make private
 pub mod sys;
 pub mod traits;
+#[cfg(feature = "transcode")]
+pub mod transcode;
 mod usage;
 pub mod view;
 
@@ -249,6 +253,12 @@ vulkan_bitflags! {
         device_extensions: [khr_maintenance1],
     },
 
+    /// For 3D images, allows creation of an image view of type `Dim2d` that can be used in a
+    /// storage image or sampled image descriptor.
+    view_2d_compatible = TYPE_2D_VIEW_COMPATIBLE_EXT {
+        device_extensions: [ext_image_2d_view_of_3d],
+    },
+
     /// For images with a compressed format, allows creation of an image view with an uncompressed
     /// format, where each texel in the view will correspond to a compressed texel block in the
     /// image.
@@ -258,6 +268,32 @@ vulkan_bitflags! {
         api_version: V1_1,
         device_extensions: [khr_maintenance1],
     },
+
+    /// The image can share memory with other images that were created with identical parameters
+    /// and also have this flag, and the contents of the memory are interpreted consistently
+    /// between them.
+    alias = ALIAS {
+        api_version: V1_1,
+        device_extensions: [khr_bind_memory2],
+    },
+
+    /// The image can be used as an attachment in a render pass that has a fragment density map
+    /// attachment, and may then be rendered to at a lower resolution in some regions.
+    ///
+    /// Requires a 2D image with optimal tiling and a single mip level, that is not
+    /// `cube_compatible`.
+    subsampled = SUBSAMPLED_EXT {
+        device_extensions: [ext_fragment_density_map],
+    },
+
+    /// The image is a protected image, which must be bound to protected memory and can only be
+    /// accessed by protected command buffers.
+    ///
+    /// Requires the [`protected_memory`](crate::device::Features::protected_memory) feature to be
+    /// enabled.
+    protected = PROTECTED {
+        api_version: V1_1,
+    },
 }
 
 vulkan_enum! {
@@ -286,12 +322,11 @@ vulkan_enum! {
     // TODO: document
     Linear = LINEAR,
 
-    /*
-    // TODO: document
+    /// The memory layout is defined by a Linux DRM format modifier, so that the image can be
+    /// shared with other APIs and processes, for example through a dma-buf.
     DrmFormatModifier = DRM_FORMAT_MODIFIER_EXT {
         device_extensions: [ext_image_drm_format_modifier],
     },
-     */
 }
 
 /// The dimensions of an image.
@@ -705,6 +740,14 @@ pub struct ImageFormatInfo {
     /// The default value is `None`.
     pub image_view_type: Option<ImageViewType>,
 
+    /// The DRM format modifier and sharing mode that the image will have.
+    ///
+    /// This must be `Some` if `tiling` is [`ImageTiling::DrmFormatModifier`], and `None`
+    /// otherwise.
+    ///
+    /// The default value is `None`.
+    pub drm_format_modifier_info: Option<ImageDrmFormatModifierInfo>,
+
     /// The `mutable_format` that the image will have.
     ///
     /// The default value is `false`.
@@ -720,11 +763,26 @@ pub struct ImageFormatInfo {
     /// The default value is `false`.
     pub array_2d_compatible: bool,
 
+    /// The `view_2d_compatible` that the image will have.
+    ///
+    /// The default value is `false`.
+    pub view_2d_compatible: bool,
+
     /// The `block_texel_view_compatible` that the image will have.
     ///
     /// The default value is `false`.
     pub block_texel_view_compatible: bool,
 
+    /// The `subsampled` that the image will have.
+    ///
+    /// The default value is `false`.
+    pub subsampled: bool,
+
+    /// The `protected` that the image will have.
+    ///
+    /// The default value is `false`.
+    pub protected: bool,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -739,10 +797,42 @@ impl Default for ImageFormatInfo {
             stencil_usage: ImageUsage::empty(),
             external_memory_handle_type: None,
             image_view_type: None,
+            drm_format_modifier_info: None,
             mutable_format: false,
             cube_compatible: false,
             array_2d_compatible: false,
+            view_2d_compatible: false,
             block_texel_view_compatible: false,
+            subsampled: false,
+            protected: false,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// The DRM format modifier of an image, to query the properties of images with
+/// [`ImageTiling::DrmFormatModifier`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ImageDrmFormatModifierInfo {
+    /// The DRM format modifier that the image will have.
+    ///
+    /// The default value is `0`.
+    pub drm_format_modifier: u64,
+
+    /// The `sharing` that the image will have.
+    ///
+    /// The default value is [`Sharing::Exclusive`].
+    pub sharing: Sharing<SmallVec<[u32; 4]>>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for ImageDrmFormatModifierInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            drm_format_modifier: 0,
+            sharing: Sharing::Exclusive,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -9,7 +9,8 @@
 
 use super::{
     sys::UnsafeImage, traits::ImageContent, ImageAccess, ImageCreateFlags, ImageCreationError,
-    ImageDescriptorLayouts, ImageDimensions, ImageInner, ImageLayout, ImageUsage,
+    ImageDescriptorLayouts, ImageDimensions, ImageInner, ImageLayout, ImageTiling, ImageUsage,
+    MipmapsCount,
 };
 use crate::{
     device::{Device, DeviceOwned, Queue},
@@ -17,14 +18,15 @@ use crate::{
     image::{sys::UnsafeImageCreateInfo, view::ImageView},
     memory::{
         pool::{
-            alloc_dedicated_with_exportable_fd, AllocFromRequirementsFilter, AllocLayout,
-            MappingRequirement, MemoryPoolAlloc, PotentialDedicatedAllocation, StandardMemoryPool,
+            alloc_dedicated_with_exportable_fd, alloc_dedicated_with_priority,
+            AllocFromRequirementsFilter, AllocLayout, MappingRequirement, MemoryPoolAlloc,
+            PotentialDedicatedAllocation, StandardMemoryPool,
         },
         DedicatedAllocation, DeviceMemoryError, ExternalMemoryHandleType,
         ExternalMemoryHandleTypes, MemoryPool,
     },
     sync::Sharing,
-    DeviceSize,
+    DeviceSize, VulkanObject,
 };
 use smallvec::SmallVec;
 use std::{
@@ -96,13 +98,41 @@ impl StorageImage {
         flags: ImageCreateFlags,
         queue_family_indices: impl IntoIterator<Item = u32>,
     ) -> Result<Arc<StorageImage>, ImageCreationError> {
-        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        StorageImage::with_mip_levels(
+            device,
+            dimensions,
+            MipmapsCount::One,
+            format,
+            usage,
+            flags,
+            queue_family_indices,
+        )
+    }
 
-        let image = UnsafeImage::new(
-            device.clone(),
+    /// Same as `with_usage`, but allows specifying the number of mip levels.
+    pub fn with_mip_levels(
+        device: Arc<Device>,
+        dimensions: ImageDimensions,
+        mip_levels: impl Into<MipmapsCount>,
+        format: Format,
+        usage: ImageUsage,
+        flags: ImageCreateFlags,
+        queue_family_indices: impl IntoIterator<Item = u32>,
+    ) -> Result<Arc<StorageImage>, ImageCreationError> {
+        let mut queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        queue_family_indices.sort_unstable();
+        queue_family_indices.dedup();
+
+        StorageImage::new_with_create_info(
+            device,
             UnsafeImageCreateInfo {
                 dimensions,
                 format: Some(format),
+                mip_levels: match mip_levels.into() {
+                    MipmapsCount::Specific(num) => num,
+                    MipmapsCount::Log2 => dimensions.max_mip_levels(),
+                    MipmapsCount::One => 1,
+                },
                 usage,
                 sharing: if queue_family_indices.len() >= 2 {
                     Sharing::Concurrent(queue_family_indices)
@@ -112,16 +142,47 @@ impl StorageImage {
                 mutable_format: flags.mutable_format,
                 cube_compatible: flags.cube_compatible,
                 array_2d_compatible: flags.array_2d_compatible,
+                view_2d_compatible: flags.view_2d_compatible,
                 block_texel_view_compatible: flags.block_texel_view_compatible,
+                alias: flags.alias,
+                subsampled: flags.subsampled,
                 ..Default::default()
             },
-        )?;
+        )
+    }
+
+    /// Creates a new image from a full `UnsafeImageCreateInfo`, and allocates and binds memory
+    /// for it.
+    ///
+    /// This gives control over all parameters of the image, such as the number of samples, the
+    /// tiling and the initial layout. The image is transitioned from `initial_layout` to the
+    /// `General` layout when it is first used in a command buffer.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.external_memory_handle_types` is not empty. Use
+    ///   [`new_with_exportable_fd`](StorageImage::new_with_exportable_fd) to create an image with
+    ///   exportable memory.
+    pub fn new_with_create_info(
+        device: Arc<Device>,
+        create_info: UnsafeImageCreateInfo,
+    ) -> Result<Arc<StorageImage>, ImageCreationError> {
+        assert!(create_info.external_memory_handle_types.is_empty());
+
+        let dimensions = create_info.dimensions;
+        let alloc_layout = if create_info.tiling == ImageTiling::Linear {
+            AllocLayout::Linear
+        } else {
+            AllocLayout::Optimal
+        };
+
+        let image = UnsafeImage::new(device.clone(), create_info)?;
 
         let mem_reqs = image.memory_requirements();
         let memory = MemoryPool::alloc_from_requirements(
             &device.standard_memory_pool(),
             &mem_reqs,
-            AllocLayout::Optimal,
+            alloc_layout,
             MappingRequirement::DoNotMap,
             Some(DedicatedAllocation::Image(&image)),
             |t| {
@@ -144,6 +205,54 @@ impl StorageImage {
         }))
     }
 
+    /// Same as [`new_with_create_info`](StorageImage::new_with_create_info), but the image gets a
+    /// dedicated memory allocation with the given `priority`, between 0.0 and 1.0. Images with a
+    /// higher priority are less likely to be moved out of device-local memory when it is
+    /// oversubscribed.
+    ///
+    /// The [`memory_priority`](crate::device::Features::memory_priority) feature must be enabled
+    /// on the device.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.external_memory_handle_types` is not empty.
+    pub fn new_with_priority(
+        device: Arc<Device>,
+        create_info: UnsafeImageCreateInfo,
+        priority: f32,
+    ) -> Result<Arc<StorageImage>, ImageCreationError> {
+        assert!(create_info.external_memory_handle_types.is_empty());
+
+        let dimensions = create_info.dimensions;
+        let image = UnsafeImage::new(device.clone(), create_info)?;
+
+        let mem_reqs = image.memory_requirements();
+        let memory = alloc_dedicated_with_priority(
+            device,
+            &mem_reqs,
+            MappingRequirement::DoNotMap,
+            DedicatedAllocation::Image(&image),
+            |t| {
+                if t.property_flags.device_local {
+                    AllocFromRequirementsFilter::Preferred
+                } else {
+                    AllocFromRequirementsFilter::Allowed
+                }
+            },
+            priority,
+        )?;
+        debug_assert!((memory.offset() % mem_reqs.alignment) == 0);
+        unsafe {
+            image.bind_memory(memory.memory(), memory.offset())?;
+        }
+
+        Ok(Arc::new(StorageImage {
+            image,
+            memory,
+            dimensions,
+        }))
+    }
+
     pub fn new_with_exportable_fd(
         device: Arc<Device>,
         dimensions: ImageDimensions,
@@ -152,7 +261,9 @@ impl StorageImage {
         flags: ImageCreateFlags,
         queue_family_indices: impl IntoIterator<Item = u32>,
     ) -> Result<Arc<StorageImage>, ImageCreationError> {
-        let queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        let mut queue_family_indices: SmallVec<[_; 4]> = queue_family_indices.into_iter().collect();
+        queue_family_indices.sort_unstable();
+        queue_family_indices.dedup();
 
         let image = UnsafeImage::new(
             device.clone(),
@@ -172,7 +283,10 @@ impl StorageImage {
                 mutable_format: flags.mutable_format,
                 cube_compatible: flags.cube_compatible,
                 array_2d_compatible: flags.array_2d_compatible,
+                view_2d_compatible: flags.view_2d_compatible,
                 block_texel_view_compatible: flags.block_texel_view_compatible,
+                alias: flags.alias,
+                subsampled: flags.subsampled,
                 ..Default::default()
             },
         )?;
@@ -264,6 +378,18 @@ where
     }
 }
 
+unsafe impl<A> VulkanObject for StorageImage<A>
+where
+    A: MemoryPool,
+{
+    type Object = ash::vk::Image;
+
+    #[inline]
+    fn internal_object(&self) -> ash::vk::Image {
+        self.image.internal_object()
+    }
+}
+
 unsafe impl<A> ImageAccess for StorageImage<A>
 where
     A: MemoryPool,
@@ -274,7 +400,7 @@ where
             first_layer: 0,
             num_layers: self.dimensions.array_layers(),
             first_mipmap_level: 0,
-            num_mipmap_levels: 1,
+            num_mipmap_levels: self.image.mip_levels(),
         }
     }
 
@@ -331,9 +457,10 @@ mod tests {
     use crate::{
         format::Format,
         image::{
-            view::ImageViewCreationError, ImageAccess, ImageCreationError, ImageDimensions,
-            ImageUsage,
+            sys::UnsafeImageCreateInfo, view::ImageViewCreationError, ImageAccess,
+            ImageCreationError, ImageDimensions, ImageUsage, SampleCount,
         },
+        sync::Sharing,
     };
 
     #[test]
@@ -352,6 +479,51 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn create_duplicate_queue_families() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let img = StorageImage::new(
+            device,
+            ImageDimensions::Dim2d {
+                width: 32,
+                height: 32,
+                array_layers: 1,
+            },
+            Format::R8G8B8A8_UNORM,
+            [queue.queue_family_index(), queue.queue_family_index()],
+        )
+        .unwrap();
+        assert_eq!(img.inner().image.sharing(), &Sharing::Exclusive);
+    }
+
+    #[test]
+    fn create_with_create_info() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let img = StorageImage::new_with_create_info(
+            device,
+            UnsafeImageCreateInfo {
+                dimensions: ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 6,
+                },
+                format: Some(Format::R8G8B8A8_UNORM),
+                mip_levels: 6,
+                usage: ImageUsage {
+                    transfer_dst: true,
+                    sampled: true,
+                    ..ImageUsage::empty()
+                },
+                cube_compatible: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(img.mip_levels(), 6);
+        assert_eq!(img.samples(), SampleCount::Sample1);
+        assert!(img.inner().image.cube_compatible());
+    }
+
     #[test]
     fn create_general_purpose_image_view() {
         let (_device, queue) = gfx_dev_and_queue!();
@@ -7,15 +7,23 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use super::{traits::ImageContent, ImageAccess, ImageDescriptorLayouts, ImageInner, ImageLayout};
+use super::{
+    traits::ImageContent,
+    view::{ImageView, ImageViewCreationError},
+    ImageAccess, ImageDescriptorLayouts, ImageInner, ImageLayout, ImageUsage,
+};
 use crate::{
     device::{Device, DeviceOwned},
-    swapchain::{Swapchain, SwapchainAbstract},
+    swapchain::{ColorSpace, Swapchain, SwapchainAbstract},
+    sync::Sharing,
     OomError,
 };
+use parking_lot::Mutex;
+use smallvec::SmallVec;
 use std::{
+    any::Any,
     hash::{Hash, Hasher},
-    sync::Arc,
+    sync::{Arc, Weak},
 };
 
 /// An image that is part of a swapchain.
@@ -35,6 +43,11 @@ use std::{
 pub struct SwapchainImage<W> {
     swapchain: Arc<Swapchain<W>>,
     image_index: u32,
+
+    // The default view of the image, if one was created with `view` and is still alive.
+    // This is a weak reference, because the view keeps the image alive. It is type-erased so
+    // that the struct doesn't need the bounds of `ImageView<SwapchainImage<W>>`.
+    view: Mutex<Weak<dyn Any + Send + Sync>>,
 }
 
 impl<W> SwapchainImage<W>
@@ -51,6 +64,7 @@ where
         Ok(Arc::new(SwapchainImage {
             swapchain,
             image_index,
+            view: Mutex::new(Weak::<()>::new()),
         }))
     }
 
@@ -59,6 +73,28 @@ where
         &self.swapchain
     }
 
+    /// Returns the index of this image within the swapchain.
+    pub fn image_index(&self) -> u32 {
+        self.image_index
+    }
+
+    /// Returns the usage the image was created with. This is the `image_usage` that was passed
+    /// when creating the swapchain.
+    pub fn usage(&self) -> ImageUsage {
+        *self.my_image().image.usage()
+    }
+
+    /// Returns the sharing mode the image was created with. This is the `image_sharing` that
+    /// was passed when creating the swapchain.
+    pub fn sharing(&self) -> &Sharing<SmallVec<[u32; 4]>> {
+        self.my_image().image.sharing()
+    }
+
+    /// Returns the color space that the image is presented with.
+    pub fn color_space(&self) -> ColorSpace {
+        self.swapchain.image_color_space()
+    }
+
     fn my_image(&self) -> ImageInner<'_> {
         self.swapchain.raw_image(self.image_index).unwrap()
     }
@@ -121,6 +157,44 @@ where
     }
 }
 
+impl<W> SwapchainImage<W>
+where
+    W: Send + Sync + 'static,
+{
+    /// Returns a view of the whole image, with the default parameters of
+    /// [`ImageView::new_default`].
+    ///
+    /// The image only holds a weak reference to the view, because the view keeps the image
+    /// alive. The same view is returned for as long as something else holds it, such as the views
+    /// returned by [`Swapchain::new_with_views`] and [`Swapchain::recreate_with_views`]. If
+    /// nothing does, a new view is created.
+    pub fn view(self: &Arc<Self>) -> Result<Arc<ImageView<Self>>, ImageViewCreationError> {
+        let mut view = self.view.lock();
+
+        if let Some(existing) = view
+            .upgrade()
+            .and_then(|existing| existing.downcast::<ImageView<Self>>().ok())
+        {
+            return Ok(existing);
+        }
+
+        let new = ImageView::new_default(self.clone())?;
+        *view = Arc::downgrade(&(new.clone() as Arc<dyn Any + Send + Sync>));
+
+        Ok(new)
+    }
+
+    /// Returns the views of `images`, as returned by [`view`](Self::view).
+    ///
+    /// This is intended to be called with the images returned when creating or recreating a
+    /// swapchain. The returned views must be kept alive for `view` to keep returning them.
+    pub fn views(
+        images: &[Arc<Self>],
+    ) -> Result<Vec<Arc<ImageView<Self>>>, ImageViewCreationError> {
+        images.iter().map(|image| image.view()).collect()
+    }
+}
+
 impl<W> PartialEq for SwapchainImage<W>
 where
     W: Send + Sync,
@@ -20,11 +20,11 @@ use super::{
 };
 use crate::{
     buffer::cpu_access::{ReadLockError, WriteLockError},
-    device::{Device, DeviceOwned},
-    format::{ChromaSampling, Format, FormatFeatures, NumericType},
+    device::{Device, DeviceOwned, MetalObjectExportError},
+    format::{ChromaSampling, DrmFormatModifierProperties, Format, FormatFeatures, NumericType},
     image::{
-        view::ImageViewCreationError, ImageFormatInfo, ImageFormatProperties, ImageType,
-        SparseImageFormatProperties,
+        view::ImageViewCreationError, ImageDrmFormatModifierInfo, ImageFormatInfo,
+        ImageFormatProperties, ImageType, SparseImageFormatProperties,
     },
     memory::{
         DeviceMemory, DeviceMemoryError, ExternalMemoryHandleType, ExternalMemoryHandleTypes,
@@ -72,19 +72,27 @@ pub struct UnsafeImage {
     mip_levels: u32,
     samples: SampleCount,
     tiling: ImageTiling,
+    drm_format_modifier: Option<u64>,
     usage: ImageUsage,
     stencil_usage: ImageUsage,
+    sharing: Sharing<SmallVec<[u32; 4]>>,
     external_memory_handle_types: ExternalMemoryHandleTypes,
     mutable_format: bool,
     cube_compatible: bool,
     array_2d_compatible: bool,
+    view_2d_compatible: bool,
     block_texel_view_compatible: bool,
+    alias: bool,
+    subsampled: bool,
+    protected: bool,
+    export_metal_texture: bool,
 
     aspect_list: SmallVec<[ImageAspect; 4]>,
     aspect_size: DeviceSize,
     mip_level_size: DeviceSize,
     needs_destruction: bool, // `vkDestroyImage` is called only if true.
     range_size: DeviceSize,
+    memory_binding: Mutex<Option<(ash::vk::DeviceMemory, Range<DeviceSize>)>>,
     state: Mutex<ImageState>,
 }
 
@@ -139,10 +147,17 @@ impl UnsafeImage {
             ref sharing,
             initial_layout,
             external_memory_handle_types,
+            ref drm_format_modifiers,
+            ref drm_format_modifier_plane_layouts,
             mutable_format,
             cube_compatible,
             array_2d_compatible,
+            view_2d_compatible,
             block_texel_view_compatible,
+            alias,
+            subsampled,
+            protected,
+            export_metal_texture,
             _ne: _,
         } = create_info;
 
@@ -208,6 +223,74 @@ impl UnsafeImage {
         // VUID-VkImageCreateInfo-flags-01573
         assert!(!block_texel_view_compatible || mutable_format);
 
+        let drm_format_modifier_properties: SmallVec<[DrmFormatModifierProperties; 1]> = if tiling
+            == ImageTiling::DrmFormatModifier
+        {
+            // VUID-VkImageCreateInfo-tiling-02261
+            if drm_format_modifiers.is_empty() {
+                return Err(ImageCreationError::DrmFormatModifiersMissing);
+            }
+
+            if !drm_format_modifier_plane_layouts.is_empty() && drm_format_modifiers.len() != 1 {
+                return Err(ImageCreationError::DrmFormatModifierExplicitMultipleModifiers);
+            }
+
+            // Use unchecked, because all validation has been done above.
+            let supported_properties =
+                unsafe { physical_device.drm_format_modifier_properties_unchecked(format) };
+
+            // VUID-VkImageDrmFormatModifierListCreateInfoEXT-pDrmFormatModifiers-02263
+            // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-drmFormatModifier-02264
+            let drm_format_modifier_properties = drm_format_modifiers
+                .iter()
+                .map(|&drm_format_modifier| {
+                    supported_properties
+                        .iter()
+                        .find(|properties| properties.drm_format_modifier == drm_format_modifier)
+                        .copied()
+                        .ok_or(ImageCreationError::DrmFormatModifierNotSupported {
+                            drm_format_modifier,
+                        })
+                })
+                .collect::<Result<SmallVec<_>, _>>()?;
+
+            if !drm_format_modifier_plane_layouts.is_empty() {
+                let drm_format_modifier_plane_count =
+                    drm_format_modifier_properties[0].drm_format_modifier_plane_count;
+
+                // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-drmFormatModifierPlaneCount-02265
+                if drm_format_modifier_plane_layouts.len() as u32 != drm_format_modifier_plane_count
+                {
+                    return Err(ImageCreationError::DrmFormatModifierPlaneCountMismatch {
+                        plane_layout_count: drm_format_modifier_plane_layouts.len() as u32,
+                        drm_format_modifier_plane_count,
+                    });
+                }
+
+                for plane_layout in drm_format_modifier_plane_layouts {
+                    // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-size-02267
+                    // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-arrayPitch-02268
+                    // VUID-VkImageDrmFormatModifierExplicitCreateInfoEXT-depthPitch-02269
+                    if plane_layout.size != 0
+                        || (dimensions.array_layers() == 1 && plane_layout.array_pitch != 0)
+                        || (!matches!(dimensions, ImageDimensions::Dim3d { .. })
+                            && plane_layout.depth_pitch != 0)
+                    {
+                        return Err(ImageCreationError::DrmFormatModifierPlaneLayoutInvalid);
+                    }
+                }
+            }
+
+            drm_format_modifier_properties
+        } else {
+            // VUID-VkImageCreateInfo-pNext-02262
+            if !drm_format_modifiers.is_empty() || !drm_format_modifier_plane_layouts.is_empty() {
+                return Err(ImageCreationError::DrmFormatModifiersNotDrmTiling);
+            }
+
+            SmallVec::new()
+        };
+
         // Get format features
         let format_features = {
             // Use unchecked, because all validation has been done above.
@@ -215,6 +298,13 @@ impl UnsafeImage {
             match tiling {
                 ImageTiling::Linear => format_properties.linear_tiling_features,
                 ImageTiling::Optimal => format_properties.optimal_tiling_features,
+                // The implementation may choose any of the modifiers, so only the features that
+                // all of them support can be relied upon.
+                ImageTiling::DrmFormatModifier => drm_format_modifier_properties
+                    .iter()
+                    .map(|properties| properties.drm_format_modifier_tiling_features)
+                    .reduce(|a, b| a & b)
+                    .unwrap_or_default(),
             }
         };
 
@@ -362,6 +452,12 @@ impl UnsafeImage {
             });
         }
 
+        if combined_usage.fragment_density_map && !format_features.fragment_density_map {
+            return Err(ImageCreationError::FormatUsageNotSupported {
+                usage: "fragment_density_map",
+            });
+        }
+
         if combined_usage.color_attachment
             || combined_usage.depth_stencil_attachment
             || combined_usage.input_attachment
@@ -493,6 +589,23 @@ impl UnsafeImage {
             }
         }
 
+        if view_2d_compatible {
+            if !device.enabled_extensions().ext_image_2d_view_of_3d {
+                return Err(ImageCreationError::RequirementNotMet {
+                    required_for: "`create_info.view_2d_compatible` is set",
+                    requires_one_of: RequiresOneOf {
+                        device_extensions: &["ext_image_2d_view_of_3d"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkImageCreateInfo-flags-07755
+            if image_type != ImageType::Dim3d {
+                return Err(ImageCreationError::View2dCompatibleNot3d);
+            }
+        }
+
         if block_texel_view_compatible {
             // VUID-VkImageCreateInfo-flags-01572
             if format.compression().is_none() {
@@ -500,6 +613,76 @@ impl UnsafeImage {
             }
         }
 
+        if alias {
+            if !(device.api_version() >= Version::V1_1
+                || device.enabled_extensions().khr_bind_memory2)
+            {
+                return Err(ImageCreationError::RequirementNotMet {
+                    required_for: "`create_info.alias` is set",
+                    requires_one_of: RequiresOneOf {
+                        api_version: Some(Version::V1_1),
+                        device_extensions: &["khr_bind_memory2"],
+                        ..Default::default()
+                    },
+                });
+            }
+        }
+
+        if subsampled {
+            if !device.enabled_extensions().ext_fragment_density_map {
+                return Err(ImageCreationError::RequirementNotMet {
+                    required_for: "`create_info.subsampled` is set",
+                    requires_one_of: RequiresOneOf {
+                        device_extensions: &["ext_fragment_density_map"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkImageCreateInfo-flags-02565
+            if tiling != ImageTiling::Optimal {
+                return Err(ImageCreationError::SubsampledNotOptimalTiling);
+            }
+
+            // VUID-VkImageCreateInfo-flags-02566
+            if image_type != ImageType::Dim2d {
+                return Err(ImageCreationError::SubsampledNot2d);
+            }
+
+            // VUID-VkImageCreateInfo-flags-02567
+            if cube_compatible {
+                return Err(ImageCreationError::SubsampledCubeCompatible);
+            }
+
+            // VUID-VkImageCreateInfo-flags-02568
+            if mip_levels != 1 {
+                return Err(ImageCreationError::SubsampledMultipleMipLevels);
+            }
+        }
+
+        if protected {
+            // VUID-VkImageCreateInfo-flags-01890
+            if !device.enabled_features().protected_memory {
+                return Err(ImageCreationError::RequirementNotMet {
+                    required_for: "`create_info.protected` is set",
+                    requires_one_of: RequiresOneOf {
+                        features: &["protected_memory"],
+                        ..Default::default()
+                    },
+                });
+            }
+        }
+
+        if export_metal_texture && !device.enabled_extensions().ext_metal_objects {
+            return Err(ImageCreationError::RequirementNotMet {
+                required_for: "`create_info.export_metal_texture` is set",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_metal_objects"],
+                    ..Default::default()
+                },
+            });
+        }
+
         /* Check sharing mode and queue families */
 
         match sharing {
@@ -712,7 +895,8 @@ impl UnsafeImage {
             || mip_levels_must_query()
             || array_layers_must_query()
             || samples_must_query()
-            || linear_must_query();
+            || linear_must_query()
+            || tiling == ImageTiling::DrmFormatModifier;
 
         // We determined that we must query the device in order to be sure that the image
         // configuration is supported.
@@ -726,7 +910,38 @@ impl UnsafeImage {
                     smallvec![None]
                 };
 
-            for external_memory_handle_type in external_memory_handle_types {
+            let drm_format_modifier_infos: SmallVec<[Option<ImageDrmFormatModifierInfo>; 1]> =
+                if tiling == ImageTiling::DrmFormatModifier {
+                    // Each modifier must support the image configuration, not only the one that
+                    // the implementation ends up choosing.
+                    drm_format_modifiers
+                        .iter()
+                        .map(|&drm_format_modifier| {
+                            Some(ImageDrmFormatModifierInfo {
+                                drm_format_modifier,
+                                sharing: sharing.clone(),
+                                ..Default::default()
+                            })
+                        })
+                        .collect()
+                } else {
+                    smallvec![None]
+                };
+
+            for (external_memory_handle_type, drm_format_modifier_info) in
+                external_memory_handle_types
+                    .into_iter()
+                    .flat_map(|external_memory_handle_type| {
+                        drm_format_modifier_infos
+                            .iter()
+                            .map(move |drm_format_modifier_info| {
+                                (
+                                    external_memory_handle_type,
+                                    drm_format_modifier_info.clone(),
+                                )
+                            })
+                    })
+            {
                 // Use unchecked, because all validation has been done above.
                 let image_format_properties = unsafe {
                     device
@@ -739,8 +954,12 @@ impl UnsafeImage {
                             mutable_format,
                             cube_compatible,
                             array_2d_compatible,
+                            view_2d_compatible,
                             block_texel_view_compatible,
+                            subsampled,
+                            protected,
                             external_memory_handle_type,
+                            drm_format_modifier_info,
                             ..Default::default()
                         })?
                 };
@@ -818,10 +1037,17 @@ impl UnsafeImage {
             ref sharing,
             initial_layout,
             external_memory_handle_types,
+            ref drm_format_modifiers,
+            ref drm_format_modifier_plane_layouts,
             mutable_format,
             cube_compatible,
             array_2d_compatible,
+            view_2d_compatible,
             block_texel_view_compatible,
+            alias,
+            subsampled,
+            protected,
+            export_metal_texture,
             _ne: _,
         } = &create_info;
 
@@ -839,7 +1065,11 @@ impl UnsafeImage {
             mutable_format,
             cube_compatible,
             array_2d_compatible,
+            view_2d_compatible,
             block_texel_view_compatible,
+            alias,
+            subsampled,
+            protected,
             ..ImageCreateFlags::empty()
         };
 
@@ -891,6 +1121,20 @@ impl UnsafeImage {
         };
         let mut external_memory_info_vk = None;
         let mut stencil_usage_info_vk = None;
+        let mut drm_format_modifier_list_info_vk = None;
+        let mut drm_format_modifier_explicit_info_vk = None;
+        let mut export_metal_object_info_vk = None;
+        let drm_format_modifier_plane_layouts_vk: SmallVec<[_; 4]> =
+            drm_format_modifier_plane_layouts
+                .iter()
+                .map(|plane_layout| ash::vk::SubresourceLayout {
+                    offset: plane_layout.offset,
+                    size: plane_layout.size,
+                    row_pitch: plane_layout.row_pitch,
+                    array_pitch: plane_layout.array_pitch,
+                    depth_pitch: plane_layout.depth_pitch,
+                })
+                .collect();
 
         if !external_memory_handle_types.is_empty() {
             let next = external_memory_info_vk.insert(ash::vk::ExternalMemoryImageCreateInfo {
@@ -912,6 +1156,43 @@ impl UnsafeImage {
             info_vk.p_next = next as *const _ as *const _;
         }
 
+        if !drm_format_modifier_plane_layouts_vk.is_empty() {
+            let next = drm_format_modifier_explicit_info_vk.insert(
+                ash::vk::ImageDrmFormatModifierExplicitCreateInfoEXT {
+                    drm_format_modifier: drm_format_modifiers[0],
+                    drm_format_modifier_plane_count: drm_format_modifier_plane_layouts_vk.len()
+                        as u32,
+                    p_plane_layouts: drm_format_modifier_plane_layouts_vk.as_ptr(),
+                    ..Default::default()
+                },
+            );
+
+            next.p_next = info_vk.p_next;
+            info_vk.p_next = next as *const _ as *const _;
+        } else if !drm_format_modifiers.is_empty() {
+            let next = drm_format_modifier_list_info_vk.insert(
+                ash::vk::ImageDrmFormatModifierListCreateInfoEXT {
+                    drm_format_modifier_count: drm_format_modifiers.len() as u32,
+                    p_drm_format_modifiers: drm_format_modifiers.as_ptr(),
+                    ..Default::default()
+                },
+            );
+
+            next.p_next = info_vk.p_next;
+            info_vk.p_next = next as *const _ as *const _;
+        }
+
+        if export_metal_texture {
+            let next =
+                export_metal_object_info_vk.insert(ash::vk::ExportMetalObjectCreateInfoEXT {
+                    export_object_type: ash::vk::ExportMetalObjectTypeFlagsEXT::METAL_TEXTURE,
+                    ..Default::default()
+                });
+
+            next.p_next = info_vk.p_next;
+            info_vk.p_next = next as *const _ as *const _;
+        }
+
         let handle = {
             let fns = device.fns();
             let mut output = MaybeUninit::uninit();
@@ -926,6 +1207,30 @@ impl UnsafeImage {
             output.assume_init()
         };
 
+        // If the implementation chose a modifier from a list, record which one.
+        let mut create_info = create_info;
+
+        if tiling == ImageTiling::DrmFormatModifier && create_info.drm_format_modifiers.len() > 1 {
+            let mut properties_vk = ash::vk::ImageDrmFormatModifierPropertiesEXT::default();
+            let fns = device.fns();
+            let result = (fns
+                .ext_image_drm_format_modifier
+                .get_image_drm_format_modifier_properties_ext)(
+                device.internal_object(),
+                handle,
+                &mut properties_vk,
+            )
+            .result()
+            .map_err(VulkanError::from);
+
+            if let Err(err) = result {
+                (fns.v1_0.destroy_image)(device.internal_object(), handle, ptr::null());
+                return Err(err);
+            }
+
+            create_info.drm_format_modifiers = vec![properties_vk.drm_format_modifier];
+        }
+
         Ok(Self::from_handle(device, handle, create_info))
     }
 
@@ -935,6 +1240,9 @@ impl UnsafeImage {
     ///
     /// - `handle` must be a valid Vulkan object handle created from `device`.
     /// - `create_info` must match the info used to create the object.
+    /// - If `create_info.tiling` is [`ImageTiling::DrmFormatModifier`], then
+    ///   `create_info.drm_format_modifiers` must contain only the DRM format modifier that the
+    ///   image was created with.
     #[inline]
     pub unsafe fn from_handle(
         device: Arc<Device>,
@@ -949,17 +1257,26 @@ impl UnsafeImage {
             tiling,
             usage,
             mut stencil_usage,
-            sharing: _,
+            sharing,
             initial_layout,
             external_memory_handle_types,
+            drm_format_modifiers,
+            drm_format_modifier_plane_layouts: _,
             mutable_format,
             cube_compatible,
             array_2d_compatible,
+            view_2d_compatible,
             block_texel_view_compatible,
+            alias,
+            subsampled,
+            protected,
+            export_metal_texture,
             _ne: _,
         } = create_info;
 
         let aspects = format.map_or_else(Default::default, |format| format.aspects());
+        let drm_format_modifier =
+            (tiling == ImageTiling::DrmFormatModifier).then(|| drm_format_modifiers[0]);
 
         if stencil_usage.is_empty() || !(aspects.depth && aspects.stencil) {
             stencil_usage = usage;
@@ -975,6 +1292,14 @@ impl UnsafeImage {
             match tiling {
                 ImageTiling::Linear => format_properties.linear_tiling_features,
                 ImageTiling::Optimal => format_properties.optimal_tiling_features,
+                ImageTiling::DrmFormatModifier => device
+                    .physical_device()
+                    .drm_format_modifier_properties_unchecked(format.unwrap())
+                    .into_iter()
+                    .find(|properties| Some(properties.drm_format_modifier) == drm_format_modifier)
+                    .map_or_else(Default::default, |properties| {
+                        properties.drm_format_modifier_tiling_features
+                    }),
             }
         };
         let aspects = format.unwrap().aspects();
@@ -994,19 +1319,27 @@ impl UnsafeImage {
             initial_layout,
             samples,
             tiling,
+            drm_format_modifier,
             usage,
             stencil_usage,
+            sharing,
             external_memory_handle_types,
             mutable_format,
             cube_compatible,
             array_2d_compatible,
+            view_2d_compatible,
             block_texel_view_compatible,
+            alias,
+            subsampled,
+            protected,
+            export_metal_texture,
 
             aspect_list,
             aspect_size,
             mip_level_size,
             needs_destruction: true,
             range_size,
+            memory_binding: Mutex::new(None),
             state: Mutex::new(ImageState::new(range_size, initial_layout)),
         })
     }
@@ -1023,6 +1356,7 @@ impl UnsafeImage {
         dimensions: ImageDimensions,
         samples: SampleCount,
         mip_levels: u32,
+        sharing: Sharing<SmallVec<[u32; 4]>>,
     ) -> Arc<UnsafeImage> {
         let tiling = ImageTiling::Optimal;
 
@@ -1037,7 +1371,9 @@ impl UnsafeImage {
             mutable_format: false,
             cube_compatible: false,
             array_2d_compatible: false,
+            view_2d_compatible: false,
             block_texel_view_compatible: false,
+            subsampled: false,
             ..ImageCreateFlags::empty()
         }
         .is_empty());
@@ -1063,19 +1399,27 @@ impl UnsafeImage {
             mip_levels,
             samples,
             tiling,
+            drm_format_modifier: None,
             usage,
             stencil_usage: usage,
+            sharing,
             external_memory_handle_types: ExternalMemoryHandleTypes::empty(),
             mutable_format: flags.mutable_format,
             cube_compatible: flags.cube_compatible,
             array_2d_compatible: flags.array_2d_compatible,
+            view_2d_compatible: flags.view_2d_compatible,
             block_texel_view_compatible: flags.block_texel_view_compatible,
+            alias: flags.alias,
+            subsampled: flags.subsampled,
+            protected: flags.protected,
+            export_metal_texture: false,
 
             aspect_list,
             aspect_size,
             mip_level_size,
             needs_destruction: false, // TODO: pass as parameter
             range_size,
+            memory_binding: Mutex::new(None),
             state: Mutex::new(ImageState::new(range_size, initial_layout)),
         };
 
@@ -1323,12 +1667,119 @@ impl UnsafeImage {
         }
     }
 
+    /// Binds device memory to this image.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `self.protected()` is `true` and the memory type of `memory` is not protected,
+    ///   or the other way around.
     #[inline]
     pub unsafe fn bind_memory(
         &self,
         memory: &DeviceMemory,
         offset: DeviceSize,
     ) -> Result<(), OomError> {
+        self.check_bind_memory(memory, offset);
+
+        let fns = self.device.fns();
+        (fns.v1_0.bind_image_memory)(
+            self.device.internal_object(),
+            self.handle,
+            memory.internal_object(),
+            offset,
+        )
+        .result()
+        .map_err(VulkanError::from)?;
+
+        self.set_memory_binding(memory, offset);
+
+        Ok(())
+    }
+
+    /// Binds device memory to multiple images at once.
+    ///
+    /// If the device API version is at least 1.1, or the
+    /// [`khr_bind_memory2`](crate::device::DeviceExtensions::khr_bind_memory2) extension is
+    /// enabled, all the images are bound with a single `vkBindImageMemory2` call. Otherwise they
+    /// are bound one by one.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the images do not all belong to the same device.
+    /// - Panics for the same reasons as [`bind_memory`](Self::bind_memory), for each element of
+    ///   `bind_infos`.
+    pub unsafe fn bind_memory_batch<'a>(
+        bind_infos: impl IntoIterator<Item = BindImageMemoryInfo<'a>>,
+    ) -> Result<(), OomError> {
+        let bind_infos: SmallVec<[_; 4]> = bind_infos.into_iter().collect();
+
+        let device = match bind_infos.first() {
+            Some(bind_info) => bind_info.image.device(),
+            None => return Ok(()),
+        };
+
+        for bind_info in &bind_infos {
+            assert_eq!(bind_info.image.device(), device);
+            bind_info
+                .image
+                .check_bind_memory(bind_info.memory, bind_info.offset);
+        }
+
+        let fns = device.fns();
+
+        if device.api_version() >= Version::V1_1 || device.enabled_extensions().khr_bind_memory2 {
+            let bind_infos_vk: SmallVec<[_; 4]> = bind_infos
+                .iter()
+                .map(|bind_info| ash::vk::BindImageMemoryInfo {
+                    image: bind_info.image.handle,
+                    memory: bind_info.memory.internal_object(),
+                    memory_offset: bind_info.offset,
+                    ..Default::default()
+                })
+                .collect();
+
+            if device.api_version() >= Version::V1_1 {
+                (fns.v1_1.bind_image_memory2)(
+                    device.internal_object(),
+                    bind_infos_vk.len() as u32,
+                    bind_infos_vk.as_ptr(),
+                )
+            } else {
+                (fns.khr_bind_memory2.bind_image_memory2_khr)(
+                    device.internal_object(),
+                    bind_infos_vk.len() as u32,
+                    bind_infos_vk.as_ptr(),
+                )
+            }
+            .result()
+            .map_err(VulkanError::from)?;
+
+            for bind_info in &bind_infos {
+                bind_info
+                    .image
+                    .set_memory_binding(bind_info.memory, bind_info.offset);
+            }
+        } else {
+            for bind_info in &bind_infos {
+                (fns.v1_0.bind_image_memory)(
+                    device.internal_object(),
+                    bind_info.image.handle,
+                    bind_info.memory.internal_object(),
+                    bind_info.offset,
+                )
+                .result()
+                .map_err(VulkanError::from)?;
+
+                bind_info
+                    .image
+                    .set_memory_binding(bind_info.memory, bind_info.offset);
+            }
+        }
+
+        Ok(())
+    }
+
+    unsafe fn check_bind_memory(&self, memory: &DeviceMemory, offset: DeviceSize) {
         let fns = self.device.fns();
 
         // We check for correctness in debug mode.
@@ -1346,16 +1797,28 @@ impl UnsafeImage {
                 && mem_reqs.memory_type_bits & (1 << memory.memory_type_index()) != 0
         });
 
-        (fns.v1_0.bind_image_memory)(
-            self.device.internal_object(),
-            self.handle,
-            memory.internal_object(),
-            offset,
-        )
-        .result()
-        .map_err(VulkanError::from)?;
+        // VUID-vkBindImageMemory-None-01901
+        // VUID-vkBindImageMemory-None-01902
+        assert_eq!(
+            self.protected,
+            self.device
+                .physical_device()
+                .memory_properties()
+                .memory_types[memory.memory_type_index() as usize]
+                .property_flags
+                .protected,
+        );
+    }
 
-        Ok(())
+    fn set_memory_binding(&self, memory: &DeviceMemory, offset: DeviceSize) {
+        let size = self.memory_requirements().size;
+        *self.memory_binding.lock() = Some((memory.internal_object(), offset..offset + size));
+    }
+
+    /// Returns the memory object that is bound to the image, and the range within it, if memory
+    /// was bound with `bind_memory`.
+    pub(crate) fn memory_binding(&self) -> Option<(ash::vk::DeviceMemory, Range<DeviceSize>)> {
+        self.memory_binding.lock().clone()
     }
 
     pub(crate) fn range_size(&self) -> DeviceSize {
@@ -1389,70 +1852,72 @@ impl UnsafeImage {
         )
     }
 
+    /// Returns an iterator over the subresource ranges that together cover `range`.
+    ///
+    /// This is the inverse of [`iter_ranges`](Self::iter_ranges). A range that starts or ends in
+    /// the middle of a mip level, and extends into the next mip level or aspect, can't be
+    /// described by a single subresource range. It is split into the fewest subresource ranges
+    /// that cover exactly the same subresources.
     pub(crate) fn range_to_subresources(
         &self,
-        mut range: Range<DeviceSize>,
-    ) -> ImageSubresourceRange {
+        range: Range<DeviceSize>,
+    ) -> impl Iterator<Item = ImageSubresourceRange> + '_ {
         debug_assert!(!range.is_empty());
         debug_assert!(range.end <= self.range_size);
 
-        if range.end - range.start > self.aspect_size {
-            debug_assert!(range.start % self.aspect_size == 0);
-            debug_assert!(range.end % self.aspect_size == 0);
-
-            let start_aspect_num = (range.start / self.aspect_size) as usize;
-            let end_aspect_num = (range.end / self.aspect_size) as usize;
+        let Range { mut start, end } = range;
 
-            ImageSubresourceRange {
-                aspects: self.aspect_list[start_aspect_num..end_aspect_num]
-                    .iter()
-                    .copied()
-                    .collect(),
-                mip_levels: 0..self.mip_levels,
-                array_layers: 0..self.dimensions.array_layers(),
+        std::iter::from_fn(move || {
+            if start >= end {
+                return None;
             }
-        } else {
-            let aspect_num = (range.start / self.aspect_size) as usize;
-            range.start %= self.aspect_size;
-            range.end %= self.aspect_size;
 
-            // Wraparound
-            if range.end == 0 {
-                range.end = self.aspect_size;
-            }
+            let aspect_num = (start / self.aspect_size) as usize;
+            let aspect_offset = start % self.aspect_size;
+            let aspect_end = (aspect_num as DeviceSize + 1) * self.aspect_size;
 
-            if range.end - range.start > self.mip_level_size {
-                debug_assert!(range.start % self.mip_level_size == 0);
-                debug_assert!(range.end % self.mip_level_size == 0);
+            let subresource_range = if aspect_offset == 0 && end - start >= self.aspect_size {
+                // One or more whole aspects.
+                let aspect_count = ((end - start) / self.aspect_size) as usize;
+                start += aspect_count as DeviceSize * self.aspect_size;
 
-                let start_mip_level = (range.start / self.mip_level_size) as u32;
-                let end_mip_level = (range.end / self.mip_level_size) as u32;
+                ImageSubresourceRange {
+                    aspects: self.aspect_list[aspect_num..aspect_num + aspect_count]
+                        .iter()
+                        .copied()
+                        .collect(),
+                    mip_levels: 0..self.mip_levels,
+                    array_layers: 0..self.dimensions.array_layers(),
+                }
+            } else if aspect_offset % self.mip_level_size == 0
+                && end.min(aspect_end) - start >= self.mip_level_size
+            {
+                // One or more whole mip levels within a single aspect.
+                let start_mip_level = (aspect_offset / self.mip_level_size) as u32;
+                let mip_level_count = (end.min(aspect_end) - start) / self.mip_level_size;
+                start += mip_level_count * self.mip_level_size;
 
                 ImageSubresourceRange {
                     aspects: self.aspect_list[aspect_num].into(),
-                    mip_levels: start_mip_level..end_mip_level,
+                    mip_levels: start_mip_level..start_mip_level + mip_level_count as u32,
                     array_layers: 0..self.dimensions.array_layers(),
                 }
             } else {
-                let mip_level = (range.start / self.mip_level_size) as u32;
-                range.start %= self.mip_level_size;
-                range.end %= self.mip_level_size;
-
-                // Wraparound
-                if range.end == 0 {
-                    range.end = self.mip_level_size;
-                }
-
-                let start_array_layer = range.start as u32;
-                let end_array_layer = range.end as u32;
+                // Some array layers within a single mip level.
+                let mip_level = (aspect_offset / self.mip_level_size) as u32;
+                let mip_level_offset = aspect_offset % self.mip_level_size;
+                let layer_count = (end - start).min(self.mip_level_size - mip_level_offset);
+                start += layer_count;
 
                 ImageSubresourceRange {
                     aspects: self.aspect_list[aspect_num].into(),
                     mip_levels: mip_level..mip_level + 1,
-                    array_layers: start_array_layer..end_array_layer,
+                    array_layers: mip_level_offset as u32..(mip_level_offset + layer_count) as u32,
                 }
-            }
-        }
+            };
+
+            Some(subresource_range)
+        })
     }
 
     pub(crate) fn state(&self) -> MutexGuard<'_, ImageState> {
@@ -1501,6 +1966,16 @@ impl UnsafeImage {
         self.tiling
     }
 
+    /// Returns the DRM format modifier of the image, if its tiling is
+    /// [`ImageTiling::DrmFormatModifier`].
+    ///
+    /// If the image was created with more than one modifier to choose from, this is the one that
+    /// the implementation chose.
+    #[inline]
+    pub fn drm_format_modifier(&self) -> Option<u64> {
+        self.drm_format_modifier
+    }
+
     /// Returns the usage the image was created with.
     #[inline]
     pub fn usage(&self) -> &ImageUsage {
@@ -1513,6 +1988,12 @@ impl UnsafeImage {
         &self.stencil_usage
     }
 
+    /// Returns the sharing the image was created with.
+    #[inline]
+    pub fn sharing(&self) -> &Sharing<SmallVec<[u32; 4]>> {
+        &self.sharing
+    }
+
     /// Returns the external memory handle types that are supported with this image.
     #[inline]
     pub fn external_memory_handle_types(&self) -> ExternalMemoryHandleTypes {
@@ -1537,68 +2018,179 @@ impl UnsafeImage {
         self.array_2d_compatible
     }
 
+    /// Returns whether `view_2d_compatible` is enabled on the image.
+    #[inline]
+    pub fn view_2d_compatible(&self) -> bool {
+        self.view_2d_compatible
+    }
+
     /// Returns whether `block_texel_view_compatible` is enabled on the image.
     #[inline]
     pub fn block_texel_view_compatible(&self) -> bool {
         self.block_texel_view_compatible
     }
 
-    /// Returns an `ImageSubresourceLayers` covering the first mip level of the image. All aspects
-    /// of the image are selected, or `plane0` if the image is multi-planar.
+    /// Returns whether `alias` is enabled on the image.
     #[inline]
-    pub fn subresource_layers(&self) -> ImageSubresourceLayers {
-        ImageSubresourceLayers {
-            aspects: {
-                let aspects = self.format.unwrap().aspects();
+    pub fn alias(&self) -> bool {
+        self.alias
+    }
 
-                if aspects.plane0 {
-                    ImageAspects {
-                        plane0: true,
-                        ..ImageAspects::empty()
-                    }
-                } else {
-                    aspects
-                }
-            },
-            mip_level: 0,
-            array_layers: 0..self.dimensions.array_layers(),
-        }
+    /// Returns whether `subsampled` is enabled on the image.
+    #[inline]
+    pub fn subsampled(&self) -> bool {
+        self.subsampled
     }
 
-    /// Returns an `ImageSubresourceRange` covering the whole image. If the image is multi-planar,
-    /// only the `color` aspect is selected.
+    /// Returns whether `protected` is enabled on the image.
     #[inline]
-    pub fn subresource_range(&self) -> ImageSubresourceRange {
-        ImageSubresourceRange {
-            aspects: ImageAspects {
-                plane0: false,
-                plane1: false,
-                plane2: false,
-                ..self.format.unwrap().aspects()
-            },
-            mip_levels: 0..self.mip_levels,
-            array_layers: 0..self.dimensions.array_layers(),
-        }
+    pub fn protected(&self) -> bool {
+        self.protected
     }
 
-    /// Returns a key unique to each `UnsafeImage`. Can be used for the `conflicts_key` method.
+    /// Returns whether `export_metal_texture` is enabled on the image.
     #[inline]
-    pub fn key(&self) -> u64 {
-        self.handle.as_raw()
+    pub fn export_metal_texture(&self) -> bool {
+        self.export_metal_texture
     }
 
-    /// Queries the layout of an image in memory. Only valid for images with linear tiling.
-    ///
-    /// This function is only valid for images with a color format. See the other similar functions
-    /// for the other aspects.
+    /// Returns the `MTLTexture` that underlies a plane of the image, when running on top of
+    /// Metal.
     ///
-    /// The layout is invariant for each image. However it is not cached, as this would waste
-    /// memory in the case of non-linear-tiling images. You are encouraged to store the layout
-    /// somewhere in order to avoid calling this semi-expensive function at every single memory
-    /// access.
+    /// `plane` must be 0 if the image format is not multi-planar, and less than the number of
+    /// planes of the format otherwise.
     ///
-    /// Note that while Vulkan allows querying the array layers other than 0, it is redundant as
-    /// you can easily calculate the position of any layer.
+    /// The [`ext_metal_objects`](crate::device::DeviceExtensions::ext_metal_objects) extension
+    /// must be enabled on the device, and the image must have been created with
+    /// [`UnsafeImageCreateInfo::export_metal_texture`] set.
+    ///
+    /// The returned object is owned by the image, and must not be used after the image has been
+    /// destroyed.
+    #[inline]
+    pub fn export_metal_texture_object(
+        &self,
+        plane: usize,
+    ) -> Result<ash::vk::MTLTexture_id, MetalObjectExportError> {
+        self.validate_export_metal_texture_object(plane)?;
+
+        unsafe { Ok(self.export_metal_texture_object_unchecked(plane)) }
+    }
+
+    fn validate_export_metal_texture_object(
+        &self,
+        plane: usize,
+    ) -> Result<(), MetalObjectExportError> {
+        if !self.device.enabled_extensions().ext_metal_objects {
+            return Err(MetalObjectExportError::RequirementNotMet {
+                required_for: "`export_metal_texture_object`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_metal_objects"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if !self.export_metal_texture {
+            return Err(MetalObjectExportError::ExportNotEnabled);
+        }
+
+        let plane_count = self.format.unwrap().planes().len().max(1);
+
+        if plane >= plane_count {
+            return Err(MetalObjectExportError::PlaneOutOfRange { plane, plane_count });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn export_metal_texture_object_unchecked(
+        &self,
+        plane: usize,
+    ) -> ash::vk::MTLTexture_id {
+        let plane = match plane {
+            0 => ash::vk::ImageAspectFlags::PLANE_0,
+            1 => ash::vk::ImageAspectFlags::PLANE_1,
+            2 => ash::vk::ImageAspectFlags::PLANE_2,
+            _ => unreachable!(),
+        };
+
+        let mut export_info_vk = ash::vk::ExportMetalTextureInfoEXT {
+            image: self.handle,
+            plane,
+            ..Default::default()
+        };
+        let mut info_vk = ash::vk::ExportMetalObjectsInfoEXT {
+            p_next: &mut export_info_vk as *mut _ as *const _,
+            ..Default::default()
+        };
+
+        let fns = self.device.fns();
+        (fns.ext_metal_objects.export_metal_objects_ext)(
+            self.device.internal_object(),
+            &mut info_vk,
+        );
+
+        export_info_vk.mtl_texture
+    }
+
+    /// Returns an `ImageSubresourceLayers` covering the first mip level of the image. All aspects
+    /// of the image are selected, or `plane0` if the image is multi-planar.
+    #[inline]
+    pub fn subresource_layers(&self) -> ImageSubresourceLayers {
+        ImageSubresourceLayers {
+            aspects: {
+                let aspects = self.format.unwrap().aspects();
+
+                if aspects.plane0 {
+                    ImageAspects {
+                        plane0: true,
+                        ..ImageAspects::empty()
+                    }
+                } else {
+                    aspects
+                }
+            },
+            mip_level: 0,
+            array_layers: 0..self.dimensions.array_layers(),
+        }
+    }
+
+    /// Returns an `ImageSubresourceRange` covering the whole image. If the image is multi-planar,
+    /// only the `color` aspect is selected.
+    #[inline]
+    pub fn subresource_range(&self) -> ImageSubresourceRange {
+        ImageSubresourceRange {
+            aspects: ImageAspects {
+                plane0: false,
+                plane1: false,
+                plane2: false,
+                ..self.format.unwrap().aspects()
+            },
+            mip_levels: 0..self.mip_levels,
+            array_layers: 0..self.dimensions.array_layers(),
+        }
+    }
+
+    /// Returns a key unique to each `UnsafeImage`. Can be used for the `conflicts_key` method.
+    #[inline]
+    pub fn key(&self) -> u64 {
+        self.handle.as_raw()
+    }
+
+    /// Queries the layout of an image in memory. Only valid for images with linear tiling.
+    ///
+    /// This function is only valid for images with a color format. See the other similar functions
+    /// for the other aspects.
+    ///
+    /// The layout is invariant for each image. However it is not cached, as this would waste
+    /// memory in the case of non-linear-tiling images. You are encouraged to store the layout
+    /// somewhere in order to avoid calling this semi-expensive function at every single memory
+    /// access.
+    ///
+    /// Note that while Vulkan allows querying the array layers other than 0, it is redundant as
+    /// you can easily calculate the position of any layer.
     ///
     /// # Panics
     ///
@@ -1817,6 +2409,30 @@ pub struct UnsafeImageCreateInfo {
     /// The default value is [`ExternalMemoryHandleTypes::empty()`].
     pub external_memory_handle_types: ExternalMemoryHandleTypes,
 
+    /// The DRM format modifiers that the image can be created with, if `tiling` is
+    /// [`ImageTiling::DrmFormatModifier`].
+    ///
+    /// If `tiling` is `DrmFormatModifier`, this must not be empty, and each modifier must be
+    /// supported for `format`, as returned by
+    /// [`PhysicalDevice::drm_format_modifier_properties`](crate::device::physical::PhysicalDevice::drm_format_modifier_properties).
+    /// If more than one modifier is given, the implementation chooses one of them, which can be
+    /// retrieved afterwards with [`UnsafeImage::drm_format_modifier`]. If `tiling` is not
+    /// `DrmFormatModifier`, this must be empty.
+    ///
+    /// The default value is empty.
+    pub drm_format_modifiers: Vec<u64>,
+
+    /// The layouts of the memory planes of the image, to create it with an explicit DRM format
+    /// modifier. This is used for example to import a dma-buf that was created by another API or
+    /// process, together with
+    /// [`ExternalMemoryHandleType::DmaBuf`].
+    ///
+    /// If not empty, `drm_format_modifiers` must contain exactly one modifier, there must be one
+    /// layout for each of its memory planes, and the `size` of each layout must be 0.
+    ///
+    /// The default value is empty.
+    pub drm_format_modifier_plane_layouts: Vec<LinearLayout>,
+
     /// For non-multi-planar formats, whether an image view wrapping the image can have a
     /// different format.
     ///
@@ -1842,6 +2458,17 @@ pub struct UnsafeImageCreateInfo {
     /// The default value is `false`.
     pub array_2d_compatible: bool,
 
+    /// For 3D images, whether an image view of type
+    /// [`ImageViewType::Dim2d`](crate::image::view::ImageViewType::Dim2d) can be created from the
+    /// image and used in a storage image or sampled image descriptor.
+    ///
+    /// If set to `true`, the
+    /// [`ext_image_2d_view_of_3d`](crate::device::DeviceExtensions::ext_image_2d_view_of_3d)
+    /// extension must be enabled on the device.
+    ///
+    /// The default value is `false`.
+    pub view_2d_compatible: bool,
+
     /// For images with a compressed format, whether an image view with an uncompressed
     /// format can be created from the image, where each texel in the view will correspond to a
     /// compressed texel block in the image.
@@ -1851,6 +2478,47 @@ pub struct UnsafeImageCreateInfo {
     /// The default value is `false`.
     pub block_texel_view_compatible: bool,
 
+    /// Whether the image can share memory with other images that were created with identical
+    /// parameters and also have `alias` set, with the contents of the memory being interpreted
+    /// consistently between them.
+    ///
+    /// If set to `true`, the device API version must be at least 1.1, or the
+    /// [`khr_bind_memory2`](crate::device::DeviceExtensions::khr_bind_memory2) extension must be
+    /// enabled on the device.
+    ///
+    /// The default value is `false`.
+    pub alias: bool,
+
+    /// Whether the image can be rendered to at a lower resolution in some regions, when it is
+    /// used as an attachment in a render pass that has a fragment density map attachment.
+    ///
+    /// If set to `true`, the
+    /// [`ext_fragment_density_map`](crate::device::DeviceExtensions::ext_fragment_density_map)
+    /// extension must be enabled on the device, the image must be 2D with optimal tiling and a
+    /// single mip level, and `cube_compatible` must be `false`.
+    ///
+    /// The default value is `false`.
+    pub subsampled: bool,
+
+    /// Whether the image is a protected image, which must be bound to protected memory and can
+    /// only be accessed by protected command buffers.
+    ///
+    /// If set to `true`, the [`protected_memory`](crate::device::Features::protected_memory)
+    /// feature must be enabled on the device.
+    ///
+    /// The default value is `false`.
+    pub protected: bool,
+
+    /// Allows the underlying `MTLTexture` of the image to be exported with
+    /// [`UnsafeImage::export_metal_texture_object`], when running on top of Metal.
+    ///
+    /// If set to `true`, the
+    /// [`ext_metal_objects`](crate::device::DeviceExtensions::ext_metal_objects) extension must
+    /// be enabled on the device.
+    ///
+    /// The default value is `false`.
+    pub export_metal_texture: bool,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -1872,15 +2540,35 @@ impl Default for UnsafeImageCreateInfo {
             sharing: Sharing::Exclusive,
             initial_layout: ImageLayout::Undefined,
             external_memory_handle_types: ExternalMemoryHandleTypes::empty(),
+            drm_format_modifiers: Vec::new(),
+            drm_format_modifier_plane_layouts: Vec::new(),
             mutable_format: false,
             cube_compatible: false,
             array_2d_compatible: false,
+            view_2d_compatible: false,
             block_texel_view_compatible: false,
+            alias: false,
+            subsampled: false,
+            protected: false,
+            export_metal_texture: false,
             _ne: crate::NonExhaustive(()),
         }
     }
 }
 
+/// Parameters to bind memory to an image with [`UnsafeImage::bind_memory_batch`].
+#[derive(Clone, Copy, Debug)]
+pub struct BindImageMemoryInfo<'a> {
+    /// The image to bind memory to.
+    pub image: &'a UnsafeImage,
+
+    /// The memory to bind to the image.
+    pub memory: &'a DeviceMemory,
+
+    /// The offset in bytes in `memory` where the image starts.
+    pub offset: DeviceSize,
+}
+
 /// Error that can happen when creating an instance.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ImageCreationError {
@@ -1910,6 +2598,33 @@ pub enum ImageCreationError {
     /// The cube_compatible flag was enabled together with multisampling.
     CubeCompatibleMultisampling,
 
+    /// `drm_format_modifier_plane_layouts` was not empty, but `drm_format_modifiers` did not
+    /// contain exactly one modifier.
+    DrmFormatModifierExplicitMultipleModifiers,
+
+    /// One of the DRM format modifiers was not supported by the device for the given format.
+    DrmFormatModifierNotSupported {
+        drm_format_modifier: u64,
+    },
+
+    /// The number of plane layouts did not equal the number of memory planes of the DRM format
+    /// modifier.
+    DrmFormatModifierPlaneCountMismatch {
+        plane_layout_count: u32,
+        drm_format_modifier_plane_count: u32,
+    },
+
+    /// One of the plane layouts had a nonzero `size`, a nonzero `array_pitch` while the image has
+    /// only one array layer, or a nonzero `depth_pitch` while the image is not 3D.
+    DrmFormatModifierPlaneLayoutInvalid,
+
+    /// Tiling was `DrmFormatModifier`, but no DRM format modifiers were provided.
+    DrmFormatModifiersMissing,
+
+    /// DRM format modifiers or plane layouts were provided, but tiling was not
+    /// `DrmFormatModifier`.
+    DrmFormatModifiersNotDrmTiling,
+
     /// One or more external memory handle types were provided, but the initial layout was not
     /// `Undefined`.
     ExternalMemoryInvalidInitialLayout,
@@ -1985,6 +2700,21 @@ pub enum ImageCreationError {
         stencil_usage: ImageUsage,
     },
 
+    /// The `subsampled` flag was set, and the `cube_compatible` flag was set.
+    SubsampledCubeCompatible,
+
+    /// The `subsampled` flag was set, and multiple mip levels were specified.
+    SubsampledMultipleMipLevels,
+
+    /// The `subsampled` flag was set, but the image type was not 2D.
+    SubsampledNot2d,
+
+    /// The `subsampled` flag was set, but tiling was not `Optimal`.
+    SubsampledNotOptimalTiling,
+
+    /// The `view_2d_compatible` flag was set, but the image type was not 3D.
+    View2dCompatibleNot3d,
+
     /// A YCbCr format was given, but the specified width and/or height was not a multiple of 2
     /// as required by the format's chroma subsampling.
     YcbcrFormatInvalidDimensions,
@@ -2048,6 +2778,43 @@ impl Display for ImageCreationError {
                 f,
                 "the cube_compatible flag was enabled together with multisampling",
             ),
+            Self::DrmFormatModifierExplicitMultipleModifiers => write!(
+                f,
+                "`drm_format_modifier_plane_layouts` was not empty, but `drm_format_modifiers` \
+                did not contain exactly one modifier",
+            ),
+            Self::DrmFormatModifierNotSupported {
+                drm_format_modifier,
+            } => write!(
+                f,
+                "the DRM format modifier {:#x} was not supported by the device for the given \
+                format",
+                drm_format_modifier,
+            ),
+            Self::DrmFormatModifierPlaneCountMismatch {
+                plane_layout_count,
+                drm_format_modifier_plane_count,
+            } => write!(
+                f,
+                "the number of plane layouts ({}) did not equal the number of memory planes of \
+                the DRM format modifier ({})",
+                plane_layout_count, drm_format_modifier_plane_count,
+            ),
+            Self::DrmFormatModifierPlaneLayoutInvalid => write!(
+                f,
+                "one of the plane layouts had a nonzero `size`, a nonzero `array_pitch` while the \
+                image has only one array layer, or a nonzero `depth_pitch` while the image is not \
+                3D",
+            ),
+            Self::DrmFormatModifiersMissing => write!(
+                f,
+                "tiling was `DrmFormatModifier`, but no DRM format modifiers were provided",
+            ),
+            Self::DrmFormatModifiersNotDrmTiling => write!(
+                f,
+                "DRM format modifiers or plane layouts were provided, but tiling was not \
+                `DrmFormatModifier`",
+            ),
             Self::ExternalMemoryInvalidInitialLayout => write!(
                 f,
                 "one or more external memory handle types were provided, but the initial layout \
@@ -2116,6 +2883,26 @@ impl Display for ImageCreationError {
                 "the provided `usage` and `stencil_usage` have different values for \
                 `depth_stencil_attachment` or `transient_attachment`",
             ),
+            Self::SubsampledCubeCompatible => write!(
+                f,
+                "the `subsampled` flag was set, and the `cube_compatible` flag was set",
+            ),
+            Self::SubsampledMultipleMipLevels => write!(
+                f,
+                "the `subsampled` flag was set, and multiple mip levels were specified",
+            ),
+            Self::SubsampledNot2d => write!(
+                f,
+                "the `subsampled` flag was set, but the image type was not 2D",
+            ),
+            Self::SubsampledNotOptimalTiling => write!(
+                f,
+                "the `subsampled` flag was set, but tiling was not `Optimal`",
+            ),
+            Self::View2dCompatibleNot3d => write!(
+                f,
+                "the `view_2d_compatible` flag was set, but the image type was not 3D",
+            ),
             Self::YcbcrFormatInvalidDimensions => write!(
                 f,
                 "a YCbCr format was given, but the specified width and/or height was not a \
@@ -2167,7 +2954,8 @@ impl From<RequirementNotMet> for ImageCreationError {
     }
 }
 
-/// Describes the memory layout of an image with linear tiling.
+/// Describes the memory layout of an image with linear tiling, or of a memory plane of an image
+/// with a DRM format modifier.
 ///
 /// Obtained by calling `*_linear_layout` on the image.
 ///
@@ -2570,14 +3358,16 @@ impl FusedIterator for SubresourceRangeIterator {}
 
 #[cfg(test)]
 mod tests {
-    use super::{ImageCreationError, ImageUsage, UnsafeImage, UnsafeImageCreateInfo};
+    use super::{ImageCreationError, ImageUsage, LinearLayout, UnsafeImage, UnsafeImageCreateInfo};
     use crate::{
+        device::{Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo},
         format::Format,
         image::{
-            sys::SubresourceRangeIterator, ImageAspect, ImageAspects, ImageDimensions,
-            ImageSubresourceRange, SampleCount,
+            sys::SubresourceRangeIterator, ImageAspect, ImageAspects, ImageCreateFlags,
+            ImageDimensions, ImageSubresourceRange, ImageTiling, SampleCount,
         },
-        DeviceSize, RequiresOneOf,
+        sync::Sharing,
+        DeviceSize, RequiresOneOf, VulkanObject,
     };
     use smallvec::SmallVec;
 
@@ -2797,6 +3587,242 @@ mod tests {
         };
     }
 
+    #[test]
+    fn subsampled_extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let res = UnsafeImage::new(
+            device,
+            UnsafeImageCreateInfo {
+                dimensions: ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 1,
+                },
+                format: Some(Format::R8G8B8A8_UNORM),
+                usage: ImageUsage {
+                    color_attachment: true,
+                    ..ImageUsage::empty()
+                },
+                subsampled: true,
+                ..Default::default()
+            },
+        );
+
+        match res {
+            Err(ImageCreationError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        };
+    }
+
+    #[test]
+    fn view_2d_compatible_extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let res = UnsafeImage::new(
+            device,
+            UnsafeImageCreateInfo {
+                dimensions: ImageDimensions::Dim3d {
+                    width: 32,
+                    height: 32,
+                    depth: 4,
+                },
+                format: Some(Format::R8G8B8A8_UNORM),
+                usage: ImageUsage {
+                    storage: true,
+                    ..ImageUsage::empty()
+                },
+                view_2d_compatible: true,
+                ..Default::default()
+            },
+        );
+
+        match res {
+            Err(ImageCreationError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        };
+    }
+
+    #[test]
+    fn protected_feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let res = UnsafeImage::new(
+            device,
+            UnsafeImageCreateInfo {
+                dimensions: ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 1,
+                },
+                format: Some(Format::R8G8B8A8_UNORM),
+                usage: ImageUsage {
+                    sampled: true,
+                    ..ImageUsage::empty()
+                },
+                protected: true,
+                ..Default::default()
+            },
+        );
+
+        match res {
+            Err(ImageCreationError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        };
+    }
+
+    #[test]
+    fn from_raw_protected() {
+        let (device, _) = gfx_dev_and_queue!(protected_memory);
+
+        let image = UnsafeImage::new(
+            device.clone(),
+            UnsafeImageCreateInfo {
+                dimensions: ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 1,
+                },
+                format: Some(Format::R8G8B8A8_UNORM),
+                usage: ImageUsage {
+                    sampled: true,
+                    ..ImageUsage::empty()
+                },
+                protected: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(image.protected());
+
+        // The wrapper doesn't destroy the handle, so `image` keeps ownership of it.
+        let raw = unsafe {
+            UnsafeImage::from_raw(
+                device,
+                image.internal_object(),
+                *image.usage(),
+                Format::R8G8B8A8_UNORM,
+                ImageCreateFlags {
+                    protected: true,
+                    ..ImageCreateFlags::empty()
+                },
+                image.dimensions(),
+                SampleCount::Sample1,
+                1,
+                Sharing::Exclusive,
+            )
+        };
+        assert!(raw.protected());
+    }
+
+    #[test]
+    fn drm_format_modifiers_not_drm_tiling() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let create_info = UnsafeImageCreateInfo {
+            dimensions: ImageDimensions::Dim2d {
+                width: 32,
+                height: 32,
+                array_layers: 1,
+            },
+            format: Some(Format::R8G8B8A8_UNORM),
+            tiling: ImageTiling::Optimal,
+            usage: ImageUsage {
+                sampled: true,
+                ..ImageUsage::empty()
+            },
+            ..Default::default()
+        };
+
+        let res = UnsafeImage::new(
+            device.clone(),
+            UnsafeImageCreateInfo {
+                drm_format_modifiers: vec![0],
+                ..create_info.clone()
+            },
+        );
+
+        match res {
+            Err(ImageCreationError::DrmFormatModifiersNotDrmTiling) => (),
+            _ => panic!(),
+        };
+
+        let res = UnsafeImage::new(
+            device,
+            UnsafeImageCreateInfo {
+                drm_format_modifier_plane_layouts: vec![LinearLayout {
+                    offset: 0,
+                    size: 0,
+                    row_pitch: 128,
+                    array_pitch: 0,
+                    depth_pitch: 0,
+                }],
+                ..create_info
+            },
+        );
+
+        match res {
+            Err(ImageCreationError::DrmFormatModifiersNotDrmTiling) => (),
+            _ => panic!(),
+        };
+    }
+
+    #[test]
+    fn drm_format_modifiers_missing() {
+        let instance = instance!();
+
+        let enabled_extensions = DeviceExtensions {
+            ext_image_drm_format_modifier: true,
+            ..DeviceExtensions::empty()
+        };
+        let physical_device = match instance
+            .enumerate_physical_devices()
+            .unwrap()
+            .find(|p| p.supported_extensions().contains(&enabled_extensions))
+        {
+            Some(p) => p,
+            None => return, // test ignored
+        };
+
+        let (device, _) = match Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index: 0,
+                    ..Default::default()
+                }],
+                enabled_extensions,
+                ..Default::default()
+            },
+        ) {
+            Ok(x) => x,
+            Err(_) => return, // test ignored
+        };
+
+        let res = UnsafeImage::new(
+            device,
+            UnsafeImageCreateInfo {
+                dimensions: ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 1,
+                },
+                format: Some(Format::R8G8B8A8_UNORM),
+                tiling: ImageTiling::DrmFormatModifier,
+                usage: ImageUsage {
+                    sampled: true,
+                    ..ImageUsage::empty()
+                },
+                ..Default::default()
+            },
+        );
+
+        match res {
+            Err(ImageCreationError::DrmFormatModifiersMissing) => (),
+            _ => panic!(),
+        };
+    }
+
     #[test]
     #[allow(clippy::erasing_op, clippy::identity_op)]
     fn subresource_range_iterator() {
@@ -2949,4 +3975,72 @@ mod tests {
         );
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn range_to_subresources() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let image = UnsafeImage::new(
+            device,
+            UnsafeImageCreateInfo {
+                dimensions: ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 4,
+                },
+                format: Some(Format::R8G8B8A8_UNORM),
+                mip_levels: 3,
+                usage: ImageUsage {
+                    sampled: true,
+                    ..ImageUsage::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let color = ImageAspects {
+            color: true,
+            ..ImageAspects::empty()
+        };
+        let subresource_range = |mip_levels, array_layers| ImageSubresourceRange {
+            aspects: color,
+            mip_levels,
+            array_layers,
+        };
+
+        // The whole image
+        assert_eq!(
+            image.range_to_subresources(0..12).collect::<Vec<_>>(),
+            [subresource_range(0..3, 0..4)]
+        );
+
+        // Whole mip levels
+        assert_eq!(
+            image.range_to_subresources(4..12).collect::<Vec<_>>(),
+            [subresource_range(1..3, 0..4)]
+        );
+
+        // Some layers of one mip level
+        assert_eq!(
+            image.range_to_subresources(5..7).collect::<Vec<_>>(),
+            [subresource_range(1..2, 1..3)]
+        );
+
+        // Crossing into the next mip level in the middle of the layers
+        assert_eq!(
+            image.range_to_subresources(2..6).collect::<Vec<_>>(),
+            [subresource_range(0..1, 2..4), subresource_range(1..2, 0..2)]
+        );
+
+        // Crossing into the next mip levels, then ending in the middle of the layers
+        assert_eq!(
+            image.range_to_subresources(2..11).collect::<Vec<_>>(),
+            [
+                subresource_range(0..1, 2..4),
+                subresource_range(1..2, 0..4),
+                subresource_range(2..3, 0..3),
+            ]
+        );
+    }
 }
@@ -0,0 +1,599 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Software decoding of compressed image data on upload.
+//!
+//! Not every device supports every block compression scheme. For example, ASTC is rare on desktop
+//! GPUs, while BC is rare on mobile GPUs. A [`Transcoder`] lets an application ship its textures in
+//! one compressed format, and have them decoded on the CPU into a format that the device does
+//! support when needed. See [`ImmutableImage::from_compressed_data`].
+//!
+//! Vulkano includes [`Etc2Rgb8Transcoder`], which decodes ETC2 RGB data. For other formats,
+//! implement `Transcoder` on top of a decoding library of your choice.
+//!
+//! This module is only available with the `transcode` cargo feature.
+//!
+//! [`ImmutableImage::from_compressed_data`]: super::ImmutableImage::from_compressed_data
+
+use crate::{
+    device::Device,
+    format::{CompressionType, Format},
+};
+use std::error::Error;
+
+/// Decodes compressed image data into another format on the CPU.
+pub trait Transcoder: Send + Sync {
+    /// Returns the format that data in `format` will be decoded into, or `None` if this
+    /// transcoder does not support decoding `format`.
+    ///
+    /// The returned format must be supported by the device for sampling and as a transfer
+    /// destination with optimal tiling.
+    fn target_format(&self, format: Format) -> Option<Format>;
+
+    /// Decodes `data` from `format` into `target_format`, which is the value previously returned
+    /// by `target_format`.
+    ///
+    /// `data` contains the tightly packed texel blocks of every array layer of the first mip level
+    /// of an image with the given `extent`. The returned data must be laid out the same way.
+    fn transcode(
+        &self,
+        data: &[u8],
+        format: Format,
+        extent: [u32; 3],
+        array_layers: u32,
+        target_format: Format,
+    ) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>>;
+}
+
+/// A [`Transcoder`] that decodes `ETC2_R8G8B8_UNORM_BLOCK` and `ETC2_R8G8B8_SRGB_BLOCK` data into
+/// `R8G8B8A8_UNORM` and `R8G8B8A8_SRGB` respectively, with every alpha value set to 255.
+///
+/// ETC2 data with an alpha channel is not supported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Etc2Rgb8Transcoder;
+
+impl Transcoder for Etc2Rgb8Transcoder {
+    fn target_format(&self, format: Format) -> Option<Format> {
+        match format {
+            Format::ETC2_R8G8B8_UNORM_BLOCK => Some(Format::R8G8B8A8_UNORM),
+            Format::ETC2_R8G8B8_SRGB_BLOCK => Some(Format::R8G8B8A8_SRGB),
+            _ => None,
+        }
+    }
+
+    fn transcode(
+        &self,
+        data: &[u8],
+        format: Format,
+        extent: [u32; 3],
+        array_layers: u32,
+        target_format: Format,
+    ) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        if self.target_format(format) != Some(target_format) {
+            return Err(format!("can't decode {:?} into {:?}", format, target_format).into());
+        }
+
+        let [width, height, depth] = extent.map(|x| x as usize);
+        let blocks_x = (width + 3) / 4;
+        let blocks_y = (height + 3) / 4;
+        let slices = depth * array_layers as usize;
+
+        let required_size = blocks_x * blocks_y * slices * 8;
+
+        if data.len() < required_size {
+            return Err(format!(
+                "the data is too small: {} bytes were provided, but {} are required",
+                data.len(),
+                required_size,
+            )
+            .into());
+        }
+
+        let slice_size = width * height * 4;
+        let mut output = vec![0; slice_size * slices];
+
+        for (index, block) in data[..required_size].chunks_exact(8).enumerate() {
+            let slice = index / (blocks_x * blocks_y);
+            let block_y = index / blocks_x % blocks_y;
+            let block_x = index % blocks_x;
+
+            let texels = decode_etc2_rgb8_block(block.try_into().unwrap());
+
+            for (y, row) in texels.iter().enumerate() {
+                let y = block_y * 4 + y;
+
+                if y >= height {
+                    break;
+                }
+
+                for (x, texel) in row.iter().enumerate() {
+                    let x = block_x * 4 + x;
+
+                    if x >= width {
+                        break;
+                    }
+
+                    let offset = slice * slice_size + (y * width + x) * 4;
+                    output[offset..offset + 3].copy_from_slice(texel);
+                    output[offset + 3] = 255;
+                }
+            }
+        }
+
+        Ok(output)
+    }
+}
+
+// The modifier tables of individual and differential mode, indexed by the table codeword.
+const ETC_MODIFIERS: [[i32; 2]; 8] = [
+    [2, 8],
+    [5, 17],
+    [9, 29],
+    [13, 42],
+    [18, 60],
+    [24, 80],
+    [33, 106],
+    [47, 183],
+];
+
+// The distances of T and H mode, indexed by the distance index.
+const ETC_DISTANCES: [i32; 8] = [3, 6, 11, 16, 23, 32, 41, 64];
+
+/// Decodes a single 4×4 ETC2 RGB block into its texels, indexed by `[y][x]`.
+fn decode_etc2_rgb8_block(block: [u8; 8]) -> [[[u8; 3]; 4]; 4] {
+    let bits = u64::from_be_bytes(block);
+    let get = |offset: u32, count: u32| ((bits >> offset) & ((1 << count) - 1)) as i32;
+
+    // The 2-bit index of each texel is split over two 16-bit fields, and texels are numbered
+    // column by column.
+    let texel_index = |x: usize, y: usize| {
+        let i = x as u32 * 4 + y as u32;
+        (get(16 + i, 1) << 1) | get(i, 1)
+    };
+    let extend4 = |x: i32| x * 17;
+    let extend5 = |x: i32| (x << 3) | (x >> 2);
+    let extend6 = |x: i32| (x << 2) | (x >> 4);
+    let extend7 = |x: i32| (x << 1) | (x >> 6);
+    let clamp = |x: i32| x.clamp(0, 255) as u8;
+    let offset_color = |color: [i32; 3], offset: i32| color.map(|c| clamp(c + offset));
+
+    let mut texels = [[[0; 3]; 4]; 4];
+
+    if get(33, 1) == 0 {
+        // Individual mode.
+        let colors = [
+            [get(60, 4), get(52, 4), get(44, 4)].map(extend4),
+            [get(56, 4), get(48, 4), get(40, 4)].map(extend4),
+        ];
+        decode_etc_subblocks(&mut texels, bits, colors, texel_index);
+
+        return texels;
+    }
+
+    let base = [get(59, 5), get(51, 5), get(43, 5)];
+    let delta = [get(56, 3), get(48, 3), get(40, 3)].map(|d| (d << 29) >> 29);
+    let second = [0, 1, 2].map(|i| base[i] + delta[i]);
+
+    if !(0..32).contains(&second[0]) {
+        // T mode.
+        let color1 = [(get(59, 2) << 2) | get(56, 2), get(52, 4), get(48, 4)].map(extend4);
+        let color2 = [get(44, 4), get(40, 4), get(36, 4)].map(extend4);
+        let distance = ETC_DISTANCES[((get(34, 2) << 1) | get(32, 1)) as usize];
+        let paint = [
+            color1.map(clamp),
+            offset_color(color2, distance),
+            color2.map(clamp),
+            offset_color(color2, -distance),
+        ];
+
+        for (y, row) in texels.iter_mut().enumerate() {
+            for (x, texel) in row.iter_mut().enumerate() {
+                *texel = paint[texel_index(x, y) as usize];
+            }
+        }
+    } else if !(0..32).contains(&second[1]) {
+        // H mode.
+        let color1 = [
+            get(59, 4),
+            (get(56, 3) << 1) | get(52, 1),
+            (get(51, 1) << 3) | get(47, 3),
+        ];
+        let color2 = [get(43, 4), get(39, 4), get(35, 4)];
+
+        // The lowest bit of the distance index is given by the order of the two colors.
+        let value = |[r, g, b]: [i32; 3]| (r << 8) | (g << 4) | b;
+        let order = (value(color1) >= value(color2)) as i32;
+        let distance = ETC_DISTANCES[((get(34, 1) << 2) | (get(32, 1) << 1) | order) as usize];
+
+        let [color1, color2] = [color1, color2].map(|c| c.map(extend4));
+        let paint = [
+            offset_color(color1, distance),
+            offset_color(color1, -distance),
+            offset_color(color2, distance),
+            offset_color(color2, -distance),
+        ];
+
+        for (y, row) in texels.iter_mut().enumerate() {
+            for (x, texel) in row.iter_mut().enumerate() {
+                *texel = paint[texel_index(x, y) as usize];
+            }
+        }
+    } else if !(0..32).contains(&second[2]) {
+        // Planar mode.
+        let origin = [
+            extend6(get(57, 6)),
+            extend7((get(56, 1) << 6) | get(49, 6)),
+            extend6((get(48, 1) << 5) | (get(43, 2) << 3) | get(39, 3)),
+        ];
+        let horizontal = [
+            extend6((get(34, 5) << 1) | get(32, 1)),
+            extend7(get(25, 7)),
+            extend6(get(19, 6)),
+        ];
+        let vertical = [extend6(get(13, 6)), extend7(get(6, 7)), extend6(get(0, 6))];
+
+        for (y, row) in texels.iter_mut().enumerate() {
+            for (x, texel) in row.iter_mut().enumerate() {
+                *texel = [0, 1, 2].map(|i| {
+                    clamp(
+                        (x as i32 * (horizontal[i] - origin[i])
+                            + y as i32 * (vertical[i] - origin[i])
+                            + 4 * origin[i]
+                            + 2)
+                            >> 2,
+                    )
+                });
+            }
+        }
+    } else {
+        // Differential mode.
+        let colors = [base.map(extend5), second.map(extend5)];
+        decode_etc_subblocks(&mut texels, bits, colors, texel_index);
+    }
+
+    texels
+}
+
+/// Decodes the two subblocks of an individual or differential mode block.
+fn decode_etc_subblocks(
+    texels: &mut [[[u8; 3]; 4]; 4],
+    bits: u64,
+    colors: [[i32; 3]; 2],
+    texel_index: impl Fn(usize, usize) -> i32,
+) {
+    let flip = (bits >> 32) & 1 != 0;
+    let tables = [(bits >> 37) & 7, (bits >> 34) & 7].map(|t| ETC_MODIFIERS[t as usize]);
+
+    for (y, row) in texels.iter_mut().enumerate() {
+        for (x, texel) in row.iter_mut().enumerate() {
+            // Without flip, the subblocks are side by side, otherwise they are on top of each
+            // other.
+            let subblock = if flip { y / 2 } else { x / 2 };
+            let [small, large] = tables[subblock];
+            let modifier = match texel_index(x, y) {
+                0 => small,
+                1 => large,
+                2 => -small,
+                _ => -large,
+            };
+
+            *texel = colors[subblock].map(|c| (c + modifier).clamp(0, 255) as u8);
+        }
+    }
+}
+
+/// Returns whether images with `format` can't be sampled on `device` and must be transcoded.
+///
+/// This is the case if the feature required for the compression scheme of `format` is not enabled,
+/// or if the device does not support sampling from, or copying to, images of that format.
+pub fn needs_transcode(device: &Device, format: Format) -> bool {
+    let features = device.enabled_features();
+
+    let feature_enabled = match format.compression() {
+        Some(CompressionType::ASTC_LDR) => features.texture_compression_astc_ldr,
+        Some(CompressionType::ASTC_HDR) => features.texture_compression_astc_hdr,
+        Some(CompressionType::BC) => features.texture_compression_bc,
+        Some(CompressionType::ETC2 | CompressionType::EAC) => features.texture_compression_etc2,
+        _ => true,
+    };
+
+    if !feature_enabled {
+        return true;
+    }
+
+    let format_features = device
+        .physical_device()
+        .format_properties(format)
+        .map_or_else(|_| Default::default(), |p| p.optimal_tiling_features);
+
+    !(format_features.sampled_image && format_features.transfer_dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{needs_transcode, Etc2Rgb8Transcoder, Transcoder};
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+        },
+        format::Format,
+        image::{
+            immutable::ImmutableImageCreationError, ImageAccess, ImageDimensions, ImmutableImage,
+            MipmapsCount,
+        },
+    };
+    use std::{
+        error::Error,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+
+    /// Pretends to decode BC1 data into R8G8B8A8, filling every texel with `fill`.
+    struct TestTranscoder {
+        fill: Option<u8>,
+        calls: AtomicU32,
+    }
+
+    impl Transcoder for TestTranscoder {
+        fn target_format(&self, format: Format) -> Option<Format> {
+            if format == Format::BC1_RGBA_UNORM_BLOCK {
+                Some(Format::R8G8B8A8_UNORM)
+            } else {
+                None
+            }
+        }
+
+        fn transcode(
+            &self,
+            _data: &[u8],
+            _format: Format,
+            extent: [u32; 3],
+            array_layers: u32,
+            _target_format: Format,
+        ) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+
+            match self.fill {
+                Some(fill) => {
+                    let len = extent[0] * extent[1] * extent[2] * array_layers * 4;
+                    Ok(vec![fill; len as usize])
+                }
+                None => Err("decoding failed".into()),
+            }
+        }
+    }
+
+    /// Decodes a single 4×4 block with `Etc2Rgb8Transcoder`, and returns the RGB values of the
+    /// texels, row by row.
+    fn decode_etc2_block(block: [u8; 8]) -> Vec<[u8; 3]> {
+        Etc2Rgb8Transcoder
+            .transcode(
+                &block,
+                Format::ETC2_R8G8B8_UNORM_BLOCK,
+                [4, 4, 1],
+                1,
+                Format::R8G8B8A8_UNORM,
+            )
+            .unwrap()
+            .chunks_exact(4)
+            .map(|texel| {
+                assert_eq!(texel[3], 255);
+                [texel[0], texel[1], texel[2]]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn etc2_individual_mode() {
+        // Red on the left and green on the right, every texel using the +2 modifier.
+        let texels = decode_etc2_block([0xf0, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+        for (i, texel) in texels.into_iter().enumerate() {
+            let expected = if i % 4 < 2 { [255, 2, 2] } else { [2, 255, 2] };
+            assert_eq!(texel, expected);
+        }
+    }
+
+    #[test]
+    fn etc2_differential_mode() {
+        // A base red of 16 and a delta of -1, with flipped subblocks.
+        let texels = decode_etc2_block([0x87, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00]);
+
+        for (i, texel) in texels.into_iter().enumerate() {
+            let expected = if i / 4 < 2 { [134, 2, 2] } else { [125, 2, 2] };
+            assert_eq!(texel, expected);
+        }
+    }
+
+    #[test]
+    fn etc2_t_mode() {
+        // Black and white, with a distance of 3. The first column uses each paint color once.
+        let texels = decode_etc2_block([0x04, 0x00, 0xff, 0xf2, 0x00, 0x06, 0x00, 0x05]);
+
+        assert_eq!(texels[0], [255; 3]);
+        assert_eq!(texels[4], [255; 3]);
+        assert_eq!(texels[8], [252; 3]);
+        assert_eq!(texels[12], [0; 3]);
+        assert!(texels
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i % 4 != 0)
+            .all(|(_, &texel)| texel == [0; 3]));
+    }
+
+    #[test]
+    fn etc2_planar_mode() {
+        // A blue of 30 everywhere, and a red gradient from 0 to 63 along the x axis.
+        let texels = decode_etc2_block([0x00, 0x00, 0xfb, 0x7f, 0x00, 0xf0, 0x00, 0x1e]);
+
+        for (i, texel) in texels.into_iter().enumerate() {
+            assert_eq!(texel, [[0, 64, 128, 191][i % 4], 0, 121]);
+        }
+    }
+
+    #[test]
+    fn etc2_partial_block() {
+        let decoded = Etc2Rgb8Transcoder
+            .transcode(
+                &[0xf0, 0x0f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+                Format::ETC2_R8G8B8_SRGB_BLOCK,
+                [3, 1, 1],
+                1,
+                Format::R8G8B8A8_SRGB,
+            )
+            .unwrap();
+
+        assert_eq!(decoded, [255, 2, 2, 255, 255, 2, 2, 255, 2, 255, 2, 255]);
+        assert!(Etc2Rgb8Transcoder
+            .transcode(
+                &[0; 8],
+                Format::ETC2_R8G8B8_UNORM_BLOCK,
+                [8, 4, 1],
+                1,
+                Format::R8G8B8A8_UNORM,
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn needs_transcode_uncompressed() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        assert!(!needs_transcode(&device, Format::R8G8B8A8_UNORM));
+    }
+
+    #[test]
+    fn needs_transcode_feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        assert!(needs_transcode(&device, Format::BC1_RGBA_UNORM_BLOCK));
+        assert!(needs_transcode(&device, Format::ETC2_R8G8B8_UNORM_BLOCK));
+        assert!(needs_transcode(&device, Format::ASTC_4x4_UNORM_BLOCK));
+    }
+
+    #[test]
+    fn needs_transcode_feature_enabled() {
+        let (device, _) = gfx_dev_and_queue!(texture_compression_bc);
+
+        let format_features = device
+            .physical_device()
+            .format_properties(Format::BC1_RGBA_UNORM_BLOCK)
+            .unwrap()
+            .optimal_tiling_features;
+
+        assert_eq!(
+            needs_transcode(&device, Format::BC1_RGBA_UNORM_BLOCK),
+            !(format_features.sampled_image && format_features.transfer_dst),
+        );
+    }
+
+    #[test]
+    fn from_compressed_data_transcodes() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device);
+        let mut command_buffer_builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        let transcoder = TestTranscoder {
+            fill: Some(0xff),
+            calls: AtomicU32::new(0),
+        };
+        let image = ImmutableImage::from_compressed_data(
+            &[0; 8],
+            ImageDimensions::Dim2d {
+                width: 4,
+                height: 4,
+                array_layers: 1,
+            },
+            MipmapsCount::One,
+            Format::BC1_RGBA_UNORM_BLOCK,
+            &transcoder,
+            &mut command_buffer_builder,
+        )
+        .unwrap();
+
+        assert_eq!(image.format(), Format::R8G8B8A8_UNORM);
+        assert_eq!(transcoder.calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn from_compressed_data_errors() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device);
+        let mut command_buffer_builder = AutoCommandBufferBuilder::primary(
+            &command_buffer_allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap();
+
+        let dimensions = ImageDimensions::Dim2d {
+            width: 4,
+            height: 4,
+            array_layers: 1,
+        };
+        let transcoder = TestTranscoder {
+            fill: Some(0xff),
+            calls: AtomicU32::new(0),
+        };
+
+        match ImmutableImage::from_compressed_data(
+            &[0; 16],
+            dimensions,
+            MipmapsCount::One,
+            Format::ASTC_4x4_UNORM_BLOCK,
+            &transcoder,
+            &mut command_buffer_builder,
+        ) {
+            Err(ImmutableImageCreationError::TranscodeNotSupported {
+                format: Format::ASTC_4x4_UNORM_BLOCK,
+            }) => (),
+            _ => panic!(),
+        }
+
+        match ImmutableImage::from_compressed_data(
+            &[0; 4],
+            dimensions,
+            MipmapsCount::One,
+            Format::BC1_RGBA_UNORM_BLOCK,
+            &transcoder,
+            &mut command_buffer_builder,
+        ) {
+            Err(ImmutableImageCreationError::SourceTooSmall {
+                source_size: 4,
+                required_size: 8,
+            }) => (),
+            _ => panic!(),
+        }
+
+        assert_eq!(transcoder.calls.load(Ordering::Relaxed), 0);
+
+        let transcoder = TestTranscoder {
+            fill: None,
+            calls: AtomicU32::new(0),
+        };
+
+        match ImmutableImage::from_compressed_data(
+            &[0; 8],
+            dimensions,
+            MipmapsCount::One,
+            Format::BC1_RGBA_UNORM_BLOCK,
+            &transcoder,
+            &mut command_buffer_builder,
+        ) {
+            Err(ImmutableImageCreationError::TranscodeFailed(_)) => (),
+            _ => panic!(),
+        }
+    }
+}
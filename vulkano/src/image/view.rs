@@ -229,8 +229,11 @@ where
         if image_type == ImageType::Dim3d
             && (view_type == ImageViewType::Dim2d || view_type == ImageViewType::Dim2dArray)
         {
-            // VUID-VkImageViewCreateInfo-image-01005
-            if !image_inner.array_2d_compatible() {
+            // VUID-VkImageViewCreateInfo-image-06723
+            // VUID-VkImageViewCreateInfo-image-06728
+            if !(image_inner.array_2d_compatible()
+                || view_type == ImageViewType::Dim2d && image_inner.view_2d_compatible())
+            {
                 return Err(ImageViewCreationError::ImageNotArray2dCompatible);
             }
 
@@ -566,6 +569,76 @@ where
         Self::new(image, create_info)
     }
 
+    /// Creates an `ImageView` that covers all mip levels of `image` starting at
+    /// `base_mip_level`, and is otherwise equivalent to [`ImageView::new_default`].
+    pub fn with_remaining_levels(
+        image: Arc<I>,
+        base_mip_level: u32,
+    ) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        let mut create_info = ImageViewCreateInfo::from_image(&image);
+        let max = create_info.subresource_range.mip_levels.end;
+
+        if base_mip_level >= max {
+            return Err(ImageViewCreationError::MipLevelsOutOfRange {
+                range_end: base_mip_level + 1,
+                max,
+            });
+        }
+
+        create_info.subresource_range.mip_levels.start = base_mip_level;
+        Self::new(image, create_info)
+    }
+
+    /// Creates an `ImageView` that covers all array layers of `image` starting at
+    /// `base_array_layer`, and is otherwise equivalent to [`ImageView::new_default`].
+    pub fn with_remaining_layers(
+        image: Arc<I>,
+        base_array_layer: u32,
+    ) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        let mut create_info = ImageViewCreateInfo::from_image(&image);
+        let max = create_info.subresource_range.array_layers.end;
+
+        if base_array_layer >= max {
+            return Err(ImageViewCreationError::ArrayLayersOutOfRange {
+                range_end: base_array_layer + 1,
+                max,
+            });
+        }
+
+        create_info.subresource_range.array_layers.start = base_array_layer;
+        Self::new(image, create_info)
+    }
+
+    /// Creates a 2D `ImageView` of a single depth slice of a 3D image, using the first mip level.
+    ///
+    /// The image must have been created with the `array_2d_compatible` or `view_2d_compatible`
+    /// flag. Views of an `array_2d_compatible` image can only be used as framebuffer attachments.
+    /// Views of a `view_2d_compatible` image can also be used in a storage image descriptor if
+    /// the [`image2_d_view_of3_d`](crate::device::Features::image2_d_view_of3_d) feature is
+    /// enabled, or in a sampled image descriptor if the
+    /// [`sampler2_d_view_of3_d`](crate::device::Features::sampler2_d_view_of3_d) feature is
+    /// enabled.
+    pub fn new_2d_slice_of_3d(
+        image: Arc<I>,
+        slice: u32,
+    ) -> Result<Arc<ImageView<I>>, ImageViewCreationError> {
+        if image.dimensions().image_type() != ImageType::Dim3d {
+            return Err(ImageViewCreationError::ImageTypeNotCompatible);
+        }
+
+        let create_info = ImageViewCreateInfo {
+            view_type: ImageViewType::Dim2d,
+            format: Some(image.format()),
+            subresource_range: ImageSubresourceRange {
+                aspects: image.format().aspects(),
+                mip_levels: 0..1,
+                array_layers: slice..slice + 1,
+            },
+            ..Default::default()
+        };
+        Self::new(image, create_info)
+    }
+
     /// Creates a new `ImageView` from a raw object handle.
     ///
     /// # Safety
@@ -628,6 +701,8 @@ where
                         cube_compatible: image_inner.cube_compatible(),
                         array_2d_compatible: image_inner.array_2d_compatible(),
                         block_texel_view_compatible: image_inner.block_texel_view_compatible(),
+                        subsampled: image_inner.subsampled(),
+                        protected: image_inner.protected(),
                         ..Default::default()
                     })?;
 
@@ -685,6 +760,16 @@ where
             match image.tiling() {
                 ImageTiling::Optimal => format_properties.optimal_tiling_features,
                 ImageTiling::Linear => format_properties.linear_tiling_features,
+                ImageTiling::DrmFormatModifier => device
+                    .physical_device()
+                    .drm_format_modifier_properties_unchecked(format)
+                    .into_iter()
+                    .find(|properties| {
+                        Some(properties.drm_format_modifier) == image.drm_format_modifier()
+                    })
+                    .map_or_else(Default::default, |properties| {
+                        properties.drm_format_modifier_tiling_features
+                    }),
             }
         } else {
             *image.format_features()
@@ -871,6 +956,17 @@ impl ImageViewCreateInfo {
             ..Default::default()
         }
     }
+
+    /// Returns an `ImageViewCreateInfo` like [`from_image`](Self::from_image), but with only
+    /// `aspects` selected in `subresource_range`.
+    ///
+    /// This can be used to create a depth-only or stencil-only view of a combined depth/stencil
+    /// image.
+    pub fn from_image_aspects(image: &(impl ImageAccess + ?Sized), aspects: ImageAspects) -> Self {
+        let mut create_info = Self::from_image(image);
+        create_info.subresource_range.aspects = aspects;
+        create_info
+    }
 }
 
 /// Error that can happen when creating an image view.
@@ -931,7 +1027,7 @@ pub enum ImageViewCreationError {
     ImageMissingUsage,
 
     /// A 2D image view was requested from a 3D image, but the image was not created with the
-    /// `array_2d_compatible` flag.
+    /// `array_2d_compatible` flag, or for a `Dim2d` view, the `view_2d_compatible` flag.
     ImageNotArray2dCompatible,
 
     /// A cube image view type was requested, but the image was not created with the
@@ -1049,7 +1145,7 @@ impl Display for ImageViewCreationError {
             Self::ImageNotArray2dCompatible => write!(
                 f,
                 "a 2D image view was requested from a 3D image, but the image was not created with \
-                the `array_2d_compatible` flag",
+                the `array_2d_compatible` flag, or for a `Dim2d` view, the `view_2d_compatible` flag",
             ),
             Self::ImageNotCubeCompatible => write!(
                 f,
@@ -40,23 +40,75 @@
 //! Note that you must keep the `_callback` object alive for as long as you want your callback to
 //! be callable. If you don't store the return value of `DebugUtilsMessenger`'s constructor in a
 //! variable, it will be immediately destroyed and your callback will not work.
+//!
+//! A `Message` only borrows its strings from the layer that reported it. To keep a message around
+//! after the callback has returned, convert it to a [`ValidationMessage`] with
+//! [`Message::to_validation_message`].
+//!
+//! # Debug printf
+//!
+//! With the validation layer, shaders can print values with `debugPrintfEXT` in GLSL. This needs
+//! [`InstanceCreateInfo::debug_printf`](super::InstanceCreateInfo::debug_printf) to be `true`
+//! when the instance is created, and the
+//! [`khr_shader_non_semantic_info`](crate::device::DeviceExtensions::khr_shader_non_semantic_info)
+//! extension to be enabled on the device if its API version is below 1.3. The printed output is
+//! reported through the debug messenger, and [`Message::debug_printf`] parses it into a
+//! [`DebugPrintfMessage`]. [`DebugUtilsMessengerCreateInfo::debug_printf`] creates a messenger
+//! that only receives the printed output:
+//!
+//! ```
+//! # use vulkano::instance::Instance;
+//! # use std::sync::Arc;
+//! # let instance: Arc<Instance> = return;
+//! use vulkano::instance::debug::{DebugUtilsMessenger, DebugUtilsMessengerCreateInfo};
+//!
+//! let _callback = unsafe {
+//!     DebugUtilsMessenger::new(
+//!         instance,
+//!         DebugUtilsMessengerCreateInfo::debug_printf(Arc::new(|msg| {
+//!             println!("{:?}: {}", msg.global_invocation_id, msg.message);
+//!         })),
+//!     ).ok()
+//! };
+//! ```
+//!
+//! # Capturing messages in tests
+//!
+//! [`DebugCallbackCapture`] registers a messenger that stores every message it receives, so that a
+//! test can check which messages were reported:
+//!
+//! ```
+//! # use vulkano::instance::Instance;
+//! # use std::sync::Arc;
+//! # let instance: Arc<Instance> = return;
+//! use vulkano::instance::debug::DebugCallbackCapture;
+//!
+//! let capture = DebugCallbackCapture::new(instance).unwrap();
+//!
+//! // Exercise the code under test...
+//!
+//! capture.assert_no_validation_errors();
+//! ```
 
 use super::Instance;
 use crate::{
     macros::{vulkan_bitflags, vulkan_enum},
     RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
 };
+use smallvec::SmallVec;
 use std::{
+    collections::VecDeque,
     error::Error,
     ffi::{c_void, CStr},
     fmt::{Debug, Display, Error as FmtError, Formatter},
     mem::MaybeUninit,
     panic::{catch_unwind, AssertUnwindSafe, RefUnwindSafe},
-    ptr,
-    sync::Arc,
+    ptr, slice,
+    sync::{Arc, Mutex},
 };
 
 pub(super) type UserCallback = Arc<dyn Fn(&Message<'_>) + RefUnwindSafe + Send + Sync>;
+type DebugPrintfCallback = Arc<dyn Fn(&DebugPrintfMessage) + RefUnwindSafe + Send + Sync>;
 
 /// Registration of a callback called by validation layers.
 ///
@@ -231,11 +283,34 @@ pub(super) unsafe extern "system" fn trampoline(
             .to_str()
             .expect("debug callback message not utf-8");
 
+        // Messages rarely concern more than a few objects, so this doesn't usually allocate.
+        let objects: SmallVec<[_; 4]> = if (*callback_data).object_count == 0 {
+            SmallVec::new()
+        } else {
+            slice::from_raw_parts(
+                (*callback_data).p_objects,
+                (*callback_data).object_count as usize,
+            )
+            .iter()
+            .map(|object| MessageObject {
+                object_type: object.object_type,
+                handle: object.object_handle,
+                name: object.p_object_name.as_ref().map(|name| {
+                    CStr::from_ptr(name)
+                        .to_str()
+                        .expect("debug callback object name not utf-8")
+                }),
+            })
+            .collect()
+        };
+
         let message = Message {
             severity: message_severity.into(),
             ty: message_types.into(),
             layer_prefix,
+            message_id_number: (*callback_data).message_id_number,
             description,
+            objects: &objects,
         };
 
         user_callback(&message);
@@ -332,6 +407,31 @@ impl DebugUtilsMessengerCreateInfo {
             _ne: crate::NonExhaustive(()),
         }
     }
+
+    /// Returns a `DebugUtilsMessengerCreateInfo` that calls `user_callback` with the output of
+    /// `debugPrintfEXT` in shaders, and ignores all other messages.
+    ///
+    /// [`InstanceCreateInfo::debug_printf`](super::InstanceCreateInfo::debug_printf) must be
+    /// `true` for the validation layer to report the output.
+    pub fn debug_printf(user_callback: DebugPrintfCallback) -> Self {
+        Self {
+            message_severity: DebugUtilsMessageSeverity {
+                warning: true,
+                information: true,
+                ..DebugUtilsMessageSeverity::empty()
+            },
+            message_type: DebugUtilsMessageType {
+                general: true,
+                validation: true,
+                ..DebugUtilsMessageType::empty()
+            },
+            ..Self::user_callback(Arc::new(move |message: &Message<'_>| {
+                if let Some(debug_printf_message) = message.debug_printf() {
+                    user_callback(&debug_printf_message);
+                }
+            }))
+        }
+    }
 }
 
 impl Debug for DebugUtilsMessengerCreateInfo {
@@ -358,8 +458,264 @@ pub struct Message<'a> {
     pub ty: DebugUtilsMessageType,
     /// Prefix of the layer that reported this message or `None` if unknown.
     pub layer_prefix: Option<&'a str>,
+    /// Number that identifies the kind of message. For the validation layers, this is a hash of
+    /// the VUID that was violated.
+    pub message_id_number: i32,
     /// Description of the message.
     pub description: &'a str,
+    /// The objects that the message is about.
+    pub objects: &'a [MessageObject<'a>],
+}
+
+impl<'a> Message<'a> {
+    /// Returns an owned copy of the message, that can be kept after the callback has returned.
+    pub fn to_validation_message(&self) -> ValidationMessage {
+        ValidationMessage {
+            severity: self.severity,
+            ty: self.ty,
+            message_id_name: self.layer_prefix.map(ToOwned::to_owned),
+            message_id_number: self.message_id_number,
+            description: self.description.to_owned(),
+            objects: self
+                .objects
+                .iter()
+                .map(|object| ValidationMessageObject {
+                    object_type: object.object_type,
+                    handle: object.handle,
+                    name: object.name.map(ToOwned::to_owned),
+                })
+                .collect(),
+        }
+    }
+
+    /// If the message contains the output of `debugPrintfEXT` in a shader, returns the parsed
+    /// output.
+    ///
+    /// The exact format of the message depends on the version of the validation layer. The
+    /// shader instruction index and global invocation ID are only included if the layer's
+    /// `printf_verbose` setting is enabled.
+    pub fn debug_printf(&self) -> Option<DebugPrintfMessage> {
+        if !matches!(
+            self.layer_prefix,
+            Some("UNASSIGNED-DEBUG-PRINTF" | "WARNING-DEBUG-PRINTF")
+        ) {
+            return None;
+        }
+
+        // The validation layer prefixes the output with the objects and message ID, separated by
+        // `|`.
+        let mut text = self.description;
+
+        if let Some(index) = text.find("MessageID = ") {
+            if let Some(end) = text[index..].find("| ") {
+                text = &text[index + end + 2..];
+            }
+        }
+
+        let shader_instruction_index =
+            text.split_once("Shader Instruction Index = ")
+                .and_then(|(_, rest)| {
+                    let end = rest
+                        .find(|c: char| !c.is_ascii_digit())
+                        .unwrap_or(rest.len());
+                    rest[..end].parse().ok()
+                });
+        let global_invocation_id = text
+            .split_once("Global invocation ID (x, y, z) = (")
+            .and_then(|(_, rest)| {
+                let mut components = rest
+                    .split(')')
+                    .next()?
+                    .split(',')
+                    .map(|component| component.trim().parse().ok());
+
+                Some([
+                    components.next()??,
+                    components.next()??,
+                    components.next()??,
+                ])
+            });
+
+        // In verbose mode, the location of the output takes up the first line.
+        if shader_instruction_index.is_some() {
+            if let Some((_, rest)) = text.split_once('\n') {
+                text = rest;
+            }
+        }
+
+        Some(DebugPrintfMessage {
+            message: text.trim_end().to_owned(),
+            shader_instruction_index,
+            global_invocation_id,
+            objects: self.to_validation_message().objects,
+        })
+    }
+}
+
+/// The output of `debugPrintfEXT` in a shader, parsed from a [`Message`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DebugPrintfMessage {
+    /// The formatted output of the shader.
+    pub message: String,
+    /// The index of the `debugPrintfEXT` instruction in the shader module, if reported.
+    pub shader_instruction_index: Option<u32>,
+    /// The global invocation ID of the invocation that produced the output, if reported.
+    pub global_invocation_id: Option<[u32; 3]>,
+    /// The objects that the message is about, usually the queue and command buffer.
+    pub objects: Vec<ValidationMessageObject>,
+}
+
+/// An object that a [`Message`] is about.
+#[derive(Clone, Copy, Debug)]
+pub struct MessageObject<'a> {
+    /// The type of the object.
+    pub object_type: ash::vk::ObjectType,
+    /// The raw handle of the object.
+    pub handle: u64,
+    /// The debug name of the object, if it has one.
+    pub name: Option<&'a str>,
+}
+
+/// An owned copy of a [`Message`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationMessage {
+    /// Severity of the message.
+    pub severity: DebugUtilsMessageSeverity,
+    /// Type of the message.
+    pub ty: DebugUtilsMessageType,
+    /// Name that identifies the kind of message, or `None` if unknown. For the validation layers,
+    /// this is the VUID that was violated.
+    pub message_id_name: Option<String>,
+    /// Number that identifies the kind of message.
+    pub message_id_number: i32,
+    /// Description of the message.
+    pub description: String,
+    /// The objects that the message is about.
+    pub objects: Vec<ValidationMessageObject>,
+}
+
+impl ValidationMessage {
+    /// Returns whether the message is an error reported by validation.
+    #[inline]
+    pub fn is_validation_error(&self) -> bool {
+        self.severity.error && self.ty.validation
+    }
+}
+
+impl Display for ValidationMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        if let Some(message_id_name) = &self.message_id_name {
+            write!(f, "[{}] ", message_id_name)?;
+        }
+
+        write!(f, "{}", self.description)
+    }
+}
+
+/// An object that a [`ValidationMessage`] is about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValidationMessageObject {
+    /// The type of the object.
+    pub object_type: ash::vk::ObjectType,
+    /// The raw handle of the object.
+    pub handle: u64,
+    /// The debug name of the object, if it has one.
+    pub name: Option<String>,
+}
+
+/// Registration of a callback that stores all messages it receives, for inspection by tests.
+///
+/// Messages of all types, and of error, warning and information severity, are captured.
+#[derive(Debug)]
+pub struct DebugCallbackCapture {
+    _messenger: DebugUtilsMessenger,
+    // A `std` mutex rather than a `parking_lot` one, because the callback must be
+    // `RefUnwindSafe`.
+    messages: Arc<Mutex<VecDeque<ValidationMessage>>>,
+}
+
+impl DebugCallbackCapture {
+    /// Registers a new messenger with `instance` that captures messages.
+    pub fn new(instance: Arc<Instance>) -> Result<Self, DebugUtilsMessengerCreationError> {
+        let messages: Arc<Mutex<VecDeque<ValidationMessage>>> = Default::default();
+        let user_callback = {
+            let messages = messages.clone();
+            Arc::new(move |message: &Message<'_>| {
+                messages
+                    .lock()
+                    .unwrap()
+                    .push_back(message.to_validation_message())
+            })
+        };
+
+        // Safe because the callback only stores the message.
+        let messenger = unsafe {
+            DebugUtilsMessenger::new(
+                instance,
+                DebugUtilsMessengerCreateInfo {
+                    message_severity: DebugUtilsMessageSeverity {
+                        error: true,
+                        warning: true,
+                        information: true,
+                        ..DebugUtilsMessageSeverity::empty()
+                    },
+                    message_type: DebugUtilsMessageType {
+                        general: true,
+                        validation: true,
+                        performance: true,
+                        ..DebugUtilsMessageType::empty()
+                    },
+                    ..DebugUtilsMessengerCreateInfo::user_callback(user_callback)
+                },
+            )?
+        };
+
+        Ok(DebugCallbackCapture {
+            _messenger: messenger,
+            messages,
+        })
+    }
+
+    /// Returns the messages that have been captured so far, in the order they were received.
+    #[inline]
+    pub fn messages(&self) -> Vec<ValidationMessage> {
+        self.messages.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Removes and returns the messages that have been captured so far, in the order they were
+    /// received.
+    #[inline]
+    pub fn take_messages(&self) -> Vec<ValidationMessage> {
+        self.messages.lock().unwrap().drain(..).collect()
+    }
+
+    /// Returns the captured messages that are errors reported by validation.
+    #[inline]
+    pub fn validation_errors(&self) -> Vec<ValidationMessage> {
+        self.messages
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|message| message.is_validation_error())
+            .cloned()
+            .collect()
+    }
+
+    /// Panics if any validation errors have been captured.
+    ///
+    /// The panic message lists all the captured validation errors.
+    pub fn assert_no_validation_errors(&self) {
+        let errors = self.validation_errors();
+
+        if !errors.is_empty() {
+            let list: Vec<_> = errors.iter().map(ToString::to_string).collect();
+            panic!(
+                "{} validation error(s) were reported:\n{}",
+                errors.len(),
+                list.join("\n"),
+            );
+        }
+    }
 }
 
 vulkan_bitflags! {
@@ -548,4 +904,78 @@ mod tests {
             drop(callback);
         });
     }
+
+    #[test]
+    fn validation_message_from_message() {
+        let message = Message {
+            severity: DebugUtilsMessageSeverity {
+                error: true,
+                ..DebugUtilsMessageSeverity::empty()
+            },
+            ty: DebugUtilsMessageType {
+                validation: true,
+                ..DebugUtilsMessageType::empty()
+            },
+            layer_prefix: Some("VUID-vkTest-test-00000"),
+            message_id_number: 42,
+            description: "test message",
+            objects: &[MessageObject {
+                object_type: ash::vk::ObjectType::BUFFER,
+                handle: 1,
+                name: Some("buffer"),
+            }],
+        };
+
+        let validation_message = message.to_validation_message();
+        assert!(validation_message.is_validation_error());
+        assert_eq!(
+            validation_message.objects[0].name.as_deref(),
+            Some("buffer")
+        );
+        assert_eq!(
+            validation_message.to_string(),
+            "[VUID-vkTest-test-00000] test message",
+        );
+    }
+
+    #[test]
+    fn debug_printf_message() {
+        let message = Message {
+            severity: DebugUtilsMessageSeverity {
+                information: true,
+                ..DebugUtilsMessageSeverity::empty()
+            },
+            ty: DebugUtilsMessageType {
+                validation: true,
+                ..DebugUtilsMessageType::empty()
+            },
+            layer_prefix: Some("UNASSIGNED-DEBUG-PRINTF"),
+            message_id_number: 0,
+            description: "Validation Information: [ UNASSIGNED-DEBUG-PRINTF ] Object 0: \
+                handle = 0x1, type = VK_OBJECT_TYPE_QUEUE; | MessageID = 0x92394c89 | Command \
+                buffer (0x2). Compute Dispatch Index 0. Shader Instruction Index = 42. Stage = \
+                Compute.  Global invocation ID (x, y, z) = (3, 1, 0 )\nvalue: 1.5",
+            objects: &[],
+        };
+
+        let debug_printf_message = message.debug_printf().unwrap();
+        assert_eq!(debug_printf_message.message, "value: 1.5");
+        assert_eq!(debug_printf_message.shader_instruction_index, Some(42));
+        assert_eq!(debug_printf_message.global_invocation_id, Some([3, 1, 0]));
+
+        let message = Message {
+            layer_prefix: Some("UNASSIGNED-DEBUG-PRINTF"),
+            description: "value: 2",
+            ..message
+        };
+        let debug_printf_message = message.debug_printf().unwrap();
+        assert_eq!(debug_printf_message.message, "value: 2");
+        assert_eq!(debug_printf_message.shader_instruction_index, None);
+
+        let message = Message {
+            layer_prefix: Some("VUID-vkTest-test-00000"),
+            ..message
+        };
+        assert!(message.debug_printf().is_none());
+    }
 }
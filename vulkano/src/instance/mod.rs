@@ -241,6 +241,7 @@ pub struct Instance {
     enabled_layers: Vec<String>,
     library: Arc<VulkanLibrary>,
     max_api_version: Version,
+    export_metal_command_queues: bool,
     _user_callbacks: Vec<Box<UserCallback>>,
 }
 
@@ -296,11 +297,19 @@ impl Instance {
             engine_version,
             max_api_version,
             enumerate_portability,
-            enabled_validation_features,
+            mut enabled_validation_features,
             disabled_validation_features,
+            debug_printf,
+            export_metal_command_queues,
             _ne: _,
         } = create_info;
 
+        if debug_printf
+            && !enabled_validation_features.contains(&ValidationFeatureEnable::DebugPrintf)
+        {
+            enabled_validation_features.push(ValidationFeatureEnable::DebugPrintf);
+        }
+
         let (api_version, max_api_version) = {
             let api_version = library.api_version();
             let max_api_version = if let Some(max_api_version) = max_api_version {
@@ -484,6 +493,20 @@ impl Instance {
             create_info_vk.p_next = info as *const _ as *const _;
         }
 
+        let mut export_metal_object_create_info_vk = None;
+
+        if export_metal_command_queues {
+            let next = export_metal_object_create_info_vk.insert(
+                ash::vk::ExportMetalObjectCreateInfoEXT {
+                    export_object_type: ash::vk::ExportMetalObjectTypeFlagsEXT::METAL_COMMAND_QUEUE,
+                    ..Default::default()
+                },
+            );
+
+            next.p_next = create_info_vk.p_next;
+            create_info_vk.p_next = next as *const _ as *const _;
+        }
+
         // Creating the Vulkan instance.
         let handle = {
             let mut output = MaybeUninit::uninit();
@@ -512,6 +535,7 @@ impl Instance {
             enabled_layers,
             library,
             max_api_version,
+            export_metal_command_queues,
             _user_callbacks: user_callbacks,
         }))
     }
@@ -556,6 +580,12 @@ impl Instance {
         &self.enabled_layers
     }
 
+    /// Returns whether the instance was created with `export_metal_command_queues` enabled.
+    #[inline]
+    pub fn export_metal_command_queues(&self) -> bool {
+        self.export_metal_command_queues
+    }
+
     /// Returns an iterator that enumerates the physical devices available.
     ///
     /// # Examples
@@ -609,6 +639,104 @@ impl Instance {
             Ok(physical_devices.into_iter())
         }
     }
+
+    /// Returns an iterator that enumerates the groups of physical devices available.
+    ///
+    /// The physical devices in a group can be used together to create a single logical device,
+    /// by passing them in [`DeviceCreateInfo::physical_devices`].
+    ///
+    /// If the instance API version is less than 1.1, and the
+    /// [`khr_device_group_creation`](crate::instance::InstanceExtensions::khr_device_group_creation)
+    /// extension is not enabled on the instance, then each physical device is returned in a group
+    /// of its own.
+    ///
+    /// [`DeviceCreateInfo::physical_devices`]: crate::device::DeviceCreateInfo::physical_devices
+    pub fn enumerate_physical_device_groups(
+        self: &Arc<Self>,
+    ) -> Result<impl ExactSizeIterator<Item = PhysicalDeviceGroupProperties>, VulkanError> {
+        if !(self.api_version() >= Version::V1_1
+            || self.enabled_extensions().khr_device_group_creation)
+        {
+            let groups: Vec<_> = self
+                .enumerate_physical_devices()?
+                .map(|physical_device| PhysicalDeviceGroupProperties {
+                    physical_devices: vec![physical_device],
+                    subset_allocation: false,
+                })
+                .collect();
+
+            return Ok(groups.into_iter());
+        }
+
+        let fns = self.fns();
+        let enumerate_physical_device_groups = if self.api_version() >= Version::V1_1 {
+            fns.v1_1.enumerate_physical_device_groups
+        } else {
+            fns.khr_device_group_creation
+                .enumerate_physical_device_groups_khr
+        };
+
+        unsafe {
+            let groups_vk = loop {
+                let mut count = 0;
+                (enumerate_physical_device_groups)(self.handle, &mut count, ptr::null_mut())
+                    .result()
+                    .map_err(VulkanError::from)?;
+
+                let mut groups_vk =
+                    vec![ash::vk::PhysicalDeviceGroupProperties::default(); count as usize];
+                let result = (enumerate_physical_device_groups)(
+                    self.handle,
+                    &mut count,
+                    groups_vk.as_mut_ptr(),
+                );
+
+                match result {
+                    ash::vk::Result::SUCCESS => {
+                        groups_vk.truncate(count as usize);
+                        break groups_vk;
+                    }
+                    ash::vk::Result::INCOMPLETE => (),
+                    err => return Err(VulkanError::from(err)),
+                }
+            };
+
+            let physical_devices: Vec<_> = self.enumerate_physical_devices()?.collect();
+
+            let groups: Vec<_> = groups_vk
+                .into_iter()
+                .map(|group_vk| PhysicalDeviceGroupProperties {
+                    physical_devices: group_vk.physical_devices
+                        [..group_vk.physical_device_count as usize]
+                        .iter()
+                        .filter_map(|&handle| {
+                            physical_devices
+                                .iter()
+                                .find(|physical_device| physical_device.internal_object() == handle)
+                                .cloned()
+                        })
+                        .collect(),
+                    subset_allocation: group_vk.subset_allocation != ash::vk::FALSE,
+                })
+                .collect();
+
+            Ok(groups.into_iter())
+        }
+    }
+}
+
+/// A group of physical devices that can be used together to create a single logical device.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct PhysicalDeviceGroupProperties {
+    /// The physical devices in the group.
+    pub physical_devices: Vec<Arc<PhysicalDevice>>,
+
+    /// Whether memory allocations of a device created from the group can be allocated on a subset
+    /// of the physical devices, with [`MemoryAllocateInfo::device_mask`].
+    ///
+    /// [`MemoryAllocateInfo::device_mask`]: crate::memory::MemoryAllocateInfo::device_mask
+    pub subset_allocation: bool,
 }
 
 impl Drop for Instance {
@@ -656,6 +784,7 @@ impl Debug for Instance {
             enabled_layers,
             library: function_pointers,
             max_api_version,
+            export_metal_command_queues,
             _user_callbacks: _,
         } = self;
 
@@ -667,6 +796,7 @@ impl Debug for Instance {
             .field("enabled_layers", enabled_layers)
             .field("function_pointers", function_pointers)
             .field("max_api_version", max_api_version)
+            .field("export_metal_command_queues", export_metal_command_queues)
             .finish_non_exhaustive()
     }
 }
@@ -740,6 +870,35 @@ pub struct InstanceCreateInfo {
     /// extension must be enabled on the instance.
     pub disabled_validation_features: Vec<ValidationFeatureDisable>,
 
+    /// Enables `debugPrintfEXT` in shaders. The output is reported through debug messengers, see
+    /// [`Message::debug_printf`](debug::Message::debug_printf).
+    ///
+    /// If `true`, [`ValidationFeatureEnable::DebugPrintf`] is added to
+    /// `enabled_validation_features`, so the
+    /// [`ext_validation_features`](crate::instance::InstanceExtensions::ext_validation_features)
+    /// extension must be enabled on the instance, and `GpuAssisted` must not be enabled.
+    ///
+    /// The size of the output buffer and the verbosity of the messages are settings of the
+    /// validation layer, which can be configured with its settings file or with the
+    /// `VK_LAYER_PRINTF_BUFFER_SIZE` and `VK_LAYER_PRINTF_VERBOSE` environment variables.
+    /// `VK_LAYER_PRINTF_TO_STDOUT` must not be set, otherwise the output is not reported through
+    /// debug messengers.
+    ///
+    /// The default value is `false`.
+    pub debug_printf: bool,
+
+    /// Allows the underlying `MTLCommandQueue` of queues created from this instance to be
+    /// exported with [`Queue::export_metal_command_queue`].
+    ///
+    /// This only has an effect when running on top of Metal, such as with MoltenVK, and the
+    /// [`ext_metal_objects`](crate::device::DeviceExtensions::ext_metal_objects) extension must
+    /// also be enabled on the device that the queues belong to.
+    ///
+    /// The default value is `false`.
+    ///
+    /// [`Queue::export_metal_command_queue`]: crate::device::Queue::export_metal_command_queue
+    pub export_metal_command_queues: bool,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -757,6 +916,8 @@ impl Default for InstanceCreateInfo {
             enumerate_portability: false,
             enabled_validation_features: Vec::new(),
             disabled_validation_features: Vec::new(),
+            debug_printf: false,
+            export_metal_command_queues: false,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -782,6 +943,77 @@ impl InstanceCreateInfo {
             ..Default::default()
         }
     }
+
+    /// Enables the Khronos validation layer, if it is available in `library`, along with the
+    /// given features of the layer.
+    ///
+    /// Returns `false` and leaves `self` unchanged if the layer is not available. This makes it
+    /// possible to enable validation in debug builds without failing on systems where the Vulkan
+    /// SDK is not installed.
+    ///
+    /// If `features` is not empty and the layer or the library supports the
+    /// [`ext_validation_features`](crate::instance::InstanceExtensions::ext_validation_features)
+    /// extension, the extension is enabled and `features` are added to
+    /// `enabled_validation_features`. Otherwise, `features` are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use vulkano::{
+    ///     instance::{debug::ValidationFeatureEnable, Instance, InstanceCreateInfo},
+    ///     VulkanLibrary,
+    /// };
+    ///
+    /// let library = VulkanLibrary::new().unwrap();
+    /// let mut create_info = InstanceCreateInfo::default();
+    ///
+    /// if cfg!(debug_assertions) {
+    ///     create_info
+    ///         .enable_validation_layer(
+    ///             &library,
+    ///             &[ValidationFeatureEnable::SynchronizationValidation],
+    ///         )
+    ///         .unwrap();
+    /// }
+    ///
+    /// let instance = Instance::new(library, create_info).unwrap();
+    /// ```
+    pub fn enable_validation_layer(
+        &mut self,
+        library: &VulkanLibrary,
+        features: &[ValidationFeatureEnable],
+    ) -> Result<bool, VulkanError> {
+        const VALIDATION_LAYER: &str = "VK_LAYER_KHRONOS_validation";
+
+        let layer_extensions = match library.supported_layer_extensions(VALIDATION_LAYER) {
+            Ok(layer_extensions) => layer_extensions,
+            Err(VulkanError::LayerNotPresent) => return Ok(false),
+            Err(err) => return Err(err),
+        };
+
+        if !self
+            .enabled_layers
+            .iter()
+            .any(|layer| layer == VALIDATION_LAYER)
+        {
+            self.enabled_layers.push(VALIDATION_LAYER.to_owned());
+        }
+
+        if !features.is_empty()
+            && (layer_extensions.ext_validation_features
+                || library.supported_extensions().ext_validation_features)
+        {
+            self.enabled_extensions.ext_validation_features = true;
+
+            for &feature in features {
+                if !self.enabled_validation_features.contains(&feature) {
+                    self.enabled_validation_features.push(feature);
+                }
+            }
+        }
+
+        Ok(true)
+    }
 }
 
 /// Error that can happen when creating an instance.
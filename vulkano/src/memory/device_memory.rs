@@ -7,19 +7,20 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
-use super::DedicatedAllocation;
+use super::{DedicatedAllocation, MemoryBudgetExceeded, MemoryHeapBudget};
 use crate::{
-    device::{Device, DeviceOwned},
+    device::{Device, DeviceOwned, MetalObjectExportError},
     macros::{vulkan_bitflags, vulkan_enum},
     DeviceSize, OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
+use bytemuck::Pod;
 use std::{
     error::Error,
     ffi::c_void,
     fmt::{Display, Error as FmtError, Formatter},
     fs::File,
     hash::{Hash, Hasher},
-    mem::MaybeUninit,
+    mem::{size_of, MaybeUninit},
     ops::Range,
     ptr, slice,
     sync::{atomic::Ordering, Arc},
@@ -56,6 +57,7 @@ pub struct DeviceMemory {
     memory_type_index: u32,
     export_handle_types: ExternalMemoryHandleTypes,
     flags: MemoryAllocateFlags,
+    export_metal_buffer: bool,
 }
 
 impl DeviceMemory {
@@ -82,6 +84,9 @@ impl DeviceMemory {
             dedicated_allocation: _,
             export_handle_types,
             flags,
+            device_mask: _,
+            priority: _,
+            export_metal_buffer,
             _ne: _,
         } = allocate_info;
 
@@ -92,6 +97,7 @@ impl DeviceMemory {
             memory_type_index,
             export_handle_types,
             flags,
+            export_metal_buffer,
         })
     }
 
@@ -113,6 +119,9 @@ impl DeviceMemory {
             dedicated_allocation: _,
             export_handle_types,
             flags,
+            device_mask: _,
+            priority: _,
+            export_metal_buffer,
             _ne: _,
         } = allocate_info;
 
@@ -123,6 +132,7 @@ impl DeviceMemory {
             memory_type_index,
             export_handle_types,
             flags,
+            export_metal_buffer,
         }
     }
 
@@ -151,6 +161,9 @@ impl DeviceMemory {
             dedicated_allocation: _,
             export_handle_types,
             flags,
+            device_mask: _,
+            priority: _,
+            export_metal_buffer,
             _ne: _,
         } = allocate_info;
 
@@ -161,6 +174,7 @@ impl DeviceMemory {
             memory_type_index,
             export_handle_types,
             flags,
+            export_metal_buffer,
         })
     }
 
@@ -175,6 +189,9 @@ impl DeviceMemory {
             ref mut dedicated_allocation,
             export_handle_types,
             flags,
+            device_mask,
+            priority,
+            export_metal_buffer,
             _ne: _,
         } = allocate_info;
 
@@ -371,6 +388,13 @@ impl DeviceMemory {
                                 // VUID-VkMemoryDedicatedAllocateInfo-image-01878
                                 // Can't validate, must be ensured by user
                             }
+                            ExternalMemoryHandleType::D3D11Texture
+                            | ExternalMemoryHandleType::D3D11TextureKmt
+                            | ExternalMemoryHandleType::D3D12Heap
+                            | ExternalMemoryHandleType::D3D12Resource => {
+                                // VUID-VkMemoryAllocateInfo-allocationSize-01743
+                                // Can't validate, must be ensured by user
+                            }
                             _ => {
                                 return Err(DeviceMemoryError::ImportWin32HandleTypeNotSupported {
                                     handle_type,
@@ -382,6 +406,29 @@ impl DeviceMemory {
                         // Can't validate, must be ensured by user
                     }
                 }
+                MemoryImportInfo::AndroidHardwareBuffer { buffer: _ } => {
+                    if !device
+                        .enabled_extensions()
+                        .android_external_memory_android_hardware_buffer
+                    {
+                        return Err(DeviceMemoryError::RequirementNotMet {
+                            required_for: "`allocate_info.import_info` is \
+                                `Some(MemoryImportInfo::AndroidHardwareBuffer)`",
+                            requires_one_of: RequiresOneOf {
+                                device_extensions: &[
+                                    "android_external_memory_android_hardware_buffer",
+                                ],
+                                ..Default::default()
+                            },
+                        });
+                    }
+
+                    // VUID-VkMemoryAllocateInfo-allocationSize-02383
+                    // Can't validate, must be ensured by user
+
+                    // VUID-VkMemoryAllocateInfo-memoryTypeIndex-02385
+                    // Can't validate, must be ensured by user
+                }
             }
         }
 
@@ -399,6 +446,14 @@ impl DeviceMemory {
             });
         }
 
+        if flags.device_mask {
+            // VUID-VkMemoryAllocateFlagsInfo-deviceMask-00675
+            // VUID-VkMemoryAllocateFlagsInfo-deviceMask-00676
+            if device_mask == 0 || device_mask & !device.all_devices_mask() != 0 {
+                return Err(DeviceMemoryError::DeviceMaskInvalid { device_mask });
+            }
+        }
+
         if flags.device_address {
             // VUID-VkMemoryAllocateInfo-flags-03331
             if !device.enabled_features().buffer_device_address {
@@ -423,6 +478,33 @@ impl DeviceMemory {
             }
         }
 
+        if let Some(priority) = priority {
+            if !device.enabled_features().memory_priority {
+                return Err(DeviceMemoryError::RequirementNotMet {
+                    required_for: "`allocate_info.priority` is `Some`",
+                    requires_one_of: RequiresOneOf {
+                        features: &["memory_priority"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkMemoryPriorityAllocateInfoEXT-priority-02602
+            if !(0.0..=1.0).contains(&priority) {
+                return Err(DeviceMemoryError::PriorityOutOfRange);
+            }
+        }
+
+        if export_metal_buffer && !device.enabled_extensions().ext_metal_objects {
+            return Err(DeviceMemoryError::RequirementNotMet {
+                required_for: "`allocate_info.export_metal_buffer` is `true`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_metal_objects"],
+                    ..Default::default()
+                },
+            });
+        }
+
         Ok(())
     }
 
@@ -437,6 +519,9 @@ impl DeviceMemory {
             dedicated_allocation,
             export_handle_types,
             flags,
+            device_mask,
+            priority,
+            export_metal_buffer,
             _ne: _,
         } = allocate_info;
 
@@ -474,6 +559,20 @@ impl DeviceMemory {
             allocate_info = allocate_info.push_next(info);
         }
 
+        let mut import_android_hardware_buffer_info = match import_info {
+            Some(MemoryImportInfo::AndroidHardwareBuffer { buffer }) => {
+                Some(ash::vk::ImportAndroidHardwareBufferInfoANDROID {
+                    buffer,
+                    ..Default::default()
+                })
+            }
+            _ => None,
+        };
+
+        if let Some(info) = import_android_hardware_buffer_info.as_mut() {
+            allocate_info = allocate_info.push_next(info);
+        }
+
         #[cfg(unix)]
         let mut import_fd_info = match import_info {
             Some(MemoryImportInfo::Fd { handle_type, file }) => {
@@ -513,6 +612,7 @@ impl DeviceMemory {
 
         let mut flags_info = ash::vk::MemoryAllocateFlagsInfo {
             flags: flags.into(),
+            device_mask,
             ..Default::default()
         };
 
@@ -520,6 +620,27 @@ impl DeviceMemory {
             allocate_info = allocate_info.push_next(&mut flags_info);
         }
 
+        let mut priority_info = priority.map(|priority| ash::vk::MemoryPriorityAllocateInfoEXT {
+            priority,
+            ..Default::default()
+        });
+
+        if let Some(info) = priority_info.as_mut() {
+            allocate_info = allocate_info.push_next(info);
+        }
+
+        let mut export_metal_object_info =
+            export_metal_buffer.then(|| ash::vk::ExportMetalObjectCreateInfoEXT {
+                export_object_type: ash::vk::ExportMetalObjectTypeFlagsEXT::METAL_BUFFER,
+                ..Default::default()
+            });
+
+        if let Some(info) = export_metal_object_info.as_mut() {
+            allocate_info = allocate_info.push_next(info);
+        }
+
+        Self::check_budget_limit(device, allocation_size, memory_type_index)?;
+
         // VUID-vkAllocateMemory-maxMemoryAllocationCount-04101
         let max_allocations = device
             .physical_device()
@@ -550,9 +671,81 @@ impl DeviceMemory {
             output.assume_init()
         };
 
+        {
+            let mut memory_statistics = device.memory_statistics.lock();
+            let statistics = &mut memory_statistics[memory_type_index as usize];
+            statistics.allocation_count += 1;
+            statistics.allocated_bytes += allocation_size;
+            statistics.total_allocation_count += 1;
+            statistics.total_allocated_bytes += allocation_size;
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            memory = ?handle,
+            allocation_size,
+            memory_type_index,
+            "allocated device memory",
+        );
+
         Ok(handle)
     }
 
+    fn check_budget_limit(
+        device: &Device,
+        allocation_size: DeviceSize,
+        memory_type_index: u32,
+    ) -> Result<(), DeviceMemoryError> {
+        // Clone the limit so that the lock is not held while calling the callback.
+        let budget_limit = match device.memory_budget_limit() {
+            Some(budget_limit) => budget_limit,
+            None => return Ok(()),
+        };
+
+        let memory_properties = device.physical_device().memory_properties();
+        let heap_index = memory_properties.memory_types[memory_type_index as usize].heap_index;
+
+        let heap_budget = device
+            .enabled_extensions()
+            .ext_memory_budget
+            .then(|| device.physical_device().memory_budget().ok())
+            .flatten()
+            .map(|heap_budgets| heap_budgets[heap_index as usize])
+            .unwrap_or_else(|| MemoryHeapBudget {
+                budget: memory_properties.memory_heaps[heap_index as usize].size,
+                usage: device
+                    .memory_statistics
+                    .lock()
+                    .iter()
+                    .zip(&memory_properties.memory_types)
+                    .filter(|(_, memory_type)| memory_type.heap_index == heap_index)
+                    .map(|(statistics, _)| statistics.allocated_bytes)
+                    .sum(),
+            });
+
+        let limit = (heap_budget.budget as f64 * budget_limit.fraction as f64) as DeviceSize;
+
+        if heap_budget.usage.saturating_add(allocation_size) <= limit {
+            return Ok(());
+        }
+
+        let allowed = budget_limit.on_exceeded.map_or(false, |on_exceeded| {
+            on_exceeded(&MemoryBudgetExceeded {
+                memory_type_index,
+                heap_index,
+                allocation_size,
+                usage: heap_budget.usage,
+                limit,
+            })
+        });
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(DeviceMemoryError::BudgetLimitExceeded { heap_index })
+        }
+    }
+
     /// Returns the index of the memory type that this memory was allocated from.
     #[inline]
     pub fn memory_type_index(&self) -> u32 {
@@ -577,6 +770,12 @@ impl DeviceMemory {
         self.flags
     }
 
+    /// Returns whether the underlying `MTLBuffer` of the memory can be exported.
+    #[inline]
+    pub fn export_metal_buffer(&self) -> bool {
+        self.export_metal_buffer
+    }
+
     /// Retrieves the amount of lazily-allocated memory that is currently commited to this
     /// memory object.
     ///
@@ -622,6 +821,54 @@ impl DeviceMemory {
         output
     }
 
+    /// Changes the priority of the memory allocation, relative to other allocations of the
+    /// device.
+    ///
+    /// `priority` must be between 0.0 and 1.0. When the memory heap is oversubscribed, the
+    /// implementation moves allocations with a lower priority out of device-local memory first.
+    ///
+    /// The [`pageable_device_local_memory`](crate::device::Features::pageable_device_local_memory)
+    /// feature must be enabled on the device.
+    #[inline]
+    pub fn set_priority(&self, priority: f32) -> Result<(), DeviceMemoryError> {
+        self.validate_set_priority(priority)?;
+
+        unsafe {
+            self.set_priority_unchecked(priority);
+        }
+
+        Ok(())
+    }
+
+    fn validate_set_priority(&self, priority: f32) -> Result<(), DeviceMemoryError> {
+        if !self.device.enabled_features().pageable_device_local_memory {
+            return Err(DeviceMemoryError::RequirementNotMet {
+                required_for: "`set_priority`",
+                requires_one_of: RequiresOneOf {
+                    features: &["pageable_device_local_memory"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-vkSetDeviceMemoryPriorityEXT-priority-06258
+        if !(0.0..=1.0).contains(&priority) {
+            return Err(DeviceMemoryError::PriorityOutOfRange);
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn set_priority_unchecked(&self, priority: f32) {
+        let fns = self.device.fns();
+        (fns.ext_pageable_device_local_memory
+            .set_device_memory_priority_ext)(
+            self.device.internal_object(), self.handle, priority
+        );
+    }
+
     /// Exports the device memory into a Unix file descriptor. The caller owns the returned `File`.
     ///
     /// # Panics
@@ -683,16 +930,177 @@ impl DeviceMemory {
             Ok(file)
         }
     }
+
+    /// Exports the device memory into a Windows handle.
+    ///
+    /// `handle_type` must be one of [`ExternalMemoryHandleType::OpaqueWin32`],
+    /// [`ExternalMemoryHandleType::OpaqueWin32Kmt`], [`ExternalMemoryHandleType::D3D11Texture`],
+    /// [`ExternalMemoryHandleType::D3D11TextureKmt`], [`ExternalMemoryHandleType::D3D12Heap`] or
+    /// [`ExternalMemoryHandleType::D3D12Resource`], and must have been included in
+    /// `export_handle_types` when allocating the memory.
+    ///
+    /// If `handle_type` is an NT handle type, the caller owns the returned handle and must close
+    /// it with `CloseHandle` when it is no longer needed. Global share handles (the `Kmt`
+    /// variants) are not owned and must not be closed.
+    #[inline]
+    pub fn export_win32_handle(
+        &self,
+        handle_type: ExternalMemoryHandleType,
+    ) -> Result<ash::vk::HANDLE, DeviceMemoryError> {
+        self.validate_export_win32_handle(handle_type)?;
+
+        unsafe { Ok(self.export_win32_handle_unchecked(handle_type)?) }
+    }
+
+    fn validate_export_win32_handle(
+        &self,
+        handle_type: ExternalMemoryHandleType,
+    ) -> Result<(), DeviceMemoryError> {
+        if !self.device.enabled_extensions().khr_external_memory_win32 {
+            return Err(DeviceMemoryError::RequirementNotMet {
+                required_for: "`export_win32_handle`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["khr_external_memory_win32"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-VkMemoryGetWin32HandleInfoKHR-handleType-parameter
+        handle_type.validate_device(&self.device)?;
+
+        // VUID-VkMemoryGetWin32HandleInfoKHR-handleType-00664
+        if !matches!(
+            handle_type,
+            ExternalMemoryHandleType::OpaqueWin32
+                | ExternalMemoryHandleType::OpaqueWin32Kmt
+                | ExternalMemoryHandleType::D3D11Texture
+                | ExternalMemoryHandleType::D3D11TextureKmt
+                | ExternalMemoryHandleType::D3D12Heap
+                | ExternalMemoryHandleType::D3D12Resource
+        ) {
+            return Err(DeviceMemoryError::HandleTypeNotSupported { handle_type });
+        }
+
+        // VUID-VkMemoryGetWin32HandleInfoKHR-handleType-00662
+        if !ash::vk::ExternalMemoryHandleTypeFlags::from(self.export_handle_types)
+            .intersects(ash::vk::ExternalMemoryHandleTypeFlags::from(handle_type))
+        {
+            return Err(DeviceMemoryError::HandleTypeNotSupported { handle_type });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn export_win32_handle_unchecked(
+        &self,
+        handle_type: ExternalMemoryHandleType,
+    ) -> Result<ash::vk::HANDLE, VulkanError> {
+        let info = ash::vk::MemoryGetWin32HandleInfoKHR {
+            memory: self.handle,
+            handle_type: handle_type.into(),
+            ..Default::default()
+        };
+
+        let fns = self.device.fns();
+        let mut output = MaybeUninit::uninit();
+        (fns.khr_external_memory_win32.get_memory_win32_handle_khr)(
+            self.device.internal_object(),
+            &info,
+            output.as_mut_ptr(),
+        )
+        .result()
+        .map_err(VulkanError::from)?;
+
+        Ok(output.assume_init())
+    }
+
+    /// Returns the `MTLBuffer` that underlies the memory, when running on top of Metal.
+    ///
+    /// The [`ext_metal_objects`](crate::device::DeviceExtensions::ext_metal_objects) extension
+    /// must be enabled on the device, and the memory must have been allocated with
+    /// [`MemoryAllocateInfo::export_metal_buffer`] set.
+    ///
+    /// The returned object is owned by the memory, and must not be used after the memory has
+    /// been freed.
+    #[inline]
+    pub fn export_metal_buffer_object(
+        &self,
+    ) -> Result<ash::vk::MTLBuffer_id, MetalObjectExportError> {
+        self.validate_export_metal_buffer_object()?;
+
+        unsafe { Ok(self.export_metal_buffer_object_unchecked()) }
+    }
+
+    fn validate_export_metal_buffer_object(&self) -> Result<(), MetalObjectExportError> {
+        if !self.device.enabled_extensions().ext_metal_objects {
+            return Err(MetalObjectExportError::RequirementNotMet {
+                required_for: "`export_metal_buffer_object`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_metal_objects"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if !self.export_metal_buffer {
+            return Err(MetalObjectExportError::ExportNotEnabled);
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn export_metal_buffer_object_unchecked(&self) -> ash::vk::MTLBuffer_id {
+        let mut export_info_vk = ash::vk::ExportMetalBufferInfoEXT {
+            memory: self.handle,
+            ..Default::default()
+        };
+        let mut info_vk = ash::vk::ExportMetalObjectsInfoEXT {
+            p_next: &mut export_info_vk as *mut _ as *const _,
+            ..Default::default()
+        };
+
+        let fns = self.device.fns();
+        (fns.ext_metal_objects.export_metal_objects_ext)(
+            self.device.internal_object(),
+            &mut info_vk,
+        );
+
+        export_info_vk.mtl_buffer
+    }
 }
 
 impl Drop for DeviceMemory {
     #[inline]
     fn drop(&mut self) {
+        #[cfg(feature = "tracing")]
+        {
+            tracing::debug!(
+                memory = ?self.handle,
+                name = %self.device.traced_object_name(self),
+                allocation_size = self.allocation_size,
+                memory_type_index = self.memory_type_index,
+                "freeing device memory",
+            );
+            self.device.forget_traced_object_name(self);
+        }
+
         unsafe {
             let fns = self.device.fns();
             (fns.v1_0.free_memory)(self.device.internal_object(), self.handle, ptr::null());
             self.device.allocation_count.fetch_sub(1, Ordering::Release);
         }
+
+        let mut memory_statistics = self.device.memory_statistics.lock();
+        let statistics = &mut memory_statistics[self.memory_type_index as usize];
+        statistics.allocation_count = statistics.allocation_count.saturating_sub(1);
+        statistics.allocated_bytes = statistics
+            .allocated_bytes
+            .saturating_sub(self.allocation_size);
     }
 }
 
@@ -761,6 +1169,36 @@ pub struct MemoryAllocateInfo<'d> {
     /// The default value is [`MemoryAllocateFlags::empty()`].
     pub flags: MemoryAllocateFlags,
 
+    /// The physical devices of a device group that the memory is allocated on, as a bitmask of
+    /// device indices.
+    ///
+    /// This is only used if `flags` contains [`device_mask`](MemoryAllocateFlags::device_mask).
+    /// In that case it must not be zero, and must only contain bits for the physical devices
+    /// of the device. Otherwise, the memory is allocated on all physical devices.
+    ///
+    /// The default value is `0`.
+    pub device_mask: u32,
+
+    /// The priority of the allocation relative to other allocations of the device, between 0.0
+    /// and 1.0. When the memory heap is oversubscribed, the implementation moves allocations with
+    /// a lower priority out of device-local memory first.
+    ///
+    /// If `Some`, the [`memory_priority`](crate::device::Features::memory_priority) feature must
+    /// be enabled on the device. If `None`, the allocation gets the default priority of 0.5.
+    ///
+    /// The default value is `None`.
+    pub priority: Option<f32>,
+
+    /// Allows the underlying `MTLBuffer` of the allocation to be exported with
+    /// [`DeviceMemory::export_metal_buffer_object`], when running on top of Metal.
+    ///
+    /// If set to `true`, the
+    /// [`ext_metal_objects`](crate::device::DeviceExtensions::ext_metal_objects) extension must
+    /// be enabled on the device.
+    ///
+    /// The default value is `false`.
+    pub export_metal_buffer: bool,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -773,6 +1211,9 @@ impl Default for MemoryAllocateInfo<'static> {
             dedicated_allocation: None,
             export_handle_types: ExternalMemoryHandleTypes::empty(),
             flags: MemoryAllocateFlags::empty(),
+            device_mask: 0,
+            priority: None,
+            export_metal_buffer: false,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -788,6 +1229,9 @@ impl<'d> MemoryAllocateInfo<'d> {
             dedicated_allocation: Some(dedicated_allocation),
             export_handle_types: ExternalMemoryHandleTypes::empty(),
             flags: MemoryAllocateFlags::empty(),
+            device_mask: 0,
+            priority: None,
+            export_metal_buffer: false,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -824,18 +1268,30 @@ pub enum MemoryImportInfo {
 
     /// Import memory from a Windows handle.
     ///
-    /// `handle_type` must be either [`ExternalMemoryHandleType::OpaqueWin32`] or
-    /// [`ExternalMemoryHandleType::OpaqueWin32Kmt`].
+    /// `handle_type` must be one of [`ExternalMemoryHandleType::OpaqueWin32`],
+    /// [`ExternalMemoryHandleType::OpaqueWin32Kmt`], [`ExternalMemoryHandleType::D3D11Texture`],
+    /// [`ExternalMemoryHandleType::D3D11TextureKmt`], [`ExternalMemoryHandleType::D3D12Heap`] or
+    /// [`ExternalMemoryHandleType::D3D12Resource`].
     ///
     /// # Safety
     ///
     /// - `handle` must be a valid Windows handle.
     /// - Vulkan will not take ownership of `handle`.
-    /// - If `handle_type` is [`ExternalMemoryHandleType::OpaqueWin32`], it owns a reference
-    ///   to the underlying resource and must eventually be closed by the caller.
-    /// - If `handle_type` is [`ExternalMemoryHandleType::OpaqueWin32Kmt`], it does not own a
-    ///   reference to the underlying resource.
-    /// - `handle` must be created by the Vulkan API.
+    /// - If `handle_type` is an NT handle type, such as
+    ///   [`ExternalMemoryHandleType::OpaqueWin32`] or [`ExternalMemoryHandleType::D3D11Texture`],
+    ///   it owns a reference to the underlying resource and must eventually be closed by the
+    ///   caller.
+    /// - If `handle_type` is a global share handle type, such as
+    ///   [`ExternalMemoryHandleType::OpaqueWin32Kmt`] or
+    ///   [`ExternalMemoryHandleType::D3D11TextureKmt`], it does not own a reference to the
+    ///   underlying resource.
+    /// - If `handle_type` is [`ExternalMemoryHandleType::OpaqueWin32`] or
+    ///   [`ExternalMemoryHandleType::OpaqueWin32Kmt`], `handle` must be created by the Vulkan API.
+    /// - If `handle_type` is a D3D11 or D3D12 handle type, `handle` must be created by Direct3D,
+    ///   for example with `IDXGIResource1::CreateSharedHandle`, and
+    ///   [`MemoryAllocateInfo::dedicated_allocation`] must be set if
+    ///   [`ExternalMemoryProperties::dedicated_only`](crate::memory::ExternalMemoryProperties::dedicated_only)
+    ///   is `true` for the handle type.
     /// - [`MemoryAllocateInfo::allocation_size`] and [`MemoryAllocateInfo::memory_type_index`]
     ///   must match those of the original memory allocation.
     /// - If the original memory allocation used [`MemoryAllocateInfo::dedicated_allocation`],
@@ -845,6 +1301,28 @@ pub enum MemoryImportInfo {
         handle_type: ExternalMemoryHandleType,
         handle: ash::vk::HANDLE,
     },
+
+    /// Import memory from an Android hardware buffer.
+    ///
+    /// The
+    /// [`android_external_memory_android_hardware_buffer`](crate::device::DeviceExtensions::android_external_memory_android_hardware_buffer)
+    /// extension must be enabled on the device.
+    ///
+    /// # Safety
+    ///
+    /// - `buffer` must be a valid `AHardwareBuffer`, and its usage flags must include at least
+    ///   one of `AHARDWAREBUFFER_USAGE_GPU_*`.
+    /// - Vulkan will acquire a reference to `buffer`, so the caller may release its own reference
+    ///   once the memory is imported.
+    /// - [`MemoryAllocateInfo::allocation_size`] must be equal to, and
+    ///   [`MemoryAllocateInfo::memory_type_index`] must be one of the memory types returned by,
+    ///   [`Device::android_hardware_buffer_properties`].
+    /// - If the memory is going to be bound to an image, then
+    ///   [`MemoryAllocateInfo::dedicated_allocation`] must be set to that image, and the image
+    ///   must have been created with a format and extent that match those of `buffer`.
+    AndroidHardwareBuffer {
+        buffer: *mut ash::vk::AHardwareBuffer,
+    },
 }
 
 vulkan_enum! {
@@ -1012,8 +1490,9 @@ vulkan_bitflags! {
     #[non_exhaustive]
     MemoryAllocateFlags = MemoryAllocateFlags(u32);
 
-    // TODO: implement
-    // device_mask = DEVICE_MASK,
+    /// Specifies that the memory is only allocated on the physical devices given in
+    /// [`MemoryAllocateInfo::device_mask`], when the device was created from a device group.
+    device_mask = DEVICE_MASK,
 
     /// Specifies that the allocated device memory can be bound to a buffer created with the
     /// [`shader_device_address`] usage. This requires that the [`buffer_device_address`] feature
@@ -1038,6 +1517,10 @@ pub enum DeviceMemoryError {
     /// The maximum number of allocations has been exceeded.
     TooManyObjects,
 
+    /// The allocation would exceed the [`MemoryBudgetLimit`] of the device for the memory heap,
+    /// and the limit's callback did not allow it.
+    BudgetLimitExceeded { heap_index: u32 },
+
     /// An error occurred when mapping the memory.
     MemoryMapError(MemoryMapError),
 
@@ -1059,6 +1542,13 @@ pub enum DeviceMemoryError {
         handle_type: ExternalMemoryHandleType,
     },
 
+    /// `flags` contains `device_mask`, but the provided `device_mask` was zero, or contained bits
+    /// for physical devices that the device was not created from.
+    DeviceMaskInvalid { device_mask: u32 },
+
+    /// The provided priority was not between 0.0 and 1.0.
+    PriorityOutOfRange,
+
     /// The provided `MemoryImportInfo::Fd::handle_type` is not supported for file descriptors.
     ImportFdHandleTypeNotSupported {
         handle_type: ExternalMemoryHandleType,
@@ -1112,6 +1602,11 @@ impl Display for DeviceMemoryError {
             Self::TooManyObjects => {
                 write!(f, "the maximum number of allocations has been exceeded")
             }
+            Self::BudgetLimitExceeded { heap_index } => write!(
+                f,
+                "the allocation would exceed the budget limit of the device for memory heap {}",
+                heap_index,
+            ),
             Self::MemoryMapError(_) => write!(f, "error occurred when mapping the memory"),
             Self::RequirementNotMet {
                 required_for,
@@ -1136,6 +1631,15 @@ impl Display for DeviceMemoryError {
                 was not provided in `export_handle_types` when allocating the memory",
                 handle_type,
             ),
+            Self::DeviceMaskInvalid { device_mask } => write!(
+                f,
+                "`flags` contains `device_mask`, but the provided `device_mask` ({:#b}) was zero, \
+                or contained bits for physical devices that the device was not created from",
+                device_mask,
+            ),
+            Self::PriorityOutOfRange => {
+                write!(f, "the provided priority was not between 0.0 and 1.0")
+            }
             Self::ImportFdHandleTypeNotSupported { handle_type } => write!(
                 f,
                 "the provided `MemoryImportInfo::Fd::handle_type` ({:?}) is not supported for file \
@@ -1516,6 +2020,113 @@ impl MappedDeviceMemory {
         Ok(bytes)
     }
 
+    /// Returns whether the mapped memory is host-coherent.
+    ///
+    /// If it is not, then writes by the host must be flushed before the device reads them, and
+    /// writes by the device must be invalidated before the host reads them.
+    #[inline]
+    pub fn is_coherent(&self) -> bool {
+        self.coherent
+    }
+
+    /// Returns the range of the memory allocation that is mapped.
+    #[inline]
+    pub fn mapped_range(&self) -> Range<DeviceSize> {
+        self.range.clone()
+    }
+
+    /// Expands `range` so that it can be passed to [`invalidate_range`](Self::invalidate_range)
+    /// and [`flush_range`](Self::flush_range).
+    ///
+    /// If the memory is not host-coherent, the start of `range` is rounded down and the end is
+    /// rounded up to a multiple of the
+    /// [`non_coherent_atom_size`](crate::device::Properties::non_coherent_atom_size) device
+    /// property, without going outside the mapped range. If the memory is host-coherent, `range`
+    /// is returned unchanged.
+    #[inline]
+    pub fn align_range(&self, range: Range<DeviceSize>) -> Range<DeviceSize> {
+        if self.coherent {
+            return range;
+        }
+
+        let start = range.start / self.atom_size * self.atom_size;
+        let end = (range.end + self.atom_size - 1) / self.atom_size * self.atom_size;
+
+        start.max(self.range.start)..end.min(self.range.end)
+    }
+
+    /// Reads a value of type `T` from the mapped memory, starting at `offset` bytes from the start
+    /// of the memory allocation. `offset` does not need to be aligned.
+    ///
+    /// If the memory is not host-coherent, the range of the value is first invalidated, expanded
+    /// with [`align_range`](Self::align_range).
+    ///
+    /// # Safety
+    ///
+    /// - There must be no operations pending or executing in a GPU queue, that write to the same
+    ///   memory.
+    /// - If the memory is not host-coherent, there must not be any references in Rust code to
+    ///   the aligned range of the memory.
+    pub unsafe fn read_typed<T: Pod>(&self, offset: DeviceSize) -> Result<T, MemoryMapError> {
+        let range = self.typed_range::<T>(offset)?;
+
+        if !self.coherent && !range.is_empty() {
+            self.invalidate_range(self.align_range(range.clone()))?;
+        }
+
+        Ok(ptr::read_unaligned(
+            self.pointer.add((range.start - self.range.start) as usize) as *const T,
+        ))
+    }
+
+    /// Writes `data` to the mapped memory, starting at `offset` bytes from the start of the memory
+    /// allocation. `offset` does not need to be aligned.
+    ///
+    /// If the memory is not host-coherent, the range of the value is flushed afterwards, expanded
+    /// with [`align_range`](Self::align_range).
+    ///
+    /// # Safety
+    ///
+    /// - There must not be any references in Rust code to the same memory.
+    /// - There must be no operations pending or executing in a GPU queue, that access the same
+    ///   memory. If the memory is not host-coherent, this applies to the whole aligned range.
+    pub unsafe fn write_typed<T: Pod>(
+        &self,
+        offset: DeviceSize,
+        data: &T,
+    ) -> Result<(), MemoryMapError> {
+        let range = self.typed_range::<T>(offset)?;
+
+        ptr::write_unaligned(
+            self.pointer.add((range.start - self.range.start) as usize) as *mut T,
+            *data,
+        );
+
+        if !self.coherent && !range.is_empty() {
+            self.flush_range(self.align_range(range))?;
+        }
+
+        Ok(())
+    }
+
+    fn typed_range<T>(&self, offset: DeviceSize) -> Result<Range<DeviceSize>, MemoryMapError> {
+        let range = offset..offset.saturating_add(size_of::<T>() as DeviceSize);
+
+        if range.start < self.range.start || range.end > self.range.end {
+            return Err(MemoryMapError::OutOfRange {
+                provided_range: range,
+                allowed_range: self.range.clone(),
+            });
+        }
+
+        debug_assert!({
+            let aligned_range = self.align_range(range.clone());
+            aligned_range.start <= range.start && aligned_range.end >= range.end
+        });
+
+        Ok(range)
+    }
+
     #[inline]
     fn check_range(&self, range: Range<DeviceSize>) -> Result<(), MemoryMapError> {
         assert!(!range.is_empty());
@@ -1654,8 +2265,15 @@ impl From<OomError> for MemoryMapError {
 mod tests {
     use super::MemoryAllocateInfo;
     use crate::{
-        memory::{DeviceMemory, DeviceMemoryError},
-        OomError,
+        memory::{
+            DeviceMemory, DeviceMemoryError, MappedDeviceMemory, MemoryAllocateFlags,
+            MemoryBudgetExceeded, MemoryBudgetLimit, MemoryMapError,
+        },
+        OomError, Version,
+    };
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
     };
 
     #[test]
@@ -1688,6 +2306,35 @@ mod tests {
         });
     }
 
+    #[test]
+    fn device_mask_invalid() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        if device.api_version() < Version::V1_1 {
+            return; // test ignored
+        }
+
+        for device_mask in [0, device.all_devices_mask() << 1] {
+            match DeviceMemory::allocate(
+                device.clone(),
+                MemoryAllocateInfo {
+                    allocation_size: 256,
+                    memory_type_index: 0,
+                    flags: MemoryAllocateFlags {
+                        device_mask: true,
+                        ..MemoryAllocateFlags::empty()
+                    },
+                    device_mask,
+                    ..Default::default()
+                },
+            ) {
+                Err(DeviceMemoryError::DeviceMaskInvalid { device_mask: mask })
+                    if mask == device_mask => {}
+                _ => panic!(),
+            }
+        }
+    }
+
     #[test]
     #[cfg(target_pointer_width = "64")]
     fn oom_single() {
@@ -1778,4 +2425,212 @@ mod tests {
         }
         assert_eq!(device.allocation_count(), 1);
     }
+
+    #[test]
+    fn memory_statistics() {
+        let (device, _) = gfx_dev_and_queue!();
+        let allocate = |allocation_size| {
+            DeviceMemory::allocate(
+                device.clone(),
+                MemoryAllocateInfo {
+                    allocation_size,
+                    memory_type_index: 0,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        let _mem1 = allocate(256);
+        {
+            let _mem2 = allocate(1024);
+            let statistics = device.memory_statistics()[0];
+            assert_eq!(statistics.allocation_count, 2);
+            assert_eq!(statistics.allocated_bytes, 1280);
+        }
+
+        let statistics = device.memory_statistics()[0];
+        assert_eq!(statistics.allocation_count, 1);
+        assert_eq!(statistics.allocated_bytes, 256);
+        assert_eq!(statistics.total_allocation_count, 2);
+        assert_eq!(statistics.total_allocated_bytes, 1280);
+        assert!(device.memory_statistics()[1..]
+            .iter()
+            .all(|statistics| statistics.total_allocation_count == 0));
+    }
+
+    #[test]
+    fn budget_limit_exceeded() {
+        let (device, _) = gfx_dev_and_queue!();
+        let allocate_info = MemoryAllocateInfo {
+            allocation_size: 256,
+            memory_type_index: 0,
+            ..Default::default()
+        };
+
+        device.set_memory_budget_limit(Some(MemoryBudgetLimit {
+            fraction: f32::MIN_POSITIVE,
+            ..Default::default()
+        }));
+
+        match DeviceMemory::allocate(device.clone(), allocate_info.clone()) {
+            Err(DeviceMemoryError::BudgetLimitExceeded { .. }) => (),
+            _ => panic!(),
+        }
+
+        let exceeded_count = Arc::new(AtomicU32::new(0));
+        device.set_memory_budget_limit(Some(MemoryBudgetLimit {
+            fraction: f32::MIN_POSITIVE,
+            on_exceeded: Some({
+                let exceeded_count = exceeded_count.clone();
+                Arc::new(move |exceeded: &MemoryBudgetExceeded| {
+                    assert_eq!(exceeded.allocation_size, 256);
+                    exceeded_count.fetch_add(1, Ordering::Relaxed);
+                    true
+                })
+            }),
+            ..Default::default()
+        }));
+
+        let _mem = DeviceMemory::allocate(device.clone(), allocate_info).unwrap();
+        assert_eq!(exceeded_count.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn budget_limit_zero_fraction() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        assert_should_panic!({
+            device.set_memory_budget_limit(Some(MemoryBudgetLimit {
+                fraction: 0.0,
+                ..Default::default()
+            }));
+        });
+        assert_should_panic!({
+            device.set_memory_budget_limit(Some(MemoryBudgetLimit {
+                fraction: f32::NAN,
+                ..Default::default()
+            }));
+        });
+        assert!(device.memory_budget_limit().is_none());
+    }
+
+    #[test]
+    fn priority_feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match DeviceMemory::allocate(
+            device,
+            MemoryAllocateInfo {
+                allocation_size: 256,
+                memory_type_index: 0,
+                priority: Some(0.5),
+                ..Default::default()
+            },
+        ) {
+            Err(DeviceMemoryError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn priority_out_of_range() {
+        let (device, _) = gfx_dev_and_queue!(memory_priority; extensions: ext_memory_priority);
+        let allocate = |priority| {
+            DeviceMemory::allocate(
+                device.clone(),
+                MemoryAllocateInfo {
+                    allocation_size: 256,
+                    memory_type_index: 0,
+                    priority: Some(priority),
+                    ..Default::default()
+                },
+            )
+        };
+
+        match allocate(1.5) {
+            Err(DeviceMemoryError::PriorityOutOfRange) => (),
+            _ => panic!(),
+        }
+        match allocate(f32::NAN) {
+            Err(DeviceMemoryError::PriorityOutOfRange) => (),
+            _ => panic!(),
+        }
+        allocate(1.0).unwrap();
+    }
+
+    #[test]
+    fn set_priority_feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory = DeviceMemory::allocate(
+            device,
+            MemoryAllocateInfo {
+                allocation_size: 256,
+                memory_type_index: 0,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        match memory.set_priority(0.5) {
+            Err(DeviceMemoryError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn set_priority_out_of_range() {
+        let (device, _) = gfx_dev_and_queue!(pageable_device_local_memory; extensions: ext_pageable_device_local_memory);
+        let memory = DeviceMemory::allocate(
+            device,
+            MemoryAllocateInfo {
+                allocation_size: 256,
+                memory_type_index: 0,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        match memory.set_priority(-0.1) {
+            Err(DeviceMemoryError::PriorityOutOfRange) => (),
+            _ => panic!(),
+        }
+        memory.set_priority(0.0).unwrap();
+    }
+
+    #[test]
+    fn typed_read_write() {
+        let (device, _) = gfx_dev_and_queue!();
+        let memory_type_index = device
+            .physical_device()
+            .memory_properties()
+            .memory_types
+            .iter()
+            .position(|t| t.property_flags.host_visible)
+            .unwrap() as u32;
+
+        let memory = DeviceMemory::allocate(
+            device,
+            MemoryAllocateInfo {
+                allocation_size: 1024,
+                memory_type_index,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let mapped_memory = MappedDeviceMemory::new(memory, 0..1024).unwrap();
+
+        unsafe {
+            mapped_memory.write_typed(13, &[1u32, 2, 3]).unwrap();
+            assert_eq!(mapped_memory.read_typed::<[u32; 3]>(13).unwrap(), [1, 2, 3]);
+
+            match mapped_memory.read_typed::<u64>(1020) {
+                Err(MemoryMapError::OutOfRange { .. }) => (),
+                _ => panic!(),
+            }
+        }
+
+        let aligned_range = mapped_memory.align_range(13..25);
+        assert!(aligned_range.start <= 13 && aligned_range.end >= 25);
+    }
 }
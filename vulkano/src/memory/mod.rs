@@ -91,6 +91,16 @@
 //! trait on your own structure and then use it when you create buffers and images so that they
 //! get memory from that pool. By default if you don't specify any pool when creating a buffer or
 //! an image, an instance of `StandardMemoryPool` that is shared by the `Device` object is used.
+//!
+//! # Budget and statistics
+//!
+//! [`PhysicalDevice::memory_budget`](crate::device::physical::PhysicalDevice::memory_budget)
+//! returns how much memory the implementation estimates can be allocated from each heap. A
+//! [`MemoryBudgetLimit`] can be set on a device with
+//! [`Device::set_memory_budget_limit`](crate::device::Device::set_memory_budget_limit) to reject,
+//! or be notified about, allocations that would go over a fraction of that budget.
+//! [`Device::memory_statistics`](crate::device::Device::memory_statistics) returns the number and
+//! size of the allocations of each memory type, for example to display them in a HUD.
 
 pub use self::{
     device_memory::{
@@ -107,7 +117,10 @@ use crate::{
     sync::Semaphore,
     DeviceSize,
 };
-use std::sync::Arc;
+use std::{
+    fmt::{Debug, Error as FmtError, Formatter},
+    sync::Arc,
+};
 
 mod device_memory;
 pub mod pool;
@@ -281,6 +294,112 @@ vulkan_bitflags! {
     },
 }
 
+/// The budget and usage of a memory heap, as returned by
+/// [`PhysicalDevice::memory_budget`](crate::device::physical::PhysicalDevice::memory_budget).
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub struct MemoryHeapBudget {
+    /// An estimate of how much memory the process can allocate from the heap before allocations
+    /// may fail or cause performance degradation.
+    pub budget: DeviceSize,
+
+    /// An estimate of how much memory the process is currently using in the heap.
+    pub usage: DeviceSize,
+}
+
+/// Statistics about the memory allocated from one memory type, as returned by
+/// [`Device::memory_statistics`](crate::device::Device::memory_statistics).
+#[derive(Clone, Copy, Debug, Default)]
+#[non_exhaustive]
+pub struct MemoryTypeStatistics {
+    /// The number of `DeviceMemory` allocations that currently exist.
+    pub allocation_count: u32,
+
+    /// The total size in bytes of the `DeviceMemory` allocations that currently exist.
+    pub allocated_bytes: DeviceSize,
+
+    /// The number of `DeviceMemory` allocations that have been made since the device was created,
+    /// including the ones that have been freed since.
+    pub total_allocation_count: u64,
+
+    /// The total size in bytes of the `DeviceMemory` allocations that have been made since the
+    /// device was created, including the ones that have been freed since.
+    pub total_allocated_bytes: DeviceSize,
+}
+
+/// A limit on how much memory can be allocated from each memory heap of a device, relative to its
+/// budget.
+///
+/// If the [`ext_memory_budget`](crate::device::DeviceExtensions::ext_memory_budget) extension is
+/// enabled on the device, the budget and usage reported by the implementation are used.
+/// Otherwise, the size of the heap is used as the budget, and the usage is calculated from the
+/// allocations that were made on the device.
+#[derive(Clone)]
+pub struct MemoryBudgetLimit {
+    /// The fraction of the budget of each heap that may be allocated.
+    ///
+    /// The value must be greater than 0.0 and less than or equal to 1.0.
+    ///
+    /// The default value is `1.0`.
+    pub fraction: f32,
+
+    /// Called when an allocation would exceed the limit. If it returns `true`, the allocation is
+    /// made anyway, which allows logging a warning instead of failing. If it returns `false`, the
+    /// allocation fails with [`DeviceMemoryError::BudgetLimitExceeded`].
+    ///
+    /// If `None`, allocations that would exceed the limit always fail.
+    ///
+    /// The default value is `None`.
+    pub on_exceeded: Option<Arc<dyn Fn(&MemoryBudgetExceeded) -> bool + Send + Sync>>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for MemoryBudgetLimit {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            fraction: 1.0,
+            on_exceeded: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+impl Debug for MemoryBudgetLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let Self {
+            fraction,
+            on_exceeded: _,
+            _ne: _,
+        } = self;
+
+        f.debug_struct("MemoryBudgetLimit")
+            .field("fraction", fraction)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Information about an allocation that would exceed a [`MemoryBudgetLimit`].
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct MemoryBudgetExceeded {
+    /// The memory type that the allocation was requested from.
+    pub memory_type_index: u32,
+
+    /// The heap of the memory type.
+    pub heap_index: u32,
+
+    /// The size of the requested allocation.
+    pub allocation_size: DeviceSize,
+
+    /// The current usage of the heap.
+    pub usage: DeviceSize,
+
+    /// The number of bytes that may be allocated from the heap according to the limit.
+    pub limit: DeviceSize,
+}
+
 /// Represents requirements expressed by the Vulkan implementation when it comes to binding memory
 /// to a resource.
 #[derive(Debug, Copy, Clone)]
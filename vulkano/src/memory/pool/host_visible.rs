@@ -10,7 +10,8 @@
 use crate::{
     device::Device,
     memory::{
-        device_memory::MemoryAllocateInfo, DeviceMemory, DeviceMemoryError, MappedDeviceMemory,
+        device_memory::MemoryAllocateInfo, pool::TrimReport, DeviceMemory, DeviceMemoryError,
+        MappedDeviceMemory,
     },
     DeviceSize,
 };
@@ -140,6 +141,23 @@ impl StandardHostVisibleMemoryTypePool {
     pub fn memory_type_index(&self) -> u32 {
         self.memory_type_index
     }
+
+    /// Frees the blocks of device memory of the pool that no longer contain any allocations.
+    pub fn trim(&self) -> TrimReport {
+        let mut report = TrimReport::default();
+
+        self.occupied.lock().retain(|(block, entries)| {
+            if !entries.is_empty() {
+                return true;
+            }
+
+            report.freed_block_count += 1;
+            report.freed_bytes += (**block).as_ref().allocation_size();
+            false
+        });
+
+        report
+    }
 }
 
 #[derive(Debug)]
@@ -112,6 +112,40 @@ where
     }
 }
 
+/// Allocate dedicated memory with a priority.
+/// The priority applies to a whole `DeviceMemory`, so it can't be given to pool memory that is
+/// shared between resources.
+pub(crate) fn alloc_dedicated_with_priority<F>(
+    device: Arc<Device>,
+    requirements: &MemoryRequirements,
+    map: MappingRequirement,
+    dedicated_allocation: DedicatedAllocation<'_>,
+    filter: F,
+    priority: f32,
+) -> Result<PotentialDedicatedAllocation<StandardMemoryPoolAlloc>, DeviceMemoryError>
+where
+    F: FnMut(&MemoryType) -> AllocFromRequirementsFilter,
+{
+    let memory_type_index = choose_allocation_memory_type(&device, requirements, filter, map);
+    let memory = DeviceMemory::allocate(
+        device,
+        MemoryAllocateInfo {
+            allocation_size: requirements.size,
+            memory_type_index,
+            priority: Some(priority),
+            ..MemoryAllocateInfo::dedicated_allocation(dedicated_allocation)
+        },
+    )?;
+
+    match map {
+        MappingRequirement::Map => {
+            let mapped_memory = MappedDeviceMemory::new(memory, 0..requirements.size)?;
+            Ok(PotentialDedicatedAllocation::DedicatedMapped(mapped_memory))
+        }
+        MappingRequirement::DoNotMap => Ok(PotentialDedicatedAllocation::Dedicated(memory)),
+    }
+}
+
 /// Pool of GPU-visible memory that can be allocated from.
 pub unsafe trait MemoryPool: DeviceOwned {
     /// Object that represents a single allocation. Its destructor should free the chunk.
@@ -241,6 +275,77 @@ pub enum AllocFromRequirementsFilter {
     Forbidden,
 }
 
+/// Policy for choosing the memory type that a resource is allocated from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MemoryTypePolicy {
+    /// Prefer memory types that are device-local, and fall back to any other memory type.
+    ///
+    /// The allocation is not mapped.
+    PreferDeviceLocal,
+
+    /// Prefer memory types that are both device-local and host-visible, and fall back to
+    /// host-visible memory types.
+    ///
+    /// The allocation is mapped, so that the host can access it directly. On devices with
+    /// resizable BAR, and on integrated GPUs where all memory is shared, this gives the device
+    /// fast access to memory that the host can write to without a staging copy.
+    PreferDeviceLocalHostVisible,
+}
+
+impl MemoryTypePolicy {
+    /// Returns how a memory type should be treated when allocating with this policy.
+    #[inline]
+    pub fn filter(self, memory_type: &MemoryType) -> AllocFromRequirementsFilter {
+        let flags = &memory_type.property_flags;
+
+        match self {
+            MemoryTypePolicy::PreferDeviceLocal => {
+                if flags.device_local {
+                    AllocFromRequirementsFilter::Preferred
+                } else {
+                    AllocFromRequirementsFilter::Allowed
+                }
+            }
+            MemoryTypePolicy::PreferDeviceLocalHostVisible => {
+                if !flags.host_visible {
+                    AllocFromRequirementsFilter::Forbidden
+                } else if flags.device_local {
+                    AllocFromRequirementsFilter::Preferred
+                } else {
+                    AllocFromRequirementsFilter::Allowed
+                }
+            }
+        }
+    }
+
+    /// Returns whether allocations made with this policy are mapped.
+    #[inline]
+    pub fn mapping_requirement(self) -> MappingRequirement {
+        match self {
+            MemoryTypePolicy::PreferDeviceLocal => MappingRequirement::DoNotMap,
+            MemoryTypePolicy::PreferDeviceLocalHostVisible => MappingRequirement::Map,
+        }
+    }
+}
+
+impl Default for MemoryTypePolicy {
+    #[inline]
+    fn default() -> Self {
+        MemoryTypePolicy::PreferDeviceLocal
+    }
+}
+
+/// The result of trimming a memory pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TrimReport {
+    /// The number of blocks of device memory that were freed.
+    pub freed_block_count: u32,
+
+    /// The total size in bytes of the blocks that were freed.
+    pub freed_bytes: DeviceSize,
+}
+
 /// Object that represents a single allocation. Its destructor should free the chunk.
 pub unsafe trait MemoryPoolAlloc: Send + Sync {
     /// Returns the memory object from which this is allocated. Returns `None` if the memory is
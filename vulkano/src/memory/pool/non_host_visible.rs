@@ -9,7 +9,9 @@
 
 use crate::{
     device::Device,
-    memory::{device_memory::MemoryAllocateInfo, DeviceMemory, DeviceMemoryError},
+    memory::{
+        device_memory::MemoryAllocateInfo, pool::TrimReport, DeviceMemory, DeviceMemoryError,
+    },
     DeviceSize,
 };
 use parking_lot::Mutex;
@@ -128,6 +130,23 @@ impl StandardNonHostVisibleMemoryTypePool {
     pub fn memory_type_index(&self) -> u32 {
         self.memory_type_index
     }
+
+    /// Frees the blocks of device memory of the pool that no longer contain any allocations.
+    pub fn trim(&self) -> TrimReport {
+        let mut report = TrimReport::default();
+
+        self.occupied.lock().retain(|(block, entries)| {
+            if !entries.is_empty() {
+                return true;
+            }
+
+            report.freed_block_count += 1;
+            report.freed_bytes += block.allocation_size();
+            false
+        });
+
+        report
+    }
 }
 
 #[derive(Debug)]
@@ -14,6 +14,7 @@ use crate::{
             AllocLayout, MappingRequirement, MemoryPool, MemoryPoolAlloc,
             StandardHostVisibleMemoryTypePool, StandardHostVisibleMemoryTypePoolAlloc,
             StandardNonHostVisibleMemoryTypePool, StandardNonHostVisibleMemoryTypePoolAlloc,
+            TrimReport,
         },
         DeviceMemory, DeviceMemoryError, MappedDeviceMemory,
     },
@@ -48,6 +49,30 @@ impl StandardMemoryPool {
             pools: Mutex::new(HashMap::with_capacity(cap)),
         })
     }
+
+    /// Frees the blocks of device memory of the pool that no longer contain any allocations.
+    ///
+    /// The pool keeps blocks around after the allocations in them are dropped, so that they can
+    /// be reused. Call this after freeing many resources, for example when unloading a level, to
+    /// return the memory to the device.
+    ///
+    /// Allocations that are still alive are never moved, so memory that is only partially used
+    /// stays allocated.
+    pub fn trim(&self) -> TrimReport {
+        let pools = self.pools.lock();
+        let mut report = TrimReport::default();
+
+        for pool in pools.values() {
+            let pool_report = match pool {
+                Pool::HostVisible(pool) => pool.trim(),
+                Pool::NonHostVisible(pool) => pool.trim(),
+            };
+            report.freed_block_count += pool_report.freed_block_count;
+            report.freed_bytes += pool_report.freed_bytes;
+        }
+
+        report
+    }
 }
 
 fn generic_allocation(
@@ -204,3 +229,45 @@ enum StandardMemoryPoolAllocInner {
     NonHostVisible(StandardNonHostVisibleMemoryTypePoolAlloc),
     HostVisible(StandardHostVisibleMemoryTypePoolAlloc),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::StandardMemoryPool;
+    use crate::memory::pool::{
+        AllocLayout, MappingRequirement, MemoryPool, MemoryPoolAlloc, TrimReport,
+    };
+
+    #[test]
+    fn trim() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = StandardMemoryPool::new(device.clone());
+        let alloc = || {
+            pool.alloc_generic(0, 256, 1, AllocLayout::Linear, MappingRequirement::DoNotMap)
+                .unwrap()
+        };
+
+        let alloc1 = alloc();
+        let alloc2 = alloc();
+        assert_eq!(device.allocation_count(), 1);
+
+        // The block still contains `alloc2`.
+        drop(alloc1);
+        assert_eq!(pool.trim(), TrimReport::default());
+        assert_eq!(device.allocation_count(), 1);
+
+        let allocation_size = alloc2.memory().allocation_size();
+        drop(alloc2);
+        assert_eq!(device.allocation_count(), 1);
+        assert_eq!(
+            pool.trim(),
+            TrimReport {
+                freed_block_count: 1,
+                freed_bytes: allocation_size,
+            }
+        );
+        assert_eq!(device.allocation_count(), 0);
+
+        // Nothing is left to free.
+        assert_eq!(pool.trim(), TrimReport::default());
+    }
+}
@@ -20,9 +20,38 @@
 //! Once that is done, you can extract the data from the cache and store it. See the documentation
 //! of [`get_data`](crate::pipeline::cache::PipelineCache::get_data) for example of how to store the data
 //! on the disk, and [`with_data`](crate::pipeline::cache::PipelineCache::with_data) for how to reload it.
-
-use crate::{device::Device, OomError, VulkanError, VulkanObject};
-use std::{mem::MaybeUninit, ptr, sync::Arc};
+//!
+//! # Multithreading
+//!
+//! A pipeline cache can be shared between threads that create pipelines at the same time. By
+//! default, the Vulkan implementation synchronizes accesses to the cache internally, and vulkano
+//! only needs to prevent pipelines from being created with a cache while other caches are being
+//! merged into it.
+//!
+//! If contention on the cache becomes a bottleneck, there are two alternatives:
+//!
+//! - Create the cache with the
+//!   [`externally_synchronized`](PipelineCacheCreateFlags::externally_synchronized) flag. The
+//!   implementation then skips its internal synchronization, and vulkano locks the cache for the
+//!   duration of each pipeline creation instead. This is mostly useful for caches that are only
+//!   used by one thread.
+//! - Give each compile thread its own [`WorkerPipelineCache`], obtained with
+//!   [`PipelineCache::worker`]. Worker caches are not shared, and merge their contents into the
+//!   parent cache when they are dropped.
+
+use crate::{
+    device::Device, macros::vulkan_bitflags, OomError, RequirementNotMet, RequiresOneOf,
+    VulkanError, VulkanObject,
+};
+use parking_lot::RwLock;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    mem::MaybeUninit,
+    ops::Deref,
+    ptr,
+    sync::Arc,
+};
 
 /// Opaque cache that contains pipeline objects.
 ///
@@ -31,6 +60,11 @@ use std::{mem::MaybeUninit, ptr, sync::Arc};
 pub struct PipelineCache {
     device: Arc<Device>,
     cache: ash::vk::PipelineCache,
+    flags: PipelineCacheCreateFlags,
+
+    // Taken for writing when the cache must not be accessed by anything else, and for reading
+    // when concurrent accesses are allowed.
+    lock: RwLock<()>,
 }
 
 impl PipelineCache {
@@ -81,7 +115,11 @@ impl PipelineCache {
         device: Arc<Device>,
         initial_data: &[u8],
     ) -> Result<Arc<PipelineCache>, OomError> {
-        PipelineCache::new_impl(device, Some(initial_data))
+        PipelineCache::new_impl(
+            device,
+            PipelineCacheCreateFlags::empty(),
+            Some(initial_data),
+        )
     }
 
     /// Builds a new empty pipeline cache.
@@ -97,19 +135,79 @@ impl PipelineCache {
     /// ```
     #[inline]
     pub fn empty(device: Arc<Device>) -> Result<Arc<PipelineCache>, OomError> {
-        unsafe { PipelineCache::new_impl(device, None) }
+        unsafe { PipelineCache::new_impl(device, PipelineCacheCreateFlags::empty(), None) }
+    }
+
+    /// Builds a new pipeline cache with the given flags, and optionally from existing data.
+    ///
+    /// # Safety
+    ///
+    /// - If `create_info.initial_data` is not empty, it must have been previously obtained with
+    ///   [`get_data`](#method.get_data). See [`with_data`](#method.with_data).
+    pub unsafe fn new(
+        device: Arc<Device>,
+        create_info: PipelineCacheCreateInfo,
+    ) -> Result<Arc<PipelineCache>, PipelineCacheCreationError> {
+        let PipelineCacheCreateInfo {
+            flags,
+            initial_data,
+            _ne: _,
+        } = create_info;
+
+        // VUID-VkPipelineCacheCreateInfo-flags-parameter
+        flags.validate_device(&device)?;
+
+        // VUID-VkPipelineCacheCreateInfo-pipelineCreationCacheControl-02892
+        if flags.externally_synchronized
+            && !device.enabled_features().pipeline_creation_cache_control
+        {
+            return Err(PipelineCacheCreationError::RequirementNotMet {
+                required_for: "`create_info.flags.externally_synchronized` is set",
+                requires_one_of: RequiresOneOf {
+                    features: &["pipeline_creation_cache_control"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        let initial_data = (!initial_data.is_empty()).then_some(initial_data.as_slice());
+
+        Ok(PipelineCache::new_impl(device, flags, initial_data)?)
+    }
+
+    /// Builds a new empty pipeline cache for use by a single thread, that merges its contents
+    /// into `self` when it is dropped.
+    ///
+    /// If the
+    /// [`pipeline_creation_cache_control`](crate::device::Features::pipeline_creation_cache_control)
+    /// feature is enabled, the worker cache is created with the
+    /// [`externally_synchronized`](PipelineCacheCreateFlags::externally_synchronized) flag.
+    pub fn worker(self: &Arc<Self>) -> Result<WorkerPipelineCache, OomError> {
+        let flags = PipelineCacheCreateFlags {
+            externally_synchronized: self
+                .device
+                .enabled_features()
+                .pipeline_creation_cache_control,
+            ..PipelineCacheCreateFlags::empty()
+        };
+
+        Ok(WorkerPipelineCache {
+            cache: unsafe { PipelineCache::new_impl(self.device.clone(), flags, None)? },
+            parent: self.clone(),
+        })
     }
 
     // Actual implementation of the constructor.
     unsafe fn new_impl(
         device: Arc<Device>,
+        flags: PipelineCacheCreateFlags,
         initial_data: Option<&[u8]>,
     ) -> Result<Arc<PipelineCache>, OomError> {
         let fns = device.fns();
 
         let cache = {
             let infos = ash::vk::PipelineCacheCreateInfo {
-                flags: ash::vk::PipelineCacheCreateFlags::empty(),
+                flags: flags.into(),
                 initial_data_size: initial_data.map(|d| d.len()).unwrap_or(0),
                 p_initial_data: initial_data
                     .map(|d| d.as_ptr() as *const _)
@@ -132,18 +230,41 @@ impl PipelineCache {
         Ok(Arc::new(PipelineCache {
             device: device.clone(),
             cache,
+            flags,
+            lock: RwLock::new(()),
         }))
     }
 
+    /// Returns the flags that the pipeline cache was created with.
+    #[inline]
+    pub fn flags(&self) -> PipelineCacheCreateFlags {
+        self.flags
+    }
+
+    // Calls `f` with the handle of the cache, while holding the lock that is needed to use it for
+    // creating pipelines.
+    pub(crate) fn with_lock<R>(&self, f: impl FnOnce(ash::vk::PipelineCache) -> R) -> R {
+        if self.flags.externally_synchronized {
+            let _lock = self.lock.write();
+            f(self.cache)
+        } else {
+            let _lock = self.lock.read();
+            f(self.cache)
+        }
+    }
+
     /// Merges other pipeline caches into this one.
     ///
     /// It is `self` that is modified here. The pipeline caches passed as parameter are untouched.
     ///
+    /// Pipelines can't be created with `self` while the merge is in progress, and the other
+    /// caches are locked as well if they were created with the
+    /// [`externally_synchronized`](PipelineCacheCreateFlags::externally_synchronized) flag.
+    ///
     /// # Panics
     ///
     /// - Panics if `self` is included in the list of other pipelines.
     ///
-    // FIXME: vkMergePipelineCaches is not thread safe for the destination cache
     // TODO: write example
     pub fn merge<'a>(
         &self,
@@ -152,13 +273,31 @@ impl PipelineCache {
         unsafe {
             let fns = self.device.fns();
 
-            let pipelines = pipelines
+            let pipelines: Vec<&PipelineCache> = pipelines
                 .into_iter()
                 .map(|pipeline| {
                     assert!(&***pipeline as *const _ != self as *const _);
-                    pipeline.cache
+                    &***pipeline
                 })
-                .collect::<Vec<_>>();
+                .collect();
+
+            // VUID-vkMergePipelineCaches-dstCache-parameter: host access to `dstCache` must be
+            // externally synchronized.
+            // The locks are always taken in order of address, so that two merges running
+            // concurrently in opposite directions can't deadlock.
+            let mut to_lock: Vec<&PipelineCache> = pipelines
+                .iter()
+                .copied()
+                .filter(|pipeline| pipeline.flags.externally_synchronized)
+                .chain(std::iter::once(self))
+                .collect();
+            to_lock.sort_by_key(|pipeline| *pipeline as *const PipelineCache);
+            to_lock.dedup_by_key(|pipeline| *pipeline as *const PipelineCache);
+            let _locks: Vec<_> = to_lock
+                .iter()
+                .map(|pipeline| pipeline.lock.write())
+                .collect();
+            let pipelines: Vec<_> = pipelines.iter().map(|pipeline| pipeline.cache).collect();
 
             (fns.v1_0.merge_pipeline_caches)(
                 self.device.internal_object(),
@@ -205,38 +344,40 @@ impl PipelineCache {
     pub fn get_data(&self) -> Result<Vec<u8>, OomError> {
         let fns = self.device.fns();
 
-        let data = unsafe {
+        self.with_lock(|cache| -> Result<Vec<u8>, OomError> {
             loop {
                 let mut count = 0;
-                (fns.v1_0.get_pipeline_cache_data)(
-                    self.device.internal_object(),
-                    self.cache,
-                    &mut count,
-                    ptr::null_mut(),
-                )
-                .result()
-                .map_err(VulkanError::from)?;
+                unsafe {
+                    (fns.v1_0.get_pipeline_cache_data)(
+                        self.device.internal_object(),
+                        cache,
+                        &mut count,
+                        ptr::null_mut(),
+                    )
+                    .result()
+                    .map_err(VulkanError::from)?;
+                }
 
                 let mut data: Vec<u8> = Vec::with_capacity(count as usize);
-                let result = (fns.v1_0.get_pipeline_cache_data)(
-                    self.device.internal_object(),
-                    self.cache,
-                    &mut count,
-                    data.as_mut_ptr() as *mut _,
-                );
+                let result = unsafe {
+                    (fns.v1_0.get_pipeline_cache_data)(
+                        self.device.internal_object(),
+                        cache,
+                        &mut count,
+                        data.as_mut_ptr() as *mut _,
+                    )
+                };
 
                 match result {
                     ash::vk::Result::SUCCESS => {
-                        data.set_len(count as usize);
-                        break data;
+                        unsafe { data.set_len(count as usize) };
+                        break Ok(data);
                     }
                     ash::vk::Result::INCOMPLETE => (),
                     err => return Err(VulkanError::from(err).into()),
                 }
             }
-        };
-
-        Ok(data)
+        })
     }
 }
 
@@ -263,12 +404,164 @@ impl Drop for PipelineCache {
     }
 }
 
+/// Parameters to create a new `PipelineCache`.
+#[derive(Clone, Debug)]
+pub struct PipelineCacheCreateInfo {
+    /// Additional properties of the pipeline cache.
+    ///
+    /// The default value is empty.
+    pub flags: PipelineCacheCreateFlags,
+
+    /// The data to initialize the cache with, previously obtained with
+    /// [`PipelineCache::get_data`].
+    ///
+    /// The default value is empty.
+    pub initial_data: Vec<u8>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for PipelineCacheCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            flags: PipelineCacheCreateFlags::empty(),
+            initial_data: Vec::new(),
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+vulkan_bitflags! {
+    /// Flags that can be set when creating a pipeline cache.
+    #[non_exhaustive]
+    PipelineCacheCreateFlags = PipelineCacheCreateFlags(u32);
+
+    /// All accesses to the pipeline cache are synchronized by the application rather than by the
+    /// Vulkan implementation, which may make accessing it cheaper.
+    ///
+    /// Vulkano then takes a lock on the cache for the duration of each pipeline creation, so
+    /// creating pipelines with the cache from multiple threads at once is still safe, but is
+    /// serialized.
+    ///
+    /// Requires the
+    /// [`pipeline_creation_cache_control`](crate::device::Features::pipeline_creation_cache_control)
+    /// feature to be enabled.
+    externally_synchronized = EXTERNALLY_SYNCHRONIZED {
+        api_version: V1_3,
+        device_extensions: [ext_pipeline_creation_cache_control],
+    },
+}
+
+/// Error that can happen when creating a `PipelineCache`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PipelineCacheCreationError {
+    /// Not enough memory.
+    OomError(OomError),
+
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+}
+
+impl Error for PipelineCacheCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::OomError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for PipelineCacheCreationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::OomError(_) => write!(f, "not enough memory available"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+        }
+    }
+}
+
+impl From<OomError> for PipelineCacheCreationError {
+    fn from(err: OomError) -> Self {
+        Self::OomError(err)
+    }
+}
+
+impl From<RequirementNotMet> for PipelineCacheCreationError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
+/// A pipeline cache for use by a single thread, that merges its contents into a parent cache when
+/// it is dropped.
+///
+/// It is created with [`PipelineCache::worker`], and dereferences to the worker's own
+/// `Arc<PipelineCache>`, which is what should be passed when creating pipelines.
+#[derive(Debug)]
+pub struct WorkerPipelineCache {
+    cache: Arc<PipelineCache>,
+    parent: Arc<PipelineCache>,
+}
+
+impl WorkerPipelineCache {
+    /// Returns the cache that the contents of this cache are merged into.
+    #[inline]
+    pub fn parent(&self) -> &Arc<PipelineCache> {
+        &self.parent
+    }
+
+    /// Merges the current contents of this cache into the parent cache, without waiting for it to
+    /// be dropped.
+    #[inline]
+    pub fn merge_into_parent(&self) -> Result<(), OomError> {
+        self.parent.merge(&[&self.cache])
+    }
+}
+
+impl Deref for WorkerPipelineCache {
+    type Target = Arc<PipelineCache>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.cache
+    }
+}
+
+impl Drop for WorkerPipelineCache {
+    #[inline]
+    fn drop(&mut self) {
+        // There is no way to report the error here, and the only consequence of a failed merge is
+        // that the pipelines will have to be compiled again.
+        let _ = self.merge_into_parent();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        pipeline::{cache::PipelineCache, ComputePipeline},
+        pipeline::{
+            cache::{
+                PipelineCache, PipelineCacheCreateFlags, PipelineCacheCreateInfo,
+                PipelineCacheCreationError,
+            },
+            ComputePipeline,
+        },
         shader::ShaderModule,
     };
+    use std::sync::Arc;
 
     #[test]
     fn merge_self_forbidden() {
@@ -279,6 +572,43 @@ mod tests {
         });
     }
 
+    #[test]
+    fn externally_synchronized_requires_feature() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        if device.enabled_features().pipeline_creation_cache_control {
+            return;
+        }
+
+        match unsafe {
+            PipelineCache::new(
+                device,
+                PipelineCacheCreateInfo {
+                    flags: PipelineCacheCreateFlags {
+                        externally_synchronized: true,
+                        ..PipelineCacheCreateFlags::empty()
+                    },
+                    ..Default::default()
+                },
+            )
+        } {
+            Err(PipelineCacheCreationError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn worker_merges_on_drop() {
+        let (device, _queue) = gfx_dev_and_queue!();
+        let parent = PipelineCache::empty(device).unwrap();
+
+        let worker = parent.worker().unwrap();
+        assert!(Arc::ptr_eq(worker.parent(), &parent));
+        drop(worker);
+
+        parent.get_data().unwrap();
+    }
+
     #[test]
     fn cache_returns_same_data() {
         let (device, _queue) = gfx_dev_and_queue!();
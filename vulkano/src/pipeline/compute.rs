@@ -31,10 +31,14 @@ use crate::{
     pipeline::{
         cache::PipelineCache,
         layout::{PipelineLayout, PipelineLayoutCreationError, PipelineLayoutSupersetError},
-        Pipeline, PipelineBindPoint,
+        shader_stage::{ShaderStageOptions, ShaderStageOptionsError},
+        Pipeline, PipelineBindPoint, PipelineCreateFlags,
     },
-    shader::{DescriptorRequirements, EntryPoint, SpecializationConstants},
-    DeviceSize, OomError, VulkanError, VulkanObject,
+    shader::{
+        spirv::Capability, DescriptorBindingRequirements, EntryPoint, LocalSizeValue, ShaderStage,
+        SpecializationConstants,
+    },
+    DeviceSize, OomError, RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
 };
 use ahash::HashMap;
 use std::{
@@ -58,8 +62,10 @@ pub struct ComputePipeline {
     handle: ash::vk::Pipeline,
     device: Arc<Device>,
     layout: Arc<PipelineLayout>,
-    descriptor_requirements: HashMap<(u32, u32), DescriptorRequirements>,
+    flags: PipelineCreateFlags,
+    descriptor_requirements: HashMap<(u32, u32), DescriptorBindingRequirements>,
     num_used_descriptor_sets: u32,
+    local_size: [u32; 3],
 }
 
 impl ComputePipeline {
@@ -79,6 +85,13 @@ impl ComputePipeline {
         Css: SpecializationConstants,
         F: FnOnce(&mut [DescriptorSetLayoutCreateInfo]),
     {
+        Self::validate_shader(
+            &device,
+            &shader,
+            specialization_constants,
+            &ShaderStageOptions::default(),
+        )?;
+
         let mut set_layout_create_infos =
             DescriptorSetLayoutCreateInfo::from_requirements(shader.descriptor_requirements());
         func(&mut set_layout_create_infos);
@@ -125,6 +138,92 @@ impl ComputePipeline {
     where
         Css: SpecializationConstants,
     {
+        Self::with_pipeline_layout_and_flags(
+            device,
+            shader,
+            specialization_constants,
+            layout,
+            cache,
+            PipelineCreateFlags::empty(),
+            None,
+        )
+    }
+
+    /// Same as `with_pipeline_layout`, but also allows specifying the flags to create the pipeline
+    /// with, and a base pipeline to create it as a derivative of.
+    ///
+    /// If `base_pipeline` is provided, it must have been created with the `allow_derivatives`
+    /// flag. The `derivative` flag is set automatically in that case, and ignored otherwise.
+    pub fn with_pipeline_layout_and_flags<Css>(
+        device: Arc<Device>,
+        shader: EntryPoint<'_>,
+        specialization_constants: &Css,
+        layout: Arc<PipelineLayout>,
+        cache: Option<Arc<PipelineCache>>,
+        flags: PipelineCreateFlags,
+        base_pipeline: Option<Arc<ComputePipeline>>,
+    ) -> Result<Arc<ComputePipeline>, ComputePipelineCreationError>
+    where
+        Css: SpecializationConstants,
+    {
+        Self::with_pipeline_layout_flags_and_stage_options(
+            device,
+            shader,
+            specialization_constants,
+            layout,
+            cache,
+            flags,
+            base_pipeline,
+            ShaderStageOptions::default(),
+        )
+    }
+
+    /// Same as `with_pipeline_layout_and_flags`, but also allows specifying additional options
+    /// for the compute shader stage.
+    pub fn with_pipeline_layout_flags_and_stage_options<Css>(
+        device: Arc<Device>,
+        shader: EntryPoint<'_>,
+        specialization_constants: &Css,
+        layout: Arc<PipelineLayout>,
+        cache: Option<Arc<PipelineCache>>,
+        flags: PipelineCreateFlags,
+        base_pipeline: Option<Arc<ComputePipeline>>,
+        stage_options: ShaderStageOptions,
+    ) -> Result<Arc<ComputePipeline>, ComputePipelineCreationError>
+    where
+        Css: SpecializationConstants,
+    {
+        let flags = PipelineCreateFlags {
+            derivative: base_pipeline.is_some(),
+            ..flags
+        };
+
+        // VUID-VkComputePipelineCreateInfo-flags-parameter
+        flags.validate_device(&device)?;
+
+        // VUID-VkComputePipelineCreateInfo-pipelineCreationCacheControl-02875
+        if (flags.fail_on_pipeline_compile_required || flags.early_return_on_failure)
+            && !device.enabled_features().pipeline_creation_cache_control
+        {
+            return Err(ComputePipelineCreationError::RequirementNotMet {
+                required_for: "`flags.fail_on_pipeline_compile_required` or `flags.early_return_on_failure` is set",
+                requires_one_of: RequiresOneOf {
+                    features: &["pipeline_creation_cache_control"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if let Some(base_pipeline) = &base_pipeline {
+            // VUID-VkComputePipelineCreateInfo-commonparent
+            assert_eq!(&device, base_pipeline.device());
+
+            // VUID-vkCreateComputePipelines-flags-00696
+            if !base_pipeline.flags().allow_derivatives {
+                return Err(ComputePipelineCreationError::BasePipelineDerivativesNotAllowed);
+            }
+        }
+
         let spec_descriptors = Css::descriptors();
 
         for (constant_id, reqs) in shader.specialization_constant_requirements() {
@@ -143,13 +242,18 @@ impl ComputePipeline {
             shader.push_constant_requirements(),
         )?;
 
+        Self::validate_shader(&device, &shader, specialization_constants, &stage_options)?;
+
         unsafe {
-            ComputePipeline::with_unchecked_pipeline_layout(
+            ComputePipeline::record_create(
                 device,
                 shader,
                 specialization_constants,
                 layout,
                 cache,
+                flags,
+                base_pipeline,
+                &stage_options,
             )
         }
     }
@@ -163,6 +267,105 @@ impl ComputePipeline {
         layout: Arc<PipelineLayout>,
         cache: Option<Arc<PipelineCache>>,
     ) -> Result<Arc<ComputePipeline>, ComputePipelineCreationError>
+    where
+        Css: SpecializationConstants,
+    {
+        ComputePipeline::record_create(
+            device,
+            shader,
+            specialization_constants,
+            layout,
+            cache,
+            PipelineCreateFlags::empty(),
+            None,
+            &ShaderStageOptions::default(),
+        )
+    }
+
+    fn validate_shader<Css>(
+        device: &Device,
+        shader: &EntryPoint<'_>,
+        specialization_constants: &Css,
+        stage_options: &ShaderStageOptions,
+    ) -> Result<(), ComputePipelineCreationError>
+    where
+        Css: SpecializationConstants,
+    {
+        let properties = device.physical_device().properties();
+        // Reflection can't resolve every way that a shader can specify its local size, for
+        // example a size computed with `OpSpecConstantOp`. The checks that depend on it are then
+        // skipped, and left to the validation layers.
+        let local_size = specialized_local_size(shader, specialization_constants);
+
+        stage_options.validate(device, ShaderStage::Compute, local_size)?;
+
+        if let Some(local_size) = local_size {
+            // VUID-RuntimeSpirv-x-06429
+            // VUID-RuntimeSpirv-y-06430
+            // VUID-RuntimeSpirv-z-06431
+            if (0..3).any(|i| local_size[i] > properties.max_compute_work_group_size[i]) {
+                return Err(
+                    ComputePipelineCreationError::MaxComputeWorkGroupSizeExceeded {
+                        local_size,
+                        max: properties.max_compute_work_group_size,
+                    },
+                );
+            }
+
+            // VUID-RuntimeSpirv-x-06432
+            let invocations = local_size.iter().map(|&size| size as u64).product::<u64>();
+
+            if invocations > properties.max_compute_work_group_invocations as u64 {
+                return Err(
+                    ComputePipelineCreationError::MaxComputeWorkGroupInvocationsExceeded {
+                        invocations,
+                        max: properties.max_compute_work_group_invocations,
+                    },
+                );
+            }
+        }
+
+        // VUID-RuntimeSpirv-shaderZeroInitializeWorkgroupMemory-06372
+        if shader.initializes_workgroup_memory()
+            && !device
+                .enabled_features()
+                .shader_zero_initialize_workgroup_memory
+        {
+            return Err(ComputePipelineCreationError::RequirementNotMet {
+                required_for: "the compute shader has a variable in workgroup memory with an \
+                    initializer",
+                requires_one_of: RequiresOneOf {
+                    features: &["shader_zero_initialize_workgroup_memory"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        // VUID-RuntimeSpirv-OpTypeCooperativeMatrixNV-06322
+        if shader
+            .module()
+            .spirv_capabilities()
+            .contains(&Capability::CooperativeMatrixNV)
+            && !properties
+                .cooperative_matrix_supported_stages
+                .map_or(false, |stages| stages.compute)
+        {
+            return Err(ComputePipelineCreationError::CooperativeMatrixStageNotSupported);
+        }
+
+        Ok(())
+    }
+
+    unsafe fn record_create<Css>(
+        device: Arc<Device>,
+        shader: EntryPoint<'_>,
+        specialization_constants: &Css,
+        layout: Arc<PipelineLayout>,
+        cache: Option<Arc<PipelineCache>>,
+        flags: PipelineCreateFlags,
+        base_pipeline: Option<Arc<ComputePipeline>>,
+        stage_options: &ShaderStageOptions,
+    ) -> Result<Arc<ComputePipeline>, ComputePipelineCreationError>
     where
         Css: SpecializationConstants,
     {
@@ -177,8 +380,10 @@ impl ComputePipeline {
                 p_data: specialization_constants as *const Css as *const _,
             };
 
-            let stage = ash::vk::PipelineShaderStageCreateInfo {
-                flags: ash::vk::PipelineShaderStageCreateFlags::empty(),
+            let mut required_subgroup_size_vk = stage_options.required_subgroup_size_vk();
+
+            let mut stage = ash::vk::PipelineShaderStageCreateInfo {
+                flags: stage_options.flags.into(),
                 stage: ash::vk::ShaderStageFlags::COMPUTE,
                 module: shader.module().internal_object(),
                 p_name: shader.name().as_ptr(),
@@ -190,32 +395,41 @@ impl ComputePipeline {
                 ..Default::default()
             };
 
+            if let Some(next) = required_subgroup_size_vk.as_mut() {
+                next.p_next = stage.p_next as *mut _;
+                stage.p_next = next as *const _ as *const _;
+            }
+
             let infos = ash::vk::ComputePipelineCreateInfo {
-                flags: ash::vk::PipelineCreateFlags::empty(),
+                flags: flags.into(),
                 stage,
                 layout: layout.internal_object(),
-                base_pipeline_handle: ash::vk::Pipeline::null(),
-                base_pipeline_index: 0,
+                base_pipeline_handle: base_pipeline
+                    .as_ref()
+                    .map_or(ash::vk::Pipeline::null(), |p| p.internal_object()),
+                base_pipeline_index: -1,
                 ..Default::default()
             };
 
-            let cache_handle = match cache {
-                Some(ref cache) => cache.internal_object(),
-                None => ash::vk::PipelineCache::null(),
+            let create = |cache_handle| {
+                let mut output = MaybeUninit::uninit();
+                (fns.v1_0.create_compute_pipelines)(
+                    device.internal_object(),
+                    cache_handle,
+                    1,
+                    &infos,
+                    ptr::null(),
+                    output.as_mut_ptr(),
+                )
+                .result()
+                .map_err(VulkanError::from)?;
+                Ok::<_, VulkanError>(output.assume_init())
             };
 
-            let mut output = MaybeUninit::uninit();
-            (fns.v1_0.create_compute_pipelines)(
-                device.internal_object(),
-                cache_handle,
-                1,
-                &infos,
-                ptr::null(),
-                output.as_mut_ptr(),
-            )
-            .result()
-            .map_err(VulkanError::from)?;
-            output.assume_init()
+            match cache {
+                Some(ref cache) => cache.with_lock(create)?,
+                None => create(ash::vk::PipelineCache::null())?,
+            }
         };
 
         let descriptor_requirements: HashMap<_, _> = shader
@@ -228,13 +442,17 @@ impl ComputePipeline {
             .max()
             .map(|x| x + 1)
             .unwrap_or(0);
+        let local_size =
+            specialized_local_size(&shader, specialization_constants).unwrap_or([1; 3]);
 
         Ok(Arc::new(ComputePipeline {
             handle,
             device: device.clone(),
             layout,
+            flags,
             descriptor_requirements,
             num_used_descriptor_sets,
+            local_size,
         }))
     }
 
@@ -244,11 +462,37 @@ impl ComputePipeline {
         &self.device
     }
 
+    /// Returns the flags that the pipeline was created with.
+    #[inline]
+    pub fn flags(&self) -> PipelineCreateFlags {
+        self.flags
+    }
+
+    /// Returns the local workgroup size of the compute shader, with any specialization constants
+    /// replaced by the values that the pipeline was created with.
+    ///
+    /// If the local size could not be determined from the SPIR-V code of the shader, this returns
+    /// `[1, 1, 1]`.
+    #[inline]
+    pub fn local_size(&self) -> [u32; 3] {
+        self.local_size
+    }
+
+    /// Returns the number of workgroups that must be dispatched so that at least `work_size`
+    /// invocations are run in each dimension.
+    #[inline]
+    pub fn group_counts(&self, work_size: [u32; 3]) -> [u32; 3] {
+        [0, 1, 2].map(|i| {
+            let local_size = self.local_size[i].max(1);
+            work_size[i] / local_size + (work_size[i] % local_size != 0) as u32
+        })
+    }
+
     /// Returns an iterator over the descriptor requirements for this pipeline.
     #[inline]
     pub fn descriptor_requirements(
         &self,
-    ) -> impl ExactSizeIterator<Item = ((u32, u32), &DescriptorRequirements)> {
+    ) -> impl ExactSizeIterator<Item = ((u32, u32), &DescriptorBindingRequirements)> {
         self.descriptor_requirements
             .iter()
             .map(|(loc, reqs)| (*loc, reqs))
@@ -313,11 +557,52 @@ impl Drop for ComputePipeline {
     }
 }
 
+/// Returns the local size of `shader`, with specialization constants resolved from
+/// `specialization_constants`, or `None` if the local size of the shader is not known.
+fn specialized_local_size<Css>(
+    shader: &EntryPoint<'_>,
+    specialization_constants: &Css,
+) -> Option<[u32; 3]>
+where
+    Css: SpecializationConstants,
+{
+    let local_size = shader.local_size()?;
+
+    Some(local_size.map(|value| {
+        match value {
+            LocalSizeValue::Literal(value) => value,
+            LocalSizeValue::SpecializationConstant {
+                constant_id,
+                default_value,
+            } => Css::descriptors()
+                .iter()
+                .find(|desc| desc.constant_id == constant_id && desc.size == 4)
+                .map_or(default_value, |desc| unsafe {
+                    // Safe because `SpecializationConstants` guarantees that the offsets and sizes of
+                    // the descriptors are valid for `Css`.
+                    ptr::read_unaligned(
+                        (specialization_constants as *const Css as *const u8)
+                            .add(desc.offset as usize) as *const u32,
+                    )
+                }),
+        }
+    }))
+}
+
 /// Error that can happen when creating a compute pipeline.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ComputePipelineCreationError {
     /// Not enough memory.
     OomError(OomError),
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+    /// The base pipeline was not created with the `allow_derivatives` flag.
+    BasePipelineDerivativesNotAllowed,
+    /// The shader uses cooperative matrices, but the compute stage is not included in the
+    /// `cooperative_matrix_supported_stages` device property.
+    CooperativeMatrixStageNotSupported,
     /// Error while creating a descriptor set layout object.
     DescriptorSetLayoutCreationError(DescriptorSetLayoutCreationError),
     /// Error while creating the pipeline layout object.
@@ -326,6 +611,16 @@ pub enum ComputePipelineCreationError {
     IncompatiblePipelineLayout(PipelineLayoutSupersetError),
     /// The provided specialization constants are not compatible with what the shader expects.
     IncompatibleSpecializationConstants,
+    /// The options for the shader stage are not valid.
+    InvalidShaderStageOptions(ShaderStageOptionsError),
+    /// The total number of invocations in the local workgroup of the shader exceeds the
+    /// `max_compute_work_group_invocations` limit.
+    MaxComputeWorkGroupInvocationsExceeded { invocations: u64, max: u32 },
+    /// The local workgroup size of the shader exceeds the `max_compute_work_group_size` limit.
+    MaxComputeWorkGroupSizeExceeded { local_size: [u32; 3], max: [u32; 3] },
+    /// The `fail_on_pipeline_compile_required` flag was set, and the pipeline could not be created
+    /// without compiling it.
+    PipelineCompileRequired,
 }
 
 impl Error for ComputePipelineCreationError {
@@ -335,33 +630,69 @@ impl Error for ComputePipelineCreationError {
             Self::DescriptorSetLayoutCreationError(err) => Some(err),
             Self::PipelineLayoutCreationError(err) => Some(err),
             Self::IncompatiblePipelineLayout(err) => Some(err),
-            Self::IncompatibleSpecializationConstants => None,
+            Self::InvalidShaderStageOptions(err) => Some(err),
+            _ => None,
         }
     }
 }
 
 impl Display for ComputePipelineCreationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
-        write!(
-            f,
-            "{}",
-            match self {
-                ComputePipelineCreationError::OomError(_) => "not enough memory available",
-                ComputePipelineCreationError::DescriptorSetLayoutCreationError(_) => {
-                    "error while creating a descriptor set layout object"
-                }
-                ComputePipelineCreationError::PipelineLayoutCreationError(_) => {
-                    "error while creating the pipeline layout object"
-                }
-                ComputePipelineCreationError::IncompatiblePipelineLayout(_) => {
-                    "the pipeline layout is not compatible with what the shader expects"
-                }
-                ComputePipelineCreationError::IncompatibleSpecializationConstants => {
-                    "the provided specialization constants are not compatible with what the shader \
-                    expects"
-                }
+        match self {
+            Self::OomError(_) => write!(f, "not enough memory available"),
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::BasePipelineDerivativesNotAllowed => write!(
+                f,
+                "the base pipeline was not created with the `allow_derivatives` flag",
+            ),
+            Self::CooperativeMatrixStageNotSupported => write!(
+                f,
+                "the shader uses cooperative matrices, but the compute stage is not included in \
+                the `cooperative_matrix_supported_stages` device property",
+            ),
+            Self::DescriptorSetLayoutCreationError(_) => {
+                write!(f, "error while creating a descriptor set layout object")
             }
-        )
+            Self::PipelineLayoutCreationError(_) => {
+                write!(f, "error while creating the pipeline layout object")
+            }
+            Self::IncompatiblePipelineLayout(_) => write!(
+                f,
+                "the pipeline layout is not compatible with what the shader expects",
+            ),
+            Self::IncompatibleSpecializationConstants => write!(
+                f,
+                "the provided specialization constants are not compatible with what the shader \
+                expects",
+            ),
+            Self::InvalidShaderStageOptions(_) => {
+                write!(f, "the options for the shader stage are not valid")
+            }
+            Self::MaxComputeWorkGroupInvocationsExceeded { invocations, max } => write!(
+                f,
+                "the total number of invocations in the local workgroup of the shader ({}) \
+                exceeds the `max_compute_work_group_invocations` limit ({})",
+                invocations, max,
+            ),
+            Self::MaxComputeWorkGroupSizeExceeded { local_size, max } => write!(
+                f,
+                "the local workgroup size of the shader ({:?}) exceeds the \
+                `max_compute_work_group_size` limit ({:?})",
+                local_size, max,
+            ),
+            Self::PipelineCompileRequired => write!(
+                f,
+                "the `fail_on_pipeline_compile_required` flag was set, and the pipeline could not \
+                be created without compiling it",
+            ),
+        }
     }
 }
 
@@ -389,11 +720,29 @@ impl From<PipelineLayoutSupersetError> for ComputePipelineCreationError {
     }
 }
 
+impl From<ShaderStageOptionsError> for ComputePipelineCreationError {
+    fn from(err: ShaderStageOptionsError) -> Self {
+        Self::InvalidShaderStageOptions(err)
+    }
+}
+
+impl From<RequirementNotMet> for ComputePipelineCreationError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
 impl From<VulkanError> for ComputePipelineCreationError {
     fn from(err: VulkanError) -> ComputePipelineCreationError {
         match err {
             err @ VulkanError::OutOfHostMemory => Self::OomError(OomError::from(err)),
             err @ VulkanError::OutOfDeviceMemory => Self::OomError(OomError::from(err)),
+            VulkanError::Unnamed(ash::vk::Result::PIPELINE_COMPILE_REQUIRED) => {
+                Self::PipelineCompileRequired
+            }
             _ => panic!("unexpected error: {:?}", err),
         }
     }
@@ -409,10 +758,73 @@ mod tests {
         descriptor_set::{
             allocator::StandardDescriptorSetAllocator, PersistentDescriptorSet, WriteDescriptorSet,
         },
-        pipeline::{ComputePipeline, Pipeline, PipelineBindPoint},
-        shader::{ShaderModule, SpecializationConstants, SpecializationMapEntry},
+        device::{Device, DeviceCreateInfo, DeviceExtensions, Features, QueueCreateInfo},
+        pipeline::{
+            compute::ComputePipelineCreationError,
+            layout::{PipelineLayout, PipelineLayoutCreateInfo, PushConstantRange},
+            shader_stage::{ShaderStageOptions, ShaderStageOptionsError},
+            ComputePipeline, Pipeline, PipelineBindPoint, PipelineCreateFlags,
+        },
+        shader::{
+            reflect, spirv::Spirv, LocalSizeValue, PushConstantMember, PushConstantsTypeError,
+            ShaderModule, ShaderStages, SpecializationConstants, SpecializationMapEntry,
+        },
         sync::{now, GpuFuture},
     };
+    use std::sync::Arc;
+
+    /*
+    #version 450
+
+    layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+    layout(constant_id = 83) const int VALUE = 0xdeadbeef;
+
+    layout(set = 0, binding = 0) buffer Output {
+        int write;
+    } write;
+
+    void main() {
+        write.write = VALUE;
+    }
+    */
+    const SPEC_CONSTANT_MODULE: [u8; 480] = [
+        3, 2, 35, 7, 0, 0, 1, 0, 1, 0, 8, 0, 14, 0, 0, 0, 0, 0, 0, 0, 17, 0, 2, 0, 1, 0, 0, 0, 11,
+        0, 6, 0, 1, 0, 0, 0, 71, 76, 83, 76, 46, 115, 116, 100, 46, 52, 53, 48, 0, 0, 0, 0, 14, 0,
+        3, 0, 0, 0, 0, 0, 1, 0, 0, 0, 15, 0, 5, 0, 5, 0, 0, 0, 4, 0, 0, 0, 109, 97, 105, 110, 0, 0,
+        0, 0, 16, 0, 6, 0, 4, 0, 0, 0, 17, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 3, 0, 3, 0,
+        2, 0, 0, 0, 194, 1, 0, 0, 5, 0, 4, 0, 4, 0, 0, 0, 109, 97, 105, 110, 0, 0, 0, 0, 5, 0, 4,
+        0, 7, 0, 0, 0, 79, 117, 116, 112, 117, 116, 0, 0, 6, 0, 5, 0, 7, 0, 0, 0, 0, 0, 0, 0, 119,
+        114, 105, 116, 101, 0, 0, 0, 5, 0, 4, 0, 9, 0, 0, 0, 119, 114, 105, 116, 101, 0, 0, 0, 5,
+        0, 4, 0, 11, 0, 0, 0, 86, 65, 76, 85, 69, 0, 0, 0, 72, 0, 5, 0, 7, 0, 0, 0, 0, 0, 0, 0, 35,
+        0, 0, 0, 0, 0, 0, 0, 71, 0, 3, 0, 7, 0, 0, 0, 3, 0, 0, 0, 71, 0, 4, 0, 9, 0, 0, 0, 34, 0,
+        0, 0, 0, 0, 0, 0, 71, 0, 4, 0, 9, 0, 0, 0, 33, 0, 0, 0, 0, 0, 0, 0, 71, 0, 4, 0, 11, 0, 0,
+        0, 1, 0, 0, 0, 83, 0, 0, 0, 19, 0, 2, 0, 2, 0, 0, 0, 33, 0, 3, 0, 3, 0, 0, 0, 2, 0, 0, 0,
+        21, 0, 4, 0, 6, 0, 0, 0, 32, 0, 0, 0, 1, 0, 0, 0, 30, 0, 3, 0, 7, 0, 0, 0, 6, 0, 0, 0, 32,
+        0, 4, 0, 8, 0, 0, 0, 2, 0, 0, 0, 7, 0, 0, 0, 59, 0, 4, 0, 8, 0, 0, 0, 9, 0, 0, 0, 2, 0, 0,
+        0, 43, 0, 4, 0, 6, 0, 0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 50, 0, 4, 0, 6, 0, 0, 0, 11, 0, 0, 0,
+        239, 190, 173, 222, 32, 0, 4, 0, 12, 0, 0, 0, 2, 0, 0, 0, 6, 0, 0, 0, 54, 0, 5, 0, 2, 0, 0,
+        0, 4, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 248, 0, 2, 0, 5, 0, 0, 0, 65, 0, 5, 0, 12, 0, 0, 0,
+        13, 0, 0, 0, 9, 0, 0, 0, 10, 0, 0, 0, 62, 0, 3, 0, 13, 0, 0, 0, 11, 0, 0, 0, 253, 0, 1, 0,
+        56, 0, 1, 0,
+    ];
+
+    #[derive(Debug, Copy, Clone)]
+    #[allow(non_snake_case)]
+    #[repr(C)]
+    struct SpecConsts {
+        VALUE: i32,
+    }
+    unsafe impl SpecializationConstants for SpecConsts {
+        fn descriptors() -> &'static [SpecializationMapEntry] {
+            static DESCRIPTORS: [SpecializationMapEntry; 1] = [SpecializationMapEntry {
+                constant_id: 83,
+                offset: 0,
+                size: 4,
+            }];
+            &DESCRIPTORS
+        }
+    }
 
     // TODO: test for basic creation
     // TODO: test for pipeline layout error
@@ -425,62 +837,8 @@ mod tests {
 
         let (device, queue) = gfx_dev_and_queue!();
 
-        let module = unsafe {
-            /*
-            #version 450
-
-            layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
-
-            layout(constant_id = 83) const int VALUE = 0xdeadbeef;
-
-            layout(set = 0, binding = 0) buffer Output {
-                int write;
-            } write;
-
-            void main() {
-                write.write = VALUE;
-            }
-            */
-            const MODULE: [u8; 480] = [
-                3, 2, 35, 7, 0, 0, 1, 0, 1, 0, 8, 0, 14, 0, 0, 0, 0, 0, 0, 0, 17, 0, 2, 0, 1, 0, 0,
-                0, 11, 0, 6, 0, 1, 0, 0, 0, 71, 76, 83, 76, 46, 115, 116, 100, 46, 52, 53, 48, 0,
-                0, 0, 0, 14, 0, 3, 0, 0, 0, 0, 0, 1, 0, 0, 0, 15, 0, 5, 0, 5, 0, 0, 0, 4, 0, 0, 0,
-                109, 97, 105, 110, 0, 0, 0, 0, 16, 0, 6, 0, 4, 0, 0, 0, 17, 0, 0, 0, 1, 0, 0, 0, 1,
-                0, 0, 0, 1, 0, 0, 0, 3, 0, 3, 0, 2, 0, 0, 0, 194, 1, 0, 0, 5, 0, 4, 0, 4, 0, 0, 0,
-                109, 97, 105, 110, 0, 0, 0, 0, 5, 0, 4, 0, 7, 0, 0, 0, 79, 117, 116, 112, 117, 116,
-                0, 0, 6, 0, 5, 0, 7, 0, 0, 0, 0, 0, 0, 0, 119, 114, 105, 116, 101, 0, 0, 0, 5, 0,
-                4, 0, 9, 0, 0, 0, 119, 114, 105, 116, 101, 0, 0, 0, 5, 0, 4, 0, 11, 0, 0, 0, 86,
-                65, 76, 85, 69, 0, 0, 0, 72, 0, 5, 0, 7, 0, 0, 0, 0, 0, 0, 0, 35, 0, 0, 0, 0, 0, 0,
-                0, 71, 0, 3, 0, 7, 0, 0, 0, 3, 0, 0, 0, 71, 0, 4, 0, 9, 0, 0, 0, 34, 0, 0, 0, 0, 0,
-                0, 0, 71, 0, 4, 0, 9, 0, 0, 0, 33, 0, 0, 0, 0, 0, 0, 0, 71, 0, 4, 0, 11, 0, 0, 0,
-                1, 0, 0, 0, 83, 0, 0, 0, 19, 0, 2, 0, 2, 0, 0, 0, 33, 0, 3, 0, 3, 0, 0, 0, 2, 0, 0,
-                0, 21, 0, 4, 0, 6, 0, 0, 0, 32, 0, 0, 0, 1, 0, 0, 0, 30, 0, 3, 0, 7, 0, 0, 0, 6, 0,
-                0, 0, 32, 0, 4, 0, 8, 0, 0, 0, 2, 0, 0, 0, 7, 0, 0, 0, 59, 0, 4, 0, 8, 0, 0, 0, 9,
-                0, 0, 0, 2, 0, 0, 0, 43, 0, 4, 0, 6, 0, 0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 50, 0, 4, 0,
-                6, 0, 0, 0, 11, 0, 0, 0, 239, 190, 173, 222, 32, 0, 4, 0, 12, 0, 0, 0, 2, 0, 0, 0,
-                6, 0, 0, 0, 54, 0, 5, 0, 2, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 248, 0, 2,
-                0, 5, 0, 0, 0, 65, 0, 5, 0, 12, 0, 0, 0, 13, 0, 0, 0, 9, 0, 0, 0, 10, 0, 0, 0, 62,
-                0, 3, 0, 13, 0, 0, 0, 11, 0, 0, 0, 253, 0, 1, 0, 56, 0, 1, 0,
-            ];
-            ShaderModule::from_bytes(device.clone(), &MODULE).unwrap()
-        };
-
-        #[derive(Debug, Copy, Clone)]
-        #[allow(non_snake_case)]
-        #[repr(C)]
-        struct SpecConsts {
-            VALUE: i32,
-        }
-        unsafe impl SpecializationConstants for SpecConsts {
-            fn descriptors() -> &'static [SpecializationMapEntry] {
-                static DESCRIPTORS: [SpecializationMapEntry; 1] = [SpecializationMapEntry {
-                    constant_id: 83,
-                    offset: 0,
-                    size: 4,
-                }];
-                &DESCRIPTORS
-            }
-        }
+        let module =
+            unsafe { ShaderModule::from_bytes(device.clone(), &SPEC_CONSTANT_MODULE).unwrap() };
 
         let pipeline = ComputePipeline::new(
             device.clone(),
@@ -538,4 +896,539 @@ mod tests {
         let data_buffer_content = data_buffer.read().unwrap();
         assert_eq!(*data_buffer_content, 0x12345678);
     }
+
+    #[test]
+    fn reflect_local_size() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let module = unsafe { ShaderModule::from_bytes(device, &SPEC_CONSTANT_MODULE).unwrap() };
+
+        assert_eq!(
+            module.entry_point("main").unwrap().local_size(),
+            Some([LocalSizeValue::Literal(1); 3]),
+        );
+    }
+
+    #[test]
+    fn local_size_unknown() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let words: Vec<u32> = SPEC_CONSTANT_MODULE
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+        let spirv = Spirv::new(&words).unwrap();
+
+        // Simulate a shader whose local size could not be reflected.
+        let entry_points =
+            reflect::entry_points(&spirv).map(|(name, execution_model, mut info)| {
+                info.local_size = None;
+                (name, execution_model, info)
+            });
+        let module = unsafe {
+            ShaderModule::from_words_with_data(
+                device.clone(),
+                &words,
+                spirv.version(),
+                reflect::spirv_capabilities(&spirv),
+                reflect::spirv_extensions(&spirv),
+                entry_points,
+            )
+            .unwrap()
+        };
+
+        // The pipeline can still be created, only the checks against the local size are skipped.
+        let pipeline = ComputePipeline::new(
+            device,
+            module.entry_point("main").unwrap(),
+            &(),
+            None,
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(pipeline.local_size(), [1; 3]);
+    }
+
+    /// Creates a module from `SPEC_CONSTANT_MODULE`, with its reflected local size replaced by
+    /// `local_size`.
+    fn module_with_local_size(
+        device: Arc<Device>,
+        local_size: [LocalSizeValue; 3],
+    ) -> Arc<ShaderModule> {
+        let words: Vec<u32> = SPEC_CONSTANT_MODULE
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+        let spirv = Spirv::new(&words).unwrap();
+
+        let entry_points =
+            reflect::entry_points(&spirv).map(|(name, execution_model, mut info)| {
+                info.local_size = Some(local_size);
+                (name, execution_model, info)
+            });
+
+        unsafe {
+            ShaderModule::from_words_with_data(
+                device,
+                &words,
+                spirv.version(),
+                reflect::spirv_capabilities(&spirv),
+                reflect::spirv_extensions(&spirv),
+                entry_points,
+            )
+            .unwrap()
+        }
+    }
+
+    #[test]
+    fn max_work_group_size_exceeded() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let max = device
+            .physical_device()
+            .properties()
+            .max_compute_work_group_size;
+        let module = module_with_local_size(
+            device.clone(),
+            [
+                LocalSizeValue::Literal(max[0] + 1),
+                LocalSizeValue::Literal(1),
+                LocalSizeValue::Literal(1),
+            ],
+        );
+
+        assert!(matches!(
+            ComputePipeline::new(
+                device,
+                module.entry_point("main").unwrap(),
+                &SpecConsts { VALUE: 0 },
+                None,
+                |_| {},
+            ),
+            Err(ComputePipelineCreationError::MaxComputeWorkGroupSizeExceeded {
+                local_size,
+                ..
+            }) if local_size == [max[0] + 1, 1, 1]
+        ));
+    }
+
+    #[test]
+    fn max_work_group_invocations_exceeded() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let properties = device.physical_device().properties();
+        let max_size = properties.max_compute_work_group_size;
+        let max_invocations = properties.max_compute_work_group_invocations;
+
+        // Every dimension is within its own limit, but together they exceed the invocation limit.
+        if max_size.iter().map(|&size| size as u64).product::<u64>() <= max_invocations as u64 {
+            return;
+        }
+
+        let module = module_with_local_size(device.clone(), max_size.map(LocalSizeValue::Literal));
+
+        assert!(matches!(
+            ComputePipeline::new(
+                device,
+                module.entry_point("main").unwrap(),
+                &SpecConsts { VALUE: 0 },
+                None,
+                |_| {},
+            ),
+            Err(ComputePipelineCreationError::MaxComputeWorkGroupInvocationsExceeded {
+                max,
+                ..
+            }) if max == max_invocations
+        ));
+    }
+
+    /*
+        OpCapability Shader
+        OpMemoryModel Logical GLSL450
+        OpEntryPoint GLCompute %main "main"
+        OpExecutionMode %main LocalSize 1 1 1
+        OpDecorate %x SpecId 0
+        OpDecorate %size BuiltIn WorkgroupSize
+        %void = OpTypeVoid
+        %fn = OpTypeFunction %void
+        %uint = OpTypeInt 32 0
+        %x = OpSpecConstant %uint 1
+        %one = OpConstant %uint 1
+        %uvec3 = OpTypeVector %uint 3
+        %size = OpSpecConstantComposite %uvec3 %x %one %one
+        %main = OpFunction %void None %fn
+        %label = OpLabel
+        OpReturn
+        OpFunctionEnd
+    */
+    const SPEC_LOCAL_SIZE_MODULE: [u32; 65] = [
+        0x07230203, 0x00010000, 0, 10, 0, 0x00020011, 1, 0x0003000e, 0, 1, 0x0005000f, 5, 1,
+        0x6e69616d, 0, 0x00060010, 1, 17, 1, 1, 1, 0x00040047, 6, 1, 0, 0x00040047, 9, 11, 25,
+        0x00020013, 2, 0x00030021, 3, 2, 0x00040015, 5, 32, 0, 0x00040032, 5, 6, 1, 0x0004002b, 5,
+        7, 1, 0x00040017, 8, 5, 3, 0x00060033, 8, 9, 6, 7, 7, 0x00050036, 2, 1, 0, 3, 0x000200f8,
+        4, 0x000100fd, 0x00010038,
+    ];
+
+    #[derive(Debug, Copy, Clone)]
+    #[repr(C)]
+    struct LocalSizeX {
+        x: u32,
+    }
+    unsafe impl SpecializationConstants for LocalSizeX {
+        fn descriptors() -> &'static [SpecializationMapEntry] {
+            static DESCRIPTORS: [SpecializationMapEntry; 1] = [SpecializationMapEntry {
+                constant_id: 0,
+                offset: 0,
+                size: 4,
+            }];
+            &DESCRIPTORS
+        }
+    }
+
+    #[test]
+    fn spec_constant_local_size() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let module =
+            unsafe { ShaderModule::from_words(device.clone(), &SPEC_LOCAL_SIZE_MODULE).unwrap() };
+        let shader = module.entry_point("main").unwrap();
+        assert_eq!(
+            shader.local_size(),
+            Some([
+                LocalSizeValue::SpecializationConstant {
+                    constant_id: 0,
+                    default_value: 1,
+                },
+                LocalSizeValue::Literal(1),
+                LocalSizeValue::Literal(1),
+            ]),
+        );
+
+        // The specialized size is used, not the default one.
+        let pipeline = ComputePipeline::new(
+            device.clone(),
+            shader.clone(),
+            &LocalSizeX { x: 2 },
+            None,
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(pipeline.local_size(), [2, 1, 1]);
+
+        let max = device
+            .physical_device()
+            .properties()
+            .max_compute_work_group_size;
+
+        assert!(matches!(
+            ComputePipeline::new(device, shader, &LocalSizeX { x: max[0] + 1 }, None, |_| {}),
+            Err(ComputePipelineCreationError::MaxComputeWorkGroupSizeExceeded {
+                local_size,
+                ..
+            }) if local_size == [max[0] + 1, 1, 1]
+        ));
+    }
+
+    #[test]
+    fn push_constants_typed() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let words: Vec<u32> = SPEC_CONSTANT_MODULE
+            .chunks_exact(4)
+            .map(|word| u32::from_le_bytes(word.try_into().unwrap()))
+            .collect();
+        let spirv = Spirv::new(&words).unwrap();
+
+        let compute = ShaderStages {
+            compute: true,
+            ..ShaderStages::empty()
+        };
+
+        // Simulate a shader with a push constant block of two `uint` members.
+        let entry_points =
+            reflect::entry_points(&spirv).map(|(name, execution_model, mut info)| {
+                info.push_constant_requirements = Some(PushConstantRange {
+                    stages: compute,
+                    offset: 0,
+                    size: 8,
+                });
+                info.push_constant_members = vec![
+                    PushConstantMember {
+                        name: Some("a".to_owned()),
+                        offset: 0,
+                        size: 4,
+                    },
+                    PushConstantMember {
+                        name: Some("b".to_owned()),
+                        offset: 4,
+                        size: 4,
+                    },
+                ];
+                (name, execution_model, info)
+            });
+        let module = unsafe {
+            ShaderModule::from_words_with_data(
+                device.clone(),
+                &words,
+                spirv.version(),
+                reflect::spirv_capabilities(&spirv),
+                reflect::spirv_extensions(&spirv),
+                entry_points,
+            )
+            .unwrap()
+        };
+        let shader = module.entry_point("main").unwrap();
+
+        let layout_with_range = |stages, size| {
+            PipelineLayout::new(
+                device.clone(),
+                PipelineLayoutCreateInfo {
+                    push_constant_ranges: vec![PushConstantRange {
+                        stages,
+                        offset: 0,
+                        size,
+                    }],
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        };
+
+        let layout = layout_with_range(compute, 8);
+        assert_eq!(
+            shader.push_constants_typed::<[u32; 2]>(&layout),
+            Ok(PushConstantRange {
+                stages: compute,
+                offset: 0,
+                size: 8,
+            }),
+        );
+        assert!(matches!(
+            shader.push_constants_typed::<[u32; 1]>(&layout),
+            Err(PushConstantsTypeError::MembersNotCovered { provided: 4, .. })
+        ));
+        assert_eq!(
+            shader.push_constants_typed::<[u32; 3]>(&layout),
+            Err(PushConstantsTypeError::TypeTooLarge {
+                provided: 12,
+                required: 8,
+            }),
+        );
+
+        let layout = layout_with_range(compute, 4);
+        assert_eq!(
+            shader.push_constants_typed::<[u32; 2]>(&layout),
+            Err(PushConstantsTypeError::NotInLayout { offset: 4 }),
+        );
+
+        let layout = layout_with_range(
+            ShaderStages {
+                vertex: true,
+                ..ShaderStages::empty()
+            },
+            8,
+        );
+        assert_eq!(
+            shader.push_constants_typed::<[u32; 2]>(&layout),
+            Err(PushConstantsTypeError::StagesNotInLayout {
+                offset: 0,
+                stages: compute,
+            }),
+        );
+    }
+
+    fn create_with_flags(
+        device: Arc<Device>,
+        flags: PipelineCreateFlags,
+        base_pipeline: Option<Arc<ComputePipeline>>,
+    ) -> Result<Arc<ComputePipeline>, ComputePipelineCreationError> {
+        let module =
+            unsafe { ShaderModule::from_bytes(device.clone(), &SPEC_CONSTANT_MODULE).unwrap() };
+        let shader = module.entry_point("main").unwrap();
+        let layout = ComputePipeline::new(
+            device.clone(),
+            shader.clone(),
+            &SpecConsts { VALUE: 0 },
+            None,
+            |_| {},
+        )
+        .unwrap()
+        .layout()
+        .clone();
+
+        ComputePipeline::with_pipeline_layout_and_flags(
+            device,
+            shader,
+            &SpecConsts { VALUE: 0 },
+            layout,
+            None,
+            flags,
+            base_pipeline,
+        )
+    }
+
+    #[test]
+    fn stage_options_feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let module =
+            unsafe { ShaderModule::from_bytes(device.clone(), &SPEC_CONSTANT_MODULE).unwrap() };
+        let shader = module.entry_point("main").unwrap();
+        let layout = ComputePipeline::new(
+            device.clone(),
+            shader.clone(),
+            &SpecConsts { VALUE: 0 },
+            None,
+            |_| {},
+        )
+        .unwrap()
+        .layout()
+        .clone();
+
+        assert!(matches!(
+            ComputePipeline::with_pipeline_layout_flags_and_stage_options(
+                device,
+                shader,
+                &SpecConsts { VALUE: 0 },
+                layout,
+                None,
+                PipelineCreateFlags::empty(),
+                None,
+                ShaderStageOptions {
+                    required_subgroup_size: Some(1),
+                    ..Default::default()
+                },
+            ),
+            Err(ComputePipelineCreationError::InvalidShaderStageOptions(
+                ShaderStageOptionsError::RequirementNotMet { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn cooperative_matrix_stage() {
+        let instance = instance!();
+
+        let enabled_extensions = DeviceExtensions {
+            nv_cooperative_matrix: true,
+            ..DeviceExtensions::empty()
+        };
+        let enabled_features = Features {
+            cooperative_matrix: true,
+            ..Features::empty()
+        };
+        let physical_device = match instance.enumerate_physical_devices().unwrap().find(|p| {
+            p.supported_extensions().contains(&enabled_extensions)
+                && p.supported_features().contains(&enabled_features)
+        }) {
+            Some(x) => x,
+            None => return, // test ignored
+        };
+        let (device, _) = Device::new(
+            physical_device.clone(),
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index: 0,
+                    ..Default::default()
+                }],
+                enabled_extensions,
+                enabled_features,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        /*
+            OpCapability Shader
+            OpCapability CooperativeMatrixNV
+            OpExtension "SPV_NV_cooperative_matrix"
+            OpMemoryModel Logical GLSL450
+            OpEntryPoint GLCompute %main "main"
+            OpExecutionMode %main LocalSize 1 1 1
+            %void = OpTypeVoid
+            %fn = OpTypeFunction %void
+            %main = OpFunction %void None %fn
+            %label = OpLabel
+            OpReturn
+            OpFunctionEnd
+        */
+        const MODULE: [u32; 45] = [
+            0x07230203, 0x00010000, 0, 5, 0, 0x00020011, 1, 0x00020011, 5357, 0x0008000a,
+            0x5f565053, 0x635f564e, 0x65706f6f, 0x69746172, 0x6d5f6576, 0x69727461, 0x00000078,
+            0x0003000e, 0, 1, 0x0005000f, 5, 1, 0x6e69616d, 0, 0x00060010, 1, 17, 1, 1, 1,
+            0x00020013, 2, 0x00030021, 3, 2, 0x00050036, 2, 1, 0, 3, 0x000200f8, 4, 0x000100fd,
+            0x00010038,
+        ];
+        let module = unsafe { ShaderModule::from_words(device.clone(), &MODULE).unwrap() };
+
+        let result = ComputePipeline::new(
+            device,
+            module.entry_point("main").unwrap(),
+            &(),
+            None,
+            |_| {},
+        );
+
+        if physical_device
+            .properties()
+            .cooperative_matrix_supported_stages
+            .map_or(false, |stages| stages.compute)
+        {
+            assert!(result.is_ok());
+        } else {
+            assert!(matches!(
+                result,
+                Err(ComputePipelineCreationError::CooperativeMatrixStageNotSupported)
+            ));
+        }
+    }
+
+    #[test]
+    fn derivative() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let base = create_with_flags(
+            device.clone(),
+            PipelineCreateFlags {
+                allow_derivatives: true,
+                ..PipelineCreateFlags::empty()
+            },
+            None,
+        )
+        .unwrap();
+        assert!(base.flags().allow_derivatives);
+        assert!(!base.flags().derivative);
+
+        let derived = create_with_flags(device, PipelineCreateFlags::empty(), Some(base)).unwrap();
+        assert!(derived.flags().derivative);
+    }
+
+    #[test]
+    fn derivative_not_allowed() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let base = create_with_flags(device.clone(), PipelineCreateFlags::empty(), None).unwrap();
+
+        assert!(matches!(
+            create_with_flags(device, PipelineCreateFlags::empty(), Some(base)),
+            Err(ComputePipelineCreationError::BasePipelineDerivativesNotAllowed)
+        ));
+    }
+
+    #[test]
+    fn fail_on_compile_required_without_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        assert!(matches!(
+            create_with_flags(
+                device,
+                PipelineCreateFlags {
+                    fail_on_pipeline_compile_required: true,
+                    ..PipelineCreateFlags::empty()
+                },
+                None,
+            ),
+            Err(ComputePipelineCreationError::RequirementNotMet { .. })
+        ));
+    }
 }
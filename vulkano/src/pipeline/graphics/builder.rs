@@ -17,10 +17,14 @@ use super::{
     },
     depth_stencil::{DepthStencilState, StencilOps},
     discard_rectangle::DiscardRectangleState,
+    fragment_shading_rate::{
+        FragmentShadingRate, FragmentShadingRateCombinerOp, FragmentShadingRateState,
+    },
     input_assembly::{InputAssemblyState, PrimitiveTopology, PrimitiveTopologyClass},
-    multisample::MultisampleState,
+    multisample::{MultisampleState, SampleLocations},
     rasterization::{
-        CullMode, DepthBiasState, FrontFace, LineRasterizationMode, PolygonMode, RasterizationState,
+        CullMode, DepthBiasState, FrontFace, LineRasterizationMode, PolygonMode,
+        ProvokingVertexMode, RasterizationState,
     },
     render_pass::{PipelineRenderPassType, PipelineRenderingCreateInfo},
     tessellation::TessellationState,
@@ -43,11 +47,12 @@ use crate::{
             vertex_input::VertexInputRate,
         },
         layout::{PipelineLayoutCreateInfo, PushConstantRange},
-        DynamicState, PartialStateMode, PipelineLayout, StateMode,
+        shader_stage::ShaderStageOptions,
+        DynamicState, PartialStateMode, PipelineCreateFlags, PipelineLayout, StateMode,
     },
     shader::{
-        DescriptorRequirements, EntryPoint, ShaderExecution, ShaderStage, SpecializationConstants,
-        SpecializationMapEntry,
+        DescriptorBindingRequirements, EntryPoint, ShaderExecution, ShaderStage,
+        SpecializationConstants, SpecializationMapEntry,
     },
     DeviceSize, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
@@ -55,6 +60,7 @@ use ahash::HashMap;
 use smallvec::SmallVec;
 use std::{
     collections::hash_map::Entry,
+    ffi::c_void,
     mem::{size_of_val, MaybeUninit},
     ptr, slice,
     sync::Arc,
@@ -65,6 +71,9 @@ use std::{
 pub struct GraphicsPipelineBuilder<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss> {
     render_pass: Option<PipelineRenderPassType>,
     cache: Option<Arc<PipelineCache>>,
+    flags: PipelineCreateFlags,
+    base_pipeline: Option<Arc<GraphicsPipeline>>,
+    stage_options: HashMap<ShaderStage, ShaderStageOptions>,
 
     vertex_shader: Option<(EntryPoint<'vs>, Vss)>,
     tessellation_shaders: Option<TessellationShaders<'tcs, 'tes, Tcss, Tess>>,
@@ -76,8 +85,10 @@ pub struct GraphicsPipelineBuilder<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, T
     tessellation_state: TessellationState,
     viewport_state: ViewportState,
     discard_rectangle_state: DiscardRectangleState,
+    fragment_shading_rate_state: FragmentShadingRateState,
     rasterization_state: RasterizationState,
     multisample_state: MultisampleState,
+    sample_locations: Option<StateMode<SampleLocations>>,
     depth_stencil_state: DepthStencilState,
     color_blend_state: ColorBlendState,
 }
@@ -109,6 +120,9 @@ impl
         GraphicsPipelineBuilder {
             render_pass: None,
             cache: None,
+            flags: PipelineCreateFlags::empty(),
+            base_pipeline: None,
+            stage_options: HashMap::default(),
 
             vertex_shader: None,
             tessellation_shaders: None,
@@ -120,8 +134,10 @@ impl
             tessellation_state: Default::default(),
             viewport_state: Default::default(),
             discard_rectangle_state: Default::default(),
+            fragment_shading_rate_state: Default::default(),
             rasterization_state: Default::default(),
             multisample_state: Default::default(),
+            sample_locations: None,
             depth_stencil_state: Default::default(),
             color_blend_state: Default::default(),
         }
@@ -182,9 +198,9 @@ where
             .flatten()
             .collect();
 
-            // Produce `DescriptorRequirements` for each binding, by iterating over all shaders
+            // Produce `DescriptorBindingRequirements` for each binding, by iterating over all shaders
             // and adding the requirements of each.
-            let mut descriptor_requirements: HashMap<(u32, u32), DescriptorRequirements> =
+            let mut descriptor_requirements: HashMap<(u32, u32), DescriptorBindingRequirements> =
                 HashMap::default();
 
             for (loc, reqs) in stages
@@ -298,6 +314,9 @@ where
             let Self {
                 render_pass,
                 cache: _,
+                flags: _,
+                base_pipeline: _,
+                stage_options: _,
 
                 vertex_shader,
                 tessellation_shaders,
@@ -309,8 +328,10 @@ where
                 tessellation_state: _,
                 viewport_state: _,
                 discard_rectangle_state: _,
+                fragment_shading_rate_state: _,
                 rasterization_state,
                 multisample_state: _,
+                sample_locations: _,
                 depth_stencil_state: _,
                 color_blend_state: _,
             } = &self;
@@ -369,6 +390,9 @@ where
         let Self {
             mut render_pass,
             cache: _,
+            flags,
+            base_pipeline: _,
+            stage_options: _,
             vertex_shader: _,
             tessellation_shaders: _,
             geometry_shader: _,
@@ -378,8 +402,10 @@ where
             tessellation_state,
             viewport_state,
             discard_rectangle_state,
+            fragment_shading_rate_state,
             rasterization_state,
             multisample_state,
+            sample_locations,
             depth_stencil_state,
             color_blend_state,
         } = self;
@@ -395,6 +421,7 @@ where
             handle,
             device,
             layout: pipeline_layout,
+            flags,
             render_pass: render_pass.take().expect("Missing render pass"),
             shaders,
             descriptor_requirements,
@@ -407,8 +434,16 @@ where
             discard_rectangle_state: has
                 .pre_rasterization_shader_state
                 .then_some(discard_rectangle_state),
+            fragment_shading_rate_state: has
+                .fragment_shader_state
+                .then_some(fragment_shading_rate_state),
             rasterization_state,
             multisample_state: has.fragment_output_state.then_some(multisample_state),
+            sample_locations: if has.fragment_output_state {
+                sample_locations
+            } else {
+                None
+            },
             depth_stencil_state: has.depth_stencil_state.then_some(depth_stencil_state),
             color_blend_state: has.color_blend_state.then_some(color_blend_state),
             dynamic_state,
@@ -428,6 +463,9 @@ where
         let Self {
             render_pass,
             cache: _,
+            flags,
+            base_pipeline,
+            stage_options,
 
             vertex_shader,
             tessellation_shaders,
@@ -439,8 +477,10 @@ where
             tessellation_state,
             viewport_state,
             discard_rectangle_state,
+            fragment_shading_rate_state,
             rasterization_state,
             multisample_state,
+            sample_locations,
             depth_stencil_state,
             color_blend_state,
         } = self;
@@ -449,6 +489,56 @@ where
 
         let mut shader_stages: SmallVec<[_; 5]> = SmallVec::new();
 
+        /*
+            Flags
+        */
+
+        // VUID-VkGraphicsPipelineCreateInfo-flags-parameter
+        flags.validate_device(device)?;
+
+        // VUID-VkGraphicsPipelineCreateInfo-pipelineCreationCacheControl-02878
+        if (flags.fail_on_pipeline_compile_required || flags.early_return_on_failure)
+            && !device.enabled_features().pipeline_creation_cache_control
+        {
+            return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                required_for: "`flags.fail_on_pipeline_compile_required` or `flags.early_return_on_failure` is set",
+                requires_one_of: RequiresOneOf {
+                    features: &["pipeline_creation_cache_control"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if let Some(base_pipeline) = base_pipeline {
+            // VUID-VkGraphicsPipelineCreateInfo-commonparent
+            assert_eq!(device, base_pipeline.device().as_ref());
+
+            // VUID-vkCreateGraphicsPipelines-flags-00721
+            if !base_pipeline.flags().allow_derivatives {
+                return Err(GraphicsPipelineCreationError::BasePipelineDerivativesNotAllowed);
+            }
+        }
+
+        for (&stage, options) in stage_options {
+            let has_stage = match stage {
+                ShaderStage::Vertex => vertex_shader.is_some(),
+                ShaderStage::TessellationControl | ShaderStage::TessellationEvaluation => {
+                    tessellation_shaders.is_some()
+                }
+                ShaderStage::Geometry => geometry_shader.is_some(),
+                ShaderStage::Fragment => has.fragment_shader_state && fragment_shader.is_some(),
+                _ => false,
+            };
+
+            if !has_stage {
+                return Err(
+                    GraphicsPipelineCreationError::ShaderStageOptionsStageNotPresent { stage },
+                );
+            }
+
+            options.validate(device, stage, None)?;
+        }
+
         // VUID-VkGraphicsPipelineCreateInfo-layout-01688
         // Checked at pipeline layout creation time.
 
@@ -639,6 +729,21 @@ where
                         );
                     }
 
+                    // VUID-VkVertexInputBindingDescription-stride-04456
+                    if device.enabled_extensions().khr_portability_subset {
+                        let alignment = properties
+                            .min_vertex_input_binding_stride_alignment
+                            .unwrap();
+
+                        if stride % alignment != 0 {
+                            return Err(GraphicsPipelineCreationError::MinVertexInputBindingStrideAlignmentNotMet {
+                                binding,
+                                alignment,
+                                obtained: stride,
+                            });
+                        }
+                    }
+
                     match input_rate {
                         VertexInputRate::Instance { divisor } if divisor != 1 => {
                             // VUID-VkVertexInputBindingDivisorDescriptionEXT-vertexAttributeInstanceRateDivisor-02229
@@ -696,7 +801,8 @@ where
                 }
 
                 // VUID-VkPipelineVertexInputStateCreateInfo-pVertexAttributeDescriptions-00617
-                // Ensured by HashMap.
+                // Ensured by HashMap, except for formats that take up two locations, which are
+                // checked below.
 
                 for (&location, attribute_desc) in attributes {
                     let &VertexInputAttributeDescription {
@@ -708,6 +814,17 @@ where
                     // VUID-VkVertexInputAttributeDescription-format-parameter
                     format.validate_device(device)?;
 
+                    // VUID-VkPipelineVertexInputStateCreateInfo-pVertexAttributeDescriptions-00617
+                    // A 64-bit format with more than two components takes up two locations, so
+                    // the next location must not have an attribute of its own.
+                    if format.block_size().unwrap() > 16 && attributes.contains_key(&(location + 1))
+                    {
+                        return Err(GraphicsPipelineCreationError::VertexInputAttributeOverlap {
+                            location,
+                            overlapping_location: location + 1,
+                        });
+                    }
+
                     // TODO:
                     // VUID-VkVertexInputAttributeDescription-location-00620
 
@@ -748,6 +865,23 @@ where
                             },
                         );
                     }
+
+                    // VUID-VkVertexInputAttributeDescription-vertexAttributeAccessBeyondStride-04457
+                    if device.enabled_extensions().khr_portability_subset
+                        && !device
+                            .enabled_features()
+                            .vertex_attribute_access_beyond_stride
+                        && offset as DeviceSize + format.block_size().unwrap()
+                            > bindings[&binding].stride as DeviceSize
+                    {
+                        return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                            required_for: "this device is a portability subset device, and `vertex_input_state.attributes` has an element that extends beyond the `stride` of its binding",
+                            requires_one_of: RequiresOneOf {
+                                features: &["vertex_attribute_access_beyond_stride"],
+                                ..Default::default()
+                            },
+                        });
+                    }
                 }
             }
 
@@ -795,6 +929,20 @@ where
                                 // TODO:
                                 // VUID-VkGraphicsPipelineCreateInfo-topology-00737
                             }
+                            PrimitiveTopology::TriangleFan => {
+                                // VUID-VkPipelineInputAssemblyStateCreateInfo-triangleFans-04452
+                                if device.enabled_extensions().khr_portability_subset
+                                    && !device.enabled_features().triangle_fans
+                                {
+                                    return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                                        required_for: "this device is a portability subset device, and `input_assembly_state.topology` is `StateMode::Fixed(PrimitiveTopology::TriangleFan)`",
+                                        requires_one_of: RequiresOneOf {
+                                            features: &["triangle_fans"],
+                                            ..Default::default()
+                                        },
+                                    });
+                                }
+                            }
                             _ => (),
                         }
                     }
@@ -906,11 +1054,12 @@ where
                 // Check that the vertex input state contains attributes for all the shader's input
                 // variables.
                 for element in entry_point.input_interface().elements() {
-                    assert!(!element.ty.is_64bit); // TODO: implement
-                    let location_range =
-                        element.location..element.location + element.ty.num_locations();
+                    for element_index in 0..element.ty.num_elements {
+                        // A 64-bit vector with more than two components takes up two locations,
+                        // but is read from a single attribute at the first one.
+                        let location = element.location
+                            + element_index * element.ty.num_locations_per_element();
 
-                    for location in location_range {
                         let attribute_desc =
                             match vertex_input_state.attributes.get(&location) {
                                 Some(attribute_desc) => attribute_desc,
@@ -955,6 +1104,21 @@ where
                                 },
                             );
                         }
+
+                        // VUID?
+                        // 64-bit shader inputs must be read from 64-bit attribute formats, and
+                        // vice versa.
+                        let attribute_is_64bit = attribute_desc.format.components()[0] == 64;
+
+                        if element.ty.is_64bit != attribute_is_64bit {
+                            return Err(
+                                GraphicsPipelineCreationError::VertexInputAttributeIncompatibleBitWidth {
+                                    location,
+                                    shader_is_64bit: element.ty.is_64bit,
+                                    attribute_is_64bit,
+                                },
+                            );
+                        }
                     }
                 }
 
@@ -1127,6 +1291,8 @@ where
             {
                 let &RasterizationState {
                     depth_clamp_enable,
+                    depth_clip_enable,
+                    depth_clip_negative_one_to_one,
                     rasterizer_discard_enable,
                     polygon_mode,
                     cull_mode,
@@ -1135,17 +1301,76 @@ where
                     line_width,
                     line_rasterization_mode,
                     line_stipple,
+                    provoking_vertex_mode,
                 } = rasterization_state;
 
-                // VUID-VkPipelineRasterizationStateCreateInfo-polygonMode-parameter
-                polygon_mode.validate_device(device)?;
+                match depth_clamp_enable {
+                    StateMode::Fixed(false) => (),
+                    StateMode::Fixed(true) => {
+                        // VUID-VkPipelineRasterizationStateCreateInfo-depthClampEnable-00782
+                        if !device.enabled_features().depth_clamp {
+                            return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                                required_for: "`rasterization_state.depth_clamp_enable` is \
+                                    `StateMode::Fixed(true)`",
+                                requires_one_of: RequiresOneOf {
+                                    features: &["depth_clamp"],
+                                    ..Default::default()
+                                },
+                            });
+                        }
+                    }
+                    StateMode::Dynamic => {
+                        // VUID-VkGraphicsPipelineCreateInfo-extendedDynamicState3DepthClampEnable-07448
+                        if !device
+                            .enabled_features()
+                            .extended_dynamic_state3_depth_clamp_enable
+                        {
+                            return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                                required_for: "`rasterization_state.depth_clamp_enable` is \
+                                    `StateMode::Dynamic`",
+                                requires_one_of: RequiresOneOf {
+                                    features: &["extended_dynamic_state3_depth_clamp_enable"],
+                                    ..Default::default()
+                                },
+                            });
+                        }
+                    }
+                }
+
+                // VUID-VkPipelineRasterizationProvokingVertexStateCreateInfoEXT-provokingVertexMode-parameter
+                provoking_vertex_mode.validate_device(device)?;
+
+                // VUID-VkPipelineRasterizationProvokingVertexStateCreateInfoEXT-provokingVertexMode-04883
+                if provoking_vertex_mode == ProvokingVertexMode::LastVertex
+                    && !device.enabled_features().provoking_vertex_last
+                {
+                    return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                        required_for: "`rasterization_state.provoking_vertex_mode` is \
+                            `ProvokingVertexMode::LastVertex`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["provoking_vertex_last"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkPipelineRasterizationDepthClipStateCreateInfoEXT-depthClipEnable-02506
+                if depth_clip_enable.is_some() && !device.enabled_features().depth_clip_enable {
+                    return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                        required_for: "`rasterization_state.depth_clip_enable` is `Some`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["depth_clip_enable"],
+                            ..Default::default()
+                        },
+                    });
+                }
 
-                // VUID-VkPipelineRasterizationStateCreateInfo-depthClampEnable-00782
-                if depth_clamp_enable && !device.enabled_features().depth_clamp {
+                // VUID-VkPipelineViewportDepthClipControlCreateInfoEXT-negativeOneToOne-06470
+                if depth_clip_negative_one_to_one && !device.enabled_features().depth_clip_control {
                     return Err(GraphicsPipelineCreationError::RequirementNotMet {
-                        required_for: "`rasterization_state.depth_clamp_enable` is set",
+                        required_for: "`rasterization_state.depth_clip_negative_one_to_one` is set",
                         requires_one_of: RequiresOneOf {
-                            features: &["depth_clamp"],
+                            features: &["depth_clip_control"],
                             ..Default::default()
                         },
                     });
@@ -1166,18 +1391,56 @@ where
                     });
                 }
 
-                // VUID-VkPipelineRasterizationStateCreateInfo-polygonMode-01507
-                if polygon_mode != PolygonMode::Fill
-                    && !device.enabled_features().fill_mode_non_solid
-                {
-                    return Err(GraphicsPipelineCreationError::RequirementNotMet {
-                        required_for:
-                            "`rasterization_state.polygon_mode` is not `PolygonMode::Fill`",
-                        requires_one_of: RequiresOneOf {
-                            features: &["fill_mode_non_solid"],
-                            ..Default::default()
-                        },
-                    });
+                match polygon_mode {
+                    StateMode::Fixed(polygon_mode) => {
+                        // VUID-VkPipelineRasterizationStateCreateInfo-polygonMode-parameter
+                        polygon_mode.validate_device(device)?;
+
+                        // VUID-VkPipelineRasterizationStateCreateInfo-polygonMode-01507
+                        if polygon_mode != PolygonMode::Fill
+                            && !device.enabled_features().fill_mode_non_solid
+                        {
+                            return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                                required_for:
+                                    "`rasterization_state.polygon_mode` is not `PolygonMode::Fill`",
+                                requires_one_of: RequiresOneOf {
+                                    features: &["fill_mode_non_solid"],
+                                    ..Default::default()
+                                },
+                            });
+                        }
+
+                        // VUID-VkPipelineRasterizationStateCreateInfo-pointPolygons-04458
+                        if polygon_mode == PolygonMode::Point
+                            && rasterizer_discard_enable != StateMode::Fixed(true)
+                            && device.enabled_extensions().khr_portability_subset
+                            && !device.enabled_features().point_polygons
+                        {
+                            return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                                required_for: "this device is a portability subset device, and `rasterization_state.polygon_mode` is `PolygonMode::Point`",
+                                requires_one_of: RequiresOneOf {
+                                    features: &["point_polygons"],
+                                    ..Default::default()
+                                },
+                            });
+                        }
+                    }
+                    StateMode::Dynamic => {
+                        // VUID-VkGraphicsPipelineCreateInfo-extendedDynamicState3PolygonMode-07451
+                        if !device
+                            .enabled_features()
+                            .extended_dynamic_state3_polygon_mode
+                        {
+                            return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                                required_for: "`rasterization_state.polygon_mode` is \
+                                    `StateMode::Dynamic`",
+                                requires_one_of: RequiresOneOf {
+                                    features: &["extended_dynamic_state3_polygon_mode"],
+                                    ..Default::default()
+                                },
+                            });
+                        }
+                    }
                 }
 
                 match cull_mode {
@@ -1379,8 +1642,13 @@ where
 
                         if let StateMode::Fixed(line_stipple) = line_stipple {
                             // VUID-VkGraphicsPipelineCreateInfo-stippledLineEnable-02767
-                            assert!(line_stipple.factor >= 1 && line_stipple.factor <= 256);
-                            // TODO: return error?
+                            if !(1..=256).contains(&line_stipple.factor) {
+                                return Err(
+                                    GraphicsPipelineCreationError::LineStippleFactorOutOfRange {
+                                        factor: line_stipple.factor,
+                                    },
+                                );
+                            }
                         }
                     }
                 } else {
@@ -2009,6 +2277,97 @@ where
                 // TODO:
                 // VUID-VkGraphicsPipelineCreateInfo-renderPass-06040
             }
+
+            // Fragment shading rate state
+            if device.enabled_extensions().khr_fragment_shading_rate {
+                if let StateMode::Fixed(FragmentShadingRate {
+                    fragment_size,
+                    combiner_ops,
+                }) = fragment_shading_rate_state.rate
+                {
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04494
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04495
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04496
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04497
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04498
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04499
+                    if !fragment_size
+                        .into_iter()
+                        .all(|size| matches!(size, 1 | 2 | 4))
+                    {
+                        return Err(
+                            GraphicsPipelineCreationError::FragmentShadingRateInvalidSize {
+                                fragment_size,
+                            },
+                        );
+                    }
+
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04500
+                    if fragment_size != [1, 1]
+                        && !device.enabled_features().pipeline_fragment_shading_rate
+                    {
+                        return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                            required_for: "`fragment_shading_rate_state.rate` is `StateMode::Fixed`, where `fragment_size` is not `[1, 1]`",
+                            requires_one_of: RequiresOneOf {
+                                features: &["pipeline_fragment_shading_rate"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+
+                    for combiner_op in combiner_ops {
+                        // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-06567
+                        combiner_op.validate_device(device)?;
+
+                        // VUID-VkGraphicsPipelineCreateInfo-fragmentShadingRateNonTrivialCombinerOps-04506
+                        if !matches!(
+                            combiner_op,
+                            FragmentShadingRateCombinerOp::Keep
+                                | FragmentShadingRateCombinerOp::Replace
+                        ) && !properties
+                            .fragment_shading_rate_non_trivial_combiner_ops
+                            .unwrap_or(false)
+                        {
+                            return Err(GraphicsPipelineCreationError::FragmentShadingRateNonTrivialCombinerOpsNotSupported);
+                        }
+                    }
+
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04501
+                    if combiner_ops[0] != FragmentShadingRateCombinerOp::Keep
+                        && !device.enabled_features().primitive_fragment_shading_rate
+                    {
+                        return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                            required_for: "`fragment_shading_rate_state.rate` is `StateMode::Fixed`, where `combiner_ops[0]` is not `FragmentShadingRateCombinerOp::Keep`",
+                            requires_one_of: RequiresOneOf {
+                                features: &["primitive_fragment_shading_rate"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+
+                    // VUID-VkGraphicsPipelineCreateInfo-pDynamicState-04502
+                    if combiner_ops[1] != FragmentShadingRateCombinerOp::Keep
+                        && !device.enabled_features().attachment_fragment_shading_rate
+                    {
+                        return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                            required_for: "`fragment_shading_rate_state.rate` is `StateMode::Fixed`, where `combiner_ops[1]` is not `FragmentShadingRateCombinerOp::Keep`",
+                            requires_one_of: RequiresOneOf {
+                                features: &["attachment_fragment_shading_rate"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+                }
+            } else if !fragment_shading_rate_state.is_default() {
+                return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                    required_for:
+                        "`fragment_shading_rate_state` is not `FragmentShadingRateState::default()`",
+                    requires_one_of: RequiresOneOf {
+                        device_extensions: &["khr_fragment_shading_rate"],
+                        ..Default::default()
+                    },
+                });
+            }
         }
 
         /*
@@ -2064,8 +2423,9 @@ where
                     }
 
                     // VUID-VkPipelineMultisampleStateCreateInfo-minSampleShading-00786
-                    // TODO: return error?
-                    assert!((0.0..=1.0).contains(&min_sample_shading));
+                    if !(0.0..=1.0).contains(&min_sample_shading) {
+                        return Err(GraphicsPipelineCreationError::MinSampleShadingOutOfRange);
+                    }
                 }
 
                 // VUID-VkPipelineMultisampleStateCreateInfo-alphaToOneEnable-00785
@@ -2079,6 +2439,80 @@ where
                     });
                 }
 
+                if let Some(sample_locations) = sample_locations {
+                    if !device.enabled_extensions().ext_sample_locations {
+                        return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                            required_for: "`sample_locations` is `Some`",
+                            requires_one_of: RequiresOneOf {
+                                device_extensions: &["ext_sample_locations"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+
+                    if let StateMode::Fixed(sample_locations) = sample_locations {
+                        let &SampleLocations {
+                            per_pixel,
+                            grid_size,
+                            ref locations,
+                        } = sample_locations;
+
+                        // VUID-VkSampleLocationsInfoEXT-sampleLocationsPerPixel-parameter
+                        per_pixel.validate_device(device)?;
+
+                        // VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-01523
+                        if per_pixel != rasterization_samples {
+                            return Err(
+                                GraphicsPipelineCreationError::SampleLocationsPerPixelMismatch {
+                                    per_pixel,
+                                    rasterization_samples,
+                                },
+                            );
+                        }
+
+                        // VUID-VkSampleLocationsInfoEXT-sampleLocationsPerPixel-01526
+                        if !properties
+                            .sample_location_sample_counts
+                            .map_or(false, |counts| counts.contains_count(per_pixel))
+                        {
+                            return Err(
+                                GraphicsPipelineCreationError::SampleLocationsPerPixelNotSupported {
+                                    per_pixel,
+                                },
+                            );
+                        }
+
+                        // VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-01521
+                        // VUID-VkGraphicsPipelineCreateInfo-pDynamicStates-01522
+                        // Use unchecked, because all validation has been done above.
+                        let max_grid_size = unsafe {
+                            physical_device.multisample_properties_unchecked(rasterization_samples)
+                        }
+                        .max_sample_location_grid_size;
+
+                        if !sample_locations.grid_size_divides(max_grid_size) {
+                            return Err(
+                                GraphicsPipelineCreationError::SampleLocationsGridSizeNotSupported {
+                                    grid_size,
+                                    max_grid_size,
+                                },
+                            );
+                        }
+
+                        // VUID-VkSampleLocationsInfoEXT-sampleLocationsCount-01527
+                        let required = sample_locations.required_count();
+
+                        if required != Some(locations.len() as u32) {
+                            return Err(
+                                GraphicsPipelineCreationError::SampleLocationsCountMismatch {
+                                    provided: locations.len() as u32,
+                                    required,
+                                },
+                            );
+                        }
+                    }
+                }
+
                 // TODO:
                 // VUID-VkGraphicsPipelineCreateInfo-lineRasterizationMode-02766
             }
@@ -2225,6 +2659,28 @@ where
                         });
                     }
 
+                    // VUID-VkPipelineColorBlendAttachmentState-constantAlphaColorBlendFactors-04454
+                    // VUID-VkPipelineColorBlendAttachmentState-constantAlphaColorBlendFactors-04455
+                    if device.enabled_extensions().khr_portability_subset
+                        && !device.enabled_features().constant_alpha_color_blend_factors
+                        && [color_source, color_destination]
+                            .into_iter()
+                            .any(|blend_factor| {
+                                matches!(
+                                    blend_factor,
+                                    BlendFactor::ConstantAlpha | BlendFactor::OneMinusConstantAlpha
+                                )
+                            })
+                    {
+                        return Err(GraphicsPipelineCreationError::RequirementNotMet {
+                            required_for: "this device is a portability subset device, and `color_blend_state.attachments` has an element where `blend` is `Some(blend)`, where `blend.color_source` or `blend.color_destination` is `BlendFactor::*ConstantAlpha`",
+                            requires_one_of: RequiresOneOf {
+                                features: &["constant_alpha_color_blend_factors"],
+                                ..Default::default()
+                            },
+                        });
+                    }
+
                     let attachment_format = match render_pass {
                         PipelineRenderPassType::BeginRenderPass(subpass) => subpass
                             .subpass_desc()
@@ -2335,11 +2791,9 @@ where
 
         // Dynamic states not handled yet:
         // - ViewportWScaling (VkPipelineViewportWScalingStateCreateInfoNV)
-        // - SampleLocations (VkPipelineSampleLocationsStateCreateInfoEXT)
         // - ViewportShadingRatePalette (VkPipelineViewportShadingRateImageStateCreateInfoNV)
         // - ViewportCoarseSampleOrder (VkPipelineViewportCoarseSampleOrderStateCreateInfoNV)
         // - ExclusiveScissor (VkPipelineViewportExclusiveScissorStateCreateInfoNV)
-        // - FragmentShadingRate (VkPipelineFragmentShadingRateStateCreateInfoKHR)
 
         Ok(())
     }
@@ -2353,7 +2807,7 @@ where
     ) -> Result<
         (
             ash::vk::Pipeline,
-            HashMap<(u32, u32), DescriptorRequirements>,
+            HashMap<(u32, u32), DescriptorBindingRequirements>,
             HashMap<DynamicState, bool>,
             HashMap<ShaderStage, ()>,
         ),
@@ -2362,6 +2816,9 @@ where
         let Self {
             render_pass,
             cache,
+            flags,
+            base_pipeline,
+            stage_options,
 
             vertex_shader,
             tessellation_shaders,
@@ -2373,19 +2830,41 @@ where
             tessellation_state,
             viewport_state,
             discard_rectangle_state,
+            fragment_shading_rate_state,
             rasterization_state,
             multisample_state,
+            sample_locations,
             depth_stencil_state,
             color_blend_state,
         } = self;
 
         let render_pass = render_pass.as_ref().unwrap();
 
-        let mut descriptor_requirements: HashMap<(u32, u32), DescriptorRequirements> =
+        let mut descriptor_requirements: HashMap<(u32, u32), DescriptorBindingRequirements> =
             HashMap::default();
         let mut dynamic_state: HashMap<DynamicState, bool> = HashMap::default();
         let mut stages = HashMap::default();
         let mut stages_vk: SmallVec<[_; 5]> = SmallVec::new();
+        let required_subgroup_sizes_vk: HashMap<ShaderStage, _> = stage_options
+            .iter()
+            .filter_map(|(&stage, options)| {
+                options
+                    .required_subgroup_size_vk()
+                    .map(|required_subgroup_size_vk| (stage, required_subgroup_size_vk))
+            })
+            .collect();
+        let stage_flags_vk = |stage: ShaderStage| -> ash::vk::PipelineShaderStageCreateFlags {
+            stage_options
+                .get(&stage)
+                .map_or_else(Default::default, |options| options.flags.into())
+        };
+        let stage_p_next_vk = |stage: ShaderStage| -> *const c_void {
+            required_subgroup_sizes_vk
+                .get(&stage)
+                .map_or(ptr::null(), |required_subgroup_size_vk| {
+                    required_subgroup_size_vk as *const _ as *const _
+                })
+        };
 
         /*
             Render pass
@@ -2565,6 +3044,9 @@ where
         let mut scissors_vk: SmallVec<[_; 2]> = SmallVec::new();
         let mut viewport_state_vk = None;
         let mut rasterization_line_state_vk = None;
+        let mut rasterization_depth_clip_state_vk = None;
+        let mut rasterization_provoking_vertex_state_vk = None;
+        let mut viewport_depth_clip_control_vk = None;
         let mut rasterization_state_vk = None;
         let mut discard_rectangles: SmallVec<[_; 2]> = SmallVec::new();
         let mut discard_rectangle_state_vk = None;
@@ -2600,7 +3082,8 @@ where
 
                 stages.insert(ShaderStage::Vertex, ());
                 stages_vk.push(ash::vk::PipelineShaderStageCreateInfo {
-                    flags: ash::vk::PipelineShaderStageCreateFlags::empty(),
+                    p_next: stage_p_next_vk(ShaderStage::Vertex),
+                    flags: stage_flags_vk(ShaderStage::Vertex),
                     stage: ash::vk::ShaderStageFlags::VERTEX,
                     module: entry_point.module().internal_object(),
                     p_name: entry_point.name().as_ptr(),
@@ -2641,7 +3124,8 @@ where
 
                     stages.insert(ShaderStage::TessellationControl, ());
                     stages_vk.push(ash::vk::PipelineShaderStageCreateInfo {
-                        flags: ash::vk::PipelineShaderStageCreateFlags::empty(),
+                        p_next: stage_p_next_vk(ShaderStage::TessellationControl),
+                        flags: stage_flags_vk(ShaderStage::TessellationControl),
                         stage: ash::vk::ShaderStageFlags::TESSELLATION_CONTROL,
                         module: entry_point.module().internal_object(),
                         p_name: entry_point.name().as_ptr(),
@@ -2680,7 +3164,8 @@ where
 
                     stages.insert(ShaderStage::TessellationEvaluation, ());
                     stages_vk.push(ash::vk::PipelineShaderStageCreateInfo {
-                        flags: ash::vk::PipelineShaderStageCreateFlags::empty(),
+                        p_next: stage_p_next_vk(ShaderStage::TessellationEvaluation),
+                        flags: stage_flags_vk(ShaderStage::TessellationEvaluation),
                         stage: ash::vk::ShaderStageFlags::TESSELLATION_EVALUATION,
                         module: entry_point.module().internal_object(),
                         p_name: entry_point.name().as_ptr(),
@@ -2720,7 +3205,8 @@ where
 
                 stages.insert(ShaderStage::Geometry, ());
                 stages_vk.push(ash::vk::PipelineShaderStageCreateInfo {
-                    flags: ash::vk::PipelineShaderStageCreateFlags::empty(),
+                    p_next: stage_p_next_vk(ShaderStage::Geometry),
+                    flags: stage_flags_vk(ShaderStage::Geometry),
                     stage: ash::vk::ShaderStageFlags::GEOMETRY,
                     module: entry_point.module().internal_object(),
                     p_name: entry_point.name().as_ptr(),
@@ -2733,6 +3219,8 @@ where
             {
                 let &RasterizationState {
                     depth_clamp_enable,
+                    depth_clip_enable,
+                    depth_clip_negative_one_to_one,
                     rasterizer_discard_enable,
                     polygon_mode,
                     cull_mode,
@@ -2741,8 +3229,20 @@ where
                     line_width,
                     line_rasterization_mode,
                     line_stipple,
+                    provoking_vertex_mode,
                 } = rasterization_state;
 
+                let depth_clamp_enable = match depth_clamp_enable {
+                    StateMode::Fixed(depth_clamp_enable) => {
+                        dynamic_state.insert(DynamicState::DepthClampEnable, false);
+                        depth_clamp_enable as ash::vk::Bool32
+                    }
+                    StateMode::Dynamic => {
+                        dynamic_state.insert(DynamicState::DepthClampEnable, true);
+                        ash::vk::FALSE
+                    }
+                };
+
                 let rasterizer_discard_enable = match rasterizer_discard_enable {
                     StateMode::Fixed(rasterizer_discard_enable) => {
                         dynamic_state.insert(DynamicState::RasterizerDiscardEnable, false);
@@ -2754,6 +3254,17 @@ where
                     }
                 };
 
+                let polygon_mode = match polygon_mode {
+                    StateMode::Fixed(polygon_mode) => {
+                        dynamic_state.insert(DynamicState::PolygonMode, false);
+                        polygon_mode.into()
+                    }
+                    StateMode::Dynamic => {
+                        dynamic_state.insert(DynamicState::PolygonMode, true);
+                        PolygonMode::default().into()
+                    }
+                };
+
                 let cull_mode = match cull_mode {
                     StateMode::Fixed(cull_mode) => {
                         dynamic_state.insert(DynamicState::CullMode, false);
@@ -2818,9 +3329,9 @@ where
                 let rasterization_state =
                     rasterization_state_vk.insert(ash::vk::PipelineRasterizationStateCreateInfo {
                         flags: ash::vk::PipelineRasterizationStateCreateFlags::empty(),
-                        depth_clamp_enable: depth_clamp_enable as ash::vk::Bool32,
+                        depth_clamp_enable,
                         rasterizer_discard_enable,
-                        polygon_mode: polygon_mode.into(),
+                        polygon_mode,
                         cull_mode,
                         front_face,
                         depth_bias_enable,
@@ -2850,7 +3361,7 @@ where
                             (ash::vk::FALSE, 1, 0)
                         };
 
-                    rasterization_state.p_next = rasterization_line_state_vk.insert(
+                    let next = rasterization_line_state_vk.insert(
                         ash::vk::PipelineRasterizationLineStateCreateInfoEXT {
                             line_rasterization_mode: line_rasterization_mode.into(),
                             stippled_line_enable,
@@ -2858,7 +3369,45 @@ where
                             line_stipple_pattern,
                             ..Default::default()
                         },
-                    ) as *const _ as *const _;
+                    );
+
+                    next.p_next = rasterization_state.p_next;
+                    rasterization_state.p_next = next as *const _ as *const _;
+                }
+
+                if let Some(depth_clip_enable) = depth_clip_enable {
+                    let next =
+                        rasterization_depth_clip_state_vk
+                            .insert(ash::vk::PipelineRasterizationDepthClipStateCreateInfoEXT {
+                            flags:
+                                ash::vk::PipelineRasterizationDepthClipStateCreateFlagsEXT::empty(),
+                            depth_clip_enable: depth_clip_enable as ash::vk::Bool32,
+                            ..Default::default()
+                        });
+
+                    next.p_next = rasterization_state.p_next;
+                    rasterization_state.p_next = next as *const _ as *const _;
+                }
+
+                if device.enabled_extensions().ext_provoking_vertex {
+                    let next = rasterization_provoking_vertex_state_vk.insert(
+                        ash::vk::PipelineRasterizationProvokingVertexStateCreateInfoEXT {
+                            provoking_vertex_mode: provoking_vertex_mode.into(),
+                            ..Default::default()
+                        },
+                    );
+
+                    next.p_next = rasterization_state.p_next;
+                    rasterization_state.p_next = next as *const _ as *const _;
+                }
+
+                if depth_clip_negative_one_to_one {
+                    let _ = viewport_depth_clip_control_vk.insert(
+                        ash::vk::PipelineViewportDepthClipControlCreateInfoEXT {
+                            negative_one_to_one: ash::vk::TRUE,
+                            ..Default::default()
+                        },
+                    );
                 }
             }
 
@@ -3023,12 +3572,21 @@ where
             });
         }
 
+        // Depth clip control is specified in the rasterization state, but is chained to the
+        // viewport state, so provide one even if the pipeline has no viewport state otherwise.
+        if let Some(depth_clip_control) = viewport_depth_clip_control_vk.as_mut() {
+            let viewport_state = viewport_state_vk.get_or_insert_with(Default::default);
+            depth_clip_control.p_next = viewport_state.p_next;
+            viewport_state.p_next = depth_clip_control as *const _ as *const _;
+        }
+
         /*
             Fragment shader state
         */
 
         let mut fragment_shader_specialization_vk = None;
         let mut depth_stencil_state_vk = None;
+        let mut fragment_shading_rate_state_vk = None;
 
         if has.fragment_shader_state {
             // Fragment shader
@@ -3061,7 +3619,8 @@ where
 
                 stages.insert(ShaderStage::Fragment, ());
                 stages_vk.push(ash::vk::PipelineShaderStageCreateInfo {
-                    flags: ash::vk::PipelineShaderStageCreateFlags::empty(),
+                    p_next: stage_p_next_vk(ShaderStage::Fragment),
+                    flags: stage_flags_vk(ShaderStage::Fragment),
                     stage: ash::vk::ShaderStageFlags::FRAGMENT,
                     module: entry_point.module().internal_object(),
                     p_name: entry_point.name().as_ptr(),
@@ -3256,6 +3815,34 @@ where
                 max_depth_bounds,
                 ..Default::default()
             });
+
+            // Fragment shading rate state
+            if device.enabled_extensions().khr_fragment_shading_rate {
+                let FragmentShadingRate {
+                    fragment_size,
+                    combiner_ops,
+                } = match fragment_shading_rate_state.rate {
+                    StateMode::Fixed(rate) => {
+                        dynamic_state.insert(DynamicState::FragmentShadingRate, false);
+                        rate
+                    }
+                    StateMode::Dynamic => {
+                        dynamic_state.insert(DynamicState::FragmentShadingRate, true);
+                        Default::default()
+                    }
+                };
+
+                let _ = fragment_shading_rate_state_vk.insert(
+                    ash::vk::PipelineFragmentShadingRateStateCreateInfoKHR {
+                        fragment_size: ash::vk::Extent2D {
+                            width: fragment_size[0],
+                            height: fragment_size[1],
+                        },
+                        combiner_ops: combiner_ops.map(Into::into),
+                        ..Default::default()
+                    },
+                );
+            }
         }
 
         /*
@@ -3263,6 +3850,8 @@ where
         */
 
         let mut multisample_state_vk = None;
+        let mut sample_locations_vk = Vec::new();
+        let mut sample_locations_state_vk = None;
         let mut color_blend_attachments_vk: SmallVec<[_; 4]> = SmallVec::new();
         let mut color_write_enables_vk: SmallVec<[_; 4]> = SmallVec::new();
         let mut color_write_vk = None;
@@ -3286,16 +3875,39 @@ where
                         (ash::vk::FALSE, 0.0)
                     };
 
-                let _ = multisample_state_vk.insert(ash::vk::PipelineMultisampleStateCreateInfo {
-                    flags: ash::vk::PipelineMultisampleStateCreateFlags::empty(),
-                    rasterization_samples: rasterization_samples.into(),
-                    sample_shading_enable,
-                    min_sample_shading,
-                    p_sample_mask: sample_mask as _,
-                    alpha_to_coverage_enable: alpha_to_coverage_enable as ash::vk::Bool32,
-                    alpha_to_one_enable: alpha_to_one_enable as ash::vk::Bool32,
-                    ..Default::default()
-                });
+                let multisample_state =
+                    multisample_state_vk.insert(ash::vk::PipelineMultisampleStateCreateInfo {
+                        flags: ash::vk::PipelineMultisampleStateCreateFlags::empty(),
+                        rasterization_samples: rasterization_samples.into(),
+                        sample_shading_enable,
+                        min_sample_shading,
+                        p_sample_mask: sample_mask as _,
+                        alpha_to_coverage_enable: alpha_to_coverage_enable as ash::vk::Bool32,
+                        alpha_to_one_enable: alpha_to_one_enable as ash::vk::Bool32,
+                        ..Default::default()
+                    });
+
+                if let Some(sample_locations) = sample_locations {
+                    let sample_locations_info = match sample_locations {
+                        StateMode::Fixed(sample_locations) => {
+                            dynamic_state.insert(DynamicState::SampleLocations, false);
+                            sample_locations_vk = sample_locations.locations_vk();
+                            sample_locations.to_vk(&sample_locations_vk)
+                        }
+                        StateMode::Dynamic => {
+                            dynamic_state.insert(DynamicState::SampleLocations, true);
+                            Default::default()
+                        }
+                    };
+
+                    multisample_state.p_next = sample_locations_state_vk.insert(
+                        ash::vk::PipelineSampleLocationsStateCreateInfoEXT {
+                            sample_locations_enable: ash::vk::TRUE,
+                            sample_locations_info,
+                            ..Default::default()
+                        },
+                    ) as *const _ as *const _;
+                }
             }
         }
 
@@ -3428,7 +4040,7 @@ where
         */
 
         let mut create_info = ash::vk::GraphicsPipelineCreateInfo {
-            flags: ash::vk::PipelineCreateFlags::empty(), // TODO: some flags are available but none are critical
+            flags: (*flags).into(),
             stage_count: stages_vk.len() as u32,
             p_stages: stages_vk.as_ptr(),
             p_vertex_input_state: vertex_input_state_vk
@@ -3470,8 +4082,10 @@ where
             layout: pipeline_layout.internal_object(),
             render_pass: render_pass_vk,
             subpass: subpass_vk,
-            base_pipeline_handle: ash::vk::Pipeline::null(), // TODO:
-            base_pipeline_index: -1,                         // TODO:
+            base_pipeline_handle: base_pipeline
+                .as_ref()
+                .map_or(ash::vk::Pipeline::null(), |p| p.internal_object()),
+            base_pipeline_index: -1,
             ..Default::default()
         };
 
@@ -3480,17 +4094,17 @@ where
             create_info.p_next = info as *const _ as *const _;
         }
 
-        if let Some(info) = rendering_create_info_vk.as_mut() {
+        if let Some(info) = fragment_shading_rate_state_vk.as_mut() {
             info.p_next = create_info.p_next;
             create_info.p_next = info as *const _ as *const _;
         }
 
-        let cache_handle = match cache.as_ref() {
-            Some(cache) => cache.internal_object(),
-            None => ash::vk::PipelineCache::null(),
-        };
+        if let Some(info) = rendering_create_info_vk.as_mut() {
+            info.p_next = create_info.p_next;
+            create_info.p_next = info as *const _ as *const _;
+        }
 
-        let handle = {
+        let create = |cache_handle| {
             let fns = device.fns();
             let mut output = MaybeUninit::uninit();
             (fns.v1_0.create_graphics_pipelines)(
@@ -3504,7 +4118,12 @@ where
             .result()
             .map_err(VulkanError::from)?;
 
-            output.assume_init()
+            Ok::<_, VulkanError>(output.assume_init())
+        };
+
+        let handle = match cache.as_ref() {
+            Some(cache) => cache.with_lock(create)?,
+            None => create(ash::vk::PipelineCache::null())?,
         };
 
         // Some drivers return `VK_SUCCESS` but provide a null handle if they
@@ -3529,8 +4148,6 @@ struct ShaderStageInfo<'a> {
 impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
     GraphicsPipelineBuilder<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
 {
-    // TODO: add pipeline derivate system
-
     /// Sets the vertex shader to use.
     // TODO: correct specialization constants
     pub fn vertex_shader<'vs2, Vss2>(
@@ -3544,6 +4161,9 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         GraphicsPipelineBuilder {
             render_pass: self.render_pass,
             cache: self.cache,
+            flags: self.flags,
+            base_pipeline: self.base_pipeline,
+            stage_options: self.stage_options,
 
             vertex_shader: Some((shader, specialization_constants)),
             tessellation_shaders: self.tessellation_shaders,
@@ -3555,8 +4175,10 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             tessellation_state: self.tessellation_state,
             viewport_state: self.viewport_state,
             discard_rectangle_state: self.discard_rectangle_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
             rasterization_state: self.rasterization_state,
             multisample_state: self.multisample_state,
+            sample_locations: self.sample_locations,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
         }
@@ -3578,6 +4200,9 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         GraphicsPipelineBuilder {
             render_pass: self.render_pass,
             cache: self.cache,
+            flags: self.flags,
+            base_pipeline: self.base_pipeline,
+            stage_options: self.stage_options,
 
             vertex_shader: self.vertex_shader,
             tessellation_shaders: Some(TessellationShaders {
@@ -3592,8 +4217,10 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             tessellation_state: self.tessellation_state,
             viewport_state: self.viewport_state,
             discard_rectangle_state: self.discard_rectangle_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
             rasterization_state: self.rasterization_state,
             multisample_state: self.multisample_state,
+            sample_locations: self.sample_locations,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
         }
@@ -3612,6 +4239,9 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         GraphicsPipelineBuilder {
             render_pass: self.render_pass,
             cache: self.cache,
+            flags: self.flags,
+            base_pipeline: self.base_pipeline,
+            stage_options: self.stage_options,
 
             vertex_shader: self.vertex_shader,
             tessellation_shaders: self.tessellation_shaders,
@@ -3623,8 +4253,10 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             tessellation_state: self.tessellation_state,
             viewport_state: self.viewport_state,
             discard_rectangle_state: self.discard_rectangle_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
             rasterization_state: self.rasterization_state,
             multisample_state: self.multisample_state,
+            sample_locations: self.sample_locations,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
         }
@@ -3645,6 +4277,9 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         GraphicsPipelineBuilder {
             render_pass: self.render_pass,
             cache: self.cache,
+            flags: self.flags,
+            base_pipeline: self.base_pipeline,
+            stage_options: self.stage_options,
 
             vertex_shader: self.vertex_shader,
             tessellation_shaders: self.tessellation_shaders,
@@ -3656,8 +4291,10 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             tessellation_state: self.tessellation_state,
             viewport_state: self.viewport_state,
             discard_rectangle_state: self.discard_rectangle_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
             rasterization_state: self.rasterization_state,
             multisample_state: self.multisample_state,
+            sample_locations: self.sample_locations,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
         }
@@ -3676,6 +4313,9 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         GraphicsPipelineBuilder {
             render_pass: self.render_pass,
             cache: self.cache,
+            flags: self.flags,
+            base_pipeline: self.base_pipeline,
+            stage_options: self.stage_options,
 
             vertex_shader: self.vertex_shader,
             tessellation_shaders: self.tessellation_shaders,
@@ -3687,8 +4327,10 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             tessellation_state: self.tessellation_state,
             viewport_state: self.viewport_state,
             discard_rectangle_state: self.discard_rectangle_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
             rasterization_state: self.rasterization_state,
             multisample_state: self.multisample_state,
+            sample_locations: self.sample_locations,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
         }
@@ -3734,6 +4376,18 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         self
     }
 
+    /// Sets the fragment shading rate state.
+    ///
+    /// The default value is [`FragmentShadingRateState::default()`].
+    #[inline]
+    pub fn fragment_shading_rate_state(
+        mut self,
+        fragment_shading_rate_state: FragmentShadingRateState,
+    ) -> Self {
+        self.fragment_shading_rate_state = fragment_shading_rate_state;
+        self
+    }
+
     /// Sets the rasterization state.
     ///
     /// The default value is [`RasterizationState::default()`].
@@ -3752,6 +4406,22 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         self
     }
 
+    /// Sets custom sample locations.
+    ///
+    /// If set to `StateMode::Dynamic`, the sample locations must be set with
+    /// [`set_sample_locations`](crate::command_buffer::AutoCommandBufferBuilder::set_sample_locations)
+    /// before drawing.
+    ///
+    /// The [`ext_sample_locations`](crate::device::DeviceExtensions::ext_sample_locations)
+    /// extension must be enabled on the device.
+    ///
+    /// The default value is `None`, which uses the standard sample locations.
+    #[inline]
+    pub fn sample_locations(mut self, sample_locations: StateMode<SampleLocations>) -> Self {
+        self.sample_locations = Some(sample_locations);
+        self
+    }
+
     /// Sets the depth/stencil state.
     ///
     /// The default value is [`DepthStencilState::default()`].
@@ -4017,7 +4687,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
     #[deprecated(since = "0.27.0", note = "Use `rasterization_state` instead")]
     #[inline]
     pub fn depth_clamp(mut self, clamp: bool) -> Self {
-        self.rasterization_state.depth_clamp_enable = clamp;
+        self.rasterization_state.depth_clamp_enable = StateMode::Fixed(clamp);
         self
     }
 
@@ -4084,7 +4754,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
     #[deprecated(since = "0.27.0", note = "Use `rasterization_state` instead")]
     #[inline]
     pub fn polygon_mode_fill(mut self) -> Self {
-        self.rasterization_state.polygon_mode = PolygonMode::Fill;
+        self.rasterization_state.polygon_mode = StateMode::Fixed(PolygonMode::Fill);
         self
     }
 
@@ -4092,7 +4762,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
     #[deprecated(since = "0.27.0", note = "Use `rasterization_state` instead")]
     #[inline]
     pub fn polygon_mode_line(mut self) -> Self {
-        self.rasterization_state.polygon_mode = PolygonMode::Line;
+        self.rasterization_state.polygon_mode = StateMode::Fixed(PolygonMode::Line);
         self
     }
 
@@ -4100,7 +4770,7 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
     #[deprecated(since = "0.27.0", note = "Use `rasterization_state` instead")]
     #[inline]
     pub fn polygon_mode_point(mut self) -> Self {
-        self.rasterization_state.polygon_mode = PolygonMode::Point;
+        self.rasterization_state.polygon_mode = StateMode::Fixed(PolygonMode::Point);
         self
     }
 
@@ -4331,6 +5001,9 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         GraphicsPipelineBuilder {
             render_pass: Some(render_pass.into()),
             cache: self.cache,
+            flags: self.flags,
+            base_pipeline: self.base_pipeline,
+            stage_options: self.stage_options,
 
             vertex_shader: self.vertex_shader,
             tessellation_shaders: self.tessellation_shaders,
@@ -4343,10 +5016,12 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
             viewport_state: self.viewport_state,
             rasterization_state: self.rasterization_state,
             multisample_state: self.multisample_state,
+            sample_locations: self.sample_locations,
             depth_stencil_state: self.depth_stencil_state,
             color_blend_state: self.color_blend_state,
 
             discard_rectangle_state: self.discard_rectangle_state,
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
         }
     }
 
@@ -4360,6 +5035,38 @@ impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss>
         self.cache = Some(pipeline_cache);
         self
     }
+
+    /// Sets the flags to create the pipeline with.
+    ///
+    /// The `derivative` flag is ignored; it is set automatically when a base pipeline is
+    /// provided with [`base_pipeline`](Self::base_pipeline).
+    #[inline]
+    pub fn flags(mut self, flags: PipelineCreateFlags) -> Self {
+        self.flags = PipelineCreateFlags {
+            derivative: self.base_pipeline.is_some(),
+            ..flags
+        };
+        self
+    }
+
+    /// Creates the pipeline as a derivative of `base_pipeline`.
+    ///
+    /// The base pipeline must have been created with the `allow_derivatives` flag. Creating
+    /// derivatives of a common base may be faster than creating each pipeline separately, and
+    /// switching between them in a command buffer may be cheaper.
+    #[inline]
+    pub fn base_pipeline(mut self, base_pipeline: Arc<GraphicsPipeline>) -> Self {
+        self.base_pipeline = Some(base_pipeline);
+        self.flags.derivative = true;
+        self
+    }
+
+    /// Sets additional options for the shader of the given stage.
+    #[inline]
+    pub fn stage_options(mut self, stage: ShaderStage, options: ShaderStageOptions) -> Self {
+        self.stage_options.insert(stage, options);
+        self
+    }
 }
 
 impl<'vs, 'tcs, 'tes, 'gs, 'fs, Vdef, Vss, Tcss, Tess, Gss, Fss> Clone
@@ -4376,6 +5083,9 @@ where
         GraphicsPipelineBuilder {
             render_pass: self.render_pass.clone(),
             cache: self.cache.clone(),
+            flags: self.flags,
+            base_pipeline: self.base_pipeline.clone(),
+            stage_options: self.stage_options.clone(),
 
             vertex_shader: self.vertex_shader.clone(),
             tessellation_shaders: self.tessellation_shaders.clone(),
@@ -4388,10 +5098,12 @@ where
             viewport_state: self.viewport_state.clone(),
             rasterization_state: self.rasterization_state.clone(),
             multisample_state: self.multisample_state,
+            sample_locations: self.sample_locations.clone(),
             depth_stencil_state: self.depth_stencil_state.clone(),
             color_blend_state: self.color_blend_state.clone(),
 
             discard_rectangle_state: self.discard_rectangle_state.clone(),
+            fragment_shading_rate_state: self.fragment_shading_rate_state,
         }
     }
 }
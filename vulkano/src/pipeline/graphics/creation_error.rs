@@ -11,8 +11,12 @@ use super::vertex_input::IncompatibleVertexDefinitionError;
 use crate::{
     descriptor_set::layout::DescriptorSetLayoutCreationError,
     format::{Format, NumericType},
-    pipeline::layout::{PipelineLayoutCreationError, PipelineLayoutSupersetError},
-    shader::ShaderInterfaceMismatchError,
+    image::SampleCount,
+    pipeline::{
+        layout::{PipelineLayoutCreationError, PipelineLayoutSupersetError},
+        shader_stage::ShaderStageOptionsError,
+    },
+    shader::{ShaderInterfaceMismatchError, ShaderStage},
     OomError, RequirementNotMet, RequiresOneOf, VulkanError,
 };
 use std::{
@@ -28,6 +32,9 @@ pub enum GraphicsPipelineCreationError {
         requires_one_of: RequiresOneOf,
     },
 
+    /// The base pipeline was not created with the `allow_derivatives` flag.
+    BasePipelineDerivativesNotAllowed,
+
     /// A color attachment has a format that does not support blending.
     ColorAttachmentFormatBlendNotSupported { attachment_index: u32 },
 
@@ -44,6 +51,13 @@ pub enum GraphicsPipelineCreationError {
     /// expects.
     FragmentShaderRenderPassIncompatible,
 
+    /// The fixed fragment size is not 1, 2 or 4 in each dimension.
+    FragmentShadingRateInvalidSize { fragment_size: [u32; 2] },
+
+    /// The fixed fragment shading rate has a combiner operation other than `Keep` or `Replace`,
+    /// but the `fragment_shading_rate_non_trivial_combiner_ops` property is `false`.
+    FragmentShadingRateNonTrivialCombinerOpsNotSupported,
+
     /// The pipeline layout is not compatible with what the shaders expect.
     IncompatiblePipelineLayout(PipelineLayoutSupersetError),
 
@@ -57,9 +71,15 @@ pub enum GraphicsPipelineCreationError {
     /// tessellation shader.
     InvalidPrimitiveTopology,
 
+    /// The options for a shader stage are not valid.
+    InvalidShaderStageOptions(ShaderStageOptionsError),
+
     /// `patch_control_points` was not greater than 0 and less than or equal to the `max_tessellation_patch_size` limit.
     InvalidNumPatchControlPoints,
 
+    /// The `factor` of the fixed line stipple state is not between 1 and 256 inclusive.
+    LineStippleFactorOutOfRange { factor: u32 },
+
     /// The maximum number of discard rectangles has been exceeded.
     MaxDiscardRectanglesExceeded {
         /// Maximum allowed value.
@@ -128,6 +148,20 @@ pub enum GraphicsPipelineCreationError {
     /// The maximum dimensions of viewports has been exceeded.
     MaxViewportDimensionsExceeded,
 
+    /// The device is a portability subset device, and the stride of a vertex input binding is not
+    /// a multiple of the `min_vertex_input_binding_stride_alignment` property.
+    MinVertexInputBindingStrideAlignmentNotMet {
+        /// Index of the faulty binding.
+        binding: u32,
+        /// Required alignment.
+        alignment: u32,
+        /// Value that was passed.
+        obtained: u32,
+    },
+
+    /// The `sample_shading` value of the multisample state is not between 0.0 and 1.0 inclusive.
+    MinSampleShadingOutOfRange,
+
     /// The number of attachments specified in the blending does not match the number of
     /// attachments in the subpass.
     MismatchBlendingAttachmentsCount,
@@ -150,9 +184,42 @@ pub enum GraphicsPipelineCreationError {
     /// Error while creating a descriptor set layout object.
     DescriptorSetLayoutCreationError(DescriptorSetLayoutCreationError),
 
+    /// The `fail_on_pipeline_compile_required` flag was set, and the pipeline could not be created
+    /// without compiling it.
+    PipelineCompileRequired,
+
     /// Error while creating the pipeline layout object.
     PipelineLayoutCreationError(PipelineLayoutCreationError),
 
+    /// The number of provided sample locations does not match the sample count and grid size.
+    /// `required` is `None` if the required number overflows a `u32`.
+    SampleLocationsCountMismatch {
+        provided: u32,
+        required: Option<u32>,
+    },
+
+    /// The grid size of the sample locations does not evenly divide the
+    /// [`max_sample_location_grid_size`](crate::pipeline::graphics::multisample::MultisampleProperties::max_sample_location_grid_size)
+    /// for the number of samples per pixel.
+    SampleLocationsGridSizeNotSupported {
+        grid_size: [u32; 2],
+        max_grid_size: [u32; 2],
+    },
+
+    /// The `per_pixel` value of the sample locations does not match `rasterization_samples`.
+    SampleLocationsPerPixelMismatch {
+        per_pixel: SampleCount,
+        rasterization_samples: SampleCount,
+    },
+
+    /// The `per_pixel` value of the sample locations is not included in the
+    /// [`sample_location_sample_counts`](crate::device::Properties::sample_location_sample_counts)
+    /// device property.
+    SampleLocationsPerPixelNotSupported { per_pixel: SampleCount },
+
+    /// Options were provided for a shader stage that the pipeline does not have.
+    ShaderStageOptionsStageNotPresent { stage: ShaderStage },
+
     /// The output interface of one shader and the input interface of the next shader do not match.
     ShaderStagesMismatch(ShaderInterfaceMismatchError),
 
@@ -165,6 +232,14 @@ pub enum GraphicsPipelineCreationError {
     /// The primitives topology does not match what the geometry shader expects.
     TopologyNotMatchingGeometryShader,
 
+    /// The shader input variable at the given location is 64-bit and the format of the
+    /// corresponding vertex input attribute is not, or vice versa.
+    VertexInputAttributeIncompatibleBitWidth {
+        location: u32,
+        shader_is_64bit: bool,
+        attribute_is_64bit: bool,
+    },
+
     /// The type of the shader input variable at the given location is not compatible with the
     /// format of the corresponding vertex input attribute.
     VertexInputAttributeIncompatibleFormat {
@@ -181,6 +256,13 @@ pub enum GraphicsPipelineCreationError {
     /// attribute exists for that location.
     VertexInputAttributeMissing { location: u32 },
 
+    /// The format of the vertex input attribute at the given location takes up two locations, but
+    /// the second location also has a vertex input attribute.
+    VertexInputAttributeOverlap {
+        location: u32,
+        overlapping_location: u32,
+    },
+
     /// The format specified by a vertex input attribute is not supported for vertex buffers.
     VertexInputAttributeUnsupportedFormat { location: u32, format: Format },
 
@@ -204,6 +286,7 @@ impl Error for GraphicsPipelineCreationError {
             Self::IncompatiblePipelineLayout(err) => Some(err),
             Self::ShaderStagesMismatch(err) => Some(err),
             Self::IncompatibleVertexDefinition(err) => Some(err),
+            Self::InvalidShaderStageOptions(err) => Some(err),
             _ => None,
         }
     }
@@ -220,6 +303,10 @@ impl Display for GraphicsPipelineCreationError {
                 "a requirement was not met for: {}; requires one of: {}",
                 required_for, requires_one_of,
             ),
+            Self::BasePipelineDerivativesNotAllowed => write!(
+                f,
+                "the base pipeline was not created with the `allow_derivatives` flag",
+            ),
             Self::ColorAttachmentFormatBlendNotSupported { attachment_index } => write!(
                 f,
                 "color attachment {} has a format that does not support blending",
@@ -243,6 +330,16 @@ impl Display for GraphicsPipelineCreationError {
                 "the output of the fragment shader is not compatible with what the render pass \
                 subpass expects",
             ),
+            Self::FragmentShadingRateInvalidSize { fragment_size } => write!(
+                f,
+                "the fixed fragment size {:?} is not 1, 2 or 4 in each dimension",
+                fragment_size,
+            ),
+            Self::FragmentShadingRateNonTrivialCombinerOpsNotSupported => write!(
+                f,
+                "the fixed fragment shading rate has a combiner operation other than `Keep` or \
+                `Replace`, but the device does not support non-trivial combiner operations",
+            ),
             Self::IncompatiblePipelineLayout(_) => write!(
                 f,
                 "the pipeline layout is not compatible with what the shaders expect",
@@ -261,11 +358,20 @@ impl Display for GraphicsPipelineCreationError {
                 "trying to use a patch list without a tessellation shader, or a non-patch-list \
                 with a tessellation shader",
             ),
+            Self::InvalidShaderStageOptions(_) => {
+                write!(f, "the options for a shader stage are not valid")
+            }
             Self::InvalidNumPatchControlPoints => write!(
                 f,
                 "patch_control_points was not greater than 0 and less than or equal to the \
                 max_tessellation_patch_size limit",
             ),
+            Self::LineStippleFactorOutOfRange { factor } => write!(
+                f,
+                "the `factor` of the fixed line stipple state ({}) is not between 1 and 256 \
+                inclusive",
+                factor,
+            ),
             Self::MaxDiscardRectanglesExceeded { .. } => write!(
                 f,
                 "the maximum number of discard rectangles has been exceeded",
@@ -299,6 +405,16 @@ impl Display for GraphicsPipelineCreationError {
             Self::MaxViewportDimensionsExceeded => {
                 write!(f, "the maximum dimensions of viewports has been exceeded")
             }
+            Self::MinVertexInputBindingStrideAlignmentNotMet { .. } => write!(
+                f,
+                "the stride of a vertex input binding is not a multiple of the minimum alignment \
+                required by this portability subset device",
+            ),
+            Self::MinSampleShadingOutOfRange => write!(
+                f,
+                "the `sample_shading` value of the multisample state is not between 0.0 and 1.0 \
+                inclusive",
+            ),
             Self::MismatchBlendingAttachmentsCount => write!(
                 f,
                 "the number of attachments specified in the blending does not match the number of \
@@ -321,9 +437,43 @@ impl Display for GraphicsPipelineCreationError {
             Self::DescriptorSetLayoutCreationError(_) => {
                 write!(f, "error while creating a descriptor set layout object")
             }
+            Self::PipelineCompileRequired => write!(
+                f,
+                "the `fail_on_pipeline_compile_required` flag was set, and the pipeline could not \
+                be created without compiling it",
+            ),
             Self::PipelineLayoutCreationError(_) => {
                 write!(f, "error while creating the pipeline layout object")
             }
+            Self::SampleLocationsCountMismatch { provided, .. } => write!(
+                f,
+                "the number of provided sample locations ({}) does not match the number required \
+                by the sample count and grid size",
+                provided,
+            ),
+            Self::SampleLocationsGridSizeNotSupported {
+                grid_size,
+                max_grid_size,
+            } => write!(
+                f,
+                "the grid size of the sample locations ({:?}) does not evenly divide the maximum \
+                grid size supported by the device ({:?})",
+                grid_size, max_grid_size,
+            ),
+            Self::SampleLocationsPerPixelMismatch { .. } => write!(
+                f,
+                "the `per_pixel` value of the sample locations does not match \
+                `rasterization_samples`",
+            ),
+            Self::SampleLocationsPerPixelNotSupported { .. } => write!(
+                f,
+                "the `per_pixel` value of the sample locations is not supported by the device",
+            ),
+            Self::ShaderStageOptionsStageNotPresent { stage } => write!(
+                f,
+                "options were provided for a shader stage ({:?}) that the pipeline does not have",
+                stage,
+            ),
             Self::ShaderStagesMismatch(_) => write!(
                 f,
                 "the output interface of one shader and the input interface of the next shader do \
@@ -340,6 +490,18 @@ impl Display for GraphicsPipelineCreationError {
                 f,
                 "the primitives topology does not match what the geometry shader expects",
             ),
+            Self::VertexInputAttributeIncompatibleBitWidth {
+                location,
+                shader_is_64bit,
+                attribute_is_64bit,
+            } => write!(
+                f,
+                "the shader input variable at location {} is {}64-bit, but the format of the \
+                corresponding vertex input attribute is {}64-bit",
+                location,
+                if *shader_is_64bit { "" } else { "not " },
+                if *attribute_is_64bit { "" } else { "not " },
+            ),
             Self::VertexInputAttributeIncompatibleFormat {
                 location,
                 shader_type,
@@ -362,6 +524,15 @@ impl Display for GraphicsPipelineCreationError {
                 attribute exists for that location",
                 location,
             ),
+            Self::VertexInputAttributeOverlap {
+                location,
+                overlapping_location,
+            } => write!(
+                f,
+                "the format of vertex input attribute location {} takes up two locations, but \
+                location {} also has a vertex input attribute",
+                location, overlapping_location,
+            ),
             Self::VertexInputAttributeUnsupportedFormat { location, format } => write!(
                 f,
                 "the format {:?} specified by vertex input attribute location {} is not supported \
@@ -408,11 +579,20 @@ impl From<IncompatibleVertexDefinitionError> for GraphicsPipelineCreationError {
     }
 }
 
+impl From<ShaderStageOptionsError> for GraphicsPipelineCreationError {
+    fn from(err: ShaderStageOptionsError) -> Self {
+        Self::InvalidShaderStageOptions(err)
+    }
+}
+
 impl From<VulkanError> for GraphicsPipelineCreationError {
     fn from(err: VulkanError) -> Self {
         match err {
             err @ VulkanError::OutOfHostMemory => Self::OomError(OomError::from(err)),
             err @ VulkanError::OutOfDeviceMemory => Self::OomError(OomError::from(err)),
+            VulkanError::Unnamed(ash::vk::Result::PIPELINE_COMPILE_REQUIRED) => {
+                Self::PipelineCompileRequired
+            }
             _ => panic!("unexpected error: {:?}", err),
         }
     }
@@ -0,0 +1,147 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Shading multiple pixels with a single fragment shader invocation.
+//!
+//! The fragment shading rate determines the size of the area of the framebuffer, in pixels, that
+//! each fragment covers. A larger fragment size lowers the shading cost in areas where a lower
+//! level of detail is acceptable.
+//!
+//! The rate can be specified in three ways: per pipeline or draw, per primitive by writing
+//! to `PrimitiveShadingRateKHR` in a pre-rasterization shader, and per region of the framebuffer
+//! with a fragment shading rate attachment. The three rates are merged into the final rate
+//! according to two [`FragmentShadingRateCombinerOp`]s.
+
+use crate::{macros::vulkan_enum, pipeline::StateMode};
+
+/// The state in a graphics pipeline describing the pipeline fragment shading rate, and how it
+/// is combined with the primitive and attachment fragment shading rates.
+///
+/// If this state is not the default, the
+/// [`khr_fragment_shading_rate`](crate::device::DeviceExtensions::khr_fragment_shading_rate)
+/// extension must be enabled on the device.
+#[derive(Clone, Copy, Debug)]
+pub struct FragmentShadingRateState {
+    /// The pipeline fragment shading rate, and the combiner operations.
+    ///
+    /// If set to `Dynamic`, the state must be set with
+    /// [`set_fragment_shading_rate`](crate::command_buffer::AutoCommandBufferBuilder::set_fragment_shading_rate).
+    pub rate: StateMode<FragmentShadingRate>,
+}
+
+impl FragmentShadingRateState {
+    /// Creates a `FragmentShadingRateState` with a fragment size of 1x1, that keeps the pipeline
+    /// fragment shading rate.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            rate: StateMode::Fixed(FragmentShadingRate::default()),
+        }
+    }
+
+    /// Returns whether the state is the same as when the extension is not enabled.
+    #[inline]
+    pub(crate) fn is_default(&self) -> bool {
+        match self.rate {
+            StateMode::Fixed(rate) => rate == FragmentShadingRate::default(),
+            StateMode::Dynamic => false,
+        }
+    }
+}
+
+impl Default for FragmentShadingRateState {
+    /// Returns [`FragmentShadingRateState::new`].
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A pipeline fragment shading rate, and the operations that combine it with the other rates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FragmentShadingRate {
+    /// The width and height of a fragment, in pixels.
+    ///
+    /// Each value must be 1, 2 or 4. If it is not 1, the
+    /// [`pipeline_fragment_shading_rate`](crate::device::Features::pipeline_fragment_shading_rate)
+    /// feature must be enabled on the device.
+    ///
+    /// The default value is `[1, 1]`.
+    pub fragment_size: [u32; 2],
+
+    /// The operations that combine the rates. The first operation combines the pipeline rate with
+    /// the primitive rate, the second combines that result with the attachment rate.
+    ///
+    /// If the first operation is not `Keep`, the
+    /// [`primitive_fragment_shading_rate`](crate::device::Features::primitive_fragment_shading_rate)
+    /// feature must be enabled on the device. If the second operation is not `Keep`, the
+    /// [`attachment_fragment_shading_rate`](crate::device::Features::attachment_fragment_shading_rate)
+    /// feature must be enabled on the device. If the
+    /// [`fragment_shading_rate_non_trivial_combiner_ops`](crate::device::Properties::fragment_shading_rate_non_trivial_combiner_ops)
+    /// device property is `false`, the operations must be `Keep` or `Replace`.
+    ///
+    /// The default value is `[Keep, Keep]`.
+    pub combiner_ops: [FragmentShadingRateCombinerOp; 2],
+}
+
+impl Default for FragmentShadingRate {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            fragment_size: [1, 1],
+            combiner_ops: [FragmentShadingRateCombinerOp::Keep; 2],
+        }
+    }
+}
+
+vulkan_enum! {
+    /// How two fragment shading rates are combined into one.
+    ///
+    /// In the descriptions below, `A` is the first (previous) rate and `B` is the second rate.
+    #[non_exhaustive]
+    FragmentShadingRateCombinerOp = FragmentShadingRateCombinerOpKHR(i32);
+
+    /// The result is `A`.
+    Keep = KEEP,
+
+    /// The result is `B`.
+    Replace = REPLACE,
+
+    /// The result is the minimum of `A` and `B`, for each dimension.
+    Min = MIN,
+
+    /// The result is the maximum of `A` and `B`, for each dimension.
+    Max = MAX,
+
+    /// The result is the product of `A` and `B`, for each dimension.
+    ///
+    /// If the
+    /// [`fragment_shading_rate_strict_multiply_combiner`](crate::device::Properties::fragment_shading_rate_strict_multiply_combiner)
+    /// device property is `false`, a result of `1x1` may be returned when both rates are `1x2`
+    /// or `2x1`.
+    Mul = MUL,
+}
+
+impl Default for FragmentShadingRateCombinerOp {
+    #[inline]
+    fn default() -> Self {
+        FragmentShadingRateCombinerOp::Keep
+    }
+}
+
+/// A fragment size, and the sample counts that are supported with it, as returned by
+/// [`PhysicalDevice::fragment_shading_rates`](crate::device::physical::PhysicalDevice::fragment_shading_rates).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SupportedFragmentShadingRate {
+    /// The width and height of a fragment, in pixels.
+    pub fragment_size: [u32; 2],
+
+    /// The sample counts that can be used with the fragment size.
+    pub sample_counts: crate::image::SampleCounts,
+}
@@ -225,15 +225,18 @@ vulkan_enum! {
     #[non_exhaustive]
     IndexType = IndexType(i32);
 
-    // TODO: document
+    /// Indices are 8-bit unsigned integers.
+    ///
+    /// The [`index_type_uint8`](crate::device::Features::index_type_uint8) feature must be
+    /// enabled on the device.
     U8 = UINT8_EXT {
         device_extensions: [ext_index_type_uint8],
     },
 
-    // TODO: document
+    /// Indices are 16-bit unsigned integers.
     U16 = UINT16,
 
-    // TODO: document
+    /// Indices are 32-bit unsigned integers.
     U32 = UINT32,
 
     /*
@@ -254,4 +257,15 @@ impl IndexType {
             IndexType::U32 => 4,
         }
     }
+
+    /// Returns the index value that restarts the primitive, if primitive restart is enabled.
+    /// This is the maximum value of the type.
+    #[inline]
+    pub fn primitive_restart_value(&self) -> u32 {
+        match self {
+            IndexType::U8 => u8::MAX as u32,
+            IndexType::U16 => u16::MAX as u32,
+            IndexType::U32 => u32::MAX,
+        }
+    }
 }
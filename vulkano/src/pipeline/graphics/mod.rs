@@ -58,16 +58,24 @@
 
 pub use self::{builder::GraphicsPipelineBuilder, creation_error::GraphicsPipelineCreationError};
 use self::{
-    color_blend::ColorBlendState, depth_stencil::DepthStencilState,
-    discard_rectangle::DiscardRectangleState, input_assembly::InputAssemblyState,
-    multisample::MultisampleState, rasterization::RasterizationState,
-    render_pass::PipelineRenderPassType, tessellation::TessellationState,
-    vertex_input::VertexInputState, viewport::ViewportState,
+    color_blend::ColorBlendState,
+    depth_stencil::DepthStencilState,
+    discard_rectangle::DiscardRectangleState,
+    fragment_shading_rate::FragmentShadingRateState,
+    input_assembly::InputAssemblyState,
+    multisample::{MultisampleState, SampleLocations},
+    rasterization::RasterizationState,
+    render_pass::PipelineRenderPassType,
+    tessellation::TessellationState,
+    vertex_input::VertexInputState,
+    viewport::ViewportState,
+};
+use super::{
+    DynamicState, Pipeline, PipelineBindPoint, PipelineCreateFlags, PipelineLayout, StateMode,
 };
-use super::{DynamicState, Pipeline, PipelineBindPoint, PipelineLayout};
 use crate::{
     device::{Device, DeviceOwned},
-    shader::{DescriptorRequirements, ShaderStage},
+    shader::{DescriptorBindingRequirements, ShaderStage},
     VulkanObject,
 };
 use ahash::HashMap;
@@ -83,15 +91,15 @@ pub mod color_blend;
 mod creation_error;
 pub mod depth_stencil;
 pub mod discard_rectangle;
+pub mod fragment_shading_rate;
 pub mod input_assembly;
 pub mod multisample;
 pub mod rasterization;
 pub mod render_pass;
 pub mod tessellation;
+mod tests;
 pub mod vertex_input;
 pub mod viewport;
-// FIXME: restore
-//mod tests;
 
 /// Defines how the implementation should perform a draw operation.
 ///
@@ -101,11 +109,12 @@ pub struct GraphicsPipeline {
     handle: ash::vk::Pipeline,
     device: Arc<Device>,
     layout: Arc<PipelineLayout>,
+    flags: PipelineCreateFlags,
     render_pass: PipelineRenderPassType,
 
     // TODO: replace () with an object that describes the shaders in some way.
     shaders: HashMap<ShaderStage, ()>,
-    descriptor_requirements: HashMap<(u32, u32), DescriptorRequirements>,
+    descriptor_requirements: HashMap<(u32, u32), DescriptorBindingRequirements>,
     num_used_descriptor_sets: u32,
 
     vertex_input_state: VertexInputState,
@@ -113,8 +122,10 @@ pub struct GraphicsPipeline {
     tessellation_state: Option<TessellationState>,
     viewport_state: Option<ViewportState>,
     discard_rectangle_state: Option<DiscardRectangleState>,
+    fragment_shading_rate_state: Option<FragmentShadingRateState>,
     rasterization_state: RasterizationState,
     multisample_state: Option<MultisampleState>,
+    sample_locations: Option<StateMode<SampleLocations>>,
     depth_stencil_state: Option<DepthStencilState>,
     color_blend_state: Option<ColorBlendState>,
     dynamic_state: HashMap<DynamicState, bool>,
@@ -146,6 +157,12 @@ impl GraphicsPipeline {
         &self.device
     }
 
+    /// Returns the flags that the pipeline was created with.
+    #[inline]
+    pub fn flags(&self) -> PipelineCreateFlags {
+        self.flags
+    }
+
     /// Returns the render pass this graphics pipeline is rendering to.
     #[inline]
     pub fn render_pass(&self) -> &PipelineRenderPassType {
@@ -168,7 +185,7 @@ impl GraphicsPipeline {
     #[inline]
     pub fn descriptor_requirements(
         &self,
-    ) -> impl ExactSizeIterator<Item = ((u32, u32), &DescriptorRequirements)> {
+    ) -> impl ExactSizeIterator<Item = ((u32, u32), &DescriptorBindingRequirements)> {
         self.descriptor_requirements
             .iter()
             .map(|(loc, reqs)| (*loc, reqs))
@@ -204,6 +221,12 @@ impl GraphicsPipeline {
         self.discard_rectangle_state.as_ref()
     }
 
+    /// Returns the fragment shading rate state used to create this pipeline.
+    #[inline]
+    pub fn fragment_shading_rate_state(&self) -> Option<&FragmentShadingRateState> {
+        self.fragment_shading_rate_state.as_ref()
+    }
+
     /// Returns the rasterization state used to create this pipeline.
     #[inline]
     pub fn rasterization_state(&self) -> &RasterizationState {
@@ -216,6 +239,12 @@ impl GraphicsPipeline {
         self.multisample_state.as_ref()
     }
 
+    /// Returns the custom sample locations used to create this pipeline.
+    #[inline]
+    pub fn sample_locations(&self) -> Option<&StateMode<SampleLocations>> {
+        self.sample_locations.as_ref()
+    }
+
     /// Returns the depth/stencil state used to create this pipeline.
     #[inline]
     pub fn depth_stencil_state(&self) -> Option<&DepthStencilState> {
@@ -33,7 +33,9 @@ pub struct MultisampleState {
     /// went through the shader.
     ///
     /// If set to `Some`, the [`sample_rate_shading`](crate::device::Features::sample_rate_shading)
-    /// feature must be enabled on the device.
+    /// feature must be enabled on the device, and the value must be between 0.0 and 1.0 inclusive.
+    ///
+    /// The default value is `None`.
     pub sample_shading: Option<f32>,
 
     /// A mask of bits that is ANDed with the coverage mask of each set of `rasterization_samples`
@@ -47,6 +49,8 @@ pub struct MultisampleState {
     /// then about half of the samples will be discarded. If you render to a multisample image, this
     /// means that the color will end up being mixed with whatever color was underneath, which gives
     /// the same effect as alpha blending.
+    ///
+    /// The default value is `false`.
     pub alpha_to_coverage_enable: bool,
 
     /// Controls whether the alpha value of all the samples will be forced to 1.0 (or the
@@ -54,6 +58,8 @@ pub struct MultisampleState {
     ///
     /// If set to `true`, the [`alpha_to_one`](crate::device::Features::alpha_to_one)
     /// feature must be enabled on the device.
+    ///
+    /// The default value is `false`.
     pub alpha_to_one_enable: bool,
 }
 
@@ -78,3 +84,144 @@ impl Default for MultisampleState {
         Self::new()
     }
 }
+
+/// A set of custom sample locations.
+///
+/// Custom sample locations can be given to a graphics pipeline with
+/// [`GraphicsPipelineBuilder::sample_locations`](super::GraphicsPipelineBuilder::sample_locations).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SampleLocations {
+    /// The number of samples per pixel that the locations are specified for.
+    ///
+    /// This must be a sample count included in the
+    /// [`sample_location_sample_counts`](crate::device::Properties::sample_location_sample_counts)
+    /// device property.
+    ///
+    /// The default value is [`SampleCount::Sample1`].
+    pub per_pixel: SampleCount,
+
+    /// The size in pixels of the grid that the locations repeat over.
+    ///
+    /// Each dimension must evenly divide the corresponding dimension of
+    /// [`max_sample_location_grid_size`](MultisampleProperties::max_sample_location_grid_size),
+    /// as returned by
+    /// [`PhysicalDevice::multisample_properties`](crate::device::physical::PhysicalDevice::multisample_properties)
+    /// for `per_pixel` samples.
+    ///
+    /// The default value is `[1, 1]`.
+    pub grid_size: [u32; 2],
+
+    /// The location of each sample, relative to the top left corner of its pixel, where `[0.0,
+    /// 0.0]` is the top left and `[1.0, 1.0]` the bottom right corner. Values are clamped to the
+    /// [`sample_location_coordinate_range`](crate::device::Properties::sample_location_coordinate_range)
+    /// device property.
+    ///
+    /// There must be exactly `per_pixel * grid_size[0] * grid_size[1]` locations. The locations of
+    /// the samples of one pixel are consecutive, and the pixels are ordered by row.
+    ///
+    /// The default value is empty.
+    pub locations: Vec<[f32; 2]>,
+}
+
+impl SampleLocations {
+    /// Returns the number of locations that `locations` must contain, or `None` if the number
+    /// does not fit in a `u32`.
+    #[inline]
+    pub fn required_count(&self) -> Option<u32> {
+        (self.per_pixel as u32)
+            .checked_mul(self.grid_size[0])?
+            .checked_mul(self.grid_size[1])
+    }
+
+    /// Returns whether `grid_size` evenly divides `max_grid_size`.
+    pub(crate) fn grid_size_divides(&self, max_grid_size: [u32; 2]) -> bool {
+        self.grid_size
+            .iter()
+            .zip(max_grid_size)
+            .all(|(&size, max_size)| size != 0 && max_size % size == 0)
+    }
+
+    pub(crate) fn to_vk(
+        &self,
+        locations_vk: &[ash::vk::SampleLocationEXT],
+    ) -> ash::vk::SampleLocationsInfoEXT {
+        ash::vk::SampleLocationsInfoEXT {
+            sample_locations_per_pixel: self.per_pixel.into(),
+            sample_location_grid_size: ash::vk::Extent2D {
+                width: self.grid_size[0],
+                height: self.grid_size[1],
+            },
+            sample_locations_count: locations_vk.len() as u32,
+            p_sample_locations: locations_vk.as_ptr(),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn locations_vk(&self) -> Vec<ash::vk::SampleLocationEXT> {
+        self.locations
+            .iter()
+            .map(|&[x, y]| ash::vk::SampleLocationEXT { x, y })
+            .collect()
+    }
+}
+
+impl Default for SampleLocations {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            per_pixel: SampleCount::Sample1,
+            grid_size: [1, 1],
+            locations: Vec::new(),
+        }
+    }
+}
+
+/// The properties of custom sample locations for a specific number of samples per pixel, as
+/// returned by
+/// [`PhysicalDevice::multisample_properties`](crate::device::physical::PhysicalDevice::multisample_properties).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MultisampleProperties {
+    /// The maximum size in pixels of the grid that custom sample locations can repeat over.
+    pub max_sample_location_grid_size: [u32; 2],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SampleLocations;
+    use crate::image::SampleCount;
+
+    #[test]
+    fn required_count() {
+        let sample_locations = SampleLocations {
+            per_pixel: SampleCount::Sample4,
+            grid_size: [2, 3],
+            ..Default::default()
+        };
+        assert_eq!(sample_locations.required_count(), Some(24));
+
+        let sample_locations = SampleLocations {
+            per_pixel: SampleCount::Sample4,
+            grid_size: [u32::MAX, 2],
+            ..Default::default()
+        };
+        assert_eq!(sample_locations.required_count(), None);
+    }
+
+    #[test]
+    fn grid_size_divides() {
+        let sample_locations = SampleLocations {
+            grid_size: [2, 4],
+            ..Default::default()
+        };
+        assert!(sample_locations.grid_size_divides([4, 4]));
+        assert!(!sample_locations.grid_size_divides([4, 2]));
+        assert!(!sample_locations.grid_size_divides([3, 8]));
+
+        let sample_locations = SampleLocations {
+            grid_size: [0, 1],
+            ..Default::default()
+        };
+        assert!(!sample_locations.grid_size_divides([4, 4]));
+    }
+}
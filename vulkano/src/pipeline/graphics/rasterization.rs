@@ -19,7 +19,32 @@ pub struct RasterizationState {
     ///
     /// If enabled, the [`depth_clamp`](crate::device::Features::depth_clamp) feature must be
     /// enabled on the device.
-    pub depth_clamp_enable: bool,
+    ///
+    /// If set to `Dynamic`, the
+    /// [`extended_dynamic_state3_depth_clamp_enable`](crate::device::Features::extended_dynamic_state3_depth_clamp_enable)
+    /// feature must be enabled on the device.
+    pub depth_clamp_enable: StateMode<bool>,
+
+    /// Whether fragments whose depth is outside of the clip volume will be discarded, independently
+    /// of `depth_clamp_enable`.
+    ///
+    /// If set to `None`, depth clipping is enabled if `depth_clamp_enable` is false, and disabled
+    /// otherwise. If set to `Some`, the
+    /// [`depth_clip_enable`](crate::device::Features::depth_clip_enable) feature must be enabled
+    /// on the device.
+    ///
+    /// The default value is `None`.
+    pub depth_clip_enable: Option<bool>,
+
+    /// If true, the depth range of the clip volume is [-1.0, 1.0], as in OpenGL, instead of
+    /// [0.0, 1.0]. This allows OpenGL-style projection matrices to be used unmodified.
+    ///
+    /// If set to `true`, the
+    /// [`depth_clip_control`](crate::device::Features::depth_clip_control) feature must be
+    /// enabled on the device.
+    ///
+    /// The default value is `false`.
+    pub depth_clip_negative_one_to_one: bool,
 
     /// If true, all the fragments will be discarded, and the fragment shader will not be run. This
     /// is usually used when your vertex shader has some side effects and you don't need to run the
@@ -36,7 +61,11 @@ pub struct RasterizationState {
     /// If set to a value other than `Fill`, the
     /// [`fill_mode_non_solid`](crate::device::Features::fill_mode_non_solid) feature must be
     /// enabled on the device.
-    pub polygon_mode: PolygonMode,
+    ///
+    /// If set to `Dynamic`, the
+    /// [`extended_dynamic_state3_polygon_mode`](crate::device::Features::extended_dynamic_state3_polygon_mode)
+    /// feature must be enabled on the device.
+    pub polygon_mode: StateMode<PolygonMode>,
 
     /// Specifies whether front faces or back faces should be discarded, or none, or both.
     ///
@@ -78,6 +107,15 @@ pub struct RasterizationState {
     /// [`ext_line_rasterization`](crate::device::DeviceExtensions::ext_line_rasterization)
     /// extension and an additional feature must be enabled on the device.
     pub line_stipple: Option<StateMode<LineStipple>>,
+
+    /// Which vertex of a primitive provides the values of flat-shaded vertex outputs.
+    ///
+    /// If this is not set to `FirstVertex`, the
+    /// [`ext_provoking_vertex`](crate::device::DeviceExtensions::ext_provoking_vertex) extension
+    /// and an additional feature must be enabled on the device.
+    ///
+    /// The default value is [`ProvokingVertexMode::FirstVertex`].
+    pub provoking_vertex_mode: ProvokingVertexMode,
 }
 
 impl RasterizationState {
@@ -87,22 +125,32 @@ impl RasterizationState {
     #[inline]
     pub fn new() -> Self {
         Self {
-            depth_clamp_enable: false,
+            depth_clamp_enable: StateMode::Fixed(false),
+            depth_clip_enable: None,
+            depth_clip_negative_one_to_one: false,
             rasterizer_discard_enable: StateMode::Fixed(false),
-            polygon_mode: Default::default(),
+            polygon_mode: StateMode::Fixed(Default::default()),
             cull_mode: StateMode::Fixed(Default::default()),
             front_face: StateMode::Fixed(Default::default()),
             depth_bias: None,
             line_width: StateMode::Fixed(1.0),
             line_rasterization_mode: Default::default(),
             line_stipple: None,
+            provoking_vertex_mode: Default::default(),
         }
     }
 
     /// Sets the polygon mode.
     #[inline]
     pub fn polygon_mode(mut self, polygon_mode: PolygonMode) -> Self {
-        self.polygon_mode = polygon_mode;
+        self.polygon_mode = StateMode::Fixed(polygon_mode);
+        self
+    }
+
+    /// Sets the polygon mode to dynamic.
+    #[inline]
+    pub fn polygon_mode_dynamic(mut self) -> Self {
+        self.polygon_mode = StateMode::Dynamic;
         self
     }
 
@@ -305,10 +353,37 @@ impl Default for LineRasterizationMode {
 }
 
 /// The parameters of a stippled line.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct LineStipple {
     /// The repeat factor used in stippled line rasterization. Must be between 1 and 256 inclusive.
     pub factor: u32,
     /// The bit pattern used in stippled line rasterization.
     pub pattern: u16,
 }
+
+vulkan_enum! {
+    /// Specifies which vertex of a primitive is the provoking vertex, which provides the values
+    /// of vertex outputs that are flat-shaded.
+    #[non_exhaustive]
+    ProvokingVertexMode = ProvokingVertexModeEXT(i32);
+
+    /// The first vertex of the primitive is the provoking vertex. This is the convention used by
+    /// Direct3D.
+    FirstVertex = FIRST_VERTEX,
+
+    /// The last vertex of the primitive is the provoking vertex. This is the convention used by
+    /// OpenGL.
+    ///
+    /// The [`provoking_vertex_last`](crate::device::Features::provoking_vertex_last) feature must
+    /// be enabled on the device.
+    LastVertex = LAST_VERTEX {
+        device_extensions: [ext_provoking_vertex],
+    },
+}
+
+impl Default for ProvokingVertexMode {
+    #[inline]
+    fn default() -> ProvokingVertexMode {
+        ProvokingVertexMode::FirstVertex
+    }
+}
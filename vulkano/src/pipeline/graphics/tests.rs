@@ -11,123 +11,73 @@
 
 #![cfg(test)]
 
-use format::Format;
-use framebuffer::Subpass;
-use pipeline::blend::Blend;
-use pipeline::depth_stencil::DepthStencil;
-use pipeline::input_assembly::InputAssembly;
-use pipeline::input_assembly::PrimitiveTopology;
-use pipeline::layout::PipelineLayoutDesc;
-use pipeline::multisample::Multisample;
-use pipeline::shader::ShaderInterface;
-use pipeline::shader::ShaderModule;
-use pipeline::vertex::SingleBufferDefinition;
-use pipeline::viewport::ViewportsState;
-use pipeline::GraphicsPipeline;
-use pipeline::GraphicsPipelineCreationError;
-use pipeline::GraphicsPipelineParams;
-use std::ffi::CString;
+use crate::{
+    buffer::{BufferAccess, BufferUsage, CpuAccessibleBuffer},
+    command_buffer::{
+        allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+        DrawIndexedIndirectCommand, DrawIndirectCommand, PipelineExecutionError,
+        PrimaryAutoCommandBuffer, RenderPassBeginInfo, SubpassContents,
+    },
+    device::{Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo},
+    format::Format,
+    image::{view::ImageView, AttachmentImage},
+    instance::{Instance, InstanceCreateInfo},
+    pipeline::{
+        graphics::{
+            depth_stencil::DepthStencilState,
+            input_assembly::{InputAssemblyState, PrimitiveTopology},
+            multisample::SampleLocations,
+            rasterization::{
+                LineRasterizationMode, LineStipple, ProvokingVertexMode, RasterizationState,
+            },
+            vertex_input::{
+                VertexInputAttributeDescription, VertexInputBindingDescription, VertexInputRate,
+                VertexInputState,
+            },
+            viewport::{Viewport, ViewportState},
+            GraphicsPipelineCreationError,
+        },
+        shader_stage::{ShaderStageOptions, ShaderStageOptionsError},
+        GraphicsPipeline, StateMode,
+    },
+    render_pass::{Framebuffer, FramebufferCreateInfo, Subpass},
+    shader::{ShaderModule, ShaderStage},
+    VulkanLibrary,
+};
+use std::sync::Arc;
 
 #[test]
 fn create() {
     let (device, _) = gfx_dev_and_queue!();
-
-    let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
-    let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
-
-    let _ = GraphicsPipeline::new(
-        &device,
-        GraphicsPipelineParams {
-            vertex_input: SingleBufferDefinition::<()>::new(),
-            vertex_shader: unsafe {
-                vs.vertex_shader_entry_point::<(), _, _, _>(
-                    &CString::new("main").unwrap(),
-                    ShaderInterface::empty(),
-                    ShaderInterface::empty(),
-                    PipelineLayoutDesc::empty(),
-                )
-            },
-            input_assembly: InputAssembly::triangle_list(),
-            tessellation: None,
-            geometry_shader: None,
-            viewport: ViewportsState::Dynamic { num: 1 },
-            raster: Default::default(),
-            multisample: Multisample::disabled(),
-            fragment_shader: unsafe {
-                fs.fragment_shader_entry_point::<(), _, _, _>(
-                    &CString::new("main").unwrap(),
-                    ShaderInterface::empty(),
-                    ShaderInterface::empty(),
-                    PipelineLayoutDesc::empty(),
-                )
-            },
-            depth_stencil: DepthStencil::disabled(),
-            blend: Blend::pass_through(),
-            render_pass: Subpass::from(
-                simple_rp::CustomRenderPass::new(&device, &{
-                    simple_rp::Formats {
-                        color: (Format::R8G8B8A8_UNORM, 1),
-                    }
-                })
-                .unwrap(),
-                0,
-            )
-            .unwrap(),
-        },
-    )
-    .unwrap();
+    let (vs, fs) = basic_shaders(&device);
+
+    GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device)
+        .unwrap();
 }
 
 #[test]
 fn bad_primitive_restart() {
     let (device, _) = gfx_dev_and_queue!();
-
-    let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
-    let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
-
-    let result = GraphicsPipeline::new(
-        &device,
-        GraphicsPipelineParams {
-            vertex_input: SingleBufferDefinition::<()>::new(),
-            vertex_shader: unsafe {
-                vs.vertex_shader_entry_point::<(), _, _, _>(
-                    &CString::new("main").unwrap(),
-                    ShaderInterface::empty(),
-                    ShaderInterface::empty(),
-                    PipelineLayoutDesc::empty(),
-                )
-            },
-            input_assembly: InputAssembly {
-                topology: PrimitiveTopology::TriangleList,
-                primitive_restart_enable: true,
-            },
-            tessellation: None,
-            geometry_shader: None,
-            viewport: ViewportsState::Dynamic { num: 1 },
-            raster: Default::default(),
-            multisample: Multisample::disabled(),
-            fragment_shader: unsafe {
-                fs.fragment_shader_entry_point::<(), _, _, _>(
-                    &CString::new("main").unwrap(),
-                    ShaderInterface::empty(),
-                    ShaderInterface::empty(),
-                    PipelineLayoutDesc::empty(),
-                )
-            },
-            depth_stencil: DepthStencil::disabled(),
-            blend: Blend::pass_through(),
-            render_pass: Subpass::from(
-                simple_rp::CustomRenderPass::new(&device, &{
-                    simple_rp::Formats {
-                        color: (Format::R8G8B8A8_UNORM, 1),
-                    }
-                })
-                .unwrap(),
-                0,
-            )
-            .unwrap(),
-        },
-    );
+    let (vs, fs) = basic_shaders(&device);
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .input_assembly_state(
+            InputAssemblyState::new()
+                .topology(PrimitiveTopology::TriangleList)
+                .primitive_restart_enable(),
+        )
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
 
     match result {
         Err(GraphicsPipelineCreationError::RequirementNotMet { .. }) => (),
@@ -138,53 +88,18 @@ fn bad_primitive_restart() {
 #[test]
 fn multi_viewport_feature() {
     let (device, _) = gfx_dev_and_queue!();
+    let (vs, fs) = basic_shaders(&device);
 
-    let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
-    let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
-
-    let result = GraphicsPipeline::new(
-        &device,
-        GraphicsPipelineParams {
-            vertex_input: SingleBufferDefinition::<()>::new(),
-            vertex_shader: unsafe {
-                vs.vertex_shader_entry_point::<(), _, _, _>(
-                    &CString::new("main").unwrap(),
-                    ShaderInterface::empty(),
-                    ShaderInterface::empty(),
-                    PipelineLayoutDesc::empty(),
-                )
-            },
-            input_assembly: InputAssembly::triangle_list(),
-            tessellation: None,
-            geometry_shader: None,
-            viewport: ViewportsState::Dynamic { num: 2 },
-            raster: Default::default(),
-            multisample: Multisample::disabled(),
-            fragment_shader: unsafe {
-                fs.fragment_shader_entry_point::<(), _, _, _>(
-                    &CString::new("main").unwrap(),
-                    ShaderInterface::empty(),
-                    ShaderInterface::empty(),
-                    PipelineLayoutDesc::empty(),
-                )
-            },
-            depth_stencil: DepthStencil::disabled(),
-            blend: Blend::pass_through(),
-            render_pass: Subpass::from(
-                simple_rp::CustomRenderPass::new(&device, &{
-                    simple_rp::Formats {
-                        color: (Format::R8G8B8A8_UNORM, 1),
-                    }
-                })
-                .unwrap(),
-                0,
-            )
-            .unwrap(),
-        },
-    );
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_dynamic(2))
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
 
     match result {
-        Err(GraphicsPipelineCreationError::MultiViewportFeatureNotEnabled) => (),
+        Err(GraphicsPipelineCreationError::RequirementNotMet { .. }) => (),
         _ => panic!(),
     }
 }
@@ -192,50 +107,15 @@ fn multi_viewport_feature() {
 #[test]
 fn max_viewports() {
     let (device, _) = gfx_dev_and_queue!(multi_viewport);
+    let (vs, fs) = basic_shaders(&device);
 
-    let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
-    let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
-
-    let result = GraphicsPipeline::new(
-        &device,
-        GraphicsPipelineParams {
-            vertex_input: SingleBufferDefinition::<()>::new(),
-            vertex_shader: unsafe {
-                vs.vertex_shader_entry_point::<(), _, _, _>(
-                    &CString::new("main").unwrap(),
-                    ShaderInterface::empty(),
-                    ShaderInterface::empty(),
-                    PipelineLayoutDesc::empty(),
-                )
-            },
-            input_assembly: InputAssembly::triangle_list(),
-            tessellation: None,
-            geometry_shader: None,
-            viewport: ViewportsState::Dynamic { num: !0 },
-            raster: Default::default(),
-            multisample: Multisample::disabled(),
-            fragment_shader: unsafe {
-                fs.fragment_shader_entry_point::<(), _, _, _>(
-                    &CString::new("main").unwrap(),
-                    ShaderInterface::empty(),
-                    ShaderInterface::empty(),
-                    PipelineLayoutDesc::empty(),
-                )
-            },
-            depth_stencil: DepthStencil::disabled(),
-            blend: Blend::pass_through(),
-            render_pass: Subpass::from(
-                simple_rp::CustomRenderPass::new(&device, &{
-                    simple_rp::Formats {
-                        color: (Format::R8G8B8A8_UNORM, 1),
-                    }
-                })
-                .unwrap(),
-                0,
-            )
-            .unwrap(),
-        },
-    );
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_dynamic(!0))
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
 
     match result {
         Err(GraphicsPipelineCreationError::MaxViewportsExceeded { .. }) => (),
@@ -246,73 +126,710 @@ fn max_viewports() {
 #[test]
 fn no_depth_attachment() {
     let (device, _) = gfx_dev_and_queue!();
+    let (vs, fs) = basic_shaders(&device);
 
-    let vs = unsafe { ShaderModule::new(device.clone(), &BASIC_VS).unwrap() };
-    let fs = unsafe { ShaderModule::new(device.clone(), &BASIC_FS).unwrap() };
-
-    let result = GraphicsPipeline::new(
-        &device,
-        GraphicsPipelineParams {
-            vertex_input: SingleBufferDefinition::<()>::new(),
-            vertex_shader: unsafe {
-                vs.vertex_shader_entry_point::<(), _, _, _>(
-                    &CString::new("main").unwrap(),
-                    ShaderInterface::empty(),
-                    ShaderInterface::empty(),
-                    PipelineLayoutDesc::empty(),
-                )
-            },
-            input_assembly: InputAssembly::triangle_list(),
-            tessellation: None,
-            geometry_shader: None,
-            viewport: ViewportsState::Dynamic { num: 1 },
-            raster: Default::default(),
-            multisample: Multisample::disabled(),
-            fragment_shader: unsafe {
-                fs.fragment_shader_entry_point::<(), _, _, _>(
-                    &CString::new("main").unwrap(),
-                    ShaderInterface::empty(),
-                    ShaderInterface::empty(),
-                    PipelineLayoutDesc::empty(),
-                )
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .depth_stencil_state(DepthStencilState::simple_depth_test())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
+
+    match result {
+        Err(GraphicsPipelineCreationError::NoDepthAttachment) => (),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn portability_subset_triangle_fans() {
+    let device = match portability_subset_device() {
+        Some(x) => x,
+        None => return,
+    };
+    let (vs, fs) = basic_shaders(&device);
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .input_assembly_state(InputAssemblyState::new().topology(PrimitiveTopology::TriangleFan))
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
+
+    match result {
+        Err(GraphicsPipelineCreationError::RequirementNotMet { .. }) => (),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn sample_locations_extension_not_enabled() {
+    let (device, _) = gfx_dev_and_queue!();
+    let (vs, fs) = basic_shaders(&device);
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .sample_locations(StateMode::Fixed(SampleLocations {
+            locations: vec![[0.5, 0.5]],
+            ..Default::default()
+        }))
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
+
+    match result {
+        Err(GraphicsPipelineCreationError::RequirementNotMet { .. }) => (),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn depth_clip_negative_one_to_one_feature_not_enabled() {
+    let (device, _) = gfx_dev_and_queue!();
+    let (vs, fs) = basic_shaders(&device);
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .rasterization_state(RasterizationState {
+            depth_clip_negative_one_to_one: true,
+            ..RasterizationState::new()
+        })
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
+
+    match result {
+        Err(GraphicsPipelineCreationError::RequirementNotMet { .. }) => (),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn polygon_mode_dynamic_feature_not_enabled() {
+    let (device, _) = gfx_dev_and_queue!();
+    let (vs, fs) = basic_shaders(&device);
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .rasterization_state(RasterizationState::new().polygon_mode_dynamic())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
+
+    match result {
+        Err(GraphicsPipelineCreationError::RequirementNotMet { .. }) => (),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn line_stipple_factor_out_of_range() {
+    let (device, _) = gfx_dev_and_queue!(
+        rectangular_lines, stippled_rectangular_lines;
+        extensions: ext_line_rasterization
+    );
+    let (vs, fs) = basic_shaders(&device);
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .input_assembly_state(InputAssemblyState::new().topology(PrimitiveTopology::LineList))
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .rasterization_state(RasterizationState {
+            line_rasterization_mode: LineRasterizationMode::Rectangular,
+            line_stipple: Some(StateMode::Fixed(LineStipple {
+                factor: 0,
+                pattern: 0xffff,
+            })),
+            ..RasterizationState::new()
+        })
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
+
+    assert!(matches!(
+        result,
+        Err(GraphicsPipelineCreationError::LineStippleFactorOutOfRange { factor: 0 })
+    ));
+}
+
+#[test]
+fn depth_clip_negative_one_to_one() {
+    let (device, _) = gfx_dev_and_queue!(depth_clip_control; extensions: ext_depth_clip_control);
+    let (vs, fs) = basic_shaders(&device);
+
+    GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .rasterization_state(RasterizationState {
+            depth_clip_negative_one_to_one: true,
+            ..RasterizationState::new()
+        })
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device)
+        .unwrap();
+}
+
+#[test]
+fn depth_clip_negative_one_to_one_rasterizer_discard() {
+    let (device, _) = gfx_dev_and_queue!(depth_clip_control; extensions: ext_depth_clip_control);
+    let (vs, _) = basic_shaders(&device);
+
+    // With rasterizer discard there is no viewport state, but the depth clip control must still
+    // be provided.
+    GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .rasterization_state(RasterizationState {
+            depth_clip_negative_one_to_one: true,
+            rasterizer_discard_enable: StateMode::Fixed(true),
+            ..RasterizationState::new()
+        })
+        .render_pass(basic_subpass(device.clone()))
+        .build(device)
+        .unwrap();
+}
+
+#[test]
+fn provoking_vertex_mode_bound_before_render_pass() {
+    let (device, _) = gfx_dev_and_queue!(provoking_vertex_last; extensions: ext_provoking_vertex);
+
+    // Pipelines with different provoking vertex modes can be mixed inside a render pass.
+    if device
+        .physical_device()
+        .properties()
+        .provoking_vertex_mode_per_pipeline
+        != Some(false)
+    {
+        return; // test ignored
+    }
+
+    let (vs, fs) = basic_shaders(&device);
+    let subpass = basic_subpass(device.clone());
+    let pipeline = |provoking_vertex_mode: ProvokingVertexMode| {
+        GraphicsPipeline::start()
+            .vertex_input_state(basic_vertex_input_state(8))
+            .vertex_shader(vs.entry_point("main").unwrap(), ())
+            .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+            .rasterization_state(RasterizationState {
+                provoking_vertex_mode,
+                ..RasterizationState::new()
+            })
+            .fragment_shader(fs.entry_point("main").unwrap(), ())
+            .render_pass(subpass.clone())
+            .build(device.clone())
+            .unwrap()
+    };
+    let first_vertex = pipeline(ProvokingVertexMode::FirstVertex);
+    let last_vertex = pipeline(ProvokingVertexMode::LastVertex);
+
+    let image = AttachmentImage::new(device.clone(), [1, 1], Format::R8G8B8A8_UNORM).unwrap();
+    let framebuffer = Framebuffer::new(
+        subpass.render_pass().clone(),
+        FramebufferCreateInfo {
+            attachments: vec![ImageView::new_default(image).unwrap()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let render_pass_begin_info = RenderPassBeginInfo {
+        clear_values: vec![Some([0.0; 4].into())],
+        ..RenderPassBeginInfo::framebuffer(framebuffer)
+    };
+
+    let cb_allocator = StandardCommandBufferAllocator::new(device.clone());
+    let queue_family_index = device.active_queue_family_indices()[0];
+
+    let mut builder = AutoCommandBufferBuilder::primary(
+        &cb_allocator,
+        queue_family_index,
+        CommandBufferUsage::OneTimeSubmit,
+    )
+    .unwrap();
+    builder
+        .begin_render_pass(render_pass_begin_info.clone(), SubpassContents::Inline)
+        .unwrap()
+        .bind_pipeline_graphics(last_vertex.clone());
+
+    // The pipeline that is bound when the render pass begins counts as bound inside it.
+    let mut builder = AutoCommandBufferBuilder::primary(
+        &cb_allocator,
+        queue_family_index,
+        CommandBufferUsage::OneTimeSubmit,
+    )
+    .unwrap();
+    builder
+        .bind_pipeline_graphics(first_vertex)
+        .begin_render_pass(render_pass_begin_info, SubpassContents::Inline)
+        .unwrap();
+
+    assert_should_panic!("PreviousPipelineProvokingVertexModeMismatch", {
+        builder.bind_pipeline_graphics(last_vertex);
+    });
+}
+
+#[test]
+fn draw_indexed_indirect_negative_vertex_offset() {
+    let (device, _) = gfx_dev_and_queue!();
+    let mut builder = indirect_draw_builder(&device);
+
+    let indirect_buffer = CpuAccessibleBuffer::from_iter(
+        device,
+        BufferUsage {
+            indirect_buffer: true,
+            ..BufferUsage::empty()
+        },
+        false,
+        [DrawIndexedIndirectCommand {
+            index_count: 3,
+            instance_count: 1,
+            first_index: 0,
+            vertex_offset: -1,
+            first_instance: 0,
+        }],
+    )
+    .unwrap();
+
+    builder.draw_indexed_indirect(indirect_buffer).unwrap();
+}
+
+#[test]
+fn draw_indirect_offset_not_aligned() {
+    let (device, _) = gfx_dev_and_queue!();
+    let mut builder = indirect_draw_builder(&device);
+
+    let indirect_buffer = misaligned_indirect_buffer(&device, 16);
+    let indirect_buffer = unsafe {
+        indirect_buffer
+            .slice::<u8>(2..18)
+            .unwrap()
+            .reinterpret::<[DrawIndirectCommand]>()
+    };
+
+    assert!(matches!(
+        builder.draw_indirect(indirect_buffer),
+        Err(PipelineExecutionError::IndirectBufferOffsetNotAligned { .. })
+    ));
+}
+
+#[test]
+fn draw_indexed_indirect_offset_not_aligned() {
+    let (device, _) = gfx_dev_and_queue!();
+    let mut builder = indirect_draw_builder(&device);
+
+    let indirect_buffer = misaligned_indirect_buffer(&device, 20);
+    let indirect_buffer = unsafe {
+        indirect_buffer
+            .slice::<u8>(2..22)
+            .unwrap()
+            .reinterpret::<[DrawIndexedIndirectCommand]>()
+    };
+
+    assert!(matches!(
+        builder.draw_indexed_indirect(indirect_buffer),
+        Err(PipelineExecutionError::IndirectBufferOffsetNotAligned { .. })
+    ));
+}
+
+#[test]
+fn stage_options_stage_not_present() {
+    let (device, _) = gfx_dev_and_queue!();
+    let (vs, fs) = basic_shaders(&device);
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .stage_options(ShaderStage::Geometry, ShaderStageOptions::default())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
+
+    match result {
+        Err(GraphicsPipelineCreationError::ShaderStageOptionsStageNotPresent {
+            stage: ShaderStage::Geometry,
+        }) => (),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn stage_options_feature_not_enabled() {
+    let (device, _) = gfx_dev_and_queue!();
+    let (vs, fs) = basic_shaders(&device);
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .stage_options(
+            ShaderStage::Vertex,
+            ShaderStageOptions {
+                required_subgroup_size: Some(1),
+                ..Default::default()
             },
-            depth_stencil: DepthStencil::simple_depth_test(),
-            blend: Blend::pass_through(),
-            render_pass: Subpass::from(
-                simple_rp::CustomRenderPass::new(&device, &{
-                    simple_rp::Formats {
-                        color: (Format::R8G8B8A8_UNORM, 1),
-                    }
-                })
-                .unwrap(),
-                0,
-            )
-            .unwrap(),
+        )
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
+
+    match result {
+        Err(GraphicsPipelineCreationError::InvalidShaderStageOptions(
+            ShaderStageOptionsError::RequirementNotMet { .. },
+        )) => (),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn portability_subset_stride_alignment() {
+    let device = match portability_subset_device() {
+        Some(x) => x,
+        None => return,
+    };
+    let alignment = device
+        .physical_device()
+        .properties()
+        .min_vertex_input_binding_stride_alignment
+        .unwrap();
+
+    // Every stride is a multiple of an alignment of 1.
+    if alignment == 1 {
+        return;
+    }
+
+    let (vs, fs) = basic_shaders(&device);
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(9))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
+
+    match result {
+        Err(GraphicsPipelineCreationError::MinVertexInputBindingStrideAlignmentNotMet {
+            binding: 0,
+            obtained: 9,
+            ..
+        }) => (),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn vertex_input_64bit() {
+    let (device, _) = gfx_dev_and_queue!(shader_float64);
+
+    if !format_supports_vertex_buffer(&device, Format::R64G64B64A64_SFLOAT) {
+        return; // test ignored
+    }
+
+    let vs = unsafe { ShaderModule::from_bytes(device.clone(), &VS_64BIT).unwrap() };
+    let (_, fs) = basic_shaders(&device);
+
+    GraphicsPipeline::start()
+        .vertex_input_state(vertex_input_state_64bit(Format::R64G64B64A64_SFLOAT))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device)
+        .unwrap();
+}
+
+#[test]
+fn vertex_input_64bit_incompatible_bit_width() {
+    let (device, _) = gfx_dev_and_queue!(shader_float64);
+    let vs = unsafe { ShaderModule::from_bytes(device.clone(), &VS_64BIT).unwrap() };
+    let (_, fs) = basic_shaders(&device);
+
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(vertex_input_state_64bit(Format::R32G32B32A32_SFLOAT))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
+
+    match result {
+        Err(GraphicsPipelineCreationError::VertexInputAttributeIncompatibleBitWidth {
+            location: 0,
+            shader_is_64bit: true,
+            attribute_is_64bit: false,
+        }) => (),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn vertex_input_64bit_overlap() {
+    let (device, _) = gfx_dev_and_queue!(shader_float64);
+
+    if !format_supports_vertex_buffer(&device, Format::R64G64B64A64_SFLOAT) {
+        return; // test ignored
+    }
+
+    let vs = unsafe { ShaderModule::from_bytes(device.clone(), &VS_64BIT).unwrap() };
+    let (_, fs) = basic_shaders(&device);
+
+    // The `dvec4` attribute at location 0 also takes up location 1.
+    let vertex_input_state = vertex_input_state_64bit(Format::R64G64B64A64_SFLOAT).attribute(
+        1,
+        VertexInputAttributeDescription {
+            binding: 0,
+            format: Format::R32G32B32A32_SFLOAT,
+            offset: 0,
         },
     );
 
+    let result = GraphicsPipeline::start()
+        .vertex_input_state(vertex_input_state)
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(basic_subpass(device.clone()))
+        .build(device);
+
     match result {
-        Err(GraphicsPipelineCreationError::NoDepthAttachment) => (),
+        Err(GraphicsPipelineCreationError::VertexInputAttributeOverlap {
+            location: 0,
+            overlapping_location: 1,
+        }) => (),
         _ => panic!(),
     }
 }
 
-mod simple_rp {
-    use format::Format;
+fn basic_shaders(device: &Arc<Device>) -> (Arc<ShaderModule>, Arc<ShaderModule>) {
+    unsafe {
+        (
+            ShaderModule::from_bytes(device.clone(), &BASIC_VS).unwrap(),
+            ShaderModule::from_bytes(device.clone(), &BASIC_FS).unwrap(),
+        )
+    }
+}
+
+/// Returns a vertex input state that provides the `position` input of `BASIC_VS` from a single
+/// binding with the given stride.
+fn basic_vertex_input_state(stride: u32) -> VertexInputState {
+    VertexInputState::new()
+        .binding(
+            0,
+            VertexInputBindingDescription {
+                stride,
+                input_rate: VertexInputRate::Vertex,
+            },
+        )
+        .attribute(
+            0,
+            VertexInputAttributeDescription {
+                binding: 0,
+                format: Format::R32G32_SFLOAT,
+                offset: 0,
+            },
+        )
+}
+
+/// Returns a command buffer builder inside a render pass, with everything that an indexed draw
+/// using `basic_shaders` needs already bound.
+fn indirect_draw_builder(
+    device: &Arc<Device>,
+) -> AutoCommandBufferBuilder<PrimaryAutoCommandBuffer> {
+    let (vs, fs) = basic_shaders(device);
+    let subpass = basic_subpass(device.clone());
+    let pipeline = GraphicsPipeline::start()
+        .vertex_input_state(basic_vertex_input_state(8))
+        .vertex_shader(vs.entry_point("main").unwrap(), ())
+        .viewport_state(ViewportState::viewport_dynamic_scissor_irrelevant())
+        .fragment_shader(fs.entry_point("main").unwrap(), ())
+        .render_pass(subpass.clone())
+        .build(device.clone())
+        .unwrap();
+
+    let image = AttachmentImage::new(device.clone(), [1, 1], Format::R8G8B8A8_UNORM).unwrap();
+    let framebuffer = Framebuffer::new(
+        subpass.render_pass().clone(),
+        FramebufferCreateInfo {
+            attachments: vec![ImageView::new_default(image).unwrap()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let vertex_buffer = CpuAccessibleBuffer::from_iter(
+        device.clone(),
+        BufferUsage {
+            vertex_buffer: true,
+            ..BufferUsage::empty()
+        },
+        false,
+        [[0.0f32; 2]; 3],
+    )
+    .unwrap();
+    let index_buffer = CpuAccessibleBuffer::from_iter(
+        device.clone(),
+        BufferUsage {
+            index_buffer: true,
+            ..BufferUsage::empty()
+        },
+        false,
+        [0u32, 1, 2],
+    )
+    .unwrap();
 
-    single_pass_renderpass! {
+    let cb_allocator = StandardCommandBufferAllocator::new(device.clone());
+    let mut builder = AutoCommandBufferBuilder::primary(
+        &cb_allocator,
+        device.active_queue_family_indices()[0],
+        CommandBufferUsage::OneTimeSubmit,
+    )
+    .unwrap();
+    builder
+        .begin_render_pass(
+            RenderPassBeginInfo {
+                clear_values: vec![Some([0.0; 4].into())],
+                ..RenderPassBeginInfo::framebuffer(framebuffer)
+            },
+            SubpassContents::Inline,
+        )
+        .unwrap()
+        .bind_pipeline_graphics(pipeline)
+        .set_viewport(
+            0,
+            [Viewport {
+                origin: [0.0, 0.0],
+                dimensions: [1.0, 1.0],
+                depth_range: 0.0..1.0,
+            }],
+        )
+        .bind_vertex_buffers(0, vertex_buffer)
+        .bind_index_buffer(index_buffer);
+
+    builder
+}
+
+/// Returns an indirect buffer with `size` bytes of zeroed commands, preceded by 2 bytes so that
+/// a slice of the commands starts at a misaligned offset.
+fn misaligned_indirect_buffer(device: &Arc<Device>, size: usize) -> Arc<CpuAccessibleBuffer<[u8]>> {
+    CpuAccessibleBuffer::from_iter(
+        device.clone(),
+        BufferUsage {
+            indirect_buffer: true,
+            ..BufferUsage::empty()
+        },
+        false,
+        vec![0u8; size + 2],
+    )
+    .unwrap()
+}
+
+/// Returns a vertex input state that provides the `position` input of `VS_64BIT` with the given
+/// format.
+fn vertex_input_state_64bit(format: Format) -> VertexInputState {
+    VertexInputState::new()
+        .binding(
+            0,
+            VertexInputBindingDescription {
+                stride: 32,
+                input_rate: VertexInputRate::Vertex,
+            },
+        )
+        .attribute(
+            0,
+            VertexInputAttributeDescription {
+                binding: 0,
+                format,
+                offset: 0,
+            },
+        )
+}
+
+fn format_supports_vertex_buffer(device: &Device, format: Format) -> bool {
+    device
+        .physical_device()
+        .format_properties(format)
+        .unwrap()
+        .buffer_features
+        .vertex_buffer
+}
+
+fn basic_subpass(device: Arc<Device>) -> Subpass {
+    let render_pass = single_pass_renderpass!(device,
         attachments: {
             color: {
                 load: Clear,
                 store: Store,
-                format: Format,
+                format: Format::R8G8B8A8_UNORM,
+                samples: 1,
             }
         },
         pass: {
             color: [color],
             depth_stencil: {}
         }
-    }
+    )
+    .unwrap();
+
+    Subpass::from(render_pass, 0).unwrap()
+}
+
+/// Creates a device with the `khr_portability_subset` extension enabled, or returns `None` if
+/// there is no portability subset device.
+fn portability_subset_device() -> Option<Arc<Device>> {
+    let library = VulkanLibrary::new().ok()?;
+    let instance = Instance::new(
+        library,
+        InstanceCreateInfo {
+            enumerate_portability: true,
+            ..Default::default()
+        },
+    )
+    .ok()?;
+
+    let enabled_extensions = DeviceExtensions {
+        khr_portability_subset: true,
+        ..DeviceExtensions::empty()
+    };
+    let (physical_device, queue_family_index) = instance
+        .enumerate_physical_devices()
+        .ok()?
+        .filter(|p| p.supported_extensions().contains(&enabled_extensions))
+        .find_map(|p| {
+            let queue_family_index = p
+                .queue_family_properties()
+                .iter()
+                .position(|q| q.queue_flags.graphics)?;
+
+            Some((p, queue_family_index as u32))
+        })?;
+
+    let (device, _) = Device::new(
+        physical_device,
+        DeviceCreateInfo {
+            queue_create_infos: vec![QueueCreateInfo {
+                queue_family_index,
+                ..Default::default()
+            }],
+            enabled_extensions,
+            ..Default::default()
+        },
+    )
+    .ok()?;
+
+    Some(device)
 }
 
 /*
@@ -393,3 +910,23 @@ const BASIC_FS: [u8; 420] = [
     0, 3, 0, 0, 0, 248, 0, 2, 0, 5, 0, 0, 0, 62, 0, 3, 0, 9, 0, 0, 0, 12, 0, 0, 0, 253, 0, 1, 0,
     56, 0, 1, 0,
 ];
+
+/*
+    #version 450
+
+    layout(location = 0) in dvec4 position;
+
+    void main() {
+    }
+*/
+const VS_64BIT: [u8; 252] = [
+    3, 2, 35, 7, 0, 0, 1, 0, 0, 0, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0, 17, 0, 2, 0, 1, 0, 0, 0, 17, 0, 2,
+    0, 10, 0, 0, 0, 14, 0, 3, 0, 0, 0, 0, 0, 1, 0, 0, 0, 15, 0, 6, 0, 0, 0, 0, 0, 1, 0, 0, 0, 109,
+    97, 105, 110, 0, 0, 0, 0, 2, 0, 0, 0, 3, 0, 3, 0, 2, 0, 0, 0, 194, 1, 0, 0, 5, 0, 4, 0, 1, 0,
+    0, 0, 109, 97, 105, 110, 0, 0, 0, 0, 5, 0, 5, 0, 2, 0, 0, 0, 112, 111, 115, 105, 116, 105, 111,
+    110, 0, 0, 0, 0, 71, 0, 4, 0, 2, 0, 0, 0, 30, 0, 0, 0, 0, 0, 0, 0, 19, 0, 2, 0, 3, 0, 0, 0, 33,
+    0, 3, 0, 4, 0, 0, 0, 3, 0, 0, 0, 22, 0, 3, 0, 5, 0, 0, 0, 64, 0, 0, 0, 23, 0, 4, 0, 6, 0, 0, 0,
+    5, 0, 0, 0, 4, 0, 0, 0, 32, 0, 4, 0, 7, 0, 0, 0, 1, 0, 0, 0, 6, 0, 0, 0, 59, 0, 4, 0, 7, 0, 0,
+    0, 2, 0, 0, 0, 1, 0, 0, 0, 54, 0, 5, 0, 3, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 248, 0,
+    2, 0, 8, 0, 0, 0, 253, 0, 1, 0, 56, 0, 1, 0,
+];
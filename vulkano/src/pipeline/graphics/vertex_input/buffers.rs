@@ -134,23 +134,23 @@ unsafe impl VertexDefinition for BuffersDefinition {
             if !infos.ty.matches(
                 infos.array_size,
                 element.ty.to_format(),
-                element.ty.num_locations(),
+                element.ty.num_elements,
             ) {
                 // TODO: move this check to GraphicsPipelineBuilder
                 return Err(IncompatibleVertexDefinitionError::FormatMismatch {
                     attribute: name.clone().into_owned(),
-                    shader: (
-                        element.ty.to_format(),
-                        (element.ty.num_locations()) as usize,
-                    ),
+                    shader: (element.ty.to_format(), element.ty.num_elements as usize),
                     definition: (infos.ty, infos.array_size),
                 });
             }
 
             let mut offset = infos.offset as DeviceSize;
-            let location_range = element.location..element.location + element.ty.num_locations();
 
-            for location in location_range {
+            // A 64-bit vector with more than two components takes up two locations, but is
+            // described by a single attribute at the first one.
+            for element_index in 0..element.ty.num_elements {
+                let location =
+                    element.location + element_index * element.ty.num_locations_per_element();
                 attributes.push((
                     location,
                     VertexInputAttributeDescription {
@@ -115,6 +115,56 @@ mod impl_vertex;
 mod vertex;
 
 /// The state in a graphics pipeline describing how the vertex input stage should behave.
+///
+/// The state can be built at runtime, without implementing [`Vertex`] for a type. When the
+/// pipeline is created, every input location of the vertex shader must have an attribute, and
+/// its format must be compatible with the type of the input variable.
+///
+/// # Examples
+///
+/// ```
+/// use vulkano::{
+///     format::Format,
+///     pipeline::graphics::vertex_input::{
+///         VertexInputAttributeDescription, VertexInputBindingDescription, VertexInputRate,
+///         VertexInputState,
+///     },
+/// };
+///
+/// // Binding 0 holds a `vec2` position per vertex, binding 1 holds a `vec4` color that
+/// // advances every 4 instances.
+/// let vertex_input_state = VertexInputState::new()
+///     .binding(
+///         0,
+///         VertexInputBindingDescription {
+///             stride: 8,
+///             input_rate: VertexInputRate::Vertex,
+///         },
+///     )
+///     .binding(
+///         1,
+///         VertexInputBindingDescription {
+///             stride: 16,
+///             input_rate: VertexInputRate::Instance { divisor: 4 },
+///         },
+///     )
+///     .attribute(
+///         0,
+///         VertexInputAttributeDescription {
+///             binding: 0,
+///             format: Format::R32G32_SFLOAT,
+///             offset: 0,
+///         },
+///     )
+///     .attribute(
+///         1,
+///         VertexInputAttributeDescription {
+///             binding: 1,
+///             format: Format::R32G32B32A32_SFLOAT,
+///             offset: 0,
+///         },
+///     );
+/// ```
 #[derive(Clone, Debug, Default)]
 pub struct VertexInputState {
     /// A description of the vertex buffers that the vertex input stage will read from.
@@ -66,7 +66,7 @@
 use crate::{
     descriptor_set::layout::{DescriptorRequirementsNotMet, DescriptorSetLayout, DescriptorType},
     device::{Device, DeviceOwned},
-    shader::{DescriptorRequirements, ShaderStages},
+    shader::{DescriptorBindingRequirements, ShaderStages},
     OomError, RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
 };
 use smallvec::SmallVec;
@@ -210,6 +210,7 @@ impl PipelineLayout {
             let mut num_sampled_images = Counter::default();
             let mut num_storage_images = Counter::default();
             let mut num_input_attachments = Counter::default();
+            let mut num_inline_uniform_blocks = Counter::default();
             let mut push_descriptor_set = None;
 
             for (set_num, set_layout) in set_layouts.iter().enumerate() {
@@ -225,6 +226,16 @@ impl PipelineLayout {
                 }
 
                 for layout_binding in set_layout.bindings().values() {
+                    // For inline uniform blocks, `descriptor_count` is a size in bytes, and each
+                    // binding counts as one block.
+                    if layout_binding.descriptor_type == DescriptorType::InlineUniformBlock {
+                        if layout_binding.descriptor_count != 0 {
+                            num_inline_uniform_blocks.increment(1, &layout_binding.stages);
+                        }
+
+                        continue;
+                    }
+
                     num_resources
                         .increment(layout_binding.descriptor_count, &layout_binding.stages);
 
@@ -269,6 +280,7 @@ impl PipelineLayout {
                             num_input_attachments
                                 .increment(layout_binding.descriptor_count, &layout_binding.stages);
                         }
+                        DescriptorType::InlineUniformBlock => unreachable!(),
                     }
                 }
             }
@@ -429,6 +441,34 @@ impl PipelineLayout {
                     },
                 );
             }
+
+            // VUID-VkPipelineLayoutCreateInfo-descriptorType-02214
+            let max_per_stage_inline_uniform_blocks = properties
+                .max_per_stage_descriptor_inline_uniform_blocks
+                .unwrap_or(0);
+
+            if num_inline_uniform_blocks.max_per_stage() > max_per_stage_inline_uniform_blocks {
+                return Err(
+                    PipelineLayoutCreationError::MaxPerStageDescriptorInlineUniformBlocksExceeded {
+                        provided: num_inline_uniform_blocks.max_per_stage(),
+                        max_supported: max_per_stage_inline_uniform_blocks,
+                    },
+                );
+            }
+
+            // VUID-VkPipelineLayoutCreateInfo-descriptorType-02216
+            let max_descriptor_set_inline_uniform_blocks = properties
+                .max_descriptor_set_inline_uniform_blocks
+                .unwrap_or(0);
+
+            if num_inline_uniform_blocks.total > max_descriptor_set_inline_uniform_blocks {
+                return Err(
+                    PipelineLayoutCreationError::MaxDescriptorSetInlineUniformBlocksExceeded {
+                        provided: num_inline_uniform_blocks.total,
+                        max_supported: max_descriptor_set_inline_uniform_blocks,
+                    },
+                );
+            }
         }
 
         /* Check push constant ranges */
@@ -618,7 +658,9 @@ impl PipelineLayout {
     /// constant ranges. Returns an `Err` if this is not the case.
     pub fn ensure_compatible_with_shader<'a>(
         &self,
-        descriptor_requirements: impl IntoIterator<Item = ((u32, u32), &'a DescriptorRequirements)>,
+        descriptor_requirements: impl IntoIterator<
+            Item = ((u32, u32), &'a DescriptorBindingRequirements),
+        >,
         push_constant_range: Option<&PushConstantRange>,
     ) -> Result<(), PipelineLayoutSupersetError> {
         for ((set_num, binding_num), reqs) in descriptor_requirements.into_iter() {
@@ -770,6 +812,11 @@ pub enum PipelineLayoutCreationError {
     /// limit.
     MaxDescriptorSetInputAttachmentsExceeded { provided: u32, max_supported: u32 },
 
+    /// The `set_layouts` contain more [`DescriptorType::InlineUniformBlock`] bindings than the
+    /// [`max_descriptor_set_inline_uniform_blocks`](crate::device::Properties::max_descriptor_set_inline_uniform_blocks)
+    /// limit.
+    MaxDescriptorSetInlineUniformBlocksExceeded { provided: u32, max_supported: u32 },
+
     /// The `set_layouts` contain more bound resources in a single stage than the
     /// [`max_per_stage_resources`](crate::device::Properties::max_per_stage_resources)
     /// limit.
@@ -812,6 +859,12 @@ pub enum PipelineLayoutCreationError {
     /// limit.
     MaxPerStageDescriptorInputAttachmentsExceeded { provided: u32, max_supported: u32 },
 
+    /// The `set_layouts` contain more [`DescriptorType::InlineUniformBlock`] bindings in a single
+    /// stage than the
+    /// [`max_per_stage_descriptor_inline_uniform_blocks`](crate::device::Properties::max_per_stage_descriptor_inline_uniform_blocks)
+    /// limit.
+    MaxPerStageDescriptorInlineUniformBlocksExceeded { provided: u32, max_supported: u32 },
+
     /// An element in `push_constant_ranges` has an `offset + size` greater than the
     /// [`max_push_constants_size`](crate::device::Properties::max_push_constants_size) limit.
     MaxPushConstantsSizeExceeded { provided: u32, max_supported: u32 },
@@ -928,6 +981,15 @@ impl Display for PipelineLayoutCreationError {
                 than the `max_descriptor_set_input_attachments` limit ({})",
                 provided, max_supported,
             ),
+            Self::MaxDescriptorSetInlineUniformBlocksExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::InlineUniformBlock` bindings \
+                ({}) than the `max_descriptor_set_inline_uniform_blocks` limit ({})",
+                provided, max_supported,
+            ),
             Self::MaxPerStageResourcesExceeded {
                 provided,
                 max_supported,
@@ -998,6 +1060,16 @@ impl Display for PipelineLayoutCreationError {
                 ({})",
                 provided, max_supported,
             ),
+            Self::MaxPerStageDescriptorInlineUniformBlocksExceeded {
+                provided,
+                max_supported,
+            } => write!(
+                f,
+                "the `set_layouts` contain more `DescriptorType::InlineUniformBlock` bindings \
+                ({}) in a single stage than the `max_per_stage_descriptor_inline_uniform_blocks` \
+                limit ({})",
+                provided, max_supported,
+            ),
             Self::MaxPushConstantsSizeExceeded {
                 provided,
                 max_supported,
@@ -18,13 +18,17 @@
 //! initialization or during a loading screen.
 
 pub use self::{compute::ComputePipeline, graphics::GraphicsPipeline, layout::PipelineLayout};
-use crate::{device::DeviceOwned, macros::vulkan_enum};
+use crate::{
+    device::DeviceOwned,
+    macros::{vulkan_bitflags, vulkan_enum},
+};
 use std::sync::Arc;
 
 pub mod cache;
 pub mod compute;
 pub mod graphics;
 pub mod layout;
+pub mod shader_stage;
 
 /// A trait for operations shared between pipeline types.
 pub trait Pipeline: DeviceOwned {
@@ -68,6 +72,62 @@ vulkan_enum! {
      */
 }
 
+vulkan_bitflags! {
+    /// Flags that can be set when creating a pipeline.
+    #[non_exhaustive]
+    PipelineCreateFlags = PipelineCreateFlags(u32);
+
+    /// The implementation should not optimize the pipeline. This may reduce the time it takes to
+    /// create the pipeline, at the cost of slower execution.
+    disable_optimization = DISABLE_OPTIMIZATION,
+
+    /// Other pipelines can be created as derivatives of this pipeline.
+    allow_derivatives = ALLOW_DERIVATIVES,
+
+    /// The pipeline is a derivative of another pipeline.
+    ///
+    /// This flag is set automatically by the pipeline builders when a base pipeline is provided,
+    /// and is ignored otherwise.
+    derivative = DERIVATIVE,
+
+    /// If creating the pipeline would require compiling it, because it is not found in the
+    /// pipeline cache, then creation fails with a `PipelineCompileRequired` error instead.
+    ///
+    /// Requires the
+    /// [`pipeline_creation_cache_control`](crate::device::Features::pipeline_creation_cache_control)
+    /// feature to be enabled.
+    fail_on_pipeline_compile_required = FAIL_ON_PIPELINE_COMPILE_REQUIRED {
+        api_version: V1_3,
+        device_extensions: [ext_pipeline_creation_cache_control],
+    },
+
+    /// When creating multiple pipelines at once, stop at the first one that fails to be created.
+    ///
+    /// Requires the
+    /// [`pipeline_creation_cache_control`](crate::device::Features::pipeline_creation_cache_control)
+    /// feature to be enabled.
+    early_return_on_failure = EARLY_RETURN_ON_FAILURE {
+        api_version: V1_3,
+        device_extensions: [ext_pipeline_creation_cache_control],
+    },
+
+    /// A compute pipeline can be used with
+    /// [`dispatch_base`](crate::command_buffer::AutoCommandBufferBuilder::dispatch_base) with a
+    /// nonzero base workgroup.
+    dispatch_base = DISPATCH_BASE {
+        api_version: V1_1,
+        device_extensions: [khr_device_group],
+    },
+
+    /*
+    // TODO: document
+    view_index_from_device_index = VIEW_INDEX_FROM_DEVICE_INDEX {
+        api_version: V1_1,
+        device_extensions: [khr_device_group],
+    },
+     */
+}
+
 vulkan_enum! {
     /// A particular state value within a graphics pipeline that can be dynamically set by a command
     /// buffer.
@@ -255,6 +315,161 @@ vulkan_enum! {
     ColorWriteEnable = COLOR_WRITE_ENABLE_EXT {
         device_extensions: [ext_color_write_enable],
     },
+
+    // TODO: document
+    TessellationDomainOrigin = TESSELLATION_DOMAIN_ORIGIN_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    DepthClampEnable = DEPTH_CLAMP_ENABLE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    PolygonMode = POLYGON_MODE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    RasterizationSamples = RASTERIZATION_SAMPLES_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    SampleMask = SAMPLE_MASK_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    AlphaToCoverageEnable = ALPHA_TO_COVERAGE_ENABLE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    AlphaToOneEnable = ALPHA_TO_ONE_ENABLE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    LogicOpEnable = LOGIC_OP_ENABLE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    ColorBlendEnable = COLOR_BLEND_ENABLE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    ColorBlendEquation = COLOR_BLEND_EQUATION_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    ColorWriteMask = COLOR_WRITE_MASK_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    RasterizationStream = RASTERIZATION_STREAM_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    ConservativeRasterizationMode = CONSERVATIVE_RASTERIZATION_MODE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    ExtraPrimitiveOverestimationSize = EXTRA_PRIMITIVE_OVERESTIMATION_SIZE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    DepthClipEnable = DEPTH_CLIP_ENABLE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    SampleLocationsEnable = SAMPLE_LOCATIONS_ENABLE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    ColorBlendAdvanced = COLOR_BLEND_ADVANCED_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    ProvokingVertexMode = PROVOKING_VERTEX_MODE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    LineRasterizationMode = LINE_RASTERIZATION_MODE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    LineStippleEnable = LINE_STIPPLE_ENABLE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    DepthClipNegativeOneToOne = DEPTH_CLIP_NEGATIVE_ONE_TO_ONE_EXT {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    ViewportWScalingEnable = VIEWPORT_W_SCALING_ENABLE_NV {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    ViewportSwizzle = VIEWPORT_SWIZZLE_NV {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    CoverageToColorEnable = COVERAGE_TO_COLOR_ENABLE_NV {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    CoverageToColorLocation = COVERAGE_TO_COLOR_LOCATION_NV {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    CoverageModulationMode = COVERAGE_MODULATION_MODE_NV {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    CoverageModulationTableEnable = COVERAGE_MODULATION_TABLE_ENABLE_NV {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    CoverageModulationTable = COVERAGE_MODULATION_TABLE_NV {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    ShadingRateImageEnable = SHADING_RATE_IMAGE_ENABLE_NV {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    RepresentativeFragmentTestEnable = REPRESENTATIVE_FRAGMENT_TEST_ENABLE_NV {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
+
+    // TODO: document
+    CoverageReductionMode = COVERAGE_REDUCTION_MODE_NV {
+        device_extensions: [ext_extended_dynamic_state3],
+    },
 }
 
 /// Specifies how a dynamic state is handled by a graphics pipeline.
@@ -0,0 +1,350 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Additional parameters for the individual shader stages of a pipeline.
+//!
+//! Most notably, these control the subgroup size that a shader stage is run with. By default, the
+//! implementation picks a subgroup size, and the `SubgroupSize` built-in reports the value of the
+//! [`subgroup_size`](crate::device::Properties::subgroup_size) device property. With the
+//! [`subgroup_size_control`](crate::device::Features::subgroup_size_control) feature, a shader
+//! stage can instead allow any subgroup size between
+//! [`min_subgroup_size`](crate::device::Properties::min_subgroup_size) and
+//! [`max_subgroup_size`](crate::device::Properties::max_subgroup_size), or require one specific
+//! size.
+
+use crate::{
+    device::Device,
+    macros::vulkan_bitflags,
+    shader::{ShaderStage, ShaderStages},
+    RequirementNotMet, RequiresOneOf,
+};
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+};
+
+vulkan_bitflags! {
+    /// Flags that control how a shader stage of a pipeline is created.
+    #[non_exhaustive]
+    PipelineShaderStageCreateFlags = PipelineShaderStageCreateFlags(u32);
+
+    /// The `SubgroupSize` built-in of the shader may vary, and can have any value between the
+    /// [`min_subgroup_size`](crate::device::Properties::min_subgroup_size) and
+    /// [`max_subgroup_size`](crate::device::Properties::max_subgroup_size) device properties.
+    ///
+    /// The [`subgroup_size_control`](crate::device::Features::subgroup_size_control) feature must
+    /// be enabled on the device.
+    allow_varying_subgroup_size = ALLOW_VARYING_SUBGROUP_SIZE {
+        api_version: V1_3,
+        device_extensions: [ext_subgroup_size_control],
+    },
+
+    /// All invocations in each subgroup of a compute shader must be active.
+    ///
+    /// The [`compute_full_subgroups`](crate::device::Features::compute_full_subgroups) feature
+    /// must be enabled on the device.
+    require_full_subgroups = REQUIRE_FULL_SUBGROUPS {
+        api_version: V1_3,
+        device_extensions: [ext_subgroup_size_control],
+    },
+}
+
+/// Additional parameters for a single shader stage of a pipeline.
+#[derive(Clone, Debug)]
+pub struct ShaderStageOptions {
+    /// Flags for the shader stage.
+    ///
+    /// The default value is empty.
+    pub flags: PipelineShaderStageCreateFlags,
+
+    /// If `Some`, the shader stage is run with the given subgroup size.
+    ///
+    /// If set, the [`subgroup_size_control`](crate::device::Features::subgroup_size_control)
+    /// feature must be enabled on the device, the value must be a power of two between the
+    /// [`min_subgroup_size`](crate::device::Properties::min_subgroup_size) and
+    /// [`max_subgroup_size`](crate::device::Properties::max_subgroup_size) device properties, and
+    /// the stage must be included in the
+    /// [`required_subgroup_size_stages`](crate::device::Properties::required_subgroup_size_stages)
+    /// device property. `flags` must not contain `allow_varying_subgroup_size`.
+    ///
+    /// The default value is `None`.
+    pub required_subgroup_size: Option<u32>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for ShaderStageOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            flags: PipelineShaderStageCreateFlags::empty(),
+            required_subgroup_size: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+impl ShaderStageOptions {
+    /// Checks the options for a shader of the given stage. `local_size` is the local workgroup
+    /// size, if the shader is a compute shader.
+    pub(crate) fn validate(
+        &self,
+        device: &Device,
+        stage: ShaderStage,
+        local_size: Option<[u32; 3]>,
+    ) -> Result<(), ShaderStageOptionsError> {
+        let &Self {
+            flags,
+            required_subgroup_size,
+            _ne: _,
+        } = self;
+
+        let properties = device.physical_device().properties();
+
+        // VUID-VkPipelineShaderStageCreateInfo-flags-parameter
+        flags.validate_device(device)?;
+
+        // VUID-VkPipelineShaderStageCreateInfo-flags-02784
+        if flags.allow_varying_subgroup_size && !device.enabled_features().subgroup_size_control {
+            return Err(ShaderStageOptionsError::RequirementNotMet {
+                required_for: "`flags.allow_varying_subgroup_size` is set",
+                requires_one_of: RequiresOneOf {
+                    features: &["subgroup_size_control"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        if flags.require_full_subgroups {
+            // VUID-VkPipelineShaderStageCreateInfo-flags-02785
+            if !device.enabled_features().compute_full_subgroups {
+                return Err(ShaderStageOptionsError::RequirementNotMet {
+                    required_for: "`flags.require_full_subgroups` is set",
+                    requires_one_of: RequiresOneOf {
+                        features: &["compute_full_subgroups"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkPipelineShaderStageCreateInfo-flags-08988
+            if stage != ShaderStage::Compute {
+                return Err(ShaderStageOptionsError::RequireFullSubgroupsNotCompute { stage });
+            }
+        }
+
+        if let Some(required_subgroup_size) = required_subgroup_size {
+            // VUID-VkPipelineShaderStageCreateInfo-pNext-02755
+            if !device.enabled_features().subgroup_size_control {
+                return Err(ShaderStageOptionsError::RequirementNotMet {
+                    required_for: "`required_subgroup_size` is `Some`",
+                    requires_one_of: RequiresOneOf {
+                        features: &["subgroup_size_control"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkPipelineShaderStageCreateInfo-pNext-02754
+            if flags.allow_varying_subgroup_size {
+                return Err(ShaderStageOptionsError::RequiredSubgroupSizeWithVaryingSubgroupSize);
+            }
+
+            // VUID-VkPipelineShaderStageCreateInfo-pNext-02755
+            if !properties
+                .required_subgroup_size_stages
+                .map_or(false, |stages| stages.contains(&ShaderStages::from(stage)))
+            {
+                return Err(
+                    ShaderStageOptionsError::RequiredSubgroupSizeStageNotSupported { stage },
+                );
+            }
+
+            let min = properties.min_subgroup_size.unwrap_or(1);
+            let max = properties.max_subgroup_size.unwrap_or(1);
+
+            // VUID-VkPipelineShaderStageRequiredSubgroupSizeCreateInfo-requiredSubgroupSize-02760
+            // VUID-VkPipelineShaderStageRequiredSubgroupSizeCreateInfo-requiredSubgroupSize-02761
+            // VUID-VkPipelineShaderStageRequiredSubgroupSizeCreateInfo-requiredSubgroupSize-02762
+            if !required_subgroup_size.is_power_of_two()
+                || !(min..=max).contains(&required_subgroup_size)
+            {
+                return Err(ShaderStageOptionsError::RequiredSubgroupSizeInvalid {
+                    required_subgroup_size,
+                    min,
+                    max,
+                });
+            }
+        }
+
+        if let Some(local_size) = local_size {
+            if let Some(required_subgroup_size) = required_subgroup_size {
+                // VUID-VkPipelineShaderStageCreateInfo-pNext-02756
+                let invocations = local_size.iter().map(|&size| size as u64).product::<u64>();
+                let max = required_subgroup_size as u64
+                    * properties.max_compute_workgroup_subgroups.unwrap_or(0) as u64;
+
+                if invocations > max {
+                    return Err(
+                        ShaderStageOptionsError::MaxComputeWorkgroupSubgroupsExceeded {
+                            invocations,
+                            max,
+                        },
+                    );
+                }
+            }
+
+            if flags.require_full_subgroups {
+                // VUID-VkPipelineShaderStageCreateInfo-pNext-02757
+                // VUID-VkPipelineShaderStageCreateInfo-flags-02758
+                // VUID-VkPipelineShaderStageCreateInfo-flags-02759
+                let subgroup_size = if let Some(required_subgroup_size) = required_subgroup_size {
+                    required_subgroup_size
+                } else if flags.allow_varying_subgroup_size {
+                    properties.max_subgroup_size.unwrap_or(1)
+                } else {
+                    properties.subgroup_size.unwrap_or(1)
+                };
+
+                if local_size[0] % subgroup_size != 0 {
+                    return Err(
+                        ShaderStageOptionsError::LocalSizeNotMultipleOfSubgroupSize {
+                            local_size_x: local_size[0],
+                            subgroup_size,
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn required_subgroup_size_vk(
+        &self,
+    ) -> Option<ash::vk::PipelineShaderStageRequiredSubgroupSizeCreateInfo> {
+        self.required_subgroup_size.map(|required_subgroup_size| {
+            ash::vk::PipelineShaderStageRequiredSubgroupSizeCreateInfo {
+                required_subgroup_size,
+                ..Default::default()
+            }
+        })
+    }
+}
+
+/// Error that can happen when validating the options of a shader stage.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShaderStageOptionsError {
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+
+    /// `flags.require_full_subgroups` is set, and the `x` dimension of the local workgroup size
+    /// is not a multiple of the subgroup size.
+    LocalSizeNotMultipleOfSubgroupSize {
+        local_size_x: u32,
+        subgroup_size: u32,
+    },
+
+    /// The number of invocations in the local workgroup exceeds the required subgroup size
+    /// multiplied by the
+    /// [`max_compute_workgroup_subgroups`](crate::device::Properties::max_compute_workgroup_subgroups)
+    /// device property.
+    MaxComputeWorkgroupSubgroupsExceeded { invocations: u64, max: u64 },
+
+    /// `flags.require_full_subgroups` is set, but the shader is not a compute shader.
+    RequireFullSubgroupsNotCompute { stage: ShaderStage },
+
+    /// The required subgroup size is not a power of two, or is not between the
+    /// [`min_subgroup_size`](crate::device::Properties::min_subgroup_size) and
+    /// [`max_subgroup_size`](crate::device::Properties::max_subgroup_size) device properties.
+    RequiredSubgroupSizeInvalid {
+        required_subgroup_size: u32,
+        min: u32,
+        max: u32,
+    },
+
+    /// A required subgroup size was provided, but the stage is not included in the
+    /// [`required_subgroup_size_stages`](crate::device::Properties::required_subgroup_size_stages)
+    /// device property.
+    RequiredSubgroupSizeStageNotSupported { stage: ShaderStage },
+
+    /// A required subgroup size was provided, and `flags.allow_varying_subgroup_size` is also set.
+    RequiredSubgroupSizeWithVaryingSubgroupSize,
+}
+
+impl Error for ShaderStageOptionsError {}
+
+impl Display for ShaderStageOptionsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+            Self::LocalSizeNotMultipleOfSubgroupSize {
+                local_size_x,
+                subgroup_size,
+            } => write!(
+                f,
+                "`flags.require_full_subgroups` is set, and the `x` dimension of the local \
+                workgroup size ({}) is not a multiple of the subgroup size ({})",
+                local_size_x, subgroup_size,
+            ),
+            Self::MaxComputeWorkgroupSubgroupsExceeded { invocations, max } => write!(
+                f,
+                "the number of invocations in the local workgroup ({}) exceeds the required \
+                subgroup size multiplied by the `max_compute_workgroup_subgroups` device property \
+                ({})",
+                invocations, max,
+            ),
+            Self::RequireFullSubgroupsNotCompute { stage } => write!(
+                f,
+                "`flags.require_full_subgroups` is set, but the shader stage ({:?}) is not a \
+                compute shader",
+                stage,
+            ),
+            Self::RequiredSubgroupSizeInvalid {
+                required_subgroup_size,
+                min,
+                max,
+            } => write!(
+                f,
+                "the required subgroup size ({}) is not a power of two between {} and {} \
+                inclusive",
+                required_subgroup_size, min, max,
+            ),
+            Self::RequiredSubgroupSizeStageNotSupported { stage } => write!(
+                f,
+                "a required subgroup size was provided, but the shader stage ({:?}) is not \
+                included in the `required_subgroup_size_stages` device property",
+                stage,
+            ),
+            Self::RequiredSubgroupSizeWithVaryingSubgroupSize => write!(
+                f,
+                "a required subgroup size was provided, and `flags.allow_varying_subgroup_size` \
+                is also set",
+            ),
+        }
+    }
+}
+
+impl From<RequirementNotMet> for ShaderStageOptionsError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
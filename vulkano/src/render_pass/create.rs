@@ -8,7 +8,8 @@
 // according to those terms.
 
 use super::{
-    AttachmentDescription, AttachmentReference, LoadOp, RenderPass, RenderPassCreateInfo,
+    is_fragment_shading_rate_texel_size_supported, AttachmentDescription, AttachmentReference,
+    FragmentShadingRateAttachment, LoadOp, RenderPass, RenderPassCreateInfo, StoreOp,
     SubpassDependency, SubpassDescription,
 };
 use crate::{
@@ -37,6 +38,7 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            fragment_density_map_attachment,
             _ne: _,
         } = create_info;
 
@@ -163,6 +165,10 @@ impl RenderPass {
                 ref color_attachments,
                 ref resolve_attachments,
                 ref depth_stencil_attachment,
+                ref depth_stencil_resolve_attachment,
+                depth_resolve_mode,
+                stencil_resolve_mode,
+                ref fragment_shading_rate_attachment,
                 ref preserve_attachments,
                 _ne: _,
             } = subpass;
@@ -590,6 +596,269 @@ impl RenderPass {
                 }
             }
 
+            /*
+                Check depth/stencil resolve attachment
+            */
+
+            if let Some(atch_ref) = depth_stencil_resolve_attachment.as_ref() {
+                if !(device.api_version() >= Version::V1_2
+                    || device.enabled_extensions().khr_depth_stencil_resolve)
+                {
+                    return Err(RenderPassCreationError::RequirementNotMet {
+                        required_for: "`create_info.subpasses` has an element, where `depth_stencil_resolve_attachment` is `Some`",
+                        requires_one_of: RequiresOneOf {
+                            api_version: Some(Version::V1_2),
+                            device_extensions: &["khr_depth_stencil_resolve"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                let (atch, features, _first_use) = check_attachment(atch_ref)?;
+
+                // VUID-VkSubpassDescriptionDepthStencilResolve-pDepthStencilResolveAttachment-02651
+                if !features.depth_stencil_attachment {
+                    return Err(
+                        RenderPassCreationError::SubpassAttachmentFormatUsageNotSupported {
+                            subpass: subpass_num,
+                            attachment: atch_ref.attachment,
+                            usage: "depth/stencil resolve",
+                        },
+                    );
+                }
+
+                // VUID-VkAttachmentReference2-layout-03077
+                if matches!(
+                    atch_ref.layout,
+                    ImageLayout::Undefined
+                        | ImageLayout::Preinitialized
+                        | ImageLayout::PresentSrc
+                        | ImageLayout::ColorAttachmentOptimal
+                        | ImageLayout::ShaderReadOnlyOptimal
+                ) {
+                    return Err(RenderPassCreationError::SubpassAttachmentLayoutInvalid {
+                        subpass: subpass_num,
+                        attachment: atch_ref.attachment,
+                        usage: "depth/stencil resolve",
+                    });
+                }
+
+                // Not required by spec, but enforced by Vulkano for sanity.
+                if !atch_ref.aspects.is_empty() {
+                    return Err(RenderPassCreationError::SubpassAttachmentAspectsNotEmpty {
+                        subpass: subpass_num,
+                        attachment: atch_ref.attachment,
+                    });
+                }
+
+                // VUID-VkSubpassDescriptionDepthStencilResolve-pDepthStencilResolveAttachment-03177
+                let depth_stencil_atch_ref = depth_stencil_attachment.as_ref().ok_or(
+                    RenderPassCreationError::SubpassDepthStencilResolveAttachmentWithoutDepthStencilAttachment {
+                        subpass: subpass_num,
+                    },
+                )?;
+                let depth_stencil_atch = &attachments[depth_stencil_atch_ref.attachment as usize];
+
+                // VUID-VkSubpassDescriptionDepthStencilResolve-pDepthStencilResolveAttachment-03179
+                if depth_stencil_atch.samples == SampleCount::Sample1 {
+                    return Err(
+                        RenderPassCreationError::SubpassDepthStencilAttachmentWithResolveNotMultisampled {
+                            subpass: subpass_num,
+                            attachment: depth_stencil_atch_ref.attachment,
+                        },
+                    );
+                }
+
+                // VUID-VkSubpassDescriptionDepthStencilResolve-pDepthStencilResolveAttachment-03180
+                if atch.samples != SampleCount::Sample1 {
+                    return Err(
+                        RenderPassCreationError::SubpassDepthStencilResolveAttachmentMultisampled {
+                            subpass: subpass_num,
+                            attachment: atch_ref.attachment,
+                        },
+                    );
+                }
+
+                let resolve_format = atch.format.unwrap();
+                let resolve_aspects = resolve_format.aspects();
+                let depth_stencil_format = depth_stencil_atch.format.unwrap();
+
+                // VUID-VkSubpassDescriptionDepthStencilResolve-pDepthStencilResolveAttachment-03181
+                // VUID-VkSubpassDescriptionDepthStencilResolve-pDepthStencilResolveAttachment-03182
+                if resolve_aspects.depth
+                    && (resolve_format.components()[0] != depth_stencil_format.components()[0]
+                        || resolve_format.type_depth() != depth_stencil_format.type_depth())
+                    || resolve_aspects.stencil
+                        && (resolve_format.components()[1] != depth_stencil_format.components()[1]
+                            || resolve_format.type_stencil() != depth_stencil_format.type_stencil())
+                {
+                    return Err(
+                        RenderPassCreationError::SubpassDepthStencilResolveAttachmentFormatMismatch {
+                            subpass: subpass_num,
+                            resolve_attachment: atch_ref.attachment,
+                            depth_stencil_attachment: depth_stencil_atch_ref.attachment,
+                        },
+                    );
+                }
+
+                for mode in [depth_resolve_mode, stencil_resolve_mode]
+                    .into_iter()
+                    .flatten()
+                {
+                    // VUID-VkSubpassDescriptionDepthStencilResolve-depthResolveMode-parameter
+                    // VUID-VkSubpassDescriptionDepthStencilResolve-stencilResolveMode-parameter
+                    mode.validate_device(device)?;
+                }
+
+                // VUID-VkSubpassDescriptionDepthStencilResolve-pDepthStencilResolveAttachment-03178
+                if depth_resolve_mode.is_none() && stencil_resolve_mode.is_none() {
+                    return Err(
+                        RenderPassCreationError::SubpassDepthStencilResolveModesNone {
+                            subpass: subpass_num,
+                        },
+                    );
+                }
+
+                // VUID-VkSubpassDescriptionDepthStencilResolve-pDepthStencilResolveAttachment-03183
+                if let Some(mode) = depth_resolve_mode.filter(|_| resolve_aspects.depth) {
+                    if !properties
+                        .supported_depth_resolve_modes
+                        .map_or(false, |modes| modes.contains_mode(mode))
+                    {
+                        return Err(
+                            RenderPassCreationError::SubpassDepthStencilResolveModeNotSupported {
+                                subpass: subpass_num,
+                            },
+                        );
+                    }
+                }
+
+                // VUID-VkSubpassDescriptionDepthStencilResolve-pDepthStencilResolveAttachment-03184
+                if let Some(mode) = stencil_resolve_mode.filter(|_| resolve_aspects.stencil) {
+                    if !properties
+                        .supported_stencil_resolve_modes
+                        .map_or(false, |modes| modes.contains_mode(mode))
+                    {
+                        return Err(
+                            RenderPassCreationError::SubpassDepthStencilResolveModeNotSupported {
+                                subpass: subpass_num,
+                            },
+                        );
+                    }
+                }
+
+                if resolve_aspects.depth && resolve_aspects.stencil {
+                    let modes_supported = match (depth_resolve_mode, stencil_resolve_mode) {
+                        // VUID-VkSubpassDescriptionDepthStencilResolve-pDepthStencilResolveAttachment-03186
+                        (None, Some(_)) | (Some(_), None) => {
+                            properties.independent_resolve_none.unwrap_or(false)
+                                || properties.independent_resolve.unwrap_or(false)
+                        }
+                        // VUID-VkSubpassDescriptionDepthStencilResolve-pDepthStencilResolveAttachment-03185
+                        (Some(depth_mode), Some(stencil_mode)) => {
+                            depth_mode == stencil_mode
+                                || properties.independent_resolve.unwrap_or(false)
+                        }
+                        (None, None) => true,
+                    };
+
+                    if !modes_supported {
+                        return Err(
+                            RenderPassCreationError::SubpassDepthStencilResolveModeNotSupported {
+                                subpass: subpass_num,
+                            },
+                        );
+                    }
+                }
+            }
+
+            /*
+                Check fragment shading rate attachment
+            */
+
+            if let Some(fragment_shading_rate_attachment) = fragment_shading_rate_attachment {
+                let &FragmentShadingRateAttachment {
+                    ref attachment_ref,
+                    texel_size,
+                } = fragment_shading_rate_attachment;
+
+                if !device.enabled_features().attachment_fragment_shading_rate {
+                    return Err(RenderPassCreationError::RequirementNotMet {
+                        required_for: "`create_info.subpasses` has an element, where `fragment_shading_rate_attachment` is `Some`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["attachment_fragment_shading_rate"],
+                            ..Default::default()
+                        },
+                    });
+                }
+
+                // VUID-VkRenderPassCreateInfo2-fragmentShadingRateAttachment-06504
+                if (input_attachments.iter())
+                    .chain(color_attachments)
+                    .chain(resolve_attachments)
+                    .chain([depth_stencil_attachment, depth_stencil_resolve_attachment])
+                    .flatten()
+                    .any(|other_atch_ref| other_atch_ref.attachment == attachment_ref.attachment)
+                {
+                    return Err(
+                        RenderPassCreationError::SubpassAttachmentUsageFragmentShadingRate {
+                            subpass: subpass_num,
+                            attachment: attachment_ref.attachment,
+                        },
+                    );
+                }
+
+                let (_atch, features, _first_use) = check_attachment(attachment_ref)?;
+
+                // VUID-VkRenderPassCreateInfo2-pAttachments-04586
+                if !features.fragment_shading_rate_attachment {
+                    return Err(
+                        RenderPassCreationError::SubpassAttachmentFormatUsageNotSupported {
+                            subpass: subpass_num,
+                            attachment: attachment_ref.attachment,
+                            usage: "fragment shading rate",
+                        },
+                    );
+                }
+
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04524
+                if !matches!(
+                    attachment_ref.layout,
+                    ImageLayout::General | ImageLayout::FragmentShadingRateAttachmentOptimal
+                ) {
+                    return Err(RenderPassCreationError::SubpassAttachmentLayoutInvalid {
+                        subpass: subpass_num,
+                        attachment: attachment_ref.attachment,
+                        usage: "fragment shading rate",
+                    });
+                }
+
+                // Not required by spec, but enforced by Vulkano for sanity.
+                if !attachment_ref.aspects.is_empty() {
+                    return Err(RenderPassCreationError::SubpassAttachmentAspectsNotEmpty {
+                        subpass: subpass_num,
+                        attachment: attachment_ref.attachment,
+                    });
+                }
+
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04525
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04526
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04527
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04528
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04529
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04530
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04531
+                // VUID-VkFragmentShadingRateAttachmentInfoKHR-pFragmentShadingRateAttachment-04532
+                if !is_fragment_shading_rate_texel_size_supported(properties, texel_size) {
+                    return Err(
+                        RenderPassCreationError::SubpassFragmentShadingRateTexelSizeNotSupported {
+                            subpass: subpass_num,
+                            texel_size,
+                        },
+                    );
+                }
+            }
+
             /*
                 Check preserve attachments
             */
@@ -615,6 +884,86 @@ impl RenderPass {
             }
         }
 
+        /*
+            Fragment density map attachment
+        */
+
+        if let Some(atch_ref) = fragment_density_map_attachment {
+            if !device.enabled_features().fragment_density_map {
+                return Err(RenderPassCreationError::RequirementNotMet {
+                    required_for: "`create_info.fragment_density_map_attachment` is `Some`",
+                    requires_one_of: RequiresOneOf {
+                        features: &["fragment_density_map"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkAttachmentReference-layout-parameter
+            atch_ref.layout.validate_device(device)?;
+
+            // VUID-VkRenderPassFragmentDensityMapCreateInfoEXT-fragmentDensityMapAttachment-02547
+            let atch = attachments.get(atch_ref.attachment as usize).ok_or(
+                RenderPassCreationError::FragmentDensityMapAttachmentOutOfRange {
+                    attachment: atch_ref.attachment,
+                },
+            )?;
+
+            // VUID-VkRenderPassFragmentDensityMapCreateInfoEXT-fragmentDensityMapAttachment-02548
+            if attachment_used[atch_ref.attachment as usize]
+                || subpasses
+                    .iter()
+                    .any(|subpass| subpass.preserve_attachments.contains(&atch_ref.attachment))
+            {
+                return Err(
+                    RenderPassCreationError::FragmentDensityMapAttachmentUsedInSubpass {
+                        attachment: atch_ref.attachment,
+                    },
+                );
+            }
+
+            // VUID-VkRenderPassFragmentDensityMapCreateInfoEXT-fragmentDensityMapAttachment-02549
+            if !matches!(
+                atch_ref.layout,
+                ImageLayout::General | ImageLayout::FragmentDensityMapOptimal
+            ) {
+                return Err(
+                    RenderPassCreationError::FragmentDensityMapAttachmentLayoutInvalid {
+                        attachment: atch_ref.attachment,
+                    },
+                );
+            }
+
+            // VUID-VkRenderPassFragmentDensityMapCreateInfoEXT-fragmentDensityMapAttachment-02550
+            if !attachment_potential_format_features[atch_ref.attachment as usize]
+                .fragment_density_map
+            {
+                return Err(
+                    RenderPassCreationError::FragmentDensityMapAttachmentFormatUsageNotSupported {
+                        attachment: atch_ref.attachment,
+                    },
+                );
+            }
+
+            // VUID-VkRenderPassFragmentDensityMapCreateInfoEXT-fragmentDensityMapAttachment-02551
+            if !matches!(atch.load_op, LoadOp::Load | LoadOp::DontCare) {
+                return Err(
+                    RenderPassCreationError::FragmentDensityMapAttachmentLoadOpInvalid {
+                        attachment: atch_ref.attachment,
+                    },
+                );
+            }
+
+            // VUID-VkRenderPassFragmentDensityMapCreateInfoEXT-fragmentDensityMapAttachment-02552
+            if atch.store_op != StoreOp::DontCare {
+                return Err(
+                    RenderPassCreationError::FragmentDensityMapAttachmentStoreOpInvalid {
+                        attachment: atch_ref.attachment,
+                    },
+                );
+            }
+        }
+
         /*
             Dependencies
         */
@@ -963,6 +1312,7 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            fragment_density_map_attachment,
             _ne: _,
         } = create_info;
 
@@ -992,6 +1342,11 @@ impl RenderPass {
                     .chain(subpass.resolve_attachments.iter())
                     .map(Option::as_ref)
                     .chain(subpass.depth_stencil_attachment.iter().map(Some))
+                    .chain(subpass.depth_stencil_resolve_attachment.iter().map(Some))
+                    .chain(
+                        (subpass.fragment_shading_rate_attachment.iter())
+                            .map(|atch| Some(&atch.attachment_ref)),
+                    )
                     .map(|atch_ref| {
                         if let Some(atch_ref) = atch_ref {
                             ash::vk::AttachmentReference2 {
@@ -1010,11 +1365,11 @@ impl RenderPass {
             })
             .collect::<SmallVec<[_; 8]>>();
 
-        let subpasses_vk = {
+        let (mut subpasses_vk, mut subpass_extensions_vk) = {
             // `ref_index` is increased during the loop and points to the next element to use
             // in `attachment_references_vk`.
             let mut ref_index = 0usize;
-            let out: SmallVec<[_; 4]> = subpasses
+            let out: (SmallVec<[_; 4]>, SmallVec<[_; 4]>) = subpasses
                 .iter()
                 .map(|subpass| {
                     let input_attachments = attachment_references_vk.as_ptr().add(ref_index);
@@ -1030,8 +1385,40 @@ impl RenderPass {
                     } else {
                         ptr::null()
                     };
+                    let depth_stencil_resolve =
+                        subpass.depth_stencil_resolve_attachment.as_ref().map(|_| {
+                            let a = attachment_references_vk.as_ptr().add(ref_index);
+                            ref_index += 1;
+
+                            ash::vk::SubpassDescriptionDepthStencilResolve {
+                                depth_resolve_mode: subpass
+                                    .depth_resolve_mode
+                                    .map_or(ash::vk::ResolveModeFlags::NONE, Into::into),
+                                stencil_resolve_mode: subpass
+                                    .stencil_resolve_mode
+                                    .map_or(ash::vk::ResolveModeFlags::NONE, Into::into),
+                                p_depth_stencil_resolve_attachment: a,
+                                ..Default::default()
+                            }
+                        });
+                    let fragment_shading_rate_attachment = subpass
+                        .fragment_shading_rate_attachment
+                        .as_ref()
+                        .map(|atch| {
+                            let a = attachment_references_vk.as_ptr().add(ref_index);
+                            ref_index += 1;
+
+                            ash::vk::FragmentShadingRateAttachmentInfoKHR {
+                                p_fragment_shading_rate_attachment: a,
+                                shading_rate_attachment_texel_size: ash::vk::Extent2D {
+                                    width: atch.texel_size[0],
+                                    height: atch.texel_size[1],
+                                },
+                                ..Default::default()
+                            }
+                        });
 
-                    ash::vk::SubpassDescription2 {
+                    let subpass_vk = ash::vk::SubpassDescription2 {
                         flags: ash::vk::SubpassDescriptionFlags::empty(),
                         pipeline_bind_point: ash::vk::PipelineBindPoint::GRAPHICS, // TODO: any need to make this user-specifiable?
                         view_mask: subpass.view_mask,
@@ -1060,9 +1447,14 @@ impl RenderPass {
                             subpass.preserve_attachments.as_ptr()
                         },
                         ..Default::default()
-                    }
+                    };
+
+                    (
+                        subpass_vk,
+                        (depth_stencil_resolve, fragment_shading_rate_attachment),
+                    )
                 })
-                .collect();
+                .unzip();
 
             // If this assertion fails, there's a serious bug in the code above ^.
             debug_assert!(ref_index == attachment_references_vk.len());
@@ -1070,6 +1462,22 @@ impl RenderPass {
             out
         };
 
+        for (subpass_vk, (depth_stencil_resolve_vk, fragment_shading_rate_attachment_vk)) in
+            subpasses_vk
+                .iter_mut()
+                .zip(subpass_extensions_vk.iter_mut())
+        {
+            if let Some(next) = depth_stencil_resolve_vk {
+                next.p_next = subpass_vk.p_next;
+                subpass_vk.p_next = next as *const _ as *const _;
+            }
+
+            if let Some(next) = fragment_shading_rate_attachment_vk {
+                next.p_next = subpass_vk.p_next;
+                subpass_vk.p_next = next as *const _ as *const _;
+            }
+        }
+
         let dependencies_vk = dependencies
             .iter()
             .map(|dependency| {
@@ -1102,7 +1510,7 @@ impl RenderPass {
             })
             .collect::<SmallVec<[_; 4]>>();
 
-        let create_info = ash::vk::RenderPassCreateInfo2 {
+        let mut create_info = ash::vk::RenderPassCreateInfo2 {
             flags: ash::vk::RenderPassCreateFlags::empty(),
             attachment_count: attachments_vk.len() as u32,
             p_attachments: if attachments_vk.is_empty() {
@@ -1127,6 +1535,22 @@ impl RenderPass {
             ..Default::default()
         };
 
+        let mut fragment_density_map_create_info =
+            fragment_density_map_attachment.as_ref().map(|atch_ref| {
+                ash::vk::RenderPassFragmentDensityMapCreateInfoEXT {
+                    fragment_density_map_attachment: ash::vk::AttachmentReference {
+                        attachment: atch_ref.attachment,
+                        layout: atch_ref.layout.into(),
+                    },
+                    ..Default::default()
+                }
+            });
+
+        if let Some(fragment_density_map_create_info) = fragment_density_map_create_info.as_mut() {
+            fragment_density_map_create_info.p_next = create_info.p_next;
+            create_info.p_next = fragment_density_map_create_info as *const _ as *const _;
+        }
+
         Ok({
             let fns = device.fns();
             let mut output = MaybeUninit::uninit();
@@ -1162,9 +1586,26 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            fragment_density_map_attachment,
             _ne: _,
         } = create_info;
 
+        // `VkSubpassDescription` can't hold a depth/stencil resolve attachment, only
+        // `VkSubpassDescription2` can.
+        if subpasses
+            .iter()
+            .any(|subpass| subpass.depth_stencil_resolve_attachment.is_some())
+        {
+            return Err(RenderPassCreationError::RequirementNotMet {
+                required_for: "`create_info.subpasses` has an element, where `depth_stencil_resolve_attachment` is `Some`",
+                requires_one_of: RequiresOneOf {
+                    api_version: Some(Version::V1_2),
+                    device_extensions: &["khr_depth_stencil_resolve"],
+                    ..Default::default()
+                },
+            });
+        }
+
         let attachments_vk = attachments
             .iter()
             .map(|attachment| ash::vk::AttachmentDescription {
@@ -1357,6 +1798,19 @@ impl RenderPass {
             None
         };
 
+        /* Fragment density map */
+
+        let mut fragment_density_map_create_info =
+            fragment_density_map_attachment.as_ref().map(|atch_ref| {
+                ash::vk::RenderPassFragmentDensityMapCreateInfoEXT {
+                    fragment_density_map_attachment: ash::vk::AttachmentReference {
+                        attachment: atch_ref.attachment,
+                        layout: atch_ref.layout.into(),
+                    },
+                    ..Default::default()
+                }
+            });
+
         /* Create */
 
         let mut create_info = ash::vk::RenderPassCreateInfo {
@@ -1394,6 +1848,11 @@ impl RenderPass {
             create_info.p_next = multiview_create_info as *const _ as *const _;
         }
 
+        if let Some(fragment_density_map_create_info) = fragment_density_map_create_info.as_mut() {
+            fragment_density_map_create_info.p_next = create_info.p_next;
+            create_info.p_next = fragment_density_map_create_info as *const _ as *const _;
+        }
+
         Ok({
             let fns = device.fns();
             let mut output = MaybeUninit::uninit();
@@ -1481,6 +1940,27 @@ pub enum RenderPassCreationError {
     /// on the render pass.
     DependencyViewLocalMultiviewNotEnabled { dependency: u32 },
 
+    /// The format of the fragment density map attachment does not support the
+    /// `fragment_density_map` format feature.
+    FragmentDensityMapAttachmentFormatUsageNotSupported { attachment: u32 },
+
+    /// The fragment density map attachment has a layout other than `FragmentDensityMapOptimal` or
+    /// `General`.
+    FragmentDensityMapAttachmentLayoutInvalid { attachment: u32 },
+
+    /// The fragment density map attachment has a `load_op` other than [`LoadOp::Load`] or
+    /// [`LoadOp::DontCare`].
+    FragmentDensityMapAttachmentLoadOpInvalid { attachment: u32 },
+
+    /// The fragment density map attachment refers to an attachment that does not exist.
+    FragmentDensityMapAttachmentOutOfRange { attachment: u32 },
+
+    /// The fragment density map attachment has a `store_op` other than [`StoreOp::DontCare`].
+    FragmentDensityMapAttachmentStoreOpInvalid { attachment: u32 },
+
+    /// The fragment density map attachment is also used by a subpass.
+    FragmentDensityMapAttachmentUsedInSubpass { attachment: u32 },
+
     /// A reference to an attachment used other than as an input attachment in a subpass has
     /// one or more aspects selected.
     SubpassAttachmentAspectsNotEmpty { subpass: u32, attachment: u32 },
@@ -1504,6 +1984,10 @@ pub enum RenderPassCreationError {
     /// subpass.
     SubpassAttachmentUsageColorDepthStencil { subpass: u32, attachment: u32 },
 
+    /// An attachment is used as both a fragment shading rate attachment and another attachment
+    /// type in a subpass.
+    SubpassAttachmentUsageFragmentShadingRate { subpass: u32, attachment: u32 },
+
     /// An attachment used as an attachment in a subpass has a format that does not support that
     /// usage.
     SubpassAttachmentFormatUsageNotSupported {
@@ -1525,6 +2009,39 @@ pub enum RenderPassCreationError {
         first_samples: SampleCount,
     },
 
+    /// A subpass has a depth/stencil resolve attachment, but its depth/stencil attachment has a
+    /// `samples` value of [`SampleCount::Sample1`].
+    SubpassDepthStencilAttachmentWithResolveNotMultisampled { subpass: u32, attachment: u32 },
+
+    /// The depth or stencil component of the format of the depth/stencil resolve attachment of a
+    /// subpass does not match that of its depth/stencil attachment.
+    SubpassDepthStencilResolveAttachmentFormatMismatch {
+        subpass: u32,
+        resolve_attachment: u32,
+        depth_stencil_attachment: u32,
+    },
+
+    /// The depth/stencil resolve attachment of a subpass has a `samples` value other than
+    /// [`SampleCount::Sample1`].
+    SubpassDepthStencilResolveAttachmentMultisampled { subpass: u32, attachment: u32 },
+
+    /// A subpass has a depth/stencil resolve attachment, but no depth/stencil attachment.
+    SubpassDepthStencilResolveAttachmentWithoutDepthStencilAttachment { subpass: u32 },
+
+    /// The `depth_resolve_mode` or `stencil_resolve_mode` of a subpass, or the combination of
+    /// both, is not supported by the device.
+    SubpassDepthStencilResolveModeNotSupported { subpass: u32 },
+
+    /// A subpass has a depth/stencil resolve attachment, but `depth_resolve_mode` and
+    /// `stencil_resolve_mode` are both `None`.
+    SubpassDepthStencilResolveModesNone { subpass: u32 },
+
+    /// The texel size of the fragment shading rate attachment of a subpass is not a power of two,
+    /// is outside the range of the `min_fragment_shading_rate_attachment_texel_size` and
+    /// `max_fragment_shading_rate_attachment_texel_size` limits, or exceeds the
+    /// `max_fragment_shading_rate_attachment_texel_size_aspect_ratio` limit.
+    SubpassFragmentShadingRateTexelSizeNotSupported { subpass: u32, texel_size: [u32; 2] },
+
     /// A reference to an attachment used as an input attachment in a subpass selects aspects that
     /// are not present in the format of the attachment.
     SubpassInputAttachmentAspectsNotCompatible { subpass: u32, attachment: u32 },
@@ -1702,6 +2219,39 @@ impl Display for RenderPassCreationError {
                 not enabled on the render pass",
                 dependency,
             ),
+            Self::FragmentDensityMapAttachmentFormatUsageNotSupported { attachment } => write!(
+                f,
+                "the format of the fragment density map attachment {} does not support the \
+                `fragment_density_map` format feature",
+                attachment,
+            ),
+            Self::FragmentDensityMapAttachmentLayoutInvalid { attachment } => write!(
+                f,
+                "the fragment density map attachment {} has a layout other than \
+                `FragmentDensityMapOptimal` or `General`",
+                attachment,
+            ),
+            Self::FragmentDensityMapAttachmentLoadOpInvalid { attachment } => write!(
+                f,
+                "the fragment density map attachment {} has a `load_op` other than `Load` or \
+                `DontCare`",
+                attachment,
+            ),
+            Self::FragmentDensityMapAttachmentOutOfRange { attachment } => write!(
+                f,
+                "the fragment density map attachment {} does not exist",
+                attachment,
+            ),
+            Self::FragmentDensityMapAttachmentStoreOpInvalid { attachment } => write!(
+                f,
+                "the fragment density map attachment {} has a `store_op` other than `DontCare`",
+                attachment,
+            ),
+            Self::FragmentDensityMapAttachmentUsedInSubpass { attachment } => write!(
+                f,
+                "the fragment density map attachment {} is also used by a subpass",
+                attachment,
+            ),
             Self::SubpassAttachmentAspectsNotEmpty {
                 subpass,
                 attachment,
@@ -1747,6 +2297,15 @@ impl Display for RenderPassCreationError {
                 in subpass {}",
                 attachment, subpass,
             ),
+            Self::SubpassAttachmentUsageFragmentShadingRate {
+                subpass,
+                attachment,
+            } => write!(
+                f,
+                "attachment {} is used as both a fragment shading rate attachment and another \
+                attachment type in subpass {}",
+                attachment, subpass,
+            ),
             Self::SubpassAttachmentFormatUsageNotSupported {
                 subpass,
                 attachment,
@@ -1777,6 +2336,63 @@ impl Display for RenderPassCreationError {
                 `samples` value {:?} that is different from the first color attachment ({:?})",
                 attachment, subpass, samples, first_samples,
             ),
+            Self::SubpassDepthStencilAttachmentWithResolveNotMultisampled {
+                subpass,
+                attachment,
+            } => write!(
+                f,
+                "attachment {} used as a depth/stencil attachment in subpass {} has a depth/stencil \
+                resolve attachment, but its `samples` value is `SampleCount::Sample1`",
+                attachment, subpass,
+            ),
+            Self::SubpassDepthStencilResolveAttachmentFormatMismatch {
+                subpass,
+                resolve_attachment,
+                depth_stencil_attachment,
+            } => write!(
+                f,
+                "the depth or stencil component of the format of attachment {} used as a \
+                depth/stencil resolve attachment in subpass {} does not match that of attachment {} \
+                used as the depth/stencil attachment",
+                resolve_attachment, subpass, depth_stencil_attachment,
+            ),
+            Self::SubpassDepthStencilResolveAttachmentMultisampled {
+                subpass,
+                attachment,
+            } => write!(
+                f,
+                "attachment {} used as a depth/stencil resolve attachment in subpass {} has a \
+                `samples` value other than `SampleCount::Sample1`",
+                attachment, subpass,
+            ),
+            Self::SubpassDepthStencilResolveAttachmentWithoutDepthStencilAttachment { subpass } => {
+                write!(
+                    f,
+                    "subpass {} has a depth/stencil resolve attachment, but no depth/stencil \
+                    attachment",
+                    subpass,
+                )
+            }
+            Self::SubpassDepthStencilResolveModeNotSupported { subpass } => write!(
+                f,
+                "the depth/stencil resolve modes of subpass {} are not supported by the device",
+                subpass,
+            ),
+            Self::SubpassDepthStencilResolveModesNone { subpass } => write!(
+                f,
+                "subpass {} has a depth/stencil resolve attachment, but `depth_resolve_mode` and \
+                `stencil_resolve_mode` are both `None`",
+                subpass,
+            ),
+            Self::SubpassFragmentShadingRateTexelSizeNotSupported {
+                subpass,
+                texel_size,
+            } => write!(
+                f,
+                "the texel size {:?} of the fragment shading rate attachment of subpass {} is not \
+                supported by the device",
+                texel_size, subpass,
+            ),
             Self::SubpassInputAttachmentAspectsNotCompatible {
                 subpass,
                 attachment,
@@ -114,6 +114,11 @@ impl Framebuffer {
             }
         }
 
+        // Fragment shading rate and fragment density map attachments only need to cover the
+        // framebuffer extent divided by their texel size, so they are checked once the extent is
+        // known.
+        let mut texel_size_attachments: SmallVec<[_; 1]> = SmallVec::new();
+
         let attachments_vk = attachments
             .iter()
             .zip(render_pass.attachments())
@@ -122,7 +127,28 @@ impl Framebuffer {
                 let attachment_num = attachment_num as u32;
                 assert_eq!(device, image_view.device());
 
+                // The number of views of the subpasses that use the attachment.
+                let mut attachment_views_used = 0;
+                let mut attachment_texel_size = None;
+
                 for subpass in render_pass.subpasses() {
+                    let is_used = (subpass.color_attachments.iter())
+                        .chain(subpass.resolve_attachments.iter())
+                        .chain(subpass.input_attachments.iter())
+                        .chain(Some(&subpass.depth_stencil_attachment))
+                        .chain(Some(&subpass.depth_stencil_resolve_attachment))
+                        .flatten()
+                        .chain(
+                            (subpass.fragment_shading_rate_attachment.iter())
+                                .map(|atch| &atch.attachment_ref),
+                        )
+                        .any(|atch_ref| atch_ref.attachment == attachment_num);
+
+                    if is_used {
+                        attachment_views_used = attachment_views_used
+                            .max(u32::BITS - subpass.view_mask.leading_zeros());
+                    }
+
                     // VUID-VkFramebufferCreateInfo-pAttachments-00877
                     if subpass
                         .color_attachments
@@ -139,14 +165,30 @@ impl Framebuffer {
                     }
 
                     // VUID-VkFramebufferCreateInfo-pAttachments-02633
-                    if let Some(atch_ref) = &subpass.depth_stencil_attachment {
-                        if atch_ref.attachment == attachment_num {
-                            if !image_view.usage().depth_stencil_attachment {
+                    // VUID-VkFramebufferCreateInfo-pAttachments-02634
+                    if (subpass.depth_stencil_attachment.iter())
+                        .chain(subpass.depth_stencil_resolve_attachment.iter())
+                        .any(|atch_ref| atch_ref.attachment == attachment_num)
+                    {
+                        if !image_view.usage().depth_stencil_attachment {
+                            return Err(FramebufferCreationError::AttachmentMissingUsage {
+                                attachment: attachment_num,
+                                usage: "depth_stencil",
+                            });
+                        }
+                    }
+
+                    // VUID-VkFramebufferCreateInfo-flags-04548
+                    if let Some(atch) = &subpass.fragment_shading_rate_attachment {
+                        if atch.attachment_ref.attachment == attachment_num {
+                            if !image_view.usage().fragment_shading_rate_attachment {
                                 return Err(FramebufferCreationError::AttachmentMissingUsage {
                                     attachment: attachment_num,
-                                    usage: "depth_stencil",
+                                    usage: "fragment_shading_rate_attachment",
                                 });
                             }
+
+                            attachment_texel_size = Some(atch.texel_size);
                         }
                     }
 
@@ -166,6 +208,26 @@ impl Framebuffer {
                     }
                 }
 
+                if let Some(atch_ref) = render_pass.fragment_density_map_attachment() {
+                    if atch_ref.attachment == attachment_num {
+                        // VUID-VkFramebufferCreateInfo-pAttachments-02552
+                        if !image_view.usage().fragment_density_map {
+                            return Err(FramebufferCreationError::AttachmentMissingUsage {
+                                attachment: attachment_num,
+                                usage: "fragment_density_map",
+                            });
+                        }
+
+                        attachment_texel_size = Some(
+                            device
+                                .physical_device()
+                                .properties()
+                                .max_fragment_density_texel_size
+                                .unwrap_or([1, 1]),
+                        );
+                    }
+                }
+
                 // VUID-VkFramebufferCreateInfo-pAttachments-00880
                 if image_view.format() != attachment_desc.format {
                     return Err(FramebufferCreationError::AttachmentFormatMismatch {
@@ -189,19 +251,21 @@ impl Framebuffer {
                     - image_view.subresource_range().array_layers.start;
 
                 // VUID-VkFramebufferCreateInfo-renderPass-04536
-                if image_view_array_layers < render_pass.views_used() {
+                if image_view_array_layers < attachment_views_used {
                     return Err(
                         FramebufferCreationError::MultiviewAttachmentNotEnoughLayers {
                             attachment: attachment_num,
                             provided: image_view_array_layers,
-                            min: render_pass.views_used(),
+                            min: attachment_views_used,
                         },
                     );
                 }
 
                 // VUID-VkFramebufferCreateInfo-flags-04533
                 // VUID-VkFramebufferCreateInfo-flags-04534
-                if auto_extent {
+                if let Some(texel_size) = attachment_texel_size {
+                    texel_size_attachments.push((attachment_num, image_view_extent, texel_size));
+                } else if auto_extent {
                     extent[0] = extent[0].min(image_view_extent[0]);
                     extent[1] = extent[1].min(image_view_extent[1]);
                 } else if image_view_extent[0] < extent[0] || image_view_extent[1] < extent[1] {
@@ -269,6 +333,25 @@ impl Framebuffer {
             })
             .collect::<Result<SmallVec<[_; 4]>, _>>()?;
 
+        for (attachment_num, image_view_extent, texel_size) in texel_size_attachments {
+            let min = [
+                (extent[0] - 1) / texel_size[0] + 1,
+                (extent[1] - 1) / texel_size[1] + 1,
+            ];
+
+            // VUID-VkFramebufferCreateInfo-flags-04539
+            // VUID-VkFramebufferCreateInfo-flags-04540
+            // VUID-VkFramebufferCreateInfo-pAttachments-02555
+            // VUID-VkFramebufferCreateInfo-pAttachments-02556
+            if image_view_extent[0] < min[0] || image_view_extent[1] < min[1] {
+                return Err(FramebufferCreationError::AttachmentExtentTooSmall {
+                    attachment: attachment_num,
+                    provided: image_view_extent,
+                    min,
+                });
+            }
+        }
+
         {
             let properties = device.physical_device().properties();
 
@@ -450,10 +533,15 @@ pub struct FramebufferCreateInfo {
     ///
     /// The attachment images must not be smaller than `extent` and `layers`, but can be larger and
     /// have different sizes from each other. Any leftover parts of an image will be left untouched
-    /// during rendering.
+    /// during rendering. Images used as a fragment shading rate attachment only need to cover
+    /// `extent` divided by the `texel_size` of the attachment, rounded up. Likewise, an image used
+    /// as the fragment density map attachment only needs to cover `extent` divided by the
+    /// [`max_fragment_density_texel_size`](crate::device::Properties::max_fragment_density_texel_size)
+    /// device property, rounded up.
     ///
     /// If the render pass has multiview enabled (`views_used` does not return 0), then each
-    /// image must have at least `views_used` array layers.
+    /// image must have at least as many array layers as the number of views of the subpasses that
+    /// use it.
     ///
     /// The default value is empty.
     pub attachments: Vec<Arc<dyn ImageViewAbstract>>,
@@ -576,7 +664,7 @@ pub enum FramebufferCreationError {
     MaxFramebufferLayersExceeded { provided: u32, max: u32 },
 
     /// The render pass has multiview enabled, and an attachment image has less layers than the
-    /// number of views in the render pass.
+    /// number of views of the subpasses that use it.
     MultiviewAttachmentNotEnoughLayers {
         attachment: u32,
         provided: u32,
@@ -702,7 +790,7 @@ impl Display for FramebufferCreationError {
             } => write!(
                 f,
                 "the render pass has multiview enabled, and attachment image {} has less layers \
-                ({}) than the number of views in the render pass ({})",
+                ({}) than the number of views of the subpasses that use it ({})",
                 attachment, provided, min,
             ),
             Self::MultiviewLayersInvalid => write!(
@@ -723,8 +811,11 @@ impl From<VulkanError> for FramebufferCreationError {
 mod tests {
     use crate::{
         format::Format,
-        image::{attachment::AttachmentImage, view::ImageView},
-        render_pass::{Framebuffer, FramebufferCreateInfo, FramebufferCreationError, RenderPass},
+        image::{attachment::AttachmentImage, view::ImageView, ImageLayout},
+        render_pass::{
+            AttachmentDescription, AttachmentReference, Framebuffer, FramebufferCreateInfo,
+            FramebufferCreationError, RenderPass, RenderPassCreateInfo, SubpassDescription,
+        },
     };
 
     #[test]
@@ -1080,4 +1171,52 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[test]
+    fn multiview_attachment_not_enough_layers() {
+        let (device, _) = gfx_dev_and_queue!(multiview);
+
+        let render_pass = RenderPass::new(
+            device.clone(),
+            RenderPassCreateInfo {
+                attachments: vec![AttachmentDescription {
+                    format: Some(Format::R8G8B8A8_UNORM),
+                    initial_layout: ImageLayout::ColorAttachmentOptimal,
+                    final_layout: ImageLayout::ColorAttachmentOptimal,
+                    ..Default::default()
+                }],
+                subpasses: vec![SubpassDescription {
+                    view_mask: 0b11,
+                    color_attachments: vec![Some(AttachmentReference {
+                        attachment: 0,
+                        layout: ImageLayout::ColorAttachmentOptimal,
+                        ..Default::default()
+                    })],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let view = ImageView::new_default(
+            AttachmentImage::new(device, [256, 512], Format::R8G8B8A8_UNORM).unwrap(),
+        )
+        .unwrap();
+
+        match Framebuffer::new(
+            render_pass,
+            FramebufferCreateInfo {
+                attachments: vec![view],
+                ..Default::default()
+            },
+        ) {
+            Err(FramebufferCreationError::MultiviewAttachmentNotEnoughLayers {
+                attachment: 0,
+                provided: 1,
+                min: 2,
+            }) => (),
+            _ => panic!(),
+        }
+    }
 }
@@ -30,7 +30,7 @@ pub use self::{
     framebuffer::{Framebuffer, FramebufferCreateInfo, FramebufferCreationError},
 };
 use crate::{
-    device::{Device, DeviceOwned},
+    device::{Device, DeviceOwned, Properties},
     format::Format,
     image::{ImageAspects, ImageLayout, SampleCount},
     macros::{vulkan_bitflags, vulkan_enum},
@@ -113,6 +113,7 @@ pub struct RenderPass {
     subpasses: Vec<SubpassDescription>,
     dependencies: Vec<SubpassDependency>,
     correlated_view_masks: Vec<u32>,
+    fragment_density_map_attachment: Option<AttachmentReference>,
 
     granularity: [u32; 2],
     views_used: u32,
@@ -146,6 +147,7 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            fragment_density_map_attachment,
             _ne: _,
         } = create_info;
 
@@ -159,6 +161,7 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            fragment_density_map_attachment,
 
             granularity,
             views_used,
@@ -217,6 +220,7 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            fragment_density_map_attachment,
             _ne: _,
         } = create_info;
 
@@ -228,6 +232,7 @@ impl RenderPass {
             subpasses,
             dependencies,
             correlated_view_masks,
+            fragment_density_map_attachment,
 
             granularity,
             views_used,
@@ -258,6 +263,12 @@ impl RenderPass {
         &self.correlated_view_masks
     }
 
+    /// Returns the fragment density map attachment of the render pass, if any.
+    #[inline]
+    pub fn fragment_density_map_attachment(&self) -> Option<&AttachmentReference> {
+        self.fragment_density_map_attachment.as_ref()
+    }
+
     /// If the render pass has multiview enabled, returns the number of views used by the render
     /// pass. Returns 0 if multiview is not enabled.
     #[inline]
@@ -298,6 +309,7 @@ impl RenderPass {
             subpasses: subpasses1,
             dependencies: dependencies1,
             correlated_view_masks: correlated_view_masks1,
+            fragment_density_map_attachment: fragment_density_map_attachment1,
             granularity: _,
             views_used: _,
         } = self;
@@ -308,6 +320,7 @@ impl RenderPass {
             subpasses: subpasses2,
             dependencies: dependencies2,
             correlated_view_masks: correlated_view_masks2,
+            fragment_density_map_attachment: fragment_density_map_attachment2,
             granularity: _,
             views_used: _,
         } = other;
@@ -406,6 +419,10 @@ impl RenderPass {
                     color_attachments: color_attachments1,
                     resolve_attachments: resolve_attachments1,
                     depth_stencil_attachment: depth_stencil_attachment1,
+                    depth_stencil_resolve_attachment: depth_stencil_resolve_attachment1,
+                    depth_resolve_mode: _,
+                    stencil_resolve_mode: _,
+                    fragment_shading_rate_attachment: fragment_shading_rate_attachment1,
                     preserve_attachments: _,
                     _ne: _,
                 } = subpass1;
@@ -415,6 +432,10 @@ impl RenderPass {
                     color_attachments: color_attachments2,
                     resolve_attachments: resolve_attachments2,
                     depth_stencil_attachment: depth_stencil_attachment2,
+                    depth_stencil_resolve_attachment: depth_stencil_resolve_attachment2,
+                    depth_resolve_mode: _,
+                    stencil_resolve_mode: _,
+                    fragment_shading_rate_attachment: fragment_shading_rate_attachment2,
                     preserve_attachments: _,
                     _ne: _,
                 } = subpass2;
@@ -455,6 +476,32 @@ impl RenderPass {
                     return false;
                 }
 
+                if subpasses1.len() > 1
+                    && !are_atch_refs_compatible(
+                        depth_stencil_resolve_attachment1.as_ref(),
+                        depth_stencil_resolve_attachment2.as_ref(),
+                    )
+                {
+                    return false;
+                }
+
+                if !are_atch_refs_compatible(
+                    fragment_shading_rate_attachment1
+                        .as_ref()
+                        .map(|atch| &atch.attachment_ref),
+                    fragment_shading_rate_attachment2
+                        .as_ref()
+                        .map(|atch| &atch.attachment_ref),
+                ) || fragment_shading_rate_attachment1
+                    .as_ref()
+                    .map(|atch| atch.texel_size)
+                    != fragment_shading_rate_attachment2
+                        .as_ref()
+                        .map(|atch| atch.texel_size)
+                {
+                    return false;
+                }
+
                 if view_mask1 != view_mask2 {
                     return false;
                 }
@@ -473,6 +520,16 @@ impl RenderPass {
             return false;
         }
 
+        if fragment_density_map_attachment1
+            .as_ref()
+            .map(|atch_ref| atch_ref.attachment)
+            != fragment_density_map_attachment2
+                .as_ref()
+                .map(|atch_ref| atch_ref.attachment)
+        {
+            return false;
+        }
+
         true
     }
 
@@ -775,6 +832,20 @@ pub struct RenderPassCreateInfo {
     /// The default value is empty.
     pub correlated_view_masks: Vec<u32>,
 
+    /// The attachment that provides the fragment density map for the render pass, which
+    /// specifies the fragment density to use in each region of the render area.
+    ///
+    /// If set to `Some`, the
+    /// [`fragment_density_map`](crate::device::Features::fragment_density_map) feature must be
+    /// enabled on the device. The attachment must not be used by any subpass, and its `layout`
+    /// must be [`ImageLayout::FragmentDensityMapOptimal`] or [`ImageLayout::General`]. The
+    /// `aspects` of the reference are ignored. The attachment description must have a `load_op` of
+    /// [`LoadOp::Load`] or [`LoadOp::DontCare`], a `store_op` of [`StoreOp::DontCare`], and a
+    /// format that supports the `fragment_density_map` format feature.
+    ///
+    /// The default value is `None`.
+    pub fragment_density_map_attachment: Option<AttachmentReference>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -786,6 +857,7 @@ impl Default for RenderPassCreateInfo {
             subpasses: Vec::new(),
             dependencies: Vec::new(),
             correlated_view_masks: Vec::new(),
+            fragment_density_map_attachment: None,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -924,6 +996,60 @@ pub struct SubpassDescription {
     /// The default value is `None`.
     pub depth_stencil_attachment: Option<AttachmentReference>,
 
+    /// The attachment of the render pass that is to be used as the resolve attachment of
+    /// `depth_stencil_attachment` in this subpass.
+    ///
+    /// If set to `Some`, the device API version must be at least 1.2, or the
+    /// [`khr_depth_stencil_resolve`](crate::device::DeviceExtensions::khr_depth_stencil_resolve)
+    /// extension must be enabled on the device. `depth_stencil_attachment` must also be `Some`,
+    /// and must have a `samples` value other than [`SampleCount::Sample1`], while the resolve
+    /// attachment must have a `samples` value of [`SampleCount::Sample1`]. The depth and stencil
+    /// components of the resolve attachment's format must match those of the depth/stencil
+    /// attachment's format, and at least one of `depth_resolve_mode` and `stencil_resolve_mode`
+    /// must be `Some`.
+    ///
+    /// The default value is `None`.
+    pub depth_stencil_resolve_attachment: Option<AttachmentReference>,
+
+    /// How the depth component of `depth_stencil_attachment` is resolved into
+    /// `depth_stencil_resolve_attachment`. `None` means that the depth component is not resolved.
+    ///
+    /// If the format of the resolve attachment has a depth component, the mode must be included
+    /// in the
+    /// [`supported_depth_resolve_modes`](crate::device::Properties::supported_depth_resolve_modes)
+    /// device property. If the format has both a depth and a stencil component, and the
+    /// [`independent_resolve`](crate::device::Properties::independent_resolve) device property is
+    /// `false`, then `depth_resolve_mode` and `stencil_resolve_mode` must be equal, or one of them
+    /// must be `None` if the
+    /// [`independent_resolve_none`](crate::device::Properties::independent_resolve_none) device
+    /// property is `true`.
+    ///
+    /// The default value is `None`.
+    pub depth_resolve_mode: Option<ResolveMode>,
+
+    /// How the stencil component of `depth_stencil_attachment` is resolved into
+    /// `depth_stencil_resolve_attachment`. `None` means that the stencil component is not
+    /// resolved.
+    ///
+    /// If the format of the resolve attachment has a stencil component, the mode must be
+    /// included in the
+    /// [`supported_stencil_resolve_modes`](crate::device::Properties::supported_stencil_resolve_modes)
+    /// device property.
+    ///
+    /// The default value is `None`.
+    pub stencil_resolve_mode: Option<ResolveMode>,
+
+    /// The attachment of the render pass that is to be used as the fragment shading rate
+    /// attachment in this subpass.
+    ///
+    /// If set to `Some`, the
+    /// [`attachment_fragment_shading_rate`](crate::device::Features::attachment_fragment_shading_rate)
+    /// feature must be enabled on the device, and the referenced attachment must not be used as
+    /// any other attachment type in the subpass.
+    ///
+    /// The default value is `None`.
+    pub fragment_shading_rate_attachment: Option<FragmentShadingRateAttachment>,
+
     /// The indices of attachments of the render pass that will be preserved during this subpass.
     ///
     /// The referenced attachments must not be used as any other attachment type in the subpass.
@@ -941,6 +1067,10 @@ impl Default for SubpassDescription {
             view_mask: 0,
             color_attachments: Vec::new(),
             depth_stencil_attachment: None,
+            depth_stencil_resolve_attachment: None,
+            depth_resolve_mode: None,
+            stencil_resolve_mode: None,
+            fragment_shading_rate_attachment: None,
             input_attachments: Vec::new(),
             resolve_attachments: Vec::new(),
             preserve_attachments: Vec::new(),
@@ -949,6 +1079,74 @@ impl Default for SubpassDescription {
     }
 }
 
+/// A fragment shading rate attachment in a subpass description.
+///
+/// Each texel of the attachment specifies the fragment shading rate for an area of
+/// `texel_size` pixels of the framebuffer.
+#[derive(Clone, Debug)]
+pub struct FragmentShadingRateAttachment {
+    /// The reference to the attachment.
+    ///
+    /// The layout must be [`ImageLayout::General`] or
+    /// [`ImageLayout::FragmentShadingRateAttachmentOptimal`], and the format of the attachment
+    /// must support the `fragment_shading_rate_attachment` format feature.
+    pub attachment_ref: AttachmentReference,
+
+    /// The width and height of the area of the framebuffer, in pixels, that each texel of the
+    /// attachment corresponds to.
+    ///
+    /// Each value must be a power of two, and within the range of the
+    /// [`min_fragment_shading_rate_attachment_texel_size`](crate::device::Properties::min_fragment_shading_rate_attachment_texel_size)
+    /// and
+    /// [`max_fragment_shading_rate_attachment_texel_size`](crate::device::Properties::max_fragment_shading_rate_attachment_texel_size)
+    /// limits. The ratio between the two values must not exceed the
+    /// [`max_fragment_shading_rate_attachment_texel_size_aspect_ratio`](crate::device::Properties::max_fragment_shading_rate_attachment_texel_size_aspect_ratio)
+    /// limit.
+    pub texel_size: [u32; 2],
+}
+
+impl FragmentShadingRateAttachment {
+    /// Returns a `FragmentShadingRateAttachment` that references `attachment` in the
+    /// [`ImageLayout::FragmentShadingRateAttachmentOptimal`] layout.
+    #[inline]
+    pub fn new(attachment: u32, texel_size: [u32; 2]) -> Self {
+        Self {
+            attachment_ref: AttachmentReference {
+                attachment,
+                layout: ImageLayout::FragmentShadingRateAttachmentOptimal,
+                ..Default::default()
+            },
+            texel_size,
+        }
+    }
+}
+
+// Returns whether `texel_size` is supported as the texel size of a fragment shading rate
+// attachment, according to the limits in `properties`.
+pub(crate) fn is_fragment_shading_rate_texel_size_supported(
+    properties: &Properties,
+    texel_size: [u32; 2],
+) -> bool {
+    let min_texel_size = properties
+        .min_fragment_shading_rate_attachment_texel_size
+        .unwrap_or([1, 1]);
+    let max_texel_size = properties
+        .max_fragment_shading_rate_attachment_texel_size
+        .unwrap_or([1, 1]);
+    let max_aspect_ratio = properties
+        .max_fragment_shading_rate_attachment_texel_size_aspect_ratio
+        .unwrap_or(1);
+    let [width, height] = texel_size;
+
+    // The sizes are checked to be powers of two first, so they can't be zero when dividing.
+    (0..2).all(|i| {
+        texel_size[i].is_power_of_two()
+            && texel_size[i] >= min_texel_size[i]
+            && texel_size[i] <= max_texel_size[i]
+    }) && width / height <= max_aspect_ratio
+        && height / width <= max_aspect_ratio
+}
+
 /// A reference in a subpass description to a particular attachment of the render pass.
 #[derive(Clone, Debug)]
 pub struct AttachmentReference {
@@ -1233,7 +1431,12 @@ impl ResolveModes {
 mod tests {
     use crate::{
         format::Format,
-        render_pass::{RenderPass, RenderPassCreationError},
+        image::ImageLayout,
+        render_pass::{
+            is_fragment_shading_rate_texel_size_supported, AttachmentDescription,
+            AttachmentReference, FragmentShadingRateAttachment, RenderPass, RenderPassCreateInfo,
+            RenderPassCreationError, ResolveMode, SubpassDescription,
+        },
     };
 
     #[test]
@@ -1296,4 +1499,113 @@ mod tests {
         assert_ne!(granularity[0], 0);
         assert_ne!(granularity[1], 0);
     }
+
+    #[test]
+    fn depth_stencil_resolve_without_depth_stencil() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let rp = RenderPass::new(
+            device,
+            RenderPassCreateInfo {
+                attachments: vec![AttachmentDescription {
+                    format: Some(Format::D16_UNORM),
+                    initial_layout: ImageLayout::DepthStencilAttachmentOptimal,
+                    final_layout: ImageLayout::DepthStencilAttachmentOptimal,
+                    ..Default::default()
+                }],
+                subpasses: vec![SubpassDescription {
+                    depth_stencil_resolve_attachment: Some(AttachmentReference {
+                        attachment: 0,
+                        layout: ImageLayout::DepthStencilAttachmentOptimal,
+                        ..Default::default()
+                    }),
+                    depth_resolve_mode: Some(ResolveMode::SampleZero),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+
+        match rp {
+            Err(RenderPassCreationError::RequirementNotMet { .. }) => (),
+            Err(
+                RenderPassCreationError::SubpassDepthStencilResolveAttachmentWithoutDepthStencilAttachment {
+                    ..
+                },
+            ) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn fragment_shading_rate_attachment_without_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let rp = RenderPass::new(
+            device,
+            RenderPassCreateInfo {
+                attachments: vec![
+                    AttachmentDescription {
+                        format: Some(Format::R8G8B8A8_UNORM),
+                        ..Default::default()
+                    },
+                    AttachmentDescription {
+                        format: Some(Format::R8_UINT),
+                        initial_layout: ImageLayout::FragmentShadingRateAttachmentOptimal,
+                        final_layout: ImageLayout::FragmentShadingRateAttachmentOptimal,
+                        ..Default::default()
+                    },
+                ],
+                subpasses: vec![SubpassDescription {
+                    color_attachments: vec![Some(AttachmentReference {
+                        attachment: 0,
+                        layout: ImageLayout::ColorAttachmentOptimal,
+                        ..Default::default()
+                    })],
+                    fragment_shading_rate_attachment: Some(FragmentShadingRateAttachment::new(
+                        1,
+                        [16, 16],
+                    )),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        );
+
+        match rp {
+            Err(RenderPassCreationError::RequirementNotMet { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn fragment_shading_rate_texel_size() {
+        let (device, _) = gfx_dev_and_queue!();
+        let properties = device.physical_device().properties();
+
+        let (min_texel_size, max_texel_size) = match (
+            properties.min_fragment_shading_rate_attachment_texel_size,
+            properties.max_fragment_shading_rate_attachment_texel_size,
+        ) {
+            (Some(min), Some(max)) => (min, max),
+            _ => return, // test ignored
+        };
+
+        assert!(is_fragment_shading_rate_texel_size_supported(
+            properties,
+            min_texel_size,
+        ));
+        assert!(!is_fragment_shading_rate_texel_size_supported(
+            properties,
+            [max_texel_size[0] * 2, max_texel_size[1]],
+        ));
+        assert!(!is_fragment_shading_rate_texel_size_supported(
+            properties,
+            [max_texel_size[0] + 1, max_texel_size[1]],
+        ));
+        assert!(!is_fragment_shading_rate_texel_size_supported(
+            properties,
+            [0, max_texel_size[1]],
+        ));
+    }
 }
@@ -49,6 +49,7 @@ pub mod ycbcr;
 use self::ycbcr::SamplerYcbcrConversion;
 use crate::{
     device::{Device, DeviceOwned},
+    format::{ClearColorValue, Format},
     image::{view::ImageViewType, ImageViewAbstract},
     macros::vulkan_enum,
     pipeline::graphics::depth_stencil::CompareOp,
@@ -62,7 +63,7 @@ use std::{
     mem::MaybeUninit,
     ops::RangeInclusive,
     ptr,
-    sync::Arc,
+    sync::{atomic::Ordering, Arc},
 };
 
 /// Describes how to retrieve data from a sampled image within a shader.
@@ -102,6 +103,7 @@ pub struct Sampler {
     address_mode: [SamplerAddressMode; 3],
     anisotropy: Option<f32>,
     border_color: Option<BorderColor>,
+    custom_border_color: Option<SamplerCustomBorderColor>,
     compare: Option<CompareOp>,
     lod: RangeInclusive<f32>,
     mag_filter: Filter,
@@ -134,6 +136,7 @@ impl Sampler {
             compare,
             lod,
             border_color,
+            custom_border_color,
             unnormalized_coordinates,
             reduction_mode,
             sampler_ycbcr_conversion,
@@ -161,6 +164,60 @@ impl Sampler {
             }
         }
 
+        // The border color is only used, and the custom border color only passed to Vulkan, if
+        // an address mode is `ClampToBorder`.
+        let custom_border_color = if address_mode.contains(&SamplerAddressMode::ClampToBorder)
+            && matches!(
+                border_color,
+                BorderColor::FloatCustom | BorderColor::IntCustom
+            ) {
+            // VUID-VkSamplerCreateInfo-customBorderColors-04085
+            if !device.enabled_features().custom_border_colors {
+                return Err(SamplerCreationError::RequirementNotMet {
+                    required_for: "`create_info.border_color` is `BorderColor::FloatCustom` or `BorderColor::IntCustom`",
+                    requires_one_of: RequiresOneOf {
+                        features: &["custom_border_colors"],
+                        ..Default::default()
+                    },
+                });
+            }
+
+            // VUID-VkSamplerCreateInfo-borderColor-04011
+            let custom_border_color =
+                custom_border_color.ok_or(SamplerCreationError::CustomBorderColorMissing)?;
+
+            if !matches!(
+                (border_color, custom_border_color.color),
+                (BorderColor::FloatCustom, ClearColorValue::Float(_))
+                    | (
+                        BorderColor::IntCustom,
+                        ClearColorValue::Int(_) | ClearColorValue::Uint(_)
+                    )
+            ) {
+                return Err(SamplerCreationError::CustomBorderColorTypeMismatch { border_color });
+            }
+
+            if let Some(format) = custom_border_color.format {
+                // VUID-VkSamplerCustomBorderColorCreateInfoEXT-format-parameter
+                format.validate_device(&device)?;
+            } else {
+                // VUID-VkSamplerCustomBorderColorCreateInfoEXT-format-04014
+                if !device.enabled_features().custom_border_color_without_format {
+                    return Err(SamplerCreationError::RequirementNotMet {
+                        required_for: "`create_info.custom_border_color.format` is `None`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["custom_border_color_without_format"],
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+
+            Some(custom_border_color)
+        } else {
+            None
+        };
+
         if address_mode.contains(&SamplerAddressMode::MirrorClampToEdge) {
             if !device.enabled_features().sampler_mirror_clamp_to_edge
                 && !device.enabled_extensions().khr_sampler_mirror_clamp_to_edge
@@ -312,27 +369,32 @@ impl Sampler {
         {
             assert_eq!(&device, sampler_ycbcr_conversion.device());
 
-            // Use unchecked, because all validation has been done by the SamplerYcbcrConversion.
-            let potential_format_features = unsafe {
-                device
-                    .physical_device()
-                    .format_properties_unchecked(sampler_ycbcr_conversion.format().unwrap())
-                    .potential_format_features()
-            };
-
-            // VUID-VkSamplerCreateInfo-minFilter-01645
-            if !potential_format_features
-                .sampled_image_ycbcr_conversion_separate_reconstruction_filter
-                && !(mag_filter == sampler_ycbcr_conversion.chroma_filter()
-                    && min_filter == sampler_ycbcr_conversion.chroma_filter())
-            {
-                return Err(
-                    SamplerCreationError::SamplerYcbcrConversionChromaFilterMismatch {
-                        chroma_filter: sampler_ycbcr_conversion.chroma_filter(),
-                        mag_filter,
-                        min_filter,
-                    },
-                );
+            // The format features of an external format can't be queried, so this check is
+            // only done for conversions with a known format.
+            if let Some(format) = sampler_ycbcr_conversion.format() {
+                // Use unchecked, because all validation has been done by the
+                // SamplerYcbcrConversion.
+                let potential_format_features = unsafe {
+                    device
+                        .physical_device()
+                        .format_properties_unchecked(format)
+                        .potential_format_features()
+                };
+
+                // VUID-VkSamplerCreateInfo-minFilter-01645
+                if !potential_format_features
+                    .sampled_image_ycbcr_conversion_separate_reconstruction_filter
+                    && !(mag_filter == sampler_ycbcr_conversion.chroma_filter()
+                        && min_filter == sampler_ycbcr_conversion.chroma_filter())
+                {
+                    return Err(
+                        SamplerCreationError::SamplerYcbcrConversionChromaFilterMismatch {
+                            chroma_filter: sampler_ycbcr_conversion.chroma_filter(),
+                            mag_filter,
+                            min_filter,
+                        },
+                    );
+                }
             }
 
             // VUID-VkSamplerCreateInfo-addressModeU-01646
@@ -406,6 +468,41 @@ impl Sampler {
             create_info.p_next = sampler_ycbcr_conversion_info as *const _ as *const _;
         }
 
+        let mut sampler_custom_border_color_create_info =
+            custom_border_color.map(|custom_border_color| {
+                ash::vk::SamplerCustomBorderColorCreateInfoEXT {
+                    custom_border_color: custom_border_color.color.into(),
+                    format: custom_border_color
+                        .format
+                        .map_or(ash::vk::Format::UNDEFINED, Into::into),
+                    ..Default::default()
+                }
+            });
+
+        if let Some(sampler_custom_border_color_create_info) =
+            sampler_custom_border_color_create_info.as_mut()
+        {
+            sampler_custom_border_color_create_info.p_next = create_info.p_next;
+            create_info.p_next = sampler_custom_border_color_create_info as *const _ as *const _;
+
+            // VUID-VkSamplerCreateInfo-None-04012
+            let max_custom_border_color_samplers = device
+                .physical_device()
+                .properties()
+                .max_custom_border_color_samplers
+                .unwrap_or(0);
+            device
+                .custom_border_color_sampler_count
+                .fetch_update(Ordering::Acquire, Ordering::Relaxed, move |count| {
+                    (count < max_custom_border_color_samplers).then_some(count + 1)
+                })
+                .map_err(
+                    |_| SamplerCreationError::MaxCustomBorderColorSamplersExceeded {
+                        maximum: max_custom_border_color_samplers,
+                    },
+                )?;
+        }
+
         let handle = unsafe {
             let fns = device.fns();
             let mut output = MaybeUninit::uninit();
@@ -416,7 +513,15 @@ impl Sampler {
                 output.as_mut_ptr(),
             )
             .result()
-            .map_err(VulkanError::from)?;
+            .map_err(|err| {
+                if custom_border_color.is_some() {
+                    device
+                        .custom_border_color_sampler_count
+                        .fetch_sub(1, Ordering::Release);
+                }
+
+                VulkanError::from(err)
+            })?;
             output.assume_init()
         };
 
@@ -430,6 +535,7 @@ impl Sampler {
                 .into_iter()
                 .any(|mode| mode == SamplerAddressMode::ClampToBorder)
                 .then_some(border_color),
+            custom_border_color,
             compare,
             lod,
             mag_filter,
@@ -464,12 +570,27 @@ impl Sampler {
             compare,
             lod,
             border_color,
+            custom_border_color,
             unnormalized_coordinates,
             reduction_mode,
             sampler_ycbcr_conversion,
             _ne: _,
         } = create_info;
 
+        let custom_border_color = (address_mode.contains(&SamplerAddressMode::ClampToBorder)
+            && matches!(
+                border_color,
+                BorderColor::FloatCustom | BorderColor::IntCustom
+            ))
+        .then_some(custom_border_color)
+        .flatten();
+
+        if custom_border_color.is_some() {
+            device
+                .custom_border_color_sampler_count
+                .fetch_add(1, Ordering::Acquire);
+        }
+
         Arc::new(Sampler {
             handle,
             device,
@@ -480,6 +601,7 @@ impl Sampler {
                 .into_iter()
                 .any(|mode| mode == SamplerAddressMode::ClampToBorder)
                 .then_some(border_color),
+            custom_border_color,
             compare,
             lod,
             mag_filter,
@@ -515,7 +637,7 @@ impl Sampler {
             if !image_view.subresource_range().aspects.depth {
                 return Err(SamplerImageViewIncompatibleError::DepthComparisonWrongAspect);
             }
-        } else {
+        } else if self.reduction_mode == SamplerReductionMode::WeightedAverage {
             if !image_view.format_features().sampled_image_filter_linear {
                 // VUID-vkCmdDispatch-magFilter-04553
                 if self.mag_filter == Filter::Linear || self.min_filter == Filter::Linear {
@@ -527,6 +649,15 @@ impl Sampler {
                     return Err(SamplerImageViewIncompatibleError::MipmapModeLinearNotSupported);
                 }
             }
+        } else if !image_view.format_features().sampled_image_filter_minmax {
+            // VUID-vkCmdDispatch-magFilter-09598
+            // VUID-vkCmdDispatch-mipmapMode-09599
+            if self.mag_filter == Filter::Linear
+                || self.min_filter == Filter::Linear
+                || self.mipmap_mode == SamplerMipmapMode::Linear
+            {
+                return Err(SamplerImageViewIncompatibleError::FilterMinmaxNotSupported);
+            }
         }
 
         if self.mag_filter == Filter::Cubic || self.min_filter == Filter::Cubic {
@@ -566,10 +697,23 @@ impl Sampler {
                 },
             );
 
+            // The sampler has a custom border color with a format, and the image view has a
+            // different format.
+            if let Some(SamplerCustomBorderColor {
+                format: Some(format),
+                ..
+            }) = self.custom_border_color
+            {
+                if image_view.format() != Some(format) {
+                    return Err(SamplerImageViewIncompatibleError::BorderColorFormatNotCompatible);
+                }
+            }
+
             match border_color {
                 BorderColor::IntTransparentBlack
                 | BorderColor::IntOpaqueBlack
-                | BorderColor::IntOpaqueWhite => {
+                | BorderColor::IntOpaqueWhite
+                | BorderColor::IntCustom => {
                     // The sampler borderColor is an integer type and the image view
                     // format is not one of the VkFormat integer types or a stencil
                     // component of a depth/stencil format.
@@ -584,7 +728,8 @@ impl Sampler {
                 }
                 BorderColor::FloatTransparentBlack
                 | BorderColor::FloatOpaqueBlack
-                | BorderColor::FloatOpaqueWhite => {
+                | BorderColor::FloatOpaqueWhite
+                | BorderColor::FloatCustom => {
                     // The sampler borderColor is a float type and the image view
                     // format is not one of the VkFormat float types or a depth
                     // component of a depth/stencil format.
@@ -614,6 +759,22 @@ impl Sampler {
             }
         }
 
+        // VUID-VkWriteDescriptorSet-descriptorType-01948
+        let conversions_match = match (
+            self.sampler_ycbcr_conversion.as_ref(),
+            image_view.sampler_ycbcr_conversion(),
+        ) {
+            (None, None) => true,
+            (Some(sampler_conversion), Some(image_view_conversion)) => {
+                sampler_conversion.is_identical(image_view_conversion)
+            }
+            _ => false,
+        };
+
+        if !conversions_match {
+            return Err(SamplerImageViewIncompatibleError::SamplerYcbcrConversionMismatch);
+        }
+
         // The sampler unnormalizedCoordinates is VK_TRUE and any of the limitations of
         // unnormalized coordinates are violated.
         // https://registry.khronos.org/vulkan/specs/1.2-extensions/html/chap13.html#samplers-unnormalizedCoordinates
@@ -662,6 +823,12 @@ impl Sampler {
         self.border_color
     }
 
+    /// Returns the custom border color if one is used by this sampler.
+    #[inline]
+    pub fn custom_border_color(&self) -> Option<SamplerCustomBorderColor> {
+        self.custom_border_color
+    }
+
     /// Returns the compare operation if the sampler is a compare-mode sampler.
     #[inline]
     pub fn compare(&self) -> Option<CompareOp> {
@@ -724,6 +891,12 @@ impl Drop for Sampler {
             let fns = self.device.fns();
             (fns.v1_0.destroy_sampler)(self.device.internal_object(), self.handle, ptr::null());
         }
+
+        if self.custom_border_color.is_some() {
+            self.device
+                .custom_border_color_sampler_count
+                .fetch_sub(1, Ordering::Release);
+        }
     }
 }
 
@@ -785,6 +958,19 @@ pub enum SamplerCreationError {
         reduction_mode: SamplerReductionMode,
     },
 
+    /// The border color is `FloatCustom` or `IntCustom`, but no custom border color was provided.
+    CustomBorderColorMissing,
+
+    /// The numeric type of the custom border color does not match the border color.
+    CustomBorderColorTypeMismatch { border_color: BorderColor },
+
+    /// The maximum number of samplers with a custom border color that can exist at the same time
+    /// on the device has been exceeded.
+    MaxCustomBorderColorSamplersExceeded {
+        /// The maximum supported value.
+        maximum: u32,
+    },
+
     /// The requested anisotropy level exceeds the device's limits.
     MaxSamplerAnisotropyExceeded {
         /// The value that was requested.
@@ -882,6 +1068,22 @@ impl Display for SamplerCreationError {
                 f,
                 "depth comparison was enabled with an invalid reduction mode",
             ),
+            Self::CustomBorderColorMissing => write!(
+                f,
+                "the border color is `FloatCustom` or `IntCustom`, but no custom border color was \
+                provided",
+            ),
+            Self::CustomBorderColorTypeMismatch { border_color } => write!(
+                f,
+                "the numeric type of the custom border color does not match the border color \
+                ({:?})",
+                border_color,
+            ),
+            Self::MaxCustomBorderColorSamplersExceeded { maximum } => write!(
+                f,
+                "max_custom_border_color_samplers limit ({}) exceeded",
+                maximum,
+            ),
             Self::MaxSamplerAnisotropyExceeded { .. } => {
                 write!(f, "max_sampler_anisotropy limit exceeded")
             }
@@ -1043,6 +1245,13 @@ pub struct SamplerCreateInfo {
     /// The default value is [`FloatTransparentBlack`](BorderColor::FloatTransparentBlack).
     pub border_color: BorderColor,
 
+    /// The border color to use if `border_color` is [`FloatCustom`](BorderColor::FloatCustom) or
+    /// [`IntCustom`](BorderColor::IntCustom). It must be `Some` in that case, and is ignored
+    /// otherwise.
+    ///
+    /// The default value is `None`.
+    pub custom_border_color: Option<SamplerCustomBorderColor>,
+
     /// Whether unnormalized texture coordinates are enabled.
     ///
     /// When a sampler is set to use unnormalized coordinates as input, the texture coordinates are
@@ -1108,6 +1317,7 @@ impl Default for SamplerCreateInfo {
             compare: None,
             lod: 0.0..=0.0,
             border_color: BorderColor::FloatTransparentBlack,
+            custom_border_color: None,
             unnormalized_coordinates: false,
             reduction_mode: SamplerReductionMode::WeightedAverage,
             sampler_ycbcr_conversion: None,
@@ -1404,17 +1614,67 @@ vulkan_enum! {
     /// The value `(1, 1, 1, 1)`. Can only be used with integer images.
     IntOpaqueWhite = INT_OPAQUE_WHITE,
 
-    /*
-    // TODO: document
-    FloatCustom = VK_BORDER_COLOR_FLOAT_CUSTOM_EXT {
+    /// A floating-point value given by the `custom_border_color` of the sampler.
+    ///
+    /// The [`custom_border_colors`](crate::device::Features::custom_border_colors) feature must
+    /// be enabled on the device.
+    FloatCustom = FLOAT_CUSTOM_EXT {
         device_extensions: [ext_custom_border_color],
     },
 
-    // TODO: document
+    /// An integer value given by the `custom_border_color` of the sampler.
+    ///
+    /// The [`custom_border_colors`](crate::device::Features::custom_border_colors) feature must
+    /// be enabled on the device.
     IntCustom = INT_CUSTOM_EXT {
         device_extensions: [ext_custom_border_color],
     },
-     */
+}
+
+/// A custom border color for a sampler.
+///
+/// The number of samplers with a custom border color that can exist at the same time on a device
+/// is limited by the
+/// [`max_custom_border_color_samplers`](crate::device::Properties::max_custom_border_color_samplers)
+/// limit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SamplerCustomBorderColor {
+    /// The border color value.
+    ///
+    /// This must be a [`Float`](ClearColorValue::Float) value if the sampler's `border_color` is
+    /// [`FloatCustom`](BorderColor::FloatCustom), and an [`Int`](ClearColorValue::Int) or
+    /// [`Uint`](ClearColorValue::Uint) value if it is [`IntCustom`](BorderColor::IntCustom).
+    pub color: ClearColorValue,
+
+    /// The format of the image views that the sampler will be used with, or `None` if it can be
+    /// used with image views of any format.
+    ///
+    /// If set to `None`, the
+    /// [`custom_border_color_without_format`](crate::device::Features::custom_border_color_without_format)
+    /// feature must be enabled on the device.
+    pub format: Option<Format>,
+}
+
+impl SamplerCustomBorderColor {
+    /// Returns a `SamplerCustomBorderColor` with the given color, that can be used with image
+    /// views of `format`.
+    #[inline]
+    pub fn with_format(color: ClearColorValue, format: Format) -> Self {
+        Self {
+            color,
+            format: Some(format),
+        }
+    }
+
+    /// Returns a `SamplerCustomBorderColor` with the given color, that can be used with image
+    /// views of any format.
+    #[inline]
+    pub fn without_format(color: ClearColorValue) -> Self {
+        Self {
+            color,
+            format: None,
+        }
+    }
 }
 
 vulkan_enum! {
@@ -1471,10 +1731,17 @@ pub enum SamplerImageViewIncompatibleError {
     /// supported by the image view's format features.
     FilterCubicMinmaxNotSupported,
 
+    /// The sampler uses a linear filter or mipmap mode with a `Min` or `Max` reduction mode, but
+    /// this is not supported by the image view's format features.
+    FilterMinmaxNotSupported,
+
     /// The sampler uses a linear mipmap mode, but this is not supported by the image view's format
     /// features.
     MipmapModeLinearNotSupported,
 
+    /// The sampler and the image view do not use the same sampler YCbCr conversion.
+    SamplerYcbcrConversionMismatch,
+
     /// The sampler uses unnormalized coordinates, but the image view has multiple mip levels.
     UnnormalizedCoordinatesMultipleMipLevels,
 
@@ -1522,11 +1789,20 @@ impl Display for SamplerImageViewIncompatibleError {
                 "the sampler uses a cubic filter with a `Min` or `Max` reduction mode, but this is \
                 not supported by the image view's format features",
             ),
+            Self::FilterMinmaxNotSupported => write!(
+                f,
+                "the sampler uses a linear filter or mipmap mode with a `Min` or `Max` reduction \
+                mode, but this is not supported by the image view's format features",
+            ),
             Self::MipmapModeLinearNotSupported => write!(
                 f,
                 "the sampler uses a linear mipmap mode, but this is not supported by the image \
                 view's format features",
             ),
+            Self::SamplerYcbcrConversionMismatch => write!(
+                f,
+                "the sampler and the image view do not use the same sampler YCbCr conversion",
+            ),
             Self::UnnormalizedCoordinatesMultipleMipLevels => write!(
                 f,
                 "the sampler uses unnormalized coordinates, but the image view has multiple mip \
@@ -1544,10 +1820,11 @@ impl Display for SamplerImageViewIncompatibleError {
 #[cfg(test)]
 mod tests {
     use crate::{
+        format::ClearColorValue,
         pipeline::graphics::depth_stencil::CompareOp,
         sampler::{
-            Filter, Sampler, SamplerAddressMode, SamplerCreateInfo, SamplerCreationError,
-            SamplerReductionMode,
+            BorderColor, Filter, Sampler, SamplerAddressMode, SamplerCreateInfo,
+            SamplerCreationError, SamplerCustomBorderColor, SamplerReductionMode,
         },
         RequiresOneOf,
     };
@@ -1792,4 +2069,32 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[test]
+    fn custom_border_color_extension() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        let r = Sampler::new(
+            device,
+            SamplerCreateInfo {
+                address_mode: [SamplerAddressMode::ClampToBorder; 3],
+                border_color: BorderColor::FloatCustom,
+                custom_border_color: Some(SamplerCustomBorderColor::without_format(
+                    ClearColorValue::Float([1.0, 0.0, 1.0, 1.0]),
+                )),
+                ..Default::default()
+            },
+        );
+
+        match r {
+            Err(SamplerCreationError::RequirementNotMet {
+                requires_one_of:
+                    RequiresOneOf {
+                        device_extensions, ..
+                    },
+                ..
+            }) if device_extensions.contains(&"ext_custom_border_color") => {}
+            _ => panic!(),
+        }
+    }
 }
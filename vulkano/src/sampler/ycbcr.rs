@@ -18,7 +18,10 @@
 //! A sampler YCbCr conversion can only be used with a combined image sampler descriptor in a
 //! descriptor set. The conversion must be attached on both the image view and sampler in the
 //! descriptor, and the sampler must be included in the descriptor set layout as an immutable
-//! sampler.
+//! sampler. [`DescriptorSetLayoutBinding::immutable_ycbcr_sampler`] creates such a sampler and
+//! layout binding in one step.
+//!
+//! [`DescriptorSetLayoutBinding::immutable_ycbcr_sampler`]: crate::descriptor_set::layout::DescriptorSetLayoutBinding::immutable_ycbcr_sampler
 //!
 //! # Examples
 //!
@@ -29,11 +32,11 @@
 //! # let descriptor_set_allocator: vulkano::descriptor_set::allocator::StandardDescriptorSetAllocator = return;
 //! # let mut command_buffer_builder: vulkano::command_buffer::AutoCommandBufferBuilder<vulkano::command_buffer::PrimaryAutoCommandBuffer> = return;
 //! use vulkano::descriptor_set::{PersistentDescriptorSet, WriteDescriptorSet};
-//! use vulkano::descriptor_set::layout::{DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo, DescriptorType};
+//! use vulkano::descriptor_set::layout::{DescriptorSetLayout, DescriptorSetLayoutBinding, DescriptorSetLayoutCreateInfo};
 //! use vulkano::format::Format;
 //! use vulkano::image::{ImmutableImage, ImageCreateFlags, ImageDimensions, ImageUsage, MipmapsCount};
 //! use vulkano::image::view::{ImageView, ImageViewCreateInfo};
-//! use vulkano::sampler::{Sampler, SamplerCreateInfo};
+//! use vulkano::sampler::SamplerCreateInfo;
 //! use vulkano::sampler::ycbcr::{SamplerYcbcrConversion, SamplerYcbcrConversionCreateInfo, SamplerYcbcrModelConversion};
 //! use vulkano::shader::ShaderStage;
 //!
@@ -44,10 +47,11 @@
 //! })
 //! .unwrap();
 //!
-//! let sampler = Sampler::new(device.clone(), SamplerCreateInfo {
-//!     sampler_ycbcr_conversion: Some(conversion.clone()),
-//!     ..Default::default()
-//! })
+//! // Creates the immutable sampler with the conversion attached.
+//! let binding = DescriptorSetLayoutBinding::immutable_ycbcr_sampler(
+//!     conversion.clone(),
+//!     SamplerCreateInfo::default(),
+//! )
 //! .unwrap();
 //!
 //! let descriptor_set_layout = DescriptorSetLayout::new(
@@ -57,8 +61,7 @@
 //!             0,
 //!             DescriptorSetLayoutBinding {
 //!                 stages: ShaderStage::Fragment.into(),
-//!                 immutable_samplers: vec![sampler],
-//!                 ..DescriptorSetLayoutBinding::descriptor_type(DescriptorType::CombinedImageSampler)
+//!                 ..binding
 //!             },
 //!         )]
 //!         .into(),
@@ -110,6 +113,7 @@ pub struct SamplerYcbcrConversion {
     device: Arc<Device>,
 
     format: Option<Format>,
+    external_format: Option<u64>,
     ycbcr_model: SamplerYcbcrModelConversion,
     ycbcr_range: SamplerYcbcrRange,
     component_mapping: ComponentMapping,
@@ -129,6 +133,7 @@ impl SamplerYcbcrConversion {
     ) -> Result<Arc<SamplerYcbcrConversion>, SamplerYcbcrConversionCreationError> {
         let SamplerYcbcrConversionCreateInfo {
             format,
+            external_format,
             ycbcr_model,
             ycbcr_range,
             component_mapping,
@@ -148,15 +153,33 @@ impl SamplerYcbcrConversion {
             });
         }
 
-        let format = match format {
-            Some(f) => f,
-            None => {
+        match (format, external_format) {
+            (None, None) => {
                 return Err(SamplerYcbcrConversionCreationError::FormatMissing);
             }
-        };
-
-        // VUID-VkSamplerYcbcrConversionCreateInfo-format-parameter
-        format.validate_device(&device)?;
+            (Some(_), Some(_)) => {
+                // VUID-VkSamplerYcbcrConversionCreateInfo-format-01904
+                return Err(SamplerYcbcrConversionCreationError::FormatAndExternalFormat);
+            }
+            (None, Some(_)) => {
+                if !device
+                    .enabled_extensions()
+                    .android_external_memory_android_hardware_buffer
+                {
+                    return Err(SamplerYcbcrConversionCreationError::RequirementNotMet {
+                        required_for: "`create_info.external_format` is `Some`",
+                        requires_one_of: RequiresOneOf {
+                            device_extensions: &["android_external_memory_android_hardware_buffer"],
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+            (Some(format), None) => {
+                // VUID-VkSamplerYcbcrConversionCreateInfo-format-parameter
+                format.validate_device(&device)?;
+            }
+        }
 
         // VUID-VkSamplerYcbcrConversionCreateInfo-ycbcrModel-parameter
         ycbcr_model.validate_device(&device)?;
@@ -185,6 +208,93 @@ impl SamplerYcbcrConversion {
         // VUID-VkSamplerYcbcrConversionCreateInfo-chromaFilter-parameter
         chroma_filter.validate_device(&device)?;
 
+        // The format features of an external format are only known from the `AHardwareBuffer`
+        // that it was queried from, so only the checks that don't depend on them can be done.
+        if let Some(format) = format {
+            Self::validate_format(
+                &device,
+                format,
+                ycbcr_model,
+                ycbcr_range,
+                component_mapping,
+                chroma_offset,
+                chroma_filter,
+                force_explicit_reconstruction,
+            )?;
+        } else if chroma_filter == Filter::Cubic {
+            return Err(SamplerYcbcrConversionCreationError::CubicFilterNotSupported);
+        }
+
+        let mut create_info = ash::vk::SamplerYcbcrConversionCreateInfo {
+            format: format.map_or(ash::vk::Format::UNDEFINED, Into::into),
+            ycbcr_model: ycbcr_model.into(),
+            ycbcr_range: ycbcr_range.into(),
+            components: component_mapping.into(),
+            x_chroma_offset: chroma_offset[0].into(),
+            y_chroma_offset: chroma_offset[1].into(),
+            chroma_filter: chroma_filter.into(),
+            force_explicit_reconstruction: force_explicit_reconstruction as ash::vk::Bool32,
+            ..Default::default()
+        };
+
+        let mut external_format_vk = None;
+
+        if let Some(external_format) = external_format {
+            let next = external_format_vk.insert(ash::vk::ExternalFormatANDROID {
+                external_format,
+                ..Default::default()
+            });
+
+            next.p_next = create_info.p_next as *mut _;
+            create_info.p_next = next as *const _ as *const _;
+        }
+
+        let handle = unsafe {
+            let fns = device.fns();
+            let create_sampler_ycbcr_conversion = if device.api_version() >= Version::V1_1 {
+                fns.v1_1.create_sampler_ycbcr_conversion
+            } else {
+                fns.khr_sampler_ycbcr_conversion
+                    .create_sampler_ycbcr_conversion_khr
+            };
+
+            let mut output = MaybeUninit::uninit();
+            create_sampler_ycbcr_conversion(
+                device.internal_object(),
+                &create_info,
+                ptr::null(),
+                output.as_mut_ptr(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+            output.assume_init()
+        };
+
+        Ok(Arc::new(SamplerYcbcrConversion {
+            handle,
+            device,
+            format,
+            external_format,
+            ycbcr_model,
+            ycbcr_range,
+            component_mapping,
+            chroma_offset,
+            chroma_filter,
+            force_explicit_reconstruction,
+        }))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn validate_format(
+        device: &Device,
+        format: Format,
+        ycbcr_model: SamplerYcbcrModelConversion,
+        ycbcr_range: SamplerYcbcrRange,
+        component_mapping: ComponentMapping,
+        chroma_offset: [ChromaLocation; 2],
+        chroma_filter: Filter,
+        force_explicit_reconstruction: bool,
+    ) -> Result<(), SamplerYcbcrConversionCreationError> {
         // VUID-VkSamplerYcbcrConversionCreateInfo-format-04061
         if !format
             .type_color()
@@ -223,7 +333,11 @@ impl SamplerYcbcrConversion {
                         // VUID-VkSamplerYcbcrConversionCreateInfo-xChromaOffset-01651
                         if !potential_format_features.cosited_chroma_samples {
                             return Err(
-                                SamplerYcbcrConversionCreationError::FormatChromaOffsetNotSupported,
+                                SamplerYcbcrConversionCreationError::FormatChromaOffsetNotSupported {
+                                    format,
+                                    chroma_offset: ChromaLocation::CositedEven,
+                                    supported_chroma_offset: ChromaLocation::Midpoint,
+                                },
                             );
                         }
                     }
@@ -231,7 +345,11 @@ impl SamplerYcbcrConversion {
                         // VUID-VkSamplerYcbcrConversionCreateInfo-xChromaOffset-01652
                         if !potential_format_features.midpoint_chroma_samples {
                             return Err(
-                                SamplerYcbcrConversionCreationError::FormatChromaOffsetNotSupported,
+                                SamplerYcbcrConversionCreationError::FormatChromaOffsetNotSupported {
+                                    format,
+                                    chroma_offset: ChromaLocation::Midpoint,
+                                    supported_chroma_offset: ChromaLocation::CositedEven,
+                                },
                             );
                         }
                     }
@@ -312,50 +430,7 @@ impl SamplerYcbcrConversion {
             }
         }
 
-        let create_info = ash::vk::SamplerYcbcrConversionCreateInfo {
-            format: format.into(),
-            ycbcr_model: ycbcr_model.into(),
-            ycbcr_range: ycbcr_range.into(),
-            components: component_mapping.into(),
-            x_chroma_offset: chroma_offset[0].into(),
-            y_chroma_offset: chroma_offset[1].into(),
-            chroma_filter: chroma_filter.into(),
-            force_explicit_reconstruction: force_explicit_reconstruction as ash::vk::Bool32,
-            ..Default::default()
-        };
-
-        let handle = unsafe {
-            let fns = device.fns();
-            let create_sampler_ycbcr_conversion = if device.api_version() >= Version::V1_1 {
-                fns.v1_1.create_sampler_ycbcr_conversion
-            } else {
-                fns.khr_sampler_ycbcr_conversion
-                    .create_sampler_ycbcr_conversion_khr
-            };
-
-            let mut output = MaybeUninit::uninit();
-            create_sampler_ycbcr_conversion(
-                device.internal_object(),
-                &create_info,
-                ptr::null(),
-                output.as_mut_ptr(),
-            )
-            .result()
-            .map_err(VulkanError::from)?;
-            output.assume_init()
-        };
-
-        Ok(Arc::new(SamplerYcbcrConversion {
-            handle,
-            device,
-            format: Some(format),
-            ycbcr_model,
-            ycbcr_range,
-            component_mapping,
-            chroma_offset,
-            chroma_filter,
-            force_explicit_reconstruction,
-        }))
+        Ok(())
     }
 
     /// Creates a new `SamplerYcbcrConversion` from a raw object handle.
@@ -364,7 +439,7 @@ impl SamplerYcbcrConversion {
     ///
     /// - `handle` must be a valid Vulkan object handle created from `device`.
     /// - `create_info` must match the info used to create the object.
-    /// - `create_info.format` must be `Some`.
+    /// - Exactly one of `create_info.format` and `create_info.external_format` must be `Some`.
     #[inline]
     pub unsafe fn from_handle(
         device: Arc<Device>,
@@ -373,6 +448,7 @@ impl SamplerYcbcrConversion {
     ) -> Arc<SamplerYcbcrConversion> {
         let SamplerYcbcrConversionCreateInfo {
             format,
+            external_format,
             ycbcr_model,
             ycbcr_range,
             component_mapping,
@@ -386,6 +462,7 @@ impl SamplerYcbcrConversion {
             handle,
             device,
             format,
+            external_format,
             ycbcr_model,
             ycbcr_range,
             component_mapping,
@@ -420,11 +497,19 @@ impl SamplerYcbcrConversion {
     }
 
     /// Returns the format that the conversion was created for.
+    ///
+    /// This is `None` if the conversion was created for an external format.
     #[inline]
     pub fn format(&self) -> Option<Format> {
         self.format
     }
 
+    /// Returns the Android external format that the conversion was created for, if any.
+    #[inline]
+    pub fn external_format(&self) -> Option<u64> {
+        self.external_format
+    }
+
     /// Returns the YCbCr model of the conversion.
     #[inline]
     pub fn ycbcr_model(&self) -> SamplerYcbcrModelConversion {
@@ -445,6 +530,7 @@ impl SamplerYcbcrConversion {
                 handle: _,
                 device: _,
                 format,
+                external_format,
                 ycbcr_model,
                 ycbcr_range,
                 component_mapping,
@@ -454,6 +540,7 @@ impl SamplerYcbcrConversion {
             } = self;
 
             format == other.format
+                && external_format == other.external_format
                 && ycbcr_model == other.ycbcr_model
                 && ycbcr_range == other.ycbcr_range
                 && component_mapping == other.component_mapping
@@ -534,6 +621,9 @@ pub enum SamplerYcbcrConversionCreationError {
     /// No format was specified when one was required.
     FormatMissing,
 
+    /// Both `format` and `external_format` were `Some`.
+    FormatAndExternalFormat,
+
     /// The format has a color type other than `UNORM`.
     FormatNotUnorm,
 
@@ -541,7 +631,14 @@ pub enum SamplerYcbcrConversionCreationError {
     FormatNotSupported,
 
     /// The format does not support the chosen chroma offsets.
-    FormatChromaOffsetNotSupported,
+    ///
+    /// Every format that supports sampler YCbCr conversion supports at least one of the chroma
+    /// locations, so `supported_chroma_offset` can be used instead.
+    FormatChromaOffsetNotSupported {
+        format: Format,
+        chroma_offset: ChromaLocation,
+        supported_chroma_offset: ChromaLocation,
+    },
 
     /// The component mapping was not valid for use with the chosen format.
     FormatInvalidComponentMapping,
@@ -587,15 +684,25 @@ impl Display for SamplerYcbcrConversionCreationError {
             Self::FormatMissing => {
                 write!(f, "no format was specified when one was required")
             }
+            Self::FormatAndExternalFormat => {
+                write!(f, "both `format` and `external_format` were `Some`")
+            }
             Self::FormatNotUnorm => {
                 write!(f, "the format has a color type other than `UNORM`")
             }
             Self::FormatNotSupported => {
                 write!(f, "the format does not support sampler YCbCr conversion")
             }
-            Self::FormatChromaOffsetNotSupported => {
-                write!(f, "the format does not support the chosen chroma offsets")
-            }
+            Self::FormatChromaOffsetNotSupported {
+                format,
+                chroma_offset,
+                supported_chroma_offset,
+            } => write!(
+                f,
+                "the format {:?} does not support `{:?}` chroma offsets; use \
+                `chroma_offset: [ChromaLocation::{:?}; 2]` instead",
+                format, chroma_offset, supported_chroma_offset,
+            ),
             Self::FormatInvalidComponentMapping => write!(
                 f,
                 "the component mapping was not valid for use with the chosen format",
@@ -664,12 +771,27 @@ pub struct SamplerYcbcrConversionCreateInfo {
     /// - `a` must be identity swizzled or `Zero` or `One`.
     /// - `r` and `b` must be identity swizzled or mapped to each other.
     ///
-    /// Compatibility notice: currently, this value must be `Some`, but future additions may allow
-    /// `None` as a valid value as well.
+    /// This must be `Some` if `external_format` is `None`, and `None` otherwise.
     ///
     /// The default value is `None`.
     pub format: Option<Format>,
 
+    /// An Android external format, as returned in
+    /// [`AndroidHardwareBufferProperties::external_format`], that this conversion will read data
+    /// from instead of `format`.
+    ///
+    /// If set to `Some`, the
+    /// [`android_external_memory_android_hardware_buffer`](crate::device::DeviceExtensions::android_external_memory_android_hardware_buffer)
+    /// extension must be enabled on the device. The format features of an external format are
+    /// not known to vulkano, so the requirements on `chroma_offset`, `chroma_filter` and
+    /// `force_explicit_reconstruction` that would otherwise be checked against `format` are the
+    /// responsibility of the caller.
+    ///
+    /// The default value is `None`.
+    ///
+    /// [`AndroidHardwareBufferProperties::external_format`]: crate::device::AndroidHardwareBufferProperties::external_format
+    pub external_format: Option<u64>,
+
     /// The conversion between the input color model and the output RGB color model.
     ///
     /// If this is not set to `RgbIdentity`, then the `r`, `g` and `b` components of
@@ -727,6 +849,7 @@ impl Default for SamplerYcbcrConversionCreateInfo {
     fn default() -> Self {
         Self {
             format: None,
+            external_format: None,
             ycbcr_model: SamplerYcbcrModelConversion::RgbIdentity,
             ycbcr_range: SamplerYcbcrRange::ItuFull,
             component_mapping: ComponentMapping::identity(),
@@ -792,8 +915,11 @@ vulkan_enum! {
 
 #[cfg(test)]
 mod tests {
-    use super::{SamplerYcbcrConversion, SamplerYcbcrConversionCreationError};
-    use crate::RequiresOneOf;
+    use super::{
+        SamplerYcbcrConversion, SamplerYcbcrConversionCreateInfo,
+        SamplerYcbcrConversionCreationError,
+    };
+    use crate::{format::Format, RequiresOneOf};
 
     #[test]
     fn feature_not_enabled() {
@@ -809,4 +935,23 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[test]
+    fn format_and_external_format() {
+        let (device, _queue) = gfx_dev_and_queue!(sampler_ycbcr_conversion);
+
+        let r = SamplerYcbcrConversion::new(
+            device,
+            SamplerYcbcrConversionCreateInfo {
+                format: Some(Format::G8_B8_R8_3PLANE_420_UNORM),
+                external_format: Some(1),
+                ..Default::default()
+            },
+        );
+
+        match r {
+            Err(SamplerYcbcrConversionCreationError::FormatAndExternalFormat) => (),
+            _ => panic!(),
+        }
+    }
 }
@@ -18,12 +18,16 @@
 //! wraps around vulkano's shaders API.
 
 use crate::{
+    buffer::BufferContents,
     descriptor_set::layout::DescriptorType,
     device::Device,
     format::{Format, NumericType},
     image::view::ImageViewType,
     macros::{vulkan_bitflags, vulkan_enum},
-    pipeline::{graphics::input_assembly::PrimitiveTopology, layout::PushConstantRange},
+    pipeline::{
+        graphics::input_assembly::PrimitiveTopology,
+        layout::{PipelineLayout, PushConstantRange},
+    },
     shader::spirv::{Capability, Spirv, SpirvError},
     sync::PipelineStages,
     DeviceSize, OomError, Version, VulkanError, VulkanObject,
@@ -53,6 +57,7 @@ include!(concat!(env!("OUT_DIR"), "/spirv_reqs.rs"));
 pub struct ShaderModule {
     handle: ash::vk::ShaderModule,
     device: Arc<Device>,
+    spirv_capabilities: Vec<Capability>,
     entry_points: HashMap<String, HashMap<ExecutionModel, EntryPointInfo>>,
 }
 
@@ -124,7 +129,9 @@ impl ShaderModule {
             });
         }
 
-        for &capability in spirv_capabilities {
+        let spirv_capabilities: Vec<Capability> = spirv_capabilities.into_iter().copied().collect();
+
+        for &capability in &spirv_capabilities {
             if let Err(reason) = check_spirv_capability(&device, capability) {
                 return Err(ShaderCreationError::SpirvCapabilityNotSupported {
                     capability,
@@ -189,6 +196,7 @@ impl ShaderModule {
         Ok(Arc::new(ShaderModule {
             handle,
             device,
+            spirv_capabilities,
             entry_points,
         }))
     }
@@ -221,6 +229,12 @@ impl ShaderModule {
         )
     }
 
+    /// Returns the SPIR-V capabilities that the shader module declares.
+    #[inline]
+    pub fn spirv_capabilities(&self) -> &[Capability] {
+        &self.spirv_capabilities
+    }
+
     /// Returns information about the entry point with the provided name. Returns `None` if no entry
     /// point with that name exists in the shader module or if multiple entry points with the same
     /// name exist.
@@ -373,11 +387,14 @@ impl Display for ShaderSupportError {
 #[derive(Clone, Debug)]
 pub struct EntryPointInfo {
     pub execution: ShaderExecution,
-    pub descriptor_requirements: HashMap<(u32, u32), DescriptorRequirements>,
+    pub descriptor_requirements: HashMap<(u32, u32), DescriptorBindingRequirements>,
     pub push_constant_requirements: Option<PushConstantRange>,
+    pub push_constant_members: Vec<PushConstantMember>,
     pub specialization_constant_requirements: HashMap<u32, SpecializationConstantRequirements>,
     pub input_interface: ShaderInterface,
     pub output_interface: ShaderInterface,
+    pub local_size: Option<[LocalSizeValue; 3]>,
+    pub initializes_workgroup_memory: bool,
 }
 
 /// Represents a shader entry point in a shader module.
@@ -413,7 +430,7 @@ impl<'a> EntryPoint<'a> {
     #[inline]
     pub fn descriptor_requirements(
         &self,
-    ) -> impl ExactSizeIterator<Item = ((u32, u32), &DescriptorRequirements)> {
+    ) -> impl ExactSizeIterator<Item = ((u32, u32), &DescriptorBindingRequirements)> {
         self.info
             .descriptor_requirements
             .iter()
@@ -426,6 +443,89 @@ impl<'a> EntryPoint<'a> {
         self.info.push_constant_requirements.as_ref()
     }
 
+    /// Returns the members of the push constant block, ordered by offset.
+    #[inline]
+    pub fn push_constant_members(&self) -> &[PushConstantMember] {
+        &self.info.push_constant_members
+    }
+
+    /// Returns the push constant requirements, after checking that `T` matches the push constant
+    /// block of the shader and can be pushed with `pipeline_layout`.
+    ///
+    /// `T` is expected to describe the push constants starting at offset 0, as the structs
+    /// generated by `vulkano-shaders` do. It must cover every member of the block, and must not
+    /// extend beyond the end of the block. Every byte of `T` must be within a push constant range
+    /// of `pipeline_layout`, and the ranges that overlap the block must be accessible from the
+    /// stage of this entry point. This catches a mismatch when the pipeline is set up, rather
+    /// than when the push constants are recorded.
+    pub fn push_constants_typed<T>(
+        &self,
+        pipeline_layout: &PipelineLayout,
+    ) -> Result<PushConstantRange, PushConstantsTypeError>
+    where
+        T: BufferContents,
+    {
+        let range = self
+            .info
+            .push_constant_requirements
+            .ok_or(PushConstantsTypeError::NoPushConstants)?;
+        let provided = mem::size_of::<T>() as u32;
+        let required = range.offset + range.size;
+
+        if provided % 4 != 0 {
+            return Err(PushConstantsTypeError::SizeNotAligned { size: provided });
+        }
+
+        if provided > required {
+            return Err(PushConstantsTypeError::TypeTooLarge { provided, required });
+        }
+
+        let members: Vec<_> = self
+            .info
+            .push_constant_members
+            .iter()
+            .filter(|member| member.offset + member.size > provided)
+            .cloned()
+            .collect();
+
+        if !members.is_empty() {
+            return Err(PushConstantsTypeError::MembersNotCovered { provided, members });
+        }
+
+        // The disjoint ranges are sorted by offset and don't overlap, so walk them from offset 0
+        // until the whole type is covered, the same way `push_constants` does when recording.
+        let mut current_offset = 0;
+
+        for layout_range in pipeline_layout.push_constant_ranges_disjoint() {
+            let layout_range_end = layout_range.offset + layout_range.size;
+
+            if layout_range_end <= current_offset {
+                continue;
+            }
+
+            if layout_range.offset > current_offset || current_offset >= provided {
+                break;
+            }
+
+            if layout_range_end > range.offset && !layout_range.stages.contains(&range.stages) {
+                return Err(PushConstantsTypeError::StagesNotInLayout {
+                    offset: current_offset.max(range.offset),
+                    stages: range.stages,
+                });
+            }
+
+            current_offset = layout_range_end;
+        }
+
+        if current_offset < provided {
+            return Err(PushConstantsTypeError::NotInLayout {
+                offset: current_offset,
+            });
+        }
+
+        Ok(range)
+    }
+
     /// Returns the specialization constant requirements.
     #[inline]
     pub fn specialization_constant_requirements(
@@ -448,6 +548,137 @@ impl<'a> EntryPoint<'a> {
     pub fn output_interface(&self) -> &ShaderInterface {
         &self.info.output_interface
     }
+
+    /// Returns the local workgroup size declared by a compute shader, or `None` for other
+    /// shader types or if the local size could not be determined.
+    #[inline]
+    pub fn local_size(&self) -> Option<[LocalSizeValue; 3]> {
+        self.info.local_size
+    }
+
+    /// Returns whether the shader has variables in workgroup memory with an initializer.
+    #[inline]
+    pub fn initializes_workgroup_memory(&self) -> bool {
+        self.info.initializes_workgroup_memory
+    }
+}
+
+/// One dimension of the local workgroup size of a compute shader.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LocalSizeValue {
+    /// The size is a fixed value in the shader.
+    Literal(u32),
+
+    /// The size is given by a specialization constant, and can be changed when creating the
+    /// pipeline.
+    SpecializationConstant {
+        /// The ID of the specialization constant.
+        constant_id: u32,
+
+        /// The value that is used if the constant is not specialized.
+        default_value: u32,
+    },
+}
+
+impl LocalSizeValue {
+    /// Returns the value of this dimension if it is not specialized.
+    #[inline]
+    pub fn default_value(&self) -> u32 {
+        match *self {
+            Self::Literal(value) => value,
+            Self::SpecializationConstant { default_value, .. } => default_value,
+        }
+    }
+}
+
+/// A member of the push constant block of a shader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PushConstantMember {
+    /// The name of the member, if the shader contains debug names.
+    pub name: Option<String>,
+
+    /// The offset of the member in bytes.
+    pub offset: u32,
+
+    /// The size of the member in bytes.
+    pub size: u32,
+}
+
+/// Error that can happen when checking a push constants type against a shader.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PushConstantsTypeError {
+    /// The type does not cover some members of the push constant block.
+    MembersNotCovered {
+        provided: u32,
+        members: Vec<PushConstantMember>,
+    },
+
+    /// The shader does not have a push constant block.
+    NoPushConstants,
+
+    /// The byte of the type at the given offset is not within any push constant range of the
+    /// pipeline layout.
+    NotInLayout { offset: u32 },
+
+    /// The size of the type is not a multiple of 4.
+    SizeNotAligned { size: u32 },
+
+    /// The push constant range of the pipeline layout at the given offset overlaps the push
+    /// constant block, but is not accessible from the stages of the shader.
+    StagesNotInLayout { offset: u32, stages: ShaderStages },
+
+    /// The type extends beyond the end of the push constant block.
+    TypeTooLarge { provided: u32, required: u32 },
+}
+
+impl Error for PushConstantsTypeError {}
+
+impl Display for PushConstantsTypeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::MembersNotCovered { provided, members } => {
+                write!(
+                    f,
+                    "the type is {} bytes in size, but the following members of the push constant \
+                    block are beyond that:",
+                    provided,
+                )?;
+
+                for member in members {
+                    write!(
+                        f,
+                        " `{}` (offset {}, size {})",
+                        member.name.as_deref().unwrap_or("<unnamed>"),
+                        member.offset,
+                        member.size,
+                    )?;
+                }
+
+                Ok(())
+            }
+            Self::NoPushConstants => write!(f, "the shader does not have a push constant block"),
+            Self::NotInLayout { offset } => write!(
+                f,
+                "the byte of the type at offset {} is not within any push constant range of the \
+                pipeline layout",
+                offset,
+            ),
+            Self::SizeNotAligned { size } => {
+                write!(f, "the size of the type ({}) is not a multiple of 4", size,)
+            }
+            Self::StagesNotInLayout { offset, stages } => write!(
+                f,
+                "the push constant range of the pipeline layout at offset {} is not accessible \
+                from the shader stages {:?}",
+                offset, stages,
+            ),
+            Self::TypeTooLarge { provided, required } => write!(
+                f,
+                "the type is {} bytes in size, but the push constant block ends at {} bytes",
+                provided, required,
+            ),
+        }
+    }
 }
 
 /// The mode in which a shader executes. This includes both information about the shader type/stage,
@@ -541,7 +772,7 @@ pub enum GeometryShaderOutput {
 /// The requirements imposed by a shader on a descriptor within a descriptor set layout, and on any
 /// resource that is bound to that descriptor.
 #[derive(Clone, Debug, Default)]
-pub struct DescriptorRequirements {
+pub struct DescriptorBindingRequirements {
     /// The descriptor types that are allowed.
     pub descriptor_types: Vec<DescriptorType>,
 
@@ -552,6 +783,13 @@ pub struct DescriptorRequirements {
     /// access every array element provided in the descriptor set.
     pub descriptor_count: Option<u32>,
 
+    /// For uniform and storage buffer bindings, the size in bytes of the block that the shader
+    /// declares. Buffers bound to this descriptor must be at least this large. If the block ends
+    /// with a runtime-sized array, the array is not included.
+    ///
+    /// This is `None` for other bindings, or if the size could not be determined.
+    pub buffer_block_size: Option<DeviceSize>,
+
     /// The image format that is required for image views bound to this descriptor. If this is
     /// `None`, then any image format is allowed.
     pub image_format: Option<Format>,
@@ -606,11 +844,14 @@ pub struct DescriptorIdentifier {
     pub index: u32,
 }
 
-impl DescriptorRequirements {
+impl DescriptorBindingRequirements {
     /// Produces the intersection of two descriptor requirements, so that the requirements of both
     /// are satisfied. An error is returned if the requirements conflict.
     #[inline]
-    pub fn intersection(&self, other: &Self) -> Result<Self, DescriptorRequirementsIncompatible> {
+    pub fn intersection(
+        &self,
+        other: &Self,
+    ) -> Result<Self, DescriptorBindingRequirementsIncompatible> {
         let descriptor_types: Vec<_> = self
             .descriptor_types
             .iter()
@@ -619,29 +860,29 @@ impl DescriptorRequirements {
             .collect();
 
         if descriptor_types.is_empty() {
-            return Err(DescriptorRequirementsIncompatible::DescriptorType);
+            return Err(DescriptorBindingRequirementsIncompatible::DescriptorType);
         }
 
         if let (Some(first), Some(second)) = (self.image_format, other.image_format) {
             if first != second {
-                return Err(DescriptorRequirementsIncompatible::ImageFormat);
+                return Err(DescriptorBindingRequirementsIncompatible::ImageFormat);
             }
         }
 
         if let (Some(first), Some(second)) = (self.image_scalar_type, other.image_scalar_type) {
             if first != second {
-                return Err(DescriptorRequirementsIncompatible::ImageScalarType);
+                return Err(DescriptorBindingRequirementsIncompatible::ImageScalarType);
             }
         }
 
         if let (Some(first), Some(second)) = (self.image_view_type, other.image_view_type) {
             if first != second {
-                return Err(DescriptorRequirementsIncompatible::ImageViewType);
+                return Err(DescriptorBindingRequirementsIncompatible::ImageViewType);
             }
         }
 
         if self.image_multisampled != other.image_multisampled {
-            return Err(DescriptorRequirementsIncompatible::ImageMultisampled);
+            return Err(DescriptorBindingRequirementsIncompatible::ImageMultisampled);
         }
 
         let sampler_with_images = {
@@ -654,9 +895,16 @@ impl DescriptorRequirements {
             result
         };
 
+        // A runtime-sized array in either shader makes the intersection runtime-sized too.
+        let descriptor_count = match (self.descriptor_count, other.descriptor_count) {
+            (Some(first), Some(second)) => Some(first.max(second)),
+            _ => None,
+        };
+
         Ok(Self {
             descriptor_types,
-            descriptor_count: self.descriptor_count.max(other.descriptor_count),
+            descriptor_count,
+            buffer_block_size: self.buffer_block_size.max(other.buffer_block_size),
             image_format: self.image_format.or(other.image_format),
             image_multisampled: self.image_multisampled,
             image_scalar_type: self.image_scalar_type.or(other.image_scalar_type),
@@ -675,10 +923,16 @@ impl DescriptorRequirements {
     }
 }
 
+#[deprecated(
+    since = "0.31.0",
+    note = "Use `DescriptorBindingRequirements` instead."
+)]
+pub type DescriptorRequirements = DescriptorBindingRequirements;
+
 /// An error that can be returned when trying to create the intersection of two
-/// `DescriptorRequirements` values.
+/// `DescriptorBindingRequirements` values.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum DescriptorRequirementsIncompatible {
+pub enum DescriptorBindingRequirementsIncompatible {
     /// The allowed descriptor types of the descriptors do not overlap.
     DescriptorType,
     /// The descriptors require different formats.
@@ -691,26 +945,32 @@ pub enum DescriptorRequirementsIncompatible {
     ImageViewType,
 }
 
-impl Error for DescriptorRequirementsIncompatible {}
+#[deprecated(
+    since = "0.31.0",
+    note = "Use `DescriptorBindingRequirementsIncompatible` instead."
+)]
+pub type DescriptorRequirementsIncompatible = DescriptorBindingRequirementsIncompatible;
+
+impl Error for DescriptorBindingRequirementsIncompatible {}
 
-impl Display for DescriptorRequirementsIncompatible {
+impl Display for DescriptorBindingRequirementsIncompatible {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
         match self {
-            DescriptorRequirementsIncompatible::DescriptorType => write!(
+            DescriptorBindingRequirementsIncompatible::DescriptorType => write!(
                 f,
                 "the allowed descriptor types of the two descriptors do not overlap",
             ),
-            DescriptorRequirementsIncompatible::ImageFormat => {
+            DescriptorBindingRequirementsIncompatible::ImageFormat => {
                 write!(f, "the descriptors require different formats",)
             }
-            DescriptorRequirementsIncompatible::ImageMultisampled => write!(
+            DescriptorBindingRequirementsIncompatible::ImageMultisampled => write!(
                 f,
                 "the multisampling requirements of the descriptors differ",
             ),
-            DescriptorRequirementsIncompatible::ImageScalarType => {
+            DescriptorBindingRequirementsIncompatible::ImageScalarType => {
                 write!(f, "the descriptors require different scalar types",)
             }
-            DescriptorRequirementsIncompatible::ImageViewType => {
+            DescriptorBindingRequirementsIncompatible::ImageViewType => {
                 write!(f, "the descriptors require different image view types",)
             }
         }
@@ -836,8 +1096,6 @@ impl ShaderInterface {
     /// # Safety
     ///
     /// - Must only provide one entry per location.
-    /// - The format of each element must not be larger than 128 bits.
-    // TODO: 4x64 bit formats are possible, but they require special handling.
     // TODO: could this be made safe?
     #[inline]
     pub unsafe fn new_unchecked(elements: Vec<ShaderInterfaceEntry>) -> ShaderInterface {
@@ -942,35 +1200,64 @@ pub struct ShaderInterfaceEntryType {
 
 impl ShaderInterfaceEntryType {
     pub(crate) fn to_format(&self) -> Format {
-        assert!(!self.is_64bit); // TODO: implement
-        match self.base_type {
-            ShaderScalarType::Float => match self.num_components {
+        match (self.base_type, self.is_64bit) {
+            (ShaderScalarType::Float, false) => match self.num_components {
                 1 => Format::R32_SFLOAT,
                 2 => Format::R32G32_SFLOAT,
                 3 => Format::R32G32B32_SFLOAT,
                 4 => Format::R32G32B32A32_SFLOAT,
                 _ => unreachable!(),
             },
-            ShaderScalarType::Sint => match self.num_components {
+            (ShaderScalarType::Sint, false) => match self.num_components {
                 1 => Format::R32_SINT,
                 2 => Format::R32G32_SINT,
                 3 => Format::R32G32B32_SINT,
                 4 => Format::R32G32B32A32_SINT,
                 _ => unreachable!(),
             },
-            ShaderScalarType::Uint => match self.num_components {
+            (ShaderScalarType::Uint, false) => match self.num_components {
                 1 => Format::R32_UINT,
                 2 => Format::R32G32_UINT,
                 3 => Format::R32G32B32_UINT,
                 4 => Format::R32G32B32A32_UINT,
                 _ => unreachable!(),
             },
+            (ShaderScalarType::Float, true) => match self.num_components {
+                1 => Format::R64_SFLOAT,
+                2 => Format::R64G64_SFLOAT,
+                3 => Format::R64G64B64_SFLOAT,
+                4 => Format::R64G64B64A64_SFLOAT,
+                _ => unreachable!(),
+            },
+            (ShaderScalarType::Sint, true) => match self.num_components {
+                1 => Format::R64_SINT,
+                2 => Format::R64G64_SINT,
+                3 => Format::R64G64B64_SINT,
+                4 => Format::R64G64B64A64_SINT,
+                _ => unreachable!(),
+            },
+            (ShaderScalarType::Uint, true) => match self.num_components {
+                1 => Format::R64_UINT,
+                2 => Format::R64G64_UINT,
+                3 => Format::R64G64B64_UINT,
+                4 => Format::R64G64B64A64_UINT,
+                _ => unreachable!(),
+            },
+        }
+    }
+
+    /// Returns the number of locations taken up by a single array element or matrix column.
+    /// 64-bit vectors with more than two components take up two locations.
+    pub(crate) fn num_locations_per_element(&self) -> u32 {
+        if self.is_64bit && self.num_components > 2 {
+            2
+        } else {
+            1
         }
     }
 
     pub(crate) fn num_locations(&self) -> u32 {
-        assert!(!self.is_64bit); // TODO: implement
-        self.num_elements
+        self.num_elements * self.num_locations_per_element()
     }
 }
 
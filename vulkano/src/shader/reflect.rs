@@ -15,13 +15,13 @@ use crate::{
     pipeline::layout::PushConstantRange,
     shader::{
         spirv::{
-            Capability, Decoration, Dim, ExecutionMode, ExecutionModel, Id, Instruction, Spirv,
-            StorageClass,
+            BuiltIn, Capability, Decoration, Dim, ExecutionMode, ExecutionModel, Id, Instruction,
+            Spirv, StorageClass,
         },
-        DescriptorIdentifier, DescriptorRequirements, EntryPointInfo, GeometryShaderExecution,
-        GeometryShaderInput, ShaderExecution, ShaderInterface, ShaderInterfaceEntry,
-        ShaderInterfaceEntryType, ShaderScalarType, ShaderStage,
-        SpecializationConstantRequirements,
+        DescriptorBindingRequirements, DescriptorIdentifier, EntryPointInfo,
+        GeometryShaderExecution, GeometryShaderInput, LocalSizeValue, PushConstantMember,
+        ShaderExecution, ShaderInterface, ShaderInterfaceEntry, ShaderInterfaceEntryType,
+        ShaderScalarType, ShaderStage, SpecializationConstantRequirements,
     },
     DeviceSize,
 };
@@ -80,6 +80,7 @@ pub fn entry_points(
         }
 
         let push_constant_requirements = push_constant_requirements(spirv, stage);
+        let push_constant_members = push_constant_members(spirv);
         let specialization_constant_requirements = specialization_constant_requirements(spirv);
         let input_interface = shader_interface(
             spirv,
@@ -98,6 +99,11 @@ pub fn entry_points(
             StorageClass::Output,
             matches!(execution_model, ExecutionModel::TessellationControl),
         );
+        let local_size = match execution_model {
+            ExecutionModel::GLCompute => local_size(spirv, function_id),
+            _ => None,
+        };
+        let initializes_workgroup_memory = initializes_workgroup_memory(spirv);
 
         Some((
             entry_point_name.clone(),
@@ -106,14 +112,139 @@ pub fn entry_points(
                 execution,
                 descriptor_requirements,
                 push_constant_requirements,
+                push_constant_members,
                 specialization_constant_requirements,
                 input_interface,
                 output_interface,
+                local_size,
+                initializes_workgroup_memory,
             },
         ))
     })
 }
 
+/// Extracts the local workgroup size of the compute entry point `function_id` from `spirv`.
+///
+/// A constant decorated with the `WorkgroupSize` built-in takes precedence over the `LocalSize`
+/// and `LocalSizeId` execution modes. Returns `None` if the local size could not be determined.
+fn local_size(spirv: &Spirv, function_id: Id) -> Option<[LocalSizeValue; 3]> {
+    let workgroup_size = spirv
+        .iter_global()
+        .find_map(|instruction| match *instruction {
+            Instruction::ConstantComposite {
+                result_id,
+                ref constituents,
+                ..
+            }
+            | Instruction::SpecConstantComposite {
+                result_id,
+                ref constituents,
+                ..
+            } if is_builtin_workgroup_size(spirv, result_id) => Some(constituents),
+            _ => None,
+        });
+
+    if let Some(constituents) = workgroup_size {
+        return match constituents[..] {
+            [x_size, y_size, z_size] => local_size_values(spirv, [x_size, y_size, z_size]),
+            _ => None,
+        };
+    }
+
+    spirv
+        .iter_execution_mode()
+        .find_map(|instruction| match *instruction {
+            Instruction::ExecutionMode {
+                entry_point,
+                mode:
+                    ExecutionMode::LocalSize {
+                        x_size,
+                        y_size,
+                        z_size,
+                    },
+            } if entry_point == function_id => {
+                Some([x_size, y_size, z_size].map(LocalSizeValue::Literal))
+            }
+            Instruction::ExecutionModeId {
+                entry_point,
+                mode:
+                    ExecutionMode::LocalSizeId {
+                        x_size,
+                        y_size,
+                        z_size,
+                    },
+            } if entry_point == function_id => local_size_values(spirv, [x_size, y_size, z_size]),
+            _ => None,
+        })
+}
+
+/// Returns whether `spirv` has a variable in workgroup memory with an initializer.
+fn initializes_workgroup_memory(spirv: &Spirv) -> bool {
+    spirv.iter_global().any(|instruction| {
+        matches!(
+            instruction,
+            Instruction::Variable {
+                storage_class: StorageClass::Workgroup,
+                initializer: Some(_),
+                ..
+            }
+        )
+    })
+}
+
+fn is_builtin_workgroup_size(spirv: &Spirv, id: Id) -> bool {
+    spirv.id(id).iter_decoration().any(|instruction| {
+        matches!(
+            instruction,
+            Instruction::Decorate {
+                decoration: Decoration::BuiltIn {
+                    built_in: BuiltIn::WorkgroupSize,
+                },
+                ..
+            }
+        )
+    })
+}
+
+fn local_size_values(spirv: &Spirv, ids: [Id; 3]) -> Option<[LocalSizeValue; 3]> {
+    Some([
+        local_size_value(spirv, ids[0])?,
+        local_size_value(spirv, ids[1])?,
+        local_size_value(spirv, ids[2])?,
+    ])
+}
+
+/// Returns the `LocalSizeValue` for a constant or specialization constant, or `None` if `id` is
+/// not a 32-bit constant with a known ID.
+fn local_size_value(spirv: &Spirv, id: Id) -> Option<LocalSizeValue> {
+    match *spirv.id(id).instruction() {
+        Instruction::Constant { ref value, .. } => Some(LocalSizeValue::Literal(*value.first()?)),
+        Instruction::SpecConstant { ref value, .. } => {
+            Some(LocalSizeValue::SpecializationConstant {
+                constant_id: spec_constant_id(spirv, id)?,
+                default_value: *value.first()?,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn spec_constant_id(spirv: &Spirv, id: Id) -> Option<u32> {
+    spirv
+        .id(id)
+        .iter_decoration()
+        .find_map(|instruction| match *instruction {
+            Instruction::Decorate {
+                decoration:
+                    Decoration::SpecId {
+                        specialization_constant_id,
+                    },
+                ..
+            } => Some(specialization_constant_id),
+            _ => None,
+        })
+}
+
 /// Extracts the `ShaderExecution` for the entry point `function_id` from `spirv`.
 fn shader_execution(
     spirv: &Spirv,
@@ -178,7 +309,7 @@ struct InterfaceVariables {
 struct DescriptorVariable {
     set: u32,
     binding: u32,
-    reqs: DescriptorRequirements,
+    reqs: DescriptorBindingRequirements,
 }
 
 fn interface_variables(spirv: &Spirv) -> InterfaceVariables {
@@ -212,7 +343,7 @@ fn inspect_entry_point(
     global: &HashMap<Id, DescriptorVariable>,
     spirv: &Spirv,
     entry_point: Id,
-) -> HashMap<(u32, u32), DescriptorRequirements> {
+) -> HashMap<(u32, u32), DescriptorBindingRequirements> {
     fn instruction_chain<'a, const N: usize>(
         result: &'a mut HashMap<Id, DescriptorVariable>,
         global: &HashMap<Id, DescriptorVariable>,
@@ -702,13 +833,13 @@ fn inspect_entry_point(
         .collect()
 }
 
-/// Returns a `DescriptorRequirements` value for the pointed type.
+/// Returns a `DescriptorBindingRequirements` value for the pointed type.
 ///
 /// See also section 14.5.2 of the Vulkan specs: Descriptor Set Interface
 fn descriptor_requirements_of(spirv: &Spirv, variable_id: Id) -> DescriptorVariable {
     let variable_id_info = spirv.id(variable_id);
 
-    let mut reqs = DescriptorRequirements {
+    let mut reqs = DescriptorBindingRequirements {
         descriptor_count: Some(1),
         ..Default::default()
     };
@@ -770,9 +901,12 @@ fn descriptor_requirements_of(spirv: &Spirv, variable_id: Id) -> DescriptorVaria
                     reqs.descriptor_types = vec![
                         DescriptorType::UniformBuffer,
                         DescriptorType::UniformBufferDynamic,
+                        DescriptorType::InlineUniformBlock,
                     ];
                 };
 
+                reqs.buffer_block_size = size_of_block(spirv, id);
+
                 None
             }
 
@@ -876,8 +1010,10 @@ fn descriptor_requirements_of(spirv: &Spirv, variable_id: Id) -> DescriptorVaria
                 length,
                 ..
             } => {
+                // Arrays sized by a specialization constant use the default value of the constant.
                 let len = match spirv.id(length).instruction() {
-                    Instruction::Constant { value, .. } => {
+                    Instruction::Constant { value, .. }
+                    | Instruction::SpecConstant { value, .. } => {
                         value.iter().rev().fold(0, |a, &b| (a << 32) | b as u64)
                     }
                     _ => panic!("failed to find array length"),
@@ -916,6 +1052,12 @@ fn descriptor_requirements_of(spirv: &Spirv, variable_id: Id) -> DescriptorVaria
         };
     }
 
+    // Inline uniform blocks can't be arrayed.
+    if reqs.descriptor_count != Some(1) {
+        reqs.descriptor_types
+            .retain(|&ty| ty != DescriptorType::InlineUniformBlock);
+    }
+
     DescriptorVariable {
         set: variable_id_info
             .iter_decoration()
@@ -970,6 +1112,57 @@ fn push_constant_requirements(spirv: &Spirv, stage: ShaderStage) -> Option<PushC
         })
 }
 
+/// Extracts the members of the push constant block from `spirv`, ordered by offset.
+fn push_constant_members(spirv: &Spirv) -> Vec<PushConstantMember> {
+    let ty = match spirv
+        .iter_global()
+        .find_map(|instruction| match *instruction {
+            Instruction::TypePointer {
+                ty,
+                storage_class: StorageClass::PushConstant,
+                ..
+            } => Some(ty),
+            _ => None,
+        }) {
+        Some(ty) => ty,
+        None => return Vec::new(),
+    };
+
+    let id_info = spirv.id(ty);
+    let member_types = match id_info.instruction() {
+        Instruction::TypeStruct { member_types, .. } => member_types,
+        _ => unreachable!(),
+    };
+
+    let mut members: Vec<_> = member_types
+        .iter()
+        .zip(id_info.iter_members())
+        .map(|(&member_type, member_info)| PushConstantMember {
+            name: member_info
+                .iter_name()
+                .find_map(|instruction| match instruction {
+                    Instruction::MemberName { name, .. } => Some(name.clone()),
+                    _ => None,
+                }),
+            offset: member_info
+                .iter_decoration()
+                .find_map(|instruction| match *instruction {
+                    Instruction::MemberDecorate {
+                        decoration: Decoration::Offset { byte_offset },
+                        ..
+                    } => Some(byte_offset),
+                    _ => None,
+                })
+                .unwrap(),
+            size: size_of_type(spirv, member_type).expect("Found runtime-sized push constants")
+                as u32,
+        })
+        .collect();
+    members.sort_by_key(|member| member.offset);
+
+    members
+}
+
 /// Extracts the `SpecializationConstantRequirements` from `spirv`.
 fn specialization_constant_requirements(
     spirv: &Spirv,
@@ -1172,7 +1365,7 @@ fn size_of_type(spirv: &Spirv, id: Id) -> Option<DeviceSize> {
 
             Some(stride as DeviceSize * length)
         }
-        Instruction::TypeRuntimeArray { .. } => None,
+        Instruction::TypeRuntimeArray { .. } | Instruction::TypePointer { .. } => None,
         Instruction::TypeStruct {
             ref member_types, ..
         } => {
@@ -1214,6 +1407,37 @@ fn size_of_type(spirv: &Spirv, id: Id) -> Option<DeviceSize> {
     }
 }
 
+/// Returns the size of a struct that is used as a uniform or storage buffer block, not counting a
+/// runtime-sized array at the end, or `None` if its size cannot be determined.
+fn size_of_block(spirv: &Spirv, id: Id) -> Option<DeviceSize> {
+    let id_info = spirv.id(id);
+    let member_types = match id_info.instruction() {
+        Instruction::TypeStruct { member_types, .. } => member_types,
+        _ => return None,
+    };
+
+    let mut end_of_block = 0;
+
+    for (&member, member_info) in member_types.iter().zip(id_info.iter_members()) {
+        let offset = member_info
+            .iter_decoration()
+            .find_map(|instruction| match *instruction {
+                Instruction::MemberDecorate {
+                    decoration: Decoration::Offset { byte_offset },
+                    ..
+                } => Some(byte_offset),
+                _ => None,
+            })?;
+        let size = match spirv.id(member).instruction() {
+            Instruction::TypeRuntimeArray { .. } => 0,
+            _ => size_of_type(spirv, member)?,
+        };
+        end_of_block = end_of_block.max(offset as DeviceSize + size);
+    }
+
+    Some(end_of_block)
+}
+
 /// Returns the smallest offset of all members of a struct, or 0 if `id` is not a struct.
 fn offset_of_struct(spirv: &Spirv, id: Id) -> u32 {
     spirv
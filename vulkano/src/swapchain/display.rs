@@ -9,8 +9,9 @@
 
 //! Allows you to create surfaces that fill a whole display, outside of the windowing system.
 //!
-//! **As far as the author knows, no existing device supports these features. Therefore the code
-//! here is mostly a draft and needs rework in both the API and the implementation.**
+//! This uses the `khr_display` instance extension, which lets you render directly to a display
+//! connected to the physical device. It is mostly useful on embedded systems, or on machines that
+//! don't run a windowing system at all.
 //!
 //! The purpose of the objects in this module is to let you create a `Surface` object that
 //! represents a location on the screen. This is done in four steps:
@@ -20,21 +21,23 @@
 //!   `Display::enumerate`.
 //! - Choose a `DisplayMode`, which is the combination of a display, a resolution and a refresh
 //!   rate. You can enumerate the modes available on a display with `Display::display_modes`, or
-//!   attempt to create your own mode with `TODO`.
-//! - Choose a `DisplayPlane`. A display can show multiple planes in a stacking fashion.
+//!   attempt to create your own mode with `DisplayMode::new`.
+//! - Choose a `DisplayPlane`. A display can show multiple planes in a stacking fashion. The
+//!   capabilities of a plane when used with a given mode can be queried with
+//!   `DisplayPlane::capabilities`.
 //! - Create a `Surface` object with `Surface::from_display_plane` and pass the chosen `DisplayMode`
 //!   and `DisplayPlane`.
 
-#![allow(dead_code)] // TODO: this module isn't finished
-#![allow(unused_variables)] // TODO: this module isn't finished
-
 use crate::{
-    device::physical::PhysicalDevice, swapchain::SupportedSurfaceTransforms, OomError, VulkanError,
-    VulkanObject,
+    device::physical::PhysicalDevice,
+    macros::{vulkan_bitflags, vulkan_enum},
+    swapchain::{SupportedSurfaceTransforms, SurfaceTransform},
+    OomError, VulkanError, VulkanObject,
 };
 use std::{
     ffi::CStr,
     fmt::{Display as FmtDisplay, Error as FmtError, Formatter},
+    mem::MaybeUninit,
     ptr,
     sync::Arc,
     vec::IntoIter,
@@ -42,8 +45,8 @@ use std::{
 
 // TODO: extract this to a `display` module and solve the visibility problems
 
-/// ?
-// TODO: plane capabilities
+/// Represents a plane of a physical device, which is a layer of the image that is scanned out to a
+/// display.
 // TODO: store properties in the instance?
 pub struct DisplayPlane {
     physical_device: Arc<PhysicalDevice>,
@@ -93,7 +96,7 @@ impl DisplayPlane {
             }
         };
 
-        Ok(display_plane_properties
+        display_plane_properties
             .into_iter()
             .enumerate()
             .map(|(index, prop)| {
@@ -107,8 +110,7 @@ impl DisplayPlane {
                             ptr::null_mut(),
                         )
                         .result()
-                        .map_err(VulkanError::from)
-                        .unwrap(); // TODO: shouldn't unwrap
+                        .map_err(VulkanError::from)?;
 
                         let mut displays = Vec::with_capacity(count as usize);
                         let result = (fns.khr_display.get_display_plane_supported_displays_khr)(
@@ -124,20 +126,20 @@ impl DisplayPlane {
                                 break displays;
                             }
                             ash::vk::Result::INCOMPLETE => (),
-                            err => todo!(), // TODO: shouldn't panic
+                            err => return Err(VulkanError::from(err).into()),
                         }
                     }
                 };
 
-                DisplayPlane {
+                Ok(DisplayPlane {
                     physical_device: physical_device.clone(),
                     index: index as u32,
                     properties: prop,
                     supported_displays,
-                }
+                })
             })
-            .collect::<Vec<_>>()
-            .into_iter())
+            .collect::<Result<Vec<_>, OomError>>()
+            .map(IntoIterator::into_iter)
     }
 
     /// Enumerates all the display planes that are available on a given physical device.
@@ -163,6 +165,75 @@ impl DisplayPlane {
         self.index
     }
 
+    /// Returns the index of the plane in the stack of planes of the display that it is currently
+    /// associated with.
+    #[inline]
+    pub fn current_stack_index(&self) -> u32 {
+        self.properties.current_stack_index
+    }
+
+    /// Returns the capabilities of the plane when it is used with the given display mode.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `mode` doesn't belong to the same physical device as the plane.
+    pub fn capabilities(&self, mode: &DisplayMode) -> Result<DisplayPlaneCapabilities, OomError> {
+        assert_eq!(
+            self.physical_device.internal_object(),
+            mode.display().physical_device().internal_object(),
+        );
+
+        let capabilities = unsafe {
+            let fns = self.physical_device.instance().fns();
+            let mut output = MaybeUninit::uninit();
+            (fns.khr_display.get_display_plane_capabilities_khr)(
+                self.physical_device.internal_object(),
+                mode.internal_object(),
+                self.index,
+                output.as_mut_ptr(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+            output.assume_init()
+        };
+
+        Ok(DisplayPlaneCapabilities {
+            supported_alpha: capabilities.supported_alpha.into(),
+            min_src_position: [
+                capabilities.min_src_position.x,
+                capabilities.min_src_position.y,
+            ],
+            max_src_position: [
+                capabilities.max_src_position.x,
+                capabilities.max_src_position.y,
+            ],
+            min_src_extent: [
+                capabilities.min_src_extent.width,
+                capabilities.min_src_extent.height,
+            ],
+            max_src_extent: [
+                capabilities.max_src_extent.width,
+                capabilities.max_src_extent.height,
+            ],
+            min_dst_position: [
+                capabilities.min_dst_position.x,
+                capabilities.min_dst_position.y,
+            ],
+            max_dst_position: [
+                capabilities.max_dst_position.x,
+                capabilities.max_dst_position.y,
+            ],
+            min_dst_extent: [
+                capabilities.min_dst_extent.width,
+                capabilities.min_dst_extent.height,
+            ],
+            max_dst_extent: [
+                capabilities.max_dst_extent.width,
+                capabilities.max_dst_extent.height,
+            ],
+        })
+    }
+
     /// Returns true if this plane supports the given display.
     #[inline]
     pub fn supports(&self, display: &Display) -> bool {
@@ -282,13 +353,14 @@ impl Display {
         self.properties.supported_transforms.into()
     }
 
-    /// Returns true if TODO.
+    /// Returns true if the planes of this display can have their stack order changed.
     #[inline]
     pub fn plane_reorder_possible(&self) -> bool {
         self.properties.plane_reorder_possible != 0
     }
 
-    /// Returns true if TODO.
+    /// Returns true if the display can keep showing its content without the images being
+    /// presented again, which allows submitting only the regions that have changed.
     #[inline]
     pub fn persistent_content(&self) -> bool {
         self.properties.persistent_content != 0
@@ -371,35 +443,62 @@ pub struct DisplayMode {
 }
 
 impl DisplayMode {
-    /*pub fn new(display: &Display) -> Result<Arc<DisplayMode>, OomError> {
-        let fns = instance.fns();
-        assert!(device.instance().enabled_extensions().khr_display);     // TODO: return error instead
+    /// Creates a new display mode with the given visible region and refresh rate.
+    ///
+    /// The refresh rate is in millihertz, the same as for [`refresh_rate`](Self::refresh_rate).
+    /// The implementation may not support arbitrary modes, in which case an error is returned.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `visible_region` contains a zero value.
+    /// - Panics if `refresh_rate` is zero.
+    pub fn new(
+        display: &Display,
+        visible_region: [u32; 2],
+        refresh_rate: u32,
+    ) -> Result<DisplayMode, OomError> {
+        // VUID-VkDisplayModeParametersKHR-width-01990
+        // VUID-VkDisplayModeParametersKHR-height-01991
+        assert!(visible_region[0] != 0 && visible_region[1] != 0);
+
+        // VUID-VkDisplayModeParametersKHR-refreshRate-01992
+        assert!(refresh_rate != 0);
 
         let parameters = ash::vk::DisplayModeParametersKHR {
-            visibleRegion: ash::vk::Extent2D { width: , height:  },
-            refreshRate: ,
+            visible_region: ash::vk::Extent2D {
+                width: visible_region[0],
+                height: visible_region[1],
+            },
+            refresh_rate,
         };
 
-        let display_mode = {
-            let infos = ash::vk::DisplayModeCreateInfoKHR {
-                flags: ash::vk::DisplayModeCreateFlags::empty(),
-                parameters: parameters,
-                ..Default::default()
-            };
-
-            let mut output = mem::uninitialized();
-            (fns.v1_0.CreateDisplayModeKHR)(display.device.internal_object(),
-                                                      display.display, &infos, ptr::null(),
-                                                      &mut output).result().map_err(VulkanError::from)?;
-            output
+        let create_info = ash::vk::DisplayModeCreateInfoKHR {
+            flags: ash::vk::DisplayModeCreateFlagsKHR::empty(),
+            parameters,
+            ..Default::default()
         };
 
-        Ok(Arc::new(DisplayMode {
-            instance: display.device.instance().clone(),
-            display_mode: display_mode,
-            parameters: ,
-        }))
-    }*/
+        let display_mode = unsafe {
+            let fns = display.physical_device().instance().fns();
+            let mut output = MaybeUninit::uninit();
+            (fns.khr_display.create_display_mode_khr)(
+                display.physical_device().internal_object(),
+                display.internal_object(),
+                &create_info,
+                ptr::null(),
+                output.as_mut_ptr(),
+            )
+            .result()
+            .map_err(VulkanError::from)?;
+            output.assume_init()
+        };
+
+        Ok(DisplayMode {
+            display: display.clone(),
+            display_mode,
+            parameters,
+        })
+    }
 
     /// Returns the display corresponding to this mode.
     #[inline]
@@ -448,3 +547,141 @@ unsafe impl VulkanObject for DisplayMode {
         self.display_mode
     }
 }
+
+/// Parameters to create a surface with [`Surface::from_display_plane`].
+///
+/// [`Surface::from_display_plane`]: crate::swapchain::Surface::from_display_plane
+#[derive(Clone, Debug)]
+pub struct DisplaySurfaceCreateInfo {
+    /// The transform to apply to the images of the surface when they are shown on the display.
+    ///
+    /// The transform must be supported by the display, as returned by
+    /// [`Display::supported_transforms`].
+    ///
+    /// The default value is [`SurfaceTransform::Identity`].
+    pub transform: SurfaceTransform,
+
+    /// How the alpha values of the plane are used.
+    ///
+    /// The alpha mode must be supported by the plane, as returned by
+    /// [`DisplayPlane::capabilities`].
+    ///
+    /// The default value is [`DisplayPlaneAlpha::Opaque`].
+    pub alpha_mode: DisplayPlaneAlpha,
+
+    /// The alpha value of the whole plane, if `alpha_mode` is [`DisplayPlaneAlpha::Global`].
+    /// It must be between 0.0 and 1.0 inclusive.
+    ///
+    /// The default value is `1.0`.
+    pub global_alpha: f32,
+
+    /// The size of the images of the surface, or `None` to use the visible region of the display
+    /// mode.
+    ///
+    /// The default value is `None`.
+    pub image_extent: Option<[u32; 2]>,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for DisplaySurfaceCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            transform: SurfaceTransform::Identity,
+            alpha_mode: DisplayPlaneAlpha::Opaque,
+            global_alpha: 1.0,
+            image_extent: None,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// The capabilities of a display plane when used with a specific display mode.
+///
+/// The positions and extents are in pixels. The source rectangle is the region of the presented
+/// image that is read, the destination rectangle is the region of the display mode that it is
+/// shown in.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct DisplayPlaneCapabilities {
+    /// The alpha modes that are supported by the plane.
+    pub supported_alpha: SupportedDisplayPlaneAlpha,
+
+    /// The minimum position of the source rectangle.
+    pub min_src_position: [i32; 2],
+
+    /// The maximum position of the source rectangle.
+    pub max_src_position: [i32; 2],
+
+    /// The minimum extent of the source rectangle.
+    pub min_src_extent: [u32; 2],
+
+    /// The maximum extent of the source rectangle.
+    pub max_src_extent: [u32; 2],
+
+    /// The minimum position of the destination rectangle.
+    pub min_dst_position: [i32; 2],
+
+    /// The maximum position of the destination rectangle.
+    pub max_dst_position: [i32; 2],
+
+    /// The minimum extent of the destination rectangle.
+    pub min_dst_extent: [u32; 2],
+
+    /// The maximum extent of the destination rectangle.
+    pub max_dst_extent: [u32; 2],
+}
+
+vulkan_enum! {
+    /// How the alpha values of a display plane are used when blending it with the planes below.
+    #[non_exhaustive]
+    DisplayPlaneAlpha = DisplayPlaneAlphaFlagsKHR(u32);
+
+    /// The plane is opaque, and the alpha values of the image are ignored.
+    Opaque = OPAQUE,
+
+    /// A single alpha value, given when creating the surface, is used for the whole plane.
+    Global = GLOBAL,
+
+    /// The alpha value of each pixel of the image is used. The color channels are expected to
+    /// not have been multiplied by the alpha value.
+    PerPixel = PER_PIXEL,
+
+    /// The alpha value of each pixel of the image is used. The color channels are expected to
+    /// have already been multiplied by the alpha value.
+    PerPixelPremultiplied = PER_PIXEL_PREMULTIPLIED,
+}
+
+vulkan_bitflags! {
+    /// List of supported display plane alpha modes.
+    ///
+    /// See the docs of `DisplayPlaneAlpha`.
+    #[non_exhaustive]
+    SupportedDisplayPlaneAlpha = DisplayPlaneAlphaFlagsKHR(u32);
+
+    /// See [`DisplayPlaneAlpha::Opaque`].
+    opaque = OPAQUE,
+
+    /// See [`DisplayPlaneAlpha::Global`].
+    global = GLOBAL,
+
+    /// See [`DisplayPlaneAlpha::PerPixel`].
+    per_pixel = PER_PIXEL,
+
+    /// See [`DisplayPlaneAlpha::PerPixelPremultiplied`].
+    per_pixel_premultiplied = PER_PIXEL_PREMULTIPLIED,
+}
+
+impl SupportedDisplayPlaneAlpha {
+    /// Returns true if the given `DisplayPlaneAlpha` is in this list.
+    #[inline]
+    pub fn supports(&self, value: DisplayPlaneAlpha) -> bool {
+        match value {
+            DisplayPlaneAlpha::Opaque => self.opaque,
+            DisplayPlaneAlpha::Global => self.global,
+            DisplayPlaneAlpha::PerPixel => self.per_pixel,
+            DisplayPlaneAlpha::PerPixelPremultiplied => self.per_pixel_premultiplied,
+        }
+    }
+}
@@ -0,0 +1,838 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Rendering without a window.
+//!
+//! An [`ImageChain`] stands in for a [`Swapchain`](super::Swapchain) when there is no surface to
+//! present to, for example in automated tests or in a renderer running on a server. It owns a
+//! ring of [`StorageImage`]s, and provides [`acquire_next_image`] and [`present`] functions that
+//! follow the same workflow as their swapchain counterparts, so that a render loop can be reused
+//! unchanged.
+//!
+//! Acquiring an image blocks until the previous presentation of that image has finished on the
+//! device. Presenting only marks the end of the work that renders into the image; nothing is
+//! shown anywhere. If the chain was created with
+//! [`readback`](ImageChainCreateInfo::readback) enabled, [`present_with_readback`] additionally
+//! copies the image into a host-visible buffer, which can be read once the presentation has
+//! finished.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use vulkano::{
+//!     format::Format,
+//!     image::ImageUsage,
+//!     swapchain::headless::{self, ImageChain, ImageChainCreateInfo},
+//!     sync::GpuFuture,
+//! };
+//! # let device: std::sync::Arc<vulkano::device::Device> = return;
+//! # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+//!
+//! let (chain, images) = ImageChain::new(
+//!     device.clone(),
+//!     ImageChainCreateInfo {
+//!         image_format: Format::R8G8B8A8_UNORM,
+//!         image_extent: [1280, 720],
+//!         image_usage: ImageUsage {
+//!             color_attachment: true,
+//!             ..ImageUsage::empty()
+//!         },
+//!         ..Default::default()
+//!     },
+//! )
+//! .unwrap();
+//!
+//! let (image_index, _suboptimal, acquire_future) =
+//!     headless::acquire_next_image(chain.clone(), None).unwrap();
+//!
+//! // Render into `images[image_index as usize]`...
+//!
+//! let future = headless::present(acquire_future, queue.clone(), chain.clone(), image_index)
+//!     .then_signal_fence_and_flush()
+//!     .unwrap();
+//! ```
+
+use super::AcquireError;
+use crate::{
+    buffer::{sys::UnsafeBuffer, BufferUsage, CpuAccessibleBuffer},
+    command_buffer::{
+        allocator::CommandBufferAllocator, AutoCommandBufferBuilder, BuildError,
+        CommandBufferBeginError, CommandBufferExecError, CommandBufferExecFuture,
+        CommandBufferUsage, CopyError, CopyImageToBufferInfo, SemaphoreSubmitInfo, SubmitInfo,
+    },
+    device::{Device, DeviceOwned, Queue},
+    format::Format,
+    image::{
+        sys::UnsafeImage, ImageCreateFlags, ImageCreationError, ImageDimensions, ImageLayout,
+        ImageUsage, StorageImage,
+    },
+    memory::DeviceMemoryError,
+    sync::{
+        now, AccessCheckError, AccessFlags, Fence, FenceError, FlushError, GpuFuture, NowFuture,
+        PipelineStages, SubmitAnyBuilder,
+    },
+    DeviceSize,
+};
+use parking_lot::Mutex;
+use smallvec::smallvec;
+use std::{
+    error::Error,
+    fmt::{Display, Error as FmtError, Formatter},
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// A ring of images that can be rendered to with the same acquire/present workflow as a
+/// swapchain, but without a surface.
+#[derive(Debug)]
+pub struct ImageChain {
+    device: Arc<Device>,
+    image_format: Format,
+    image_extent: [u32; 2],
+    image_usage: ImageUsage,
+
+    images: Vec<Arc<StorageImage>>,
+    readback_buffers: Vec<Arc<CpuAccessibleBuffer<[u8]>>>,
+    state: Mutex<ImageChainState>,
+}
+
+#[derive(Debug)]
+struct ImageChainState {
+    next_image_index: u32,
+    // For each image, the fence signaled by its most recent presentation that has been submitted.
+    present_fences: Vec<Option<Arc<Fence>>>,
+}
+
+impl ImageChain {
+    /// Creates a new `ImageChain`.
+    ///
+    /// This function returns the chain plus a list of the images that belong to it. The order in
+    /// which the images are returned is important for the `acquire_next_image` and `present`
+    /// functions.
+    pub fn new(
+        device: Arc<Device>,
+        create_info: ImageChainCreateInfo,
+    ) -> Result<(Arc<ImageChain>, Vec<Arc<StorageImage>>), ImageChainCreationError> {
+        let ImageChainCreateInfo {
+            image_count,
+            image_format,
+            image_extent,
+            mut image_usage,
+            readback,
+            _ne: _,
+        } = create_info;
+
+        if image_count == 0 {
+            return Err(ImageChainCreationError::ImageCountZero);
+        }
+
+        if image_extent.contains(&0) {
+            return Err(ImageChainCreationError::ImageExtentZeroLengthDimensions);
+        }
+
+        let readback_size = if readback {
+            let aspects = image_format.aspects();
+
+            if aspects.depth || aspects.stencil || aspects.plane0 {
+                return Err(ImageChainCreationError::ReadbackFormatNotSupported {
+                    format: image_format,
+                });
+            }
+
+            let block_extent = image_format.block_extent();
+            let block_size = match image_format.block_size() {
+                Some(block_size) => block_size,
+                None => {
+                    return Err(ImageChainCreationError::ReadbackFormatNotSupported {
+                        format: image_format,
+                    })
+                }
+            };
+
+            image_usage.transfer_src = true;
+
+            let row_blocks = (image_extent[0] + block_extent[0] - 1) / block_extent[0];
+            let rows = (image_extent[1] + block_extent[1] - 1) / block_extent[1];
+            Some(row_blocks as DeviceSize * rows as DeviceSize * block_size)
+        } else {
+            None
+        };
+
+        let images = (0..image_count)
+            .map(|_| {
+                StorageImage::with_usage(
+                    device.clone(),
+                    ImageDimensions::Dim2d {
+                        width: image_extent[0],
+                        height: image_extent[1],
+                        array_layers: 1,
+                    },
+                    image_format,
+                    image_usage,
+                    ImageCreateFlags::empty(),
+                    [],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let readback_buffers = match readback_size {
+            Some(size) => (0..image_count)
+                .map(|_| unsafe {
+                    CpuAccessibleBuffer::uninitialized_array(
+                        device.clone(),
+                        size,
+                        BufferUsage {
+                            transfer_dst: true,
+                            ..BufferUsage::empty()
+                        },
+                        true,
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+
+        let chain = Arc::new(ImageChain {
+            device,
+            image_format,
+            image_extent,
+            image_usage,
+
+            images: images.clone(),
+            readback_buffers,
+            state: Mutex::new(ImageChainState {
+                next_image_index: 0,
+                present_fences: vec![None; image_count as usize],
+            }),
+        });
+
+        Ok((chain, images))
+    }
+
+    /// Returns the number of images of the chain.
+    #[inline]
+    pub fn image_count(&self) -> u32 {
+        self.images.len() as u32
+    }
+
+    /// Returns the format of the images of the chain.
+    #[inline]
+    pub fn image_format(&self) -> Format {
+        self.image_format
+    }
+
+    /// Returns the extent of the images of the chain.
+    #[inline]
+    pub fn image_extent(&self) -> [u32; 2] {
+        self.image_extent
+    }
+
+    /// Returns the usage of the images of the chain.
+    ///
+    /// If readback is enabled, this includes `transfer_src` even if it was not requested.
+    #[inline]
+    pub fn image_usage(&self) -> ImageUsage {
+        self.image_usage
+    }
+
+    /// Returns the images of the chain, in the order of their indices.
+    #[inline]
+    pub fn images(&self) -> &[Arc<StorageImage>] {
+        &self.images
+    }
+
+    /// Returns the host-visible buffer that the image at `image_index` is copied into by
+    /// [`present_with_readback`], or `None` if readback is not enabled.
+    ///
+    /// The texels are tightly packed, row after row. The buffer can be read once the future
+    /// returned by `present_with_readback` has finished, for example after waiting on a fence
+    /// signaled after it.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `image_index` is not less than the number of images.
+    #[inline]
+    pub fn readback_buffer(&self, image_index: u32) -> Option<&Arc<CpuAccessibleBuffer<[u8]>>> {
+        assert!(image_index < self.image_count());
+
+        self.readback_buffers.get(image_index as usize)
+    }
+}
+
+unsafe impl DeviceOwned for ImageChain {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+/// Parameters to create a new `ImageChain`.
+#[derive(Clone, Debug)]
+pub struct ImageChainCreateInfo {
+    /// The number of images in the chain.
+    ///
+    /// The default value is `2`.
+    pub image_count: u32,
+
+    /// The format of the images.
+    ///
+    /// The default value is `Format::R8G8B8A8_UNORM`.
+    pub image_format: Format,
+
+    /// The width and height of the images.
+    ///
+    /// The default value is `[0, 0]`, which must be overridden.
+    pub image_extent: [u32; 2],
+
+    /// How the images will be used.
+    ///
+    /// The default value is [`ImageUsage::empty()`], which must be overridden.
+    pub image_usage: ImageUsage,
+
+    /// Whether to create a host-visible buffer for each image, that the image can be copied into
+    /// with [`present_with_readback`].
+    ///
+    /// If `true`, `transfer_src` is added to `image_usage`, and `image_format` must be a
+    /// single-plane color format.
+    ///
+    /// The default value is `false`.
+    pub readback: bool,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for ImageChainCreateInfo {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            image_count: 2,
+            image_format: Format::R8G8B8A8_UNORM,
+            image_extent: [0, 0],
+            image_usage: ImageUsage::empty(),
+            readback: false,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// Error that can happen when creating an `ImageChain`.
+#[derive(Clone, Debug)]
+pub enum ImageChainCreationError {
+    /// Creating one of the images failed.
+    ImageCreationError(ImageCreationError),
+
+    /// Allocating one of the readback buffers failed.
+    DeviceMemoryError(DeviceMemoryError),
+
+    /// The provided `image_count` is zero.
+    ImageCountZero,
+
+    /// The provided `image_extent` contained at least one dimension of zero length.
+    ImageExtentZeroLengthDimensions,
+
+    /// `readback` was enabled, but the provided `image_format` can't be copied into a tightly
+    /// packed buffer.
+    ReadbackFormatNotSupported { format: Format },
+}
+
+impl Error for ImageChainCreationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::ImageCreationError(err) => Some(err),
+            Self::DeviceMemoryError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ImageChainCreationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::ImageCreationError(_) => write!(f, "creating one of the images failed"),
+            Self::DeviceMemoryError(_) => {
+                write!(f, "allocating one of the readback buffers failed")
+            }
+            Self::ImageCountZero => write!(f, "the provided `image_count` is zero"),
+            Self::ImageExtentZeroLengthDimensions => write!(
+                f,
+                "the provided `image_extent` contained at least one dimension of zero length",
+            ),
+            Self::ReadbackFormatNotSupported { format } => write!(
+                f,
+                "`readback` was enabled, but the provided `image_format` {:?} can't be copied \
+                into a tightly packed buffer",
+                format,
+            ),
+        }
+    }
+}
+
+impl From<ImageCreationError> for ImageChainCreationError {
+    fn from(err: ImageCreationError) -> Self {
+        Self::ImageCreationError(err)
+    }
+}
+
+impl From<DeviceMemoryError> for ImageChainCreationError {
+    fn from(err: DeviceMemoryError) -> Self {
+        Self::DeviceMemoryError(err)
+    }
+}
+
+/// Gets the next image of the chain.
+///
+/// The images are handed out in order. This function blocks until the previous presentation of
+/// the image has finished executing on the device, or until `timeout` has elapsed.
+///
+/// The returned tuple has the same layout as the one returned by
+/// [`swapchain::acquire_next_image`](super::acquire_next_image). The second field, which
+/// indicates whether the acquisition was suboptimal, is always `false`.
+pub fn acquire_next_image(
+    chain: Arc<ImageChain>,
+    timeout: Option<Duration>,
+) -> Result<(u32, bool, NowFuture), AcquireError> {
+    // The lock is held while waiting, so that the image is not handed out twice, and so that the
+    // same image is tried again if the wait fails.
+    let mut state = chain.state.lock();
+    let image_index = state.next_image_index;
+
+    if let Some(present_fence) = &state.present_fences[image_index as usize] {
+        present_fence.wait(timeout).map_err(|err| match err {
+            FenceError::OomError(err) => AcquireError::OomError(err),
+            FenceError::DeviceLost => AcquireError::DeviceLost,
+            FenceError::Timeout => AcquireError::Timeout,
+            err => AcquireError::FenceError(err),
+        })?;
+    }
+
+    state.present_fences[image_index as usize] = None;
+    state.next_image_index = (image_index + 1) % chain.image_count();
+    drop(state);
+
+    Ok((image_index, false, now(chain.device.clone())))
+}
+
+/// Marks the end of the work that renders into an image of the chain.
+///
+/// The returned future must be flushed for the image to become available to
+/// [`acquire_next_image`] again.
+///
+/// # Panics
+///
+/// - Panics if `image_index` is not less than the number of images.
+/// - Panics if `queue` does not belong to the same device as `chain`.
+pub fn present<F>(
+    before: F,
+    queue: Arc<Queue>,
+    chain: Arc<ImageChain>,
+    image_index: u32,
+) -> ImageChainPresentFuture<F>
+where
+    F: GpuFuture,
+{
+    assert!(image_index < chain.image_count());
+    assert_eq!(queue.device(), chain.device());
+
+    ImageChainPresentFuture {
+        previous: before,
+        queue,
+        chain,
+        image_index,
+        fence: Mutex::new(None),
+        flushed: AtomicBool::new(false),
+        finished: AtomicBool::new(false),
+    }
+}
+
+/// Copies an image of the chain into its readback buffer, then marks the end of the work that
+/// renders into it.
+///
+/// The copied texels can be read from [`ImageChain::readback_buffer`] once the returned future has
+/// finished.
+///
+/// # Panics
+///
+/// - Panics if `chain` was not created with [`readback`](ImageChainCreateInfo::readback)
+///   enabled.
+/// - Panics if `image_index` is not less than the number of images.
+/// - Panics if `queue` does not belong to the same device as `chain`.
+pub fn present_with_readback<F, A>(
+    before: F,
+    queue: Arc<Queue>,
+    chain: Arc<ImageChain>,
+    image_index: u32,
+    command_buffer_allocator: &A,
+) -> Result<ImageChainPresentFuture<CommandBufferExecFuture<F>>, ImageChainReadbackError>
+where
+    F: GpuFuture,
+    A: CommandBufferAllocator,
+{
+    let buffer = chain
+        .readback_buffer(image_index)
+        .expect("`readback` was not enabled when creating the chain")
+        .clone();
+    let image = chain.images[image_index as usize].clone();
+
+    let mut builder = AutoCommandBufferBuilder::primary(
+        command_buffer_allocator,
+        queue.queue_family_index(),
+        CommandBufferUsage::OneTimeSubmit,
+    )?;
+    builder.copy_image_to_buffer(CopyImageToBufferInfo::image_buffer(image, buffer))?;
+
+    let before = before.then_execute(queue.clone(), builder.build()?)?;
+
+    Ok(present(before, queue, chain, image_index))
+}
+
+/// Error that can happen when calling [`present_with_readback`].
+#[derive(Clone, Debug)]
+pub enum ImageChainReadbackError {
+    /// Beginning the command buffer failed.
+    CommandBufferBeginError(CommandBufferBeginError),
+
+    /// Recording the copy command failed.
+    CopyError(CopyError),
+
+    /// Building the command buffer failed.
+    BuildError(BuildError),
+
+    /// Executing the command buffer failed.
+    CommandBufferExecError(CommandBufferExecError),
+}
+
+impl Error for ImageChainReadbackError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::CommandBufferBeginError(err) => Some(err),
+            Self::CopyError(err) => Some(err),
+            Self::BuildError(err) => Some(err),
+            Self::CommandBufferExecError(err) => Some(err),
+        }
+    }
+}
+
+impl Display for ImageChainReadbackError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::CommandBufferBeginError(_) => write!(f, "beginning the command buffer failed"),
+            Self::CopyError(_) => write!(f, "recording the copy command failed"),
+            Self::BuildError(_) => write!(f, "building the command buffer failed"),
+            Self::CommandBufferExecError(_) => write!(f, "executing the command buffer failed"),
+        }
+    }
+}
+
+impl From<CommandBufferBeginError> for ImageChainReadbackError {
+    fn from(err: CommandBufferBeginError) -> Self {
+        Self::CommandBufferBeginError(err)
+    }
+}
+
+impl From<CopyError> for ImageChainReadbackError {
+    fn from(err: CopyError) -> Self {
+        Self::CopyError(err)
+    }
+}
+
+impl From<BuildError> for ImageChainReadbackError {
+    fn from(err: BuildError) -> Self {
+        Self::BuildError(err)
+    }
+}
+
+impl From<CommandBufferExecError> for ImageChainReadbackError {
+    fn from(err: CommandBufferExecError) -> Self {
+        Self::CommandBufferExecError(err)
+    }
+}
+
+/// Represents the moment when the GPU has finished the work that renders into an image of an
+/// [`ImageChain`].
+///
+/// When flushed, this future submits the work of the previous future along with a fence, which
+/// [`acquire_next_image`] waits on before handing out the image again.
+#[must_use = "Dropping this object will immediately block the thread until the GPU has finished processing the submission"]
+pub struct ImageChainPresentFuture<P>
+where
+    P: GpuFuture,
+{
+    previous: P,
+    queue: Arc<Queue>,
+    chain: Arc<ImageChain>,
+    image_index: u32,
+    // The fence that was submitted when flushing, if `flush()` has succeeded.
+    fence: Mutex<Option<Arc<Fence>>>,
+    // True if `flush()` has been called on the future, which means that the submission has been
+    // attempted.
+    flushed: AtomicBool,
+    // True if `signal_finished()` has been called on the future, which means that the future has
+    // been submitted and has already been processed by the GPU.
+    finished: AtomicBool,
+}
+
+impl<P> ImageChainPresentFuture<P>
+where
+    P: GpuFuture,
+{
+    /// Returns the index of the image in the list of images of the chain.
+    #[inline]
+    pub fn image_id(&self) -> u32 {
+        self.image_index
+    }
+
+    /// Returns the corresponding image chain.
+    #[inline]
+    pub fn chain(&self) -> &Arc<ImageChain> {
+        &self.chain
+    }
+}
+
+unsafe impl<P> GpuFuture for ImageChainPresentFuture<P>
+where
+    P: GpuFuture,
+{
+    fn cleanup_finished(&mut self) {
+        self.previous.cleanup_finished();
+    }
+
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        // The fence must be part of our own submission, so the previous work is always submitted
+        // here rather than being merged with later work.
+        self.flush()?;
+
+        Ok(SubmitAnyBuilder::Empty)
+    }
+
+    fn flush(&self) -> Result<(), FlushError> {
+        unsafe {
+            if self.flushed.swap(true, Ordering::SeqCst) {
+                return Ok(());
+            }
+
+            let fence = Arc::new(Fence::from_pool(self.queue.device().clone())?);
+
+            let submit_infos = match self.previous.build_submission()? {
+                SubmitAnyBuilder::Empty => smallvec![SubmitInfo::default()],
+                SubmitAnyBuilder::SemaphoresWait(semaphores) => smallvec![SubmitInfo {
+                    wait_semaphores: semaphores
+                        .into_iter()
+                        .map(|semaphore| {
+                            SemaphoreSubmitInfo {
+                                stages: PipelineStages {
+                                    // TODO: correct stages ; hard
+                                    all_commands: true,
+                                    ..PipelineStages::empty()
+                                },
+                                ..SemaphoreSubmitInfo::semaphore(semaphore)
+                            }
+                        })
+                        .collect(),
+                    ..Default::default()
+                }],
+                SubmitAnyBuilder::CommandBuffer(submit_infos, fence) => {
+                    // Same remark as in `FenceSignalFuture`: this is part of the safety contract
+                    // of `GpuFuture`, but is easy to get wrong.
+                    assert!(fence.is_none());
+                    submit_infos
+                }
+                SubmitAnyBuilder::BindSparse(_, _) | SubmitAnyBuilder::QueuePresent(_) => {
+                    // A fence signal operation of a queue submission also waits for all work that
+                    // was submitted before it on the same queue.
+                    self.previous.flush()?;
+                    smallvec![SubmitInfo::default()]
+                }
+            };
+
+            self.queue
+                .with(|mut q| q.submit_unchecked(submit_infos, Some(fence.clone())))?;
+
+            *self.fence.lock() = Some(fence.clone());
+            self.chain.state.lock().present_fences[self.image_index as usize] = Some(fence);
+
+            Ok(())
+        }
+    }
+
+    unsafe fn signal_finished(&self) {
+        self.flushed.store(true, Ordering::SeqCst);
+        self.finished.store(true, Ordering::SeqCst);
+        self.previous.signal_finished();
+    }
+
+    fn queue_change_allowed(&self) -> bool {
+        false
+    }
+
+    fn queue(&self) -> Option<Arc<Queue>> {
+        debug_assert!(match self.previous.queue() {
+            None => true,
+            Some(q) => q == self.queue,
+        });
+
+        Some(self.queue.clone())
+    }
+
+    fn check_buffer_access(
+        &self,
+        buffer: &UnsafeBuffer,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        self.previous
+            .check_buffer_access(buffer, range, exclusive, queue)
+    }
+
+    fn check_image_access(
+        &self,
+        image: &UnsafeImage,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        expected_layout: ImageLayout,
+        queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        self.previous
+            .check_image_access(image, range, exclusive, expected_layout, queue)
+    }
+
+    #[inline]
+    fn check_swapchain_image_acquired(
+        &self,
+        image: &UnsafeImage,
+        before: bool,
+    ) -> Result<(), AccessCheckError> {
+        self.previous.check_swapchain_image_acquired(image, before)
+    }
+}
+
+unsafe impl<P> DeviceOwned for ImageChainPresentFuture<P>
+where
+    P: GpuFuture,
+{
+    fn device(&self) -> &Arc<Device> {
+        self.queue.device()
+    }
+}
+
+impl<P> Drop for ImageChainPresentFuture<P>
+where
+    P: GpuFuture,
+{
+    fn drop(&mut self) {
+        unsafe {
+            if !*self.flushed.get_mut() {
+                // Flushing may fail, that's okay. We will still wait for the queue later, so any
+                // previous futures that were flushed correctly will still be waited upon.
+                self.flush().ok();
+            }
+
+            if !*self.finished.get_mut() {
+                // Block until the submission has finished.
+                match self.fence.get_mut().clone() {
+                    Some(fence) => fence.wait(None).unwrap(),
+                    None => self.queue.with(|mut q| q.wait_idle()).unwrap(),
+                }
+
+                self.previous.signal_finished();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImageChain, ImageChainCreateInfo, ImageChainCreationError};
+    use crate::{
+        format::Format,
+        image::ImageUsage,
+        swapchain::headless::{acquire_next_image, present},
+        sync::GpuFuture,
+    };
+
+    #[test]
+    fn zero_image_count() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        match ImageChain::new(
+            device,
+            ImageChainCreateInfo {
+                image_count: 0,
+                image_extent: [16, 16],
+                image_usage: ImageUsage {
+                    color_attachment: true,
+                    ..ImageUsage::empty()
+                },
+                ..Default::default()
+            },
+        ) {
+            Err(ImageChainCreationError::ImageCountZero) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn readback_depth_format() {
+        let (device, _queue) = gfx_dev_and_queue!();
+
+        match ImageChain::new(
+            device,
+            ImageChainCreateInfo {
+                image_format: Format::D16_UNORM,
+                image_extent: [16, 16],
+                image_usage: ImageUsage {
+                    depth_stencil_attachment: true,
+                    ..ImageUsage::empty()
+                },
+                readback: true,
+                ..Default::default()
+            },
+        ) {
+            Err(ImageChainCreationError::ReadbackFormatNotSupported { .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn images_acquired_in_order() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let (chain, images) = ImageChain::new(
+            device,
+            ImageChainCreateInfo {
+                image_count: 3,
+                image_extent: [16, 16],
+                image_usage: ImageUsage {
+                    color_attachment: true,
+                    ..ImageUsage::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(images.len(), 3);
+
+        for expected_index in [0, 1, 2, 0, 1] {
+            let (image_index, suboptimal, acquire_future) =
+                acquire_next_image(chain.clone(), None).unwrap();
+            assert_eq!(image_index, expected_index);
+            assert!(!suboptimal);
+
+            present(acquire_future, queue.clone(), chain.clone(), image_index)
+                .then_signal_fence_and_flush()
+                .unwrap()
+                .wait(None)
+                .unwrap();
+        }
+    }
+}
@@ -242,6 +242,10 @@
 //!    the implementation that you are finished drawing to the image and that it can queue a
 //!    command to present the image on the screen after the draw operations are finished.
 //!
+//! `acquire_next_image` blocks until an image is available. A render loop that must not block can
+//! call `swapchain::try_acquire_next_image` instead, which returns `AcquireError::NotReady`
+//! immediately if no image is available, so that the frame can be skipped.
+//!
 //! ```
 //! use vulkano::swapchain::{self, SwapchainPresentInfo};
 //! use vulkano::sync::GpuFuture;
@@ -267,6 +271,11 @@
 //! }
 //! ```
 //!
+//! When rendering to several windows, the images of all their swapchains can be presented with a
+//! single present operation by using `then_swapchains_present` with one `SwapchainPresentInfo` per
+//! swapchain. The outcome for each swapchain is then available from
+//! `PresentFuture::swapchain_results`.
+//!
 //! ## Recreating a swapchain
 //!
 //! In some situations, the swapchain will become invalid by itself. This includes for example when
@@ -331,10 +340,11 @@ pub use self::{
         SurfaceInfo, SurfaceTransform,
     },
     swapchain::{
-        acquire_next_image, acquire_next_image_raw, present, wait_for_present, AcquireError,
-        AcquiredImage, FullScreenExclusive, FullScreenExclusiveError, PresentFuture,
-        PresentWaitError, Swapchain, SwapchainAbstract, SwapchainAcquireFuture,
-        SwapchainCreateInfo, SwapchainCreationError, Win32Monitor,
+        acquire_next_image, acquire_next_image_device_mask, acquire_next_image_raw, present,
+        present_multiple, try_acquire_next_image, wait_for_present, AcquireError, AcquiredImage,
+        FullScreenExclusive, FullScreenExclusiveError, HdrMetadata, HdrMetadataError,
+        PresentFuture, PresentWaitError, SuboptimalPolicy, Swapchain, SwapchainAbstract,
+        SwapchainAcquireFuture, SwapchainCreateInfo, SwapchainCreationError, Win32Monitor,
     },
 };
 #[cfg(target_os = "ios")]
@@ -347,6 +357,7 @@ use std::{
 };
 
 pub mod display;
+pub mod headless;
 mod surface;
 mod swapchain;
 
@@ -417,6 +428,16 @@ pub struct SwapchainPresentInfo {
     /// The default value is empty.
     pub present_regions: Vec<RectangleLayer>,
 
+    /// The physical device that presents the image, when the device was created from a device
+    /// group, as a bitmask with a single bit set for the device index.
+    ///
+    /// If `device_mask` is `0`, the image is presented by the physical device with device index
+    /// 0. For alternate frame rendering, this is the same mask that the image was acquired with
+    /// in [`acquire_next_image_device_mask`].
+    ///
+    /// The default value is `0`.
+    pub device_mask: u32,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -429,6 +450,7 @@ impl SwapchainPresentInfo {
             image_index,
             present_id: None,
             present_regions: Vec::new(),
+            device_mask: 0,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -15,7 +15,7 @@ use crate::{
     instance::Instance,
     macros::{vulkan_bitflags, vulkan_enum},
     swapchain::{
-        display::{DisplayMode, DisplayPlane},
+        display::{DisplayMode, DisplayPlane, DisplayPlaneAlpha, DisplaySurfaceCreateInfo},
         SurfaceSwapchainLock,
     },
     OomError, RequiresOneOf, VulkanError, VulkanObject,
@@ -52,7 +52,8 @@ pub struct Surface<W> {
     // `Surface` is destroyed.
     pub(crate) surface_formats:
         OnceCache<(ash::vk::PhysicalDevice, SurfaceInfo), Vec<(Format, ColorSpace)>>,
-    pub(crate) surface_present_modes: OnceCache<ash::vk::PhysicalDevice, Vec<PresentMode>>,
+    pub(crate) surface_present_modes:
+        OnceCache<(ash::vk::PhysicalDevice, SurfaceInfo), Vec<PresentMode>>,
     pub(crate) surface_support: OnceCache<(ash::vk::PhysicalDevice, u32), bool>,
 }
 
@@ -153,6 +154,9 @@ impl<W> Surface<W> {
 
     /// Creates a `Surface` from a `DisplayPlane`.
     ///
+    /// The surface is shown on the display of `display_mode`, using that mode. See the
+    /// [`display`](crate::swapchain::display) module for how to choose a mode and a plane.
+    ///
     /// # Panics
     ///
     /// - Panics if `display_mode` and `plane` don't belong to the same physical device.
@@ -160,15 +164,23 @@ impl<W> Surface<W> {
     pub fn from_display_plane(
         display_mode: &DisplayMode,
         plane: &DisplayPlane,
+        create_info: DisplaySurfaceCreateInfo,
     ) -> Result<Arc<Surface<()>>, SurfaceCreationError> {
-        Self::validate_from_display_plane(display_mode, plane)?;
+        Self::validate_from_display_plane(display_mode, plane, &create_info)?;
 
-        unsafe { Ok(Self::from_display_plane_unchecked(display_mode, plane)?) }
+        unsafe {
+            Ok(Self::from_display_plane_unchecked(
+                display_mode,
+                plane,
+                create_info,
+            )?)
+        }
     }
 
     fn validate_from_display_plane(
         display_mode: &DisplayMode,
         plane: &DisplayPlane,
+        create_info: &DisplaySurfaceCreateInfo,
     ) -> Result<(), SurfaceCreationError> {
         if !display_mode
             .display()
@@ -192,6 +204,53 @@ impl<W> Surface<W> {
         );
         assert!(plane.supports(display_mode.display()));
 
+        let &DisplaySurfaceCreateInfo {
+            transform,
+            alpha_mode,
+            global_alpha,
+            image_extent,
+            _ne: _,
+        } = create_info;
+
+        // VUID-VkDisplaySurfaceCreateInfoKHR-transform-06740
+        if !display_mode
+            .display()
+            .supported_transforms()
+            .supports(transform)
+        {
+            return Err(SurfaceCreationError::DisplayTransformNotSupported { transform });
+        }
+
+        // VUID-VkDisplaySurfaceCreateInfoKHR-alphaMode-01255
+        if !plane
+            .capabilities(display_mode)?
+            .supported_alpha
+            .supports(alpha_mode)
+        {
+            return Err(SurfaceCreationError::DisplayPlaneAlphaNotSupported { alpha_mode });
+        }
+
+        // VUID-VkDisplaySurfaceCreateInfoKHR-alphaMode-01254
+        if alpha_mode == DisplayPlaneAlpha::Global && !(0.0..=1.0).contains(&global_alpha) {
+            return Err(SurfaceCreationError::GlobalAlphaOutOfRange);
+        }
+
+        // VUID-VkDisplaySurfaceCreateInfoKHR-width-01256
+        if let Some(image_extent) = image_extent {
+            let max = display_mode
+                .display()
+                .physical_device()
+                .properties()
+                .max_image_dimension2_d;
+
+            if image_extent[0] > max || image_extent[1] > max {
+                return Err(SurfaceCreationError::MaxImageDimension2DExceeded {
+                    provided: image_extent,
+                    max,
+                });
+            }
+        }
+
         Ok(())
     }
 
@@ -199,21 +258,30 @@ impl<W> Surface<W> {
     pub unsafe fn from_display_plane_unchecked(
         display_mode: &DisplayMode,
         plane: &DisplayPlane,
+        create_info: DisplaySurfaceCreateInfo,
     ) -> Result<Arc<Surface<()>>, VulkanError> {
+        let DisplaySurfaceCreateInfo {
+            transform,
+            alpha_mode,
+            global_alpha,
+            image_extent,
+            _ne: _,
+        } = create_info;
+
         let instance = display_mode.display().physical_device().instance();
+        let image_extent = image_extent.unwrap_or_else(|| display_mode.visible_region());
 
         let create_info = ash::vk::DisplaySurfaceCreateInfoKHR {
             flags: ash::vk::DisplaySurfaceCreateFlagsKHR::empty(),
             display_mode: display_mode.internal_object(),
             plane_index: plane.index(),
-            plane_stack_index: 0, // FIXME: plane.properties.currentStackIndex,
-            transform: ash::vk::SurfaceTransformFlagsKHR::IDENTITY, // TODO: let user choose
-            global_alpha: 0.0,    // TODO: let user choose
-            alpha_mode: ash::vk::DisplayPlaneAlphaFlagsKHR::OPAQUE, // TODO: let user choose
+            plane_stack_index: plane.current_stack_index(),
+            transform: transform.into(),
+            global_alpha,
+            alpha_mode: alpha_mode.into(),
             image_extent: ash::vk::Extent2D {
-                // TODO: let user choose
-                width: display_mode.visible_region()[0],
-                height: display_mode.visible_region()[1],
+                width: image_extent[0],
+                height: image_extent[1],
             },
             ..Default::default()
         };
@@ -1461,6 +1529,18 @@ pub enum SurfaceCreationError {
         required_for: &'static str,
         requires_one_of: RequiresOneOf,
     },
+
+    /// The display plane does not support the provided alpha mode.
+    DisplayPlaneAlphaNotSupported { alpha_mode: DisplayPlaneAlpha },
+
+    /// The display does not support the provided transform.
+    DisplayTransformNotSupported { transform: SurfaceTransform },
+
+    /// The global alpha value is not between 0.0 and 1.0 inclusive.
+    GlobalAlphaOutOfRange,
+
+    /// The image extent exceeds the `max_image_dimension2_d` limit.
+    MaxImageDimension2DExceeded { provided: [u32; 2], max: u32 },
 }
 
 impl Error for SurfaceCreationError {
@@ -1484,6 +1564,25 @@ impl Display for SurfaceCreationError {
                 "a requirement was not met for: {}; requires one of: {}",
                 required_for, requires_one_of,
             ),
+            Self::DisplayPlaneAlphaNotSupported { alpha_mode } => write!(
+                f,
+                "the display plane does not support the provided alpha mode {:?}",
+                alpha_mode,
+            ),
+            Self::DisplayTransformNotSupported { transform } => write!(
+                f,
+                "the display does not support the provided transform {:?}",
+                transform,
+            ),
+            Self::GlobalAlphaOutOfRange => write!(
+                f,
+                "the global alpha value is not between 0.0 and 1.0 inclusive",
+            ),
+            Self::MaxImageDimension2DExceeded { provided, max } => write!(
+                f,
+                "the image extent {:?} exceeds the `max_image_dimension2_d` limit ({})",
+                provided, max,
+            ),
         }
     }
 }
@@ -1848,12 +1947,14 @@ vulkan_enum! {
         instance_extensions: [ext_swapchain_colorspace],
     },
 
-    // TODO: document
+    /// The scRGB color space, with linear encoding. Values outside of the `0.0..=1.0` range
+    /// extend the gamut and brightness beyond sRGB, so this is typically used for HDR with a
+    /// floating-point format such as `R16G16B16A16_SFLOAT`.
     ExtendedSrgbLinear = EXTENDED_SRGB_LINEAR_EXT {
         instance_extensions: [ext_swapchain_colorspace],
     },
 
-    // TODO: document
+    /// The scRGB color space, with the sRGB transfer function.
     ExtendedSrgbNonLinear = EXTENDED_SRGB_NONLINEAR_EXT {
         instance_extensions: [ext_swapchain_colorspace],
     },
@@ -1883,7 +1984,11 @@ vulkan_enum! {
         instance_extensions: [ext_swapchain_colorspace],
     },
 
-    // TODO: document
+    /// The HDR10 color space: ITU-R BT.2020 primaries with the SMPTE ST 2084 (PQ) transfer
+    /// function. This is typically used with a 10-bit format such as
+    /// `A2B10G10R10_UNORM_PACK32`, together with [`Swapchain::set_hdr_metadata`].
+    ///
+    /// [`Swapchain::set_hdr_metadata`]: crate::swapchain::Swapchain::set_hdr_metadata
     Hdr10St2084 = HDR10_ST2084_EXT {
         instance_extensions: [ext_swapchain_colorspace],
     },
@@ -1893,7 +1998,7 @@ vulkan_enum! {
         instance_extensions: [ext_swapchain_colorspace],
     },
 
-    // TODO: document
+    /// ITU-R BT.2020 primaries with the Hybrid Log Gamma (HLG) transfer function.
     Hdr10Hlg = HDR10_HLG_EXT {
         instance_extensions: [ext_swapchain_colorspace],
     },
@@ -1919,14 +2024,31 @@ vulkan_enum! {
     },
 }
 
-/// Parameters for [`PhysicalDevice::surface_capabilities`] and [`PhysicalDevice::surface_formats`].
+/// Parameters for [`PhysicalDevice::surface_capabilities`], [`PhysicalDevice::surface_formats`]
+/// and [`PhysicalDevice::surface_present_modes`].
 ///
 /// [`PhysicalDevice::surface_capabilities`]: crate::device::physical::PhysicalDevice::surface_capabilities
 /// [`PhysicalDevice::surface_formats`]: crate::device::physical::PhysicalDevice::surface_formats
+/// [`PhysicalDevice::surface_present_modes`]: crate::device::physical::PhysicalDevice::surface_present_modes
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct SurfaceInfo {
+    /// The full-screen exclusive mode that a swapchain created for the surface would use.
+    ///
+    /// If this is not `FullScreenExclusive::Default`, the
+    /// [`ext_full_screen_exclusive`](crate::device::DeviceExtensions::ext_full_screen_exclusive)
+    /// extension must be supported by the physical device.
+    ///
+    /// The default value is [`FullScreenExclusive::Default`].
     pub full_screen_exclusive: FullScreenExclusive,
+
+    /// The monitor that full-screen exclusivity would be acquired on.
+    ///
+    /// This must be `Some` if the surface is a Win32 surface and `full_screen_exclusive` is
+    /// `FullScreenExclusive::ApplicationControlled`, and `None` otherwise.
+    ///
+    /// The default value is `None`.
     pub win32_monitor: Option<Win32Monitor>,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -2024,11 +2146,72 @@ pub struct SurfaceCapabilities {
 #[cfg(test)]
 mod tests {
     use crate::{
-        swapchain::{Surface, SurfaceCreationError},
-        RequiresOneOf,
+        device::physical::PhysicalDeviceError,
+        instance::{Instance, InstanceCreateInfo, InstanceExtensions},
+        swapchain::{FullScreenExclusive, PresentMode, Surface, SurfaceCreationError, SurfaceInfo},
+        RequiresOneOf, VulkanLibrary,
     };
     use std::ptr;
 
+    #[test]
+    fn surface_present_modes() {
+        let library = match VulkanLibrary::new() {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        let instance = match Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_extensions: InstanceExtensions {
+                    khr_surface: true,
+                    ext_headless_surface: true,
+                    ..InstanceExtensions::empty()
+                },
+                ..Default::default()
+            },
+        ) {
+            Ok(x) => x,
+            Err(_) => return,
+        };
+        let surface = Surface::headless(instance.clone(), ()).unwrap();
+        let physical_device = match instance.enumerate_physical_devices().unwrap().find(|p| {
+            (0..p.queue_family_properties().len() as u32)
+                .any(|i| p.surface_support(i, &surface).unwrap_or(false))
+        }) {
+            Some(x) => x,
+            None => return,
+        };
+
+        // FIFO is always supported.
+        let present_modes: Vec<_> = physical_device
+            .surface_present_modes(&surface, SurfaceInfo::default())
+            .unwrap()
+            .collect();
+        assert!(present_modes.contains(&PresentMode::Fifo));
+
+        // The second call is answered from the cache.
+        assert_eq!(
+            physical_device
+                .surface_present_modes(&surface, SurfaceInfo::default())
+                .unwrap()
+                .collect::<Vec<_>>(),
+            present_modes,
+        );
+
+        // Without `khr_get_surface_capabilities2`, only the default full-screen exclusive mode
+        // can be queried.
+        assert!(matches!(
+            physical_device.surface_present_modes(
+                &surface,
+                SurfaceInfo {
+                    full_screen_exclusive: FullScreenExclusive::Disallowed,
+                    ..Default::default()
+                },
+            ),
+            Err(PhysicalDeviceError::NotSupported)
+        ));
+    }
+
     #[test]
     fn khr_win32_surface_ext_missing() {
         let instance = instance!();
@@ -16,8 +16,10 @@ use crate::{
     device::{Device, DeviceOwned, Queue},
     format::Format,
     image::{
-        sys::UnsafeImage, ImageCreateFlags, ImageDimensions, ImageFormatInfo, ImageInner,
-        ImageLayout, ImageTiling, ImageType, ImageUsage, SampleCount, SwapchainImage,
+        sys::UnsafeImage,
+        view::{ImageView, ImageViewCreationError},
+        ImageCreateFlags, ImageDimensions, ImageFormatInfo, ImageInner, ImageLayout, ImageTiling,
+        ImageType, ImageUsage, SampleCount, SwapchainImage,
     },
     macros::vulkan_enum,
     swapchain::{PresentInfo, SurfaceApi, SurfaceInfo, SurfaceSwapchainLock},
@@ -25,7 +27,7 @@ use crate::{
         AccessCheckError, AccessError, AccessFlags, Fence, FenceError, FlushError, GpuFuture,
         PipelineStages, Semaphore, SemaphoreError, Sharing, SubmitAnyBuilder,
     },
-    DeviceSize, OomError, RequirementNotMet, RequiresOneOf, VulkanError, VulkanObject,
+    DeviceSize, OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
 use parking_lot::Mutex;
 use smallvec::{smallvec, SmallVec};
@@ -65,9 +67,15 @@ pub struct Swapchain<W> {
     win32_monitor: Option<Win32Monitor>,
     prev_present_id: AtomicU64,
 
+    suboptimal_policy: SuboptimalPolicy,
+
     // Whether full-screen exclusive is currently held.
     full_screen_exclusive_held: AtomicBool,
 
+    // Whether an operation on the swapchain, or the user, has reported that the swapchain should
+    // be recreated.
+    needs_recreation: AtomicBool,
+
     // The images of this swapchain.
     images: Vec<ImageEntry>,
 
@@ -136,6 +144,7 @@ where
             clipped,
             full_screen_exclusive,
             win32_monitor,
+            suboptimal_policy,
             _ne: _,
         } = create_info;
 
@@ -158,8 +167,10 @@ where
             full_screen_exclusive,
             win32_monitor,
             prev_present_id: Default::default(),
+            suboptimal_policy,
 
             full_screen_exclusive_held: AtomicBool::new(false),
+            needs_recreation: AtomicBool::new(false),
             images,
             retired: Mutex::new(false),
         });
@@ -171,6 +182,32 @@ where
         Ok((swapchain, swapchain_images))
     }
 
+    /// Creates a new `Swapchain`, and returns a view of each of its images instead of the images
+    /// themselves.
+    ///
+    /// The views are created with [`SwapchainImage::view`], and are returned by it for as long as
+    /// they are kept alive. Keep them alongside the swapchain until it is recreated, so that the
+    /// views aren't created again each frame. The images can be accessed with
+    /// [`ImageView::image`].
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the device and the surface don't belong to the same instance.
+    /// - Panics if `create_info.usage` is empty.
+    pub fn new_with_views(
+        device: Arc<Device>,
+        surface: Arc<Surface<W>>,
+        create_info: SwapchainCreateInfo,
+    ) -> Result<(Arc<Swapchain<W>>, Vec<Arc<ImageView<SwapchainImage<W>>>>), SwapchainCreationError>
+    where
+        W: 'static,
+    {
+        let (swapchain, images) = Self::new(device, surface, create_info)?;
+        let views = SwapchainImage::views(&images)?;
+
+        Ok((swapchain, views))
+    }
+
     /// Creates a new swapchain from this one.
     ///
     /// Use this when a swapchain has become invalidated, such as due to window resizes.
@@ -230,6 +267,7 @@ where
             clipped,
             full_screen_exclusive,
             win32_monitor,
+            suboptimal_policy,
             _ne: _,
         } = create_info;
 
@@ -252,8 +290,10 @@ where
             full_screen_exclusive,
             win32_monitor,
             prev_present_id: Default::default(),
+            suboptimal_policy,
 
             full_screen_exclusive_held: AtomicBool::new(full_screen_exclusive_held),
+            needs_recreation: AtomicBool::new(false),
             images,
             retired: Mutex::new(false),
         });
@@ -265,6 +305,27 @@ where
         Ok((swapchain, swapchain_images))
     }
 
+    /// Creates a new swapchain from this one, and returns a view of each of its images instead of
+    /// the images themselves.
+    ///
+    /// See [`new_with_views`](Self::new_with_views) for details about the views.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `create_info.usage` is empty.
+    pub fn recreate_with_views(
+        self: &Arc<Self>,
+        create_info: SwapchainCreateInfo,
+    ) -> Result<(Arc<Swapchain<W>>, Vec<Arc<ImageView<SwapchainImage<W>>>>), SwapchainCreationError>
+    where
+        W: 'static,
+    {
+        let (swapchain, images) = self.recreate(create_info)?;
+        let views = SwapchainImage::views(&images)?;
+
+        Ok((swapchain, views))
+    }
+
     fn validate(
         device: &Device,
         surface: &Surface<W>,
@@ -284,6 +345,7 @@ where
             clipped: _,
             full_screen_exclusive,
             win32_monitor,
+            suboptimal_policy: _,
             _ne: _,
         } = create_info;
 
@@ -522,9 +584,14 @@ where
         // VUID-VkSwapchainCreateInfoKHR-presentMode-01281
         // Use unchecked, because all validation has been done above.
         if !unsafe {
-            device
-                .physical_device()
-                .surface_present_modes_unchecked(surface)?
+            device.physical_device().surface_present_modes_unchecked(
+                surface,
+                SurfaceInfo {
+                    full_screen_exclusive,
+                    win32_monitor,
+                    ..Default::default()
+                },
+            )?
         }
         .any(|mode| mode == present_mode)
         {
@@ -572,6 +639,7 @@ where
             clipped,
             full_screen_exclusive,
             win32_monitor,
+            suboptimal_policy: _,
             _ne: _,
         } = create_info;
 
@@ -691,7 +759,7 @@ where
             image_extent,
             image_array_layers,
             image_usage,
-            image_sharing: _, // TODO: put this in the image too
+            ref image_sharing,
             ..
         } = create_info;
 
@@ -714,6 +782,7 @@ where
                         dims,
                         SampleCount::Sample1,
                         1,
+                        image_sharing.clone(),
                     )
                 };
 
@@ -741,6 +810,7 @@ where
             clipped: self.clipped,
             full_screen_exclusive: self.full_screen_exclusive,
             win32_monitor: self.win32_monitor,
+            suboptimal_policy: self.suboptimal_policy,
             _ne: crate::NonExhaustive(()),
         }
     }
@@ -775,6 +845,31 @@ where
         self.full_screen_exclusive
     }
 
+    /// Returns the suboptimal policy that was passed when creating the swapchain.
+    pub fn suboptimal_policy(&self) -> SuboptimalPolicy {
+        self.suboptimal_policy
+    }
+
+    /// Returns whether the swapchain should be recreated before acquiring the next image.
+    ///
+    /// This is the case if acquiring or presenting an image returned
+    /// [`AcquireError::OutOfDate`] or [`FlushError::OutOfDate`], if it reported the swapchain
+    /// as suboptimal and the [`SuboptimalPolicy`] of the swapchain is not
+    /// [`SuboptimalPolicy::Ignore`], or if [`surface_resized`](Self::surface_resized) was called.
+    /// It is never the case for a newly created swapchain.
+    pub fn needs_recreation(&self) -> bool {
+        self.needs_recreation.load(Ordering::Acquire)
+    }
+
+    /// Notifies the swapchain that the size of its surface has changed, for example because the
+    /// window was resized, so that [`needs_recreation`](Self::needs_recreation) returns `true`.
+    ///
+    /// Many windowing systems don't report a resize through the swapchain operations, so the
+    /// application should call this from its resize event handler.
+    pub fn surface_resized(&self) {
+        self.needs_recreation.store(true, Ordering::Release);
+    }
+
     /// Acquires full-screen exclusivity.
     ///
     /// The swapchain must have been created with [`FullScreenExclusive::ApplicationControlled`],
@@ -846,6 +941,74 @@ where
         }
     }
 
+    /// Sets the HDR metadata of the swapchain, which describes the mastering display and content
+    /// light levels of the images that are presented to it.
+    ///
+    /// The metadata is used by the presentation engine from the next present operation onwards.
+    /// It is meant to be used together with an HDR color space, such as
+    /// [`ColorSpace::Hdr10St2084`].
+    ///
+    /// The [`ext_hdr_metadata`](crate::device::DeviceExtensions::ext_hdr_metadata) extension must
+    /// be enabled on the device.
+    pub fn set_hdr_metadata(&self, metadata: HdrMetadata) -> Result<(), HdrMetadataError> {
+        self.validate_set_hdr_metadata(&metadata)?;
+
+        unsafe { self.set_hdr_metadata_unchecked(metadata) };
+
+        Ok(())
+    }
+
+    fn validate_set_hdr_metadata(&self, _metadata: &HdrMetadata) -> Result<(), HdrMetadataError> {
+        if !self.device.enabled_extensions().ext_hdr_metadata {
+            return Err(HdrMetadataError::RequirementNotMet {
+                required_for: "`set_hdr_metadata`",
+                requires_one_of: RequiresOneOf {
+                    device_extensions: &["ext_hdr_metadata"],
+                    ..Default::default()
+                },
+            });
+        }
+
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    pub unsafe fn set_hdr_metadata_unchecked(&self, metadata: HdrMetadata) {
+        let HdrMetadata {
+            display_primary_red,
+            display_primary_green,
+            display_primary_blue,
+            white_point,
+            max_luminance,
+            min_luminance,
+            max_content_light_level,
+            max_frame_average_light_level,
+            _ne: _,
+        } = metadata;
+
+        let xy_color = |[x, y]: [f32; 2]| ash::vk::XYColorEXT { x, y };
+
+        let metadata_vk = ash::vk::HdrMetadataEXT {
+            display_primary_red: xy_color(display_primary_red),
+            display_primary_green: xy_color(display_primary_green),
+            display_primary_blue: xy_color(display_primary_blue),
+            white_point: xy_color(white_point),
+            max_luminance,
+            min_luminance,
+            max_content_light_level,
+            max_frame_average_light_level,
+            ..Default::default()
+        };
+
+        let fns = self.device.fns();
+        (fns.ext_hdr_metadata.set_hdr_metadata_ext)(
+            self.device.internal_object(),
+            1,
+            &self.handle,
+            &metadata_vk,
+        );
+    }
+
     // This method is necessary to allow `SwapchainImage`s to signal when they have been
     // transitioned out of their initial `undefined` image layout.
     //
@@ -869,6 +1032,9 @@ where
 
 impl<W> Drop for Swapchain<W> {
     fn drop(&mut self) {
+        #[cfg(feature = "tracing")]
+        self.device.forget_traced_object_name(self);
+
         unsafe {
             let fns = self.device.fns();
             (fns.khr_swapchain.destroy_swapchain_khr)(
@@ -985,8 +1151,17 @@ pub unsafe trait SwapchainAbstract:
     #[doc(hidden)]
     unsafe fn try_claim_present_id(&self, present_id: NonZeroU64) -> bool;
 
+    /// Returns the policy for handling suboptimal results of the swapchain.
+    fn suboptimal_policy(&self) -> SuboptimalPolicy;
+
     #[doc(hidden)]
     unsafe fn full_screen_exclusive_held(&self) -> &AtomicBool;
+
+    // Marks the swapchain as needing recreation if an acquire or present operation reported it
+    // as out of date, or as suboptimal and the suboptimal policy allows it. Returns whether the
+    // result of the operation must be treated as out of date.
+    #[doc(hidden)]
+    unsafe fn update_needs_recreation(&self, out_of_date: bool, suboptimal: bool) -> bool;
 }
 
 unsafe impl<W> SwapchainAbstract for Swapchain<W>
@@ -1023,10 +1198,30 @@ where
         self.image_array_layers
     }
 
+    fn suboptimal_policy(&self) -> SuboptimalPolicy {
+        self.suboptimal_policy
+    }
+
     unsafe fn full_screen_exclusive_held(&self) -> &AtomicBool {
         &self.full_screen_exclusive_held
     }
 
+    unsafe fn update_needs_recreation(&self, out_of_date: bool, suboptimal: bool) -> bool {
+        let (needs_recreation, treat_as_out_of_date) = match self.suboptimal_policy {
+            _ if out_of_date => (true, true),
+            _ if !suboptimal => (false, false),
+            SuboptimalPolicy::RecreateImmediately => (true, true),
+            SuboptimalPolicy::RecreateNextFrame => (true, false),
+            SuboptimalPolicy::Ignore => (false, false),
+        };
+
+        if needs_recreation {
+            self.needs_recreation.store(true, Ordering::Release);
+        }
+
+        treat_as_out_of_date
+    }
+
     unsafe fn try_claim_present_id(&self, present_id: NonZeroU64) -> bool {
         let present_id = u64::from(present_id);
         self.prev_present_id.fetch_max(present_id, Ordering::SeqCst) < present_id
@@ -1141,6 +1336,11 @@ pub struct SwapchainCreateInfo {
     /// The default value is `None`.
     pub win32_monitor: Option<Win32Monitor>,
 
+    /// How acquire and present operations that report the swapchain as suboptimal are handled.
+    ///
+    /// The default value is [`SuboptimalPolicy::RecreateNextFrame`].
+    pub suboptimal_policy: SuboptimalPolicy,
+
     pub _ne: crate::NonExhaustive,
 }
 
@@ -1161,11 +1361,34 @@ impl Default for SwapchainCreateInfo {
             clipped: true,
             full_screen_exclusive: FullScreenExclusive::Default,
             win32_monitor: None,
+            suboptimal_policy: SuboptimalPolicy::RecreateNextFrame,
             _ne: crate::NonExhaustive(()),
         }
     }
 }
 
+/// How a swapchain handles acquire and present operations that report it as suboptimal.
+///
+/// A suboptimal swapchain can still be used to present images, but no longer matches the
+/// properties of the surface exactly, for example after a window was resized on some platforms.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SuboptimalPolicy {
+    /// A suboptimal acquire is treated as out of date: [`acquire_next_image`] returns
+    /// [`AcquireError::OutOfDate`] instead of the image, and the swapchain must be recreated
+    /// before acquiring another image.
+    RecreateImmediately,
+
+    /// The acquired image is returned and can be used for the current frame, but
+    /// [`Swapchain::needs_recreation`] returns `true` afterwards, so that the swapchain can be
+    /// recreated before the next frame.
+    RecreateNextFrame,
+
+    /// Suboptimal results are only reported through the returned `bool`, and don't affect
+    /// [`Swapchain::needs_recreation`].
+    Ignore,
+}
+
 /// Error that can happen when creating a `Swapchain`.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum SwapchainCreationError {
@@ -1260,12 +1483,16 @@ pub enum SwapchainCreationError {
 
     /// The `win32_monitor` value was `Some` when it must be `None` or vice-versa.
     Win32MonitorInvalid,
+
+    /// Creating the views of the swapchain images failed.
+    ImageViewCreationError(ImageViewCreationError),
 }
 
 impl Error for SwapchainCreationError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             Self::OomError(err) => Some(err),
+            Self::ImageViewCreationError(err) => Some(err),
             _ => None,
         }
     }
@@ -1371,6 +1598,9 @@ impl Display for SwapchainCreationError {
                 f,
                 "the `win32_monitor` value was `Some` when it must be `None` or vice-versa",
             ),
+            Self::ImageViewCreationError(_) => {
+                write!(f, "creating the views of the swapchain images failed")
+            }
         }
     }
 }
@@ -1403,6 +1633,12 @@ impl From<RequirementNotMet> for SwapchainCreationError {
     }
 }
 
+impl From<ImageViewCreationError> for SwapchainCreationError {
+    fn from(err: ImageViewCreationError) -> Self {
+        Self::ImageViewCreationError(err)
+    }
+}
+
 vulkan_enum! {
     /// The way full-screen exclusivity is handled.
     #[non_exhaustive]
@@ -1447,6 +1683,107 @@ impl Win32Monitor {
 unsafe impl Send for Win32Monitor {}
 unsafe impl Sync for Win32Monitor {}
 
+/// HDR metadata of a swapchain, as set with [`Swapchain::set_hdr_metadata`].
+///
+/// Chromaticity coordinates are given as CIE 1931 `[x, y]` pairs, and luminance values in nits
+/// (candela per square meter).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HdrMetadata {
+    /// The chromaticity of the red primary of the mastering display.
+    ///
+    /// The default value is `[0.708, 0.292]`, the red primary of ITU-R BT.2020.
+    pub display_primary_red: [f32; 2],
+
+    /// The chromaticity of the green primary of the mastering display.
+    ///
+    /// The default value is `[0.170, 0.797]`, the green primary of ITU-R BT.2020.
+    pub display_primary_green: [f32; 2],
+
+    /// The chromaticity of the blue primary of the mastering display.
+    ///
+    /// The default value is `[0.131, 0.046]`, the blue primary of ITU-R BT.2020.
+    pub display_primary_blue: [f32; 2],
+
+    /// The chromaticity of the white point of the mastering display.
+    ///
+    /// The default value is `[0.3127, 0.3290]`, the D65 white point.
+    pub white_point: [f32; 2],
+
+    /// The maximum luminance of the mastering display.
+    ///
+    /// The default value is `0.0`.
+    pub max_luminance: f32,
+
+    /// The minimum luminance of the mastering display.
+    ///
+    /// The default value is `0.0`.
+    pub min_luminance: f32,
+
+    /// The maximum luminance of any single pixel of the content (MaxCLL).
+    ///
+    /// The default value is `0.0`.
+    pub max_content_light_level: f32,
+
+    /// The maximum average luminance of a whole frame of the content (MaxFALL).
+    ///
+    /// The default value is `0.0`.
+    pub max_frame_average_light_level: f32,
+
+    pub _ne: crate::NonExhaustive,
+}
+
+impl Default for HdrMetadata {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            display_primary_red: [0.708, 0.292],
+            display_primary_green: [0.170, 0.797],
+            display_primary_blue: [0.131, 0.046],
+            white_point: [0.3127, 0.3290],
+            max_luminance: 0.0,
+            min_luminance: 0.0,
+            max_content_light_level: 0.0,
+            max_frame_average_light_level: 0.0,
+            _ne: crate::NonExhaustive(()),
+        }
+    }
+}
+
+/// Error that can happen when calling `Swapchain::set_hdr_metadata`.
+#[derive(Clone, Debug)]
+pub enum HdrMetadataError {
+    RequirementNotMet {
+        required_for: &'static str,
+        requires_one_of: RequiresOneOf,
+    },
+}
+
+impl Error for HdrMetadataError {}
+
+impl Display for HdrMetadataError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self {
+            Self::RequirementNotMet {
+                required_for,
+                requires_one_of,
+            } => write!(
+                f,
+                "a requirement was not met for: {}; requires one of: {}",
+                required_for, requires_one_of,
+            ),
+        }
+    }
+}
+
+impl From<RequirementNotMet> for HdrMetadataError {
+    fn from(err: RequirementNotMet) -> Self {
+        Self::RequirementNotMet {
+            required_for: err.required_for,
+            requires_one_of: err.requires_one_of,
+        }
+    }
+}
+
 /// Error that can happen when calling `Swapchain::acquire_full_screen_exclusive` or
 /// `Swapchain::release_full_screen_exclusive`.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -1538,9 +1875,54 @@ impl From<OomError> for FullScreenExclusiveError {
 /// The second field in the tuple in the Ok result is a bool represent if the acquisition was
 /// suboptimal. In this case the acquired image is still usable, but the swapchain should be
 /// recreated as the Surface's properties no longer match the swapchain.
+///
+/// If `timeout` is `None`, the function blocks until an image is available. Otherwise, it returns
+/// [`AcquireError::Timeout`] if no image became available within `timeout`. A timeout of zero
+/// never blocks, and returns [`AcquireError::NotReady`] instead; see also
+/// [`try_acquire_next_image`].
 pub fn acquire_next_image<W>(
     swapchain: Arc<Swapchain<W>>,
     timeout: Option<Duration>,
+) -> Result<(u32, bool, SwapchainAcquireFuture<W>), AcquireError> {
+    acquire_next_image_impl(swapchain, timeout, None)
+}
+
+/// Tries to take ownership of an image in order to draw on it with some of the physical devices
+/// of a device group.
+///
+/// This is the same as [`acquire_next_image`], except that the returned future only becomes
+/// ready for the physical devices in `device_mask`. This is used for alternate frame rendering,
+/// where each frame is drawn by a different physical device, and the image is then presented by
+/// that same physical device by setting [`SwapchainPresentInfo::device_mask`] to the same value.
+///
+/// # Panics
+///
+/// - Panics if the device API version is less than 1.1, and the
+///   [`khr_device_group`](crate::device::DeviceExtensions::khr_device_group) extension is not
+///   enabled on the device.
+/// - Panics if `device_mask` is zero, or contains bits for physical devices that the device was
+///   not created from.
+pub fn acquire_next_image_device_mask<W>(
+    swapchain: Arc<Swapchain<W>>,
+    timeout: Option<Duration>,
+    device_mask: u32,
+) -> Result<(u32, bool, SwapchainAcquireFuture<W>), AcquireError> {
+    assert!(
+        swapchain.device.api_version() >= Version::V1_1
+            || swapchain.device.enabled_extensions().khr_device_group
+    );
+
+    // VUID-VkAcquireNextImageInfoKHR-deviceMask-01290
+    // VUID-VkAcquireNextImageInfoKHR-deviceMask-01291
+    assert!(device_mask != 0 && device_mask & !swapchain.device.all_devices_mask() == 0);
+
+    acquire_next_image_impl(swapchain, timeout, Some(device_mask))
+}
+
+fn acquire_next_image_impl<W>(
+    swapchain: Arc<Swapchain<W>>,
+    timeout: Option<Duration>,
+    device_mask: Option<u32>,
 ) -> Result<(u32, bool, SwapchainAcquireFuture<W>), AcquireError> {
     let semaphore = Arc::new(Semaphore::from_pool(swapchain.device.clone())?);
     let fence = Fence::from_pool(swapchain.device.clone())?;
@@ -1557,29 +1939,60 @@ pub fn acquire_next_image<W>(
             return Err(AcquireError::OutOfDate);
         }
 
-        let acquire_result =
-            unsafe { acquire_next_image_raw(&swapchain, timeout, Some(&semaphore), Some(&fence)) };
+        let acquire_result = unsafe {
+            acquire_next_image_raw_impl(
+                &swapchain,
+                timeout,
+                Some(&semaphore),
+                Some(&fence),
+                device_mask,
+            )
+        };
 
-        if let &Err(AcquireError::FullScreenExclusiveModeLost) = &acquire_result {
-            swapchain
-                .full_screen_exclusive_held
-                .store(false, Ordering::SeqCst);
+        match &acquire_result {
+            Err(AcquireError::FullScreenExclusiveModeLost) => {
+                swapchain
+                    .full_screen_exclusive_held
+                    .store(false, Ordering::SeqCst);
+            }
+            Err(AcquireError::OutOfDate) => unsafe {
+                swapchain.update_needs_recreation(true, false);
+            },
+            _ => (),
         }
 
         acquire_result?
     };
 
-    Ok((
+    let future = SwapchainAcquireFuture {
+        swapchain,
+        semaphore: Some(semaphore),
+        fence: Some(fence),
         image_index,
-        suboptimal,
-        SwapchainAcquireFuture {
-            swapchain,
-            semaphore: Some(semaphore),
-            fence: Some(fence),
-            image_index,
-            finished: AtomicBool::new(false),
-        },
-    ))
+        finished: AtomicBool::new(false),
+    };
+
+    if unsafe { future.swapchain.update_needs_recreation(false, suboptimal) } {
+        // Dropping the future waits for the acquire to complete. The image stays acquired until
+        // the swapchain is recreated.
+        return Err(AcquireError::OutOfDate);
+    }
+
+    Ok((image_index, suboptimal, future))
+}
+
+/// Tries to take ownership of an image in order to draw on it, without blocking.
+///
+/// This is equivalent to calling [`acquire_next_image`] with a timeout of zero. If no image is
+/// available right now, [`AcquireError::NotReady`] is returned, so that the caller can skip the
+/// frame instead of waiting for the presentation engine.
+///
+/// As with `acquire_next_image`, the second field in the tuple in the Ok result indicates whether
+/// the acquisition was suboptimal.
+pub fn try_acquire_next_image<W>(
+    swapchain: Arc<Swapchain<W>>,
+) -> Result<(u32, bool, SwapchainAcquireFuture<W>), AcquireError> {
+    acquire_next_image(swapchain, Some(Duration::ZERO))
 }
 
 /// Presents an image on the screen.
@@ -1593,7 +2006,57 @@ pub fn present<F>(
 where
     F: GpuFuture,
 {
-    assert!(swapchain_info.image_index < swapchain_info.swapchain.image_count());
+    present_multiple(before, queue, [swapchain_info])
+}
+
+/// Presents images on several swapchains at once, with a single present operation.
+///
+/// This is equivalent to calling [`present`] for each swapchain, but only submits one present
+/// command to the queue. The results of the individual swapchains can be retrieved with
+/// [`PresentFuture::swapchain_results`] once the future has been flushed.
+///
+/// # Panics
+///
+/// - Panics if `swapchain_infos` is empty.
+/// - Panics if the `image_index` of an element is not less than the number of images of its
+///   swapchain.
+/// - Panics if the same swapchain appears more than once in `swapchain_infos`.
+/// - Panics if the `device_mask` of an element is not zero, and does not contain exactly one
+///   physical device of the device.
+///
+/// If `before` is itself a present future that presents to one of the swapchains, the two are
+/// submitted as separate present operations instead of being merged into one.
+pub fn present_multiple<F>(
+    before: F,
+    queue: Arc<Queue>,
+    swapchain_infos: impl IntoIterator<Item = SwapchainPresentInfo>,
+) -> PresentFuture<F>
+where
+    F: GpuFuture,
+{
+    let swapchain_infos: SmallVec<[_; 1]> = swapchain_infos.into_iter().collect();
+
+    // VUID-VkPresentInfoKHR-swapchainCount-arraylength
+    assert!(!swapchain_infos.is_empty());
+
+    for (index, swapchain_info) in swapchain_infos.iter().enumerate() {
+        assert!(swapchain_info.image_index < swapchain_info.swapchain.image_count());
+
+        // VUID-VkDeviceGroupPresentInfoKHR-mode-01297
+        // VUID-VkDeviceGroupPresentInfoKHR-pDeviceMasks-01298
+        assert!(
+            swapchain_info.device_mask == 0
+                || swapchain_info.device_mask.count_ones() == 1
+                    && swapchain_info.device_mask
+                        & !swapchain_info.swapchain.device().all_devices_mask()
+                        == 0
+        );
+
+        // VUID-VkPresentInfoKHR-pSwapchain-09231
+        assert!(swapchain_infos[..index].iter().all(|other| {
+            other.swapchain.internal_object() != swapchain_info.swapchain.internal_object()
+        }));
+    }
 
     // TODO: restore this check with a dummy ImageAccess implementation
     /*let swapchain_image = me.images.lock().unwrap().get(index).unwrap().0.upgrade().unwrap();       // TODO: return error instead
@@ -1605,7 +2068,8 @@ where
     PresentFuture {
         previous: before,
         queue,
-        swapchain_info,
+        swapchain_infos,
+        swapchain_results: Mutex::new(None),
         flushed: AtomicBool::new(false),
         finished: AtomicBool::new(false),
     }
@@ -1826,6 +2290,9 @@ pub enum AcquireError {
     /// The timeout of the function has been reached before an image was available.
     Timeout,
 
+    /// The timeout was zero, and no image was available.
+    NotReady,
+
     /// The surface is no longer accessible and must be recreated.
     SurfaceLost,
 
@@ -1862,6 +2329,7 @@ impl Display for AcquireError {
                 AcquireError::OomError(_) => "not enough memory",
                 AcquireError::DeviceLost => "the connection to the device has been lost",
                 AcquireError::Timeout => "no image is available for acquiring yet",
+                AcquireError::NotReady => "no image is ready to be acquired right now",
                 AcquireError::SurfaceLost => "the surface of this swapchain is no longer valid",
                 AcquireError::OutOfDate => "the swapchain needs to be recreated",
                 AcquireError::FullScreenExclusiveModeLost => {
@@ -2009,7 +2477,9 @@ where
 {
     previous: P,
     queue: Arc<Queue>,
-    swapchain_info: SwapchainPresentInfo,
+    swapchain_infos: SmallVec<[SwapchainPresentInfo; 1]>,
+    // The results of the present operations of `swapchain_infos`, set when the future is flushed.
+    swapchain_results: Mutex<Option<SmallVec<[Result<bool, FlushError>; 1]>>>,
     // True if `flush()` has been called on the future, which means that the present command has
     // been submitted.
     flushed: AtomicBool,
@@ -2023,13 +2493,39 @@ where
     P: GpuFuture,
 {
     /// Returns the index of the image in the list of images returned when creating the swapchain.
+    ///
+    /// If the future presents to multiple swapchains, this is the image of the first one.
     pub fn image_id(&self) -> u32 {
-        self.swapchain_info.image_index
+        self.swapchain_infos[0].image_index
     }
 
     /// Returns the corresponding swapchain.
+    ///
+    /// If the future presents to multiple swapchains, this is the first one.
     pub fn swapchain(&self) -> &Arc<dyn SwapchainAbstract> {
-        &self.swapchain_info.swapchain
+        &self.swapchain_infos[0].swapchain
+    }
+
+    /// Returns the present operations that this future performs.
+    #[inline]
+    pub fn swapchain_infos(&self) -> &[SwapchainPresentInfo] {
+        &self.swapchain_infos
+    }
+
+    /// Returns the result of the present operation of each swapchain, in the same order as
+    /// [`swapchain_infos`](Self::swapchain_infos). `Ok(true)` means that the image was presented,
+    /// but the swapchain is suboptimal.
+    ///
+    /// Returns `None` if the future hasn't been flushed yet, or if flushing failed before the
+    /// present operation was submitted.
+    ///
+    /// When a present operation fails, [`flush`](GpuFuture::flush) only returns the first error.
+    /// This can be used to find out which swapchains need to be recreated.
+    pub fn swapchain_results(&self) -> Option<Vec<Result<bool, FlushError>>> {
+        self.swapchain_results
+            .lock()
+            .as_ref()
+            .map(|results| results.to_vec())
     }
 }
 
@@ -2046,21 +2542,35 @@ where
             return Ok(SubmitAnyBuilder::Empty);
         }
 
-        let mut swapchain_info = self.swapchain_info.clone();
-        debug_assert!((swapchain_info.image_index as u32) < swapchain_info.swapchain.image_count());
-        let device = swapchain_info.swapchain.device();
+        let swapchain_infos: Vec<_> = self
+            .swapchain_infos
+            .iter()
+            .map(|swapchain_info| {
+                let mut swapchain_info = swapchain_info.clone();
+                debug_assert!(swapchain_info.image_index < swapchain_info.swapchain.image_count());
+                let device = swapchain_info.swapchain.device();
 
-        if !device.enabled_features().present_id {
-            swapchain_info.present_id = None;
-        }
+                if !device.enabled_features().present_id {
+                    swapchain_info.present_id = None;
+                }
 
-        if device.enabled_extensions().khr_incremental_present {
-            for rectangle in &swapchain_info.present_regions {
-                assert!(rectangle.is_compatible_with(swapchain_info.swapchain.as_ref()));
-            }
-        } else {
-            swapchain_info.present_regions = Default::default();
-        }
+                if !(device.api_version() >= Version::V1_1
+                    || device.enabled_extensions().khr_device_group)
+                {
+                    swapchain_info.device_mask = 0;
+                }
+
+                if device.enabled_extensions().khr_incremental_present {
+                    for rectangle in &swapchain_info.present_regions {
+                        assert!(rectangle.is_compatible_with(swapchain_info.swapchain.as_ref()));
+                    }
+                } else {
+                    swapchain_info.present_regions = Default::default();
+                }
+
+                swapchain_info
+            })
+            .collect();
 
         let _queue = self.previous.queue();
 
@@ -2069,13 +2579,13 @@ where
 
         Ok(match self.previous.build_submission()? {
             SubmitAnyBuilder::Empty => SubmitAnyBuilder::QueuePresent(PresentInfo {
-                swapchain_infos: vec![self.swapchain_info.clone()],
+                swapchain_infos,
                 ..Default::default()
             }),
             SubmitAnyBuilder::SemaphoresWait(semaphores) => {
                 SubmitAnyBuilder::QueuePresent(PresentInfo {
                     wait_semaphores: semaphores.into_iter().collect(),
-                    swapchain_infos: vec![self.swapchain_info.clone()],
+                    swapchain_infos,
                     ..Default::default()
                 })
             }
@@ -2085,7 +2595,7 @@ where
                 self.previous.flush()?;
 
                 SubmitAnyBuilder::QueuePresent(PresentInfo {
-                    swapchain_infos: vec![self.swapchain_info.clone()],
+                    swapchain_infos,
                     ..Default::default()
                 })
             }
@@ -2095,16 +2605,31 @@ where
                 self.previous.flush()?;
 
                 SubmitAnyBuilder::QueuePresent(PresentInfo {
-                    swapchain_infos: vec![self.swapchain_info.clone()],
+                    swapchain_infos,
                     ..Default::default()
                 })
             }
             SubmitAnyBuilder::QueuePresent(mut present_info) => {
-                present_info
-                    .swapchain_infos
-                    .push(self.swapchain_info.clone());
+                // VUID-VkPresentInfoKHR-pSwapchain-09231
+                // A swapchain can only appear once in a present operation, so if the previous
+                // future already presents to one of our swapchains, it must be submitted first.
+                if present_info.swapchain_infos.iter().any(|previous_info| {
+                    swapchain_infos.iter().any(|swapchain_info| {
+                        previous_info.swapchain.internal_object()
+                            == swapchain_info.swapchain.internal_object()
+                    })
+                }) {
+                    self.previous.flush()?;
+
+                    SubmitAnyBuilder::QueuePresent(PresentInfo {
+                        swapchain_infos,
+                        ..Default::default()
+                    })
+                } else {
+                    present_info.swapchain_infos.extend(swapchain_infos);
 
-                SubmitAnyBuilder::QueuePresent(present_info)
+                    SubmitAnyBuilder::QueuePresent(present_info)
+                }
             }
         })
     }
@@ -2128,26 +2653,39 @@ where
                         }
                     }
 
-                    match self.previous.check_swapchain_image_acquired(
-                        self.swapchain_info
-                            .swapchain
-                            .raw_image(self.swapchain_info.image_index)
-                            .unwrap()
-                            .image,
-                        true,
-                    ) {
-                        Ok(_) => (),
-                        Err(AccessCheckError::Unknown) => {
-                            return Err(AccessError::SwapchainImageNotAcquired.into())
+                    for swapchain_info in &self.swapchain_infos {
+                        match self.previous.check_swapchain_image_acquired(
+                            swapchain_info
+                                .swapchain
+                                .raw_image(swapchain_info.image_index)
+                                .unwrap()
+                                .image,
+                            true,
+                        ) {
+                            Ok(_) => (),
+                            Err(AccessCheckError::Unknown) => {
+                                return Err(AccessError::SwapchainImageNotAcquired.into())
+                            }
+                            Err(AccessCheckError::Denied(e)) => return Err(e.into()),
                         }
-                        Err(AccessCheckError::Denied(e)) => return Err(e.into()),
                     }
 
-                    Ok(self
+                    // The present operations of this future come last, after any that were
+                    // merged from previous present futures.
+                    let mut results: SmallVec<[_; 1]> = self
                         .queue
                         .with(|mut q| q.present_unchecked(present_info))?
+                        .map(|r| r.map_err(FlushError::from))
+                        .collect();
+                    let result = results
+                        .iter()
+                        .cloned()
                         .map(|r| r.map(|_| ()))
-                        .fold(Ok(()), Result::and)?)
+                        .fold(Ok(()), Result::and);
+                    results.drain(..results.len() - self.swapchain_infos.len());
+                    *self.swapchain_results.lock() = Some(results);
+
+                    result
                 }
                 _ => unreachable!(),
             }
@@ -2192,13 +2730,16 @@ where
         expected_layout: ImageLayout,
         queue: &Queue,
     ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
-        let swapchain_image = self
-            .swapchain_info
-            .swapchain
-            .raw_image(self.swapchain_info.image_index)
-            .unwrap();
+        let is_presented = self.swapchain_infos.iter().any(|swapchain_info| {
+            let swapchain_image = swapchain_info
+                .swapchain
+                .raw_image(swapchain_info.image_index)
+                .unwrap();
 
-        if swapchain_image.image.internal_object() == image.internal_object() {
+            swapchain_image.image.internal_object() == image.internal_object()
+        });
+
+        if is_presented {
             // This future presents the swapchain image, which "unlocks" it. Therefore any attempt
             // to use this swapchain image afterwards shouldn't get granted automatic access.
             // Instead any attempt to access the image afterwards should get an authorization from
@@ -2216,15 +2757,20 @@ where
         image: &UnsafeImage,
         before: bool,
     ) -> Result<(), AccessCheckError> {
-        let swapchain_image = self
-            .swapchain_info
-            .swapchain
-            .raw_image(self.swapchain_info.image_index)
-            .unwrap();
+        let is_presented = || {
+            self.swapchain_infos.iter().any(|swapchain_info| {
+                let swapchain_image = swapchain_info
+                    .swapchain
+                    .raw_image(swapchain_info.image_index)
+                    .unwrap();
+
+                **swapchain_image.image == *image
+            })
+        };
 
         if before {
             self.previous.check_swapchain_image_acquired(image, false)
-        } else if **swapchain_image.image == *image {
+        } else if is_presented() {
             Err(AccessError::SwapchainImageNotAcquired.into())
         } else {
             self.previous.check_swapchain_image_acquired(image, false)
@@ -2280,6 +2826,23 @@ pub unsafe fn acquire_next_image_raw<W>(
     semaphore: Option<&Semaphore>,
     fence: Option<&Fence>,
 ) -> Result<AcquiredImage, AcquireError> {
+    acquire_next_image_raw_impl(swapchain, timeout, semaphore, fence, None)
+}
+
+unsafe fn acquire_next_image_raw_impl<W>(
+    swapchain: &Swapchain<W>,
+    timeout: Option<Duration>,
+    semaphore: Option<&Semaphore>,
+    fence: Option<&Fence>,
+    device_mask: Option<u32>,
+) -> Result<AcquiredImage, AcquireError> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!(
+        "acquire_next_image",
+        swapchain = %swapchain.device.traced_object_name(swapchain),
+    )
+    .entered();
+
     let fns = swapchain.device.fns();
 
     let timeout_ns = if let Some(timeout) = timeout {
@@ -2291,24 +2854,44 @@ pub unsafe fn acquire_next_image_raw<W>(
         u64::MAX
     };
 
+    let semaphore_vk = semaphore
+        .map(|s| s.internal_object())
+        .unwrap_or(ash::vk::Semaphore::null());
+    let fence_vk = fence
+        .map(|f| f.internal_object())
+        .unwrap_or(ash::vk::Fence::null());
+
     let mut out = MaybeUninit::uninit();
-    let result = (fns.khr_swapchain.acquire_next_image_khr)(
-        swapchain.device.internal_object(),
-        swapchain.handle,
-        timeout_ns,
-        semaphore
-            .map(|s| s.internal_object())
-            .unwrap_or(ash::vk::Semaphore::null()),
-        fence
-            .map(|f| f.internal_object())
-            .unwrap_or(ash::vk::Fence::null()),
-        out.as_mut_ptr(),
-    );
+    let result = if let Some(device_mask) = device_mask {
+        let acquire_info = ash::vk::AcquireNextImageInfoKHR {
+            swapchain: swapchain.handle,
+            timeout: timeout_ns,
+            semaphore: semaphore_vk,
+            fence: fence_vk,
+            device_mask,
+            ..Default::default()
+        };
+
+        (fns.khr_swapchain.acquire_next_image2_khr)(
+            swapchain.device.internal_object(),
+            &acquire_info,
+            out.as_mut_ptr(),
+        )
+    } else {
+        (fns.khr_swapchain.acquire_next_image_khr)(
+            swapchain.device.internal_object(),
+            swapchain.handle,
+            timeout_ns,
+            semaphore_vk,
+            fence_vk,
+            out.as_mut_ptr(),
+        )
+    };
 
     let suboptimal = match result {
         ash::vk::Result::SUCCESS => false,
         ash::vk::Result::SUBOPTIMAL_KHR => true,
-        ash::vk::Result::NOT_READY => return Err(AcquireError::Timeout),
+        ash::vk::Result::NOT_READY => return Err(AcquireError::NotReady),
         ash::vk::Result::TIMEOUT => return Err(AcquireError::Timeout),
         err => return Err(VulkanError::from(err).into()),
     };
@@ -2328,3 +2911,293 @@ pub unsafe fn acquire_next_image_raw<W>(
         suboptimal,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        acquire_next_image, acquire_next_image_device_mask, present_multiple, AcquireError,
+        SuboptimalPolicy, Swapchain, SwapchainAbstract, SwapchainCreateInfo,
+    };
+    use crate::{
+        device::{Device, DeviceCreateInfo, DeviceExtensions, DeviceOwned, Queue, QueueCreateInfo},
+        image::{ImageUsage, SwapchainImage},
+        instance::{Instance, InstanceCreateInfo, InstanceExtensions},
+        swapchain::{Surface, SwapchainPresentInfo},
+        sync::{now, GpuFuture},
+        Version, VulkanLibrary,
+    };
+    use std::sync::Arc;
+
+    /// Creates a swapchain on a headless surface, or returns `None` if headless surfaces or
+    /// swapchains are not supported.
+    fn headless_swapchain() -> Option<(Arc<Queue>, Arc<Swapchain<()>>)> {
+        let library = VulkanLibrary::new().ok()?;
+        let instance = Instance::new(
+            library,
+            InstanceCreateInfo {
+                enabled_extensions: InstanceExtensions {
+                    khr_surface: true,
+                    ext_headless_surface: true,
+                    ..InstanceExtensions::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .ok()?;
+        let surface = Surface::headless(instance.clone(), ()).ok()?;
+
+        let enabled_extensions = DeviceExtensions {
+            khr_swapchain: true,
+            ..DeviceExtensions::empty()
+        };
+        let (physical_device, queue_family_index) = instance
+            .enumerate_physical_devices()
+            .ok()?
+            .filter(|p| p.supported_extensions().contains(&enabled_extensions))
+            .find_map(|p| {
+                let queue_family_index = (0..p.queue_family_properties().len() as u32)
+                    .find(|&i| p.surface_support(i, &surface).unwrap_or(false))?;
+
+                Some((p, queue_family_index))
+            })?;
+
+        let (device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    ..Default::default()
+                }],
+                enabled_extensions,
+                ..Default::default()
+            },
+        )
+        .ok()?;
+
+        let surface_capabilities = device
+            .physical_device()
+            .surface_capabilities(&surface, Default::default())
+            .ok()?;
+        let (image_format, image_color_space) = device
+            .physical_device()
+            .surface_formats(&surface, Default::default())
+            .ok()?
+            .into_iter()
+            .next()?;
+
+        // One more than the minimum, so that two images can be acquired at the same time.
+        let min_image_count = match surface_capabilities.max_image_count {
+            Some(max_image_count) => {
+                (surface_capabilities.min_image_count + 1).min(max_image_count)
+            }
+            None => surface_capabilities.min_image_count + 1,
+        };
+
+        let (swapchain, _) = Swapchain::new(
+            device,
+            surface,
+            SwapchainCreateInfo {
+                min_image_count,
+                image_format: Some(image_format),
+                image_color_space,
+                image_extent: [16, 16],
+                image_usage: ImageUsage {
+                    color_attachment: true,
+                    ..ImageUsage::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .ok()?;
+
+        Some((queues.next().unwrap(), swapchain))
+    }
+
+    #[test]
+    fn present_multiple_duplicate_swapchain() {
+        let (queue, swapchain) = match headless_swapchain() {
+            Some(x) => x,
+            None => return,
+        };
+
+        assert_should_panic!({
+            present_multiple(
+                now(queue.device().clone()),
+                queue.clone(),
+                [
+                    SwapchainPresentInfo::swapchain_image_index(swapchain.clone(), 0),
+                    SwapchainPresentInfo::swapchain_image_index(swapchain.clone(), 0),
+                ],
+            );
+        });
+    }
+
+    #[test]
+    fn device_mask() {
+        let (queue, swapchain) = match headless_swapchain() {
+            Some(x) => x,
+            None => return,
+        };
+
+        if queue.device().api_version() < Version::V1_1 {
+            return; // test ignored
+        }
+
+        // The device was not created from a group, so it only has device index 0.
+        assert_should_panic!({
+            let _ = acquire_next_image_device_mask(swapchain.clone(), None, 0b10);
+        });
+        assert_should_panic!({
+            present_multiple(
+                now(queue.device().clone()),
+                queue.clone(),
+                [SwapchainPresentInfo {
+                    device_mask: 0b11,
+                    ..SwapchainPresentInfo::swapchain_image_index(swapchain.clone(), 0)
+                }],
+            );
+        });
+
+        // Alternate frame rendering with a single physical device: acquire and present the image
+        // on device index 0.
+        let (image_index, _, acquire_future) =
+            acquire_next_image_device_mask(swapchain.clone(), None, 0b1).unwrap();
+
+        acquire_future
+            .then_swapchain_present(
+                queue.clone(),
+                SwapchainPresentInfo {
+                    device_mask: 0b1,
+                    ..SwapchainPresentInfo::swapchain_image_index(swapchain.clone(), image_index)
+                },
+            )
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+
+    #[test]
+    fn present_same_swapchain_chained() {
+        let (queue, swapchain) = match headless_swapchain() {
+            Some(x) => x,
+            None => return,
+        };
+
+        if swapchain.image_count() < 2 {
+            return;
+        }
+
+        let (image_index0, _, acquire_future0) =
+            acquire_next_image(swapchain.clone(), None).unwrap();
+        let (image_index1, _, acquire_future1) =
+            acquire_next_image(swapchain.clone(), None).unwrap();
+
+        // Both presents go to the same swapchain, so they can't be merged into one present
+        // operation. The first one must be submitted on its own before the second.
+        acquire_future0
+            .join(acquire_future1)
+            .then_swapchain_present(
+                queue.clone(),
+                SwapchainPresentInfo::swapchain_image_index(swapchain.clone(), image_index0),
+            )
+            .then_swapchain_present(
+                queue.clone(),
+                SwapchainPresentInfo::swapchain_image_index(swapchain.clone(), image_index1),
+            )
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+
+    #[test]
+    fn suboptimal_policy() {
+        let (_, mut swapchain) = match headless_swapchain() {
+            Some(x) => x,
+            None => return,
+        };
+
+        // (policy, needs recreation after a suboptimal result, treated as out of date)
+        for (policy, needs_recreation, out_of_date) in [
+            (SuboptimalPolicy::RecreateImmediately, true, true),
+            (SuboptimalPolicy::RecreateNextFrame, true, false),
+            (SuboptimalPolicy::Ignore, false, false),
+        ] {
+            swapchain = swapchain
+                .recreate(SwapchainCreateInfo {
+                    suboptimal_policy: policy,
+                    ..swapchain.create_info()
+                })
+                .unwrap()
+                .0;
+            assert_eq!(swapchain.suboptimal_policy(), policy);
+            assert!(!swapchain.needs_recreation());
+
+            unsafe {
+                assert!(!swapchain.update_needs_recreation(false, false));
+                assert!(!swapchain.needs_recreation());
+
+                assert_eq!(swapchain.update_needs_recreation(false, true), out_of_date);
+                assert_eq!(swapchain.needs_recreation(), needs_recreation);
+
+                // Out of date results always need recreation, regardless of the policy.
+                assert!(swapchain.update_needs_recreation(true, false));
+                assert!(swapchain.needs_recreation());
+            }
+        }
+    }
+
+    #[test]
+    fn needs_recreation_surface_resized() {
+        let (_, swapchain) = match headless_swapchain() {
+            Some(x) => x,
+            None => return,
+        };
+
+        assert!(!swapchain.needs_recreation());
+        swapchain.surface_resized();
+        assert!(swapchain.needs_recreation());
+
+        // The new swapchain starts out without needing recreation, and keeps the policy.
+        let (new_swapchain, _) = swapchain.recreate(swapchain.create_info()).unwrap();
+        assert!(!new_swapchain.needs_recreation());
+        assert_eq!(
+            new_swapchain.suboptimal_policy(),
+            SuboptimalPolicy::RecreateNextFrame
+        );
+
+        // Acquiring from the retired swapchain is out of date.
+        assert!(matches!(
+            acquire_next_image(swapchain.clone(), None),
+            Err(AcquireError::OutOfDate)
+        ));
+    }
+
+    #[test]
+    fn recreate_with_views() {
+        let (_, swapchain) = match headless_swapchain() {
+            Some(x) => x,
+            None => return,
+        };
+
+        let (swapchain, views) = swapchain
+            .recreate_with_views(swapchain.create_info())
+            .unwrap();
+        assert_eq!(views.len(), swapchain.image_count() as usize);
+
+        // As long as the returned views are held, the images return them instead of creating new
+        // ones.
+        for view in &views {
+            assert!(Arc::ptr_eq(&view.image().view().unwrap(), view));
+            assert!(Arc::ptr_eq(&view.image().view().unwrap(), view));
+        }
+
+        let images: Vec<_> = views.iter().map(|view| view.image().clone()).collect();
+        assert!(SwapchainImage::views(&images)
+            .unwrap()
+            .iter()
+            .zip(&views)
+            .all(|(a, b)| Arc::ptr_eq(a, b)));
+    }
+}
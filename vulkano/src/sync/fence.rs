@@ -297,6 +297,9 @@ impl Fence {
     ///
     /// If you pass a duration of 0, then the function will return without blocking.
     pub fn wait(&self, timeout: Option<Duration>) -> Result<(), FenceError> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("fence_wait", fence = ?self.handle).entered();
+
         let queue_to_signal = {
             let mut state = self.state.lock();
 
@@ -341,6 +344,50 @@ impl Fence {
         Ok(())
     }
 
+    // Returns whether the fence is signaled, without locking or updating the state of the fence.
+    // Used by the waiter thread of `FenceSignalFuture`, so that the fence can still be polled while
+    // it's being waited for.
+    #[cfg(feature = "futures")]
+    pub(crate) fn is_signaled_without_state(&self) -> Result<bool, VulkanError> {
+        let result = unsafe {
+            let fns = self.device.fns();
+            (fns.v1_0.get_fence_status)(self.device.internal_object(), self.handle)
+        };
+
+        match result {
+            ash::vk::Result::SUCCESS => Ok(true),
+            ash::vk::Result::NOT_READY => Ok(false),
+            err => Err(VulkanError::from(err)),
+        }
+    }
+
+    // Blocks until at least one of `fences` is signaled or `timeout` has elapsed, without locking
+    // or updating the state of the fences. All fences must belong to the same device.
+    #[cfg(feature = "futures")]
+    pub(crate) fn wait_any_without_state(
+        fences: &[Arc<Fence>],
+        timeout: Duration,
+    ) -> Result<(), VulkanError> {
+        let device = &fences[0].device;
+        let fences_vk: SmallVec<[_; 8]> = fences.iter().map(|fence| fence.handle).collect();
+
+        let result = unsafe {
+            let fns = device.fns();
+            (fns.v1_0.wait_for_fences)(
+                device.internal_object(),
+                fences_vk.len() as u32,
+                fences_vk.as_ptr(),
+                ash::vk::FALSE,
+                timeout.as_nanos() as u64,
+            )
+        };
+
+        match result {
+            ash::vk::Result::SUCCESS | ash::vk::Result::TIMEOUT => Ok(()),
+            err => Err(VulkanError::from(err)),
+        }
+    }
+
     /// Waits for multiple fences at once.
     ///
     /// # Panics
@@ -375,9 +422,45 @@ impl Fence {
     }
 
     #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
     pub unsafe fn multi_wait_unchecked<'a>(
         fences: impl IntoIterator<Item = &'a Fence>,
         timeout: Option<Duration>,
+    ) -> Result<(), FenceError> {
+        Self::multi_wait_unchecked_impl(fences, true, timeout)
+    }
+
+    /// Waits until at least one of multiple fences is signaled.
+    ///
+    /// Returns immediately if `fences` is empty, or if one of the fences is already known to be
+    /// signaled.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if not all fences belong to the same device.
+    pub fn multi_wait_any<'a>(
+        fences: impl IntoIterator<Item = &'a Fence>,
+        timeout: Option<Duration>,
+    ) -> Result<(), FenceError> {
+        let fences: SmallVec<[_; 8]> = fences.into_iter().collect();
+        Self::validate_multi_wait(&fences, timeout)?;
+
+        unsafe { Self::multi_wait_any_unchecked(fences, timeout) }
+    }
+
+    #[cfg_attr(not(feature = "document_unchecked"), doc(hidden))]
+    #[inline]
+    pub unsafe fn multi_wait_any_unchecked<'a>(
+        fences: impl IntoIterator<Item = &'a Fence>,
+        timeout: Option<Duration>,
+    ) -> Result<(), FenceError> {
+        Self::multi_wait_unchecked_impl(fences, false, timeout)
+    }
+
+    unsafe fn multi_wait_unchecked_impl<'a>(
+        fences: impl IntoIterator<Item = &'a Fence>,
+        wait_all: bool,
+        timeout: Option<Duration>,
     ) -> Result<(), FenceError> {
         let queues_to_signal: SmallVec<[_; 8]> = {
             let iter = fences.into_iter();
@@ -393,6 +476,9 @@ impl Fence {
                     fences_vk.push(fence.handle);
                     fences.push(fence);
                     states.push(state);
+                } else if !wait_all {
+                    // One signaled fence is enough.
+                    return Ok(());
                 }
             }
 
@@ -410,21 +496,25 @@ impl Fence {
                     .saturating_add(timeout.subsec_nanos() as u64)
             });
 
-            let result = {
-                let fns = device.fns();
-                (fns.v1_0.wait_for_fences)(
-                    device.internal_object(),
-                    fences_vk.len() as u32,
-                    fences_vk.as_ptr(),
-                    ash::vk::TRUE, // TODO: let the user choose false here?
-                    timeout_ns,
-                )
-            };
+            let fns = device.fns();
+            let result = (fns.v1_0.wait_for_fences)(
+                device.internal_object(),
+                fences_vk.len() as u32,
+                fences_vk.as_ptr(),
+                wait_all as ash::vk::Bool32,
+                timeout_ns,
+            );
 
             match result {
                 ash::vk::Result::SUCCESS => fences
                     .into_iter()
                     .zip(&mut states)
+                    .filter(|(fence, _)| {
+                        // When waiting for any fence, we don't know which ones were signaled.
+                        wait_all
+                            || (fns.v1_0.get_fence_status)(device.internal_object(), fence.handle)
+                                == ash::vk::Result::SUCCESS
+                    })
                     .filter_map(|(fence, state)| state.set_signaled().map(|state| (state, fence)))
                     .collect(),
                 ash::vk::Result::TIMEOUT => return Err(FenceError::Timeout),
@@ -1654,7 +1744,7 @@ impl From<RequirementNotMet> for FenceError {
 #[cfg(test)]
 mod tests {
     use crate::{
-        sync::{fence::FenceCreateInfo, Fence},
+        sync::{fence::FenceCreateInfo, Fence, FenceError},
         VulkanObject,
     };
     use std::time::Duration;
@@ -1743,6 +1833,46 @@ mod tests {
         });
     }
 
+    #[test]
+    fn multiwait_any() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let signaled = Fence::new(
+            device.clone(),
+            FenceCreateInfo {
+                signaled: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let unsignaled = Fence::new(device, Default::default()).unwrap();
+
+        Fence::multi_wait_any([], Some(Duration::ZERO)).unwrap();
+        Fence::multi_wait_any([&unsignaled, &signaled], Some(Duration::ZERO)).unwrap();
+
+        match Fence::multi_wait_any([&unsignaled], Some(Duration::ZERO)) {
+            Err(FenceError::Timeout) => (),
+            _ => panic!(),
+        }
+        match Fence::multi_wait([&unsignaled, &signaled], Some(Duration::ZERO)) {
+            Err(FenceError::Timeout) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn multiwait_any_different_devices() {
+        let (device1, _) = gfx_dev_and_queue!();
+        let (device2, _) = gfx_dev_and_queue!();
+
+        assert_should_panic!({
+            let fence1 = Fence::new(device1.clone(), Default::default()).unwrap();
+            let fence2 = Fence::new(device2.clone(), Default::default()).unwrap();
+
+            let _ = Fence::multi_wait_any([&fence1, &fence2], Some(Duration::new(0, 10)));
+        });
+    }
+
     #[test]
     fn multireset_different_devices() {
         let (device1, _) = gfx_dev_and_queue!();
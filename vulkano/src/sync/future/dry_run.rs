@@ -0,0 +1,432 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use super::{AccessCheckError, FlushError, GpuFuture, SubmitAnyBuilder};
+use crate::{
+    buffer::sys::UnsafeBuffer,
+    command_buffer::{
+        CommandBufferExecError, PrimaryCommandBuffer, SemaphoreSubmitInfo, SubmitInfo,
+    },
+    device::{Device, DeviceOwned, Queue},
+    image::{sys::UnsafeImage, ImageLayout},
+    sync::{AccessFlags, PipelineStages},
+    DeviceSize, VulkanObject,
+};
+use parking_lot::Mutex;
+use std::{
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// A queue that validates and records command buffer executions, but never submits them to the
+/// device.
+///
+/// Executing a command buffer with [`execute`](DryRunQueue::execute) performs the same checks and
+/// resource locking as [`GpuFuture::then_execute`], and returns a [`DryRunFuture`]. When the
+/// future is flushed, the submission is recorded in the queue instead of being submitted. The
+/// recorded submissions stay pending until [`complete_all`](DryRunQueue::complete_all) is called,
+/// which simulates the device finishing them, and releases their resources. This makes it
+/// possible to test render loop logic, such as the chaining of futures, calls to
+/// `cleanup_finished` and resource lock conflicts, deterministically.
+///
+/// A device and a real queue are still needed to create the resources and command buffers, but
+/// no work is submitted to the queue by the dry run futures. Futures that are chained after a
+/// `DryRunFuture` and that do their own submissions, such as a fence or semaphore signal or a
+/// swapchain present, still submit them to the real queue.
+#[derive(Debug)]
+pub struct DryRunQueue {
+    queue: Arc<Queue>,
+    state: Mutex<DryRunQueueState>,
+}
+
+#[derive(Debug, Default)]
+struct DryRunQueueState {
+    submissions: Vec<DryRunSubmission>,
+    // Submissions that have been recorded but not completed yet.
+    pending: Vec<SubmitInfo>,
+    completed: usize,
+}
+
+impl DryRunQueue {
+    /// Creates a new `DryRunQueue` that stands in for `queue`.
+    #[inline]
+    pub fn new(queue: Arc<Queue>) -> Arc<Self> {
+        Arc::new(DryRunQueue {
+            queue,
+            state: Mutex::new(Default::default()),
+        })
+    }
+
+    /// Returns the queue that this `DryRunQueue` stands in for.
+    #[inline]
+    pub fn queue(&self) -> &Arc<Queue> {
+        &self.queue
+    }
+
+    /// Validates and locks the resources of `command_buffer` for an execution after `before`, the
+    /// same way as [`GpuFuture::then_execute`] does.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the device of the command buffer is not the same as the device of the future.
+    pub fn execute<F, Cb>(
+        self: &Arc<Self>,
+        before: F,
+        command_buffer: Cb,
+    ) -> Result<DryRunFuture<F>, CommandBufferExecError>
+    where
+        F: GpuFuture,
+        Cb: PrimaryCommandBuffer + 'static,
+    {
+        assert_eq!(
+            command_buffer.device().internal_object(),
+            before.device().internal_object()
+        );
+
+        if !before.queue_change_allowed() {
+            assert!(before.queue().unwrap() == self.queue);
+        }
+
+        command_buffer.lock_submit(&before, &self.queue)?;
+
+        Ok(DryRunFuture {
+            previous: before,
+            command_buffer: Arc::new(command_buffer),
+            dry_run_queue: self.clone(),
+            submission: Mutex::new(None),
+            finished: AtomicBool::new(false),
+        })
+    }
+
+    /// Returns all the submissions that have been recorded so far, in the order they were
+    /// recorded.
+    #[inline]
+    pub fn submissions(&self) -> Vec<DryRunSubmission> {
+        self.state.lock().submissions.clone()
+    }
+
+    /// Returns the number of recorded submissions that have not been completed yet.
+    #[inline]
+    pub fn pending_count(&self) -> usize {
+        self.state.lock().pending.len()
+    }
+
+    /// Simulates the device finishing all the recorded submissions, and unlocks their command
+    /// buffers.
+    ///
+    /// The futures of these submissions release their own resources the next time
+    /// `cleanup_finished` is called on them.
+    pub fn complete_all(&self) {
+        let mut state = self.state.lock();
+        state.completed = state.submissions.len();
+
+        for submit_info in state.pending.drain(..) {
+            for command_buffer in submit_info.command_buffers {
+                unsafe {
+                    command_buffer.unlock();
+                }
+            }
+        }
+    }
+
+    fn record(&self, submit_info: SubmitInfo, signals_fence: bool) -> usize {
+        let mut state = self.state.lock();
+        let index = state.submissions.len();
+
+        state.submissions.push(DryRunSubmission {
+            command_buffer_count: submit_info.command_buffers.len(),
+            wait_semaphore_count: submit_info.wait_semaphores.len(),
+            signal_semaphore_count: submit_info.signal_semaphores.len(),
+            signals_fence,
+        });
+        state.pending.push(submit_info);
+
+        index
+    }
+
+    fn is_completed(&self, index: usize) -> bool {
+        index < self.state.lock().completed
+    }
+}
+
+/// A submission that was recorded by a [`DryRunQueue`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DryRunSubmission {
+    /// The number of command buffers in the submission.
+    pub command_buffer_count: usize,
+
+    /// The number of semaphores that the submission waits on.
+    pub wait_semaphore_count: usize,
+
+    /// The number of semaphores that the submission signals.
+    pub signal_semaphore_count: usize,
+
+    /// Whether the submission signals a fence.
+    pub signals_fence: bool,
+}
+
+/// Represents the execution of a command buffer on a [`DryRunQueue`].
+#[derive(Debug)]
+#[must_use = "Dropping this object will immediately complete all the submissions of its dry run queue"]
+pub struct DryRunFuture<F>
+where
+    F: GpuFuture,
+{
+    previous: F,
+    command_buffer: Arc<dyn PrimaryCommandBuffer>,
+    dry_run_queue: Arc<DryRunQueue>,
+    // The index of the recorded submission, if the future has been flushed.
+    submission: Mutex<Option<usize>>,
+    finished: AtomicBool,
+}
+
+impl<F> DryRunFuture<F>
+where
+    F: GpuFuture,
+{
+    /// Returns the `DryRunQueue` that the command buffer was executed on.
+    #[inline]
+    pub fn dry_run_queue(&self) -> &Arc<DryRunQueue> {
+        &self.dry_run_queue
+    }
+}
+
+unsafe impl<F> GpuFuture for DryRunFuture<F>
+where
+    F: GpuFuture,
+{
+    fn cleanup_finished(&mut self) {
+        let completed = (*self.submission.get_mut())
+            .map_or(false, |index| self.dry_run_queue.is_completed(index));
+
+        if completed && !*self.finished.get_mut() {
+            unsafe {
+                self.signal_finished();
+            }
+        }
+
+        self.previous.cleanup_finished();
+    }
+
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        // The command buffer must never reach a real queue, so it is recorded right away and
+        // subsequent futures are given nothing to submit.
+        self.flush()?;
+
+        Ok(SubmitAnyBuilder::Empty)
+    }
+
+    fn flush(&self) -> Result<(), FlushError> {
+        unsafe {
+            let mut submission = self.submission.lock();
+
+            if submission.is_some() {
+                return Ok(());
+            }
+
+            let (mut submit_info, signals_fence) = match self.previous.build_submission()? {
+                SubmitAnyBuilder::Empty => (Default::default(), false),
+                SubmitAnyBuilder::SemaphoresWait(semaphores) => (
+                    SubmitInfo {
+                        wait_semaphores: semaphores
+                            .into_iter()
+                            .map(|semaphore| SemaphoreSubmitInfo {
+                                stages: PipelineStages {
+                                    all_commands: true,
+                                    ..PipelineStages::empty()
+                                },
+                                ..SemaphoreSubmitInfo::semaphore(semaphore)
+                            })
+                            .collect(),
+                        ..Default::default()
+                    },
+                    false,
+                ),
+                SubmitAnyBuilder::CommandBuffer(mut submit_infos, fence) => {
+                    // The command buffer is added to the last batch, the others are recorded as
+                    // they are.
+                    let submit_info = submit_infos.pop().unwrap();
+
+                    for submit_info in submit_infos {
+                        self.dry_run_queue.record(submit_info, false);
+                    }
+
+                    (submit_info, fence.is_some())
+                }
+                SubmitAnyBuilder::QueuePresent(_) | SubmitAnyBuilder::BindSparse(_, _) => {
+                    // These can't be part of a command buffer submission, so the previous future
+                    // submits them to the real queue itself, like futures that are chained after
+                    // this one do.
+                    self.previous.flush()?;
+                    (Default::default(), false)
+                }
+            };
+
+            submit_info
+                .command_buffers
+                .push(self.command_buffer.clone());
+            *submission = Some(self.dry_run_queue.record(submit_info, signals_fence));
+
+            Ok(())
+        }
+    }
+
+    unsafe fn signal_finished(&self) {
+        self.finished.store(true, Ordering::SeqCst);
+        self.previous.signal_finished();
+    }
+
+    fn queue_change_allowed(&self) -> bool {
+        false
+    }
+
+    fn queue(&self) -> Option<Arc<Queue>> {
+        Some(self.dry_run_queue.queue.clone())
+    }
+
+    fn check_buffer_access(
+        &self,
+        buffer: &UnsafeBuffer,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        match self
+            .command_buffer
+            .check_buffer_access(buffer, range.clone(), exclusive, queue)
+        {
+            Ok(v) => Ok(v),
+            Err(AccessCheckError::Denied(err)) => Err(AccessCheckError::Denied(err)),
+            Err(AccessCheckError::Unknown) => self
+                .previous
+                .check_buffer_access(buffer, range, exclusive, queue),
+        }
+    }
+
+    fn check_image_access(
+        &self,
+        image: &UnsafeImage,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        expected_layout: ImageLayout,
+        queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        match self.command_buffer.check_image_access(
+            image,
+            range.clone(),
+            exclusive,
+            expected_layout,
+            queue,
+        ) {
+            Ok(v) => Ok(v),
+            Err(AccessCheckError::Denied(err)) => Err(AccessCheckError::Denied(err)),
+            Err(AccessCheckError::Unknown) => {
+                self.previous
+                    .check_image_access(image, range, exclusive, expected_layout, queue)
+            }
+        }
+    }
+
+    #[inline]
+    fn check_swapchain_image_acquired(
+        &self,
+        image: &UnsafeImage,
+        _before: bool,
+    ) -> Result<(), AccessCheckError> {
+        self.previous.check_swapchain_image_acquired(image, false)
+    }
+}
+
+unsafe impl<F> DeviceOwned for DryRunFuture<F>
+where
+    F: GpuFuture,
+{
+    fn device(&self) -> &Arc<Device> {
+        self.command_buffer.device()
+    }
+}
+
+impl<F> Drop for DryRunFuture<F>
+where
+    F: GpuFuture,
+{
+    fn drop(&mut self) {
+        unsafe {
+            if !*self.finished.get_mut() {
+                // This is the equivalent of waiting for the queue to become idle.
+                self.flush().unwrap();
+                self.dry_run_queue.complete_all();
+                self.previous.signal_finished();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DryRunQueue;
+    use crate::{
+        buffer::{BufferUsage, CpuAccessibleBuffer},
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+            CommandBufferExecError, CommandBufferUsage, FillBufferInfo,
+        },
+        sync::{now, GpuFuture},
+    };
+
+    #[test]
+    fn records_without_submitting() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let command_buffer_allocator = StandardCommandBufferAllocator::new(device.clone());
+        let buffer = CpuAccessibleBuffer::from_data(
+            device.clone(),
+            BufferUsage {
+                transfer_dst: true,
+                ..BufferUsage::empty()
+            },
+            false,
+            0u32,
+        )
+        .unwrap();
+
+        let build = || {
+            let mut builder = AutoCommandBufferBuilder::primary(
+                &command_buffer_allocator,
+                queue.queue_family_index(),
+                CommandBufferUsage::OneTimeSubmit,
+            )
+            .unwrap();
+            builder
+                .fill_buffer(FillBufferInfo::dst_buffer(buffer.clone()))
+                .unwrap();
+            builder.build().unwrap()
+        };
+
+        let dry_run_queue = DryRunQueue::new(queue);
+        let mut future = dry_run_queue.execute(now(device.clone()), build()).unwrap();
+        future.flush().unwrap();
+        assert_eq!(dry_run_queue.submissions().len(), 1);
+        assert_eq!(dry_run_queue.pending_count(), 1);
+
+        // The buffer is still locked by the pending submission.
+        assert!(matches!(
+            dry_run_queue.execute(now(device.clone()), build()),
+            Err(CommandBufferExecError::AccessError { .. })
+        ));
+        assert!(buffer.read().is_err());
+
+        dry_run_queue.complete_all();
+        future.cleanup_finished();
+        assert_eq!(dry_run_queue.pending_count(), 0);
+        assert!(buffer.read().is_ok());
+    }
+}
@@ -8,6 +8,8 @@
 // according to those terms.
 
 use super::{AccessCheckError, FlushError, GpuFuture};
+#[cfg(feature = "futures")]
+use crate::VulkanError;
 use crate::{
     buffer::sys::UnsafeBuffer,
     command_buffer::{SemaphoreSubmitInfo, SubmitInfo},
@@ -17,6 +19,15 @@ use crate::{
     DeviceSize, OomError,
 };
 use parking_lot::{Mutex, MutexGuard};
+use smallvec::SmallVec;
+#[cfg(feature = "futures")]
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Weak,
+    task::{Context, Poll, Waker},
+    thread,
+};
 use std::{mem::replace, ops::Range, sync::Arc, time::Duration};
 
 /// Builds a new fence signal future.
@@ -33,7 +44,104 @@ where
         device,
         state: Mutex::new(FenceSignalFutureState::Pending(future, fence)),
         behavior,
+        #[cfg(feature = "futures")]
+        waiter: Mutex::new(None),
+    }
+}
+
+/// Blocks the current thread until at least one of `futures` is signaled by the GPU, and returns
+/// the index of a signaled future. Performs a flush of each future if necessary.
+///
+/// If `timeout` is `None`, then the wait is infinite. Otherwise the thread will unblock after
+/// the specified timeout has elapsed and an error will be returned.
+///
+/// The futures that are signaled when the wait completes have the resources locked by their
+/// previous submissions cleaned, as with [`FenceSignalFuture::wait`].
+///
+/// # Panics
+///
+/// - Panics if `futures` is empty.
+/// - Panics if not all futures belong to the same device.
+pub fn wait_any<F>(
+    futures: &[&FenceSignalFuture<F>],
+    timeout: Option<Duration>,
+) -> Result<usize, FlushError>
+where
+    F: GpuFuture,
+{
+    assert!(!futures.is_empty());
+
+    let fences = flush_and_collect_fences(futures)?;
+
+    // A future that was already cleaned is signaled.
+    if let Some(index) = fences.iter().position(Option::is_none) {
+        return Ok(index);
+    }
+
+    Fence::multi_wait_any(fences.iter().flatten().map(|fence| &**fence), timeout)?;
+
+    for future in futures {
+        future.cleanup_finished_impl();
+    }
+
+    match futures.iter().position(|future| future.is_cleaned()) {
+        Some(index) => Ok(index),
+        None => {
+            // One of the fences was signaled, but none of the futures could be cleaned up. Either
+            // a future was poisoned by another thread in the meantime, or the device was lost.
+            for future in futures {
+                future.fence()?;
+            }
+
+            Err(FlushError::DeviceLost)
+        }
+    }
+}
+
+/// Blocks the current thread until all of `futures` are signaled by the GPU. Performs a flush of
+/// each future if necessary.
+///
+/// If `timeout` is `None`, then the wait is infinite. Otherwise the thread will unblock after
+/// the specified timeout has elapsed and an error will be returned.
+///
+/// If the wait is successful, this function also cleans any resource locked by previous
+/// submissions of the futures.
+///
+/// # Panics
+///
+/// - Panics if not all futures belong to the same device.
+pub fn wait_all<F>(
+    futures: &[&FenceSignalFuture<F>],
+    timeout: Option<Duration>,
+) -> Result<(), FlushError>
+where
+    F: GpuFuture,
+{
+    let fences = flush_and_collect_fences(futures)?;
+    Fence::multi_wait(fences.iter().flatten().map(|fence| &**fence), timeout)?;
+
+    for future in futures {
+        future.cleanup_finished_impl();
     }
+
+    Ok(())
+}
+
+// Flushes every future, and returns the fence of each one, or `None` if the future was already
+// cleaned.
+fn flush_and_collect_fences<F>(
+    futures: &[&FenceSignalFuture<F>],
+) -> Result<SmallVec<[Option<Arc<Fence>>; 8]>, FlushError>
+where
+    F: GpuFuture,
+{
+    futures
+        .iter()
+        .map(|future| {
+            future.flush_impl(&mut future.state.lock())?;
+            future.fence()
+        })
+        .collect()
 }
 
 /// Describes the behavior of the future if you submit something after it.
@@ -79,6 +187,19 @@ pub enum FenceSignalFutureBehavior {
 /// // Later you can wait until you reach the point of `fence_signal`:
 /// fence_signal.wait(None).unwrap();
 /// ```
+///
+/// # Async
+///
+/// If the `futures` cargo feature is enabled, `FenceSignalFuture` and `&FenceSignalFuture`
+/// implement [`std::future::Future`], so that the signal can be awaited from an async runtime
+/// without blocking it. The future is flushed when it is first polled, and the fence is waited
+/// for on a background thread that wakes the task once the fence is signaled. A single thread
+/// waits for the fences of all polled futures, and it stops waiting for the fence of a future that
+/// is dropped.
+///
+/// Flushing only submits the work to the queue and doesn't wait for the GPU. If another thread is
+/// holding the future, for example while blocked in [`wait`](Self::wait), polling yields instead
+/// of waiting for it.
 #[must_use = "Dropping this object will immediately block the thread until the GPU has finished \
               processing the submission"]
 pub struct FenceSignalFuture<F>
@@ -90,6 +211,117 @@ where
     // The device of the future.
     device: Arc<Device>,
     behavior: FenceSignalFutureBehavior,
+    // The registration with the waiter thread, made when the future is first polled.
+    #[cfg(feature = "futures")]
+    waiter: Mutex<Option<Arc<Mutex<FenceWaiter>>>>,
+}
+
+// State shared between a polled `FenceSignalFuture` and the waiter thread.
+#[cfg(feature = "futures")]
+#[derive(Default)]
+struct FenceWaiter {
+    // The result of the wait, once the fence is signaled or waiting for it has failed.
+    result: Option<Result<(), VulkanError>>,
+    // The waker of the task that last polled the future.
+    waker: Option<Waker>,
+}
+
+// The fences of the polled `FenceSignalFuture`s that haven't finished yet. They are all waited for
+// by a single thread, which is started when a fence is registered and exits when there are none
+// left.
+#[cfg(feature = "futures")]
+static FENCE_WAITERS: Mutex<FenceWaiters> = parking_lot::const_mutex(FenceWaiters {
+    registered: Vec::new(),
+    thread_running: false,
+});
+
+#[cfg(feature = "futures")]
+struct FenceWaiters {
+    // The waiter is weak, so that the fence stops being waited for when the future is dropped.
+    registered: Vec<(Arc<Fence>, Weak<Mutex<FenceWaiter>>)>,
+    thread_running: bool,
+}
+
+// How long the waiter thread blocks on the fences before it looks at the registered fences again.
+// This bounds how long it takes to notice fences that are registered or dropped during a wait, or
+// that belong to another device than the one being waited on.
+#[cfg(feature = "futures")]
+const FENCE_WAITER_TIMEOUT: Duration = Duration::from_millis(5);
+
+#[cfg(feature = "futures")]
+fn register_fence_waiter(fence: Arc<Fence>, waiter: Weak<Mutex<FenceWaiter>>) {
+    let mut waiters = FENCE_WAITERS.lock();
+    waiters.registered.push((fence, waiter));
+
+    if !waiters.thread_running {
+        waiters.thread_running = true;
+        thread::spawn(run_fence_waiter_thread);
+    }
+}
+
+#[cfg(feature = "futures")]
+fn run_fence_waiter_thread() {
+    loop {
+        // Only the fences of one device can be waited for at once. The fences of other devices
+        // are checked after each wait.
+        let fences: SmallVec<[_; 8]> = {
+            let mut waiters = FENCE_WAITERS.lock();
+            waiters
+                .registered
+                .retain(|(_, waiter)| waiter.strong_count() > 0);
+
+            let device = match waiters.registered.first() {
+                Some((fence, _)) => fence.device().clone(),
+                None => {
+                    waiters.thread_running = false;
+                    return;
+                }
+            };
+
+            waiters
+                .registered
+                .iter()
+                .filter(|(fence, _)| fence.device() == &device)
+                .map(|(fence, _)| fence.clone())
+                .collect()
+        };
+
+        // Errors are reported below, when the status of each fence is checked.
+        let _ = Fence::wait_any_without_state(&fences, FENCE_WAITER_TIMEOUT);
+
+        let finished: SmallVec<[_; 8]> = {
+            let mut waiters = FENCE_WAITERS.lock();
+            let mut finished = SmallVec::new();
+
+            waiters.registered.retain(|(fence, waiter)| {
+                let result = match fence.is_signaled_without_state() {
+                    Ok(false) => return true,
+                    Ok(true) => Ok(()),
+                    Err(err) => Err(err),
+                };
+
+                if let Some(waiter) = waiter.upgrade() {
+                    finished.push((waiter, result));
+                }
+
+                false
+            });
+
+            finished
+        };
+
+        for (waiter, result) in finished {
+            let waker = {
+                let mut waiter = waiter.lock();
+                waiter.result = Some(result);
+                waiter.waker.take()
+            };
+
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
 }
 
 // This future can be in three different states: pending (ie. newly-created), submitted (ie. the
@@ -121,6 +353,9 @@ where
     F: GpuFuture,
 {
     /// Returns true if the fence is signaled by the GPU.
+    ///
+    /// This doesn't block the current thread, and can be used to poll for the completion of the
+    /// submission.
     pub fn is_signaled(&self) -> Result<bool, OomError> {
         let state = self.state.lock();
 
@@ -155,7 +390,104 @@ where
                 Ok(())
             }
             FenceSignalFutureState::Cleaned => Ok(()),
-            _ => unreachable!(),
+            FenceSignalFutureState::Poisoned => {
+                *state = FenceSignalFutureState::Poisoned;
+                Err(FlushError::Poisoned)
+            }
+            FenceSignalFutureState::Pending(_, _)
+            | FenceSignalFutureState::PartiallyFlushed(_, _) => unreachable!(),
+        }
+    }
+
+    // Implementation of `Future::poll`, shared by `FenceSignalFuture` and `&FenceSignalFuture`.
+    #[cfg(feature = "futures")]
+    fn poll_impl(&self, cx: &mut Context<'_>) -> Poll<Result<(), FlushError>> {
+        let fence = {
+            // Another thread may hold the lock for as long as it waits on the fence in `wait`.
+            // Yield to the executor instead of blocking it.
+            let mut state = match self.state.try_lock() {
+                Some(state) => state,
+                None => {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+            };
+
+            if let Err(err) = self.flush_impl(&mut state) {
+                return Poll::Ready(Err(err));
+            }
+
+            match &*state {
+                FenceSignalFutureState::Flushed(_, fence) => fence.clone(),
+                FenceSignalFutureState::Cleaned => return Poll::Ready(Ok(())),
+                FenceSignalFutureState::Pending(_, _)
+                | FenceSignalFutureState::PartiallyFlushed(_, _)
+                | FenceSignalFutureState::Poisoned => {
+                    return Poll::Ready(Err(FlushError::Poisoned))
+                }
+            }
+        };
+
+        match fence.is_signaled() {
+            Ok(true) => {
+                self.cleanup_finished_impl();
+                return Poll::Ready(Ok(()));
+            }
+            Ok(false) => (),
+            Err(err) => return Poll::Ready(Err(FlushError::OomError(err))),
+        }
+
+        let waiter = self
+            .waiter
+            .lock()
+            .get_or_insert_with(|| {
+                let waiter = Arc::new(Mutex::new(FenceWaiter::default()));
+                register_fence_waiter(fence.clone(), Arc::downgrade(&waiter));
+
+                waiter
+            })
+            .clone();
+
+        let result = {
+            let mut waiter = waiter.lock();
+
+            match waiter.result {
+                Some(result) => result,
+                None => {
+                    waiter.waker = Some(cx.waker().clone());
+                    return Poll::Pending;
+                }
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                // Update the state of the fence, and clean up the previous submissions.
+                if let Err(err) = fence.is_signaled() {
+                    return Poll::Ready(Err(FlushError::OomError(err)));
+                }
+
+                self.cleanup_finished_impl();
+                Poll::Ready(Ok(()))
+            }
+            Err(err) => Poll::Ready(Err(err.into())),
+        }
+    }
+
+    // Returns true if the submission has finished and been cleaned up.
+    fn is_cleaned(&self) -> bool {
+        matches!(*self.state.lock(), FenceSignalFutureState::Cleaned)
+    }
+
+    // Returns the fence that will be signaled, or `None` if the submission has already finished
+    // and been cleaned up. Returns an error if a previous flush panicked.
+    pub(crate) fn fence(&self) -> Result<Option<Arc<Fence>>, FlushError> {
+        match &*self.state.lock() {
+            FenceSignalFutureState::Pending(_, fence)
+            | FenceSignalFutureState::PartiallyFlushed(_, fence)
+            | FenceSignalFutureState::Flushed(_, fence) => Ok(Some(fence.clone())),
+            FenceSignalFutureState::Cleaned => Ok(None),
+            FenceSignalFutureState::Poisoned => Err(FlushError::Poisoned),
         }
     }
 }
@@ -260,7 +592,7 @@ where
                         })
                         .map_err(|err| OutcomeErr::Full(err.into()))
                 }
-                SubmitAnyBuilder::CommandBuffer(submit_info, fence) => {
+                SubmitAnyBuilder::CommandBuffer(submit_infos, fence) => {
                     debug_assert!(!partially_flushed);
                     // The assert below could technically be a debug assertion as it is part of the
                     // safety contract of the trait. However it is easy to get this wrong if you
@@ -270,7 +602,7 @@ where
                     assert!(fence.is_none());
 
                     queue
-                        .with(|mut q| q.submit_unchecked([submit_info], Some(new_fence.clone())))
+                        .with(|mut q| q.submit_unchecked(submit_infos, Some(new_fence.clone())))
                         .map_err(|err| OutcomeErr::Full(err.into()))
                 }
                 SubmitAnyBuilder::BindSparse(bind_infos, fence) => {
@@ -336,6 +668,7 @@ where
             // Restore the state before returning.
             match result {
                 Ok(()) => {
+                    self.device.register_outstanding_fence(&new_fence);
                     **state = FenceSignalFutureState::Flushed(previous, new_fence);
                     Ok(())
                 }
@@ -480,6 +813,32 @@ where
     }
 }
 
+#[cfg(feature = "futures")]
+impl<F> Future for FenceSignalFuture<F>
+where
+    F: GpuFuture,
+{
+    type Output = Result<(), FlushError>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_impl(cx)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<F> Future for &FenceSignalFuture<F>
+where
+    F: GpuFuture,
+{
+    type Output = Result<(), FlushError>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.poll_impl(cx)
+    }
+}
+
 impl<F> Drop for FenceSignalFuture<F>
 where
     F: GpuFuture,
@@ -574,3 +933,263 @@ where
         (**self).check_swapchain_image_acquired(image, before)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "futures")]
+    use super::FENCE_WAITERS;
+    use super::{wait_all, wait_any, FenceSignalFuture};
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+            CommandBufferExecFuture, CommandBufferUsage,
+        },
+        device::Queue,
+        sync::{now, FlushError, FutureHooks, GpuFuture, NowFuture},
+    };
+    use std::{
+        panic::{catch_unwind, AssertUnwindSafe},
+        sync::Arc,
+        time::Duration,
+    };
+
+    fn execute(
+        queue: &Arc<Queue>,
+        allocator: &StandardCommandBufferAllocator,
+    ) -> CommandBufferExecFuture<NowFuture> {
+        let command_buffer = AutoCommandBufferBuilder::primary(
+            allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        now(queue.device().clone())
+            .then_execute(queue.clone(), command_buffer)
+            .unwrap()
+    }
+
+    fn signal_fence(
+        queue: &Arc<Queue>,
+        allocator: &StandardCommandBufferAllocator,
+    ) -> FenceSignalFuture<CommandBufferExecFuture<NowFuture>> {
+        execute(queue, allocator).then_signal_fence()
+    }
+
+    // Returns a future whose flush panicked, which leaves it poisoned.
+    fn poisoned_future(
+        queue: &Arc<Queue>,
+        allocator: &StandardCommandBufferAllocator,
+    ) -> FenceSignalFuture<Box<dyn GpuFuture>> {
+        struct PanicOnSubmit;
+
+        impl FutureHooks for PanicOnSubmit {
+            fn submitted(&self) {
+                panic!("submission hook panicked");
+            }
+        }
+
+        let future = execute(queue, allocator)
+            .with_hooks(PanicOnSubmit)
+            .boxed()
+            .then_signal_fence();
+        assert!(catch_unwind(AssertUnwindSafe(|| future.flush())).is_err());
+
+        future
+    }
+
+    #[test]
+    fn wait_any_signaled() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = StandardCommandBufferAllocator::new(device);
+
+        let futures = [
+            signal_fence(&queue, &allocator),
+            signal_fence(&queue, &allocator),
+        ];
+        let index = wait_any(&[&futures[0], &futures[1]], Some(Duration::from_secs(5))).unwrap();
+        assert!(futures[index].is_cleaned());
+    }
+
+    #[test]
+    fn wait_any_already_cleaned() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = StandardCommandBufferAllocator::new(device);
+
+        let pending = signal_fence(&queue, &allocator);
+        let cleaned = signal_fence(&queue, &allocator);
+        cleaned.wait(Some(Duration::from_secs(5))).unwrap();
+        assert!(cleaned.is_cleaned());
+
+        // The cleaned future is reported without waiting for the other one.
+        let index = wait_any(&[&pending, &cleaned], Some(Duration::from_secs(5))).unwrap();
+        assert_eq!(index, 1);
+    }
+
+    #[test]
+    fn wait_any_empty() {
+        assert_should_panic!({
+            let _ = wait_any::<CommandBufferExecFuture<NowFuture>>(&[], None);
+        });
+    }
+
+    #[test]
+    fn wait_any_poisoned() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = StandardCommandBufferAllocator::new(device);
+
+        let pending = execute(&queue, &allocator).boxed().then_signal_fence();
+        let poisoned = poisoned_future(&queue, &allocator);
+        assert!(matches!(
+            wait_any(&[&pending, &poisoned], Some(Duration::from_secs(5))),
+            Err(FlushError::Poisoned)
+        ));
+    }
+
+    #[test]
+    fn wait_all_poisoned() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = StandardCommandBufferAllocator::new(device);
+
+        let pending = execute(&queue, &allocator).boxed().then_signal_fence();
+        let poisoned = poisoned_future(&queue, &allocator);
+        assert!(matches!(
+            wait_all(&[&pending, &poisoned], Some(Duration::from_secs(5))),
+            Err(FlushError::Poisoned)
+        ));
+        assert!(matches!(poisoned.wait(None), Err(FlushError::Poisoned)));
+    }
+
+    #[test]
+    fn wait_all_signaled() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = StandardCommandBufferAllocator::new(device);
+
+        let future1 = signal_fence(&queue, &allocator);
+        let future2 = signal_fence(&queue, &allocator);
+        wait_all(&[&future1, &future2], Some(Duration::from_secs(5))).unwrap();
+        assert!(future1.is_cleaned());
+        assert!(future2.is_cleaned());
+
+        // Waiting again on cleaned futures doesn't wait at all.
+        wait_all(&[&future1, &future2], Some(Duration::ZERO)).unwrap();
+    }
+
+    // Returns a waker that unparks the current thread.
+    #[cfg(feature = "futures")]
+    fn thread_waker() -> std::task::Waker {
+        use std::{
+            task::Wake,
+            thread::{self, Thread},
+        };
+
+        struct ThreadWaker(Thread);
+
+        impl Wake for ThreadWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        Arc::new(ThreadWaker(thread::current())).into()
+    }
+
+    // Drives `future` to completion on the current thread, parking it while the future is pending.
+    #[cfg(feature = "futures")]
+    fn block_on<T>(future: impl std::future::Future<Output = T>) -> T {
+        use std::{
+            task::{Context, Poll},
+            thread,
+        };
+
+        let waker = thread_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn await_signal() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = StandardCommandBufferAllocator::new(device);
+
+        let future = signal_fence(&queue, &allocator);
+        block_on(&future).unwrap();
+        assert!(future.is_cleaned());
+
+        // Polling a cleaned future completes immediately.
+        block_on(&future).unwrap();
+
+        let future = signal_fence(&queue, &allocator);
+        block_on(future).unwrap();
+    }
+
+    // Returns whether `fence` is waited for by the waiter thread.
+    #[cfg(feature = "futures")]
+    fn is_registered(fence: &Arc<crate::sync::Fence>) -> bool {
+        FENCE_WAITERS
+            .lock()
+            .registered
+            .iter()
+            .any(|(registered, _)| Arc::ptr_eq(registered, fence))
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn await_signal_unregisters() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = StandardCommandBufferAllocator::new(device);
+
+        let futures: Vec<_> = (0..4).map(|_| signal_fence(&queue, &allocator)).collect();
+        let fences: Vec<_> = futures
+            .iter()
+            .map(|future| future.fence().unwrap().unwrap())
+            .collect();
+
+        for future in &futures {
+            block_on(future).unwrap();
+        }
+
+        // The waiter thread forgets a fence before it wakes the task that awaits it.
+        assert!(!fences.iter().any(is_registered));
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn await_dropped_future() {
+        use std::{future::Future, pin::Pin, task::Context, thread, time::Instant};
+
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = StandardCommandBufferAllocator::new(device);
+
+        let future = signal_fence(&queue, &allocator);
+        let waker = thread_waker();
+
+        if Pin::new(&mut &future)
+            .poll(&mut Context::from_waker(&waker))
+            .is_ready()
+        {
+            return; // test ignored, the fence was signaled before it could be registered
+        }
+
+        let fence = future.fence().unwrap().unwrap();
+        drop(future);
+
+        // The waiter thread stops waiting for fences of futures that were dropped.
+        let start = Instant::now();
+
+        while is_registered(&fence) {
+            assert!(start.elapsed() < Duration::from_secs(1));
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
@@ -0,0 +1,248 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use super::{AccessCheckError, FlushError, GpuFuture, SubmitAnyBuilder};
+use crate::{
+    buffer::sys::UnsafeBuffer,
+    device::{Device, DeviceOwned, Queue},
+    image::{sys::UnsafeImage, ImageLayout},
+    sync::{AccessFlags, PipelineStages},
+    DeviceSize,
+};
+use std::{ops::Range, sync::Arc};
+
+/// Callbacks that are invoked by a [`HookedFuture`] when the future it wraps progresses.
+///
+/// All methods have an empty default implementation, so that implementations only need to
+/// override the events they are interested in.
+pub trait FutureHooks {
+    /// Called after the wrapped future was flushed successfully, or after its submission was
+    /// built to be submitted together with a following operation.
+    ///
+    /// This can be called more than once for the same future.
+    #[inline]
+    fn submitted(&self) {}
+
+    /// Called when the GPU has finished executing the wrapped future.
+    ///
+    /// This can be called more than once for the same future.
+    #[inline]
+    fn finished(&self) {}
+}
+
+/// A future that wraps another one, and calls user-provided [`FutureHooks`] when it progresses.
+///
+/// All operations are delegated to the wrapped future, including the access checks, so this
+/// can be used to observe a chain of futures, for example for profiling or custom scheduling,
+/// without implementing the unsafe parts of [`GpuFuture`].
+///
+/// This is created with [`GpuFuture::with_hooks`].
+#[must_use]
+pub struct HookedFuture<F, H> {
+    inner: F,
+    hooks: H,
+}
+
+impl<F, H> HookedFuture<F, H>
+where
+    F: GpuFuture,
+    H: FutureHooks,
+{
+    pub(crate) fn new(inner: F, hooks: H) -> Self {
+        HookedFuture { inner, hooks }
+    }
+
+    /// Returns the wrapped future.
+    #[inline]
+    pub fn inner(&self) -> &F {
+        &self.inner
+    }
+
+    /// Returns the hooks.
+    #[inline]
+    pub fn hooks(&self) -> &H {
+        &self.hooks
+    }
+
+    /// Returns the wrapped future and the hooks.
+    #[inline]
+    pub fn into_inner(self) -> (F, H) {
+        (self.inner, self.hooks)
+    }
+}
+
+unsafe impl<F, H> DeviceOwned for HookedFuture<F, H>
+where
+    F: DeviceOwned,
+{
+    fn device(&self) -> &Arc<Device> {
+        self.inner.device()
+    }
+}
+
+unsafe impl<F, H> GpuFuture for HookedFuture<F, H>
+where
+    F: GpuFuture,
+    H: FutureHooks,
+{
+    fn cleanup_finished(&mut self) {
+        self.inner.cleanup_finished()
+    }
+
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        let submission = self.inner.build_submission()?;
+        self.hooks.submitted();
+
+        Ok(submission)
+    }
+
+    fn flush(&self) -> Result<(), FlushError> {
+        self.inner.flush()?;
+        self.hooks.submitted();
+
+        Ok(())
+    }
+
+    unsafe fn signal_finished(&self) {
+        self.inner.signal_finished();
+        self.hooks.finished();
+    }
+
+    fn queue_change_allowed(&self) -> bool {
+        self.inner.queue_change_allowed()
+    }
+
+    fn queue(&self) -> Option<Arc<Queue>> {
+        self.inner.queue()
+    }
+
+    fn check_buffer_access(
+        &self,
+        buffer: &UnsafeBuffer,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        self.inner
+            .check_buffer_access(buffer, range, exclusive, queue)
+    }
+
+    fn check_image_access(
+        &self,
+        image: &UnsafeImage,
+        range: Range<DeviceSize>,
+        exclusive: bool,
+        expected_layout: ImageLayout,
+        queue: &Queue,
+    ) -> Result<Option<(PipelineStages, AccessFlags)>, AccessCheckError> {
+        self.inner
+            .check_image_access(image, range, exclusive, expected_layout, queue)
+    }
+
+    #[inline]
+    fn check_swapchain_image_acquired(
+        &self,
+        image: &UnsafeImage,
+        before: bool,
+    ) -> Result<(), AccessCheckError> {
+        self.inner.check_swapchain_image_acquired(image, before)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FutureHooks;
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+            CommandBufferUsage, PrimaryAutoCommandBuffer, PrimaryCommandBuffer,
+        },
+        device::Queue,
+        sync::{now, GpuFuture},
+    };
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    #[derive(Clone, Default)]
+    struct CountingHooks {
+        submitted: Arc<AtomicU32>,
+        finished: Arc<AtomicU32>,
+    }
+
+    impl FutureHooks for CountingHooks {
+        fn submitted(&self) {
+            self.submitted.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn finished(&self) {
+            self.finished.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    fn command_buffer(
+        queue: &Arc<Queue>,
+        allocator: &StandardCommandBufferAllocator,
+    ) -> PrimaryAutoCommandBuffer {
+        AutoCommandBufferBuilder::primary(
+            allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap()
+        .build()
+        .unwrap()
+    }
+
+    #[test]
+    fn hooks_called() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = StandardCommandBufferAllocator::new(device.clone());
+        let hooks = CountingHooks::default();
+
+        let future = now(device)
+            .with_hooks(hooks.clone())
+            .then_execute(queue.clone(), command_buffer(&queue, &allocator))
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+
+        // The hooked future was submitted together with the command buffer.
+        assert!(hooks.submitted.load(Ordering::SeqCst) >= 1);
+
+        future.wait(None).unwrap();
+        assert!(hooks.finished.load(Ordering::SeqCst) >= 1);
+    }
+
+    #[test]
+    fn hooks_called_on_flush() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = StandardCommandBufferAllocator::new(device);
+        let hooks = CountingHooks::default();
+
+        let future = command_buffer(&queue, &allocator)
+            .execute(queue.clone())
+            .unwrap()
+            .with_hooks(hooks.clone());
+        assert_eq!(hooks.submitted.load(Ordering::SeqCst), 0);
+        assert_eq!(hooks.finished.load(Ordering::SeqCst), 0);
+
+        future.flush().unwrap();
+        assert_eq!(hooks.submitted.load(Ordering::SeqCst), 1);
+        assert_eq!(hooks.finished.load(Ordering::SeqCst), 0);
+
+        future
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        assert!(hooks.finished.load(Ordering::SeqCst) >= 1);
+    }
+}
@@ -36,6 +36,56 @@ where
     JoinFuture { first, second }
 }
 
+/// Joins an arbitrary number of futures together into one.
+///
+/// The futures that must be submitted on a specific queue are grouped by that queue, and the
+/// submissions of each group are merged together. If there is more than one group, each group
+/// signals a semaphore, and the next submission of the returned future waits on all of them.
+/// Semaphores that more than one of the futures would wait on are only waited on once.
+///
+/// # Panics
+///
+/// - Panics if `futures` is empty.
+/// - Panics if not all futures belong to the same device.
+pub fn join_all(futures: impl IntoIterator<Item = Box<dyn GpuFuture>>) -> Box<dyn GpuFuture> {
+    fn join_boxed(first: Box<dyn GpuFuture>, second: Box<dyn GpuFuture>) -> Box<dyn GpuFuture> {
+        Box::new(join(first, second))
+    }
+
+    // The futures that must be submitted on a specific queue, grouped by that queue.
+    let mut queue_groups: Vec<(Arc<Queue>, Vec<Box<dyn GpuFuture>>)> = Vec::new();
+    // The futures that can be submitted on any queue.
+    let mut any_queue: Vec<Box<dyn GpuFuture>> = Vec::new();
+
+    for future in futures {
+        match future.queue().filter(|_| !future.queue_change_allowed()) {
+            Some(queue) => match queue_groups.iter_mut().find(|(q, _)| *q == queue) {
+                Some((_, group)) => group.push(future),
+                None => queue_groups.push((queue, vec![future])),
+            },
+            None => any_queue.push(future),
+        }
+    }
+
+    let multiple_queues = queue_groups.len() > 1;
+    let queue_groups = queue_groups.into_iter().map(|(_, group)| {
+        let joined = group.into_iter().reduce(join_boxed).unwrap();
+
+        if multiple_queues {
+            // Submissions on different queues can't be merged, so the work on each queue is
+            // submitted on its own and signals a semaphore instead.
+            joined.then_signal_semaphore().boxed()
+        } else {
+            joined
+        }
+    });
+
+    queue_groups
+        .chain(any_queue)
+        .reduce(join_boxed)
+        .expect("`futures` must not be empty")
+}
+
 /// Two futures joined into one.
 #[must_use]
 pub struct JoinFuture<A, B> {
@@ -88,7 +138,12 @@ where
             (SubmitAnyBuilder::Empty, b) => b,
             (a, SubmitAnyBuilder::Empty) => a,
             (SubmitAnyBuilder::SemaphoresWait(mut a), SubmitAnyBuilder::SemaphoresWait(b)) => {
-                a.extend(b);
+                for semaphore in b {
+                    if !a.iter().any(|s| Arc::ptr_eq(s, &semaphore)) {
+                        a.push(semaphore);
+                    }
+                }
+
                 SubmitAnyBuilder::SemaphoresWait(a)
             }
             (SubmitAnyBuilder::SemaphoresWait(a), SubmitAnyBuilder::CommandBuffer(_, _)) => {
@@ -116,25 +171,47 @@ where
                 SubmitAnyBuilder::SemaphoresWait(b)
             }
             (
-                SubmitAnyBuilder::CommandBuffer(mut submit_info_a, fence_a),
-                SubmitAnyBuilder::CommandBuffer(submit_info_b, fence_b),
+                SubmitAnyBuilder::CommandBuffer(mut submit_infos_a, fence_a),
+                SubmitAnyBuilder::CommandBuffer(submit_infos_b, fence_b),
             ) => {
                 assert!(
                     fence_a.is_none() || fence_b.is_none(),
                     "Can't merge two queue submits that both have a fence"
                 );
 
-                submit_info_a
-                    .wait_semaphores
-                    .extend(submit_info_b.wait_semaphores);
-                submit_info_a
-                    .command_buffers
-                    .extend(submit_info_b.command_buffers);
-                submit_info_a
-                    .signal_semaphores
-                    .extend(submit_info_b.signal_semaphores);
-
-                SubmitAnyBuilder::CommandBuffer(submit_info_a, fence_a.or(fence_b))
+                for submit_info_b in submit_infos_b {
+                    let submit_info_a = match submit_infos_a.last_mut() {
+                        Some(submit_info_a)
+                            if submit_info_a.protected_submit == submit_info_b.protected_submit =>
+                        {
+                            submit_info_a
+                        }
+                        // Protected and unprotected command buffers can't be in the same batch.
+                        _ => {
+                            submit_infos_a.push(submit_info_b);
+                            continue;
+                        }
+                    };
+
+                    for semaphore_submit_info in submit_info_b.wait_semaphores {
+                        // A semaphore can only be waited on once, so merge the stages instead.
+                        match submit_info_a.wait_semaphores.iter_mut().find(|info| {
+                            Arc::ptr_eq(&info.semaphore, &semaphore_submit_info.semaphore)
+                        }) {
+                            Some(info) => info.stages |= semaphore_submit_info.stages,
+                            None => submit_info_a.wait_semaphores.push(semaphore_submit_info),
+                        }
+                    }
+
+                    submit_info_a
+                        .command_buffers
+                        .extend(submit_info_b.command_buffers);
+                    submit_info_a
+                        .signal_semaphores
+                        .extend(submit_info_b.signal_semaphores);
+                }
+
+                SubmitAnyBuilder::CommandBuffer(submit_infos_a, fence_a.or(fence_b))
             }
             (SubmitAnyBuilder::QueuePresent(_), SubmitAnyBuilder::QueuePresent(_)) => {
                 self.first.flush()?;
@@ -292,3 +369,112 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::join_all;
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder,
+            CommandBufferUsage, PrimaryCommandBuffer,
+        },
+        device::{Device, DeviceCreateInfo, Queue, QueueCreateInfo},
+        sync::{GpuFuture, SubmitAnyBuilder},
+    };
+    use std::sync::Arc;
+
+    fn execute(
+        queue: &Arc<Queue>,
+        allocator: &StandardCommandBufferAllocator,
+    ) -> Box<dyn GpuFuture> {
+        AutoCommandBufferBuilder::primary(
+            allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap()
+        .build()
+        .unwrap()
+        .execute(queue.clone())
+        .unwrap()
+        .boxed()
+    }
+
+    #[test]
+    fn join_all_same_queue() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = StandardCommandBufferAllocator::new(device);
+
+        let future = join_all([
+            execute(&queue, &allocator),
+            execute(&queue, &allocator),
+            execute(&queue, &allocator),
+        ]);
+        assert!(future.queue().unwrap() == queue);
+
+        // The command buffers are merged into a single batch.
+        match unsafe { future.build_submission() }.unwrap() {
+            SubmitAnyBuilder::CommandBuffer(submit_infos, None) => {
+                assert_eq!(submit_infos.len(), 1);
+                assert_eq!(submit_infos[0].command_buffers.len(), 3);
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn join_all_different_queues() {
+        let instance = instance!();
+
+        let (physical_device, queue_family_index) = match instance
+            .enumerate_physical_devices()
+            .unwrap()
+            .find_map(|p| {
+                let queue_family_index = p
+                    .queue_family_properties()
+                    .iter()
+                    .position(|q| q.queue_count >= 2)?;
+
+                Some((p, queue_family_index as u32))
+            }) {
+            Some(x) => x,
+            None => return, // test ignored
+        };
+
+        let (device, mut queues) = Device::new(
+            physical_device,
+            DeviceCreateInfo {
+                queue_create_infos: vec![QueueCreateInfo {
+                    queue_family_index,
+                    queues: vec![0.5, 0.5],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let (queue1, queue2) = (queues.next().unwrap(), queues.next().unwrap());
+        let allocator = StandardCommandBufferAllocator::new(device);
+
+        // Joining pairwise would panic, because neither future allows a queue change.
+        let future = join_all([
+            execute(&queue1, &allocator),
+            execute(&queue2, &allocator),
+            execute(&queue1, &allocator),
+        ]);
+        assert!(future.queue().is_some());
+
+        future
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+
+    #[test]
+    fn join_all_empty() {
+        assert_should_panic!({
+            let _ = join_all([]);
+        });
+    }
+}
@@ -8,8 +8,10 @@
 // according to those terms.
 
 pub use self::{
-    fence_signal::{FenceSignalFuture, FenceSignalFutureBehavior},
-    join::JoinFuture,
+    dry_run::{DryRunFuture, DryRunQueue, DryRunSubmission},
+    fence_signal::{wait_all, wait_any, FenceSignalFuture, FenceSignalFutureBehavior},
+    hooked::{FutureHooks, HookedFuture},
+    join::{join_all, JoinFuture},
     now::{now, NowFuture},
     semaphore_signal::SemaphoreSignalFuture,
 };
@@ -33,7 +35,9 @@ use std::{
     sync::Arc,
 };
 
+mod dry_run;
 mod fence_signal;
+mod hooked;
 mod join;
 mod now;
 mod semaphore_signal;
@@ -41,6 +45,9 @@ mod semaphore_signal;
 /// Represents an event that will happen on the GPU in the future.
 ///
 /// See the documentation of the `sync` module for explanations about futures.
+///
+/// To observe the progress of an existing future, for example for profiling, wrap it with
+/// [`with_hooks`](GpuFuture::with_hooks) instead of implementing this trait.
 // TODO: consider switching all methods to take `&mut self` for optimization purposes
 pub unsafe trait GpuFuture: DeviceOwned {
     /// If possible, checks whether the submission has finished. If so, gives up ownership of the
@@ -162,6 +169,29 @@ pub unsafe trait GpuFuture: DeviceOwned {
         join::join(self, other)
     }
 
+    /// Joins this future with an arbitrary number of other futures, representing the moment
+    /// when all of them have happened.
+    ///
+    /// See [`join_all`](crate::sync::join_all) for details.
+    fn join_all<I>(self, others: I) -> Box<dyn GpuFuture>
+    where
+        Self: Sized + 'static,
+        I: IntoIterator<Item = Box<dyn GpuFuture>>,
+    {
+        join::join_all(std::iter::once(self.boxed()).chain(others))
+    }
+
+    /// Wraps this future in a [`HookedFuture`], which calls the given hooks when the future is
+    /// submitted and when it has finished executing.
+    #[inline]
+    fn with_hooks<H>(self, hooks: H) -> HookedFuture<Self, H>
+    where
+        Self: Sized,
+        H: FutureHooks,
+    {
+        HookedFuture::new(self, hooks)
+    }
+
     /// Executes a command buffer after this future.
     ///
     /// > **Note**: This is just a shortcut function. The actual implementation is in the
@@ -178,6 +208,26 @@ pub unsafe trait GpuFuture: DeviceOwned {
         command_buffer.execute_after(self, queue)
     }
 
+    /// Executes several command buffers after this future, in a single batch of a queue
+    /// submission.
+    ///
+    /// Chained calls to `then_execute` on the same queue are already submitted together when the
+    /// future is flushed. This function does the same for a number of command buffers that is
+    /// only known at runtime.
+    ///
+    /// > **Note**: This is just a shortcut for
+    /// > [`CommandBufferExecFuture::new_batch`].
+    fn then_execute_batch(
+        self,
+        queue: Arc<Queue>,
+        command_buffers: impl IntoIterator<Item = Arc<dyn PrimaryCommandBuffer>>,
+    ) -> Result<CommandBufferExecFuture<Self>, CommandBufferExecError>
+    where
+        Self: Sized,
+    {
+        CommandBufferExecFuture::new_batch(self, queue, command_buffers)
+    }
+
     /// Executes a command buffer after this future, on the same queue as the future.
     ///
     /// > **Note**: This is just a shortcut function. The actual implementation is in the
@@ -274,6 +324,25 @@ pub unsafe trait GpuFuture: DeviceOwned {
         swapchain::present(self, queue, swapchain_info)
     }
 
+    /// Presents images on several swapchains after this future, with a single present
+    /// operation.
+    ///
+    /// This is useful when rendering to multiple windows, as all of them are presented with one
+    /// submission to the queue instead of one per window.
+    ///
+    /// > **Note**: This is just a shortcut for the `swapchain::present_multiple()` function.
+    #[inline]
+    fn then_swapchains_present(
+        self,
+        queue: Arc<Queue>,
+        swapchain_infos: impl IntoIterator<Item = SwapchainPresentInfo>,
+    ) -> PresentFuture<Self>
+    where
+        Self: Sized,
+    {
+        swapchain::present_multiple(self, queue, swapchain_infos)
+    }
+
     /// Turn the current future into a `Box<dyn GpuFuture>`.
     ///
     /// This is a helper function that calls `Box::new(yourFuture) as Box<dyn GpuFuture>`.
@@ -384,7 +453,7 @@ where
 pub enum SubmitAnyBuilder {
     Empty,
     SemaphoresWait(SmallVec<[Arc<Semaphore>; 8]>),
-    CommandBuffer(SubmitInfo, Option<Arc<Fence>>),
+    CommandBuffer(SmallVec<[SubmitInfo; 1]>, Option<Arc<Fence>>),
     QueuePresent(PresentInfo),
     BindSparse(SmallVec<[BindSparseInfo; 1]>, Option<Arc<Fence>>),
 }
@@ -423,6 +492,13 @@ pub enum AccessError {
 
     /// Trying to use a swapchain image without depending on a corresponding acquire image future.
     SwapchainImageNotAcquired,
+
+    /// Trying to use a resource with concurrent sharing on a queue whose queue family is not one
+    /// of the queue families that the resource is shared with.
+    QueueFamilyNotShared {
+        /// The queue family index of the queue.
+        queue_family_index: u32,
+    },
 }
 
 impl Error for AccessError {}
@@ -451,6 +527,10 @@ impl Display for AccessError {
                     "trying to use a swapchain image without depending on a corresponding acquire \
                     image future"
                 }
+                AccessError::QueueFamilyNotShared { .. } => {
+                    "trying to use a resource with concurrent sharing on a queue whose queue family \
+                    the resource is not shared with"
+                }
             }
         )
     }
@@ -515,6 +595,9 @@ pub enum FlushError {
     /// A non-zero present_id must be greater than any non-zero present_id passed previously
     /// for the same swapchain.
     PresentIdLessThanOrEqual,
+
+    /// A previous flush of the future panicked, and the submission is in an unknown state.
+    Poisoned,
 }
 
 impl Error for FlushError {
@@ -547,6 +630,7 @@ impl Display for FlushError {
                 FlushError::PresentIdLessThanOrEqual => {
                     "present id is less than or equal to previous"
                 }
+                FlushError::Poisoned => "a previous flush of the future panicked",
             }
         )
     }
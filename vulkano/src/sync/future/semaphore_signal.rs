@@ -125,14 +125,16 @@ where
                         )
                     })?;
                 }
-                SubmitAnyBuilder::CommandBuffer(mut submit_info, fence) => {
+                SubmitAnyBuilder::CommandBuffer(mut submit_infos, fence) => {
+                    // The semaphore is signaled by the last batch, which starts after the others.
+                    let submit_info = submit_infos.last_mut().unwrap();
                     debug_assert!(submit_info.signal_semaphores.is_empty());
 
                     submit_info
                         .signal_semaphores
                         .push(SemaphoreSubmitInfo::semaphore(self.semaphore.clone()));
 
-                    queue.with(|mut q| q.submit_unchecked([submit_info], fence))?;
+                    queue.with(|mut q| q.submit_unchecked(submit_infos, fence))?;
                 }
                 SubmitAnyBuilder::BindSparse(_, _) => {
                     unimplemented!() // TODO: how to do that?
@@ -101,7 +101,10 @@
 //!
 //! TODO: lots of problems with how to use fences
 //! TODO: talk about fence + semaphore simultaneously
-//! TODO: talk about using fences to clean up
+//!
+//! Resources that must stay alive until a submission has finished can be handed to a
+//! [`ResourceReclaimer`], together with the future of the submission. The reclaimer drops them
+//! once the fence of that future is signaled.
 
 #[cfg(unix)]
 pub use self::fence::ImportFenceFdInfo;
@@ -114,17 +117,19 @@ pub use self::{
         ExternalFenceProperties, Fence, FenceCreateInfo, FenceError, FenceImportFlags,
     },
     future::{
-        now, AccessCheckError, AccessError, FenceSignalFuture, FlushError, GpuFuture, JoinFuture,
-        NowFuture, SemaphoreSignalFuture, SubmitAnyBuilder,
+        join_all, now, wait_all, wait_any, AccessCheckError, AccessError, DryRunFuture,
+        DryRunQueue, DryRunSubmission, FenceSignalFuture, FlushError, FutureHooks, GpuFuture,
+        HookedFuture, JoinFuture, NowFuture, SemaphoreSignalFuture, SubmitAnyBuilder,
     },
     pipeline::{
         AccessFlags, BufferMemoryBarrier, DependencyInfo, ImageMemoryBarrier, MemoryBarrier,
         PipelineMemoryAccess, PipelineStage, PipelineStages, QueueFamilyTransfer,
     },
+    reclaimer::ResourceReclaimer,
     semaphore::{
         ExternalSemaphoreHandleType, ExternalSemaphoreHandleTypes, ExternalSemaphoreInfo,
         ExternalSemaphoreProperties, Semaphore, SemaphoreCreateInfo, SemaphoreError,
-        SemaphoreImportFlags,
+        SemaphoreImportFlags, SemaphoreType,
     },
 };
 pub(crate) use self::{fence::FenceState, semaphore::SemaphoreState};
@@ -135,6 +140,7 @@ mod event;
 mod fence;
 mod future;
 mod pipeline;
+mod reclaimer;
 mod semaphore;
 
 /// Declares in which queue(s) a resource can be used.
@@ -171,7 +177,7 @@ impl<'a> From<&'a [&'a Arc<Queue>]> for SharingMode {
 }
 
 /// Declares in which queue(s) a resource can be used.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Sharing<I>
 where
     I: IntoIterator<Item = u32>,
@@ -537,6 +537,19 @@ impl AccessFlags {
             _ne: crate::NonExhaustive(()),
         }
     }
+
+    // Returns the access types that write to memory.
+    pub(crate) fn writes() -> AccessFlags {
+        AccessFlags {
+            shader_write: true,
+            color_attachment_write: true,
+            depth_stencil_attachment_write: true,
+            transfer_write: true,
+            host_write: true,
+            memory_write: true,
+            ..AccessFlags::empty()
+        }
+    }
 }
 
 impl From<AccessFlags> for ash::vk::AccessFlags {
@@ -601,6 +614,139 @@ impl DependencyInfo {
         self.buffer_memory_barriers.clear();
         self.image_memory_barriers.clear();
     }
+
+    /// Combines barriers, so that fewer of them need to be processed by the device.
+    ///
+    /// - All memory barriers, all buffer memory barriers and the image memory barriers that don't
+    ///   change the layout are combined into a single memory barrier. Barriers that transfer
+    ///   queue family ownership are kept as they are.
+    /// - Image memory barriers that perform the same layout transition on adjacent or overlapping
+    ///   subresource ranges of the same image are combined into one. This also removes barriers
+    ///   that are duplicates of another.
+    ///
+    /// A combined barrier uses the union of the stages and access types of the barriers it
+    /// replaces. Every dependency of the original barriers is kept, but new dependencies can be
+    /// created between stages that didn't previously depend on each other.
+    pub fn optimize(&mut self) {
+        let mut global: Option<MemoryBarrier> = None;
+        let mut add_global = |source_stages: PipelineStages,
+                              source_access: AccessFlags,
+                              destination_stages: PipelineStages,
+                              destination_access: AccessFlags| {
+            let barrier = global.get_or_insert_with(Default::default);
+            barrier.source_stages = barrier.source_stages.union(&source_stages);
+            barrier.source_access = barrier.source_access.union(&source_access);
+            barrier.destination_stages = barrier.destination_stages.union(&destination_stages);
+            barrier.destination_access = barrier.destination_access.union(&destination_access);
+        };
+
+        for barrier in self.memory_barriers.drain(..) {
+            add_global(
+                barrier.source_stages,
+                barrier.source_access,
+                barrier.destination_stages,
+                barrier.destination_access,
+            );
+        }
+
+        self.buffer_memory_barriers.retain(|barrier| {
+            if barrier.queue_family_transfer.is_some() {
+                return true;
+            }
+
+            add_global(
+                barrier.source_stages,
+                barrier.source_access,
+                barrier.destination_stages,
+                barrier.destination_access,
+            );
+            false
+        });
+
+        self.image_memory_barriers.retain(|barrier| {
+            if barrier.queue_family_transfer.is_some() || barrier.old_layout != barrier.new_layout {
+                return true;
+            }
+
+            add_global(
+                barrier.source_stages,
+                barrier.source_access,
+                barrier.destination_stages,
+                barrier.destination_access,
+            );
+            false
+        });
+
+        self.memory_barriers.extend(global);
+
+        let mut index = 0;
+
+        while index < self.image_memory_barriers.len() {
+            let mut other_index = index + 1;
+
+            while other_index < self.image_memory_barriers.len() {
+                let subresource_range = match merged_subresource_range(
+                    &self.image_memory_barriers[index],
+                    &self.image_memory_barriers[other_index],
+                ) {
+                    Some(subresource_range) => subresource_range,
+                    None => {
+                        other_index += 1;
+                        continue;
+                    }
+                };
+
+                let other = self.image_memory_barriers.remove(other_index);
+                let barrier = &mut self.image_memory_barriers[index];
+                barrier.source_stages = barrier.source_stages.union(&other.source_stages);
+                barrier.source_access = barrier.source_access.union(&other.source_access);
+                barrier.destination_stages =
+                    barrier.destination_stages.union(&other.destination_stages);
+                barrier.destination_access =
+                    barrier.destination_access.union(&other.destination_access);
+                barrier.subresource_range = subresource_range;
+
+                // The grown range may now be adjacent to a barrier that was skipped earlier.
+                other_index = index + 1;
+            }
+
+            index += 1;
+        }
+    }
+}
+
+// Returns the subresource range covered by both barriers, if they can be combined into one.
+fn merged_subresource_range(
+    a: &ImageMemoryBarrier,
+    b: &ImageMemoryBarrier,
+) -> Option<ImageSubresourceRange> {
+    if a.image != b.image
+        || a.old_layout != b.old_layout
+        || a.new_layout != b.new_layout
+        || a.queue_family_transfer.is_some()
+        || b.queue_family_transfer.is_some()
+        || a.subresource_range.aspects != b.subresource_range.aspects
+    {
+        return None;
+    }
+
+    let touches = |a: &Range<u32>, b: &Range<u32>| a.start <= b.end && b.start <= a.end;
+    let union = |a: &Range<u32>, b: &Range<u32>| a.start.min(b.start)..a.end.max(b.end);
+    let (a, b) = (&a.subresource_range, &b.subresource_range);
+
+    if a.mip_levels == b.mip_levels && touches(&a.array_layers, &b.array_layers) {
+        Some(ImageSubresourceRange {
+            array_layers: union(&a.array_layers, &b.array_layers),
+            ..a.clone()
+        })
+    } else if a.array_layers == b.array_layers && touches(&a.mip_levels, &b.mip_levels) {
+        Some(ImageSubresourceRange {
+            mip_levels: union(&a.mip_levels, &b.mip_levels),
+            ..a.clone()
+        })
+    } else {
+        None
+    }
 }
 
 impl Default for DependencyInfo {
@@ -778,3 +924,132 @@ pub struct QueueFamilyTransfer {
     /// The queue family to transfer ownership to.
     pub destination_index: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AccessFlags, BufferMemoryBarrier, DependencyInfo, ImageMemoryBarrier, PipelineStages,
+    };
+    use crate::{
+        buffer::{
+            sys::{UnsafeBuffer, UnsafeBufferCreateInfo},
+            BufferUsage,
+        },
+        format::Format,
+        image::{
+            sys::{UnsafeImage, UnsafeImageCreateInfo},
+            ImageAspects, ImageDimensions, ImageLayout, ImageSubresourceRange, ImageUsage,
+        },
+    };
+
+    #[test]
+    fn optimize() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let buffer = UnsafeBuffer::new(
+            device.clone(),
+            UnsafeBufferCreateInfo {
+                size: 128,
+                usage: BufferUsage {
+                    transfer_dst: true,
+                    ..BufferUsage::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let image = UnsafeImage::new(
+            device,
+            UnsafeImageCreateInfo {
+                dimensions: ImageDimensions::Dim2d {
+                    width: 32,
+                    height: 32,
+                    array_layers: 1,
+                },
+                format: Some(Format::R8G8B8A8_UNORM),
+                mip_levels: 4,
+                usage: ImageUsage {
+                    transfer_dst: true,
+                    ..ImageUsage::empty()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let buffer_barrier = |range| BufferMemoryBarrier {
+            source_stages: PipelineStages {
+                transfer: true,
+                ..PipelineStages::empty()
+            },
+            source_access: AccessFlags {
+                transfer_write: true,
+                ..AccessFlags::empty()
+            },
+            destination_stages: PipelineStages {
+                transfer: true,
+                ..PipelineStages::empty()
+            },
+            destination_access: AccessFlags {
+                transfer_read: true,
+                ..AccessFlags::empty()
+            },
+            range,
+            ..BufferMemoryBarrier::buffer(buffer.clone())
+        };
+        let image_barrier = |mip_levels, new_layout| ImageMemoryBarrier {
+            source_stages: PipelineStages {
+                transfer: true,
+                ..PipelineStages::empty()
+            },
+            destination_stages: PipelineStages {
+                fragment_shader: true,
+                ..PipelineStages::empty()
+            },
+            old_layout: ImageLayout::TransferDstOptimal,
+            new_layout,
+            subresource_range: ImageSubresourceRange {
+                aspects: ImageAspects {
+                    color: true,
+                    ..ImageAspects::empty()
+                },
+                mip_levels,
+                array_layers: 0..1,
+            },
+            ..ImageMemoryBarrier::image(image.clone())
+        };
+
+        let mut dependency_info = DependencyInfo {
+            buffer_memory_barriers: [buffer_barrier(0..64), buffer_barrier(64..128)]
+                .into_iter()
+                .collect(),
+            image_memory_barriers: [
+                image_barrier(2..3, ImageLayout::ShaderReadOnlyOptimal),
+                image_barrier(0..1, ImageLayout::ShaderReadOnlyOptimal),
+                image_barrier(3..4, ImageLayout::TransferSrcOptimal),
+                image_barrier(1..2, ImageLayout::ShaderReadOnlyOptimal),
+                image_barrier(0..1, ImageLayout::ShaderReadOnlyOptimal),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+        dependency_info.optimize();
+
+        assert_eq!(dependency_info.memory_barriers.len(), 1);
+        assert!(dependency_info.buffer_memory_barriers.is_empty());
+        assert_eq!(dependency_info.image_memory_barriers.len(), 2);
+        assert_eq!(
+            dependency_info.image_memory_barriers[0]
+                .subresource_range
+                .mip_levels,
+            0..3
+        );
+        assert_eq!(
+            dependency_info.image_memory_barriers[1]
+                .subresource_range
+                .mip_levels,
+            3..4
+        );
+    }
+}
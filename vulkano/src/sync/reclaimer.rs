@@ -0,0 +1,186 @@
+// Copyright (c) 2022 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// https://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or https://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use super::{Fence, FenceSignalFuture, FlushError, GpuFuture};
+use std::{any::Any, sync::Arc};
+
+/// Keeps resources alive until the device has finished using them, then drops them.
+///
+/// Each resource is paired with the [`FenceSignalFuture`] of the submission that uses it. Calling
+/// [`cleanup_finished`](Self::cleanup_finished), typically once per frame, drops every resource
+/// whose fence has been signaled. This makes the point where resources are freed explicit,
+/// instead of it depending on when the futures that happen to hold them are dropped.
+///
+/// A resource can be anything: a buffer, an image, a descriptor set, or a tuple or `Vec` of
+/// several of them.
+///
+/// If the future is never flushed, its fence is never signaled, and the resource is only dropped
+/// along with the `ResourceReclaimer`.
+///
+/// # Examples
+///
+/// ```
+/// use vulkano::sync::{FenceSignalFuture, GpuFuture, ResourceReclaimer};
+/// # let staging_buffer: std::sync::Arc<vulkano::buffer::CpuAccessibleBuffer<[u8]>> = return;
+/// # let future: FenceSignalFuture<Box<dyn GpuFuture>> = return;
+///
+/// let mut reclaimer = ResourceReclaimer::new();
+/// reclaimer.defer(&future, staging_buffer).unwrap();
+///
+/// // Later, for example at the start of the next frame.
+/// reclaimer.cleanup_finished();
+/// ```
+#[derive(Default)]
+pub struct ResourceReclaimer {
+    pending: Vec<(Arc<Fence>, Box<dyn Any + Send + Sync>)>,
+}
+
+impl ResourceReclaimer {
+    /// Creates a new `ResourceReclaimer` with no pending resources.
+    #[inline]
+    pub fn new() -> Self {
+        ResourceReclaimer {
+            pending: Vec::new(),
+        }
+    }
+
+    /// Takes ownership of `resource`, and drops it once the fence of `future` is signaled.
+    ///
+    /// If the submission of `future` has already finished and been cleaned up, `resource` is
+    /// dropped immediately.
+    ///
+    /// If a previous flush of `future` panicked, the state of its submission is unknown and
+    /// [`FlushError::Poisoned`] is returned. `resource` is dropped in that case too, as the
+    /// previous future was dropped while unwinding, which blocks until the queue is done with it.
+    pub fn defer<F>(
+        &mut self,
+        future: &FenceSignalFuture<F>,
+        resource: impl Send + Sync + 'static,
+    ) -> Result<(), FlushError>
+    where
+        F: GpuFuture,
+    {
+        if let Some(fence) = future.fence()? {
+            self.pending.push((fence, Box::new(resource)));
+        }
+
+        Ok(())
+    }
+
+    /// Drops all resources whose fence has been signaled.
+    ///
+    /// This function doesn't block.
+    pub fn cleanup_finished(&mut self) {
+        // If querying the fence fails, the resource is kept and checked again on the next call.
+        self.pending
+            .retain(|(fence, _)| !fence.is_signaled().unwrap_or(false));
+    }
+
+    /// Returns the number of resources that are waiting for their fence to be signaled.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns whether there are no resources waiting for their fence to be signaled.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResourceReclaimer;
+    use crate::{
+        command_buffer::{
+            allocator::StandardCommandBufferAllocator, AutoCommandBufferBuilder, CommandBufferUsage,
+        },
+        sync::{now, FlushError, FutureHooks, GpuFuture},
+    };
+    use std::{
+        panic::{catch_unwind, AssertUnwindSafe},
+        sync::Arc,
+    };
+
+    #[test]
+    fn drops_after_fence() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let allocator = StandardCommandBufferAllocator::new(device.clone());
+        let command_buffer = AutoCommandBufferBuilder::primary(
+            &allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let future = now(device)
+            .then_execute(queue, command_buffer)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+
+        let resource = Arc::new(());
+        let mut reclaimer = ResourceReclaimer::new();
+        reclaimer.defer(&future, resource.clone()).unwrap();
+        assert_eq!(reclaimer.len(), 1);
+
+        future.wait(None).unwrap();
+        reclaimer.cleanup_finished();
+        assert!(reclaimer.is_empty());
+        assert_eq!(Arc::strong_count(&resource), 1);
+
+        // The submission has been cleaned up, so there is nothing left to wait for.
+        reclaimer.defer(&future, resource.clone()).unwrap();
+        assert!(reclaimer.is_empty());
+        assert_eq!(Arc::strong_count(&resource), 1);
+    }
+
+    #[test]
+    fn poisoned_future() {
+        struct PanicOnSubmit;
+
+        impl FutureHooks for PanicOnSubmit {
+            fn submitted(&self) {
+                panic!("submission hook panicked");
+            }
+        }
+
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let allocator = StandardCommandBufferAllocator::new(device.clone());
+        let command_buffer = AutoCommandBufferBuilder::primary(
+            &allocator,
+            queue.queue_family_index(),
+            CommandBufferUsage::OneTimeSubmit,
+        )
+        .unwrap()
+        .build()
+        .unwrap();
+
+        let future = now(device)
+            .then_execute(queue, command_buffer)
+            .unwrap()
+            .with_hooks(PanicOnSubmit)
+            .then_signal_fence();
+        assert!(catch_unwind(AssertUnwindSafe(|| future.flush())).is_err());
+
+        let resource = Arc::new(());
+        let mut reclaimer = ResourceReclaimer::new();
+        assert!(matches!(
+            reclaimer.defer(&future, resource.clone()),
+            Err(FlushError::Poisoned)
+        ));
+        assert!(reclaimer.is_empty());
+        assert_eq!(Arc::strong_count(&resource), 1);
+    }
+}
@@ -13,6 +13,7 @@ use crate::{
     OomError, RequirementNotMet, RequiresOneOf, Version, VulkanError, VulkanObject,
 };
 use parking_lot::{Mutex, MutexGuard};
+use smallvec::SmallVec;
 use std::{
     error::Error,
     fmt::{Display, Error as FmtError, Formatter},
@@ -33,6 +34,7 @@ pub struct Semaphore {
     device: Arc<Device>,
     must_put_in_pool: bool,
 
+    semaphore_type: SemaphoreType,
     export_handle_types: ExternalSemaphoreHandleTypes,
 
     state: Mutex<SemaphoreState>,
@@ -55,10 +57,36 @@ impl Semaphore {
         create_info: &SemaphoreCreateInfo,
     ) -> Result<(), SemaphoreError> {
         let &SemaphoreCreateInfo {
+            semaphore_type,
+            initial_value,
             export_handle_types,
             _ne: _,
         } = create_info;
 
+        // VUID-VkSemaphoreTypeCreateInfo-semaphoreType-parameter
+        semaphore_type.validate_device(device)?;
+
+        match semaphore_type {
+            SemaphoreType::Binary => {
+                // VUID-VkSemaphoreTypeCreateInfo-semaphoreType-03279
+                if initial_value != 0 {
+                    return Err(SemaphoreError::BinaryInitialValueNotZero);
+                }
+            }
+            SemaphoreType::Timeline => {
+                // VUID-VkSemaphoreTypeCreateInfo-timelineSemaphore-03252
+                if !device.enabled_features().timeline_semaphore {
+                    return Err(SemaphoreError::RequirementNotMet {
+                        required_for: "`create_info.semaphore_type` is `SemaphoreType::Timeline`",
+                        requires_one_of: RequiresOneOf {
+                            features: &["timeline_semaphore"],
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+        }
+
         if !export_handle_types.is_empty() {
             if !(device.api_version() >= Version::V1_1
                 || device.enabled_extensions().khr_external_semaphore)
@@ -109,6 +137,8 @@ impl Semaphore {
         create_info: SemaphoreCreateInfo,
     ) -> Result<Semaphore, VulkanError> {
         let SemaphoreCreateInfo {
+            semaphore_type,
+            initial_value,
             export_handle_types,
             _ne: _,
         } = create_info;
@@ -117,8 +147,17 @@ impl Semaphore {
             flags: ash::vk::SemaphoreCreateFlags::empty(),
             ..Default::default()
         };
+        let mut semaphore_type_create_info_vk = None;
         let mut export_semaphore_create_info_vk = None;
 
+        if semaphore_type != SemaphoreType::Binary {
+            let _ = semaphore_type_create_info_vk.insert(ash::vk::SemaphoreTypeCreateInfo {
+                semaphore_type: semaphore_type.into(),
+                initial_value,
+                ..Default::default()
+            });
+        }
+
         if !export_handle_types.is_empty() {
             let _ = export_semaphore_create_info_vk.insert(ash::vk::ExportSemaphoreCreateInfo {
                 handle_types: export_handle_types.into(),
@@ -126,6 +165,11 @@ impl Semaphore {
             });
         };
 
+        if let Some(info) = semaphore_type_create_info_vk.as_mut() {
+            info.p_next = create_info_vk.p_next;
+            create_info_vk.p_next = info as *const _ as *const _;
+        }
+
         if let Some(info) = export_semaphore_create_info_vk.as_mut() {
             info.p_next = create_info_vk.p_next;
             create_info_vk.p_next = info as *const _ as *const _;
@@ -150,9 +194,13 @@ impl Semaphore {
             handle,
             must_put_in_pool: false,
 
+            semaphore_type,
             export_handle_types,
 
-            state: Mutex::new(Default::default()),
+            state: Mutex::new(SemaphoreState {
+                counter_value: initial_value,
+                ..Default::default()
+            }),
         })
     }
 
@@ -171,6 +219,7 @@ impl Semaphore {
                 handle,
                 must_put_in_pool: true,
 
+                semaphore_type: SemaphoreType::Binary,
                 export_handle_types: ExternalSemaphoreHandleTypes::empty(),
 
                 state: Mutex::new(Default::default()),
@@ -199,6 +248,8 @@ impl Semaphore {
         create_info: SemaphoreCreateInfo,
     ) -> Semaphore {
         let SemaphoreCreateInfo {
+            semaphore_type,
+            initial_value,
             export_handle_types,
             _ne: _,
         } = create_info;
@@ -208,12 +259,22 @@ impl Semaphore {
             handle,
             must_put_in_pool: false,
 
+            semaphore_type,
             export_handle_types,
 
-            state: Mutex::new(Default::default()),
+            state: Mutex::new(SemaphoreState {
+                counter_value: initial_value,
+                ..Default::default()
+            }),
         }
     }
 
+    /// Returns the type of the semaphore.
+    #[inline]
+    pub fn semaphore_type(&self) -> SemaphoreType {
+        self.semaphore_type
+    }
+
     /// Exports the semaphore into a POSIX file descriptor. The caller owns the returned `File`.
     #[cfg(unix)]
     #[inline]
@@ -1040,6 +1101,12 @@ pub(crate) struct SemaphoreState {
     pending_signal: Option<SignalType>,
     pending_wait: Option<Weak<Queue>>,
 
+    // Timeline semaphores don't use the binary state above. Instead, they track the last known
+    // value of their payload, and the values of the queue operations that are pending on them.
+    counter_value: u64,
+    pending_signal_values: SmallVec<[u64; 2]>,
+    pending_wait_values: SmallVec<[u64; 2]>,
+
     reference_exported: bool,
     exported_handle_types: ExternalSemaphoreHandleTypes,
     current_import: Option<ImportType>,
@@ -1074,7 +1141,20 @@ impl SemaphoreState {
 
     #[inline]
     fn is_in_queue(&self) -> bool {
-        matches!(self.pending_signal, Some(SignalType::Queue(_))) || self.pending_wait.is_some()
+        matches!(self.pending_signal, Some(SignalType::Queue(_)))
+            || self.pending_wait.is_some()
+            || !self.pending_signal_values.is_empty()
+            || !self.pending_wait_values.is_empty()
+    }
+
+    /// For a timeline semaphore, returns the highest value that the payload is known to have,
+    /// or to be signaled to by a pending queue operation.
+    #[inline]
+    pub(crate) fn last_signal_value(&self) -> u64 {
+        self.pending_signal_values
+            .iter()
+            .copied()
+            .fold(self.counter_value, u64::max)
     }
 
     /// Returns whether there are any potential external references to the semaphore payload.
@@ -1115,6 +1195,37 @@ impl SemaphoreState {
         self.is_signaled = false;
     }
 
+    #[inline]
+    pub(crate) unsafe fn add_queue_signal_value(&mut self, value: u64) {
+        self.pending_signal_values.push(value);
+    }
+
+    #[inline]
+    pub(crate) unsafe fn add_queue_wait_value(&mut self, value: u64) {
+        self.pending_wait_values.push(value);
+    }
+
+    /// Called when a queue is unlocking resources.
+    #[inline]
+    pub(crate) unsafe fn set_signal_value_finished(&mut self, value: u64) {
+        if let Some(index) = self.pending_signal_values.iter().position(|&v| v == value) {
+            self.pending_signal_values.remove(index);
+        }
+
+        self.counter_value = self.counter_value.max(value);
+    }
+
+    /// Called when a queue is unlocking resources.
+    #[inline]
+    pub(crate) unsafe fn set_wait_value_finished(&mut self, value: u64) {
+        if let Some(index) = self.pending_wait_values.iter().position(|&v| v == value) {
+            self.pending_wait_values.remove(index);
+        }
+
+        // A completed wait means that the payload has reached the value.
+        self.counter_value = self.counter_value.max(value);
+    }
+
     #[allow(dead_code)]
     #[inline]
     unsafe fn export(&mut self, handle_type: ExternalSemaphoreHandleType) {
@@ -1167,6 +1278,22 @@ impl From<ExternalSemaphoreHandleType> for ImportType {
 /// Parameters to create a new `Semaphore`.
 #[derive(Clone, Debug)]
 pub struct SemaphoreCreateInfo {
+    /// The type of semaphore to create.
+    ///
+    /// If set to [`SemaphoreType::Timeline`], the
+    /// [`timeline_semaphore`](crate::device::Features::timeline_semaphore) feature must be
+    /// enabled on the device.
+    ///
+    /// The default value is [`SemaphoreType::Binary`].
+    pub semaphore_type: SemaphoreType,
+
+    /// The initial payload value of a timeline semaphore.
+    ///
+    /// If `semaphore_type` is [`SemaphoreType::Binary`], this must be 0.
+    ///
+    /// The default value is `0`.
+    pub initial_value: u64,
+
     /// The handle types that can be exported from the semaphore.
     ///
     /// The default value is [`ExternalSemaphoreHandleTypes::empty()`].
@@ -1179,12 +1306,29 @@ impl Default for SemaphoreCreateInfo {
     #[inline]
     fn default() -> Self {
         Self {
+            semaphore_type: SemaphoreType::Binary,
+            initial_value: 0,
             export_handle_types: ExternalSemaphoreHandleTypes::empty(),
             _ne: crate::NonExhaustive(()),
         }
     }
 }
 
+vulkan_enum! {
+    /// The type that a semaphore can have.
+    #[non_exhaustive]
+    SemaphoreType = SemaphoreType(i32);
+
+    /// A semaphore that has two states, signaled and unsignaled.
+    Binary = BINARY,
+
+    /// A semaphore whose state is a monotonically increasing 64-bit integer payload.
+    Timeline = TIMELINE {
+        api_version: V1_2,
+        device_extensions: [khr_timeline_semaphore],
+    },
+}
+
 vulkan_enum! {
     /// The handle type used for Vulkan external semaphore APIs.
     #[non_exhaustive]
@@ -1518,6 +1662,9 @@ pub enum SemaphoreError {
         imported_handle_type: ExternalSemaphoreHandleType,
     },
 
+    /// The semaphore type is binary, but a nonzero initial value was provided.
+    BinaryInitialValueNotZero,
+
     /// One of the export handle types is not compatible with the other provided handles.
     ExportHandleTypesNotCompatible,
 
@@ -1592,6 +1739,10 @@ impl Display for SemaphoreError {
                 {:?}",
                 imported_handle_type,
             ),
+            Self::BinaryInitialValueNotZero => write!(
+                f,
+                "the semaphore type is binary, but a nonzero initial value was provided",
+            ),
             Self::ExportHandleTypesNotCompatible => write!(
                 f,
                 "one of the export handle types is not compatible with the other provided handles",
@@ -1664,11 +1815,14 @@ impl From<RequirementNotMet> for SemaphoreError {
 
 #[cfg(test)]
 mod tests {
-    use super::ExternalSemaphoreHandleType;
+    use super::{ExternalSemaphoreHandleType, SemaphoreState};
     use crate::{
         device::{Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo},
         instance::{Instance, InstanceCreateInfo, InstanceExtensions},
-        sync::{ExternalSemaphoreHandleTypes, Semaphore, SemaphoreCreateInfo},
+        sync::{
+            ExternalSemaphoreHandleTypes, Semaphore, SemaphoreCreateInfo, SemaphoreError,
+            SemaphoreType,
+        },
         VulkanLibrary, VulkanObject,
     };
 
@@ -1678,6 +1832,49 @@ mod tests {
         let _ = Semaphore::new(device, Default::default());
     }
 
+    #[test]
+    fn timeline_state() {
+        let mut state = SemaphoreState {
+            counter_value: 1,
+            ..Default::default()
+        };
+
+        unsafe {
+            state.add_queue_signal_value(3);
+            state.add_queue_wait_value(2);
+            assert!(state.is_in_queue());
+            assert_eq!(state.last_signal_value(), 3);
+
+            state.set_wait_value_finished(2);
+            assert_eq!(state.last_signal_value(), 3);
+            state.set_signal_value_finished(3);
+        }
+
+        assert!(!state.is_in_queue());
+        assert_eq!(state.last_signal_value(), 3);
+
+        // The binary state is left alone.
+        assert!(!state.is_signal_pending());
+        assert_eq!(state.is_signaled(), Some(false));
+    }
+
+    #[test]
+    fn binary_initial_value() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match Semaphore::new(
+            device,
+            SemaphoreCreateInfo {
+                semaphore_type: SemaphoreType::Binary,
+                initial_value: 1,
+                ..Default::default()
+            },
+        ) {
+            Err(SemaphoreError::BinaryInitialValueNotZero) => (),
+            _ => panic!(),
+        }
+    }
+
     #[test]
     fn semaphore_pool() {
         let (device, _) = gfx_dev_and_queue!();
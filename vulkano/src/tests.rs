@@ -27,14 +27,22 @@ macro_rules! instance {
 }
 
 /// Creates a device and a queue for graphics operations.
+///
+/// The features to enable can be listed, optionally followed by `; extensions:` and the device
+/// extensions to enable. Returns from the calling test if no device supports them.
 macro_rules! gfx_dev_and_queue {
-    ($($feature:ident),*) => ({
+    ($($feature:ident),* $(; extensions: $($extension:ident),*)?) => ({
         use crate::device::physical::PhysicalDeviceType;
         use crate::device::{Device, DeviceCreateInfo, DeviceExtensions, QueueCreateInfo};
         use crate::device::Features;
 
         let instance = instance!();
-        let enabled_extensions = DeviceExtensions::empty();
+        let enabled_extensions = DeviceExtensions {
+            $($(
+                $extension: true,
+            )*)?
+            .. DeviceExtensions::empty()
+        };
         let enabled_features = Features {
             $(
                 $feature: true,